@@ -0,0 +1,126 @@
+use anyhow::{anyhow, Context};
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use circomspect::ChangedLines;
+use parser::Remapping;
+
+/// Returns the lines changed in the working tree (including staged changes)
+/// relative to `HEAD`, as reported by `git diff`.
+pub(crate) fn get_changed_lines() -> anyhow::Result<ChangedLines> {
+    let root = run_git(&["rev-parse", "--show-toplevel"])?;
+    let diff = run_git(&["diff", "--unified=0", "--no-color", "--no-ext-diff", "HEAD", "--"])?;
+    Ok(parse_diff(Path::new(root.trim()), &diff))
+}
+
+fn run_git(args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("git").args(args).output().context("Failed to run `git`")?;
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("`git {}` failed: {}", args.join(" "), error.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Parses the output of `git diff --unified=0` and returns the changed lines
+/// of each file. Files are resolved relative to the repository root. Since
+/// removed lines have no location in the new file, a removal is recorded as a
+/// change to the line preceding the removed lines.
+fn parse_diff(root: &Path, diff: &str) -> ChangedLines {
+    let mut changed_lines = ChangedLines::new();
+    let mut current_file = None;
+    for line in diff.lines() {
+        if let Some(path) = line.strip_prefix("+++ ") {
+            // Deleted files are reported as `/dev/null`.
+            current_file = path.strip_prefix("b/").map(|path| {
+                let path = root.join(path);
+                fs::canonicalize(&path).unwrap_or(path)
+            });
+        } else if let (Some(hunk), Some(file)) = (line.strip_prefix("@@ "), &current_file) {
+            // The hunk header has the form `@@ -start,count +start,count @@`.
+            let Some(range) = hunk.split(' ').find_map(|range| range.strip_prefix('+')) else {
+                continue;
+            };
+            let mut range = range.split(',').map(str::parse::<usize>);
+            let (Some(Ok(start)), count) = (range.next(), range.next()) else {
+                continue;
+            };
+            let count = match count {
+                Some(Ok(count)) => count,
+                _ => 1,
+            };
+            let end = start + count.max(1) - 1;
+            changed_lines.entry(file.clone()).or_default().push(start.max(1)..=end.max(1));
+        }
+    }
+    changed_lines
+}
+
+/// Returns the files which are changed, or which (transitively) include a
+/// changed file, among the given files and the files they include.
+pub(crate) fn get_affected_files(
+    input_files: &[PathBuf],
+    libraries: &[PathBuf],
+    remappings: &[Remapping],
+    changed_lines: &ChangedLines,
+) -> Vec<PathBuf> {
+    let (include_graph, _) = parser::include_graph(&input_files.to_vec(), libraries, remappings);
+    let mut affected = changed_lines
+        .keys()
+        .filter(|path| include_graph.contains_key(*path))
+        .cloned()
+        .collect::<HashSet<_>>();
+    // Iterate until no more including files are found.
+    loop {
+        let includers = include_graph
+            .iter()
+            .filter(|(path, includes)| {
+                !affected.contains(*path)
+                    && includes.iter().any(|include| affected.contains(include))
+            })
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<_>>();
+        if includers.is_empty() {
+            break;
+        }
+        affected.extend(includers);
+    }
+    affected.into_iter().collect::<BTreeSet<_>>().into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_diff() {
+        let diff = r#"
+diff --git a/circuits/main.circom b/circuits/main.circom
+index 1234567..89abcde 100644
+--- a/circuits/main.circom
++++ b/circuits/main.circom
+@@ -3 +3 @@ template Main() {
+-    signal input a;
++    signal input b;
+@@ -10,0 +11,2 @@ template Main() {
++    b === 1;
++    b === 2;
+@@ -20,3 +21,0 @@ template Main() {
+-    a === 1;
+diff --git a/circuits/old.circom b/circuits/old.circom
+deleted file mode 100644
+--- a/circuits/old.circom
++++ /dev/null
+@@ -1,2 +0,0 @@
+-pragma circom 2.0.0;
+"#;
+        let changed_lines = parse_diff(Path::new("/src"), diff);
+        assert_eq!(changed_lines.len(), 1);
+        assert_eq!(
+            changed_lines[Path::new("/src/circuits/main.circom")],
+            vec![3..=3, 11..=12, 21..=21]
+        );
+    }
+}
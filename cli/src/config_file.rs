@@ -0,0 +1,257 @@
+use anyhow::{anyhow, Context};
+use std::fs;
+use std::path::Path;
+
+use program_analysis::definition_complexity::{ComplexityPass, ComplexityThresholds};
+use program_analysis::get_analysis_passes;
+use program_analysis::naming_conventions::{NamingConventions, NamingPattern};
+use program_structure::report_code::RuleCategory;
+use program_structure::rules::find_rule;
+
+/// The configuration file loaded from the current directory by default.
+pub(crate) const DEFAULT_CONFIG_FILE: &str = "circomspect.toml";
+
+/// Settings read from a `circomspect.toml` configuration file.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ConfigFile {
+    pub(crate) naming_conventions: NamingConventions,
+    pub(crate) complexity_thresholds: ComplexityThresholds,
+    /// If non-empty, only results from rules in the given categories are
+    /// reported.
+    pub(crate) only_categories: Vec<RuleCategory>,
+    /// Results from rules in the given categories are not reported.
+    pub(crate) skipped_categories: Vec<RuleCategory>,
+    /// Results with the given rule IDs or names are not reported.
+    pub(crate) allow_list: Vec<String>,
+    /// Results with the given rule IDs or names (or all `warnings`) are
+    /// promoted to errors.
+    pub(crate) deny_list: Vec<String>,
+    /// Analysis passes with the given names are not run.
+    pub(crate) disabled_passes: Vec<String>,
+}
+
+/// Reads and parses the given configuration file.
+pub(crate) fn load_config_file(path: &Path) -> anyhow::Result<ConfigFile> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("Failed to read `{}`", path.display()))?;
+    parse_config_file(&contents).with_context(|| format!("Failed to parse `{}`", path.display()))
+}
+
+/// Parses the subset of TOML used by Circomspect configuration files. That
+/// is, tables (like `[naming]`) containing string and integer values. Both
+/// basic (double-quoted) and literal (single-quoted) strings are supported.
+/// Literal strings are convenient for regular expressions, since backslashes
+/// are not interpreted as escapes.
+fn parse_config_file(contents: &str) -> anyhow::Result<ConfigFile> {
+    let mut config = ConfigFile::default();
+    let mut table = None;
+    for (index, line) in contents.lines().enumerate() {
+        let line_number = index + 1;
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+            let name = name.trim();
+            if !["naming", "complexity", "categories", "rules", "passes"].contains(&name) {
+                return Err(anyhow!("unknown table `[{name}]` on line {line_number}"));
+            }
+            table = Some(name.to_string());
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(anyhow!("expected `key = value` on line {line_number}"));
+        };
+        let (key, value) = (key.trim(), value.trim());
+        match table.as_deref() {
+            Some("naming") => {
+                let value = parse_string(value)
+                    .ok_or_else(|| anyhow!("expected a string value on line {line_number}"))?;
+                let pattern = value.parse::<NamingPattern>().map_err(|error| {
+                    anyhow!("invalid naming convention on line {line_number}: {error}")
+                })?;
+                let conventions = &mut config.naming_conventions;
+                match key {
+                    "input-signals" => conventions.input_signals = Some(pattern),
+                    "output-signals" => conventions.output_signals = Some(pattern),
+                    "intermediate-signals" => conventions.intermediate_signals = Some(pattern),
+                    "templates" => conventions.templates = Some(pattern),
+                    _ => return Err(anyhow!("unknown key `{key}` on line {line_number}")),
+                }
+            }
+            Some("complexity") => {
+                let value = value.replace('_', "").parse::<usize>().map_err(|_| {
+                    anyhow!("expected a non-negative integer value on line {line_number}")
+                })?;
+                let thresholds = &mut config.complexity_thresholds;
+                match key {
+                    "max-parameters" => thresholds.max_parameters = value,
+                    "max-cyclomatic-complexity" => thresholds.max_cyclomatic_complexity = value,
+                    "max-statements" => thresholds.max_statements = value,
+                    "max-nesting-depth" => thresholds.max_nesting_depth = value,
+                    _ => return Err(anyhow!("unknown key `{key}` on line {line_number}")),
+                }
+            }
+            Some("categories") => {
+                let value = parse_string(value)
+                    .ok_or_else(|| anyhow!("expected a string value on line {line_number}"))?;
+                let categories = value
+                    .split(',')
+                    .map(|category| category.trim().parse::<RuleCategory>())
+                    .collect::<anyhow::Result<Vec<_>>>()
+                    .map_err(|error| anyhow!("{error} on line {line_number}"))?;
+                match key {
+                    "only" => config.only_categories = categories,
+                    "skip" => config.skipped_categories = categories,
+                    _ => return Err(anyhow!("unknown key `{key}` on line {line_number}")),
+                }
+            }
+            Some("rules") => {
+                let value = parse_string(value)
+                    .ok_or_else(|| anyhow!("expected a string value on line {line_number}"))?;
+                let rules = value
+                    .split(',')
+                    .map(|rule| rule.trim().to_string())
+                    .filter(|rule| !rule.is_empty())
+                    .collect::<Vec<_>>();
+                let is_known = |rule: &String| {
+                    find_rule(rule).is_some() || (key == "deny" && rule == "warnings")
+                };
+                if let Some(rule) = rules.iter().find(|rule| !is_known(rule)) {
+                    return Err(anyhow!("unknown rule `{rule}` on line {line_number}"));
+                }
+                match key {
+                    "allow" => config.allow_list = rules,
+                    "deny" => config.deny_list = rules,
+                    _ => return Err(anyhow!("unknown key `{key}` on line {line_number}")),
+                }
+            }
+            Some("passes") => {
+                let value = parse_string(value)
+                    .ok_or_else(|| anyhow!("expected a string value on line {line_number}"))?;
+                let passes = value
+                    .split(',')
+                    .map(|pass| pass.trim().to_string())
+                    .filter(|pass| !pass.is_empty())
+                    .collect::<Vec<_>>();
+                let mut registry = get_analysis_passes();
+                registry.register(ComplexityPass::new(ComplexityThresholds::default()));
+                if let Some(pass) = passes.iter().find(|pass| !registry.contains(pass)) {
+                    return Err(anyhow!("unknown analysis pass `{pass}` on line {line_number}"));
+                }
+                match key {
+                    "disable" => config.disabled_passes = passes,
+                    _ => return Err(anyhow!("unknown key `{key}` on line {line_number}")),
+                }
+            }
+            _ => return Err(anyhow!("unknown key `{key}` on line {line_number}")),
+        }
+    }
+    Ok(config)
+}
+
+/// Removes a trailing `#` comment, ignoring `#` characters inside strings.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    for (index, char) in line.char_indices() {
+        match (quote, char) {
+            (Some('"'), '\\') if !escaped => {
+                escaped = true;
+                continue;
+            }
+            (Some(q), c) if c == q && !escaped => quote = None,
+            (None, '"' | '\'') => quote = Some(char),
+            (None, '#') => return &line[..index],
+            _ => {}
+        }
+        escaped = false;
+    }
+    line
+}
+
+/// Parses a basic or literal TOML string.
+fn parse_string(value: &str) -> Option<String> {
+    if let Some(value) = value.strip_prefix('\'').and_then(|value| value.strip_suffix('\'')) {
+        return (!value.contains('\'')).then(|| value.to_string());
+    }
+    let value = value.strip_prefix('"')?.strip_suffix('"')?;
+    let mut result = String::new();
+    let mut chars = value.chars();
+    while let Some(char) = chars.next() {
+        match char {
+            '\\' => match chars.next()? {
+                '\\' => result.push('\\'),
+                '"' => result.push('"'),
+                'n' => result.push('\n'),
+                't' => result.push('\t'),
+                _ => return None,
+            },
+            '"' => return None,
+            char => result.push(char),
+        }
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config_file() {
+        let contents = r#"
+            # Naming conventions.
+            [naming]
+            input-signals = "camelCase"  # Trailing comment.
+            templates = 'PascalCase'
+            intermediate-signals = '_?[a-z]\w*'
+            output-signals = "out\\d*"
+
+            [complexity]
+            max-statements = 1_000
+            max-nesting-depth = 3
+
+            [categories]
+            only = "soundness, correctness"
+
+            [rules]
+            allow = "unused-variable, CS0019"
+            deny = 'warnings'
+
+            [passes]
+            disable = "field-overflow, definition-complexity"
+        "#;
+        let config = parse_config_file(contents).unwrap();
+        let conventions = &config.naming_conventions;
+        assert!(conventions.input_signals.as_ref().unwrap().is_match("inValue"));
+        assert!(conventions.templates.as_ref().unwrap().is_match("Num2Bits"));
+        assert!(conventions.intermediate_signals.as_ref().unwrap().is_match("_sum"));
+        assert!(conventions.output_signals.as_ref().unwrap().is_match("out12"));
+        assert!(!conventions.output_signals.as_ref().unwrap().is_match("result"));
+        let thresholds = &config.complexity_thresholds;
+        assert_eq!(thresholds.max_statements, 1000);
+        assert_eq!(thresholds.max_nesting_depth, 3);
+        assert_eq!(thresholds.max_parameters, ComplexityThresholds::default().max_parameters);
+        assert_eq!(config.only_categories, [RuleCategory::Soundness, RuleCategory::Correctness]);
+        assert!(config.skipped_categories.is_empty());
+        assert_eq!(config.allow_list, ["unused-variable", "CS0019"]);
+        assert_eq!(config.deny_list, ["warnings"]);
+        assert_eq!(config.disabled_passes, ["field-overflow", "definition-complexity"]);
+
+        assert!(parse_config_file("[naming]\ntemplates = PascalCase").is_err());
+        assert!(parse_config_file("[naming]\nfunctions = 'camelCase'").is_err());
+        assert!(parse_config_file("[naming]\ntemplates = '[A-Z'").is_err());
+        assert!(parse_config_file("[style]").is_err());
+        assert!(parse_config_file("[complexity]\nmax-statements = '100'").is_err());
+        assert!(parse_config_file("[complexity]\nmax-statements = -1").is_err());
+        assert!(parse_config_file("[categories]\nskip = 'formatting'").is_err());
+        assert!(parse_config_file("[rules]\nallow = 'no-such-rule'").is_err());
+        assert!(parse_config_file("[rules]\nallow = 'warnings'").is_err());
+        assert!(parse_config_file("[rules]\nignore = 'CS0019'").is_err());
+        assert!(parse_config_file("[passes]\ndisable = 'no-such-pass'").is_err());
+        assert!(parse_config_file("[passes]\nenable = 'field-overflow'").is_err());
+        assert!(parse_config_file("templates = 'PascalCase'").is_err());
+        assert!(parse_config_file("").unwrap().naming_conventions.is_empty());
+    }
+}
@@ -0,0 +1,81 @@
+use anyhow::anyhow;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use circomspect::glob_match;
+use parser::{ParseResult, Remapping};
+use program_analysis::template_summary::{summarize_template, to_json, to_markdown};
+use program_structure::report_writer::{ReportWriter, StdoutWriter};
+
+use crate::{log_message, COMPILER_VERSION};
+
+/// The format used to export template summaries.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum DescribeFormat {
+    Markdown,
+    Json,
+}
+
+impl FromStr for DescribeFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(format: &str) -> Result<DescribeFormat, Self::Err> {
+        match format.to_lowercase().as_str() {
+            "markdown" | "md" => Ok(DescribeFormat::Markdown),
+            "json" => Ok(DescribeFormat::Json),
+            _ => Err(anyhow!("unknown summary format '{format}'")),
+        }
+    }
+}
+
+/// Writes a summary of the inputs, outputs, constraints, and hints of each
+/// template matching one of the given patterns (or all templates if no
+/// patterns are given) to the output file (or stdout). Returns false if no
+/// template matched or the summary could not be written.
+pub(crate) fn describe_templates(
+    input_files: &[PathBuf],
+    (libraries, remappings): (&[PathBuf], &[Remapping]),
+    patterns: &[String],
+    format: DescribeFormat,
+    output_file: &Option<PathBuf>,
+) -> bool {
+    let (file_library, templates, warnings) =
+        match parser::parse_files(&input_files.to_vec(), libraries, remappings, COMPILER_VERSION) {
+            ParseResult::Program(program, warnings) => {
+                (program.file_library, program.templates, warnings)
+            }
+            ParseResult::Library(library, warnings) => {
+                (library.file_library, library.templates, warnings)
+            }
+        };
+    let mut names = templates
+        .keys()
+        .filter(|name| patterns.is_empty() || patterns.iter().any(|p| glob_match(p, name)))
+        .collect::<Vec<_>>();
+    if names.is_empty() {
+        // The templates may be missing because of a parse error.
+        StdoutWriter::new(false).write(&warnings, &file_library);
+        log_message("No matching templates found.");
+        return false;
+    }
+    names.sort();
+    let summaries = names
+        .into_iter()
+        .map(|name| summarize_template(&templates[name], &file_library))
+        .collect::<Vec<_>>();
+    let summary = match format {
+        DescribeFormat::Markdown => to_markdown(&summaries),
+        DescribeFormat::Json => format!("{:#}\n", to_json(&summaries)),
+    };
+    match output_file {
+        Some(output_file) => {
+            if std::fs::write(output_file, summary).is_err() {
+                log_message(&format!("Failed to write `{}`.", output_file.display()));
+                return false;
+            }
+            log_message(&format!("Summary written to `{}`.", output_file.display()));
+        }
+        None => print!("{summary}"),
+    }
+    true
+}
@@ -0,0 +1,186 @@
+use anyhow::{anyhow, Context};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use program_structure::sarif_conversion::FINGERPRINT_KEY;
+
+use crate::log_message;
+
+/// A single (unsuppressed) result read from a Sarif file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Finding {
+    rule_id: String,
+    message: String,
+    location: Option<String>,
+}
+
+impl Finding {
+    fn to_text(&self) -> String {
+        match &self.location {
+            Some(location) => format!("{} {location}: {}", self.rule_id, self.message),
+            None => format!("{}: {}", self.rule_id, self.message),
+        }
+    }
+}
+
+/// The findings of two runs, partitioned into new, fixed, and unchanged
+/// findings.
+#[derive(Debug, Default)]
+struct Diff {
+    new: Vec<Finding>,
+    fixed: Vec<Finding>,
+    unchanged: Vec<Finding>,
+}
+
+/// Compares the results in the two given Sarif files and prints the findings
+/// that are new, fixed, or unchanged. Results are matched using their partial
+/// fingerprints. Returns `Some(true)` if there are new findings, and `None` if
+/// either file could not be read.
+pub(crate) fn diff_results(old_file: &Path, new_file: &Path, show_unchanged: bool) -> Option<bool> {
+    let read_findings = |sarif_file: &Path| match read_findings(sarif_file) {
+        Ok(findings) => Some(findings),
+        Err(error) => {
+            log_message(&format!("{error:#}."));
+            None
+        }
+    };
+    let diff = diff_findings(read_findings(old_file)?, read_findings(new_file)?);
+    print_findings("New", &diff.new);
+    print_findings("Fixed", &diff.fixed);
+    if show_unchanged {
+        print_findings("Unchanged", &diff.unchanged);
+    }
+    log_message(&format!(
+        "{} new, {} fixed, and {} unchanged finding(s).",
+        diff.new.len(),
+        diff.fixed.len(),
+        diff.unchanged.len()
+    ));
+    Some(!diff.new.is_empty())
+}
+
+fn print_findings(title: &str, findings: &[Finding]) {
+    if findings.is_empty() {
+        return;
+    }
+    println!("{title} findings:");
+    for finding in findings {
+        println!("  {}", finding.to_text());
+    }
+    println!();
+}
+
+/// Returns the unsuppressed results in the given Sarif file, indexed by
+/// fingerprint.
+fn read_findings(sarif_file: &Path) -> anyhow::Result<BTreeMap<String, Finding>> {
+    let sarif = std::fs::read_to_string(sarif_file)
+        .with_context(|| format!("Failed to read `{}`", sarif_file.display()))?;
+    let sarif: Value = serde_json::from_str(&sarif)
+        .with_context(|| format!("Failed to parse `{}`", sarif_file.display()))?;
+    let runs = sarif["runs"]
+        .as_array()
+        .ok_or_else(|| anyhow!("`{}` is not a valid Sarif file", sarif_file.display()))?;
+    Ok(to_findings(runs.iter().flat_map(|run| run["results"].as_array().into_iter().flatten())))
+}
+
+/// Converts Sarif results to findings, indexed by fingerprint. Suppressed
+/// results are ignored. If a result does not have a fingerprint, the rule ID,
+/// location, and message are used instead.
+fn to_findings<'a>(results: impl Iterator<Item = &'a Value>) -> BTreeMap<String, Finding> {
+    let mut findings = BTreeMap::new();
+    let mut occurrences = BTreeMap::new();
+    for result in results {
+        let is_suppressed =
+            result["suppressions"].as_array().map(|suppressions| !suppressions.is_empty());
+        if is_suppressed.unwrap_or_default() {
+            continue;
+        }
+        let physical_location = &result["locations"][0]["physicalLocation"];
+        let uri = physical_location["artifactLocation"]["uri"].as_str();
+        let line = physical_location["region"]["startLine"].as_u64();
+        let finding = Finding {
+            rule_id: result["ruleId"].as_str().unwrap_or_default().to_string(),
+            message: result["message"]["text"].as_str().unwrap_or_default().to_string(),
+            location: match (uri, line) {
+                (Some(uri), Some(line)) => Some(format!("{uri}:{line}")),
+                (Some(uri), None) => Some(uri.to_string()),
+                (None, _) => None,
+            },
+        };
+        let fingerprint = match result["partialFingerprints"][FINGERPRINT_KEY].as_str() {
+            Some(fingerprint) => fingerprint.to_string(),
+            None => {
+                let key = finding.to_text();
+                let occurrence = occurrences.entry(key.clone()).or_insert(0);
+                *occurrence += 1;
+                format!("{key}:{occurrence}")
+            }
+        };
+        findings.insert(fingerprint, finding);
+    }
+    findings
+}
+
+fn diff_findings(
+    old_findings: BTreeMap<String, Finding>,
+    mut new_findings: BTreeMap<String, Finding>,
+) -> Diff {
+    let mut diff = Diff::default();
+    for (fingerprint, finding) in old_findings {
+        match new_findings.remove(&fingerprint) {
+            // Report the unchanged finding using the location from the new run.
+            Some(finding) => diff.unchanged.push(finding),
+            None => diff.fixed.push(finding),
+        }
+    }
+    diff.new.extend(new_findings.into_values());
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn result(rule_id: &str, fingerprint: &str, line: u64) -> Value {
+        json!({
+            "ruleId": rule_id,
+            "message": { "text": "A message." },
+            "locations": [{
+                "physicalLocation": {
+                    "artifactLocation": { "uri": "file:///src/main.circom" },
+                    "region": { "startLine": line }
+                }
+            }],
+            "partialFingerprints": { FINGERPRINT_KEY: fingerprint }
+        })
+    }
+
+    #[test]
+    fn test_diff_findings() {
+        let old_results = [result("CS0005", "a:1", 3), result("CS0006", "b:1", 5)];
+        let mut suppressed = result("CS0007", "c:1", 7);
+        suppressed["suppressions"] = json!([{ "kind": "external" }]);
+        // The first finding has moved, since a line was added to the file.
+        let new_results = [result("CS0005", "a:1", 4), suppressed, result("CS0008", "d:1", 9)];
+
+        let diff = diff_findings(to_findings(old_results.iter()), to_findings(new_results.iter()));
+        assert_eq!(diff.unchanged.len(), 1);
+        assert_eq!(diff.unchanged[0].location.as_deref(), Some("file:///src/main.circom:4"));
+        assert_eq!(diff.fixed.len(), 1);
+        assert_eq!(diff.fixed[0].rule_id, "CS0006");
+        assert_eq!(diff.new.len(), 1);
+        assert_eq!(diff.new[0].rule_id, "CS0008");
+        assert_eq!(diff.new[0].to_text(), "CS0008 file:///src/main.circom:9: A message.");
+    }
+
+    #[test]
+    fn test_missing_fingerprints() {
+        let mut first = result("CS0005", "", 3);
+        first["partialFingerprints"] = json!({});
+        let findings = to_findings([first.clone(), first].iter());
+        assert_eq!(findings.len(), 2);
+    }
+}
@@ -0,0 +1,59 @@
+use std::path::PathBuf;
+
+use program_structure::file_definition::FileLibrary;
+use program_structure::report_writer::{ReportWriter, StdoutWriter};
+
+use crate::log_message;
+
+/// How the `format` subcommand handles formatted files.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum FormatMode {
+    // Write the formatted files to stdout.
+    Print,
+    // Overwrite each file with the formatted file.
+    Write,
+    // Only list the files that are not formatted.
+    Check,
+}
+
+/// Formats each of the given files. Files with syntax errors are skipped, and
+/// the errors are written to stdout. Returns false if a file could not be
+/// formatted, or if `mode` is `Check` and a file is not formatted.
+pub(crate) fn format_files(input_files: &[PathBuf], mode: FormatMode) -> bool {
+    let mut file_library = FileLibrary::new();
+    let mut success = true;
+    for input_file in input_files {
+        let Ok(src) = std::fs::read_to_string(input_file) else {
+            log_message(&format!("Failed to read `{}`.", input_file.display()));
+            success = false;
+            continue;
+        };
+        let file_id = file_library.add_file(input_file.display().to_string(), src.clone());
+        let formatted = match parser::format_source(&src, file_id) {
+            Ok(formatted) => formatted,
+            Err(reports) => {
+                StdoutWriter::new(false).write(&reports, &file_library);
+                log_message(&format!("Failed to format `{}`.", input_file.display()));
+                success = false;
+                continue;
+            }
+        };
+        match mode {
+            FormatMode::Print => print!("{formatted}"),
+            FormatMode::Write if formatted != src => {
+                if std::fs::write(input_file, formatted).is_err() {
+                    log_message(&format!("Failed to write `{}`.", input_file.display()));
+                    success = false;
+                } else {
+                    log_message(&format!("Formatted `{}`.", input_file.display()));
+                }
+            }
+            FormatMode::Check if formatted != src => {
+                println!("{}", input_file.display());
+                success = false;
+            }
+            FormatMode::Write | FormatMode::Check => {}
+        }
+    }
+    success
+}
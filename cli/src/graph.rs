@@ -0,0 +1,189 @@
+use anyhow::anyhow;
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use parser::{ParseResult, Remapping};
+use program_analysis::analysis_runner::AnalysisRunner;
+use program_structure::constants::Curve;
+use program_structure::report::{MessageCategory, ReportCollection};
+
+use crate::{log_message, COMPILER_VERSION};
+
+/// The graph exported by the `graph` subcommand.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum GraphKind {
+    // Functions and templates, and the definitions they call or instantiate.
+    Calls,
+    // Files, and the files they include.
+    Includes,
+}
+
+impl FromStr for GraphKind {
+    type Err = anyhow::Error;
+
+    fn from_str(kind: &str) -> Result<GraphKind, Self::Err> {
+        match kind.to_lowercase().as_str() {
+            "calls" => Ok(GraphKind::Calls),
+            "includes" => Ok(GraphKind::Includes),
+            _ => Err(anyhow!("unknown graph kind '{kind}'")),
+        }
+    }
+}
+
+/// The format used to export graphs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum GraphFormat {
+    Dot,
+    Json,
+}
+
+impl FromStr for GraphFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(format: &str) -> Result<GraphFormat, Self::Err> {
+        match format.to_lowercase().as_str() {
+            "dot" => Ok(GraphFormat::Dot),
+            "json" => Ok(GraphFormat::Json),
+            _ => Err(anyhow!("unknown graph format '{format}'")),
+        }
+    }
+}
+
+/// Writes the call graph or include graph of the given files to the output
+/// file (or stdout). Returns false if the graph could not be written.
+pub(crate) fn export_graph(
+    kind: GraphKind,
+    format: GraphFormat,
+    input_files: &[PathBuf],
+    (libraries, remappings): (&[PathBuf], &[Remapping]),
+    output_file: &Option<PathBuf>,
+) -> bool {
+    let graph = match kind {
+        GraphKind::Calls => call_graph(input_files, libraries, remappings),
+        GraphKind::Includes => include_graph(input_files, libraries, remappings),
+    };
+    let graph = match format {
+        GraphFormat::Dot => to_dot(kind, &graph),
+        GraphFormat::Json => to_json(&graph),
+    };
+    match output_file {
+        Some(output_file) => {
+            if std::fs::write(output_file, graph).is_err() {
+                log_message(&format!("Failed to write `{}`.", output_file.display()));
+                return false;
+            }
+            log_message(&format!("Graph written to `{}`.", output_file.display()));
+        }
+        None => print!("{graph}"),
+    }
+    true
+}
+
+fn call_graph(
+    input_files: &[PathBuf],
+    libraries: &[PathBuf],
+    remappings: &[Remapping],
+) -> BTreeMap<String, BTreeSet<String>> {
+    let (file_library, functions, templates, warnings) =
+        match parser::parse_files(&input_files.to_vec(), libraries, remappings, COMPILER_VERSION) {
+            ParseResult::Program(program, warnings) => {
+                (program.file_library, program.functions, program.templates, warnings)
+            }
+            ParseResult::Library(library, warnings) => {
+                (library.file_library, library.functions, library.templates, warnings)
+            }
+        };
+    log_errors(&warnings);
+    AnalysisRunner::new(&Curve::default(), file_library, functions, templates).call_graph()
+}
+
+fn include_graph(
+    input_files: &[PathBuf],
+    libraries: &[PathBuf],
+    remappings: &[Remapping],
+) -> BTreeMap<String, BTreeSet<String>> {
+    let (include_graph, errors) =
+        parser::include_graph(&input_files.to_vec(), libraries, remappings);
+    log_errors(&errors);
+    include_graph
+        .into_iter()
+        .map(|(path, includes)| {
+            let includes = includes.iter().map(|path| path.display().to_string()).collect();
+            (path.display().to_string(), includes)
+        })
+        .collect()
+}
+
+/// Parse errors are written to stderr, since the graph may be written to
+/// stdout.
+fn log_errors(reports: &ReportCollection) {
+    for report in reports {
+        if report.category() == &MessageCategory::Error {
+            eprintln!("circomspect: {}", report.message());
+        }
+    }
+}
+
+fn to_dot<T: Display>(kind: GraphKind, graph: &BTreeMap<T, BTreeSet<T>>) -> String {
+    let name = match kind {
+        GraphKind::Calls => "calls",
+        GraphKind::Includes => "includes",
+    };
+    let mut dot = format!("digraph \"{name}\" {{\n");
+    for (node, successors) in graph {
+        dot.push_str(&format!("    {};\n", quote(node)));
+        for successor in successors {
+            dot.push_str(&format!("    {} -> {};\n", quote(node), quote(successor)));
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Exports the graph as a JSON object with a list of nodes and a list of
+/// (directed) edges.
+fn to_json<T: Display>(graph: &BTreeMap<T, BTreeSet<T>>) -> String {
+    let nodes = graph.keys().map(ToString::to_string).collect::<Vec<_>>();
+    let edges = graph
+        .iter()
+        .flat_map(|(node, successors)| {
+            successors.iter().map(
+                move |successor| json!({ "from": node.to_string(), "to": successor.to_string() }),
+            )
+        })
+        .collect::<Vec<_>>();
+    let mut json = serde_json::to_string_pretty(&json!({ "nodes": nodes, "edges": edges }))
+        .expect("graph is valid JSON");
+    json.push('\n');
+    json
+}
+
+fn quote<T: Display>(value: &T) -> String {
+    format!("\"{}\"", value.to_string().replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph_export() {
+        let graph = BTreeMap::from([
+            ("A", BTreeSet::from(["B", "f"])),
+            ("B", BTreeSet::new()),
+            ("f", BTreeSet::new()),
+        ]);
+        let dot = to_dot(GraphKind::Calls, &graph);
+        assert!(dot.starts_with("digraph \"calls\" {\n"));
+        assert!(dot.contains("    \"A\" -> \"B\";\n"));
+        assert!(dot.contains("    \"A\" -> \"f\";\n"));
+
+        let json: serde_json::Value = serde_json::from_str(&to_json(&graph)).unwrap();
+        assert_eq!(json["nodes"].as_array().unwrap().len(), 3);
+        assert_eq!(json["edges"].as_array().unwrap().len(), 2);
+        assert_eq!(json["edges"][0], json!({ "from": "A", "to": "B" }));
+    }
+}
@@ -0,0 +1,410 @@
+//! Expansion of the input paths passed on the command line. Inputs may be
+//! Circom files, directories (which are searched recursively for Circom
+//! files), or glob patterns like `circuits/**/*.circom`.
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::thread;
+
+use crate::glob_match;
+use crate::progress::{log_message, log_verbose};
+
+/// A glob pattern, split into path components. Here, `*` and `?` match
+/// characters within a single component, and `**` matches any number of
+/// components.
+#[derive(Clone)]
+struct Pattern(Vec<String>);
+
+impl Pattern {
+    fn new(pattern: &Path) -> Pattern {
+        Pattern(to_components(pattern))
+    }
+
+    /// Returns true if the pattern matches the given path.
+    fn matches(&self, path: &[String]) -> bool {
+        matches_components(&self.0, path)
+    }
+
+    /// Returns true if the pattern matches the given path, or any directory
+    /// containing it. Patterns consisting of a single component (like
+    /// `test`) match any file or directory with that name.
+    fn excludes(&self, path: &[String]) -> bool {
+        if let [pattern] = &self.0[..] {
+            return path.iter().any(|component| glob_match(pattern, component));
+        }
+        (1..=path.len()).any(|length| self.matches(&path[..length]))
+    }
+}
+
+fn matches_components(pattern: &[String], path: &[String]) -> bool {
+    match (pattern.first(), path.first()) {
+        (None, None) => true,
+        (Some(first), _) if first == "**" => {
+            matches_components(&pattern[1..], path)
+                || (!path.is_empty() && matches_components(pattern, &path[1..]))
+        }
+        (Some(first), Some(component)) => {
+            glob_match(first, component) && matches_components(&pattern[1..], &path[1..])
+        }
+        _ => false,
+    }
+}
+
+/// Returns the normal components of the given path as strings.
+fn to_components(path: &Path) -> Vec<String> {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(component) => Some(component.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn is_glob(path: &Path) -> bool {
+    path.to_string_lossy().contains(['*', '?'])
+}
+
+fn is_circom_file(path: &Path) -> bool {
+    path.extension().map(|extension| extension == "circom").unwrap_or_default()
+}
+
+/// Expands the given inputs to a list of Circom files. Directories are
+/// searched recursively, and glob patterns are expanded relative to the
+/// longest prefix not containing any wildcards. Files and directories
+/// matching any of the given exclude patterns are skipped. If
+/// `use_ignore_files` is set, files and directories ignored by `.gitignore` or
+/// `.circomspectignore` files are also skipped when searching directories.
+/// Each file is only returned once, even if it is reached through multiple
+/// inputs. Paths which do not exist are returned unchanged, so that they are
+/// reported by the parser.
+#[must_use]
+pub fn expand_inputs(
+    inputs: &[PathBuf],
+    excludes: &[String],
+    use_ignore_files: bool,
+) -> Vec<PathBuf> {
+    let excludes =
+        excludes.iter().map(|pattern| Pattern::new(pattern.as_ref())).collect::<Vec<_>>();
+    let walker = Walker { excludes: &excludes, use_ignore_files };
+    // Inputs are expanded in parallel.
+    let files = thread::scope(|scope| {
+        let handles = inputs
+            .iter()
+            .map(|input| scope.spawn(|| walker.expand_input(input)))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("failed to expand input"))
+            .collect::<Vec<_>>()
+    });
+
+    let mut visited = HashSet::new();
+    files
+        .into_iter()
+        .filter(|file| visited.insert(fs::canonicalize(file).unwrap_or_else(|_| file.clone())))
+        .collect()
+}
+
+/// Searches inputs for Circom files, skipping excluded and ignored paths.
+struct Walker<'a> {
+    excludes: &'a [Pattern],
+    use_ignore_files: bool,
+}
+
+impl Walker<'_> {
+    fn is_excluded(&self, path: &Path) -> bool {
+        let components = to_components(path);
+        self.excludes.iter().any(|pattern| pattern.excludes(&components))
+    }
+
+    fn expand_input(&self, input: &Path) -> Vec<PathBuf> {
+        if is_glob(input) {
+            // Search the longest prefix not containing any wildcards.
+            let root = input
+                .ancestors()
+                .skip(1)
+                .find(|ancestor| !is_glob(ancestor))
+                .filter(|root| !root.as_os_str().is_empty())
+                .unwrap_or_else(|| Path::new("."));
+            let pattern = Pattern::new(input);
+            let files = self
+                .find_files(root)
+                .into_iter()
+                .filter(|file| pattern.matches(&to_components(file)))
+                .collect::<Vec<_>>();
+            if files.is_empty() {
+                log_message(&format!("No Circom files matching `{}` found.", input.display()));
+            }
+            log_verbose(&format!("`{}` matched {} file(s).", input.display(), files.len()));
+            files
+        } else if self.is_excluded(input) {
+            Vec::new()
+        } else if input.is_dir() {
+            self.find_files(input)
+        } else {
+            vec![input.to_path_buf()]
+        }
+    }
+
+    /// Returns all Circom files in the given directory and its
+    /// subdirectories, sorted by path. Subdirectories of the root are
+    /// searched in parallel.
+    fn find_files(&self, root: &Path) -> Vec<PathBuf> {
+        let Ok(canonical_root) = fs::canonicalize(root) else {
+            return Vec::new();
+        };
+        let rules = if self.use_ignore_files {
+            IgnoreRules::from_ancestors(&canonical_root)
+        } else {
+            IgnoreRules::default()
+        };
+        let (mut files, directories, rules) = self.read_dir(root, &canonical_root, &rules);
+        thread::scope(|scope| {
+            let handles = directories
+                .iter()
+                .map(|(directory, canonical_directory)| {
+                    scope.spawn(|| {
+                        self.find_files_sequential(directory, canonical_directory, &rules)
+                    })
+                })
+                .collect::<Vec<_>>();
+            for handle in handles {
+                files.extend(handle.join().expect("failed to read directory"));
+            }
+        });
+        files.sort();
+        files
+    }
+
+    fn find_files_sequential(
+        &self,
+        directory: &Path,
+        canonical_directory: &Path,
+        rules: &IgnoreRules,
+    ) -> Vec<PathBuf> {
+        let (mut files, directories, rules) = self.read_dir(directory, canonical_directory, rules);
+        for (directory, canonical_directory) in directories {
+            files.extend(self.find_files_sequential(&directory, &canonical_directory, &rules));
+        }
+        files
+    }
+
+    /// Returns the Circom files and the subdirectories (together with their
+    /// canonical paths) in the given directory, and the ignore rules which
+    /// apply to them. Directories are handled on a best effort basis, so
+    /// errors are ignored.
+    fn read_dir(
+        &self,
+        directory: &Path,
+        canonical_directory: &Path,
+        rules: &IgnoreRules,
+    ) -> (Vec<PathBuf>, Vec<(PathBuf, PathBuf)>, IgnoreRules) {
+        let mut files = Vec::new();
+        let mut directories = Vec::new();
+        let mut rules = rules.clone();
+        if self.use_ignore_files {
+            rules.read_ignore_files(canonical_directory);
+        }
+        let Ok(entries) = fs::read_dir(directory) else {
+            return (files, directories, rules);
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let canonical_path = canonical_directory.join(entry.file_name());
+            let is_dir = path.is_dir();
+            if self.is_excluded(&path) {
+                continue;
+            }
+            if self.use_ignore_files
+                && (entry.file_name() == ".git" || rules.is_ignored(&canonical_path, is_dir))
+            {
+                continue;
+            }
+            if is_dir {
+                directories.push((path, canonical_path));
+            } else if is_circom_file(&path) {
+                files.push(path);
+            }
+        }
+        (files, directories, rules)
+    }
+}
+
+/// The names of the ignore files read when searching directories. Rules in
+/// later files take precedence.
+const IGNORE_FILES: [&str; 2] = [".gitignore", ".circomspectignore"];
+
+/// A rule read from an ignore file. This supports the subset of the
+/// `.gitignore` format consisting of glob patterns, negated patterns starting
+/// with `!`, and directory patterns ending with `/`.
+#[derive(Clone)]
+struct IgnoreRule {
+    /// The directory containing the ignore file.
+    base: PathBuf,
+    pattern: Pattern,
+    negated: bool,
+    directory_only: bool,
+}
+
+impl IgnoreRule {
+    fn parse(base: &Path, line: &str) -> Option<IgnoreRule> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(line) => (true, line),
+            None => (false, line),
+        };
+        let (directory_only, line) = match line.strip_suffix('/') {
+            Some(line) => (true, line),
+            None => (false, line),
+        };
+        let mut components = to_components(line.as_ref());
+        if components.is_empty() {
+            return None;
+        }
+        // Patterns without a `/` match at any depth below the base directory.
+        if !line.contains('/') {
+            components.insert(0, "**".to_string());
+        }
+        Some(IgnoreRule {
+            base: base.to_path_buf(),
+            pattern: Pattern(components),
+            negated,
+            directory_only,
+        })
+    }
+}
+
+/// The ignore rules which apply to a directory, ordered by precedence.
+#[derive(Clone, Default)]
+struct IgnoreRules(Vec<IgnoreRule>);
+
+impl IgnoreRules {
+    /// Returns the rules read from the ancestors of the given (canonical)
+    /// directory, up to and including the root of the enclosing Git
+    /// repository. If the directory is not inside a Git repository, no rules
+    /// are returned.
+    fn from_ancestors(directory: &Path) -> IgnoreRules {
+        let mut rules = IgnoreRules::default();
+        if directory.join(".git").exists() {
+            return rules;
+        }
+        let ancestors = directory.ancestors().skip(1).collect::<Vec<_>>();
+        let Some(git_root) = ancestors.iter().position(|ancestor| ancestor.join(".git").exists())
+        else {
+            return rules;
+        };
+        for ancestor in ancestors[..=git_root].iter().rev() {
+            rules.read_ignore_files(ancestor);
+        }
+        rules
+    }
+
+    /// Adds the rules from the ignore files in the given directory.
+    fn read_ignore_files(&mut self, directory: &Path) {
+        for ignore_file in IGNORE_FILES {
+            if let Ok(contents) = fs::read_to_string(directory.join(ignore_file)) {
+                self.0
+                    .extend(contents.lines().filter_map(|line| IgnoreRule::parse(directory, line)));
+            }
+        }
+    }
+
+    /// Returns true if the given (canonical) path is ignored. The last
+    /// matching rule determines if the path is ignored.
+    fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let mut is_ignored = false;
+        for rule in &self.0 {
+            if rule.directory_only && !is_dir {
+                continue;
+            }
+            let Ok(relative_path) = path.strip_prefix(&rule.base) else {
+                continue;
+            };
+            if rule.pattern.matches(&to_components(relative_path)) {
+                is_ignored = !rule.negated;
+            }
+        }
+        is_ignored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn components(path: &str) -> Vec<String> {
+        to_components(Path::new(path))
+    }
+
+    #[test]
+    fn test_patterns() {
+        let pattern = Pattern::new("circuits/**/*.circom".as_ref());
+        assert!(pattern.matches(&components("circuits/main.circom")));
+        assert!(pattern.matches(&components("./circuits/lib/util/bits.circom")));
+        assert!(!pattern.matches(&components("circuits/lib/bits.txt")));
+        assert!(!pattern.matches(&components("other/main.circom")));
+
+        let pattern = Pattern::new("test".as_ref());
+        assert!(pattern.excludes(&components("circuits/test/main.circom")));
+        assert!(!pattern.excludes(&components("circuits/tests/main.circom")));
+        let pattern = Pattern::new("circuits/lib".as_ref());
+        assert!(pattern.excludes(&components("circuits/lib/bits.circom")));
+        assert!(!pattern.excludes(&components("circuits/main.circom")));
+    }
+
+    #[test]
+    fn test_expand_inputs() {
+        let root = std::env::temp_dir().join(format!("circomspect-inputs-{}", std::process::id()));
+        for file in ["main.circom", "lib/bits.circom", "lib/README.md", "test/main.circom"] {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        let files = expand_inputs(std::slice::from_ref(&root), &["test".to_string()], true);
+        assert_eq!(files, vec![root.join("lib/bits.circom"), root.join("main.circom")]);
+
+        // Files reached through multiple inputs are only returned once.
+        let inputs = [root.join("**/*.circom"), root.join("main.circom")];
+        let files = expand_inputs(&inputs, &[], true);
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0], root.join("lib/bits.circom"));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_ignore_files() {
+        let root = std::env::temp_dir().join(format!("circomspect-ignore-{}", std::process::id()));
+        for file in [
+            "main.circom",
+            "build/main.circom",
+            "circuits/main.circom",
+            "circuits/main.tmp.circom",
+            "circuits/keep.tmp.circom",
+            "circuits/node_modules/circomlib/gates.circom",
+        ] {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::write(root.join(".gitignore"), "# Build output\nbuild/\n*.tmp.circom\n").unwrap();
+        fs::write(root.join("circuits/.circomspectignore"), "node_modules\n!keep.tmp.circom\n")
+            .unwrap();
+
+        // Ignore files in parent directories apply up to the repository root.
+        let circuits = root.join("circuits");
+        let files = expand_inputs(std::slice::from_ref(&circuits), &[], true);
+        assert_eq!(files, vec![circuits.join("keep.tmp.circom"), circuits.join("main.circom")]);
+        let files = expand_inputs(std::slice::from_ref(&root), &[], true);
+        assert_eq!(files.len(), 3);
+
+        let files = expand_inputs(std::slice::from_ref(&root), &[], false);
+        assert_eq!(files.len(), 6);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
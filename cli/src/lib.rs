@@ -0,0 +1,1229 @@
+//! Circomspect is a static analyzer and linter for Circom programs. This crate
+//! exposes the analysis driver used by the `circomspect` binary, which allows
+//! other tools to run the analysis passes without invoking the binary.
+//!
+//! ```no_run
+//! use circomspect::{analyze_file, Config};
+//!
+//! let (reports, _) = analyze_file("circuit.circom".as_ref(), &Config::default());
+//! for report in reports {
+//!     println!("{}: {}", report.id(), report.message());
+//! }
+//! ```
+use std::collections::{HashMap, HashSet};
+use std::ops::{Range, RangeInclusive};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use parser::{ParseResult, Remapping};
+use program_analysis::analysis_cache::AnalysisCache;
+use program_analysis::analysis_runner::AnalysisRunner;
+use program_analysis::constant_parameters::annotate_constant_parameters;
+use program_analysis::compiled_circuit::{
+    annotate_reports, cross_check_reports, CircuitMapping, CompiledCircuit, SymbolMapping,
+};
+use program_analysis::definition_complexity::{ComplexityPass, ComplexityThresholds};
+use program_analysis::get_analysis_passes;
+use program_analysis::hygiene::find_hygiene_issues;
+use program_analysis::naming_conventions::{find_naming_convention_violations, NamingConventions};
+use program_analysis::main_inputs::find_unconstrained_main_inputs;
+use program_analysis::instantiation::{
+    estimate_instantiation, estimate_template_instantiation, InstantiationMetrics,
+};
+use program_structure::ast::Expression;
+use program_structure::constants::Curve;
+use program_structure::file_definition::{FileID, FileLibrary, FileLocation};
+use program_structure::report::{
+    MessageCategory, Report, ReportCollection, SortReports, SuppressionKind,
+};
+use program_structure::report_code::{ReportCode, RuleCategory};
+use program_structure::report_writer::ReportWriter;
+use program_structure::rules::find_rule;
+use program_structure::sym_file::Symbol;
+use program_structure::function_data::FunctionInfo;
+use program_structure::template_data::TemplateInfo;
+
+pub mod inputs;
+pub mod progress;
+pub mod summary;
+
+pub use progress::log_message;
+
+use summary::Summary;
+
+/// The default target Circom compiler version. This is the latest version
+/// supported by the parser, so that files using any supported language
+/// feature can be analyzed without passing `--circom-version`.
+pub const COMPILER_VERSION: &str = parser::LATEST_COMPILER_VERSION;
+
+/// Configuration used when analyzing Circom programs.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Library path(s) used to resolve include statements.
+    pub libraries: Vec<PathBuf>,
+    /// Include path remappings (applied before the library paths).
+    pub remappings: Vec<Remapping>,
+    /// The curve used to analyze the program.
+    pub curve: Curve,
+    /// Target Circom compiler version (used for files without a version pragma).
+    pub compiler_version: String,
+    /// Results from analysis passes with the given IDs (or rule names) are
+    /// ignored.
+    pub allow_list: Vec<String>,
+    /// Results from analysis passes with the given IDs (or rule names) are
+    /// promoted to errors. If the list contains `warnings`, all warnings are
+    /// promoted to errors.
+    pub deny_list: Vec<String>,
+    /// Only results at or above this level are returned.
+    pub output_level: MessageCategory,
+    /// If set, warnings from soundness rules (like under-constrained signals)
+    /// are reported as errors.
+    pub soundness_errors: bool,
+    /// If non-empty, only results from rules in the given categories are
+    /// returned.
+    pub only_categories: Vec<RuleCategory>,
+    /// Results from rules in the given categories are ignored.
+    pub skipped_categories: Vec<RuleCategory>,
+    /// Cache analysis results in the given directory between runs.
+    pub cache_dir: Option<PathBuf>,
+    /// If set, the remaining analysis passes are skipped for any function or
+    /// template whose analysis exceeds the given time limit.
+    pub timeout_per_template: Option<Duration>,
+    /// Analysis passes with the given names are not run.
+    pub disabled_passes: Vec<String>,
+    /// If set, only definitions reachable from the entry point are analyzed.
+    pub entry_point: Option<EntryPoint>,
+    /// If non-empty, only templates and functions with a name matching one of
+    /// the given glob patterns (e.g. `Num2Bits*`) are analyzed.
+    pub selected_definitions: Vec<String>,
+    /// Templates and functions with a name matching one of the given glob
+    /// patterns are not analyzed.
+    pub skipped_definitions: Vec<String>,
+    /// If set, only reports with a primary location intersecting one of the
+    /// changed lines are returned.
+    pub changed_lines: Option<ChangedLines>,
+    /// If set, `log` statements and TODO/FIXME comments in definitions
+    /// reachable from the main component are reported.
+    pub hygiene: bool,
+    /// Naming conventions enforced for signals and templates.
+    pub naming_conventions: NamingConventions,
+    /// Thresholds used to report overly complex functions and templates.
+    pub complexity_thresholds: ComplexityThresholds,
+    /// If set, reports about unconstrained signals are cross-checked against
+    /// the compiled circuit.
+    pub compiled_circuit: Option<CompiledCircuit>,
+    /// If set, reports affecting signals are annotated with the corresponding
+    /// compiled signals from the given `.sym` file.
+    pub symbols: Option<Vec<Symbol>>,
+}
+
+/// The (1-based) line ranges changed in each file, indexed by canonical file
+/// path.
+pub type ChangedLines = HashMap<PathBuf, Vec<RangeInclusive<usize>>>;
+
+/// The entry point used to restrict the analysis to reachable definitions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EntryPoint {
+    /// The main component of the program. Templates are also analyzed using
+    /// the parameter values passed to each template instance.
+    MainComponent,
+    /// The given template (with unknown parameter values).
+    Template(String),
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            libraries: Vec::new(),
+            remappings: Vec::new(),
+            curve: Curve::default(),
+            compiler_version: COMPILER_VERSION.to_string(),
+            allow_list: Vec::new(),
+            deny_list: Vec::new(),
+            output_level: MessageCategory::Warning,
+            soundness_errors: true,
+            only_categories: Vec::new(),
+            skipped_categories: Vec::new(),
+            cache_dir: None,
+            timeout_per_template: None,
+            disabled_passes: Vec::new(),
+            entry_point: None,
+            selected_definitions: Vec::new(),
+            skipped_definitions: Vec::new(),
+            changed_lines: None,
+            hygiene: false,
+            naming_conventions: NamingConventions::default(),
+            complexity_thresholds: ComplexityThresholds::default(),
+            compiled_circuit: None,
+            symbols: None,
+        }
+    }
+}
+
+/// Parses and analyzes the given file, together with all included files.
+/// Returns the generated reports (filtered based on the allow list and output
+/// level) together with the file library used to resolve report locations.
+#[must_use]
+pub fn analyze_file(file_path: &Path, config: &Config) -> (ReportCollection, FileLibrary) {
+    analyze_project(&[file_path.to_path_buf()], config)
+}
+
+/// Parses and analyzes the given files (or directories), together with all
+/// included files. Returns the generated reports (filtered based on the allow
+/// list and output level) together with the file library used to resolve
+/// report locations.
+#[must_use]
+pub fn analyze_project(file_paths: &[PathBuf], config: &Config) -> (ReportCollection, FileLibrary) {
+    let parse_result = parser::parse_files(
+        &file_paths.to_vec(),
+        &config.libraries,
+        &config.remappings,
+        &config.compiler_version,
+    );
+    filter_reports(run_analysis(parse_result, config, &mut NullWriter, true), config)
+}
+
+/// Parses and analyzes the given Circom source. Here, `name` is used to
+/// identify the source in reports. Includes are resolved relative to the
+/// current directory and the configured library paths. Returns the generated
+/// reports (filtered based on the allow list and output level) together with
+/// the file library used to resolve report locations.
+#[must_use]
+pub fn analyze_source(name: &str, src: &str, config: &Config) -> (ReportCollection, FileLibrary) {
+    let parse_result = parser::parse_source(
+        name,
+        src,
+        &config.libraries,
+        &config.remappings,
+        &config.compiler_version,
+    );
+    filter_reports(run_analysis(parse_result, config, &mut NullWriter, true), config)
+}
+
+/// Analyzes the parsed program. Reports are written to the given writer as
+/// they are generated. If `quiet` is set, progress messages are not logged.
+///
+/// Note that the returned reports are not filtered based on the allow list
+/// and output level, since this is handled by the writer.
+pub fn run_analysis(
+    parse_result: ParseResult,
+    config: &Config,
+    writer: &mut dyn ReportWriter,
+    quiet: bool,
+) -> (ReportCollection, FileLibrary) {
+    let (reports, file_library, _) = run_analysis_with_summary(parse_result, config, writer, quiet);
+    (reports, file_library)
+}
+
+/// Analyzes the parsed program like `run_analysis`, and also returns summary
+/// statistics for the analysis. The summary only covers reports which are not
+/// filtered out based on the allow list and output level.
+pub fn run_analysis_with_summary(
+    parse_result: ParseResult,
+    config: &Config,
+    writer: &mut dyn ReportWriter,
+    quiet: bool,
+) -> (ReportCollection, FileLibrary, Summary) {
+    run_analysis_with_cache(parse_result, load_cache(config), config, writer, quiet)
+}
+
+/// Loads the analysis cache from the configured cache directory, if any. The
+/// cache is only reused if the settings affecting the analysis are unchanged.
+#[must_use]
+pub fn load_cache(config: &Config) -> Option<AnalysisCache> {
+    let cache_dir = config.cache_dir.as_ref()?;
+    let complexity_thresholds = format!("{:?}", config.complexity_thresholds);
+    let naming_conventions = format!("{:?}", config.naming_conventions);
+    let settings = [&config.compiler_version, &complexity_thresholds, &naming_conventions];
+    Some(AnalysisCache::load(cache_dir, &settings.map(String::as_str)))
+}
+
+/// Analyzes the parsed program like `run_analysis_with_summary`, using the
+/// given cache (typically returned by `load_cache`) to avoid re-analyzing
+/// unchanged definitions. The cache is written back to disk afterwards.
+pub fn run_analysis_with_cache(
+    parse_result: ParseResult,
+    cache: Option<AnalysisCache>,
+    config: &Config,
+    writer: &mut dyn ReportWriter,
+    quiet: bool,
+) -> (ReportCollection, FileLibrary, Summary) {
+    let mut reports = ReportCollection::new();
+    let mut summary = Summary::default();
+    let mut processor = ReportProcessor::new(config);
+
+    let reachable;
+    let invalid_definitions;
+    let mut main_component = None;
+    let mut runner = match parse_result {
+        // Analyze a complete Circom program.
+        ParseResult::Program(program, mut warnings) => {
+            invalid_definitions =
+                definitions_with_syntax_errors(&warnings, &program.functions, &program.templates);
+            if let Expression::Call { meta, id, .. } = &program.initial_template_call {
+                processor.compiled_signals = CompiledSignals::new(config, id, &program.templates);
+                main_component = Some(MainComponent {
+                    template_name: id.clone(),
+                    file_id: meta.file_id,
+                    file_location: meta.file_location(),
+                });
+            }
+            processor.process(&mut warnings, None, &program.file_library, writer, &mut summary);
+            reports.append(&mut warnings);
+            reachable = config.entry_point.as_ref().and_then(|entry_point| match entry_point {
+                EntryPoint::MainComponent => estimate_instantiation(
+                    &program.initial_template_call,
+                    &program.templates,
+                    &program.functions,
+                    &config.curve,
+                ),
+                EntryPoint::Template(name) => estimate_template_instantiation(
+                    name,
+                    &program.templates,
+                    &program.functions,
+                    &config.curve,
+                ),
+            });
+            if config.hygiene {
+                let mut hygiene_reports = match (&config.entry_point, &reachable) {
+                    (Some(EntryPoint::MainComponent), Some(reachable)) => find_hygiene_issues(
+                        &program.templates,
+                        &program.functions,
+                        reachable,
+                        &program.file_library,
+                    ),
+                    _ => estimate_instantiation(
+                        &program.initial_template_call,
+                        &program.templates,
+                        &program.functions,
+                        &config.curve,
+                    )
+                    .map(|reachable| {
+                        find_hygiene_issues(
+                            &program.templates,
+                            &program.functions,
+                            &reachable,
+                            &program.file_library,
+                        )
+                    })
+                    .unwrap_or_default(),
+                };
+                processor.process(
+                    &mut hygiene_reports,
+                    None,
+                    &program.file_library,
+                    writer,
+                    &mut summary,
+                );
+                reports.append(&mut hygiene_reports);
+            }
+            reports.append(&mut check_naming_conventions(
+                &program.templates,
+                &program.file_library,
+                &processor,
+                writer,
+                &mut summary,
+            ));
+            AnalysisRunner::new(
+                &config.curve,
+                program.file_library,
+                program.functions,
+                program.templates,
+            )
+        }
+        // Analyze a set of Circom template files.
+        ParseResult::Library(library, mut warnings) => {
+            invalid_definitions =
+                definitions_with_syntax_errors(&warnings, &library.functions, &library.templates);
+            processor.process(&mut warnings, None, &library.file_library, writer, &mut summary);
+            reports.append(&mut warnings);
+            if config.hygiene && !quiet {
+                log_message("No main component found. Hygiene checks will be skipped.");
+            }
+            if (config.compiled_circuit.is_some() || config.symbols.is_some()) && !quiet {
+                log_message(
+                    "No main component found. Reports will not be mapped to the compiled circuit.",
+                );
+            }
+            reports.append(&mut check_naming_conventions(
+                &library.templates,
+                &library.file_library,
+                &processor,
+                writer,
+                &mut summary,
+            ));
+            reachable = config.entry_point.as_ref().and_then(|entry_point| match entry_point {
+                EntryPoint::MainComponent => None,
+                EntryPoint::Template(name) => estimate_template_instantiation(
+                    name,
+                    &library.templates,
+                    &library.functions,
+                    &config.curve,
+                ),
+            });
+            AnalysisRunner::new(
+                &config.curve,
+                library.file_library,
+                library.functions,
+                library.templates,
+            )
+        }
+    };
+    let mut passes = get_analysis_passes();
+    passes.register(ComplexityPass::new(config.complexity_thresholds.clone()));
+    for name in &config.disabled_passes {
+        if !passes.disable(name) {
+            log_message(&format!("Unknown analysis pass `{name}`."));
+        }
+    }
+    runner = runner.with_passes(passes);
+    if let (Some(entry_point), None) = (&config.entry_point, &reachable) {
+        let message = match entry_point {
+            EntryPoint::MainComponent => "No main component found.".to_string(),
+            EntryPoint::Template(name) => format!("Unknown template `{name}`."),
+        };
+        log_message(&format!("{message} All definitions will be analyzed."));
+    }
+    if let Some(cache) = cache {
+        runner = runner.with_cache(cache);
+    }
+    if let Some(timeout) = config.timeout_per_template {
+        runner = runner.with_timeout(timeout);
+    }
+    reports.append(&mut analyze_definitions(
+        &mut runner,
+        config,
+        &processor,
+        main_component.as_ref(),
+        reachable.as_ref(),
+        &invalid_definitions,
+        writer,
+        &mut summary,
+        quiet,
+    ));
+    if let Some(cache) = runner.cache() {
+        if !quiet {
+            log_message(&format!(
+                "{} file(s) and {} definition(s) loaded from cache.",
+                cache.parsed_files().hits(),
+                cache.hits()
+            ));
+        }
+        if cache.save().is_err() {
+            log_message("Failed to write analysis cache.");
+        }
+    }
+    summary.pass_timings = runner.pass_timings().clone();
+    if !quiet {
+        progress::log_timings("Time per analysis pass", summary.pass_timings.clone());
+    }
+    let file_library = runner.file_library().clone();
+    reports.sort(&file_library);
+    (reports, file_library, summary)
+}
+
+/// Checks the configured naming conventions for all templates. The generated
+/// reports are written to the given writer and added to the summary.
+fn check_naming_conventions(
+    templates: &TemplateInfo,
+    file_library: &FileLibrary,
+    processor: &ReportProcessor,
+    writer: &mut dyn ReportWriter,
+    summary: &mut Summary,
+) -> ReportCollection {
+    let naming_conventions = &processor.config.naming_conventions;
+    if naming_conventions.is_empty() {
+        return ReportCollection::new();
+    }
+    let mut reports = find_naming_convention_violations(templates, naming_conventions);
+    processor.process(&mut reports, None, file_library, writer, summary);
+    reports
+}
+
+/// Analyzes all functions and templates. If `reachable` is given, only the
+/// definitions reachable from the entry point are analyzed, and templates are
+/// also analyzed using the parameter values passed to each instance. The
+/// reports for each definition are post-processed using the given report
+/// processor. If the main component is given, the input signals of the main
+/// template are also checked. Definitions containing syntax errors (given by
+/// `invalid_definitions`) are skipped.
+#[allow(clippy::too_many_arguments)]
+fn analyze_definitions(
+    runner: &mut AnalysisRunner,
+    config: &Config,
+    processor: &ReportProcessor,
+    main_component: Option<&MainComponent>,
+    reachable: Option<&InstantiationMetrics>,
+    invalid_definitions: &HashSet<String>,
+    writer: &mut dyn ReportWriter,
+    summary: &mut Summary,
+    quiet: bool,
+) -> ReportCollection {
+    let mut all_reports = ReportCollection::new();
+
+    // Analyze all functions.
+    // Definitions are analyzed in a fixed order to ensure that the output is
+    // deterministic.
+    let mut function_names = runner.functions().keys().cloned().collect::<Vec<_>>();
+    function_names.sort();
+    if let Some(reachable) = reachable {
+        function_names.retain(|name| reachable.functions.contains(name));
+    }
+    function_names.retain(|name| is_selected(name, config));
+    skip_invalid_definitions(&mut function_names, invalid_definitions, "function", quiet);
+    summary.functions = function_names.len();
+    for name in function_names {
+        if !quiet {
+            log_message(&format!("analyzing function '{name}'"));
+        }
+        let mut new_reports = runner.analyze_function(&name).cloned().unwrap_or_default();
+        processor.process(&mut new_reports, Some(&name), runner.file_library(), writer, summary);
+        all_reports.extend(new_reports);
+    }
+    // Analyze all templates.
+    let mut template_names = runner.templates().keys().cloned().collect::<Vec<_>>();
+    template_names.sort();
+    if let Some(reachable) = reachable {
+        template_names.retain(|name| reachable.instances.contains_key(name));
+    }
+    template_names.retain(|name| is_selected(name, config));
+    skip_invalid_definitions(&mut template_names, invalid_definitions, "template", quiet);
+    summary.templates = template_names.len();
+    for name in template_names {
+        if !quiet {
+            log_message(&format!("analyzing template '{name}'"));
+        }
+        let mut new_reports = runner.analyze_template(&name).cloned().unwrap_or_default();
+        if let Some(main_component) =
+            main_component.filter(|main_component| main_component.template_name == name)
+        {
+            if let Some(Ok(cfg)) = runner.get_cfg(&name) {
+                new_reports.extend(find_unconstrained_main_inputs(
+                    &cfg,
+                    main_component.file_id,
+                    main_component.file_location.clone(),
+                ));
+            }
+        }
+        let instances = reachable.and_then(|reachable| reachable.instances.get(&name));
+        let params = runner
+            .templates()
+            .get(&name)
+            .map(|template| template.get_name_of_params().clone())
+            .unwrap_or_default();
+        let mut instance_reports = Vec::new();
+        for args in instances.into_iter().flatten() {
+            // Instances with unknown parameter values are covered by the generic template.
+            if args.iter().any(Option::is_some) {
+                let reports = runner.analyze_template_instance(&name, args).unwrap_or_default();
+                instance_reports.push((format_arguments(&params, args), reports));
+            }
+        }
+        dedup_reports(&mut new_reports);
+        merge_instance_reports(&mut new_reports, instance_reports);
+        if let Some(instances) = instances {
+            annotate_constant_parameters(&mut new_reports, &name, &params, instances);
+        }
+        processor.process(&mut new_reports, Some(&name), runner.file_library(), writer, summary);
+        all_reports.extend(new_reports);
+    }
+    all_reports
+}
+
+/// Returns the names of the functions and templates containing syntax errors
+/// that the parser recovered from. Since invalid statements are dropped from
+/// the AST, analyzing these definitions would generate spurious reports (e.g.
+/// for signals only used by a dropped statement).
+fn definitions_with_syntax_errors(
+    reports: &ReportCollection,
+    functions: &FunctionInfo,
+    templates: &TemplateInfo,
+) -> HashSet<String> {
+    let errors = reports
+        .iter()
+        .filter(|report| matches!(report.code(), ReportCode::ParseFail))
+        .flat_map(|report| report.primary())
+        .collect::<Vec<_>>();
+    let definitions =
+        functions
+            .values()
+            .map(|function| (function.get_name(), function.get_file_id(), function.get_body()))
+            .chain(templates.values().map(|template| {
+                (template.get_name(), template.get_file_id(), template.get_body())
+            }));
+    definitions
+        .filter(|(_, file_id, body)| {
+            let location = body.get_meta().file_location();
+            errors
+                .iter()
+                .any(|label| label.file_id == *file_id && location.contains(&label.range.start))
+        })
+        .map(|(name, _, _)| name.to_string())
+        .collect()
+}
+
+/// Removes definitions containing syntax errors from the given list of names.
+fn skip_invalid_definitions(
+    names: &mut Vec<String>,
+    invalid_definitions: &HashSet<String>,
+    kind: &str,
+    quiet: bool,
+) {
+    names.retain(|name| {
+        let is_invalid = invalid_definitions.contains(name);
+        if is_invalid && !quiet {
+            log_message(&format!("skipping {kind} '{name}' since it contains syntax errors"));
+        }
+        !is_invalid
+    });
+}
+
+/// The main template of the program, together with the location of the main
+/// component.
+struct MainComponent {
+    template_name: String,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+/// The post-processing applied to each batch of reports (the reports for a
+/// single definition, or the reports generated for the whole program) before
+/// the reports are written.
+struct ReportProcessor<'a> {
+    config: &'a Config,
+    deny_list: Vec<String>,
+    compiled_signals: CompiledSignals<'a>,
+}
+
+impl<'a> ReportProcessor<'a> {
+    fn new(config: &'a Config) -> Self {
+        ReportProcessor {
+            config,
+            deny_list: to_rule_ids(&config.deny_list),
+            compiled_signals: CompiledSignals::default(),
+        }
+    }
+
+    /// Filters and promotes the given reports based on the configuration, maps
+    /// them to the compiled circuit, and sorts them. The resulting reports are
+    /// written to the given writer and added to the summary for the given
+    /// definition.
+    fn process(
+        &self,
+        reports: &mut ReportCollection,
+        definition: Option<&str>,
+        file_library: &FileLibrary,
+        writer: &mut dyn ReportWriter,
+        summary: &mut Summary,
+    ) {
+        retain_changed(reports, file_library, self.config);
+        retain_categories(reports, self.config);
+        promote_soundness_reports(reports, self.config);
+        self.compiled_signals.apply(reports);
+        deny_reports(reports, &self.deny_list);
+        reports.sort(file_library);
+        writer.write(reports, file_library);
+        summary.add_reports(definition, &filter(reports, self.config), file_library);
+    }
+}
+
+/// Maps reports to the signals of the compiled circuit (if given).
+#[derive(Default)]
+struct CompiledSignals<'a> {
+    symbols: Option<SymbolMapping<'a>>,
+    circuit: Option<CircuitMapping<'a>>,
+}
+
+impl<'a> CompiledSignals<'a> {
+    fn new(config: &'a Config, main_template: &str, templates: &TemplateInfo) -> Self {
+        CompiledSignals {
+            symbols: config
+                .symbols
+                .as_ref()
+                .map(|symbols| SymbolMapping::new(symbols, main_template, templates)),
+            circuit: config
+                .compiled_circuit
+                .as_ref()
+                .map(|circuit| CircuitMapping::new(circuit, main_template, templates)),
+        }
+    }
+
+    /// Annotates the given reports with the affected compiled signals, and
+    /// cross-checks them against the compiled constraint system.
+    fn apply(&self, reports: &mut ReportCollection) {
+        if let Some(symbols) = &self.symbols {
+            annotate_reports(reports, symbols);
+        }
+        if let Some(circuit) = &self.circuit {
+            cross_check_reports(reports, circuit);
+        }
+    }
+}
+
+/// Returns true if the definition with the given name should be analyzed,
+/// based on the selected and skipped definitions.
+fn is_selected(name: &str, config: &Config) -> bool {
+    let matches = |patterns: &[String]| patterns.iter().any(|pattern| glob_match(pattern, name));
+    (config.selected_definitions.is_empty() || matches(&config.selected_definitions))
+        && !matches(&config.skipped_definitions)
+}
+
+/// Returns true if the text matches the given glob pattern. Here, `*` matches
+/// any sequence of characters, and `?` matches any single character.
+#[must_use]
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+    // The position of the last `*` in the pattern, and the position in the
+    // text matched by the `*` so far.
+    let mut backtrack = None;
+    let (mut i, mut j) = (0, 0);
+    while j < text.len() {
+        match pattern.get(i) {
+            Some('*') => {
+                backtrack = Some((i, j));
+                i += 1;
+            }
+            Some(c) if *c == '?' || *c == text[j] => {
+                i += 1;
+                j += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` match one more character.
+                Some((star, matched)) => {
+                    backtrack = Some((star, matched + 1));
+                    i = star + 1;
+                    j = matched + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[i..].iter().all(|c| *c == '*')
+}
+
+/// Removes duplicate reports (e.g. generated by multiple template instances)
+/// while preserving the order of the remaining reports.
+fn dedup_reports(reports: &mut ReportCollection) {
+    let mut seen = HashSet::new();
+    reports.retain(|report| seen.insert(serde_json::to_string(report).unwrap_or_default()));
+}
+
+/// Merges the reports generated for the given template instances into the
+/// reports generated for the generic template. Reports which are already
+/// generated for the generic template are dropped, and reports with the same
+/// rule and primary locations are merged into a single report listing the
+/// parameter values of each instance.
+fn merge_instance_reports(
+    reports: &mut ReportCollection,
+    instance_reports: Vec<(String, ReportCollection)>,
+) {
+    let mut seen = reports
+        .iter()
+        .map(|report| serde_json::to_string(report).unwrap_or_default())
+        .collect::<HashSet<_>>();
+    let mut merged: Vec<(Report, Vec<String>)> = Vec::new();
+    for (args, new_reports) in instance_reports {
+        for report in new_reports {
+            if !seen.insert(serde_json::to_string(&report).unwrap_or_default()) {
+                continue;
+            }
+            let key = instance_report_key(&report);
+            match merged.iter_mut().find(|(other, _)| instance_report_key(other) == key) {
+                Some((_, instances)) => {
+                    if !instances.contains(&args) {
+                        instances.push(args.clone());
+                    }
+                }
+                None => merged.push((report, vec![args.clone()])),
+            }
+        }
+    }
+    for (mut report, instances) in merged {
+        if instances.len() > 1 {
+            let message = format!(
+                "{} (Found in {} instances with parameters {}.)",
+                report.message(),
+                instances.len(),
+                instances.iter().map(|args| format!("`{args}`")).collect::<Vec<_>>().join(", ")
+            );
+            report.set_message(message);
+        }
+        reports.push(report);
+    }
+}
+
+/// Reports generated for different template instances are considered to
+/// describe the same issue if they have the same rule and primary locations.
+fn instance_report_key(report: &Report) -> (String, Vec<(FileID, Range<usize>)>) {
+    let locations =
+        report.primary().iter().map(|label| (label.file_id, label.range.clone())).collect();
+    (report.id(), locations)
+}
+
+/// Formats the arguments passed to a template instance (like `n = 8, m = ?`).
+fn format_arguments(params: &[String], args: &[Option<num_bigint_dig::BigInt>]) -> String {
+    params
+        .iter()
+        .zip(args)
+        .map(|(param, arg)| match arg {
+            Some(value) => format!("{param} = {value}"),
+            None => format!("{param} = ?"),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Returns the reports which are not filtered out based on the allow list and
+/// output level.
+fn filter(reports: &[Report], config: &Config) -> ReportCollection {
+    let allow_list = to_rule_ids(&config.allow_list);
+    reports
+        .iter()
+        .filter(|report| filter_by_id(report, &allow_list))
+        .filter(|report| filter_by_level(report, &config.output_level))
+        .cloned()
+        .collect()
+}
+
+fn filter_reports(
+    (reports, file_library): (ReportCollection, FileLibrary),
+    config: &Config,
+) -> (ReportCollection, FileLibrary) {
+    (filter(&reports, config), file_library)
+}
+
+/// A writer which discards all reports.
+struct NullWriter;
+
+impl ReportWriter for NullWriter {
+    fn write(&mut self, _: &ReportCollection, _: &FileLibrary) -> usize {
+        0
+    }
+
+    fn written(&self) -> usize {
+        0
+    }
+}
+
+/// Maps rule names (like `unused-variable`) to the corresponding rule ID.
+/// Other values are returned unchanged.
+#[must_use]
+pub fn to_rule_ids(ids_or_names: &[String]) -> Vec<String> {
+    ids_or_names
+        .iter()
+        .map(|id_or_name| match find_rule(id_or_name) {
+            Some(rule) => rule.id(),
+            None => id_or_name.clone(),
+        })
+        .collect()
+}
+
+/// Promotes reports with an ID in the given list to errors. If the list
+/// contains `warnings`, all warnings are promoted to errors.
+pub fn deny_reports(reports: &mut ReportCollection, deny_list: &[String]) {
+    let deny_warnings = deny_list.iter().any(|id| id == "warnings");
+    for report in reports.iter_mut() {
+        let is_warning = report.category() == &MessageCategory::Warning;
+        if deny_list.contains(&report.id()) || (deny_warnings && is_warning) {
+            report.set_category(MessageCategory::Error);
+        }
+    }
+}
+
+/// Promotes warnings from soundness rules to errors, unless this is disabled
+/// by the configuration. This is applied before the reports are cross-checked
+/// against the compiled circuit, so that refuted findings are downgraded again.
+fn promote_soundness_reports(reports: &mut ReportCollection, config: &Config) {
+    if !config.soundness_errors {
+        return;
+    }
+    for report in reports.iter_mut() {
+        let is_warning = report.category() == &MessageCategory::Warning;
+        if is_warning && report.code().rule_category() == RuleCategory::Soundness {
+            report.set_category(MessageCategory::Error);
+        }
+    }
+}
+
+/// Removes reports from rules in categories which are not selected by the
+/// configuration. Reports without a corresponding rule (like parse errors) are
+/// always retained.
+fn retain_categories(reports: &mut ReportCollection, config: &Config) {
+    reports.retain(|report| {
+        if find_rule(&report.id()).is_none() {
+            return true;
+        }
+        let category = report.code().rule_category();
+        (config.only_categories.is_empty() || config.only_categories.contains(&category))
+            && !config.skipped_categories.contains(&category)
+    });
+}
+
+/// Removes reports without a primary location intersecting one of the changed
+/// lines, if changed lines are given by the configuration.
+fn retain_changed(reports: &mut ReportCollection, file_library: &FileLibrary, config: &Config) {
+    let Some(changed_lines) = &config.changed_lines else {
+        return;
+    };
+    let storage = file_library.to_storage();
+    reports.retain(|report| {
+        report.primary().iter().any(|label| {
+            let Some(ranges) = storage
+                .get(label.file_id)
+                .and_then(|file| changed_lines.get(Path::new(file.name())))
+            else {
+                return false;
+            };
+            let first = file_library.get_line(label.range.start, label.file_id);
+            let last = file_library
+                .get_line(label.range.end.max(label.range.start + 1) - 1, label.file_id);
+            let (Some(first), Some(last)) = (first, last) else {
+                return false;
+            };
+            ranges.iter().any(|range| *range.start() <= last && first <= *range.end())
+        })
+    });
+}
+
+/// Marks reports with an ID in the given allow list as suppressed. This is
+/// used to include allowed reports in the output for auditing purposes,
+/// rather than dropping them.
+pub fn suppress_reports(reports: &mut ReportCollection, allow_list: &[String]) {
+    for report in reports.iter_mut() {
+        if allow_list.contains(&report.id()) {
+            let justification = format!("The rule `{}` is allowed using `--allow`.", report.id());
+            report.suppress(SuppressionKind::External, justification);
+        }
+    }
+}
+
+/// Returns true if the given reports should cause the analysis to fail.
+///
+///   1. If `error_on` is non-empty, reports with an ID in the list always
+///      cause the analysis to fail, and other errors are ignored. If
+///      `error_on` is empty, any error causes the analysis to fail.
+///   2. If `max_warnings` is set, warnings only cause the analysis to fail if
+///      there are more than `max_warnings` warnings. Only warnings are counted
+///      towards the maximum.
+///
+/// If neither is set, any report causes the analysis to fail.
+#[must_use]
+pub fn is_failure(reports: &[Report], error_on: &[String], max_warnings: Option<usize>) -> bool {
+    if reports.iter().any(|report| error_on.contains(&report.id())) {
+        return true;
+    }
+    let count = |category| reports.iter().filter(|report| report.category() == &category).count();
+    match max_warnings {
+        Some(max_warnings) => {
+            (error_on.is_empty() && count(MessageCategory::Error) > 0)
+                || count(MessageCategory::Warning) > max_warnings
+        }
+        None => error_on.is_empty() && !reports.is_empty(),
+    }
+}
+
+/// Returns true if the report level is greater than or equal to the given
+/// level.
+#[must_use]
+pub fn filter_by_level(report: &Report, output_level: &MessageCategory) -> bool {
+    report.category() >= output_level
+}
+
+/// Returns true if the report ID is not in the given list.
+#[must_use]
+pub fn filter_by_id(report: &Report, allow_list: &[String]) -> bool {
+    !allow_list.contains(&report.id())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRC: &str = r#"
+        pragma circom 2.0.0;
+
+        template T() {
+            signal input in;
+            signal output out;
+            out <-- in * in;
+            in * (in - 1) === 0;
+        }
+
+        component main = T();
+    "#;
+
+    #[test]
+    fn test_analyze_source() {
+        let (reports, _) = analyze_source("main.circom", SRC, &Config::default());
+        assert!(reports.iter().any(|report| report.id() == "CS0019"));
+
+        // Allowed results are filtered out.
+        let config =
+            Config { allow_list: vec!["unconstrained-output".to_string()], ..Config::default() };
+        let (reports, _) = analyze_source("main.circom", SRC, &config);
+        assert!(!reports.iter().any(|report| report.id() == "CS0019"));
+
+        // Denied results are promoted to errors.
+        let config = Config { deny_list: vec!["warnings".to_string()], ..Config::default() };
+        let (reports, _) = analyze_source("main.circom", SRC, &config);
+        assert!(reports.iter().all(|report| report.category() == &MessageCategory::Error));
+
+        // Rules can be denied by name.
+        let config = Config {
+            deny_list: vec!["unconstrained-output".to_string()],
+            soundness_errors: false,
+            ..Config::default()
+        };
+        let (reports, _) = analyze_source("main.circom", SRC, &config);
+        let report = reports.iter().find(|report| report.id() == "CS0019").unwrap();
+        assert_eq!(report.category(), &MessageCategory::Error);
+
+        // Soundness warnings are promoted to errors by default.
+        let is_soundness =
+            |report: &&Report| report.code().rule_category() == RuleCategory::Soundness;
+        let is_error = |report: &&Report| report.category() == &MessageCategory::Error;
+        let (reports, _) = analyze_source("main.circom", SRC, &Config::default());
+        assert!(reports.iter().filter(is_soundness).all(|report| is_error(&report)));
+        assert!(!reports.iter().all(|report| is_error(&report)));
+        let config = Config { soundness_errors: false, ..Config::default() };
+        let (reports, _) = analyze_source("main.circom", SRC, &config);
+        assert!(!reports.iter().filter(is_soundness).all(|report| is_error(&report)));
+
+        // Results can be filtered by rule category.
+        let config = Config { only_categories: vec![RuleCategory::Soundness], ..Config::default() };
+        let (reports, _) = analyze_source("main.circom", SRC, &config);
+        assert!(!reports.is_empty() && reports.iter().all(|report| is_soundness(&report)));
+        let config =
+            Config { skipped_categories: vec![RuleCategory::Soundness], ..Config::default() };
+        let (reports, _) = analyze_source("main.circom", SRC, &config);
+        assert!(!reports.is_empty() && !reports.iter().any(|report| is_soundness(&report)));
+    }
+
+    #[test]
+    fn test_disabled_passes() {
+        let config = Config {
+            disabled_passes: vec!["unconstrained-outputs".to_string()],
+            ..Config::default()
+        };
+        let (reports, _) = analyze_source("main.circom", SRC, &config);
+        assert!(!reports.is_empty());
+        assert!(!reports.iter().any(|report| report.id() == "CS0019"));
+    }
+
+    #[test]
+    fn test_is_failure() {
+        let (reports, _) = analyze_source("main.circom", SRC, &Config::default());
+        assert!(!reports.is_empty());
+        assert!(is_failure(&reports, &[], None));
+        assert!(!is_failure(&[], &[], None));
+
+        // Only selected rules cause a failure.
+        assert!(is_failure(&reports, &["CS0019".to_string()], None));
+        assert!(!is_failure(&reports, &["CS0001".to_string()], None));
+
+        // Warnings only cause a failure if there are more than `max_warnings`.
+        let warnings =
+            reports.iter().filter(|report| report.category() == &MessageCategory::Warning).count();
+        let errors = reports.iter().any(|report| report.category() == &MessageCategory::Error);
+        assert_eq!(is_failure(&reports, &[], Some(warnings)), errors);
+        assert!(is_failure(&reports, &[], Some(warnings - 1)));
+
+        // With `error_on`, errors from other rules do not cause a failure, and
+        // only warnings count towards `max_warnings`.
+        let error_id = reports
+            .iter()
+            .find(|report| report.category() == &MessageCategory::Error)
+            .map(|report| report.id())
+            .unwrap();
+        assert!(is_failure(&reports, &[error_id], Some(warnings)));
+        assert!(!is_failure(&reports, &["CS0001".to_string()], Some(warnings)));
+        assert!(is_failure(&reports, &["CS0001".to_string()], Some(warnings - 1)));
+    }
+
+    #[test]
+    fn test_summary() {
+        let parse_result = parser::parse_source("main.circom", SRC, &[], &[], COMPILER_VERSION);
+        let (reports, _, summary) =
+            run_analysis_with_summary(parse_result, &Config::default(), &mut NullWriter, true);
+        let (filtered, _) = analyze_source("main.circom", SRC, &Config::default());
+        assert!(reports.len() >= filtered.len());
+
+        assert_eq!(summary.functions, 0);
+        assert_eq!(summary.templates, 1);
+        assert_eq!(summary.findings, filtered.len());
+        assert_eq!(summary.by_rule.values().sum::<usize>(), filtered.len());
+        assert_eq!(summary.by_file.get("main.circom"), Some(&filtered.len()));
+        assert_eq!(summary.by_definition.get("T"), Some(&filtered.len()));
+        assert!(summary.pass_timings.contains_key("signal-assignments"));
+
+        let json: serde_json::Value = serde_json::from_str(&summary.to_json()).unwrap();
+        assert_eq!(json["findings"], filtered.len());
+        assert_eq!(json["definitions"]["T"], filtered.len());
+    }
+
+    #[test]
+    fn test_entry_point() {
+        let src = r#"
+            pragma circom 2.0.0;
+
+            template Dead() {
+                signal input in;
+                signal output out;
+                out <-- in;
+            }
+
+            template A(n) {
+                signal input in[n];
+                signal output out;
+                out <== in[n];
+            }
+
+            template Main() {
+                signal input in[2];
+                signal output out;
+                component a = A(2);
+                a.in <== in;
+                out <== a.out;
+            }
+
+            component main = Main();
+        "#;
+        let (reports, _) = analyze_source("main.circom", src, &Config::default());
+        assert!(reports.iter().any(|report| report.id() == "CS0019"));
+        assert!(!reports.iter().any(|report| report.id() == "CS0015"));
+
+        // Only reachable templates are analyzed, using the actual parameter values.
+        let config = Config { entry_point: Some(EntryPoint::MainComponent), ..Config::default() };
+        let (reports, _) = analyze_source("main.circom", src, &config);
+        assert!(!reports.iter().any(|report| report.id() == "CS0019"));
+        assert_eq!(reports.iter().filter(|report| report.id() == "CS0015").count(), 1);
+
+        let entry_point = Some(EntryPoint::Template("Dead".to_string()));
+        let config = Config { entry_point, ..Config::default() };
+        let (reports, _) = analyze_source("main.circom", src, &config);
+        assert!(reports.iter().all(|report| report.id() != "CS0015"));
+        assert!(reports.iter().any(|report| report.id() == "CS0019"));
+    }
+
+    #[test]
+    fn test_recovered_syntax_errors() {
+        let src = r#"
+            pragma circom 2.0.0;
+
+            template A() {
+                signal input in;
+                signal output out;
+                out <== in +;
+            }
+
+            template B() {
+                signal input in;
+                signal output out;
+                out <-- in;
+            }
+        "#;
+        // Only the syntax error is reported for `A`, since the invalid
+        // statement is dropped from the AST.
+        let (reports, _) = analyze_source("main.circom", src, &Config::default());
+        let start = src.find("template A").unwrap();
+        let end = src.find("template B").unwrap();
+        let reports_for_a = reports
+            .iter()
+            .filter(|report| report.primary().iter().any(|label| label.range.start < end))
+            .collect::<Vec<_>>();
+        assert_eq!(reports_for_a.len(), 1);
+        assert_eq!(reports_for_a[0].id(), "P1000");
+        assert!(reports_for_a[0].primary()[0].range.start > start);
+        // Definitions without syntax errors are still analyzed.
+        assert!(reports
+            .iter()
+            .any(|report| report.primary().iter().any(|label| label.range.start > end)));
+    }
+
+    #[test]
+    fn test_unconstrained_main_inputs() {
+        let src = r#"
+            pragma circom 2.0.0;
+
+            template Sum(n) {
+                signal input in[n];
+                signal input unused;
+                signal output out;
+                var s = 0;
+                for (var i = 0; i < n; i++) {
+                    s += in[i];
+                }
+                out <== s;
+            }
+
+            component main = Sum(2);
+        "#;
+        // Inputs constrained through a variable accumulator are not reported.
+        let (reports, _) = analyze_source("main.circom", src, &Config::default());
+        let reports = reports.iter().filter(|report| report.id() == "CS0047").collect::<Vec<_>>();
+        assert_eq!(reports.len(), 1);
+        assert!(reports[0].message().contains("`unused`"));
+    }
+
+    #[test]
+    fn test_merge_instance_reports() {
+        let src = r#"
+            pragma circom 2.0.0;
+
+            template A(n) {
+                signal input in[n];
+                signal output out;
+                out <== in[n];
+            }
+
+            template Main() {
+                signal input in[3];
+                signal output out;
+                component a = A(2);
+                component b = A(3);
+                a.in <== [in[0], in[1]];
+                b.in <== in;
+                out <== a.out + b.out;
+            }
+
+            component main = Main();
+        "#;
+        // Findings at the same location are merged into a single report.
+        let config = Config { entry_point: Some(EntryPoint::MainComponent), ..Config::default() };
+        let (reports, _) = analyze_source("main.circom", src, &config);
+        let reports = reports.iter().filter(|report| report.id() == "CS0015").collect::<Vec<_>>();
+        assert_eq!(reports.len(), 1);
+        assert!(reports[0].message().contains("`n = 2`, `n = 3`"));
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("Num2Bits", "Num2Bits"));
+        assert!(!glob_match("Num2Bits", "Num2Bits_strict"));
+        assert!(glob_match("Num2Bits*", "Num2Bits_strict"));
+        assert!(glob_match("*Than", "LessThan"));
+        assert!(glob_match("L?ss*n", "LessThan"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("?", ""));
+        assert!(!glob_match("*Eq", "LessThan"));
+    }
+
+    #[test]
+    fn test_selected_definitions() {
+        let selected_definitions = vec!["U*".to_string()];
+        let config = Config { selected_definitions, ..Config::default() };
+        let (reports, _) = analyze_source("main.circom", SRC, &config);
+        assert!(reports.is_empty());
+
+        let skipped_definitions = vec!["T".to_string()];
+        let config = Config { skipped_definitions, ..Config::default() };
+        let (reports, _) = analyze_source("main.circom", SRC, &config);
+        assert!(reports.is_empty());
+
+        let selected_definitions = vec!["T".to_string()];
+        let config = Config { selected_definitions, ..Config::default() };
+        let (reports, _) = analyze_source("main.circom", SRC, &config);
+        assert!(!reports.is_empty());
+    }
+}
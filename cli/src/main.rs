@@ -1,32 +1,217 @@
-use anyhow::Result;
-use clap::{CommandFactory, Parser};
-use parser::ParseResult;
+use anyhow::anyhow;
+use clap::{CommandFactory, Parser, Subcommand};
+use num_bigint_dig::BigInt;
+use parser::{ParseResult, Remapping};
 use program_structure::constants::Curve;
-use std::io::Write;
-use std::path::PathBuf;
+use program_structure::file_definition::FileLibrary;
+use program_structure::sym_file::{parse_symbols, Symbol};
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
-use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
+use std::str::FromStr;
+use std::time::Duration;
 
-use program_analysis::get_analysis_passes;
-use program_structure::cfg::{Cfg, IntoCfg};
-use program_structure::report::MessageCategory;
-use program_structure::report::{Report, ReportCollection};
-use program_structure::file_definition::FileLibrary;
-use program_structure::function_data::FunctionInfo;
-use program_structure::report_writer::{StdoutWriter, ReportWriter, SarifWriter};
-use program_structure::template_data::TemplateInfo;
+use program_analysis::analysis_runner::AnalysisRunner;
+use program_analysis::compiled_circuit::CompiledCircuit;
+use program_structure::report::{ColorMode, MessageCategory};
+use program_structure::r1cs::R1CS;
+use program_structure::report::{self, Report, ReportCollection};
+use program_structure::report_code::RuleCategory;
+use program_structure::rules::{all_rules, find_rule, Rule};
+use program_structure::markdown_conversion::MarkdownOptions;
+use program_structure::sarif_conversion::{SarifOptions, DEFAULT_URI_BASE_ID};
+use program_structure::report_writer::{
+    CandidatesWriter, CheckstyleWriter, CodeQualityWriter, CsvWriter, GithubWriter, HtmlWriter,
+    JUnitWriter, JsonLinesWriter, MarkdownWriter, RdjsonWriter, StdoutWriter, ReportWriter,
+    SarifWriter,
+};
+
+mod changes;
+mod config_file;
+mod describe;
+mod diff;
+mod format;
+mod graph;
+mod metrics;
+mod smt;
+mod watch;
+
+pub(crate) use circomspect::{
+    filter_by_id, filter_by_level, is_failure, log_message, suppress_reports, to_rule_ids, Config,
+    EntryPoint, COMPILER_VERSION,
+};
+use circomspect::inputs::expand_inputs;
+use circomspect::progress::{self, Verbosity};
+use circomspect::summary::{Summary, SummaryWriter};
+use changes::{get_affected_files, get_changed_lines};
+use config_file::{load_config_file, ConfigFile, DEFAULT_CONFIG_FILE};
+use describe::{describe_templates, DescribeFormat};
+use diff::diff_results;
+use format::{format_files, FormatMode};
+use graph::{export_graph, GraphFormat, GraphKind};
+use metrics::print_metrics;
+use smt::{export_smt, SmtOptions};
 
-const COMPILER_VERSION: &str = "2.0.8";
 const DEFAULT_LEVEL: &str = "WARNING";
 const DEFAULT_CURVE: &str = "BN128";
+const DEFAULT_OUTPUT_FORMAT: &str = "TEXT";
+const DEFAULT_MAX_REPORTS_PER_RULE: &str = "10";
+const DEFAULT_COLOR_MODE: &str = "AUTO";
+const DEFAULT_HTML_FILE: &str = "circomspect.html";
+const DEFAULT_SUMMARY_FILE: &str = "circomspect-summary.json";
+const DEFAULT_CANDIDATES_FILE: &str = "circomspect-candidates.json";
+const DEFAULT_MARKDOWN_FILE: &str = "circomspect.md";
+const DEFAULT_CHECKSTYLE_FILE: &str = "circomspect-checkstyle.xml";
+const DEFAULT_JUNIT_FILE: &str = "circomspect-junit.xml";
+const DEFAULT_CSV_FILE: &str = "circomspect.csv";
+const DEFAULT_CODE_QUALITY_FILE: &str = "gl-code-quality-report.json";
+
+/// The format used to output analysis results.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum OutputFormat {
+    // Human-readable diagnostics written to stdout.
+    Text,
+    // A standalone HTML report written to the output file.
+    Html,
+    // GitHub Actions workflow commands written to stdout.
+    Github,
+    // Summary statistics written as JSON to the output file.
+    SummaryJson,
+    // One JSON object per report written to stdout.
+    JsonLines,
+    // Suspected under-constrained signals written as JSON to the output file.
+    Candidates,
+    // A Markdown report written to the output file.
+    Markdown,
+    // Checkstyle XML written to the output file.
+    Checkstyle,
+    // JUnit XML written to the output file.
+    JUnit,
+    // One CSV record per report written to the output file.
+    Csv,
+    // A GitLab Code Quality (Code Climate) report written to the output file.
+    Gitlab,
+    // A Reviewdog Diagnostic Format (rdjson) document written to stdout.
+    Rdjson,
+}
+
+impl FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(format: &str) -> Result<OutputFormat, Self::Err> {
+        match format.to_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "html" => Ok(OutputFormat::Html),
+            "github" => Ok(OutputFormat::Github),
+            "summary-json" => Ok(OutputFormat::SummaryJson),
+            "jsonl" => Ok(OutputFormat::JsonLines),
+            "candidates" => Ok(OutputFormat::Candidates),
+            "markdown" | "md" => Ok(OutputFormat::Markdown),
+            "checkstyle" => Ok(OutputFormat::Checkstyle),
+            "junit" => Ok(OutputFormat::JUnit),
+            "csv" => Ok(OutputFormat::Csv),
+            "gitlab" | "codeclimate" => Ok(OutputFormat::Gitlab),
+            "rdjson" => Ok(OutputFormat::Rdjson),
+            _ => Err(anyhow!("unknown output format '{format}'")),
+        }
+    }
+}
+
+/// A Circom compiler version of the form `major.minor.patch`.
+#[derive(Clone, Debug)]
+struct CompilerVersion(String);
+
+impl FromStr for CompilerVersion {
+    type Err = anyhow::Error;
+
+    fn from_str(version: &str) -> Result<CompilerVersion, Self::Err> {
+        let parts = version.split('.').collect::<Vec<_>>();
+        if parts.len() == 3 && parts.iter().all(|part| usize::from_str(part).is_ok()) {
+            Ok(CompilerVersion(version.to_string()))
+        } else {
+            Err(anyhow!("invalid compiler version '{version}'"))
+        }
+    }
+}
 
 #[derive(Parser, Debug)]
+#[clap(args_conflicts_with_subcommands = true)]
 /// A static analyzer and linter for Circom programs.
 struct Cli {
-    /// Initial input file(s)
+    #[clap(subcommand)]
+    command: Option<Command>,
+
+    /// Initial input file(s), directories, or glob patterns (e.g. 'circuits/**/*.circom')
     #[clap(name = "INPUT")]
     input_files: Vec<PathBuf>,
 
+    /// Library path(s) used to resolve include statements
+    #[clap(short = 'L', long = "library", name = "PATH")]
+    libraries: Vec<PathBuf>,
+
+    /// Remap include path prefixes (e.g. 'circomlib=node_modules/circomlib/circuits')
+    #[clap(long = "remap", name = "REMAPPING")]
+    remappings: Vec<Remapping>,
+
+    /// Skip input files and directories matching the given glob pattern
+    #[clap(long = "exclude", name = "EXCLUDE")]
+    excludes: Vec<String>,
+
+    /// Do not skip files ignored by `.gitignore` and `.circomspectignore` files
+    #[clap(long = "no-ignore")]
+    no_ignore: bool,
+
+    /// Only analyze files changed since the last commit (and files including them), and only
+    /// report findings on changed lines
+    #[clap(long = "changed-only")]
+    changed_only: bool,
+
+    /// Only analyze definitions reachable from the main component defined by the given file
+    #[clap(long = "main", name = "MAIN", conflicts_with = "TEMPLATE")]
+    main_file: Option<PathBuf>,
+
+    /// Only analyze definitions reachable from the given template
+    #[clap(long = "entry", name = "TEMPLATE")]
+    entry: Option<String>,
+
+    /// Only analyze templates and functions matching the given name (supports `*` and `?`)
+    #[clap(long = "template", name = "PATTERN")]
+    templates: Vec<String>,
+
+    /// Do not analyze templates and functions matching the given name (supports `*` and `?`)
+    #[clap(long = "skip-template", name = "SKIP_PATTERN")]
+    skipped_templates: Vec<String>,
+
+    /// Report `log` statements and TODO/FIXME comments in definitions reachable from the main
+    /// component
+    #[clap(long = "hygiene")]
+    hygiene: bool,
+
+    /// Configuration file (defaults to `circomspect.toml` in the current directory, if it exists)
+    #[clap(long = "config", name = "CONFIG")]
+    config_file: Option<PathBuf>,
+
+    /// Settings loaded from the configuration file
+    #[clap(skip)]
+    config: ConfigFile,
+
+    /// Cross-check reports about unconstrained signals against the given R1CS file (the
+    /// corresponding `.sym` file is expected in the same directory)
+    #[clap(long = "r1cs", name = "R1CS")]
+    r1cs_file: Option<PathBuf>,
+
+    /// The compiled circuit loaded from the R1CS and symbol files
+    #[clap(skip)]
+    compiled_circuit: Option<CompiledCircuit>,
+
+    /// Annotate reports with the compiled signal index and component path of affected signals,
+    /// using the given `.sym` file
+    #[clap(long = "sym", name = "SYM")]
+    sym_file: Option<PathBuf>,
+
+    /// The symbols loaded from the symbol file
+    #[clap(skip)]
+    symbols: Option<Vec<Symbol>>,
+
     /// Output level (INFO, WARNING, or ERROR)
     #[clap(short = 'l', long = "level", name = "LEVEL", default_value = DEFAULT_LEVEL)]
     output_level: MessageCategory,
@@ -35,151 +220,863 @@ struct Cli {
     #[clap(short, long, name = "OUTPUT")]
     sarif_file: Option<PathBuf>,
 
+    /// Include all report labels in the Sarif output (by default, only the
+    /// first label is included)
+    #[clap(long = "sarif-full-locations")]
+    sarif_full_locations: bool,
+
+    /// Emit Sarif file paths relative to the given directory
+    #[clap(long = "sarif-root", name = "ROOT")]
+    sarif_root: Option<PathBuf>,
+
+    /// URI base ID used for Sarif file paths relative to the root directory
+    #[clap(long = "sarif-uri-base-id", name = "BASE_ID", default_value = DEFAULT_URI_BASE_ID)]
+    sarif_uri_base_id: String,
+
+    /// Include allowed results in the Sarif output, marked as suppressed
+    #[clap(long = "show-suppressed")]
+    show_suppressed: bool,
+
+    /// Output format (TEXT, HTML, GITHUB, SUMMARY-JSON, JSONL, CANDIDATES, MARKDOWN, CHECKSTYLE,
+    /// JUNIT, CSV, GITLAB, or RDJSON)
+    #[clap(short = 'f', long = "output-format", name = "FORMAT", default_value = DEFAULT_OUTPUT_FORMAT)]
+    output_format: OutputFormat,
+
+    /// Maximum number of reports printed for each rule by the TEXT output format. Reports are
+    /// grouped by rule, and the number of omitted reports is printed after each group
+    #[clap(long = "max-reports-per-rule", name = "MAX", default_value = DEFAULT_MAX_REPORTS_PER_RULE)]
+    max_reports_per_rule: usize,
+
+    /// Print all reports in the order they are generated, without grouping them by rule
+    #[clap(long = "show-all")]
+    show_all: bool,
+
+    /// Use colors in terminal output (AUTO, ALWAYS, or NEVER)
+    #[clap(long = "color", name = "WHEN", default_value = DEFAULT_COLOR_MODE)]
+    color: ColorMode,
+
+    /// Output file used by the HTML, SUMMARY-JSON, CANDIDATES, MARKDOWN, CHECKSTYLE, JUNIT, CSV,
+    /// and GITLAB output formats (defaults to `circomspect.html`, `circomspect-summary.json`,
+    /// `circomspect-candidates.json`, `circomspect.md`, `circomspect-checkstyle.xml`,
+    /// `circomspect-junit.xml`, `circomspect.csv`, and `gl-code-quality-report.json`
+    /// respectively)
+    #[clap(short = 'o', long = "output-file", name = "FILE")]
+    output_file: Option<PathBuf>,
+
+    /// URL template used to link locations in the MARKDOWN output format, where `{path}` is
+    /// replaced by the file path and `{line}` by the line number (e.g.
+    /// `https://github.com/org/repo/blob/main/{path}#L{line}`)
+    #[clap(long = "link-template", name = "URL")]
+    link_template: Option<String>,
+
+    /// Print a summary of the results per rule, file, and template after the analysis
+    #[clap(long = "summary")]
+    summary: bool,
+
     /// Ignore results from given analysis passes
     #[clap(short = 'a', long = "allow", name = "ID")]
     allow_list: Vec<String>,
 
-    /// Enable verbose output
+    /// Promote results from given analysis passes (or all `warnings`) to errors
+    #[clap(short = 'd', long = "deny", name = "DENY_ID")]
+    deny_list: Vec<String>,
+
+    /// Only report results from rules in the given categories (SOUNDNESS, CORRECTNESS, or STYLE)
+    #[clap(long = "only", name = "CATEGORY", value_delimiter = ',')]
+    only_categories: Vec<RuleCategory>,
+
+    /// Ignore results from rules in the given categories
+    #[clap(long = "skip", name = "SKIP_CATEGORY", value_delimiter = ',')]
+    skipped_categories: Vec<RuleCategory>,
+
+    /// Do not run the analysis passes with the given names (e.g. `field-overflow`)
+    #[clap(long = "disable-pass", name = "PASS", value_delimiter = ',')]
+    disabled_passes: Vec<String>,
+
+    /// Report results from soundness rules at their original level (by default, soundness
+    /// warnings are reported as errors)
+    #[clap(long = "no-soundness-errors")]
+    no_soundness_errors: bool,
+
+    /// Only fail (exit with a nonzero exit code) on results from given analysis passes
+    #[clap(long = "error-on", name = "ERROR_ID")]
+    error_on: Vec<String>,
+
+    /// Only fail if the number of warnings exceeds the given maximum (errors fail unless
+    /// `--error-on` is used)
+    #[clap(long = "max-warnings", name = "N")]
+    max_warnings: Option<usize>,
+
+    /// Re-run the analysis whenever an analyzed file changes
+    #[clap(short = 'w', long = "watch")]
+    watch: bool,
+
+    /// Cache analysis results in the given directory between runs
+    #[clap(long = "cache-dir", name = "DIR")]
+    cache_dir: Option<PathBuf>,
+
+    /// Skip the remaining analysis passes for templates and functions taking longer than this
+    #[clap(long = "timeout-per-template", name = "SECONDS")]
+    timeout_per_template: Option<u64>,
+
+    /// Enable verbose output (including parse and analysis timings and peak memory usage)
     #[clap(short = 'v', long = "verbose")]
     verbose: bool,
 
-    /// Set curve (BN128, BLS12_381, or GOLDILOCKS)
+    /// Suppress all output except for the findings
+    #[clap(short = 'q', long = "quiet", conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Target Circom compiler version (used for files without a version pragma)
+    #[clap(long = "circom-version", name = "VERSION", default_value = COMPILER_VERSION)]
+    circom_version: CompilerVersion,
+
+    /// Set curve (BN128 (also BN254), BLS12_381, or GOLDILOCKS)
     #[clap(short = 'c', long = "curve", name = "NAME", default_value = DEFAULT_CURVE)]
     curve: Curve,
 }
 
-fn generate_cfg<Ast: IntoCfg>(
-    ast: Ast,
-    curve: &Curve,
-    reports: &mut ReportCollection,
-) -> Result<Cfg, Report> {
-    ast.into_cfg(curve, reports).map_err(Report::from)?.into_ssa().map_err(Report::from)
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// List all rules implemented by Circomspect
+    Rules,
+    /// Print a detailed description of the given rule
+    Explain {
+        /// Rule ID or name (e.g. CS0006 or unused-variable)
+        #[clap(name = "RULE")]
+        rule: String,
+    },
+    /// Write the control-flow graph of a template or function in DOT format
+    Cfg {
+        /// Name of the template or function
+        #[clap(short = 't', long = "template", name = "NAME")]
+        name: String,
+
+        /// Input file(s)
+        #[clap(name = "INPUT", required = true)]
+        input_files: Vec<PathBuf>,
+
+        /// Library path(s) used to resolve include statements
+        #[clap(short = 'L', long = "library", name = "PATH")]
+        libraries: Vec<PathBuf>,
+
+        /// Remap include path prefixes (e.g. 'circomlib=node_modules/circomlib/circuits')
+        #[clap(long = "remap", name = "REMAPPING")]
+        remappings: Vec<Remapping>,
+
+        /// Output file (defaults to stdout)
+        #[clap(short = 'o', long = "output-file", name = "FILE")]
+        output_file: Option<PathBuf>,
+
+        /// Set curve (BN128 (also BN254), BLS12_381, or GOLDILOCKS)
+        #[clap(short = 'c', long = "curve", name = "CURVE", default_value = DEFAULT_CURVE)]
+        curve: Curve,
+    },
+    /// Write the call graph or include graph of a project in DOT or JSON format
+    Graph {
+        /// Graph to export (CALLS or INCLUDES)
+        #[clap(short = 'k', long = "kind", name = "KIND", default_value = "CALLS")]
+        kind: GraphKind,
+
+        /// Output format (DOT or JSON)
+        #[clap(short = 'f', long = "format", name = "FORMAT", default_value = "DOT")]
+        format: GraphFormat,
+
+        /// Input file(s)
+        #[clap(name = "INPUT", required = true)]
+        input_files: Vec<PathBuf>,
+
+        /// Library path(s) used to resolve include statements
+        #[clap(short = 'L', long = "library", name = "PATH")]
+        libraries: Vec<PathBuf>,
+
+        /// Remap include path prefixes (e.g. 'circomlib=node_modules/circomlib/circuits')
+        #[clap(long = "remap", name = "REMAPPING")]
+        remappings: Vec<Remapping>,
+
+        /// Output file (defaults to stdout)
+        #[clap(short = 'o', long = "output-file", name = "FILE")]
+        output_file: Option<PathBuf>,
+    },
+    /// Estimate the number of instances, signals, and constraints of each template, and report
+    /// the size and complexity of each template
+    Metrics {
+        /// Input file(s) (one of which must define the main component)
+        #[clap(name = "INPUT", required = true)]
+        input_files: Vec<PathBuf>,
+
+        /// Library path(s) used to resolve include statements
+        #[clap(short = 'L', long = "library", name = "PATH")]
+        libraries: Vec<PathBuf>,
+
+        /// Remap include path prefixes (e.g. 'circomlib=node_modules/circomlib/circuits')
+        #[clap(long = "remap", name = "REMAPPING")]
+        remappings: Vec<Remapping>,
+
+        /// Only list the given number of templates with the most constraints
+        #[clap(long = "top", name = "N")]
+        top: Option<usize>,
+
+        /// Set curve (BN128 (also BN254), BLS12_381, or GOLDILOCKS)
+        #[clap(short = 'c', long = "curve", name = "CURVE", default_value = DEFAULT_CURVE)]
+        curve: Curve,
+    },
+    /// Write the constraints of a template instance in SMT-LIB format (using the theory of
+    /// finite fields)
+    Smt {
+        /// Name of the template
+        #[clap(short = 't', long = "template", name = "NAME")]
+        name: String,
+
+        /// Template arguments (e.g. '--args 8,2')
+        #[clap(long = "args", name = "ARGS", value_delimiter = ',')]
+        args: Vec<BigInt>,
+
+        /// Add a query which is unsatisfiable if the outputs are uniquely determined by the inputs
+        #[clap(long = "uniqueness")]
+        uniqueness: bool,
+
+        /// Input file(s)
+        #[clap(name = "INPUT", required = true)]
+        input_files: Vec<PathBuf>,
+
+        /// Library path(s) used to resolve include statements
+        #[clap(short = 'L', long = "library", name = "PATH")]
+        libraries: Vec<PathBuf>,
+
+        /// Remap include path prefixes (e.g. 'circomlib=node_modules/circomlib/circuits')
+        #[clap(long = "remap", name = "REMAPPING")]
+        remappings: Vec<Remapping>,
+
+        /// Output file (defaults to stdout)
+        #[clap(short = 'o', long = "output-file", name = "FILE")]
+        output_file: Option<PathBuf>,
+
+        /// Set curve (BN128 (also BN254), BLS12_381, or GOLDILOCKS)
+        #[clap(short = 'c', long = "curve", name = "CURVE", default_value = DEFAULT_CURVE)]
+        curve: Curve,
+    },
+    /// Summarize the inputs, outputs, constraints, and `<--` hints of each template
+    Describe {
+        /// Only summarize templates matching the given pattern (e.g. 'Num2Bits*')
+        #[clap(short = 't', long = "template", name = "PATTERN")]
+        patterns: Vec<String>,
+
+        /// Output format (MARKDOWN or JSON)
+        #[clap(short = 'f', long = "format", name = "FORMAT", default_value = "MARKDOWN")]
+        format: DescribeFormat,
+
+        /// Input file(s)
+        #[clap(name = "INPUT", required = true)]
+        input_files: Vec<PathBuf>,
+
+        /// Library path(s) used to resolve include statements
+        #[clap(short = 'L', long = "library", name = "PATH")]
+        libraries: Vec<PathBuf>,
+
+        /// Remap include path prefixes (e.g. 'circomlib=node_modules/circomlib/circuits')
+        #[clap(long = "remap", name = "REMAPPING")]
+        remappings: Vec<Remapping>,
+
+        /// Output file (defaults to stdout)
+        #[clap(short = 'o', long = "output-file", name = "FILE")]
+        output_file: Option<PathBuf>,
+    },
+    /// Compare the results of two runs and print new and fixed findings
+    Diff {
+        /// Sarif file containing the results of the old run
+        #[clap(name = "OLD")]
+        old_file: PathBuf,
+
+        /// Sarif file containing the results of the new run
+        #[clap(name = "NEW")]
+        new_file: PathBuf,
+
+        /// Also print findings that are unchanged between the two runs
+        #[clap(long = "show-unchanged")]
+        show_unchanged: bool,
+    },
+    /// Format the given files as canonical Circom (comments are preserved)
+    Format {
+        /// Input file(s), directories, or glob patterns
+        #[clap(name = "INPUT", required = true)]
+        input_files: Vec<PathBuf>,
+
+        /// Overwrite the input files with the formatted files
+        #[clap(short = 'w', long = "write", conflicts_with = "check")]
+        write: bool,
+
+        /// Only list the files that are not formatted (and fail if there are any)
+        #[clap(long = "check")]
+        check: bool,
+    },
 }
 
-fn analyze_cfg(cfg: &Cfg, reports: &mut ReportCollection) {
-    for analysis_pass in get_analysis_passes() {
-        reports.extend(analysis_pass(cfg));
+fn list_rules() {
+    let name_width = all_rules().iter().map(|rule| rule.name.len()).max().unwrap_or_default();
+    for rule in all_rules() {
+        println!(
+            "{:<6}  {:<name_width$}  {:<7}  {:<11}  {}",
+            rule.id(),
+            rule.name,
+            rule.category.to_string(),
+            rule.rule_category().to_string(),
+            rule.summary
+        );
     }
 }
 
-fn analyze_ast<Ast: IntoCfg>(ast: Ast, curve: &Curve, reports: &mut ReportCollection) {
-    match generate_cfg(ast, curve, reports) {
-        Ok(cfg) => {
-            analyze_cfg(&cfg, reports);
+fn explain_rule(rule: &Rule) {
+    println!("{} ({})", rule.id(), rule.name);
+    println!();
+    println!("Category: {}", rule.rule_category());
+    println!("Default severity: {}", rule.category);
+    println!();
+    println!("{}", rule.summary);
+    println!();
+    println!("{}", rule.description);
+    if let Some(example) = rule.example {
+        println!();
+        println!("Example of code flagged by this rule:");
+        println!();
+        println!("{example}");
+    }
+    if let Some(fix) = rule.fix {
+        println!();
+        println!("Fixed version:");
+        println!();
+        println!("{fix}");
+    }
+}
+
+/// Writes the CFG of the given template or function in DOT format to the
+/// output file (or stdout).
+fn dump_cfg(
+    name: &str,
+    input_files: &[PathBuf],
+    libraries: &[PathBuf],
+    remappings: &[Remapping],
+    output_file: &Option<PathBuf>,
+    curve: &Curve,
+) -> ExitCode {
+    let (file_library, functions, templates, warnings) =
+        match parser::parse_files(&input_files.to_vec(), libraries, remappings, COMPILER_VERSION) {
+            ParseResult::Program(program, warnings) => {
+                (program.file_library, program.functions, program.templates, warnings)
+            }
+            ParseResult::Library(library, warnings) => {
+                (library.file_library, library.functions, library.templates, warnings)
+            }
+        };
+    let runner = AnalysisRunner::new(curve, file_library, functions, templates);
+    let cfg = match runner.get_cfg(name) {
+        Some(Ok(cfg)) => cfg,
+        Some(Err(error)) => {
+            StdoutWriter::new(false).write(&vec![error], runner.file_library());
+            return ExitCode::FAILURE;
         }
-        Err(error) => {
-            reports.push(error);
+        None => {
+            // The definition may be missing because of a parse error.
+            StdoutWriter::new(false).write(&warnings, runner.file_library());
+            log_message(&format!("Unknown template or function `{name}`."));
+            return ExitCode::FAILURE;
         }
     };
+    match output_file {
+        Some(output_file) => {
+            if std::fs::write(output_file, cfg.to_dot()).is_err() {
+                log_message(&format!("Failed to write `{}`.", output_file.display()));
+                return ExitCode::FAILURE;
+            }
+            log_message(&format!("CFG written to `{}`.", output_file.display()));
+        }
+        None => print!("{}", cfg.to_dot()),
+    }
+    ExitCode::SUCCESS
 }
 
-fn analyze_definitions(
-    functions: &FunctionInfo,
-    templates: &TemplateInfo,
-    file_library: &FileLibrary,
-    curve: &Curve,
-    writer: &mut StdoutWriter,
-) -> ReportCollection {
-    let mut all_reports = ReportCollection::new();
+fn html_file(options: &Cli) -> PathBuf {
+    options.output_file.clone().unwrap_or_else(|| PathBuf::from(DEFAULT_HTML_FILE))
+}
 
-    // Analyze all functions.
-    for (name, function) in functions {
-        log_message(&format!("analyzing function '{name}'"));
-        let mut new_reports = ReportCollection::new();
-        analyze_ast(function, curve, &mut new_reports);
-        writer.write(&new_reports, file_library);
-        all_reports.extend(new_reports);
-    }
-    // Analyze all templates.
-    for (name, template) in templates {
-        log_message(&format!("analyzing template '{name}'"));
-        let mut new_reports = ReportCollection::new();
-        analyze_ast(template, curve, &mut new_reports);
-        writer.write(&new_reports, file_library);
-        all_reports.extend(new_reports);
-    }
-    all_reports
+fn summary_file(options: &Cli) -> PathBuf {
+    options.output_file.clone().unwrap_or_else(|| PathBuf::from(DEFAULT_SUMMARY_FILE))
 }
 
-/// Returns true if the report level is greater than or equal to the given
-/// level.
-fn filter_by_level(report: &Report, output_level: &MessageCategory) -> bool {
-    report.category() >= output_level
+fn candidates_file(options: &Cli) -> PathBuf {
+    options.output_file.clone().unwrap_or_else(|| PathBuf::from(DEFAULT_CANDIDATES_FILE))
 }
 
-/// Returns true if the report ID is not in the given list.
-fn filter_by_id(report: &Report, allow_list: &[String]) -> bool {
-    !allow_list.contains(&report.id())
+fn markdown_file(options: &Cli) -> PathBuf {
+    options.output_file.clone().unwrap_or_else(|| PathBuf::from(DEFAULT_MARKDOWN_FILE))
 }
 
-fn log_message(message: &str) {
-    let mut writer = if atty::is(atty::Stream::Stdout) {
-        StandardStream::stdout(ColorChoice::Always)
-    } else {
-        StandardStream::stdout(ColorChoice::Never)
+fn csv_file(options: &Cli) -> PathBuf {
+    options.output_file.clone().unwrap_or_else(|| PathBuf::from(DEFAULT_CSV_FILE))
+}
+
+fn code_quality_file(options: &Cli) -> PathBuf {
+    options.output_file.clone().unwrap_or_else(|| PathBuf::from(DEFAULT_CODE_QUALITY_FILE))
+}
+
+fn xml_file(options: &Cli) -> PathBuf {
+    let default_file = match options.output_format {
+        OutputFormat::JUnit => DEFAULT_JUNIT_FILE,
+        _ => DEFAULT_CHECKSTYLE_FILE,
+    };
+    options.output_file.clone().unwrap_or_else(|| PathBuf::from(default_file))
+}
+
+/// Loads the given R1CS file together with the corresponding `.sym` file.
+fn load_compiled_circuit(r1cs_file: &Path) -> anyhow::Result<CompiledCircuit> {
+    let bytes = std::fs::read(r1cs_file)
+        .map_err(|error| anyhow!("Failed to read `{}`: {error}", r1cs_file.display()))?;
+    let r1cs = R1CS::parse(&bytes)
+        .map_err(|error| anyhow!("Failed to parse `{}`: {error}", r1cs_file.display()))?;
+    let symbols = load_symbols(&r1cs_file.with_extension("sym"))?;
+    let name = r1cs_file.file_name().unwrap_or_default().to_string_lossy().to_string();
+    Ok(CompiledCircuit { name, r1cs, symbols })
+}
+
+/// Loads the given `.sym` file.
+fn load_symbols(sym_file: &Path) -> anyhow::Result<Vec<Symbol>> {
+    let contents = std::fs::read_to_string(sym_file)
+        .map_err(|error| anyhow!("Failed to read `{}`: {error}", sym_file.display()))?;
+    parse_symbols(&contents)
+        .map_err(|error| anyhow!("Failed to parse `{}`: {error}", sym_file.display()))
+}
+
+/// Returns a writer for the selected output format that filters reports based
+/// on the given allow list and output level.
+fn build_writer(options: &Cli) -> Box<dyn ReportWriter> {
+    let allow_list = options.allow_list.clone();
+    let output_level = options.output_level;
+    let filter_by_id = move |report: &Report| filter_by_id(report, &allow_list);
+    let filter_by_level = move |report: &Report| filter_by_level(report, &output_level);
+    match options.output_format {
+        OutputFormat::Text => {
+            let mut writer = StdoutWriter::new(options.verbose)
+                .add_filter(filter_by_id)
+                .add_filter(filter_by_level);
+            if !options.show_all {
+                writer = writer.with_max_reports_per_rule(options.max_reports_per_rule);
+            }
+            Box::new(writer)
+        }
+        OutputFormat::Github => {
+            Box::new(GithubWriter::new().add_filter(filter_by_id).add_filter(filter_by_level))
+        }
+        OutputFormat::Html => Box::new(
+            HtmlWriter::new(&html_file(options))
+                .add_filter(filter_by_id)
+                .add_filter(filter_by_level),
+        ),
+        OutputFormat::SummaryJson => {
+            Box::new(SummaryWriter::new().add_filter(filter_by_id).add_filter(filter_by_level))
+        }
+        OutputFormat::JsonLines => {
+            Box::new(JsonLinesWriter::new().add_filter(filter_by_id).add_filter(filter_by_level))
+        }
+        OutputFormat::Candidates => Box::new(
+            CandidatesWriter::new(&candidates_file(options))
+                .add_filter(filter_by_id)
+                .add_filter(filter_by_level),
+        ),
+        OutputFormat::Markdown => {
+            // File paths are given relative to the current directory.
+            let markdown_options = MarkdownOptions {
+                link_template: options.link_template.clone(),
+                root: std::env::current_dir().ok(),
+            };
+            Box::new(
+                MarkdownWriter::new(&markdown_file(options))
+                    .with_options(markdown_options)
+                    .add_filter(filter_by_id)
+                    .add_filter(filter_by_level),
+            )
+        }
+        OutputFormat::Checkstyle => Box::new(
+            CheckstyleWriter::new(&xml_file(options))
+                .add_filter(filter_by_id)
+                .add_filter(filter_by_level),
+        ),
+        OutputFormat::JUnit => Box::new(
+            JUnitWriter::new(&xml_file(options))
+                .add_filter(filter_by_id)
+                .add_filter(filter_by_level),
+        ),
+        OutputFormat::Csv => Box::new(
+            CsvWriter::new(&csv_file(options)).add_filter(filter_by_id).add_filter(filter_by_level),
+        ),
+        OutputFormat::Gitlab => {
+            // GitLab expects paths relative to the repository root, which is
+            // assumed to be the current directory.
+            let mut writer = CodeQualityWriter::new(&code_quality_file(options))
+                .add_filter(filter_by_id)
+                .add_filter(filter_by_level);
+            if let Ok(root) = std::env::current_dir() {
+                writer = writer.with_root(&root);
+            }
+            Box::new(writer)
+        }
+        OutputFormat::Rdjson => {
+            // File paths are given relative to the current directory.
+            let mut writer =
+                RdjsonWriter::new().add_filter(filter_by_id).add_filter(filter_by_level);
+            if let Ok(root) = std::env::current_dir() {
+                writer = writer.with_root(&root);
+            }
+            Box::new(writer)
+        }
+    }
+}
+
+/// Parses and analyzes the input files. Reports are written to the given
+/// writer as they are generated. If `quiet` is set, progress messages are not
+/// logged.
+pub(crate) fn run_analysis(
+    options: &Cli,
+    writer: &mut dyn ReportWriter,
+    quiet: bool,
+) -> (ReportCollection, FileLibrary, Summary) {
+    let mut config = Config {
+        libraries: options.libraries.clone(),
+        remappings: options.remappings.clone(),
+        curve: options.curve.clone(),
+        compiler_version: options.circom_version.0.clone(),
+        allow_list: options.allow_list.clone(),
+        deny_list: options.deny_list.clone(),
+        output_level: options.output_level,
+        soundness_errors: !options.no_soundness_errors,
+        // Categories given on the command line override the configuration file.
+        only_categories: if options.only_categories.is_empty() {
+            options.config.only_categories.clone()
+        } else {
+            options.only_categories.clone()
+        },
+        skipped_categories: if options.skipped_categories.is_empty() {
+            options.config.skipped_categories.clone()
+        } else {
+            options.skipped_categories.clone()
+        },
+        // Passes disabled on the command line are added to the configuration file.
+        disabled_passes: options
+            .config
+            .disabled_passes
+            .iter()
+            .chain(&options.disabled_passes)
+            .cloned()
+            .collect(),
+        cache_dir: options.cache_dir.clone(),
+        timeout_per_template: options.timeout_per_template.map(Duration::from_secs),
+        entry_point: match (&options.main_file, &options.entry) {
+            (Some(_), _) => Some(EntryPoint::MainComponent),
+            (None, Some(name)) => Some(EntryPoint::Template(name.clone())),
+            (None, None) => None,
+        },
+        selected_definitions: options.templates.clone(),
+        skipped_definitions: options.skipped_templates.clone(),
+        hygiene: options.hygiene,
+        naming_conventions: options.config.naming_conventions.clone(),
+        complexity_thresholds: options.config.complexity_thresholds.clone(),
+        compiled_circuit: options.compiled_circuit.clone(),
+        symbols: options.symbols.clone(),
+        ..Config::default()
+    };
+    // Inputs are expanded on each run, since files may be added in watch mode.
+    let mut input_files =
+        expand_inputs(&options.input_files, &options.excludes, !options.no_ignore);
+    if options.changed_only {
+        match get_changed_lines() {
+            Ok(changed_lines) => {
+                input_files = get_affected_files(
+                    &input_files,
+                    &config.libraries,
+                    &config.remappings,
+                    &changed_lines,
+                );
+                if !quiet {
+                    log_message(&format!("{} file(s) affected by changes.", input_files.len()));
+                }
+                config.changed_lines = Some(changed_lines);
+            }
+            Err(error) => log_message(&format!("{error:#}. All files will be analyzed.")),
+        }
+    }
+    let mut cache = circomspect::load_cache(&config);
+    let (parse_result, parse_timings) = match &mut cache {
+        Some(cache) => parser::parse_files_cached(
+            &input_files,
+            &config.libraries,
+            &config.remappings,
+            &config.compiler_version,
+            cache.parsed_files_mut(),
+        ),
+        None => parser::parse_files_timed(
+            &input_files,
+            &config.libraries,
+            &config.remappings,
+            &config.compiler_version,
+        ),
     };
-    // We ignore logging failures.
-    let _ = writer.set_color(ColorSpec::new().set_fg(Some(Color::Green)));
-    let _ = write!(&mut writer, "circomspect");
-    let _ = writer.reset();
-    let _ = writeln!(&mut writer, ": {message}");
+    if !quiet {
+        let parse_timings = parse_timings.iter().map(|(path, time)| (path.display(), *time));
+        progress::log_timings("Time per parsed file", parse_timings);
+    }
+    circomspect::run_analysis_with_cache(parse_result, cache, &config, writer, quiet)
 }
 
 fn main() -> ExitCode {
     pretty_env_logger::init();
-    let options = Cli::from_args();
+    let mut options = Cli::from_args();
+    // Progress messages would corrupt the JSON output on stdout.
+    let quiet = options.quiet
+        || matches!(options.output_format, OutputFormat::JsonLines | OutputFormat::Rdjson);
+    progress::set_verbosity(match (quiet, options.verbose) {
+        (true, _) => Verbosity::Quiet,
+        (false, true) => Verbosity::Verbose,
+        (false, false) => Verbosity::Normal,
+    });
+    report::set_color_mode(options.color);
+    match &options.command {
+        Some(Command::Rules) => {
+            list_rules();
+            return ExitCode::SUCCESS;
+        }
+        Some(Command::Explain { rule }) => match find_rule(rule) {
+            Some(rule) => {
+                explain_rule(rule);
+                return ExitCode::SUCCESS;
+            }
+            None => {
+                log_message(&format!(
+                    "Unknown rule `{rule}`. Use `circomspect rules` to list all rules."
+                ));
+                return ExitCode::FAILURE;
+            }
+        },
+        Some(Command::Cfg { name, input_files, libraries, remappings, output_file, curve }) => {
+            let input_files = expand_inputs(input_files, &[], true);
+            return dump_cfg(name, &input_files, libraries, remappings, output_file, curve);
+        }
+        Some(Command::Graph { kind, format, input_files, libraries, remappings, output_file }) => {
+            let input_files = expand_inputs(input_files, &[], true);
+            let include_paths = (libraries.as_slice(), remappings.as_slice());
+            return if export_graph(*kind, *format, &input_files, include_paths, output_file) {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            };
+        }
+        Some(Command::Metrics { input_files, libraries, remappings, top, curve }) => {
+            let input_files = expand_inputs(input_files, &[], true);
+            return if print_metrics(&input_files, libraries, remappings, curve, *top) {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            };
+        }
+        Some(Command::Smt {
+            name,
+            args,
+            uniqueness,
+            input_files,
+            libraries,
+            remappings,
+            output_file,
+            curve,
+        }) => {
+            let input_files = expand_inputs(input_files, &[], true);
+            let options = SmtOptions {
+                name,
+                args,
+                curve,
+                uniqueness: *uniqueness,
+                output_file: output_file.as_ref(),
+            };
+            return if export_smt(&input_files, libraries, remappings, &options) {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            };
+        }
+        Some(Command::Describe {
+            patterns,
+            format,
+            input_files,
+            libraries,
+            remappings,
+            output_file,
+        }) => {
+            let input_files = expand_inputs(input_files, &[], true);
+            let include_paths = (libraries.as_slice(), remappings.as_slice());
+            return if describe_templates(
+                &input_files,
+                include_paths,
+                patterns,
+                *format,
+                output_file,
+            ) {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            };
+        }
+        Some(Command::Diff { old_file, new_file, show_unchanged }) => {
+            // Use the exit code to indicate if any new findings were found.
+            return match diff_results(old_file, new_file, *show_unchanged) {
+                Some(false) => ExitCode::SUCCESS,
+                Some(true) | None => ExitCode::FAILURE,
+            };
+        }
+        Some(Command::Format { input_files, write, check }) => {
+            let input_files = expand_inputs(input_files, &[], true);
+            let mode = match (write, check) {
+                (true, _) => FormatMode::Write,
+                (_, true) => FormatMode::Check,
+                _ => FormatMode::Print,
+            };
+            return if format_files(&input_files, mode) {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            };
+        }
+        None => {}
+    }
+    options.error_on = to_rule_ids(&options.error_on);
+    if let Some(main_file) = &options.main_file {
+        options.input_files.push(main_file.clone());
+    }
     if options.input_files.is_empty() {
         match Cli::command().print_help() {
             Ok(()) => return ExitCode::SUCCESS,
             Err(_) => return ExitCode::FAILURE,
         }
     }
-    let mut reports = ReportCollection::new();
-    let allow_list = options.allow_list.clone();
-    let output_level = options.output_level;
-    let mut writer = StdoutWriter::new(options.verbose)
-        .add_filter(move |report: &Report| filter_by_id(report, &allow_list))
-        .add_filter(move |report: &Report| filter_by_level(report, &output_level));
-
-    let file_library = match parser::parse_files(&options.input_files, COMPILER_VERSION) {
-        // Analyze a complete Circom program.
-        ParseResult::Program(program, mut warnings) => {
-            writer.write(&warnings, &program.file_library);
-            reports.append(&mut warnings);
-            reports.append(&mut analyze_definitions(
-                &program.functions,
-                &program.templates,
-                &program.file_library,
-                &options.curve,
-                &mut writer,
-            ));
-            program.file_library
-        }
-        // Analyze a set of Circom template files.
-        ParseResult::Library(library, mut warnings) => {
-            writer.write(&warnings, &library.file_library);
-            reports.append(&mut warnings);
-            reports.append(&mut analyze_definitions(
-                &library.functions,
-                &library.templates,
-                &library.file_library,
-                &options.curve,
-                &mut writer,
-            ));
-            library.file_library
+    let config_file = options.config_file.clone().or_else(|| {
+        let config_file = PathBuf::from(DEFAULT_CONFIG_FILE);
+        config_file.exists().then_some(config_file)
+    });
+    if let Some(config_file) = config_file {
+        match load_config_file(&config_file) {
+            Ok(config) => options.config = config,
+            Err(error) => {
+                log_message(&format!("{error:#}."));
+                return ExitCode::FAILURE;
+            }
         }
-    };
+    }
+    // Rules allowed or denied on the command line are added to the rules
+    // configured in the configuration file.
+    options.allow_list =
+        to_rule_ids(&[options.config.allow_list.clone(), options.allow_list].concat());
+    options.deny_list =
+        to_rule_ids(&[options.config.deny_list.clone(), options.deny_list].concat());
+    if let Some(r1cs_file) = &options.r1cs_file {
+        match load_compiled_circuit(r1cs_file) {
+            Ok(circuit) => options.compiled_circuit = Some(circuit),
+            Err(error) => {
+                log_message(&format!("{error:#}."));
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    if let Some(sym_file) = &options.sym_file {
+        match load_symbols(sym_file) {
+            Ok(symbols) => options.symbols = Some(symbols),
+            Err(error) => {
+                log_message(&format!("{error:#}."));
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    let mut writer = build_writer(&options);
+    let (reports, file_library, summary) = run_analysis(&options, writer.as_mut(), false);
+    writer.finish(&file_library);
+    progress::log_memory_usage();
     // If a Sarif file is passed to the program we write the reports to it.
-    if let Some(sarif_file) = options.sarif_file {
+    if let Some(sarif_file) = &options.sarif_file {
         let allow_list = options.allow_list.clone();
         let output_level = options.output_level;
-        let mut writer = SarifWriter::new(&sarif_file)
-            .add_filter(move |report: &Report| filter_by_id(report, &allow_list))
+        let sarif_options = SarifOptions {
+            full_locations: options.sarif_full_locations,
+            root: options.sarif_root.clone(),
+            uri_base_id: options.sarif_uri_base_id.clone(),
+        };
+        let mut writer = SarifWriter::new(sarif_file)
+            .with_options(sarif_options)
             .add_filter(move |report: &Report| filter_by_level(report, &output_level));
+        // Allowed reports are either dropped or marked as suppressed.
+        let mut reports = reports.clone();
+        if options.show_suppressed {
+            suppress_reports(&mut reports, &allow_list);
+        } else {
+            writer = writer.add_filter(move |report: &Report| filter_by_id(report, &allow_list));
+        }
         if writer.write(&reports, &file_library) > 0 {
             log_message(&format!("Result written to `{}`.", sarif_file.display()));
         }
     }
+    if options.watch {
+        watch::watch_files(&options, &reports, &file_library);
+    }
+    if options.output_format == OutputFormat::Html && writer.written() > 0 {
+        log_message(&format!("Result written to `{}`.", html_file(&options).display()));
+    }
+    if options.output_format == OutputFormat::Markdown && writer.written() > 0 {
+        log_message(&format!("Result written to `{}`.", markdown_file(&options).display()));
+    }
+    if matches!(options.output_format, OutputFormat::Checkstyle | OutputFormat::JUnit)
+        && writer.written() > 0
+    {
+        log_message(&format!("Result written to `{}`.", xml_file(&options).display()));
+    }
+    if options.output_format == OutputFormat::Csv && writer.written() > 0 {
+        log_message(&format!("Result written to `{}`.", csv_file(&options).display()));
+    }
+    if options.output_format == OutputFormat::Gitlab && writer.written() > 0 {
+        log_message(&format!("Result written to `{}`.", code_quality_file(&options).display()));
+    }
+    if options.output_format == OutputFormat::Candidates && writer.written() > 0 {
+        log_message(&format!("Candidates written to `{}`.", candidates_file(&options).display()));
+    }
+    if options.output_format == OutputFormat::SummaryJson {
+        let summary_file = summary_file(&options);
+        if std::fs::write(&summary_file, summary.to_json()).is_ok() {
+            log_message(&format!("Summary written to `{}`.", summary_file.display()));
+        } else {
+            log_message(&format!("Failed to write `{}`.", summary_file.display()));
+        }
+    }
+    if options.summary {
+        println!("{}", summary.to_text());
+    }
+    // If an exit code policy is given, only the selected results affect the exit code.
+    if !options.error_on.is_empty() || options.max_warnings.is_some() {
+        let allow_list = options.allow_list.clone();
+        let output_level = options.output_level;
+        let reports = reports
+            .iter()
+            .filter(|report| filter_by_id(report, &allow_list))
+            .filter(|report| filter_by_level(report, &output_level))
+            .cloned()
+            .collect::<ReportCollection>();
+        let is_failure = is_failure(&reports, &options.error_on, options.max_warnings);
+        log_message(&format!("{} issues found.", writer.written()));
+        return if is_failure { ExitCode::FAILURE } else { ExitCode::SUCCESS };
+    }
+    // If any results are denied, only errors affect the exit code.
+    if !options.deny_list.is_empty() {
+        let allow_list = options.allow_list.clone();
+        let errors = reports
+            .iter()
+            .filter(|report| report.category() == &MessageCategory::Error)
+            .filter(|report| filter_by_id(report, &allow_list))
+            .count();
+        log_message(&format!("{} issues found, {errors} denied.", writer.written()));
+        return if errors > 0 { ExitCode::FAILURE } else { ExitCode::SUCCESS };
+    }
     // Use the exit code to indicate if any issues were found.
     match writer.written() {
         0 => {
@@ -196,3 +1093,15 @@ fn main() -> ExitCode {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::Cli;
+
+    #[test]
+    fn test_cli() {
+        Cli::command().debug_assert();
+    }
+}
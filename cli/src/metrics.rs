@@ -0,0 +1,157 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use parser::{ParseResult, Remapping};
+use program_analysis::analysis_runner::AnalysisRunner;
+use program_analysis::definition_complexity::{compute_complexity, ComplexityMetrics};
+use program_analysis::instantiation::{estimate_instantiation, InstantiationMetrics};
+use program_structure::constants::Curve;
+use program_structure::report_writer::{ReportWriter, StdoutWriter};
+
+use crate::{log_message, COMPILER_VERSION};
+
+/// Templates generating at least this share of all constraints are flagged.
+const HEAVY_TEMPLATE_SHARE: f64 = 0.1;
+
+/// Estimates the number of instances, signals, and constraints of each
+/// template instantiated by the main component of the given files, computes
+/// the complexity of each template, and prints the result to stdout. Returns
+/// false if the program has no main component.
+pub(crate) fn print_metrics(
+    input_files: &[PathBuf],
+    libraries: &[PathBuf],
+    remappings: &[Remapping],
+    curve: &Curve,
+    top: Option<usize>,
+) -> bool {
+    let program =
+        match parser::parse_files(&input_files.to_vec(), libraries, remappings, COMPILER_VERSION) {
+            ParseResult::Program(program, _) => program,
+            ParseResult::Library(library, warnings) => {
+                // The main component may be missing because of a parse error.
+                StdoutWriter::new(false).write(&warnings, &library.file_library);
+                log_message("No main component found.");
+                return false;
+            }
+        };
+    let Some(metrics) = estimate_instantiation(
+        &program.initial_template_call,
+        &program.templates,
+        &program.functions,
+        curve,
+    ) else {
+        log_message("The main component is not a template instantiation.");
+        return false;
+    };
+    let runner =
+        AnalysisRunner::new(curve, program.file_library, program.functions, program.templates);
+    let complexity = metrics
+        .templates
+        .keys()
+        .filter_map(|name| match runner.get_cfg(name)? {
+            Ok(cfg) => Some((name.clone(), compute_complexity(&cfg))),
+            Err(_) => None,
+        })
+        .collect::<BTreeMap<_, _>>();
+    print!("{}", to_table(&metrics, &complexity, top));
+    if metrics.approximate {
+        log_message("Some values could not be evaluated, so the metrics are approximate.");
+    }
+    true
+}
+
+/// Returns a table of the estimated cost and the complexity of each template,
+/// sorted by the number of constraints. Templates generating a large share of
+/// all constraints are marked with `*`. The complexity of templates missing
+/// from `complexity` is listed as `-`.
+fn to_table(
+    metrics: &InstantiationMetrics,
+    complexity: &BTreeMap<String, ComplexityMetrics>,
+    top: Option<usize>,
+) -> String {
+    let total = metrics.total();
+    let mut templates = metrics.templates.iter().collect::<Vec<_>>();
+    templates.sort_by(|(lhs_name, lhs), (rhs_name, rhs)| {
+        rhs.constraints.cmp(&lhs.constraints).then(lhs_name.cmp(rhs_name))
+    });
+    templates.truncate(top.unwrap_or(templates.len()));
+
+    let width = templates.iter().map(|(name, _)| name.len() + 2).max().unwrap_or_default().max(8);
+    let mut table = format!(
+        "{:<width$}  {:>9}  {:>9}  {:>11}  {:>6}  {:>10}  {:>10}  {:>5}\n",
+        "Template",
+        "Instances",
+        "Signals",
+        "Constraints",
+        "Share",
+        "Complexity",
+        "Statements",
+        "Depth"
+    );
+    for (name, cost) in templates {
+        let share = match total.constraints {
+            0 => 0.0,
+            n => cost.constraints as f64 / n as f64,
+        };
+        let (cyclomatic_complexity, statements, nesting_depth) = match complexity.get(name) {
+            Some(complexity) => (
+                complexity.cyclomatic_complexity.to_string(),
+                complexity.statements.to_string(),
+                complexity.nesting_depth.to_string(),
+            ),
+            None => ("-".to_string(), "-".to_string(), "-".to_string()),
+        };
+        let name = if share >= HEAVY_TEMPLATE_SHARE { format!("{name} *") } else { name.clone() };
+        table.push_str(&format!(
+            "{name:<width$}  {:>9}  {:>9}  {:>11}  {:>5.1}%  {cyclomatic_complexity:>10}  {statements:>10}  {nesting_depth:>5}\n",
+            cost.instances,
+            cost.signals,
+            cost.constraints,
+            100.0 * share
+        ));
+    }
+    table.push_str(&format!(
+        "{:<width$}  {:>9}  {:>9}  {:>11}\n",
+        "Total", total.instances, total.signals, total.constraints
+    ));
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use program_analysis::instantiation::TemplateCost;
+
+    use super::*;
+
+    #[test]
+    fn test_metrics_table() {
+        let metrics = InstantiationMetrics {
+            templates: BTreeMap::from([
+                ("Main".to_string(), TemplateCost { instances: 1, signals: 4, constraints: 1 }),
+                (
+                    "Square".to_string(),
+                    TemplateCost { instances: 19, signals: 38, constraints: 19 },
+                ),
+            ]),
+            ..InstantiationMetrics::default()
+        };
+        let complexity = BTreeMap::from([(
+            "Square".to_string(),
+            ComplexityMetrics { cyclomatic_complexity: 3, statements: 12, nesting_depth: 2 },
+        )]);
+        let table = to_table(&metrics, &complexity, None);
+        let lines = table.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1].starts_with("Square *"));
+        assert!(lines[1].contains("95.0%"));
+        assert_eq!(lines[1].split_whitespace().rev().take(3).collect::<Vec<_>>(), ["2", "12", "3"]);
+        assert!(lines[2].starts_with("Main "));
+        assert!(lines[2].ends_with("-"));
+        assert!(lines[3].starts_with("Total"));
+
+        let table = to_table(&metrics, &complexity, Some(1));
+        assert_eq!(table.lines().count(), 3);
+    }
+}
@@ -0,0 +1,122 @@
+//! Progress messages logged during the analysis. The amount of information
+//! logged is controlled by a global verbosity level, which is set once on
+//! startup based on the `--quiet` and `--verbose` flags.
+use std::fmt::Display;
+use std::fs;
+use std::io::Write;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Duration;
+use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
+
+use program_structure::report::{self, ColorMode};
+
+/// The amount of progress information logged.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Nothing but the findings is output.
+    Quiet,
+    /// Progress messages are logged.
+    #[default]
+    Normal,
+    /// Progress messages are logged together with the time spent parsing each
+    /// file and running each analysis pass, and the peak memory usage.
+    Verbose,
+}
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+
+/// Sets the global verbosity level.
+pub fn set_verbosity(verbosity: Verbosity) {
+    VERBOSITY.store(verbosity as u8, Ordering::Relaxed);
+}
+
+/// Returns the global verbosity level.
+#[must_use]
+pub fn verbosity() -> Verbosity {
+    match VERBOSITY.load(Ordering::Relaxed) {
+        0 => Verbosity::Quiet,
+        1 => Verbosity::Normal,
+        _ => Verbosity::Verbose,
+    }
+}
+
+/// Logs the given message to stdout, unless the verbosity is `Quiet`.
+pub fn log_message(message: &str) {
+    if verbosity() == Verbosity::Quiet {
+        return;
+    }
+    let mut writer = StandardStream::stdout(match report::color_mode() {
+        ColorMode::Always => ColorChoice::Always,
+        ColorMode::Never => ColorChoice::Never,
+        ColorMode::Auto if atty::is(atty::Stream::Stdout) => ColorChoice::Always,
+        ColorMode::Auto => ColorChoice::Never,
+    });
+    // We ignore logging failures.
+    let _ = writer.set_color(ColorSpec::new().set_fg(Some(Color::Green)));
+    let _ = write!(&mut writer, "circomspect");
+    let _ = writer.reset();
+    let _ = writeln!(&mut writer, ": {message}");
+}
+
+/// Logs the given message if the verbosity is `Verbose`.
+pub fn log_verbose(message: &str) {
+    if verbosity() == Verbosity::Verbose {
+        log_message(message);
+    }
+}
+
+/// Logs the given timings (slowest first) under the given title if the
+/// verbosity is `Verbose`.
+pub fn log_timings<K: Display>(title: &str, timings: impl IntoIterator<Item = (K, Duration)>) {
+    if verbosity() != Verbosity::Verbose {
+        return;
+    }
+    let mut timings = timings.into_iter().collect::<Vec<_>>();
+    if timings.is_empty() {
+        return;
+    }
+    timings.sort_by(|(_, lhs), (_, rhs)| rhs.cmp(lhs));
+    let total = timings.iter().map(|(_, time)| *time).sum::<Duration>();
+    log_message(&format!("{title} ({} total):", format_duration(total)));
+    for (key, time) in timings {
+        log_message(&format!("  {:>10}  {key}", format_duration(time)));
+    }
+}
+
+/// Logs the peak memory usage of the process if the verbosity is `Verbose`
+/// and the memory usage is available.
+pub fn log_memory_usage() {
+    if let Some(bytes) = peak_memory_usage() {
+        log_verbose(&format!("Peak memory usage: {:.1} MiB.", bytes as f64 / (1 << 20) as f64));
+    }
+}
+
+/// Returns the peak resident set size of the process in bytes. This is only
+/// available on Linux, where it is read from `/proc/self/status`.
+#[must_use]
+pub fn peak_memory_usage() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    parse_peak_memory_usage(&status)
+}
+
+fn parse_peak_memory_usage(status: &str) -> Option<u64> {
+    let line = status.lines().find_map(|line| line.strip_prefix("VmHWM:"))?;
+    let kilobytes = line.trim().strip_suffix("kB")?.trim().parse::<u64>().ok()?;
+    Some(kilobytes * 1024)
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.2} ms", duration.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peak_memory_usage() {
+        let status = "Name:\tcircomspect\nVmPeak:\t  20000 kB\nVmHWM:\t    1234 kB\n";
+        assert_eq!(parse_peak_memory_usage(status), Some(1234 * 1024));
+        assert_eq!(parse_peak_memory_usage("Name:\tcircomspect\n"), None);
+    }
+}
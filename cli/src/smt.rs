@@ -0,0 +1,120 @@
+use std::path::PathBuf;
+
+use num_bigint_dig::BigInt;
+use parser::{ParseResult, Remapping};
+use program_analysis::constraint_system::lower_template;
+use program_structure::constants::Curve;
+use program_structure::report_writer::{ReportWriter, StdoutWriter};
+
+use crate::{log_message, COMPILER_VERSION};
+
+/// Options for exporting the constraint system of a template instance.
+pub(crate) struct SmtOptions<'a> {
+    pub name: &'a str,
+    pub args: &'a [BigInt],
+    pub curve: &'a Curve,
+    pub uniqueness: bool,
+    pub output_file: Option<&'a PathBuf>,
+}
+
+/// Lowers the constraints of the given template instance to SMT-LIB, and
+/// writes the result to the output file (or stdout). Returns false if the
+/// template could not be lowered.
+pub(crate) fn export_smt(
+    input_files: &[PathBuf],
+    libraries: &[PathBuf],
+    remappings: &[Remapping],
+    options: &SmtOptions,
+) -> bool {
+    let (file_library, functions, templates, warnings) =
+        match parser::parse_files(&input_files.to_vec(), libraries, remappings, COMPILER_VERSION) {
+            ParseResult::Program(program, warnings) => {
+                (program.file_library, program.functions, program.templates, warnings)
+            }
+            ParseResult::Library(library, warnings) => {
+                (library.file_library, library.functions, library.templates, warnings)
+            }
+        };
+    if !templates.contains_key(options.name) {
+        // The template may be missing because of a parse error.
+        StdoutWriter::new(false).write(&warnings, &file_library);
+    }
+    let system =
+        match lower_template(options.name, options.args, &templates, &functions, options.curve) {
+            Ok(system) => system,
+            Err(error) => {
+                log_message(&format!("Failed to lower `{}`: {error}.", options.name));
+                return false;
+            }
+        };
+    let smt = system.to_smt_lib(options.uniqueness);
+    match options.output_file {
+        Some(output_file) => {
+            if std::fs::write(output_file, smt).is_err() {
+                log_message(&format!("Failed to write `{}`.", output_file.display()));
+                return false;
+            }
+            log_message(&format!("SMT-LIB written to `{}`.", output_file.display()));
+        }
+        None => print!("{smt}"),
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    const SRC: &str = r#"
+        pragma circom 2.0.0;
+
+        template Multiplier() {
+            signal input a;
+            signal input b;
+            signal output c;
+            c <== a * b;
+        }
+    "#;
+
+    #[test]
+    fn test_export_smt() {
+        let temp_dir = std::env::temp_dir().join(format!("circomspect-smt-{}", std::process::id()));
+        fs::create_dir_all(&temp_dir).unwrap();
+        let input_file = temp_dir.join("multiplier.circom");
+        let output_file = temp_dir.join("multiplier.smt2");
+        fs::write(&input_file, SRC).unwrap();
+
+        let export = |name: &str, curve: &Curve| {
+            let options = SmtOptions {
+                name,
+                args: &[],
+                curve,
+                uniqueness: false,
+                output_file: Some(&output_file),
+            };
+            export_smt(std::slice::from_ref(&input_file), &[], &[], &options)
+        };
+        assert!(export("Multiplier", &Curve::Bn128));
+        let expected = concat!(
+            "; The constraint system of `Multiplier()`.\n",
+            "(set-logic QF_FF)\n",
+            "(define-sort F () (_ FiniteField ",
+            "21888242871839275222246405745257275088548364400416034343698204186575808495617))\n",
+            "(declare-const |a| F) ; input\n",
+            "(declare-const |b| F) ; input\n",
+            "(declare-const |c| F) ; output\n",
+            "(assert (= |c| (ff.mul |a| |b|)))\n",
+        );
+        assert_eq!(fs::read_to_string(&output_file).unwrap(), expected);
+
+        // The field modulus depends on the curve.
+        assert!(export("Multiplier", &Curve::Goldilocks));
+        let smt = fs::read_to_string(&output_file).unwrap();
+        assert!(smt.contains("(define-sort F () (_ FiniteField 18446744069414584321))\n"));
+
+        assert!(!export("Adder", &Curve::Bn128));
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+}
@@ -0,0 +1,174 @@
+//! Summary statistics for an analysis run. The summary can be printed after
+//! the analysis, or written as JSON using the `summary-json` output format, to
+//! track the number of findings over time.
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use program_structure::file_definition::FileLibrary;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::report_writer::{ReportFilter, ReportWriter};
+use program_structure::rules::find_rule;
+
+/// A breakdown of the findings of an analysis run per rule, file, and
+/// definition, together with the number of definitions analyzed and the time
+/// spent running each analysis pass.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Summary {
+    /// The number of functions analyzed.
+    pub functions: usize,
+    /// The number of templates analyzed.
+    pub templates: usize,
+    /// The total number of findings.
+    pub findings: usize,
+    /// The number of findings per rule ID.
+    pub by_rule: BTreeMap<String, usize>,
+    /// The number of findings per file.
+    pub by_file: BTreeMap<String, usize>,
+    /// The number of findings per function or template.
+    pub by_definition: BTreeMap<String, usize>,
+    /// The total time spent running each analysis pass.
+    pub pass_timings: BTreeMap<String, Duration>,
+}
+
+impl Summary {
+    /// Adds the given reports to the summary. Here, `definition` is the name of
+    /// the function or template that generated the reports, if any.
+    pub fn add_reports(
+        &mut self,
+        definition: Option<&str>,
+        reports: &[Report],
+        file_library: &FileLibrary,
+    ) {
+        for report in reports {
+            self.findings += 1;
+            *self.by_rule.entry(report.id()).or_default() += 1;
+            if let Some(file_name) = file_name(report, file_library) {
+                *self.by_file.entry(file_name).or_default() += 1;
+            }
+            if let Some(definition) = definition {
+                *self.by_definition.entry(definition.to_string()).or_default() += 1;
+            }
+        }
+    }
+
+    /// Returns the summary as a pretty-printed JSON object.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let rules = self
+            .by_rule
+            .iter()
+            .map(|(id, count)| {
+                let name = find_rule(id).map(|rule| rule.name);
+                json!({ "id": id, "name": name, "count": count })
+            })
+            .collect::<Vec<_>>();
+        let passes = self
+            .pass_timings
+            .iter()
+            .map(|(name, time)| json!({ "name": name, "time_ms": time.as_secs_f64() * 1000.0 }))
+            .collect::<Vec<_>>();
+        let mut json = serde_json::to_string_pretty(&json!({
+            "functions": self.functions,
+            "templates": self.templates,
+            "findings": self.findings,
+            "rules": rules,
+            "files": self.by_file,
+            "definitions": self.by_definition,
+            "passes": passes,
+        }))
+        .expect("failed to serialize summary");
+        json.push('\n');
+        json
+    }
+
+    /// Returns a human-readable version of the summary.
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        let mut lines = vec![format!(
+            "Analyzed {} function(s) and {} template(s), {} finding(s).",
+            self.functions, self.templates, self.findings
+        )];
+        let mut section = |title: &str, counts: Vec<(String, String)>| {
+            if counts.is_empty() {
+                return;
+            }
+            let width = counts.iter().map(|(key, _)| key.len()).max().unwrap_or_default();
+            lines.push(String::new());
+            lines.push(format!("{title}:"));
+            lines
+                .extend(counts.into_iter().map(|(key, value)| format!("  {key:<width$}  {value}")));
+        };
+        section(
+            "Findings per rule",
+            self.by_rule
+                .iter()
+                .map(|(id, count)| match find_rule(id) {
+                    Some(rule) => (format!("{id} ({})", rule.name), count.to_string()),
+                    None => (id.clone(), count.to_string()),
+                })
+                .collect(),
+        );
+        section("Findings per file", to_strings(&self.by_file));
+        section("Findings per template or function", to_strings(&self.by_definition));
+        section(
+            "Time per analysis pass",
+            self.pass_timings
+                .iter()
+                .map(|(name, time)| {
+                    (name.clone(), format!("{:.2} ms", time.as_secs_f64() * 1000.0))
+                })
+                .collect(),
+        );
+        lines.join("\n")
+    }
+}
+
+fn to_strings(counts: &BTreeMap<String, usize>) -> Vec<(String, String)> {
+    counts.iter().map(|(key, count)| (key.clone(), count.to_string())).collect()
+}
+
+/// Returns the name of the file containing the primary location of the
+/// report.
+fn file_name(report: &Report, file_library: &FileLibrary) -> Option<String> {
+    let file_id = report.primary().first()?.file_id;
+    let file = file_library.to_storage().get(file_id)?;
+    Some(file.name().to_string())
+}
+
+/// A writer which counts the (filtered) reports without writing them. This
+/// is used by the summary output format, where the summary is written once
+/// the analysis is complete.
+#[derive(Default)]
+pub struct SummaryWriter {
+    written: usize,
+    filters: Vec<Box<dyn ReportFilter>>,
+}
+
+impl SummaryWriter {
+    #[must_use]
+    pub fn new() -> SummaryWriter {
+        SummaryWriter::default()
+    }
+
+    #[must_use]
+    pub fn add_filter(mut self, filter: impl ReportFilter + 'static) -> SummaryWriter {
+        self.filters.push(Box::new(filter));
+        self
+    }
+}
+
+impl ReportWriter for SummaryWriter {
+    fn write(&mut self, reports: &ReportCollection, _: &FileLibrary) -> usize {
+        let written = reports
+            .iter()
+            .filter(|report| self.filters.iter().all(|filter| filter.filter(report)))
+            .count();
+        self.written += written;
+        written
+    }
+
+    fn written(&self) -> usize {
+        self.written
+    }
+}
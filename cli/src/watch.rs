@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use program_structure::file_definition::FileLibrary;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::report_writer::ReportWriter;
+
+use crate::{filter_by_id, filter_by_level, log_message, run_analysis, Cli};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The modification time of each watched file.
+type ModificationTimes = HashMap<PathBuf, Option<SystemTime>>;
+
+/// Collects filtered reports without writing them. This is used to compute
+/// the delta between two runs.
+struct CollectingWriter<'a> {
+    options: &'a Cli,
+    reports: ReportCollection,
+}
+
+impl ReportWriter for CollectingWriter<'_> {
+    fn write(&mut self, reports: &ReportCollection, _: &FileLibrary) -> usize {
+        let reports = reports
+            .iter()
+            .filter(|report| filter_by_id(report, &self.options.allow_list))
+            .filter(|report| filter_by_level(report, &self.options.output_level))
+            .cloned()
+            .collect::<Vec<_>>();
+        let written = reports.len();
+        self.reports.extend(reports);
+        written
+    }
+
+    fn written(&self) -> usize {
+        self.reports.len()
+    }
+}
+
+/// Watches all analyzed files (including included files) and re-runs the
+/// analysis whenever a file is modified. After each run, findings that were
+/// added or resolved since the previous run are printed. This function does
+/// not return.
+pub(crate) fn watch_files(options: &Cli, reports: &ReportCollection, file_library: &FileLibrary) {
+    let reports = reports
+        .iter()
+        .filter(|report| filter_by_id(report, &options.allow_list))
+        .filter(|report| filter_by_level(report, &options.output_level));
+    let mut findings = count_findings(reports, file_library);
+    let mut file_library = file_library.clone();
+    let mut detector = ChangeDetector::new(get_modification_times(options, &file_library));
+    log_message(&format!("Watching {} file(s) for changes.", detector.watched.len()));
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        if !detector.poll(get_modification_times(options, &file_library)) {
+            continue;
+        }
+        let mut writer = CollectingWriter { options, reports: ReportCollection::new() };
+        // Included files may have been added or removed by the edit, so we
+        // also update the set of watched files.
+        (_, file_library, _) = run_analysis(options, &mut writer, true);
+        detector = ChangeDetector::new(get_modification_times(options, &file_library));
+        let new_findings = count_findings(writer.reports.iter(), &file_library);
+        print_delta(&findings, &new_findings);
+        findings = new_findings;
+    }
+}
+
+/// Detects changes to the watched files. Changes are debounced, which means
+/// that a change is only reported once the modification times have been
+/// stable for a full poll interval. This ensures that saving several files at
+/// once (or writing a single file in several steps) only triggers one run.
+struct ChangeDetector {
+    watched: ModificationTimes,
+    pending: Option<ModificationTimes>,
+}
+
+impl ChangeDetector {
+    fn new(watched: ModificationTimes) -> ChangeDetector {
+        ChangeDetector { watched, pending: None }
+    }
+
+    /// Updates the detector with the current modification times. Returns true
+    /// if the files have changed and the analysis should be re-run.
+    fn poll(&mut self, current: ModificationTimes) -> bool {
+        if current == self.watched {
+            // The files were changed back before the change was reported.
+            self.pending = None;
+            return false;
+        }
+        if self.pending.as_ref() == Some(&current) {
+            self.watched = current;
+            self.pending = None;
+            return true;
+        }
+        self.pending = Some(current);
+        false
+    }
+}
+
+/// Returns the modification time of each input file and each file in the file
+/// library.
+fn get_modification_times(options: &Cli, file_library: &FileLibrary) -> ModificationTimes {
+    let file_paths = file_library.file_names().into_iter().map(PathBuf::from);
+    file_paths
+        .chain(options.input_files.iter().filter(|path| path.is_file()).cloned())
+        .map(|path| {
+            let modified = fs::metadata(&path).and_then(|metadata| metadata.modified()).ok();
+            (path, modified)
+        })
+        .collect()
+}
+
+/// Returns a short summary of each finding together with the number of times
+/// it occurs.
+fn count_findings<'a>(
+    reports: impl Iterator<Item = &'a Report>,
+    file_library: &FileLibrary,
+) -> HashMap<String, usize> {
+    let mut findings = HashMap::new();
+    for report in reports {
+        *findings.entry(summarize(report, file_library)).or_default() += 1;
+    }
+    findings
+}
+
+fn summarize(report: &Report, file_library: &FileLibrary) -> String {
+    let storage = file_library.to_storage();
+    let location = report.primary().first().and_then(|label| {
+        let file = storage.get(label.file_id)?;
+        let line = file_library.get_line(label.range.start, label.file_id)?;
+        Some(format!("{}:{line}", file.name()))
+    });
+    match location {
+        Some(location) => {
+            format!("{}[{}] {location}: {}", report.category(), report.id(), report.message())
+        }
+        None => format!("{}[{}] {}", report.category(), report.id(), report.message()),
+    }
+}
+
+fn print_delta(old_findings: &HashMap<String, usize>, new_findings: &HashMap<String, usize>) {
+    let (added, resolved) = compute_delta(old_findings, new_findings);
+    for finding in &added {
+        println!("+ {finding}");
+    }
+    for finding in &resolved {
+        println!("- {finding}");
+    }
+    log_message(&format!(
+        "{} new, {} resolved, {} total issue(s).",
+        added.len(),
+        resolved.len(),
+        new_findings.values().sum::<usize>()
+    ));
+}
+
+/// Returns the (sorted) findings that were added and resolved. Findings that
+/// occur several times are included once for each added or resolved instance.
+fn compute_delta<'a>(
+    old_findings: &'a HashMap<String, usize>,
+    new_findings: &'a HashMap<String, usize>,
+) -> (Vec<&'a String>, Vec<&'a String>) {
+    let (mut added, mut resolved) = (Vec::new(), Vec::new());
+    for (finding, count) in new_findings {
+        let old_count = old_findings.get(finding).copied().unwrap_or_default();
+        added.extend((old_count..*count).map(|_| finding));
+    }
+    for (finding, count) in old_findings {
+        let new_count = new_findings.get(finding).copied().unwrap_or_default();
+        resolved.extend((new_count..*count).map(|_| finding));
+    }
+    added.sort();
+    resolved.sort();
+    (added, resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modification_times(times: &[(&str, u64)]) -> ModificationTimes {
+        times
+            .iter()
+            .map(|(path, secs)| {
+                (PathBuf::from(path), Some(SystemTime::UNIX_EPOCH + Duration::from_secs(*secs)))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_change_detector() {
+        let mut detector = ChangeDetector::new(modification_times(&[("a.circom", 1)]));
+        assert!(!detector.poll(modification_times(&[("a.circom", 1)])));
+
+        // Changes are only reported once the files are unchanged for one poll.
+        assert!(!detector.poll(modification_times(&[("a.circom", 2)])));
+        assert!(!detector.poll(modification_times(&[("a.circom", 3)])));
+        assert!(detector.poll(modification_times(&[("a.circom", 3)])));
+        assert!(!detector.poll(modification_times(&[("a.circom", 3)])));
+
+        // Changes that are reverted before they are reported are ignored.
+        assert!(!detector.poll(modification_times(&[("a.circom", 4)])));
+        assert!(!detector.poll(modification_times(&[("a.circom", 3)])));
+        assert!(!detector.poll(modification_times(&[("a.circom", 3)])));
+
+        // Added and removed files are changes.
+        let times = modification_times(&[("a.circom", 3), ("b.circom", 1)]);
+        assert!(!detector.poll(times.clone()));
+        assert!(detector.poll(times));
+        let mut times = modification_times(&[("a.circom", 3)]);
+        times.insert(PathBuf::from("b.circom"), None);
+        assert!(!detector.poll(times.clone()));
+        assert!(detector.poll(times));
+    }
+
+    #[test]
+    fn test_compute_delta() {
+        let findings = |findings: &[(&str, usize)]| {
+            findings
+                .iter()
+                .map(|(finding, count)| (finding.to_string(), *count))
+                .collect::<HashMap<_, _>>()
+        };
+        let old_findings = findings(&[("a", 1), ("b", 2), ("c", 1)]);
+        let new_findings = findings(&[("a", 1), ("b", 1), ("d", 2)]);
+        let (added, resolved) = compute_delta(&old_findings, &new_findings);
+        assert_eq!(added, ["d", "d"]);
+        assert_eq!(resolved, ["b", "c"]);
+    }
+}
@@ -0,0 +1,215 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// Creates a fresh directory containing the given files, and returns the path
+/// to the directory.
+fn create_project(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let project_dir =
+        std::env::temp_dir().join(format!("circomspect-cli-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&project_dir);
+    fs::create_dir_all(&project_dir).unwrap();
+    for (file_name, contents) in files {
+        fs::write(project_dir.join(file_name), contents).unwrap();
+    }
+    project_dir
+}
+
+/// Runs Circomspect with the given arguments in the given directory.
+fn run_circomspect(project_dir: &PathBuf, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_circomspect"))
+        .args(args)
+        .current_dir(project_dir)
+        .output()
+        .unwrap()
+}
+
+fn output_text(output: &Output) -> String {
+    format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    )
+}
+
+#[test]
+fn test_default_compiler_version() {
+    let src = r#"
+        pragma circom 2.1.0;
+
+        template T() {
+            signal input {binary} in;
+            signal output out;
+            in * (in - 1) === 0;
+            out <== in;
+        }
+
+        component main {public [in]} = T();
+    "#;
+    let project_dir = create_project("version", &[("main.circom", src)]);
+    let output = run_circomspect(&project_dir, &["main.circom"]);
+    let text = output_text(&output);
+    assert!(output.status.success(), "{text}");
+    assert!(!text.contains("not supported"), "{text}");
+    assert!(text.contains("No issues found."), "{text}");
+    let _ = fs::remove_dir_all(&project_dir);
+}
+
+/// A template generating four reports for each of several rules.
+const REPEATED_SRC: &str = r#"
+    pragma circom 2.0.0;
+
+    template T() {
+        signal input in;
+        signal output a;
+        signal output b;
+        signal output c;
+        signal output d;
+        a <-- in;
+        b <-- in;
+        c <-- in;
+        d <-- in;
+    }
+
+    component main = T();
+"#;
+
+#[test]
+fn test_max_reports_per_rule() {
+    let project_dir = create_project("grouping", &[("main.circom", REPEATED_SRC)]);
+    let unconstrained_output = "is assigned using `<--`, but never occurs in a constraint.";
+    let unused_variable = "is assigned a value, but this value is never read.";
+
+    // Only the given number of reports is printed for each rule, followed by
+    // the number of omitted reports.
+    let output = run_circomspect(&project_dir, &["--max-reports-per-rule", "1", "main.circom"]);
+    let text = output_text(&output);
+    assert_eq!(text.matches(unconstrained_output).count(), 1, "{text}");
+    assert!(text.contains("… and 3 more CS0019 (unconstrained-output) reports."), "{text}");
+    assert!(text.contains("… and 3 more CS0006 (unused-variable) reports."), "{text}");
+
+    let output = run_circomspect(&project_dir, &["--max-reports-per-rule", "3", "main.circom"]);
+    let text = output_text(&output);
+    assert_eq!(text.matches(unconstrained_output).count(), 3, "{text}");
+    assert!(text.contains("… and 1 more CS0019 (unconstrained-output) reports."), "{text}");
+
+    // Reports are grouped by rule, with errors printed before warnings.
+    let output = run_circomspect(&project_dir, &["--max-reports-per-rule", "4", "main.circom"]);
+    let text = output_text(&output);
+    assert!(!text.contains("more CS"), "{text}");
+    let last_error = text.rfind(unconstrained_output).unwrap();
+    let first_warning = text.find(unused_variable).unwrap();
+    assert!(last_error < first_warning, "{text}");
+    assert_eq!(text.matches(unused_variable).count(), 4, "{text}");
+
+    // Passing `--show-all` disables grouping and truncation.
+    let output = run_circomspect(
+        &project_dir,
+        &["--max-reports-per-rule", "1", "--show-all", "main.circom"],
+    );
+    let text = output_text(&output);
+    assert!(!text.contains("more CS"), "{text}");
+    assert_eq!(text.matches(unconstrained_output).count(), 4, "{text}");
+    let _ = fs::remove_dir_all(&project_dir);
+}
+
+#[test]
+fn test_color_mode() {
+    let project_dir = create_project("color", &[("main.circom", REPEATED_SRC)]);
+    let has_ansi_codes = |color: &str| {
+        let output = run_circomspect(&project_dir, &["--color", color, "main.circom"]);
+        let text = output_text(&output);
+        assert!(text.contains("is never read"), "{text}");
+        text.contains("\x1b[")
+    };
+    assert!(has_ansi_codes("always"));
+    assert!(!has_ansi_codes("never"));
+    // The output of the test is not a terminal, so colors are disabled.
+    assert!(!has_ansi_codes("auto"));
+    let _ = fs::remove_dir_all(&project_dir);
+}
+
+#[test]
+fn test_config_file_categories() {
+    let unconstrained_output = "is assigned using `<--`, but never occurs in a constraint.";
+    let unused_variable = "is assigned a value, but this value is never read.";
+
+    // Only results from soundness rules are reported.
+    let config = "[categories]\nonly = \"soundness\"\n";
+    let files = [("main.circom", REPEATED_SRC), ("circomspect.toml", config)];
+    let project_dir = create_project("categories-only", &files);
+    let output = run_circomspect(&project_dir, &["--show-all", "main.circom"]);
+    let text = output_text(&output);
+    assert!(text.contains(unconstrained_output), "{text}");
+    assert!(!text.contains(unused_variable), "{text}");
+    assert!(!text.contains("warning:"), "{text}");
+    let _ = fs::remove_dir_all(&project_dir);
+
+    // Results from soundness rules are ignored.
+    let config = "[categories]\nskip = \"soundness\"\n";
+    let files = [("main.circom", REPEATED_SRC), ("circomspect.toml", config)];
+    let project_dir = create_project("categories-skip", &files);
+    let output = run_circomspect(&project_dir, &["--show-all", "main.circom"]);
+    let text = output_text(&output);
+    assert!(!text.contains(unconstrained_output), "{text}");
+    assert!(text.contains(unused_variable), "{text}");
+    let _ = fs::remove_dir_all(&project_dir);
+}
+
+/// A template generating a single style warning.
+const UNUSED_VARIABLE_SRC: &str = r#"
+    pragma circom 2.0.0;
+
+    template T() {
+        signal input in;
+        signal output out;
+        var x = 1;
+        out <== in;
+    }
+
+    component main = T();
+"#;
+
+#[test]
+fn test_config_file_rules() {
+    let unused_variable = "The variable `x` is assigned a value, but this value is never read.";
+
+    // Without configuration, the warning causes the analysis to fail.
+    let project_dir = create_project("rules-default", &[("main.circom", UNUSED_VARIABLE_SRC)]);
+    let output = run_circomspect(&project_dir, &["main.circom"]);
+    let text = output_text(&output);
+    assert!(text.contains(&format!("warning: {unused_variable}")), "{text}");
+    assert_eq!(output.status.code(), Some(1), "{text}");
+    let _ = fs::remove_dir_all(&project_dir);
+
+    // Denied rules are promoted to errors.
+    let config = "[rules]\ndeny = \"unused-variable\"\n";
+    let files = [("main.circom", UNUSED_VARIABLE_SRC), ("circomspect.toml", config)];
+    let project_dir = create_project("rules-deny", &files);
+    let output = run_circomspect(&project_dir, &["main.circom"]);
+    let text = output_text(&output);
+    assert!(text.contains(&format!("error: {unused_variable}")), "{text}");
+    assert_eq!(output.status.code(), Some(1), "{text}");
+    let _ = fs::remove_dir_all(&project_dir);
+
+    // If rules are denied, warnings from other rules do not cause a failure.
+    let config = "[rules]\ndeny = \"CS0005\"\n";
+    let files = [("main.circom", UNUSED_VARIABLE_SRC), ("circomspect.toml", config)];
+    let project_dir = create_project("rules-deny-other", &files);
+    let output = run_circomspect(&project_dir, &["main.circom"]);
+    let text = output_text(&output);
+    assert!(text.contains(&format!("warning: {unused_variable}")), "{text}");
+    assert_eq!(output.status.code(), Some(0), "{text}");
+    let _ = fs::remove_dir_all(&project_dir);
+
+    // Allowed rules are not reported.
+    let config = "[rules]\nallow = \"CS0006\"\n";
+    let files = [("main.circom", UNUSED_VARIABLE_SRC), ("circomspect.toml", config)];
+    let project_dir = create_project("rules-allow", &files);
+    let output = run_circomspect(&project_dir, &["main.circom"]);
+    let text = output_text(&output);
+    assert!(!text.contains(unused_variable), "{text}");
+    assert!(text.contains("No issues found."), "{text}");
+    assert_eq!(output.status.code(), Some(0), "{text}");
+    let _ = fs::remove_dir_all(&project_dir);
+}
@@ -0,0 +1,617 @@
+use std::collections::{HashMap, HashSet};
+
+use program_structure::ast::*;
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::report::ReportCollection;
+use program_structure::suggestions::closest_name;
+
+use super::errors::DefinitionError;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DefinitionKind {
+    Function,
+    Template,
+}
+
+/// The information about a function or template definition needed to check
+/// how it is used.
+#[derive(Clone)]
+struct DefinitionInfo {
+    kind: DefinitionKind,
+    arity: usize,
+    file_id: FileID,
+    file_location: FileLocation,
+    // The input and output signals declared by a template.
+    signals: HashSet<String>,
+}
+
+type DefinitionRegistry = HashMap<String, DefinitionInfo>;
+
+/// Checks that every call in the given definitions is consistent with the
+/// definition of the called function or template. That is, templates must be
+/// instantiated as components, functions must be called in expressions, and
+/// the number of arguments must match the number of parameters. Accessed
+/// component signals must also be declared by the instantiated template.
+pub(crate) fn check_definitions(
+    definitions: &HashMap<FileID, Vec<Definition>>,
+) -> ReportCollection {
+    let registry = build_registry(definitions);
+    let mut reports = ReportCollection::new();
+    for (file_id, definitions) in definitions {
+        for definition in definitions {
+            let body = match definition {
+                Definition::Template { body, .. } | Definition::Function { body, .. } => body,
+            };
+            let mut checker = DefinitionChecker::new(*file_id, &registry, body);
+            checker.visit_statement(body);
+            reports.extend(checker.reports);
+        }
+    }
+    reports
+}
+
+/// Removes all but the first definition with each name, and reports each
+/// removed definition. Files are ordered by file ID (that is, in the order in
+/// which they were parsed), which ensures that downstream analyses always use
+/// the same definition.
+pub(crate) fn remove_duplicate_definitions(
+    definitions: &mut HashMap<FileID, Vec<Definition>>,
+) -> ReportCollection {
+    let mut file_ids = definitions.keys().copied().collect::<Vec<_>>();
+    file_ids.sort_unstable();
+    let mut first_definitions = HashMap::<String, (FileID, FileLocation)>::new();
+    let mut reports = ReportCollection::new();
+    for file_id in file_ids {
+        let Some(file_definitions) = definitions.get_mut(&file_id) else {
+            continue;
+        };
+        file_definitions.retain(|definition| {
+            let (name, file_location) = definition_header(definition);
+            if let Some((definition_file_id, definition_location)) = first_definitions.get(name) {
+                reports.push(
+                    DefinitionError::DuplicateDefinition {
+                        name: name.clone(),
+                        file_id,
+                        file_location,
+                        definition_file_id: *definition_file_id,
+                        definition_location: definition_location.clone(),
+                    }
+                    .into_report(),
+                );
+                false
+            } else {
+                first_definitions.insert(name.clone(), (file_id, file_location));
+                true
+            }
+        });
+    }
+    reports
+}
+
+/// Returns the name of the definition, together with the location of the
+/// definition header (e.g. `template T(n)`). The location of the parameter
+/// list excludes the closing parenthesis.
+fn definition_header(definition: &Definition) -> (&String, FileLocation) {
+    match definition {
+        Definition::Template { meta, name, arg_location, .. }
+        | Definition::Function { meta, name, arg_location, .. } => {
+            (name, meta.start..arg_location.end + 1)
+        }
+    }
+}
+
+/// Checks that the main component instantiates a template with the correct
+/// number of arguments.
+pub(crate) fn check_main_call(
+    file_id: FileID,
+    call: &Expression,
+    definitions: &HashMap<FileID, Vec<Definition>>,
+) -> ReportCollection {
+    let registry = build_registry(definitions);
+    let mut checker = DefinitionChecker {
+        file_id,
+        registry: &registry,
+        components: HashSet::new(),
+        instantiations: HashMap::new(),
+        unknown_signals: HashSet::new(),
+        reports: ReportCollection::new(),
+    };
+    checker.visit_instantiation(call);
+    checker.reports
+}
+
+fn build_registry(definitions: &HashMap<FileID, Vec<Definition>>) -> DefinitionRegistry {
+    let mut registry = DefinitionRegistry::new();
+    for (file_id, definition) in definitions
+        .iter()
+        .flat_map(|(file_id, definitions)| definitions.iter().map(move |d| (file_id, d)))
+    {
+        let (kind, name, args, arg_location, body) = match definition {
+            Definition::Template { name, args, arg_location, body, .. } => {
+                (DefinitionKind::Template, name, args, arg_location, body)
+            }
+            Definition::Function { name, args, arg_location, body, .. } => {
+                (DefinitionKind::Function, name, args, arg_location, body)
+            }
+        };
+        registry.entry(name.clone()).or_insert_with(|| {
+            let mut signals = HashSet::new();
+            if kind == DefinitionKind::Template {
+                collect_signals(body, &mut signals);
+            }
+            DefinitionInfo {
+                kind,
+                arity: args.len(),
+                file_id: *file_id,
+                file_location: arg_location.clone(),
+                signals,
+            }
+        });
+    }
+    registry
+}
+
+struct DefinitionChecker<'a> {
+    file_id: FileID,
+    registry: &'a DefinitionRegistry,
+    components: HashSet<String>,
+    // The templates instantiated by each component.
+    instantiations: HashMap<String, HashSet<String>>,
+    // Component signals which have already been reported as unknown.
+    unknown_signals: HashSet<(String, String)>,
+    reports: ReportCollection,
+}
+
+impl<'a> DefinitionChecker<'a> {
+    fn new(file_id: FileID, registry: &'a DefinitionRegistry, body: &Statement) -> Self {
+        let mut components = HashSet::new();
+        collect_components(body, &mut components);
+        let mut instantiations = HashMap::new();
+        collect_instantiations(body, &components, &mut instantiations);
+        DefinitionChecker {
+            file_id,
+            registry,
+            components,
+            instantiations,
+            unknown_signals: HashSet::new(),
+            reports: ReportCollection::new(),
+        }
+    }
+
+    fn visit_statement(&mut self, stmt: &Statement) {
+        use Statement::*;
+        match stmt {
+            IfThenElse { cond, if_case, else_case, .. } => {
+                self.visit_expression(cond);
+                self.visit_statement(if_case);
+                if let Some(else_case) = else_case {
+                    self.visit_statement(else_case);
+                }
+            }
+            While { cond, stmt, .. } => {
+                self.visit_expression(cond);
+                self.visit_statement(stmt);
+            }
+            Return { value, .. } => self.visit_expression(value),
+            InitializationBlock { initializations: stmts, .. } | Block { stmts, .. } => {
+                for stmt in stmts {
+                    self.visit_statement(stmt);
+                }
+            }
+            Declaration { dimensions, .. } => {
+                for size in dimensions {
+                    self.visit_expression(size);
+                }
+            }
+            Substitution { meta, var, access, op, rhe } => {
+                self.visit_component_access(meta, var, access);
+                self.visit_access(access);
+                if matches!(op, AssignOp::AssignVar) && self.components.contains(var) {
+                    self.visit_instantiation(rhe);
+                } else {
+                    self.visit_expression(rhe);
+                }
+            }
+            MultiSubstitution { lhe, rhe, .. } | ConstraintEquality { lhe, rhe, .. } => {
+                self.visit_expression(lhe);
+                self.visit_expression(rhe);
+            }
+            LogCall { args, .. } => {
+                for arg in args {
+                    if let LogArgument::LogExp(arg) = arg {
+                        self.visit_expression(arg);
+                    }
+                }
+            }
+            Assert { arg, .. } => self.visit_expression(arg),
+        }
+    }
+
+    /// Visits the right-hand side of a component assignment.
+    fn visit_instantiation(&mut self, expr: &Expression) {
+        use Expression::*;
+        match expr {
+            ParallelOp { rhe, .. } => self.visit_instantiation(rhe),
+            Call { meta, id, args } => self.visit_call(meta, id, args, DefinitionKind::Template),
+            _ => self.visit_expression(expr),
+        }
+    }
+
+    fn visit_expression(&mut self, expr: &Expression) {
+        use Expression::*;
+        match expr {
+            InfixOp { lhe, rhe, .. } => {
+                self.visit_expression(lhe);
+                self.visit_expression(rhe);
+            }
+            PrefixOp { rhe, .. } | ParallelOp { rhe, .. } => self.visit_expression(rhe),
+            InlineSwitchOp { cond, if_true, if_false, .. } => {
+                self.visit_expression(cond);
+                self.visit_expression(if_true);
+                self.visit_expression(if_false);
+            }
+            Variable { meta, name, access } => {
+                self.visit_component_access(meta, name, access);
+                self.visit_access(access);
+            }
+            Number(..) => {}
+            Call { meta, id, args } => self.visit_call(meta, id, args, DefinitionKind::Function),
+            AnonymousComponent { params, signals, .. } => {
+                self.visit_expressions(params);
+                self.visit_expressions(signals);
+            }
+            ArrayInLine { values, .. } | Tuple { values, .. } => self.visit_expressions(values),
+        }
+    }
+
+    /// Visits a call in a position where a definition of the given kind is
+    /// expected, and checks the call against the definition of the callee.
+    fn visit_call(&mut self, meta: &Meta, name: &str, args: &[Expression], kind: DefinitionKind) {
+        if let Some(definition) = self.registry.get(name) {
+            let name = name.to_string();
+            let file_id = self.file_id;
+            let file_location = meta.file_location();
+            let definition_file_id = definition.file_id;
+            let definition_location = definition.file_location.clone();
+            let (expected, found) = (definition.arity, args.len());
+            let error = match (definition.kind, kind) {
+                (DefinitionKind::Template, DefinitionKind::Function) => {
+                    Some(DefinitionError::TemplateCalledAsFunction {
+                        name,
+                        file_id,
+                        file_location,
+                        definition_file_id,
+                        definition_location,
+                    })
+                }
+                (DefinitionKind::Function, DefinitionKind::Template) => {
+                    Some(DefinitionError::FunctionInstantiatedAsComponent {
+                        name,
+                        file_id,
+                        file_location,
+                        definition_file_id,
+                        definition_location,
+                    })
+                }
+                _ if expected == found => None,
+                (DefinitionKind::Function, _) => {
+                    Some(DefinitionError::FunctionWrongNumberOfArguments {
+                        name,
+                        expected,
+                        found,
+                        file_id,
+                        file_location,
+                        definition_file_id,
+                        definition_location,
+                    })
+                }
+                (DefinitionKind::Template, _) => {
+                    Some(DefinitionError::TemplateWrongNumberOfArguments {
+                        name,
+                        expected,
+                        found,
+                        file_id,
+                        file_location,
+                        definition_file_id,
+                        definition_location,
+                    })
+                }
+            };
+            if let Some(error) = error {
+                self.reports.push(error.into_report());
+            }
+        }
+        self.visit_expressions(args);
+    }
+
+    /// Checks that a signal accessed on a component is declared by the
+    /// template instantiated by the component. Components instantiating more
+    /// than one template (or an unknown template) are ignored.
+    fn visit_component_access(&mut self, meta: &Meta, name: &str, access: &[Access]) {
+        let Some(signal_name) = access.iter().find_map(|access| match access {
+            Access::ComponentAccess(signal_name) => Some(signal_name),
+            Access::ArrayAccess(_) => None,
+        }) else {
+            return;
+        };
+        let Some(template_names) = self.instantiations.get(name) else {
+            return;
+        };
+        let [template_name] = &template_names.iter().collect::<Vec<_>>()[..] else {
+            return;
+        };
+        let Some(definition) = self.registry.get(*template_name) else {
+            return;
+        };
+        if definition.kind != DefinitionKind::Template
+            || definition.signals.contains(signal_name)
+            || !self.unknown_signals.insert((name.to_string(), signal_name.clone()))
+        {
+            return;
+        }
+        let suggestion = closest_name(signal_name, definition.signals.iter().map(String::as_str))
+            .map(ToString::to_string);
+        self.reports.push(
+            DefinitionError::UnknownComponentSignal {
+                name: signal_name.clone(),
+                component_name: name.to_string(),
+                template_name: template_name.to_string(),
+                suggestion,
+                file_id: self.file_id,
+                file_location: meta.file_location(),
+                definition_file_id: definition.file_id,
+                definition_location: definition.file_location.clone(),
+            }
+            .into_report(),
+        );
+    }
+
+    fn visit_expressions(&mut self, exprs: &[Expression]) {
+        for expr in exprs {
+            self.visit_expression(expr);
+        }
+    }
+
+    fn visit_access(&mut self, access: &[Access]) {
+        for access in access {
+            if let Access::ArrayAccess(index) = access {
+                self.visit_expression(index);
+            }
+        }
+    }
+}
+
+/// Collects the names of all components declared in the given statement.
+fn collect_components(stmt: &Statement, components: &mut HashSet<String>) {
+    use Statement::*;
+    match stmt {
+        IfThenElse { if_case, else_case, .. } => {
+            collect_components(if_case, components);
+            if let Some(else_case) = else_case {
+                collect_components(else_case, components);
+            }
+        }
+        While { stmt, .. } => collect_components(stmt, components),
+        InitializationBlock { initializations: stmts, .. } | Block { stmts, .. } => {
+            for stmt in stmts {
+                collect_components(stmt, components);
+            }
+        }
+        Declaration { xtype: VariableType::Component, name, .. } => {
+            components.insert(name.clone());
+        }
+        _ => {}
+    }
+}
+
+/// Collects the templates instantiated by each of the given components.
+fn collect_instantiations(
+    stmt: &Statement,
+    components: &HashSet<String>,
+    instantiations: &mut HashMap<String, HashSet<String>>,
+) {
+    use Statement::*;
+    match stmt {
+        IfThenElse { if_case, else_case, .. } => {
+            collect_instantiations(if_case, components, instantiations);
+            if let Some(else_case) = else_case {
+                collect_instantiations(else_case, components, instantiations);
+            }
+        }
+        While { stmt, .. } => collect_instantiations(stmt, components, instantiations),
+        InitializationBlock { initializations: stmts, .. } | Block { stmts, .. } => {
+            for stmt in stmts {
+                collect_instantiations(stmt, components, instantiations);
+            }
+        }
+        Substitution { var, op: AssignOp::AssignVar, rhe, .. } if components.contains(var) => {
+            let rhe = match rhe {
+                Expression::ParallelOp { rhe, .. } => rhe,
+                rhe => rhe,
+            };
+            if let Expression::Call { id, .. } = rhe {
+                instantiations.entry(var.clone()).or_default().insert(id.clone());
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Collects the names of all input and output signals declared in the given
+/// statement.
+fn collect_signals(stmt: &Statement, signals: &mut HashSet<String>) {
+    use Statement::*;
+    match stmt {
+        IfThenElse { if_case, else_case, .. } => {
+            collect_signals(if_case, signals);
+            if let Some(else_case) = else_case {
+                collect_signals(else_case, signals);
+            }
+        }
+        While { stmt, .. } => collect_signals(stmt, signals),
+        InitializationBlock { initializations: stmts, .. } | Block { stmts, .. } => {
+            for stmt in stmts {
+                collect_signals(stmt, signals);
+            }
+        }
+        Declaration {
+            xtype: VariableType::Signal(SignalType::Input | SignalType::Output, _, _),
+            name,
+            ..
+        } => {
+            signals.insert(name.clone());
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use program_structure::report_code::ReportCode;
+
+    use crate::parser_logic::parse_file;
+
+    use super::*;
+
+    #[test]
+    fn test_definition_misuse() {
+        let src = r#"
+            function f(n) {
+                return n + 1;
+            }
+            template T(n) {
+                signal input in;
+                signal output out;
+                out <== in * f(n);
+            }
+            template U() {
+                signal input in;
+                signal output out;
+                component c[2];
+                c[0] = T(1);
+                c[1] = parallel T(f(2));
+                c[0].in <== in;
+                c[1].in <== c[0].out;
+                out <== c[1].out;
+            }
+        "#;
+        validate_reports(src, 0, ReportCode::DefinitionMisuse);
+
+        let src = r#"
+            function f(n) {
+                return n + 1;
+            }
+            template T(n) {
+                signal input in;
+                signal output out;
+                var x = T(n);
+                component c = f(n);
+                out <== in * x;
+            }
+        "#;
+        validate_reports(src, 2, ReportCode::DefinitionMisuse);
+    }
+
+    #[test]
+    fn test_wrong_number_of_arguments() {
+        let src = r#"
+            function f(x, y) {
+                return x + y;
+            }
+            function g(x) {
+                return f(x) + f(x, x, 1);
+            }
+        "#;
+        validate_reports(src, 2, ReportCode::FunctionWrongNumberOfArguments);
+
+        let src = r#"
+            template T(n) {
+                signal input in;
+                signal output out;
+                out <== n * in;
+            }
+            template U() {
+                signal input in;
+                signal output out;
+                component c = T();
+                c.in <== in;
+                out <== c.out;
+            }
+        "#;
+        validate_reports(src, 1, ReportCode::TemplateWrongNumberOfArguments);
+    }
+
+    #[test]
+    fn test_duplicate_definitions() {
+        let first = r#"
+            template T() {}
+            function f() {
+                return 1;
+            }
+        "#;
+        let second = r#"
+            function T() {
+                return 2;
+            }
+            template U() {}
+            template U(n) {}
+        "#;
+        let (first, _) = parse_file(first, 0).ok().unwrap();
+        let (second, _) = parse_file(second, 1).ok().unwrap();
+        let mut definitions = HashMap::from([(1, second.definitions), (0, first.definitions)]);
+        let reports = remove_duplicate_definitions(&mut definitions);
+        assert_eq!(reports.len(), 2);
+        for report in reports.iter() {
+            assert_eq!(report.id(), ReportCode::SameSymbolDeclaredTwice.id());
+            assert_eq!(report.secondary().len(), 1);
+        }
+        // The first definition in file ID order is retained.
+        assert_eq!(definitions[&0].len(), 2);
+        assert!(matches!(
+            &definitions[&1][..],
+            [Definition::Template { name, args, .. }] if name == "U" && args.is_empty()
+        ));
+    }
+
+    #[test]
+    fn test_unknown_component_signal() {
+        let src = r#"
+            template A() {
+                signal input in;
+                signal output out;
+                out <== in;
+            }
+            template T() {
+                signal input in;
+                signal output out;
+                component a[2];
+                for (var i = 0; i < 2; i++) {
+                    a[i] = A();
+                }
+                a[0].in <== in;
+                a[1].ni <== a[0].out;
+                out <== a[1].otu + a[1].otu;
+            }
+        "#;
+        validate_reports(src, 2, ReportCode::UnknownComponentSignal);
+        let (program, _) = parse_file(src, 0).ok().unwrap();
+        let definitions = HashMap::from([(0, program.definitions)]);
+        let notes = check_definitions(&definitions)
+            .iter()
+            .flat_map(|report| report.notes().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(notes, ["Did you mean `in`?", "Did you mean `out`?"]);
+    }
+
+    fn validate_reports(src: &str, expected_len: usize, expected_code: ReportCode) {
+        let (program, errors) = parse_file(src, 0).ok().unwrap();
+        assert!(errors.is_empty());
+        let definitions = HashMap::from([(0, program.definitions)]);
+        let reports = check_definitions(&definitions);
+        assert_eq!(reports.len(), expected_len);
+        for report in reports.iter() {
+            assert_eq!(report.id(), expected_code.id());
+            assert_eq!(report.secondary().len(), 1);
+        }
+    }
+}
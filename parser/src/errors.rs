@@ -2,6 +2,9 @@ use program_structure::abstract_syntax_tree::ast::Version;
 use program_structure::report_code::ReportCode;
 use program_structure::report::Report;
 use program_structure::file_definition::{FileID, FileLocation};
+use std::path::PathBuf;
+
+use super::feature_versions::Feature;
 
 pub struct UnclosedCommentError {
     pub location: FileLocation,
@@ -20,12 +23,26 @@ pub struct ParsingError {
     pub location: FileLocation,
     pub file_id: FileID,
     pub msg: String,
+    /// The location of a delimiter that is likely unbalanced.
+    pub delimiter: Option<FileLocation>,
+    /// A note explaining a likely cause of the error.
+    pub note: Option<String>,
 }
 
 impl ParsingError {
     pub fn produce_report(error: Self) -> Report {
         let mut report = Report::error(error.msg, ReportCode::ParseFail);
         report.add_primary(error.location, error.file_id, "Invalid syntax".to_string());
+        if let Some(delimiter) = error.delimiter {
+            report.add_secondary(
+                delimiter,
+                error.file_id,
+                Some("This delimiter may be unbalanced.".to_string()),
+            );
+        }
+        if let Some(note) = error.note {
+            report.add_note(note);
+        }
         report
     }
 }
@@ -43,6 +60,8 @@ pub struct IncludeError {
     pub path: String,
     pub file_id: Option<FileID>,
     pub file_location: FileLocation,
+    pub search_paths: Vec<PathBuf>,
+    pub remapped_paths: Vec<PathBuf>,
 }
 impl IncludeError {
     pub fn into_report(self) -> Report {
@@ -51,6 +70,22 @@ impl IncludeError {
         if let Some(file_id) = self.file_id {
             report.add_primary(self.file_location, file_id, "File included here.".to_string());
         }
+        let to_list = |paths: &[PathBuf]| {
+            paths.iter().map(|path| format!("`{}`", path.display())).collect::<Vec<_>>().join(", ")
+        };
+        if !self.remapped_paths.is_empty() {
+            report.add_note(format!(
+                "The include path was remapped to {}.",
+                to_list(&self.remapped_paths)
+            ));
+        }
+        report.add_note(format!(
+            "The include path was resolved against {}.",
+            to_list(&self.search_paths)
+        ));
+        report.add_note(
+            "Use `-L` or `--library` to add directories to the include search path, or `--remap` to remap include paths.".to_string(),
+        );
         report
     }
 }
@@ -99,6 +134,578 @@ impl NoCompilerVersionWarning {
     }
 }
 
+/// A cycle in the include graph. The file at index `i` includes the next file
+/// in the cycle using the include statement at index `i`.
+pub struct CyclicIncludeWarning {
+    pub file_paths: Vec<String>,
+    pub includes: Vec<(Option<FileID>, FileLocation)>,
+}
+
+impl CyclicIncludeWarning {
+    pub fn into_report(self) -> Report {
+        let first_path = self.file_paths.first().cloned().unwrap_or_default();
+        let mut report = Report::warning(
+            format!("The file `{first_path}` is included by a file that it includes."),
+            ReportCode::CyclicInclude,
+        );
+        let last = self.includes.len().saturating_sub(1);
+        for (index, (file_id, file_location)) in self.includes.into_iter().enumerate() {
+            let Some(file_id) = file_id else {
+                continue;
+            };
+            if index == last {
+                report.add_primary(
+                    file_location,
+                    file_id,
+                    format!("This include of `{first_path}` closes the cycle."),
+                );
+            } else {
+                report.add_secondary(file_location, file_id, None);
+            }
+        }
+        let cycle = self
+            .file_paths
+            .iter()
+            .chain(std::iter::once(&first_path))
+            .map(|path| format!("`{path}`"))
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        report.add_note(format!("The include cycle is {cycle}."));
+        report.add_note(
+            "Included files are only parsed once, but cyclic includes make the structure of the project hard to follow. Consider moving shared definitions into a separate file.".to_string(),
+        );
+        report
+    }
+}
+
+pub struct IncompatibleFeatureWarning {
+    pub feature: Feature,
+    pub version: Version,
+    pub has_pragma: bool,
+    pub file_id: FileID,
+    pub file_location: FileLocation,
+}
+
+impl IncompatibleFeatureWarning {
+    pub fn into_report(self) -> Report {
+        let required_version = version_string(&self.feature.required_version());
+        let message = if self.has_pragma {
+            format!(
+                "The file requires circom version {}, but uses {} which require version {}.",
+                version_string(&self.version),
+                self.feature.description(),
+                required_version
+            )
+        } else {
+            format!(
+                "The file uses {} which require circom version {}, but the target compiler version is {}.",
+                self.feature.description(),
+                required_version,
+                version_string(&self.version),
+            )
+        };
+        let mut report = Report::warning(message, ReportCode::IncompatibleCompilerVersion);
+        report.add_primary(
+            self.file_location,
+            self.file_id,
+            format!("This requires circom version {required_version}."),
+        );
+        if self.has_pragma {
+            report.add_help(format!(
+                "Consider updating the version pragma to `pragma circom {required_version};`."
+            ));
+        }
+        report
+    }
+}
+
 fn version_string(version: &Version) -> String {
     format!("{}.{}.{}", version.0, version.1, version.2)
 }
+
+/// Errors generated when anonymous components and tuple assignments are
+/// rewritten as explicit component instantiations and assignments.
+pub enum SyntaxSugarError {
+    UndefinedTemplate {
+        name: String,
+        file_id: FileID,
+        file_location: FileLocation,
+    },
+    WrongNumberOfInputs {
+        name: String,
+        expected: usize,
+        found: usize,
+        file_id: FileID,
+        file_location: FileLocation,
+    },
+    WrongNumberOfOutputs {
+        name: String,
+        expected: usize,
+        found: usize,
+        file_id: FileID,
+        file_location: FileLocation,
+    },
+    InvalidTupleAssignment {
+        file_id: FileID,
+        file_location: FileLocation,
+    },
+    InvalidTuple {
+        file_id: FileID,
+        file_location: FileLocation,
+    },
+}
+
+impl SyntaxSugarError {
+    pub fn into_report(self) -> Report {
+        use SyntaxSugarError::*;
+        match self {
+            UndefinedTemplate { name, file_id, file_location } => {
+                let mut report = Report::error(
+                    format!(
+                        "The template `{name}` used by the anonymous component is not defined."
+                    ),
+                    ReportCode::UndefinedTemplate,
+                );
+                report.add_primary(
+                    file_location,
+                    file_id,
+                    "Anonymous component instantiated here.".to_string(),
+                );
+                report
+            }
+            WrongNumberOfInputs { name, expected, found, file_id, file_location } => {
+                let mut report = Report::error(
+                    format!(
+                        "The template `{name}` declares {expected} input signal(s), but the anonymous component is given {found} signal(s)."
+                    ),
+                    ReportCode::WrongNumberOfArguments(expected, found),
+                );
+                report.add_primary(
+                    file_location,
+                    file_id,
+                    "Anonymous component instantiated here.".to_string(),
+                );
+                report
+            }
+            WrongNumberOfOutputs { name, expected, found, file_id, file_location } => {
+                let mut report = Report::error(
+                    format!(
+                        "The template `{name}` declares {found} output signal(s), but {expected} value(s) are expected here."
+                    ),
+                    ReportCode::WrongNumberOfArguments(expected, found),
+                );
+                report.add_primary(
+                    file_location,
+                    file_id,
+                    "Anonymous component instantiated here.".to_string(),
+                );
+                if expected == 1 {
+                    report.add_note(
+                        "Anonymous components with more than one output must be assigned to a tuple, as in `(a, b) <== C()(c)`.".to_string()
+                    );
+                }
+                report
+            }
+            InvalidTupleAssignment { file_id, file_location } => {
+                let mut report = Report::error(
+                    "Tuples can only be assigned from other tuples or anonymous components."
+                        .to_string(),
+                    ReportCode::ParseFail,
+                );
+                report.add_primary(file_location, file_id, "Invalid tuple assignment.".to_string());
+                report
+            }
+            InvalidTuple { file_id, file_location } => {
+                let mut report = Report::error(
+                    "Tuples and `_` can only be used in tuple assignments.".to_string(),
+                    ReportCode::ParseFail,
+                );
+                report.add_primary(file_location, file_id, "Invalid expression.".to_string());
+                report
+            }
+        }
+    }
+}
+
+/// Errors generated when a function or template is used inconsistently with
+/// its definition.
+pub enum DefinitionError {
+    TemplateCalledAsFunction {
+        name: String,
+        file_id: FileID,
+        file_location: FileLocation,
+        definition_file_id: FileID,
+        definition_location: FileLocation,
+    },
+    FunctionInstantiatedAsComponent {
+        name: String,
+        file_id: FileID,
+        file_location: FileLocation,
+        definition_file_id: FileID,
+        definition_location: FileLocation,
+    },
+    FunctionWrongNumberOfArguments {
+        name: String,
+        expected: usize,
+        found: usize,
+        file_id: FileID,
+        file_location: FileLocation,
+        definition_file_id: FileID,
+        definition_location: FileLocation,
+    },
+    TemplateWrongNumberOfArguments {
+        name: String,
+        expected: usize,
+        found: usize,
+        file_id: FileID,
+        file_location: FileLocation,
+        definition_file_id: FileID,
+        definition_location: FileLocation,
+    },
+    DuplicateDefinition {
+        name: String,
+        file_id: FileID,
+        file_location: FileLocation,
+        definition_file_id: FileID,
+        definition_location: FileLocation,
+    },
+    UnknownComponentSignal {
+        name: String,
+        component_name: String,
+        template_name: String,
+        suggestion: Option<String>,
+        file_id: FileID,
+        file_location: FileLocation,
+        definition_file_id: FileID,
+        definition_location: FileLocation,
+    },
+}
+
+impl DefinitionError {
+    pub fn into_report(self) -> Report {
+        use DefinitionError::*;
+        match self {
+            TemplateCalledAsFunction {
+                name,
+                file_id,
+                file_location,
+                definition_file_id,
+                definition_location,
+            } => {
+                let mut report = Report::error(
+                    format!("The template `{name}` is called like a function."),
+                    ReportCode::DefinitionMisuse,
+                );
+                report.add_primary(
+                    file_location,
+                    file_id,
+                    format!("The template `{name}` is called here."),
+                );
+                report.add_secondary(
+                    definition_location,
+                    definition_file_id,
+                    Some(format!("`{name}` is defined as a template here.")),
+                );
+                report.add_note(format!(
+                    "Templates must be instantiated as components, as in `component c = {name}(...)`."
+                ));
+                report
+            }
+            FunctionInstantiatedAsComponent {
+                name,
+                file_id,
+                file_location,
+                definition_file_id,
+                definition_location,
+            } => {
+                let mut report = Report::error(
+                    format!("The function `{name}` is instantiated as a component."),
+                    ReportCode::DefinitionMisuse,
+                );
+                report.add_primary(
+                    file_location,
+                    file_id,
+                    format!("The function `{name}` is instantiated here."),
+                );
+                report.add_secondary(
+                    definition_location,
+                    definition_file_id,
+                    Some(format!("`{name}` is defined as a function here.")),
+                );
+                report.add_note(
+                    "Only templates can be instantiated as components. Functions must be called in expressions."
+                        .to_string(),
+                );
+                report
+            }
+            FunctionWrongNumberOfArguments {
+                name,
+                expected,
+                found,
+                file_id,
+                file_location,
+                definition_file_id,
+                definition_location,
+            } => {
+                let mut report = Report::error(
+                    format!("The function `{name}` takes {expected} argument(s), but {found} argument(s) are given."),
+                    ReportCode::FunctionWrongNumberOfArguments,
+                );
+                report.add_primary(
+                    file_location,
+                    file_id,
+                    format!("The function `{name}` is called with {found} argument(s) here."),
+                );
+                report.add_secondary(
+                    definition_location,
+                    definition_file_id,
+                    Some(format!("`{name}` is defined with {expected} parameter(s) here.")),
+                );
+                report
+            }
+            TemplateWrongNumberOfArguments {
+                name,
+                expected,
+                found,
+                file_id,
+                file_location,
+                definition_file_id,
+                definition_location,
+            } => {
+                let mut report = Report::error(
+                    format!("The template `{name}` takes {expected} argument(s), but {found} argument(s) are given."),
+                    ReportCode::TemplateWrongNumberOfArguments,
+                );
+                report.add_primary(
+                    file_location,
+                    file_id,
+                    format!("The template `{name}` is instantiated with {found} argument(s) here."),
+                );
+                report.add_secondary(
+                    definition_location,
+                    definition_file_id,
+                    Some(format!("`{name}` is defined with {expected} parameter(s) here.")),
+                );
+                report
+            }
+            DuplicateDefinition {
+                name,
+                file_id,
+                file_location,
+                definition_file_id,
+                definition_location,
+            } => {
+                let mut report = Report::error(
+                    format!("The name `{name}` is defined more than once."),
+                    ReportCode::SameSymbolDeclaredTwice,
+                );
+                report.add_primary(
+                    file_location,
+                    file_id,
+                    format!("`{name}` is defined again here."),
+                );
+                report.add_secondary(
+                    definition_location,
+                    definition_file_id,
+                    Some(format!("`{name}` is first defined here.")),
+                );
+                report.add_note(format!(
+                    "Only the first definition of `{name}` is analyzed. Rename or remove one of the definitions."
+                ));
+                report
+            }
+            UnknownComponentSignal {
+                name,
+                component_name,
+                template_name,
+                suggestion,
+                file_id,
+                file_location,
+                definition_file_id,
+                definition_location,
+            } => {
+                let mut report = Report::error(
+                    format!("The template `{template_name}` does not declare an input or output signal `{name}`."),
+                    ReportCode::UnknownComponentSignal,
+                );
+                report.add_primary(
+                    file_location,
+                    file_id,
+                    format!(
+                        "The signal `{name}` is accessed on the component `{component_name}` here."
+                    ),
+                );
+                report.add_secondary(
+                    definition_location,
+                    definition_file_id,
+                    Some(format!("The template `{template_name}` is defined here.")),
+                );
+                if let Some(suggestion) = suggestion {
+                    report.add_note(format!("Did you mean `{suggestion}`?"));
+                }
+                report
+            }
+        }
+    }
+}
+
+/// Errors and warnings generated when checking the main component against the
+/// definition of the main template.
+pub enum MainComponentError {
+    UnknownPublicSignal {
+        name: String,
+        template_name: String,
+        file_id: FileID,
+        file_location: FileLocation,
+        definition_file_id: FileID,
+        definition_location: FileLocation,
+    },
+    PrivateInputs {
+        names: Vec<String>,
+        template_name: String,
+        file_id: FileID,
+        file_location: FileLocation,
+        declarations: Vec<(FileID, FileLocation)>,
+    },
+    UndefinedTemplate {
+        name: String,
+        file_id: FileID,
+        file_location: FileLocation,
+    },
+    NonConstantArgument {
+        name: String,
+        template_name: String,
+        file_id: FileID,
+        file_location: FileLocation,
+    },
+    MissingSignals {
+        template_name: String,
+        has_inputs: bool,
+        has_outputs: bool,
+        file_id: FileID,
+        file_location: FileLocation,
+        definition_file_id: FileID,
+        definition_location: FileLocation,
+    },
+}
+
+impl MainComponentError {
+    pub fn into_report(self) -> Report {
+        use MainComponentError::*;
+        match self {
+            UnknownPublicSignal {
+                name,
+                template_name,
+                file_id,
+                file_location,
+                definition_file_id,
+                definition_location,
+            } => {
+                let mut report = Report::error(
+                    format!("The public signal `{name}` is not an input signal of the template `{template_name}`."),
+                    ReportCode::UnknownPublicSignal,
+                );
+                report.add_primary(
+                    file_location,
+                    file_id,
+                    format!("The signal `{name}` is listed as public by this main component."),
+                );
+                report.add_secondary(
+                    definition_location,
+                    definition_file_id,
+                    Some(format!("The template `{template_name}` is defined here.")),
+                );
+                report.add_note(
+                    "Only input signals of the main template can be declared as public. (Output signals are always public.)".to_string(),
+                );
+                report
+            }
+            PrivateInputs { names, template_name, file_id, file_location, declarations } => {
+                let signals =
+                    names.iter().map(|name| format!("`{name}`")).collect::<Vec<_>>().join(", ");
+                let mut report = Report::info(
+                    format!("The input signal(s) {signals} of the template `{template_name}` are private, since they are not listed as public."),
+                    ReportCode::PrivateMainInput,
+                );
+                report.add_primary(
+                    file_location,
+                    file_id,
+                    "The main component is declared here.".to_string(),
+                );
+                for (file_id, file_location) in declarations {
+                    report.add_secondary(
+                        file_location,
+                        file_id,
+                        Some("This input signal is private.".to_string()),
+                    );
+                }
+                report.add_note(
+                    "Input signals of the main component which are not listed as public are private by default. If this is intended, consider documenting that the input is private.".to_string(),
+                );
+                report
+            }
+            UndefinedTemplate { name, file_id, file_location } => {
+                let mut report = Report::error(
+                    format!(
+                        "The template `{name}` instantiated by the main component is not defined."
+                    ),
+                    ReportCode::UndefinedTemplate,
+                );
+                report.add_primary(
+                    file_location,
+                    file_id,
+                    "The main component is declared here.".to_string(),
+                );
+                report
+            }
+            NonConstantArgument { name, template_name, file_id, file_location } => {
+                let mut report = Report::error(
+                    format!("The argument passed to the main template `{template_name}` depends on the variable `{name}`."),
+                    ReportCode::MainComponentArgument,
+                );
+                report.add_primary(
+                    file_location,
+                    file_id,
+                    format!("This argument depends on `{name}`."),
+                );
+                report.add_note(
+                    "The arguments of the main component must be constant expressions, since there are no variables in scope at the main component declaration.".to_string(),
+                );
+                report
+            }
+            MissingSignals {
+                template_name,
+                has_inputs,
+                has_outputs,
+                file_id,
+                file_location,
+                definition_file_id,
+                definition_location,
+            } => {
+                let missing = match (has_inputs, has_outputs) {
+                    (false, false) => "input or output signals",
+                    (false, true) => "input signals",
+                    _ => "output signals",
+                };
+                let mut report = Report::warning(
+                    format!("The main template `{template_name}` does not declare any {missing}."),
+                    ReportCode::MainComponentInterface,
+                );
+                report.add_primary(
+                    file_location,
+                    file_id,
+                    "The main component is declared here.".to_string(),
+                );
+                report.add_secondary(
+                    definition_location,
+                    definition_file_id,
+                    Some(format!("The template `{template_name}` is defined here.")),
+                );
+                report.add_note(
+                    "A circuit without inputs proves a statement independent of any data given by the verifier, and a circuit without outputs (and without public inputs) does not expose any values to the verifier.".to_string(),
+                );
+                report
+            }
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+
+use program_structure::ast::*;
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::report::ReportCollection;
+
+use super::errors::IncompatibleFeatureWarning;
+
+/// Language features introduced after Circom 2.0.0.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) enum Feature {
+    CustomTemplates,
+    ParallelTemplates,
+    SignalTags,
+    AnonymousComponents,
+    TupleAssignments,
+}
+
+impl Feature {
+    /// The first compiler version supporting the feature.
+    pub(crate) fn required_version(&self) -> Version {
+        use Feature::*;
+        match self {
+            CustomTemplates => (2, 0, 6),
+            ParallelTemplates => (2, 0, 8),
+            SignalTags | AnonymousComponents | TupleAssignments => (2, 1, 0),
+        }
+    }
+
+    pub(crate) fn description(&self) -> &'static str {
+        use Feature::*;
+        match self {
+            CustomTemplates => "custom templates",
+            ParallelTemplates => "parallel templates",
+            SignalTags => "signal tags",
+            AnonymousComponents => "anonymous components",
+            TupleAssignments => "tuple assignments",
+        }
+    }
+}
+
+/// Checks that the language features used by the given program are supported
+/// by the compiler version required by the file. If the file does not contain
+/// a version pragma, the features are checked against the given compiler
+/// version instead. Only the first use of each feature is reported.
+pub(crate) fn check_feature_versions(
+    program: &AST,
+    file_id: FileID,
+    compiler_version: &Version,
+) -> ReportCollection {
+    let mut features = FeatureUses::new();
+    for definition in &program.definitions {
+        features.visit_definition(definition);
+    }
+    let (version, has_pragma) = match program.compiler_version {
+        Some(version) => (version, true),
+        None => (*compiler_version, false),
+    };
+    let mut features = features.uses.into_iter().collect::<Vec<_>>();
+    features.sort_by_key(|(_, file_location)| file_location.start);
+    features
+        .into_iter()
+        .filter(|(feature, _)| feature.required_version() > version)
+        .map(|(feature, file_location)| {
+            IncompatibleFeatureWarning { feature, version, has_pragma, file_id, file_location }
+                .into_report()
+        })
+        .collect()
+}
+
+/// The location of the first use of each feature.
+struct FeatureUses {
+    uses: HashMap<Feature, FileLocation>,
+}
+
+impl FeatureUses {
+    fn new() -> FeatureUses {
+        FeatureUses { uses: HashMap::new() }
+    }
+
+    fn add(&mut self, feature: Feature, meta: &Meta) {
+        let file_location = meta.location.clone();
+        let first_use = self.uses.entry(feature).or_insert_with(|| file_location.clone());
+        if file_location.start < first_use.start {
+            *first_use = file_location;
+        }
+    }
+
+    fn visit_definition(&mut self, definition: &Definition) {
+        match definition {
+            Definition::Template { meta, body, parallel, is_custom_gate, .. } => {
+                if *is_custom_gate {
+                    self.add(Feature::CustomTemplates, meta);
+                }
+                if *parallel {
+                    self.add(Feature::ParallelTemplates, meta);
+                }
+                self.visit_statement(body);
+            }
+            Definition::Function { body, .. } => self.visit_statement(body),
+        }
+    }
+
+    fn visit_statement(&mut self, stmt: &Statement) {
+        use Statement::*;
+        match stmt {
+            IfThenElse { cond, if_case, else_case, .. } => {
+                self.visit_expression(cond);
+                self.visit_statement(if_case);
+                if let Some(else_case) = else_case {
+                    self.visit_statement(else_case);
+                }
+            }
+            While { cond, stmt, .. } => {
+                self.visit_expression(cond);
+                self.visit_statement(stmt);
+            }
+            Block { stmts, .. } | InitializationBlock { initializations: stmts, .. } => {
+                for stmt in stmts {
+                    self.visit_statement(stmt);
+                }
+            }
+            Declaration { meta, xtype, dimensions, .. } => {
+                if matches!(xtype, VariableType::Signal(_, _, tags) if !tags.is_empty()) {
+                    self.add(Feature::SignalTags, meta);
+                }
+                for size in dimensions {
+                    self.visit_expression(size);
+                }
+            }
+            Substitution { access, rhe, .. } => {
+                for access in access {
+                    if let Access::ArrayAccess(index) = access {
+                        self.visit_expression(index);
+                    }
+                }
+                self.visit_expression(rhe);
+            }
+            MultiSubstitution { meta, lhe, rhe, .. } => {
+                self.add(Feature::TupleAssignments, meta);
+                self.visit_expression(lhe);
+                self.visit_expression(rhe);
+            }
+            ConstraintEquality { lhe, rhe, .. } => {
+                self.visit_expression(lhe);
+                self.visit_expression(rhe);
+            }
+            LogCall { args, .. } => {
+                for arg in args {
+                    if let LogArgument::LogExp(arg) = arg {
+                        self.visit_expression(arg);
+                    }
+                }
+            }
+            Return { value: arg, .. } | Assert { arg, .. } => self.visit_expression(arg),
+        }
+    }
+
+    fn visit_expression(&mut self, expr: &Expression) {
+        use Expression::*;
+        match expr {
+            InfixOp { lhe, rhe, .. } => {
+                self.visit_expression(lhe);
+                self.visit_expression(rhe);
+            }
+            PrefixOp { rhe, .. } => self.visit_expression(rhe),
+            InlineSwitchOp { cond, if_true, if_false, .. } => {
+                self.visit_expression(cond);
+                self.visit_expression(if_true);
+                self.visit_expression(if_false);
+            }
+            ParallelOp { meta, rhe } => {
+                self.add(Feature::ParallelTemplates, meta);
+                self.visit_expression(rhe);
+            }
+            Variable { access, .. } => {
+                for access in access {
+                    if let Access::ArrayAccess(index) = access {
+                        self.visit_expression(index);
+                    }
+                }
+            }
+            Number(..) => {}
+            Call { args: values, .. } | ArrayInLine { values, .. } => {
+                for value in values {
+                    self.visit_expression(value);
+                }
+            }
+            AnonymousComponent { meta, params, signals, .. } => {
+                self.add(Feature::AnonymousComponents, meta);
+                for value in params.iter().chain(signals) {
+                    self.visit_expression(value);
+                }
+            }
+            Tuple { meta, values } => {
+                self.add(Feature::TupleAssignments, meta);
+                for value in values {
+                    self.visit_expression(value);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser_logic::parse_file;
+
+    use super::*;
+
+    #[test]
+    fn test_feature_versions() {
+        let src = r#"
+            pragma circom 2.0.0;
+
+            template parallel T() {
+                signal input {binary} in[2];
+                signal output out;
+                signal x;
+
+                (x, _) <== C()(in[0], in[1]);
+                out <== x;
+            }
+        "#;
+        validate_reports(
+            src,
+            &[
+                Feature::ParallelTemplates,
+                Feature::SignalTags,
+                Feature::TupleAssignments,
+                Feature::AnonymousComponents,
+            ],
+        );
+
+        let src = r#"
+            pragma circom 2.0.8;
+
+            template parallel T() {
+                signal input {binary} in;
+                signal output out;
+
+                out <== in;
+            }
+        "#;
+        validate_reports(src, &[Feature::SignalTags]);
+
+        let src = r#"
+            pragma circom 2.1.0;
+
+            template T() {
+                signal input {binary} in[2];
+                signal output out;
+
+                out <== C()(in[0], in[1]);
+            }
+        "#;
+        validate_reports(src, &[]);
+
+        // Files without a pragma are checked against the compiler version.
+        let src = r#"
+            template T() {
+                signal input in[2];
+                signal output out;
+
+                out <== C()(in[0], in[1]);
+            }
+        "#;
+        validate_reports(src, &[Feature::AnonymousComponents]);
+    }
+
+    #[test]
+    fn test_latest_compiler_version() {
+        use Feature::*;
+        let features =
+            [CustomTemplates, ParallelTemplates, SignalTags, AnonymousComponents, TupleAssignments];
+        let latest_version = features.iter().map(Feature::required_version).max();
+        assert_eq!(
+            latest_version,
+            Some(crate::parse_version_string(crate::LATEST_COMPILER_VERSION))
+        );
+    }
+
+    fn validate_reports(src: &str, expected_features: &[Feature]) {
+        let program = parse_file(src, 0).ok().unwrap().0;
+        let reports = check_feature_versions(&program, 0, &(2, 0, 8));
+        assert_eq!(reports.len(), expected_features.len());
+        for (report, feature) in reports.iter().zip(expected_features) {
+            assert!(report.message().contains(feature.description()));
+        }
+    }
+}
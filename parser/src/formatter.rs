@@ -0,0 +1,127 @@
+use program_structure::file_definition::FileID;
+use program_structure::printer::print_ast;
+use program_structure::report::ReportCollection;
+
+use super::parser_logic::parse_file;
+
+/// Formats the given source as canonical Circom, preserving comments. Files
+/// with syntax errors are not formatted, and the syntax errors are returned
+/// instead.
+pub fn format_source(src: &str, file_id: FileID) -> Result<String, ReportCollection> {
+    let (ast, reports) = parse_file(src, file_id)?;
+    if !reports.is_empty() {
+        return Err(reports);
+    }
+    Ok(print_ast(&ast, src))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_source;
+
+    #[test]
+    fn test_format_source() {
+        let src = r#"
+pragma circom   2.0.0;
+// Header comment.
+include "circomlib/poseidon.circom";
+include "circomlib/bitify.circom";
+template   Num2Bits( n ){
+  signal input in;  signal output out[n];
+    var lc1=0;
+
+
+  var e2 = 1; // Trailing comment.
+  for (var i = 0; i<n; i++) {
+      out[i] <-- (in >> i) & 1;
+      out[i] * (out[i] -1 ) === 0;
+      lc1 += out[i] * e2;
+      e2 = e2+e2;
+  }
+  /* Block comment. */
+  lc1 === in;
+}
+function f(x) {
+    if (x == 0) { return 1; } else if (x > 0x10) { return (x + 1) * 2; }
+    else {
+        return -(x - 1);
+    }
+}
+component main {public [in]} = Num2Bits(8);
+"#;
+        let expected = r#"pragma circom 2.0.0;
+
+// Header comment.
+include "circomlib/poseidon.circom";
+include "circomlib/bitify.circom";
+
+template Num2Bits(n) {
+    signal input in;
+    signal output out[n];
+    var lc1 = 0;
+
+    var e2 = 1; // Trailing comment.
+    for (var i = 0; i < n; i++) {
+        out[i] <-- in >> i & 1;
+        out[i] * (out[i] - 1) === 0;
+        lc1 += out[i] * e2;
+        e2 = e2 + e2;
+    }
+    /* Block comment. */
+    lc1 === in;
+}
+
+function f(x) {
+    if (x == 0) {
+        return 1;
+    } else if (x > 0x10) {
+        return (x + 1) * 2;
+    } else {
+        return -(x - 1);
+    }
+}
+
+component main {public [in]} = Num2Bits(8);
+"#;
+        let formatted = format_source(src, 0).ok().unwrap();
+        assert_eq!(formatted, expected);
+        // Formatting is idempotent.
+        assert_eq!(format_source(&formatted, 0).ok().unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_format_source_syntax() {
+        let src = r#"template T() {
+    signal input {binary} in[2];
+    signal output out;
+    component c[2], d = T();
+    var x[2] = [1, 2], y;
+    signal a <== in[0] * in[1];
+    in[0] --> out;
+    (x[0], _) <== C()(in[0]);
+    x[0] = c[0].out + d.out ** 2 ** 3;
+    x[1] = a - (x[0] - y) - x[0] ? y : -x[1];
+    y \= 2;
+    y--;
+    while (y > 0)
+        y -= 1;
+    log("x: ", x[0]);
+    assert(!(x[0] == x[1]) && ~y < 3);
+}
+"#;
+        let formatted = format_source(src, 0).ok().unwrap();
+        assert_eq!(formatted, src);
+    }
+
+    #[test]
+    fn test_format_source_with_errors() {
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                out <== in +;
+            }
+        "#;
+        assert!(format_source(src, 0).is_err());
+    }
+}
@@ -1,22 +1,85 @@
 use crate::errors::FileOsError;
 
-use super::errors::IncludeError;
+use super::errors::{CyclicIncludeWarning, IncludeError};
 use program_structure::ast::Include;
+use program_structure::file_definition::{FileID, FileLocation};
 use program_structure::report::{Report, ReportCollection};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
+use std::str::FromStr;
+
+/// A remapping of include paths (like `circomlib=node_modules/circomlib/circuits`).
+/// Include paths starting with the prefix are resolved by replacing the prefix
+/// with the target path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Remapping {
+    pub prefix: String,
+    pub target: PathBuf,
+}
+
+impl Remapping {
+    /// Returns the remapped path if the include path starts with the prefix.
+    fn apply(&self, include_path: &str) -> Option<PathBuf> {
+        let suffix = include_path.strip_prefix(&self.prefix)?;
+        // Only match complete path components.
+        if !self.prefix.ends_with('/') && !suffix.is_empty() && !suffix.starts_with('/') {
+            return None;
+        }
+        Some(self.target.join(suffix.trim_start_matches('/')))
+    }
+}
+
+impl FromStr for Remapping {
+    type Err = String;
+
+    fn from_str(remapping: &str) -> Result<Remapping, Self::Err> {
+        match remapping.split_once('=') {
+            Some((prefix, target)) if !prefix.is_empty() && !target.is_empty() => {
+                Ok(Remapping { prefix: prefix.to_string(), target: PathBuf::from(target) })
+            }
+            _ => Err(format!("invalid remapping `{remapping}` (expected `prefix=path`)")),
+        }
+    }
+}
+
+/// An include statement, together with the path of the included file.
+pub struct ResolvedInclude {
+    pub path: PathBuf,
+    pub file_id: Option<FileID>,
+    pub file_location: FileLocation,
+}
+
+/// Maps each file to the resolved include statements in the file.
+pub type ResolvedIncludes = BTreeMap<PathBuf, Vec<ResolvedInclude>>;
 
 pub struct FileStack {
     current_location: Option<PathBuf>,
+    libraries: Vec<PathBuf>,
+    remappings: Vec<Remapping>,
     black_paths: HashSet<PathBuf>,
     stack: Vec<PathBuf>,
+    resolved: Vec<ResolvedInclude>,
 }
 
 impl FileStack {
-    pub fn new(paths: &Vec<PathBuf>, reports: &mut ReportCollection) -> FileStack {
-        let mut result =
-            FileStack { current_location: None, black_paths: HashSet::new(), stack: Vec::new() };
+    pub fn new(
+        paths: &Vec<PathBuf>,
+        libraries: &[PathBuf],
+        remappings: &[Remapping],
+        reports: &mut ReportCollection,
+    ) -> FileStack {
+        // Longer prefixes take precedence over shorter prefixes.
+        let mut remappings = remappings.to_vec();
+        remappings.sort_by_key(|remapping| std::cmp::Reverse(remapping.prefix.len()));
+        let mut result = FileStack {
+            current_location: None,
+            libraries: libraries.to_vec(),
+            remappings,
+            black_paths: HashSet::new(),
+            stack: Vec::new(),
+            resolved: Vec::new(),
+        };
         result.add_files(paths, reports);
         result
     }
@@ -48,23 +111,54 @@ impl FileStack {
         }
     }
 
-    pub fn add_include(&mut self, include: &Include) -> Result<(), Report> {
-        let mut location = self.current_location.clone().expect("parsing file");
-        location.push(include.path.clone());
-        match fs::canonicalize(location) {
-            Ok(path) => {
+    /// Resolves the included path using the matching remappings first, then
+    /// relative to the directory of the current file, and finally relative to
+    /// each library path in order. Returns the resolved path.
+    pub fn add_include(&mut self, include: &Include) -> Result<PathBuf, Report> {
+        let current_location = self.current_location.clone().expect("parsing file");
+        let remapped_paths = self
+            .remappings
+            .iter()
+            .filter_map(|remapping| remapping.apply(&include.path))
+            .collect::<Vec<_>>();
+        let search_paths = std::iter::once(current_location)
+            .chain(self.libraries.iter().cloned())
+            .collect::<Vec<_>>();
+        let locations = remapped_paths
+            .iter()
+            .cloned()
+            .chain(search_paths.iter().map(|search_path| search_path.join(&include.path)));
+        for location in locations {
+            if let Ok(path) = fs::canonicalize(location) {
                 if !self.black_paths.contains(&path) {
-                    self.stack.push(path);
+                    self.stack.push(path.clone());
                 }
-                Ok(())
-            }
-            Err(_) => Err(IncludeError {
-                path: include.path.clone(),
-                file_id: include.meta.file_id,
-                file_location: include.meta.file_location(),
+                self.resolved.push(ResolvedInclude {
+                    path: path.clone(),
+                    file_id: include.meta.file_id,
+                    file_location: include.meta.file_location(),
+                });
+                return Ok(path);
             }
-            .into_report()),
         }
+        Err(IncludeError {
+            path: include.path.clone(),
+            file_id: include.meta.file_id,
+            file_location: include.meta.file_location(),
+            search_paths,
+            remapped_paths,
+        }
+        .into_report())
+    }
+
+    /// Returns the includes resolved since the last call.
+    pub fn take_resolved(&mut self) -> Vec<ResolvedInclude> {
+        std::mem::take(&mut self.resolved)
+    }
+
+    /// Sets the directory used to resolve relative includes.
+    pub fn set_current_location(&mut self, location: PathBuf) {
+        self.current_location = Some(location);
     }
 
     pub fn take_next(&mut self) -> Option<PathBuf> {
@@ -85,3 +179,172 @@ impl FileStack {
         }
     }
 }
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum VisitState {
+    OnStack,
+    Done,
+}
+
+/// Reports each cycle in the include graph. Included files are only parsed
+/// once, so cycles do not prevent the program from being parsed, but they
+/// make the structure of the project harder to follow. Since at most one
+/// cycle is reported for each include statement, each cycle is reported
+/// once.
+pub fn find_include_cycles(includes: &ResolvedIncludes) -> ReportCollection {
+    let mut reports = ReportCollection::new();
+    let mut states = HashMap::new();
+    for file_path in includes.keys() {
+        if !states.contains_key(file_path) {
+            visit_includes(file_path, includes, &mut states, &mut Vec::new(), &mut reports);
+        }
+    }
+    reports
+}
+
+/// Visits the files included by the given file depth-first. The edges of the
+/// current path are kept on the stack, and an include of a file on the stack
+/// closes a cycle.
+fn visit_includes<'a>(
+    file_path: &'a PathBuf,
+    includes: &'a ResolvedIncludes,
+    states: &mut HashMap<&'a PathBuf, VisitState>,
+    stack: &mut Vec<(&'a PathBuf, &'a ResolvedInclude)>,
+    reports: &mut ReportCollection,
+) {
+    states.insert(file_path, VisitState::OnStack);
+    for include in includes.get(file_path).into_iter().flatten() {
+        match states.get(&include.path) {
+            Some(VisitState::OnStack) => {
+                let start = stack
+                    .iter()
+                    .position(|(includer, _)| **includer == include.path)
+                    .unwrap_or(stack.len());
+                let mut cycle = stack[start..].to_vec();
+                cycle.push((file_path, include));
+                reports.push(
+                    CyclicIncludeWarning {
+                        file_paths: cycle
+                            .iter()
+                            .map(|(includer, _)| includer.display().to_string())
+                            .collect(),
+                        includes: cycle
+                            .iter()
+                            .map(|(_, include)| (include.file_id, include.file_location.clone()))
+                            .collect(),
+                    }
+                    .into_report(),
+                );
+            }
+            Some(VisitState::Done) => {}
+            None => {
+                stack.push((file_path, include));
+                visit_includes(&include.path, includes, states, stack, reports);
+                stack.pop();
+            }
+        }
+    }
+    states.insert(file_path, VisitState::Done);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+
+    use program_structure::ast::{build_include, Meta};
+    use program_structure::report::ReportCollection;
+
+    use super::*;
+
+    #[test]
+    fn test_library_include() {
+        let root = std::env::temp_dir().join(format!("circomspect-include-{}", std::process::id()));
+        let (src, lib) = (root.join("src"), root.join("lib"));
+        fs::create_dir_all(&src).unwrap();
+        fs::create_dir_all(&lib).unwrap();
+        fs::write(src.join("main.circom"), "").unwrap();
+        fs::write(lib.join("gates.circom"), "").unwrap();
+
+        let include = build_include(Meta::new(0, 0), "gates.circom".to_string());
+        let paths: Vec<PathBuf> = vec![src.join("main.circom")];
+        let mut reports = ReportCollection::new();
+
+        // The include cannot be resolved without the library path.
+        let mut file_stack = FileStack::new(&paths, &[], &[], &mut reports);
+        assert!(file_stack.take_next().is_some());
+        assert!(file_stack.add_include(&include).is_err());
+
+        // The include is resolved against the library path.
+        let mut file_stack = FileStack::new(&paths, std::slice::from_ref(&lib), &[], &mut reports);
+        assert!(file_stack.take_next().is_some());
+        assert!(file_stack.add_include(&include).is_ok());
+        assert_eq!(
+            file_stack.take_next(),
+            Some(fs::canonicalize(lib.join("gates.circom")).unwrap())
+        );
+        assert!(reports.is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_remapped_include() {
+        let root = std::env::temp_dir().join(format!("circomspect-remap-{}", std::process::id()));
+        let (src, lib) = (root.join("src"), root.join("node_modules/circomlib/circuits"));
+        fs::create_dir_all(&src).unwrap();
+        fs::create_dir_all(&lib).unwrap();
+        fs::write(src.join("main.circom"), "").unwrap();
+        fs::write(lib.join("poseidon.circom"), "").unwrap();
+
+        let include = build_include(Meta::new(0, 0), "circomlib/poseidon.circom".to_string());
+        let paths: Vec<PathBuf> = vec![src.join("main.circom")];
+        let mut reports = ReportCollection::new();
+
+        // Remappings only apply to complete path components.
+        let remapping = Remapping::from_str(&format!("circom={}", lib.display())).unwrap();
+        let mut file_stack = FileStack::new(&paths, &[], &[remapping], &mut reports);
+        assert!(file_stack.take_next().is_some());
+        assert!(file_stack.add_include(&include).is_err());
+
+        let remappings = [
+            Remapping::from_str(&format!("circomlib={}", root.display())).unwrap(),
+            Remapping::from_str(&format!("circomlib/={}/", lib.display())).unwrap(),
+        ];
+        let mut file_stack = FileStack::new(&paths, &[], &remappings, &mut reports);
+        assert!(file_stack.take_next().is_some());
+        assert_eq!(
+            file_stack.add_include(&include).ok(),
+            Some(fs::canonicalize(lib.join("poseidon.circom")).unwrap())
+        );
+        assert!(Remapping::from_str("circomlib").is_err());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_include_cycles() {
+        let include = |path: &str| ResolvedInclude {
+            path: PathBuf::from(path),
+            file_id: Some(0),
+            file_location: 0..0,
+        };
+        let includes = ResolvedIncludes::from([
+            (PathBuf::from("a"), vec![include("b"), include("d")]),
+            (PathBuf::from("b"), vec![include("c")]),
+            (PathBuf::from("c"), vec![include("a"), include("d")]),
+            (PathBuf::from("d"), vec![include("d")]),
+        ]);
+        let reports = find_include_cycles(&includes);
+        assert_eq!(reports.len(), 2);
+        assert!(reports[0].notes()[0].contains("`a` -> `b` -> `c` -> `a`"));
+        assert!(reports[1].notes()[0].contains("`d` -> `d`"));
+
+        let includes = ResolvedIncludes::from([
+            (PathBuf::from("a"), vec![include("b"), include("c")]),
+            (PathBuf::from("b"), vec![include("c")]),
+        ]);
+        assert!(find_include_cycles(&includes).is_empty());
+    }
+}
@@ -1,3 +1,6 @@
+// Parse errors are returned as (large) `Report` values.
+#![allow(clippy::result_large_err)]
+
 extern crate num_bigint_dig as num_bigint;
 extern crate num_traits;
 extern crate serde;
@@ -10,18 +13,41 @@ lalrpop_mod!(#[allow(clippy::all)] pub lang);
 
 use log::debug;
 
+mod definition_checks;
 mod errors;
+mod feature_versions;
+mod formatter;
 mod include_logic;
+mod main_checks;
+mod parse_cache;
 mod parser_logic;
-use include_logic::FileStack;
+mod syntax_sugar_remover;
+use include_logic::{FileStack, ResolvedIncludes};
+pub use formatter::format_source;
+pub use include_logic::Remapping;
+pub use parse_cache::{ParseCache, ParsedFile};
 use program_structure::ast::{Version, AST};
 use program_structure::report::{Report, ReportCollection};
 use program_structure::file_definition::{FileID, FileLibrary};
 use program_structure::program_archive::ProgramArchive;
+use program_structure::template_data::TemplateInfo;
 use program_structure::template_library::TemplateLibrary;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// The latest Circom compiler version supported by the parser. This is the
+/// version required by the most recent language feature recognized by the
+/// grammar.
+pub const LATEST_COMPILER_VERSION: &str = "2.1.0";
+
+/// Maps each file to the (resolved) paths of the files it includes.
+pub type IncludeGraph = BTreeMap<PathBuf, BTreeSet<PathBuf>>;
+
+/// Maps each parsed file to the time spent parsing the file.
+pub type ParseTimings = BTreeMap<PathBuf, Duration>;
 
 pub enum ParseResult {
     // The program was successfully parsed without issues.
@@ -30,16 +56,133 @@ pub enum ParseResult {
     Library(Box<TemplateLibrary>, ReportCollection),
 }
 
-pub fn parse_files(file_paths: &Vec<PathBuf>, compiler_version: &str) -> ParseResult {
+/// Parses the given files together with all (transitively) included files.
+/// Include paths are resolved using the matching remappings first, then
+/// relative to the including file, and finally relative to each of the given
+/// library paths in order.
+pub fn parse_files(
+    file_paths: &Vec<PathBuf>,
+    libraries: &[PathBuf],
+    remappings: &[Remapping],
+    compiler_version: &str,
+) -> ParseResult {
+    let mut timings = ParseTimings::new();
+    parse_program(file_paths, None, libraries, remappings, compiler_version, None, &mut timings)
+}
+
+/// Parses the given files like `parse_files`, and also returns the time spent
+/// parsing each file.
+pub fn parse_files_timed(
+    file_paths: &Vec<PathBuf>,
+    libraries: &[PathBuf],
+    remappings: &[Remapping],
+    compiler_version: &str,
+) -> (ParseResult, ParseTimings) {
+    let mut timings = ParseTimings::new();
+    let result = parse_program(
+        file_paths,
+        None,
+        libraries,
+        remappings,
+        compiler_version,
+        None,
+        &mut timings,
+    );
+    (result, timings)
+}
+
+/// Parses the given files like `parse_files_timed`. Files which are found in
+/// the given cache are not parsed again, and newly parsed files are added to
+/// the cache.
+pub fn parse_files_cached(
+    file_paths: &Vec<PathBuf>,
+    libraries: &[PathBuf],
+    remappings: &[Remapping],
+    compiler_version: &str,
+    cache: &mut ParseCache,
+) -> (ParseResult, ParseTimings) {
+    let mut timings = ParseTimings::new();
+    let result = parse_program(
+        file_paths,
+        None,
+        libraries,
+        remappings,
+        compiler_version,
+        Some(cache),
+        &mut timings,
+    );
+    (result, timings)
+}
+
+/// Parses the given source together with all (transitively) included files.
+/// Here, `name` is used to identify the source in reports. Include paths are
+/// resolved using the matching remappings first, then relative to the current
+/// directory, and finally relative to each of the given library paths in
+/// order.
+pub fn parse_source(
+    name: &str,
+    src: &str,
+    libraries: &[PathBuf],
+    remappings: &[Remapping],
+    compiler_version: &str,
+) -> ParseResult {
+    let mut timings = ParseTimings::new();
+    let source = Some((name, src));
+    parse_program(&Vec::new(), source, libraries, remappings, compiler_version, None, &mut timings)
+}
+
+fn parse_program(
+    file_paths: &Vec<PathBuf>,
+    mut source: Option<(&str, &str)>,
+    libraries: &[PathBuf],
+    remappings: &[Remapping],
+    compiler_version: &str,
+    mut cache: Option<&mut ParseCache>,
+    timings: &mut ParseTimings,
+) -> ParseResult {
     let compiler_version = parse_version_string(compiler_version);
 
     let mut reports = ReportCollection::new();
-    let mut file_stack = FileStack::new(file_paths, &mut reports);
+    let mut file_stack = FileStack::new(file_paths, libraries, remappings, &mut reports);
     let mut file_library = FileLibrary::new();
     let mut definitions = HashMap::new();
     let mut main_components = Vec::new();
-    while let Some(file_path) = FileStack::take_next(&mut file_stack) {
-        match parse_file(&file_path, &mut file_stack, &mut file_library, &compiler_version) {
+    let mut includes = ResolvedIncludes::new();
+    loop {
+        let start = Instant::now();
+        let (file_path, result) = if let Some((name, src)) = source.take() {
+            // The source is parsed first, with includes resolved relative to
+            // the current directory.
+            FileStack::set_current_location(&mut file_stack, PathBuf::from("."));
+            let result = parse_contents(
+                Path::new(name),
+                src.to_string(),
+                &mut file_stack,
+                &mut file_library,
+                &compiler_version,
+                cache.as_deref_mut(),
+            );
+            (PathBuf::from(name), result)
+        } else if let Some(file_path) = FileStack::take_next(&mut file_stack) {
+            let result = parse_file(
+                &file_path,
+                &mut file_stack,
+                &mut file_library,
+                &compiler_version,
+                cache.as_deref_mut(),
+            );
+            (file_path, result)
+        } else {
+            break;
+        };
+        let resolved = FileStack::take_resolved(&mut file_stack);
+        if !resolved.is_empty() {
+            // Included paths are canonical, so the file path is canonicalized
+            // to detect cycles through the initial file.
+            includes.insert(fs::canonicalize(&file_path).unwrap_or(file_path.clone()), resolved);
+        }
+        *timings.entry(file_path).or_default() += start.elapsed();
+        match result {
             Ok((file_id, program, mut warnings)) => {
                 if let Some(main_component) = program.main_component {
                     main_components.push((file_id, main_component, program.custom_gates));
@@ -47,11 +190,21 @@ pub fn parse_files(file_paths: &Vec<PathBuf>, compiler_version: &str) -> ParseRe
                 definitions.insert(file_id, program.definitions);
                 reports.append(&mut warnings);
             }
-            Err(error) => {
-                reports.push(error);
+            Err(mut errors) => {
+                reports.append(&mut errors);
             }
         }
     }
+    reports.extend(include_logic::find_include_cycles(&includes));
+    reports.extend(definition_checks::remove_duplicate_definitions(&mut definitions));
+    reports.extend(syntax_sugar_remover::remove_syntactic_sugar(
+        &mut definitions,
+        &TemplateInfo::new(),
+    ));
+    reports.extend(definition_checks::check_definitions(&definitions));
+    if let [(main_id, main_component, _)] = &main_components[..] {
+        reports.extend(main_checks::check_main_component(*main_id, main_component, &definitions));
+    }
     match &main_components[..] {
         [(main_id, main_component, custom_gates)] => {
             // TODO: This calls FillMeta::fill a second time.
@@ -83,20 +236,88 @@ pub fn parse_files(file_paths: &Vec<PathBuf>, compiler_version: &str) -> ParseRe
     }
 }
 
+/// Returns the include graph of the given files, together with all
+/// (transitively) included files. Include paths are resolved as in
+/// `parse_files`.
+pub fn include_graph(
+    file_paths: &Vec<PathBuf>,
+    libraries: &[PathBuf],
+    remappings: &[Remapping],
+) -> (IncludeGraph, ReportCollection) {
+    let mut reports = ReportCollection::new();
+    let mut file_stack = FileStack::new(file_paths, libraries, remappings, &mut reports);
+    let mut file_library = FileLibrary::new();
+    let mut include_graph = IncludeGraph::new();
+    while let Some(file_path) = FileStack::take_next(&mut file_stack) {
+        let includes = include_graph.entry(file_path.clone()).or_default();
+        let (path_str, file_content) = match open_file(&file_path) {
+            Ok(file) => file,
+            Err(report) => {
+                reports.push(report);
+                continue;
+            }
+        };
+        let file_id = file_library.add_file(path_str, file_content.clone());
+        let program = match parser_logic::parse_file(&file_content, file_id) {
+            Ok((program, _)) => program,
+            Err(mut errors) => {
+                reports.append(&mut errors);
+                continue;
+            }
+        };
+        for include in &program.includes {
+            match FileStack::add_include(&mut file_stack, include) {
+                Ok(path) => {
+                    includes.insert(path);
+                }
+                Err(report) => reports.push(report),
+            }
+        }
+    }
+    (include_graph, reports)
+}
+
 fn parse_file(
     file_path: &PathBuf,
     file_stack: &mut FileStack,
     file_library: &mut FileLibrary,
     compiler_version: &Version,
-) -> Result<(FileID, AST, ReportCollection), Report> {
-    let mut reports = ReportCollection::new();
-
+    cache: Option<&mut ParseCache>,
+) -> Result<(FileID, AST, ReportCollection), ReportCollection> {
     debug!("reading file `{}`", file_path.display());
-    let (path_str, file_content) = open_file(file_path)?;
+    let (_, file_content) = open_file(file_path).map_err(|report| vec![report])?;
+    parse_contents(file_path, file_content, file_stack, file_library, compiler_version, cache)
+}
+
+fn parse_contents(
+    file_path: &Path,
+    file_content: String,
+    file_stack: &mut FileStack,
+    file_library: &mut FileLibrary,
+    compiler_version: &Version,
+    cache: Option<&mut ParseCache>,
+) -> Result<(FileID, AST, ReportCollection), ReportCollection> {
+    let path_str = format!("{}", file_path.display());
     let file_id = file_library.add_file(path_str, file_content.clone());
 
-    debug!("parsing file `{}`", file_path.display());
-    let program = parser_logic::parse_file(&file_content, file_id)?;
+    // Recoverable syntax errors are reported, and the partial AST is analyzed.
+    let (program, mut reports) = match cache {
+        Some(cache) => match cache.get(file_id, &file_content) {
+            Some(ParsedFile { ast, reports }) => (ast, reports),
+            None => {
+                debug!("parsing file `{}`", file_path.display());
+                let (ast, reports) = parser_logic::parse_file(&file_content, file_id)?;
+                let parsed_file = ParsedFile { ast: ast.clone(), reports: reports.clone() };
+                cache.insert(file_id, &file_content, parsed_file);
+                (ast, reports)
+            }
+        },
+        None => {
+            debug!("parsing file `{}`", file_path.display());
+            parser_logic::parse_file(&file_content, file_id)?
+        }
+    };
+    file_library.set_trivia(file_id, program.trivia.clone());
     for include in &program.includes {
         if let Err(report) = FileStack::add_include(file_stack, include) {
             reports.push(report);
@@ -106,6 +327,7 @@ fn parse_file(
         Ok(warnings) => reports.extend(warnings),
         Err(error) => reports.push(error),
     }
+    reports.extend(feature_versions::check_feature_versions(&program, file_id, compiler_version));
     Ok((file_id, program, reports))
 }
 
@@ -137,10 +359,8 @@ fn check_compiler_version(
 ) -> Result<ReportCollection, Report> {
     use errors::{CompilerVersionError, NoCompilerVersionWarning};
     if let Some(required_version) = required_version {
-        if required_version.0 == compiler_version.0
-            && required_version.1 == compiler_version.1
-            && required_version.2 <= compiler_version.2
-        {
+        // Files requiring an earlier minor version are supported by the compiler.
+        if required_version.0 == compiler_version.0 && required_version <= *compiler_version {
             Ok(vec![])
         } else {
             let report = CompilerVersionError::produce_report(CompilerVersionError {
@@ -159,6 +379,28 @@ fn check_compiler_version(
     }
 }
 
+/// Parse the definitions in a single file that has already been added to the
+/// file library. Includes are not resolved. This is used to re-parse files
+/// after they have been edited. Anonymous components instantiating templates
+/// defined in other files are resolved using `templates`.
+pub fn parse_definitions(
+    file_content: &str,
+    file_id: FileID,
+    templates: &TemplateInfo,
+) -> Result<Vec<Definition>, Report> {
+    let (program, mut errors) =
+        parser_logic::parse_file(file_content, file_id).map_err(|mut errors| errors.remove(0))?;
+    if !errors.is_empty() {
+        return Err(errors.remove(0));
+    }
+    let mut definitions = HashMap::from([(file_id, program.definitions)]);
+    let mut reports = syntax_sugar_remover::remove_syntactic_sugar(&mut definitions, templates);
+    if !reports.is_empty() {
+        return Err(reports.remove(0));
+    }
+    Ok(definitions.remove(&file_id).unwrap_or_default())
+}
+
 /// Parse a single (function or template) definition for testing purposes.
 use program_structure::ast::Definition;
 
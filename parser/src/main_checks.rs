@@ -0,0 +1,302 @@
+use std::collections::HashMap;
+
+use program_structure::ast::*;
+use program_structure::file_definition::FileID;
+use program_structure::report::ReportCollection;
+
+use super::definition_checks::check_main_call;
+use super::errors::MainComponentError;
+
+/// Checks the main component against the definition of the main template.
+///
+///   1. The main component must instantiate a defined template, with the
+///      correct number of constant arguments.
+///   2. The public signals listed by the main component must be input signals
+///      of the template. If some (but not all) input signals are listed, the
+///      remaining private input signals are listed in an informational report.
+///   3. The main template should declare both input and output signals.
+///
+/// Input signals of the main template which never occur in a constraint are
+/// reported by the analysis of the main template, since this requires
+/// following the data flow through local variables.
+pub(crate) fn check_main_component(
+    file_id: FileID,
+    main_component: &MainComponent,
+    definitions: &HashMap<FileID, Vec<Definition>>,
+) -> ReportCollection {
+    let (public, call) = main_component;
+    let mut reports = check_main_call(file_id, call, definitions);
+    let Some((meta, template_name, args)) = main_template_call(call) else {
+        return reports;
+    };
+    for arg in args {
+        if let Some(variable_name) = find_variable(arg) {
+            reports.push(
+                MainComponentError::NonConstantArgument {
+                    name: variable_name.to_string(),
+                    template_name: template_name.to_string(),
+                    file_id,
+                    file_location: arg.get_meta().file_location(),
+                }
+                .into_report(),
+            );
+        }
+    }
+    let Some((definition_file_id, arg_location, body)) =
+        definitions.iter().find_map(|(file_id, definitions)| {
+            definitions.iter().find_map(|definition| match definition {
+                Definition::Template { name, arg_location, body, .. } if name == template_name => {
+                    Some((*file_id, arg_location, body))
+                }
+                _ => None,
+            })
+        })
+    else {
+        // Functions instantiated as the main component are reported by the
+        // definition checks.
+        let is_function = definitions.values().flatten().any(|definition| {
+            matches!(definition, Definition::Function { name, .. } if name == template_name)
+        });
+        if !is_function {
+            reports.push(
+                MainComponentError::UndefinedTemplate {
+                    name: template_name.to_string(),
+                    file_id,
+                    file_location: meta.file_location(),
+                }
+                .into_report(),
+            );
+        }
+        return reports;
+    };
+    let mut signals = Vec::new();
+    collect_signals(body, &mut signals);
+    let inputs = signals
+        .iter()
+        .filter(|(_, signal_type, _)| matches!(signal_type, SignalType::Input))
+        .map(|(name, _, meta)| (name, *meta))
+        .collect::<Vec<_>>();
+    let has_outputs =
+        signals.iter().any(|(_, signal_type, _)| matches!(signal_type, SignalType::Output));
+
+    for name in public {
+        if !inputs.iter().any(|(input, _)| *input == name) {
+            reports.push(
+                MainComponentError::UnknownPublicSignal {
+                    name: name.clone(),
+                    template_name: template_name.to_string(),
+                    file_id,
+                    file_location: meta.file_location(),
+                    definition_file_id,
+                    definition_location: arg_location.clone(),
+                }
+                .into_report(),
+            );
+        }
+    }
+    let private = inputs.iter().filter(|(input, _)| !public.contains(input)).collect::<Vec<_>>();
+    if !public.is_empty() && !private.is_empty() {
+        reports.push(
+            MainComponentError::PrivateInputs {
+                names: private.iter().map(|(input, _)| input.to_string()).collect(),
+                template_name: template_name.to_string(),
+                file_id,
+                file_location: meta.file_location(),
+                declarations: private
+                    .iter()
+                    .map(|(_, meta)| (definition_file_id, meta.file_location()))
+                    .collect(),
+            }
+            .into_report(),
+        );
+    }
+    if inputs.is_empty() || !has_outputs {
+        reports.push(
+            MainComponentError::MissingSignals {
+                template_name: template_name.to_string(),
+                has_inputs: !inputs.is_empty(),
+                has_outputs,
+                file_id,
+                file_location: meta.file_location(),
+                definition_file_id,
+                definition_location: arg_location.clone(),
+            }
+            .into_report(),
+        );
+    }
+    reports
+}
+
+/// Returns the template name and arguments of the main component.
+fn main_template_call(call: &Expression) -> Option<(&Meta, &str, &[Expression])> {
+    match call {
+        Expression::ParallelOp { rhe, .. } => main_template_call(rhe),
+        Expression::Call { meta, id, args } => Some((meta, id, args)),
+        _ => None,
+    }
+}
+
+/// Returns the first variable occurring in the given expression.
+fn find_variable(expr: &Expression) -> Option<&str> {
+    use Expression::*;
+    match expr {
+        Variable { name, .. } => Some(name),
+        InfixOp { lhe, rhe, .. } => find_variable(lhe).or_else(|| find_variable(rhe)),
+        PrefixOp { rhe, .. } | ParallelOp { rhe, .. } => find_variable(rhe),
+        InlineSwitchOp { cond, if_true, if_false, .. } => find_variable(cond)
+            .or_else(|| find_variable(if_true))
+            .or_else(|| find_variable(if_false)),
+        Call { args: values, .. }
+        | ArrayInLine { values, .. }
+        | Tuple { values, .. }
+        | AnonymousComponent { params: values, .. } => values.iter().find_map(find_variable),
+        Number(..) => None,
+    }
+}
+
+/// Collects the signals declared by the given statement, in declaration
+/// order.
+fn collect_signals<'a>(stmt: &'a Statement, signals: &mut Vec<(String, SignalType, &'a Meta)>) {
+    use Statement::*;
+    match stmt {
+        Declaration { meta, xtype: VariableType::Signal(signal_type, ..), name, .. } => {
+            signals.push((name.clone(), *signal_type, meta));
+        }
+        IfThenElse { if_case, else_case, .. } => {
+            collect_signals(if_case, signals);
+            if let Some(else_case) = else_case {
+                collect_signals(else_case, signals);
+            }
+        }
+        While { stmt, .. } => collect_signals(stmt, signals),
+        InitializationBlock { initializations: stmts, .. } | Block { stmts, .. } => {
+            for stmt in stmts {
+                collect_signals(stmt, signals);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use program_structure::report::MessageCategory;
+    use program_structure::report_code::ReportCode;
+
+    use crate::parser_logic::parse_file;
+
+    use super::*;
+
+    #[test]
+    fn test_public_signals() {
+        let src = r#"
+            template Multiplier() {
+                signal input a;
+                signal input b;
+                signal output c;
+                c <== a * b;
+            }
+            component main {public [a, b]} = Multiplier();
+        "#;
+        validate_reports(src, &[]);
+
+        let src = r#"
+            template Multiplier() {
+                signal input a;
+                signal input b;
+                signal output c;
+                c <== a * b;
+            }
+            component main = Multiplier();
+        "#;
+        validate_reports(src, &[]);
+
+        let src = r#"
+            template Multiplier() {
+                signal input a;
+                signal input b;
+                signal output c;
+                c <== a * b;
+            }
+            component main {public [a, c]} = Multiplier();
+        "#;
+        validate_reports(src, &[ReportCode::UnknownPublicSignal, ReportCode::PrivateMainInput]);
+
+        // Private inputs are only listed at the informational level, since
+        // most circuits have both public and private inputs.
+        let (program, _) = parse_file(src, 0).ok().unwrap();
+        let definitions = HashMap::from([(0, program.definitions)]);
+        let reports = check_main_component(0, &program.main_component.unwrap(), &definitions);
+        let report = reports.iter().find(|report| report.id() == "CS0044").unwrap();
+        assert_eq!(report.category(), &MessageCategory::Info);
+    }
+
+    #[test]
+    fn test_main_component() {
+        let src = r#"
+            template T(n) {
+                signal input in;
+                signal output out;
+                out <== n * in;
+            }
+            component main = T(n);
+        "#;
+        validate_reports(src, &[ReportCode::MainComponentArgument]);
+
+        let src = r#"
+            template T(n) {
+                signal input in;
+                signal output out;
+                out <== n * in;
+            }
+            component main = T(1, 2);
+        "#;
+        validate_reports(src, &[ReportCode::TemplateWrongNumberOfArguments]);
+
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                out <== in * in;
+            }
+            component main = U();
+        "#;
+        validate_reports(src, &[ReportCode::UndefinedTemplate]);
+
+        // Unconstrained inputs are reported by the analysis of the main template.
+        let src = r#"
+            template T(n) {
+                signal input in[n];
+                signal input unused;
+                signal output out;
+                var s = 0;
+                for (var i = 0; i < n; i++) {
+                    s += in[i];
+                }
+                out <== s;
+            }
+            component main = T(2);
+        "#;
+        validate_reports(src, &[]);
+
+        let src = r#"
+            template T() {
+                signal output out;
+                out <== 1;
+            }
+            component main = T();
+        "#;
+        validate_reports(src, &[ReportCode::MainComponentInterface]);
+    }
+
+    fn validate_reports(src: &str, expected_codes: &[ReportCode]) {
+        let (program, errors) = parse_file(src, 0).ok().unwrap();
+        assert!(errors.is_empty());
+        let main_component = program.main_component.unwrap();
+        let definitions = HashMap::from([(0, program.definitions)]);
+        let reports = check_main_component(0, &main_component, &definitions);
+        let ids = reports.iter().map(|report| report.id()).collect::<Vec<_>>();
+        let expected_ids = expected_codes.iter().map(|code| code.id()).collect::<Vec<_>>();
+        assert_eq!(ids, expected_ids);
+    }
+}
@@ -0,0 +1,96 @@
+use log::debug;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use program_structure::ast::AST;
+use program_structure::content_hash::{content_hash, ContentHash};
+use program_structure::file_definition::FileID;
+use program_structure::report::ReportCollection;
+
+/// The AST of a parsed file, together with the syntax errors that the parser
+/// recovered from.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ParsedFile {
+    pub ast: AST,
+    pub reports: ReportCollection,
+}
+
+/// A cache of parsed files. Files are keyed on the content hash of the file
+/// together with the file ID, since the file ID is recorded in the AST.
+///
+/// Only entries used since the cache was created are returned by
+/// `ParseCache::used_entries`, which means that entries for edited or removed
+/// files are pruned when the cache is written back to disk.
+#[derive(Default)]
+pub struct ParseCache {
+    entries: HashMap<ContentHash, ParsedFile>,
+    used: HashMap<ContentHash, ParsedFile>,
+    hits: usize,
+}
+
+impl ParseCache {
+    #[must_use]
+    pub fn new(entries: HashMap<ContentHash, ParsedFile>) -> ParseCache {
+        ParseCache { entries, used: HashMap::new(), hits: 0 }
+    }
+
+    /// Returns the cached parse result for the given file.
+    pub fn get(&mut self, file_id: FileID, file_content: &str) -> Option<ParsedFile> {
+        let key = file_hash(file_id, file_content);
+        let parsed_file = self.entries.get(&key)?.clone();
+        debug!("using cached AST for file {file_id}");
+        self.hits += 1;
+        self.used.insert(key, parsed_file.clone());
+        Some(parsed_file)
+    }
+
+    /// Add the parse result for the given file to the cache.
+    pub fn insert(&mut self, file_id: FileID, file_content: &str, parsed_file: ParsedFile) {
+        let key = file_hash(file_id, file_content);
+        self.entries.insert(key, parsed_file.clone());
+        self.used.insert(key, parsed_file);
+    }
+
+    /// Returns the number of files returned by `ParseCache::get`.
+    #[must_use]
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Returns all entries used since the cache was created.
+    #[must_use]
+    pub fn used_entries(&self) -> &HashMap<ContentHash, ParsedFile> {
+        &self.used
+    }
+}
+
+fn file_hash(file_id: FileID, file_content: &str) -> ContentHash {
+    let file_id = file_id.to_string();
+    content_hash([file_id.as_bytes(), file_content.as_bytes()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cache() {
+        let src = "template T() { signal input in; }";
+        let (ast, reports) = crate::parser_logic::parse_file(src, 0).ok().unwrap();
+        let mut cache = ParseCache::default();
+        assert!(cache.get(0, src).is_none());
+        cache.insert(0, src, ParsedFile { ast, reports });
+
+        // Entries are keyed on both the file ID and the file contents.
+        assert!(cache.get(1, src).is_none());
+        assert!(cache.get(0, "template T() {}").is_none());
+        let parsed_file = cache.get(0, src).unwrap();
+        assert_eq!(parsed_file.ast.definitions.len(), 1);
+        assert_eq!(cache.hits(), 1);
+        assert_eq!(cache.used_entries().len(), 1);
+
+        // Entries are only marked as used when they are returned by `get`.
+        let cache = ParseCache::new(cache.used_entries().clone());
+        assert!(cache.used_entries().is_empty());
+    }
+}
@@ -1,14 +1,22 @@
 use super::errors::{ParsingError, UnclosedCommentError};
 use super::lang;
-use program_structure::ast::AST;
-use program_structure::report::Report;
-use program_structure::file_definition::FileID;
+use program_structure::ast::{Comment, Trivia, AST};
+use lalrpop_util::lexer::Token;
+use lalrpop_util::ParseError;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use std::collections::BTreeSet;
 
-pub fn preprocess(expr: &str, file_id: FileID) -> Result<String, Report> {
+/// Replaces comments by whitespace (keeping the locations of all tokens
+/// intact). The removed comments and the blank lines of the source are
+/// returned as the trivia of the file.
+pub fn preprocess(expr: &str, file_id: FileID) -> Result<(String, Trivia), Report> {
     let mut pp = String::new();
     let mut state = 0;
     let mut loc = 0;
     let mut block_start = 0;
+    let mut comment_start = 0;
+    let mut comments = Vec::new();
 
     let mut it = expr.chars();
     while let Some(c0) = it.next() {
@@ -19,12 +27,14 @@ pub fn preprocess(expr: &str, file_id: FileID) -> Result<String, Report> {
                 match it.next() {
                     Some('/') => {
                         state = 1;
+                        comment_start = pp.len();
                         pp.push(' ');
                         pp.push(' ');
                     }
                     Some('*') => {
                         block_start = loc;
                         state = 2;
+                        comment_start = pp.len();
                         pp.push(' ');
                         pp.push(' ');
                     }
@@ -40,6 +50,7 @@ pub fn preprocess(expr: &str, file_id: FileID) -> Result<String, Report> {
             }
             (0, _) => pp.push(c0),
             (1, '\n') => {
+                comments.push(comment_start..pp.len());
                 pp.push(c0);
                 state = 0;
             }
@@ -49,6 +60,7 @@ pub fn preprocess(expr: &str, file_id: FileID) -> Result<String, Report> {
                     Some('/') => {
                         pp.push(' ');
                         pp.push(' ');
+                        comments.push(comment_start..pp.len());
                         state = 0;
                     }
                     Some(c) => {
@@ -71,49 +83,282 @@ pub fn preprocess(expr: &str, file_id: FileID) -> Result<String, Report> {
             }
         }
     }
-    Ok(pp)
+    if state == 1 {
+        comments.push(comment_start..pp.len());
+    }
+    let trivia = build_trivia(expr, &pp, comments);
+    Ok((pp, trivia))
 }
 
-pub fn parse_file(src: &str, file_id: FileID) -> Result<AST, Report> {
-    use lalrpop_util::ParseError::*;
-    lang::ParseAstParser::new()
-        .parse(&preprocess(src, file_id)?)
-        .map(|mut ast| {
+/// Builds the trivia of a file from the source, the preprocessed source, and
+/// the locations of the comments in the source.
+fn build_trivia(src: &str, pp: &str, locations: Vec<FileLocation>) -> Trivia {
+    let next_token = |offset: usize| {
+        pp[offset..].find(|c: char| !c.is_whitespace()).map_or(pp.len(), |index| offset + index)
+    };
+    let comments = locations
+        .iter()
+        .map(|location| {
+            let line_start = pp[..location.start].rfind('\n').map_or(0, |index| index + 1);
+            Comment {
+                location: location.clone(),
+                text: src[location.clone()].trim_end().to_string(),
+                is_trailing: !pp[line_start..location.start].trim().is_empty(),
+                attached_to: next_token(location.end),
+            }
+        })
+        .collect();
+
+    // Record the tokens and comments preceded by a blank line.
+    let mut blank_lines = BTreeSet::new();
+    let mut locations = locations.into_iter().peekable();
+    let mut newlines = 0;
+    let mut chars = src.char_indices();
+    while let Some((offset, c)) = chars.next() {
+        if c == '\n' {
+            newlines += 1;
+            continue;
+        }
+        if c.is_whitespace() {
+            continue;
+        }
+        if newlines > 1 {
+            blank_lines.insert(offset);
+        }
+        newlines = 0;
+        if let Some(location) = locations.next_if(|location| location.start == offset) {
+            // Skip the remainder of the comment.
+            while chars.offset() < location.end && chars.next().is_some() {}
+        }
+    }
+    Trivia::new(comments, blank_lines)
+}
+
+/// Parses the given file. Recoverable syntax errors are returned together
+/// with the (partial) AST. If the parser fails to recover, all syntax errors
+/// are returned.
+pub fn parse_file(src: &str, file_id: FileID) -> Result<(AST, ReportCollection), ReportCollection> {
+    let (pp, trivia) = preprocess(src, file_id).map_err(|report| vec![report])?;
+    let src = pp;
+    let mut errors = Vec::new();
+    let result = lang::ParseAstParser::new().parse(&mut errors, &src);
+    let mut reports: ReportCollection =
+        errors.into_iter().map(|error| parsing_error(error.error, &src, file_id)).collect();
+    match result {
+        Ok(mut ast) => {
             // Set file ID for better error reporting.
             for include in &mut ast.includes {
                 include.meta.set_file_id(file_id);
             }
-            ast
-        })
-        .map_err(|parse_error| match parse_error {
-            InvalidToken { location } => ParsingError {
-                file_id,
-                msg: format!("{:?}", parse_error),
-                location: location..location,
-            },
-            UnrecognizedToken { ref token, .. } => ParsingError {
-                file_id,
-                msg: format!("{:?}", parse_error),
-                location: token.0..token.2,
-            },
-            ExtraToken { ref token } => ParsingError {
-                file_id,
-                msg: format!("{:?}", parse_error),
-                location: token.0..token.2,
+            ast.trivia = trivia;
+            Ok((ast, reports))
+        }
+        Err(error) => {
+            reports.push(parsing_error(error, &src, file_id));
+            Err(reports)
+        }
+    }
+}
+
+fn parsing_error(error: ParseError<usize, Token<'_>, &str>, src: &str, file_id: FileID) -> Report {
+    use ParseError::*;
+    let note = match &error {
+        UnrecognizedToken { token: (start, Token(_, ";"), _), expected }
+            if is_comparison_statement(src, *start, expected) =>
+        {
+            Some("The comparison operator `==` does not add a constraint. Did you mean to use the constraint operator `===`?".to_string())
+        }
+        _ => None,
+    };
+    let (msg, location, delimiter) = match error {
+        InvalidToken { location } => (
+            "Invalid token.".to_string(),
+            location..location,
+            find_unbalanced_delimiter(src, location, &[]),
+        ),
+        UnrecognizedToken { token: (start, Token(_, token), end), expected } => (
+            format!("Unexpected `{token}`, expected {}.", format_expected(&expected)),
+            start..end,
+            find_unbalanced_delimiter(src, start, &expected),
+        ),
+        UnrecognizedEOF { location, expected } => (
+            format!("Unexpected end of file, expected {}.", format_expected(&expected)),
+            location..location,
+            find_unbalanced_delimiter(src, location, &expected),
+        ),
+        ExtraToken { token: (start, Token(_, token), end) } => (
+            format!("Unexpected `{token}`."),
+            start..end,
+            find_unbalanced_delimiter(src, start, &[]),
+        ),
+        User { error } => (error.to_string(), 0..0, None),
+    };
+    ParsingError::produce_report(ParsingError { file_id, msg, location, delimiter, note })
+}
+
+/// Returns true if the statement ending at the given offset is an equality
+/// comparison like `a == b;`, which was likely intended to be a constraint.
+fn is_comparison_statement(src: &str, offset: usize, expected: &[String]) -> bool {
+    if !expected.iter().any(|terminal| terminal == "\"===\"") {
+        return false;
+    }
+    let start = src[..offset].rfind([';', '{', '}']).map_or(0, |index| index + 1);
+    let statement = src[start..offset].replace("===", "").replace("<==", "").replace("==>", "");
+    statement.contains("==")
+}
+
+/// The maximum number of expected tokens listed in a syntax error.
+const MAX_EXPECTED_TOKENS: usize = 8;
+
+/// Maps the expected terminals generated by lalrpop back to Circom syntax.
+/// Literal terminals are quoted, and regular expressions are replaced by a
+/// description of the token.
+fn format_expected(expected: &[String]) -> String {
+    let mut tokens = Vec::new();
+    for terminal in expected {
+        let token = if let Some(regex) = terminal.strip_prefix("r#\"") {
+            if regex.contains("a-zA-Z") {
+                "an identifier".to_string()
+            } else if regex.contains("[^") {
+                "a string".to_string()
+            } else {
+                "a number".to_string()
+            }
+        } else {
+            format!("`{}`", unescape_terminal(terminal))
+        };
+        if !tokens.contains(&token) {
+            tokens.push(token);
+        }
+    }
+    if tokens.len() > MAX_EXPECTED_TOKENS {
+        // Long lists are shortened by grouping operators, and by omitting the
+        // remaining tokens if the list is still too long.
+        let (operators, mut other): (Vec<_>, Vec<_>) =
+            tokens.into_iter().partition(|token| is_operator(token));
+        if !operators.is_empty() {
+            other.push("an operator".to_string());
+        }
+        if other.len() > MAX_EXPECTED_TOKENS {
+            let omitted = other.len() - MAX_EXPECTED_TOKENS + 1;
+            other.truncate(MAX_EXPECTED_TOKENS - 1);
+            other.push(format!("one of {omitted} other tokens"));
+        }
+        tokens = other;
+    }
+    match &tokens[..] {
+        [] => "nothing".to_string(),
+        [token] => token.clone(),
+        [first, second] => format!("{first} or {second}"),
+        [init @ .., last] => format!("{}, or {last}", init.join(", ")),
+    }
+}
+
+/// Returns the terminal as written in the source. Terminals are given as
+/// quoted and escaped string literals (e.g. `"\\="` for `\=`).
+fn unescape_terminal(terminal: &str) -> String {
+    let Some(literal) = terminal.strip_prefix('"').and_then(|rest| rest.strip_suffix('"')) else {
+        return terminal.to_string();
+    };
+    let mut result = String::with_capacity(literal.len());
+    let mut chars = literal.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.next()) {
+            ('\\', Some(escaped)) => result.push(escaped),
+            (c, next) => {
+                result.push(c);
+                result.extend(next);
+            }
+        }
+    }
+    result
+}
+
+/// Returns true if the formatted token is an operator (rather than a keyword,
+/// a delimiter, or a class of tokens like identifiers).
+fn is_operator(token: &str) -> bool {
+    let Some(token) = token.strip_prefix('`').and_then(|token| token.strip_suffix('`')) else {
+        return false;
+    };
+    !matches!(token, "(" | ")" | "[" | "]" | "{" | "}" | "," | ";")
+        && !token.chars().any(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Returns the location of the delimiter likely to be unbalanced, given a
+/// syntax error at the given offset. This is either
+///
+///   1. the first closing delimiter that does not match the corresponding
+///      opening delimiter,
+///   2. the innermost open delimiter if the error occurs at the end of the
+///      file or on a mismatched closing delimiter, or
+///   3. the innermost open parenthesis or bracket if the parser expected the
+///      corresponding closing delimiter.
+fn find_unbalanced_delimiter(
+    src: &str,
+    offset: usize,
+    expected: &[String],
+) -> Option<FileLocation> {
+    let mut open_delimiters = Vec::new();
+    let mut in_string = false;
+    for (index, c) in src.char_indices() {
+        if index >= offset {
+            break;
+        }
+        match c {
+            '"' => in_string = !in_string,
+            _ if in_string => {}
+            '(' | '[' | '{' => open_delimiters.push((c, index)),
+            ')' | ']' | '}' => match open_delimiters.pop() {
+                Some((open, _)) if open == matching_delimiter(c) => {}
+                _ => return Some(index..index + 1),
             },
-            _ => ParsingError { file_id, msg: format!("{:?}", parse_error), location: 0..0 },
-        })
-        .map_err(ParsingError::produce_report)
+            _ => {}
+        }
+    }
+    let next = src.get(offset..).and_then(|rest| rest.trim_start().chars().next());
+    match (next, open_delimiters.last()) {
+        (None, Some((_, index))) => Some(*index..*index + 1),
+        (Some(c @ (')' | ']' | '}')), Some((open, index))) if *open != matching_delimiter(c) => {
+            Some(*index..*index + 1)
+        }
+        (Some(')' | ']' | '}'), None) => Some(offset..offset + 1),
+        (_, Some((open @ ('(' | '['), index))) => {
+            let close = if *open == '(' { ")" } else { "]" };
+            let is_expected = expected.iter().any(|terminal| unescape_terminal(terminal) == close);
+            is_expected.then(|| *index..*index + 1)
+        }
+        _ => None,
+    }
 }
 
+fn matching_delimiter(c: char) -> char {
+    match c {
+        ')' => '(',
+        ']' => '[',
+        '}' => '{',
+        _ => c,
+    }
+}
+
+/// Parses the given string. Returns `None` if the string contains syntax
+/// errors.
 pub fn parse_string(src: &str) -> Option<AST> {
-    let src = preprocess(src, 0).ok()?;
-    lang::ParseAstParser::new().parse(&src).ok()
+    let (src, trivia) = preprocess(src, 0).ok()?;
+    let mut errors = Vec::new();
+    let mut ast = lang::ParseAstParser::new().parse(&mut errors, &src).ok()?;
+    if errors.is_empty() {
+        ast.trivia = trivia;
+        Some(ast)
+    } else {
+        None
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::parse_string;
+    use program_structure::report::MessageCategory;
+
+    use super::{format_expected, parse_file, parse_string};
 
     #[test]
     fn test_parse_string() {
@@ -148,4 +393,139 @@ mod tests {
         "#;
         let _ = parse_string(template);
     }
+
+    #[test]
+    fn test_parse_file_trivia() {
+        let src = r#"
+            // The first comment.
+            /* The second comment. */
+            template T() {
+                signal input in; // A trailing comment.
+
+                signal output out;
+                out <== in;
+            }
+        "#;
+        let (ast, _) = parse_file(src, 0).ok().unwrap();
+        let comments = ast.trivia.comments();
+        assert_eq!(comments.len(), 3);
+        assert_eq!(comments[0].text, "// The first comment.");
+        assert_eq!(comments[1].text, "/* The second comment. */");
+        assert!(!comments[0].is_trailing && !comments[1].is_trailing && comments[2].is_trailing);
+
+        // The first two comments are attached to the template.
+        let start = src.find("template").unwrap();
+        assert_eq!(ast.trivia.leading_comments(start), &comments[..2]);
+        let end = src.rfind('}').unwrap() + 1;
+        assert_eq!(ast.trivia.comments_in(&(start..end)), &comments[2..]);
+
+        // Blank lines are retained.
+        assert!(ast.trivia.has_blank_line_before(src.find("signal output").unwrap()));
+        assert!(!ast.trivia.has_blank_line_before(src.find("out <==").unwrap()));
+    }
+
+    #[test]
+    fn test_parse_file_with_errors() {
+        let src = r#"
+            template A() {
+                signal input in;
+                signal output out;
+                out <== in +;
+                out === in * ;
+            }
+
+            template B() {
+                signal input in;
+                signal output out;
+                out <== in +
+            }
+        "#;
+        let (ast, reports) = parse_file(src, 0).ok().unwrap();
+        assert_eq!(ast.definitions.len(), 2);
+        assert_eq!(reports.len(), 3);
+
+        // Unbalanced delimiters cannot be recovered from.
+        let src = r#"
+            template A() {
+                signal input in;
+                signal output out;
+                out <== in +;
+        "#;
+        assert!(parse_file(src, 0).is_err());
+    }
+
+    #[test]
+    fn test_format_expected() {
+        let terminals = |terminals: &[&str]| {
+            terminals.iter().map(|terminal| format!("{terminal:?}")).collect::<Vec<_>>()
+        };
+        // Terminals are unescaped.
+        assert_eq!(format_expected(&terminals(&["\\", "\\="])), "`\\` or `\\=`");
+        assert_eq!(format_expected(&terminals(&["\""])), "`\"`");
+
+        // Operators are grouped in long lists.
+        let expected = terminals(&["+", "-", "*", "/", "\\", "%", "**", "<<", ">>", ";"]);
+        assert_eq!(format_expected(&expected), "`;` or an operator");
+
+        // Long lists are capped.
+        let expected = terminals(&["a", "b", "c", "d", "e", "f", "g", "h", "i", "j"]);
+        assert_eq!(
+            format_expected(&expected),
+            "`a`, `b`, `c`, `d`, `e`, `f`, `g`, or one of 3 other tokens"
+        );
+    }
+
+    #[test]
+    fn test_parse_error_messages() {
+        let src = r#"
+            template A() {
+                signal output out;
+                out <== (1 + 2;
+            }
+        "#;
+        let reports = parse_file(src, 0).ok().unwrap().1;
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].message(), "Unexpected `;`, expected `)` or `,`.");
+        assert_eq!(reports[0].secondary().len(), 1);
+        let offset = src.find("(1").unwrap();
+        assert_eq!(reports[0].secondary()[0].range, offset..offset + 1);
+
+        let src = r#"
+            template A() {
+                signal output out;
+                out <== 1 + ;
+            }
+        "#;
+        let reports = parse_file(src, 0).ok().unwrap().1;
+        assert_eq!(reports.len(), 1);
+        assert!(reports[0].message().starts_with("Unexpected `;`, expected `!`, `(`"));
+        assert!(reports[0].message().ends_with(", a number, or an identifier."));
+        assert!(reports[0].secondary().is_empty());
+
+        let src = r#"
+            template A() {
+                signal output out;
+                out <== 1;
+        "#;
+        let reports = parse_file(src, 0).err().unwrap();
+        assert_eq!(reports.len(), 1);
+        assert!(matches!(reports[0].category(), MessageCategory::Error));
+        assert!(reports[0].message().starts_with("Unexpected end of file"));
+        let offset = src.find('{').unwrap();
+        assert_eq!(reports[0].secondary()[0].range, offset..offset + 1);
+
+        let src = r#"
+            template A() {
+                signal input in;
+                signal output out;
+                out <== in;
+                out == in;
+            }
+        "#;
+        let reports = parse_file(src, 0).ok().unwrap().1;
+        assert_eq!(reports.len(), 1);
+        assert!(
+            reports[0].notes()[0].contains("Did you mean to use the constraint operator `===`?")
+        );
+    }
 }
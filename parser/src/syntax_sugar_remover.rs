@@ -0,0 +1,552 @@
+use num_bigint::BigInt;
+use std::collections::HashMap;
+use std::mem;
+
+use program_structure::ast::*;
+use program_structure::expression_builders::*;
+use program_structure::file_definition::FileID;
+use program_structure::report::ReportCollection;
+use program_structure::statement_builders::*;
+use program_structure::template_data::TemplateInfo;
+
+use super::errors::SyntaxSugarError;
+
+/// The input and output signals declared by a template, in declaration order.
+#[derive(Clone, Default)]
+struct TemplateInterface {
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+}
+
+impl TemplateInterface {
+    fn new(body: &Statement) -> TemplateInterface {
+        let mut interface = TemplateInterface::default();
+        interface.visit_statement(body);
+        interface
+    }
+
+    fn visit_statement(&mut self, stmt: &Statement) {
+        use Statement::*;
+        match stmt {
+            Block { stmts, .. } | InitializationBlock { initializations: stmts, .. } => {
+                for stmt in stmts {
+                    self.visit_statement(stmt);
+                }
+            }
+            IfThenElse { if_case, else_case, .. } => {
+                self.visit_statement(if_case);
+                if let Some(else_case) = else_case {
+                    self.visit_statement(else_case);
+                }
+            }
+            While { stmt, .. } => self.visit_statement(stmt),
+            Declaration { xtype: VariableType::Signal(signal_type, _, _), name, .. } => {
+                let signals = match signal_type {
+                    SignalType::Input => &mut self.inputs,
+                    SignalType::Output => &mut self.outputs,
+                    SignalType::Intermediate => return,
+                };
+                if !signals.contains(name) {
+                    signals.push(name.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+type TemplateInterfaces = HashMap<String, TemplateInterface>;
+
+/// Rewrites anonymous components (like `out <== C(n)(a, b)`) as explicit
+/// component instantiations, and tuple assignments (like `(x, y) <== C(n)(a)`)
+/// as individual assignments. The inputs of an anonymous component are
+/// assigned in the order that they are declared by the template, and the
+/// outputs are returned in declaration order.
+///
+/// The template interfaces are taken from the given definitions, and from
+/// `templates` for templates that are not defined by the given definitions.
+pub(crate) fn remove_syntactic_sugar(
+    definitions: &mut HashMap<FileID, Vec<Definition>>,
+    templates: &TemplateInfo,
+) -> ReportCollection {
+    let mut interfaces = templates
+        .iter()
+        .map(|(name, template)| (name.clone(), TemplateInterface::new(template.get_body())))
+        .collect::<TemplateInterfaces>();
+    for definition in definitions.values().flatten() {
+        if let Definition::Template { name, body, .. } = definition {
+            interfaces.insert(name.clone(), TemplateInterface::new(body));
+        }
+    }
+    let mut reports = ReportCollection::new();
+    for (file_id, definitions) in definitions.iter_mut() {
+        for definition in definitions {
+            let body = match definition {
+                Definition::Template { body, .. } | Definition::Function { body, .. } => body,
+            };
+            let mut remover = SyntaxSugarRemover::new(*file_id, &interfaces);
+            let stmt = mem::replace(body, build_block(Meta::new(0, 0), Vec::new()));
+            *body = remover.remove_from_nested_statement(stmt);
+            reports.extend(remover.reports);
+        }
+    }
+    reports
+}
+
+struct SyntaxSugarRemover<'a> {
+    file_id: FileID,
+    interfaces: &'a TemplateInterfaces,
+    components: usize,
+    reports: ReportCollection,
+}
+
+impl<'a> SyntaxSugarRemover<'a> {
+    fn new(file_id: FileID, interfaces: &'a TemplateInterfaces) -> SyntaxSugarRemover<'a> {
+        SyntaxSugarRemover { file_id, interfaces, components: 0, reports: ReportCollection::new() }
+    }
+
+    fn remove_from_statements(&mut self, stmts: Vec<Statement>) -> Vec<Statement> {
+        stmts.into_iter().flat_map(|stmt| self.remove_from_statement(stmt)).collect()
+    }
+
+    /// Returns a single statement replacing the given statement. This is used
+    /// for statements that are not part of a block (like the body of a loop).
+    fn remove_from_nested_statement(&mut self, stmt: Statement) -> Statement {
+        let meta = stmt.get_meta().clone();
+        let mut stmts = self.remove_from_statement(stmt);
+        if stmts.len() == 1 {
+            stmts.remove(0)
+        } else {
+            build_block(meta, stmts)
+        }
+    }
+
+    /// Returns the statements replacing the given statement. Component
+    /// instantiations are inserted before the rewritten statement.
+    fn remove_from_statement(&mut self, stmt: Statement) -> Vec<Statement> {
+        use Statement::*;
+        let mut stmts = Vec::new();
+        let stmt = match stmt {
+            IfThenElse { meta, cond, if_case, else_case } => IfThenElse {
+                meta,
+                cond: self.remove_from_expression(cond, &mut stmts),
+                if_case: Box::new(self.remove_from_nested_statement(*if_case)),
+                else_case: else_case
+                    .map(|else_case| Box::new(self.remove_from_nested_statement(*else_case))),
+            },
+            While { meta, cond, stmt } => While {
+                meta,
+                cond: self.remove_from_expression(cond, &mut stmts),
+                stmt: Box::new(self.remove_from_nested_statement(*stmt)),
+            },
+            Return { meta, value } => {
+                Return { meta, value: self.remove_from_expression(value, &mut stmts) }
+            }
+            InitializationBlock { meta, xtype, initializations } => InitializationBlock {
+                meta,
+                xtype,
+                initializations: self.remove_from_statements(initializations),
+            },
+            Declaration { meta, xtype, name, dimensions, is_constant } => Declaration {
+                meta,
+                xtype,
+                name,
+                dimensions: self.remove_from_expressions(dimensions, &mut stmts),
+                is_constant,
+            },
+            Substitution { meta, var, access, op, rhe } => Substitution {
+                meta,
+                var,
+                access: self.remove_from_accesses(access, &mut stmts),
+                op,
+                rhe: self.remove_from_expression(rhe, &mut stmts),
+            },
+            MultiSubstitution { meta, lhe, op, rhe } => {
+                self.remove_tuple_assignment(meta, lhe, op, rhe, &mut stmts);
+                return stmts;
+            }
+            ConstraintEquality { meta, lhe, rhe } => ConstraintEquality {
+                meta,
+                lhe: self.remove_from_expression(lhe, &mut stmts),
+                rhe: self.remove_from_expression(rhe, &mut stmts),
+            },
+            LogCall { meta, args } => LogCall {
+                meta,
+                args: args
+                    .into_iter()
+                    .map(|arg| match arg {
+                        LogArgument::LogExp(expr) => {
+                            LogArgument::LogExp(self.remove_from_expression(expr, &mut stmts))
+                        }
+                        LogArgument::LogStr(value) => LogArgument::LogStr(value),
+                    })
+                    .collect(),
+            },
+            Block { meta, stmts: block } => {
+                Block { meta, stmts: self.remove_from_statements(block) }
+            }
+            Assert { meta, arg } => {
+                Assert { meta, arg: self.remove_from_expression(arg, &mut stmts) }
+            }
+        };
+        stmts.push(stmt);
+        stmts
+    }
+
+    /// Rewrites a tuple assignment as a sequence of individual assignments.
+    /// Targets given by `_` are ignored.
+    fn remove_tuple_assignment(
+        &mut self,
+        meta: Meta,
+        lhe: Expression,
+        op: AssignOp,
+        rhe: Expression,
+        stmts: &mut Vec<Statement>,
+    ) {
+        use Expression::*;
+        let Tuple { values: targets, .. } = lhe else {
+            self.push_error(SyntaxSugarError::InvalidTupleAssignment {
+                file_id: self.file_id,
+                file_location: meta.file_location(),
+            });
+            return;
+        };
+        let (rhe, is_parallel) = match rhe {
+            ParallelOp { rhe, .. } if rhe.is_anonymous_component() => (*rhe, true),
+            rhe => (rhe, false),
+        };
+        let values = match rhe {
+            AnonymousComponent { meta: anon_meta, id, params, signals } => {
+                let Some((name, outputs)) =
+                    self.instantiate(&anon_meta, &id, params, signals, is_parallel, stmts)
+                else {
+                    return;
+                };
+                if outputs.len() != targets.len() {
+                    self.push_error(SyntaxSugarError::WrongNumberOfOutputs {
+                        name: id,
+                        expected: targets.len(),
+                        found: outputs.len(),
+                        file_id: self.file_id,
+                        file_location: anon_meta.file_location(),
+                    });
+                    return;
+                }
+                outputs
+                    .into_iter()
+                    .map(|output| {
+                        let access = vec![build_component_access(output)];
+                        build_variable(anon_meta.clone(), name.clone(), access)
+                    })
+                    .collect()
+            }
+            Tuple { values, .. } if values.len() == targets.len() => {
+                self.remove_from_expressions(values, stmts)
+            }
+            _ => {
+                self.push_error(SyntaxSugarError::InvalidTupleAssignment {
+                    file_id: self.file_id,
+                    file_location: meta.file_location(),
+                });
+                return;
+            }
+        };
+        for (target, value) in targets.into_iter().zip(values) {
+            match target {
+                Variable { name, .. } if name == "_" => {}
+                Variable { name, access, .. } => {
+                    let access = self.remove_from_accesses(access, stmts);
+                    stmts.push(build_substitution(meta.clone(), name, access, op, value));
+                }
+                target => {
+                    self.push_error(SyntaxSugarError::InvalidTupleAssignment {
+                        file_id: self.file_id,
+                        file_location: target.get_meta().file_location(),
+                    });
+                }
+            }
+        }
+    }
+
+    fn remove_from_expressions(
+        &mut self,
+        exprs: Vec<Expression>,
+        stmts: &mut Vec<Statement>,
+    ) -> Vec<Expression> {
+        exprs.into_iter().map(|expr| self.remove_from_expression(expr, stmts)).collect()
+    }
+
+    fn remove_from_accesses(
+        &mut self,
+        access: Vec<Access>,
+        stmts: &mut Vec<Statement>,
+    ) -> Vec<Access> {
+        access
+            .into_iter()
+            .map(|access| match access {
+                Access::ArrayAccess(index) => {
+                    Access::ArrayAccess(self.remove_from_expression(index, stmts))
+                }
+                Access::ComponentAccess(name) => Access::ComponentAccess(name),
+            })
+            .collect()
+    }
+
+    fn remove_from_expression(
+        &mut self,
+        expr: Expression,
+        stmts: &mut Vec<Statement>,
+    ) -> Expression {
+        use Expression::*;
+        match expr {
+            InfixOp { meta, lhe, infix_op, rhe } => InfixOp {
+                meta,
+                lhe: Box::new(self.remove_from_expression(*lhe, stmts)),
+                infix_op,
+                rhe: Box::new(self.remove_from_expression(*rhe, stmts)),
+            },
+            PrefixOp { meta, prefix_op, rhe } => PrefixOp {
+                meta,
+                prefix_op,
+                rhe: Box::new(self.remove_from_expression(*rhe, stmts)),
+            },
+            InlineSwitchOp { meta, cond, if_true, if_false } => InlineSwitchOp {
+                meta,
+                cond: Box::new(self.remove_from_expression(*cond, stmts)),
+                if_true: Box::new(self.remove_from_expression(*if_true, stmts)),
+                if_false: Box::new(self.remove_from_expression(*if_false, stmts)),
+            },
+            ParallelOp { meta, rhe } => match *rhe {
+                AnonymousComponent { meta, id, params, signals } => {
+                    self.remove_anonymous_component(meta, id, params, signals, true, stmts)
+                }
+                rhe => ParallelOp { meta, rhe: Box::new(self.remove_from_expression(rhe, stmts)) },
+            },
+            Variable { meta, name, .. } if name == "_" => self.invalid_tuple(meta),
+            Variable { meta, name, access } => {
+                Variable { meta, name, access: self.remove_from_accesses(access, stmts) }
+            }
+            Number(meta, value) => Number(meta, value),
+            Call { meta, id, args } => {
+                Call { meta, id, args: self.remove_from_expressions(args, stmts) }
+            }
+            ArrayInLine { meta, values } => {
+                ArrayInLine { meta, values: self.remove_from_expressions(values, stmts) }
+            }
+            AnonymousComponent { meta, id, params, signals } => {
+                self.remove_anonymous_component(meta, id, params, signals, false, stmts)
+            }
+            Tuple { meta, .. } => self.invalid_tuple(meta),
+        }
+    }
+
+    /// Replaces the anonymous component by a component instantiation, and
+    /// returns an access to the single output signal of the component.
+    fn remove_anonymous_component(
+        &mut self,
+        meta: Meta,
+        id: String,
+        params: Vec<Expression>,
+        signals: Vec<Expression>,
+        is_parallel: bool,
+        stmts: &mut Vec<Statement>,
+    ) -> Expression {
+        match self.instantiate(&meta, &id, params, signals, is_parallel, stmts) {
+            Some((name, outputs)) if outputs.len() == 1 => {
+                let access = vec![build_component_access(outputs[0].clone())];
+                build_variable(meta, name, access)
+            }
+            Some((_, outputs)) => {
+                self.push_error(SyntaxSugarError::WrongNumberOfOutputs {
+                    name: id,
+                    expected: 1,
+                    found: outputs.len(),
+                    file_id: self.file_id,
+                    file_location: meta.file_location(),
+                });
+                placeholder(meta)
+            }
+            None => placeholder(meta),
+        }
+    }
+
+    /// Adds a declaration and instantiation of a new component, and assigns
+    /// the given signals to the inputs of the component. Returns the name of
+    /// the new component together with its outputs.
+    fn instantiate(
+        &mut self,
+        meta: &Meta,
+        id: &str,
+        params: Vec<Expression>,
+        signals: Vec<Expression>,
+        is_parallel: bool,
+        stmts: &mut Vec<Statement>,
+    ) -> Option<(String, Vec<String>)> {
+        let params = self.remove_from_expressions(params, stmts);
+        let signals = self.remove_from_expressions(signals, stmts);
+        let Some(interface) = self.interfaces.get(id) else {
+            self.push_error(SyntaxSugarError::UndefinedTemplate {
+                name: id.to_string(),
+                file_id: self.file_id,
+                file_location: meta.file_location(),
+            });
+            return None;
+        };
+        if interface.inputs.len() != signals.len() {
+            self.push_error(SyntaxSugarError::WrongNumberOfInputs {
+                name: id.to_string(),
+                expected: interface.inputs.len(),
+                found: signals.len(),
+                file_id: self.file_id,
+                file_location: meta.file_location(),
+            });
+            return None;
+        }
+        let name = format!("anon_{id}_{}", self.components);
+        self.components += 1;
+
+        let mut call = build_call(meta.clone(), id.to_string(), params);
+        if is_parallel {
+            call = build_parallel_op(meta.clone(), call);
+        }
+        stmts.push(build_declaration(
+            meta.clone(),
+            VariableType::Component,
+            name.clone(),
+            Vec::new(),
+        ));
+        stmts.push(build_substitution(
+            meta.clone(),
+            name.clone(),
+            Vec::new(),
+            AssignOp::AssignVar,
+            call,
+        ));
+        for (input, signal) in interface.inputs.iter().zip(signals) {
+            stmts.push(build_substitution(
+                meta.clone(),
+                name.clone(),
+                vec![build_component_access(input.clone())],
+                AssignOp::AssignConstraintSignal,
+                signal,
+            ));
+        }
+        Some((name, interface.outputs.clone()))
+    }
+
+    fn invalid_tuple(&mut self, meta: Meta) -> Expression {
+        self.push_error(SyntaxSugarError::InvalidTuple {
+            file_id: self.file_id,
+            file_location: meta.file_location(),
+        });
+        placeholder(meta)
+    }
+
+    fn push_error(&mut self, error: SyntaxSugarError) {
+        self.reports.push(error.into_report());
+    }
+}
+
+/// Invalid expressions are replaced by `0` after an error is generated.
+fn placeholder(meta: Meta) -> Expression {
+    build_number(meta, BigInt::from(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser_logic::parse_file;
+
+    use super::*;
+
+    #[test]
+    fn test_anonymous_components() {
+        let src = r#"
+            template A(n) {
+                signal input a;
+                signal input b;
+                signal output c;
+                c <== a * b;
+            }
+
+            template B() {
+                signal input in;
+                signal output out1;
+                signal output out2;
+                out1 <== in;
+                out2 <== in;
+            }
+
+            template T() {
+                signal input in[2];
+                signal output out;
+                signal x;
+                signal y;
+
+                out <== A(1)(in[0], A(2)(in[1], in[1]));
+                (x, _) <== B()(in[0]);
+                (x, y) <== (in[0], in[1]);
+            }
+        "#;
+        let (definitions, reports) = remove_from_source(src);
+        assert!(reports.is_empty());
+        let Definition::Template { body, .. } = &definitions[2] else {
+            panic!("expected template definition");
+        };
+        let stmts = match body {
+            Statement::Block { stmts, .. } => stmts.iter().map(|stmt| stmt.to_string()),
+            _ => panic!("expected block statement"),
+        };
+        let stmts = stmts.filter(|stmt| !stmt.is_empty()).collect::<Vec<_>>();
+        assert_eq!(
+            stmts,
+            vec![
+                "component anon_A_0",
+                "anon_A_0 = A(2)",
+                "anon_A_0.a <== in[1]",
+                "anon_A_0.b <== in[1]",
+                "component anon_A_1",
+                "anon_A_1 = A(1)",
+                "anon_A_1.a <== in[0]",
+                "anon_A_1.b <== anon_A_0.c",
+                "out <== anon_A_1.c",
+                "component anon_B_2",
+                "anon_B_2 = B()",
+                "anon_B_2.in <== in[0]",
+                "x <== anon_B_2.out1",
+                "x <== in[0]",
+                "y <== in[1]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_anonymous_component_errors() {
+        let src = r#"
+            template B() {
+                signal input in;
+                signal output out1;
+                signal output out2;
+            }
+
+            template T() {
+                signal input in;
+                signal output out;
+                signal x;
+
+                out <== B()(in);
+                out <== B()(in, in);
+                out <== C()(in);
+                (out, x) <== in;
+                out <== (in, in);
+            }
+        "#;
+        let (_, reports) = remove_from_source(src);
+        assert_eq!(reports.len(), 5);
+    }
+
+    fn remove_from_source(src: &str) -> (Vec<Definition>, ReportCollection) {
+        let mut definitions = HashMap::from([(0, parse_file(src, 0).ok().unwrap().0.definitions)]);
+        let reports = remove_syntactic_sugar(&mut definitions, &TemplateInfo::new());
+        (definitions.remove(&0).unwrap(), reports)
+    }
+}
@@ -0,0 +1,215 @@
+use anyhow::Context;
+use log::debug;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use parser::{ParseCache, ParsedFile};
+use program_structure::report::ReportCollection;
+
+/// The cache is invalidated whenever the analysis version changes.
+const ANALYSIS_VERSION: &str = env!("CARGO_PKG_VERSION");
+const CACHE_FILE_NAME: &str = "analysis-cache.json";
+
+/// Cached inputs are identified by their content hash.
+pub use program_structure::content_hash::{content_hash, ContentHash};
+
+/// The cached analysis result for a single function or template.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    /// The reports generated for the definition.
+    pub reports: ReportCollection,
+    /// The functions and templates (transitively) called by the definition,
+    /// together with the content hash of each callee at the time of analysis.
+    pub callees: Vec<(String, ContentHash)>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct CacheFile {
+    version: String,
+    entries: HashMap<String, CacheEntry>,
+    #[serde(default)]
+    parsed_files: HashMap<String, ParsedFile>,
+}
+
+/// An on-disk cache of parsed files and per-definition analysis results.
+/// Analysis results are keyed on the content hash of the analyzed definition
+/// (which covers the definition source, location, and the curve used),
+/// together with the settings passed to `AnalysisCache::load`. Entries are
+/// only valid if the content hash of each (transitive) callee is unchanged.
+///
+/// Only entries used during the current run are written back to disk, which
+/// means that entries for removed or edited definitions are pruned
+/// automatically.
+pub struct AnalysisCache {
+    cache_file: PathBuf,
+    settings: ContentHash,
+    entries: HashMap<ContentHash, CacheEntry>,
+    used: HashMap<ContentHash, CacheEntry>,
+    parsed_files: ParseCache,
+    hits: usize,
+}
+
+impl AnalysisCache {
+    /// Load the cache from the given directory. If the cache does not exist,
+    /// or was generated by a different version of Circomspect, an empty cache
+    /// is returned. The given settings should describe any configuration that
+    /// affects the analysis results (like the compiler version or the
+    /// complexity thresholds). Cached results are only reused if the settings
+    /// are unchanged.
+    #[must_use]
+    pub fn load(cache_dir: &Path, settings: &[&str]) -> AnalysisCache {
+        let cache_file = cache_dir.join(CACHE_FILE_NAME);
+        let cache = match read_cache_file(&cache_file) {
+            Ok(cache) if cache.version == ANALYSIS_VERSION => cache,
+            Ok(_) => {
+                debug!("ignoring cache generated by a different analysis version");
+                CacheFile::default()
+            }
+            Err(_) => {
+                debug!("failed to read cache file `{}`", cache_file.display());
+                CacheFile::default()
+            }
+        };
+        let entries = parse_keys(cache.entries);
+        let parsed_files = parse_keys(cache.parsed_files);
+        debug!("loaded {} cache entries and {} parsed files", entries.len(), parsed_files.len());
+        AnalysisCache {
+            cache_file,
+            settings: content_hash(settings.iter().map(|setting| setting.as_bytes())),
+            entries,
+            used: HashMap::new(),
+            parsed_files: ParseCache::new(parsed_files),
+            hits: 0,
+        }
+    }
+
+    /// Returns the cache of parsed files.
+    #[must_use]
+    pub fn parsed_files(&self) -> &ParseCache {
+        &self.parsed_files
+    }
+
+    pub fn parsed_files_mut(&mut self) -> &mut ParseCache {
+        &mut self.parsed_files
+    }
+
+    /// Returns the cached entry for the definition with the given content
+    /// hash. The function `current_hash` should return the current content
+    /// hash of the given callee, and is used to validate the entry.
+    pub fn get(
+        &mut self,
+        key: ContentHash,
+        current_hash: impl Fn(&str) -> Option<ContentHash>,
+    ) -> Option<&CacheEntry> {
+        let key = self.entry_key(key);
+        let entry = self.entries.get(&key)?;
+        let is_valid =
+            entry.callees.iter().all(|(callee, hash)| current_hash(callee) == Some(*hash));
+        if !is_valid {
+            return None;
+        }
+        self.hits += 1;
+        self.used.insert(key, entry.clone());
+        self.used.get(&key)
+    }
+
+    /// Returns the number of valid entries returned by `AnalysisCache::get`.
+    #[must_use]
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Add a new entry to the cache.
+    pub fn insert(&mut self, key: ContentHash, entry: CacheEntry) {
+        let key = self.entry_key(key);
+        self.entries.insert(key, entry.clone());
+        self.used.insert(key, entry);
+    }
+
+    /// Write all entries used during this run back to disk.
+    pub fn save(&self) -> anyhow::Result<()> {
+        let cache = CacheFile {
+            version: ANALYSIS_VERSION.to_string(),
+            entries: self
+                .used
+                .iter()
+                .map(|(key, entry)| (key.to_string(), entry.clone()))
+                .collect(),
+            parsed_files: self
+                .parsed_files
+                .used_entries()
+                .iter()
+                .map(|(key, parsed_file)| (key.to_string(), parsed_file.clone()))
+                .collect(),
+        };
+        if let Some(cache_dir) = self.cache_file.parent() {
+            fs::create_dir_all(cache_dir)?;
+        }
+        let json = serde_json::to_string(&cache)?;
+        fs::write(&self.cache_file, json)
+            .with_context(|| format!("could not write to {}", self.cache_file.display()))?;
+        debug!("wrote {} cache entries", self.used.len());
+        Ok(())
+    }
+
+    /// Returns the key of the entry for the given content hash, which also
+    /// covers the settings used for the analysis.
+    fn entry_key(&self, key: ContentHash) -> ContentHash {
+        content_hash([key.to_le_bytes().as_slice(), self.settings.to_le_bytes().as_slice()])
+    }
+}
+
+fn parse_keys<T>(entries: HashMap<String, T>) -> HashMap<ContentHash, T> {
+    entries.into_iter().filter_map(|(key, entry)| Some((key.parse().ok()?, entry))).collect()
+}
+
+fn read_cache_file(cache_file: &Path) -> anyhow::Result<CacheFile> {
+    let json = fs::read_to_string(cache_file)?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use program_structure::ast::{Meta, AST};
+    use program_structure::report::Report;
+    use program_structure::report_code::ReportCode;
+
+    use super::*;
+
+    #[test]
+    fn test_cache_round_trip() {
+        let cache_dir =
+            std::env::temp_dir().join(format!("circomspect-round-trip-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&cache_dir);
+
+        let src = "pragma circom 2.0.0;";
+        let ast = AST::new(Meta::new(0, src.len()), Some((2, 0, 0)), false, vec![], vec![], None);
+        let reports = ReportCollection::new();
+        let report = Report::warning("message".to_string(), ReportCode::UnusedVariableValue);
+        let entry = CacheEntry { reports: vec![report], callees: vec![("f".to_string(), 1)] };
+        let mut cache = AnalysisCache::load(&cache_dir, &["settings"]);
+        cache.insert(1, entry);
+        cache.parsed_files_mut().insert(0, src, ParsedFile { ast, reports });
+        cache.save().ok().unwrap();
+
+        // Entries are only valid if the hash of each callee is unchanged.
+        let mut cache = AnalysisCache::load(&cache_dir, &["settings"]);
+        assert!(cache.get(1, |_| Some(2)).is_none());
+        let entry = cache.get(1, |_| Some(1)).unwrap();
+        assert_eq!(entry.reports.len(), 1);
+        assert_eq!(entry.reports[0].id(), "CS0006");
+        assert_eq!(cache.hits(), 1);
+        let parsed_file = cache.parsed_files_mut().get(0, src).unwrap();
+        assert_eq!(parsed_file.ast.compiler_version, Some((2, 0, 0)));
+
+        // Entries are only valid if the settings are unchanged.
+        let mut cache = AnalysisCache::load(&cache_dir, &["other settings"]);
+        assert!(cache.get(1, |_| Some(1)).is_none());
+        // Parsed files do not depend on the settings.
+        assert!(cache.parsed_files_mut().get(0, src).is_some());
+
+        let _ = std::fs::remove_dir_all(&cache_dir);
+    }
+}
@@ -0,0 +1,298 @@
+use std::collections::{BTreeMap, HashSet};
+use std::time::{Duration, Instant};
+
+use program_structure::cfg::Cfg;
+use program_structure::report::ReportCollection;
+use program_structure::template_data::TemplateInfo;
+
+/// The total time spent running each analysis pass, indexed by pass name.
+pub type PassTimings = BTreeMap<String, Duration>;
+
+/// An analysis pass run on the CFG of each function and template.
+pub trait AnalysisPass {
+    /// The name used to enable or disable the pass (e.g. `signal-assignments`).
+    fn name(&self) -> &str;
+
+    /// A short description of what the pass checks.
+    fn description(&self) -> &str;
+
+    /// Runs the pass on the given CFG and returns the generated reports.
+    fn run(&self, cfg: &Cfg) -> ReportCollection;
+
+    /// Runs the pass on the given CFG, with access to the templates defined by
+    /// the program. Passes that require information about other templates
+    /// should override this method. By default, this calls `AnalysisPass::run`.
+    fn run_with_templates(&self, cfg: &Cfg, _templates: &TemplateInfo) -> ReportCollection {
+        self.run(cfg)
+    }
+}
+
+/// An analysis pass implemented by a function taking a CFG.
+pub struct FunctionPass {
+    name: &'static str,
+    description: &'static str,
+    run: fn(&Cfg) -> ReportCollection,
+}
+
+impl FunctionPass {
+    #[must_use]
+    pub const fn new(
+        name: &'static str,
+        description: &'static str,
+        run: fn(&Cfg) -> ReportCollection,
+    ) -> FunctionPass {
+        FunctionPass { name, description, run }
+    }
+}
+
+impl AnalysisPass for FunctionPass {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn description(&self) -> &str {
+        self.description
+    }
+
+    fn run(&self, cfg: &Cfg) -> ReportCollection {
+        (self.run)(cfg)
+    }
+}
+
+/// An analysis pass implemented by a function taking a CFG together with the
+/// templates defined by the program.
+pub struct TemplatePass {
+    name: &'static str,
+    description: &'static str,
+    run: fn(&Cfg, &TemplateInfo) -> ReportCollection,
+}
+
+impl TemplatePass {
+    #[must_use]
+    pub const fn new(
+        name: &'static str,
+        description: &'static str,
+        run: fn(&Cfg, &TemplateInfo) -> ReportCollection,
+    ) -> TemplatePass {
+        TemplatePass { name, description, run }
+    }
+}
+
+impl AnalysisPass for TemplatePass {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn description(&self) -> &str {
+        self.description
+    }
+
+    /// Runs the pass without any information about the templates defined by
+    /// the program.
+    fn run(&self, cfg: &Cfg) -> ReportCollection {
+        (self.run)(cfg, &TemplateInfo::new())
+    }
+
+    fn run_with_templates(&self, cfg: &Cfg, templates: &TemplateInfo) -> ReportCollection {
+        (self.run)(cfg, templates)
+    }
+}
+
+/// The set of analysis passes run by the `AnalysisRunner`. Passes are run in
+/// the order they were registered, and may be disabled by name.
+#[derive(Default)]
+pub struct PassRegistry {
+    passes: Vec<Box<dyn AnalysisPass>>,
+    disabled: HashSet<String>,
+}
+
+impl PassRegistry {
+    /// Returns an empty registry. Use `get_analysis_passes` to obtain a
+    /// registry containing the built-in analysis passes.
+    #[must_use]
+    pub fn new() -> PassRegistry {
+        PassRegistry::default()
+    }
+
+    /// Registers the given pass. If a pass with the same name is already
+    /// registered, it is replaced by the new pass.
+    pub fn register(&mut self, pass: impl AnalysisPass + 'static) {
+        match self.passes.iter().position(|other| other.name() == pass.name()) {
+            Some(index) => self.passes[index] = Box::new(pass),
+            None => self.passes.push(Box::new(pass)),
+        }
+    }
+
+    /// Enables the pass with the given name. Returns false if there is no
+    /// such pass.
+    pub fn enable(&mut self, name: &str) -> bool {
+        self.disabled.remove(name);
+        self.contains(name)
+    }
+
+    /// Disables the pass with the given name. Returns false if there is no
+    /// such pass.
+    pub fn disable(&mut self, name: &str) -> bool {
+        if !self.contains(name) {
+            return false;
+        }
+        self.disabled.insert(name.to_string());
+        true
+    }
+
+    /// Returns true if a pass with the given name is registered.
+    #[must_use]
+    pub fn contains(&self, name: &str) -> bool {
+        self.passes.iter().any(|pass| pass.name() == name)
+    }
+
+    /// Returns true if a pass with the given name is registered and enabled.
+    #[must_use]
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.contains(name) && !self.disabled.contains(name)
+    }
+
+    /// Returns an iterator over all registered passes (including disabled
+    /// passes).
+    pub fn iter(&self) -> impl Iterator<Item = &dyn AnalysisPass> {
+        self.passes.iter().map(AsRef::as_ref)
+    }
+
+    /// Returns an iterator over all enabled passes.
+    pub fn enabled(&self) -> impl Iterator<Item = &dyn AnalysisPass> {
+        self.iter().filter(|pass| !self.disabled.contains(pass.name()))
+    }
+
+    /// Runs all enabled passes on the given CFG.
+    #[must_use]
+    pub fn run(&self, cfg: &Cfg, templates: &TemplateInfo) -> ReportCollection {
+        self.enabled().flat_map(|pass| pass.run_with_templates(cfg, templates)).collect()
+    }
+
+    /// Runs all enabled passes on the given CFG, and adds the time spent
+    /// running each pass to the given timings.
+    #[must_use]
+    pub fn run_timed(
+        &self,
+        cfg: &Cfg,
+        templates: &TemplateInfo,
+        timings: &mut PassTimings,
+    ) -> ReportCollection {
+        let (reports, _) = self.run_until(cfg, templates, timings, None, &[]);
+        reports
+    }
+
+    /// Runs all enabled passes (except the excluded passes) on the given CFG
+    /// like `run_timed`. If the deadline is reached, the remaining passes are
+    /// skipped. Since passes are not interrupted, a pass which is already
+    /// running when the deadline is reached is allowed to finish. Returns the
+    /// generated reports together with the names of the skipped passes.
+    #[must_use]
+    pub fn run_until(
+        &self,
+        cfg: &Cfg,
+        templates: &TemplateInfo,
+        timings: &mut PassTimings,
+        deadline: Option<Instant>,
+        excluded: &[&str],
+    ) -> (ReportCollection, Vec<String>) {
+        let mut reports = ReportCollection::new();
+        let mut skipped = Vec::new();
+        for pass in self.enabled().filter(|pass| !excluded.contains(&pass.name())) {
+            let start = Instant::now();
+            if deadline.map(|deadline| start >= deadline).unwrap_or_default() {
+                skipped.push(pass.name().to_string());
+                continue;
+            }
+            reports.extend(pass.run_with_templates(cfg, templates));
+            *timings.entry(pass.name().to_string()).or_default() += start.elapsed();
+        }
+        (reports, skipped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use crate::get_analysis_passes;
+
+    use super::*;
+
+    struct CountingPass;
+
+    impl AnalysisPass for CountingPass {
+        fn name(&self) -> &str {
+            "counting-pass"
+        }
+
+        fn description(&self) -> &str {
+            "Generates one report per basic block."
+        }
+
+        fn run(&self, cfg: &Cfg) -> ReportCollection {
+            use program_structure::report::Report;
+            use program_structure::report_code::ReportCode;
+            cfg.iter()
+                .map(|_| Report::info("basic block".to_string(), ReportCode::CyclomaticComplexity))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_pass_registry() {
+        let src = r#"
+            template T(n) {
+                signal input in;
+                signal output out;
+
+                out <-- in;
+            }
+        "#;
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        let templates = TemplateInfo::new();
+
+        let mut passes = get_analysis_passes();
+        assert!(passes.is_enabled("signal-assignments"));
+        assert!(!passes.run(&cfg, &templates).is_empty());
+
+        // Disabling all built-in passes disables all reports.
+        let names = passes.iter().map(|pass| pass.name().to_string()).collect::<Vec<_>>();
+        for name in &names {
+            assert!(passes.disable(name));
+        }
+        assert!(!passes.disable("unknown-pass"));
+        assert!(passes.run(&cfg, &templates).is_empty());
+
+        // Custom passes can be registered and run in isolation.
+        passes.register(CountingPass);
+        assert_eq!(passes.run(&cfg, &templates).len(), cfg.iter().count());
+        assert!(passes.enable("signal-assignments"));
+        assert!(passes.run(&cfg, &templates).len() > cfg.iter().count());
+
+        // Timed runs record the time spent in each enabled pass.
+        let mut timings = PassTimings::new();
+        let reports = passes.run_timed(&cfg, &templates, &mut timings);
+        assert_eq!(reports.len(), passes.run(&cfg, &templates).len());
+        assert_eq!(
+            timings.keys().cloned().collect::<Vec<_>>(),
+            vec!["counting-pass".to_string(), "signal-assignments".to_string()]
+        );
+
+        // Passes are skipped once the deadline is reached.
+        let (reports, skipped) =
+            passes.run_until(&cfg, &templates, &mut timings, Some(Instant::now()), &[]);
+        assert!(reports.is_empty());
+        assert_eq!(skipped, vec!["signal-assignments".to_string(), "counting-pass".to_string()]);
+        let (_, skipped) =
+            passes.run_until(&cfg, &templates, &mut timings, None, &["counting-pass"]);
+        assert!(skipped.is_empty());
+    }
+}
@@ -0,0 +1,833 @@
+use codespan_reporting::files::Files;
+use log::debug;
+use num_bigint::BigInt;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use parser::parse_definitions;
+use program_structure::cfg::{Cfg, DefinitionType, IntoCfg};
+use program_structure::constants::Curve;
+use program_structure::file_definition::{FileID, FileLibrary, FileLocation};
+use program_structure::function_data::{FunctionData, FunctionInfo};
+use program_structure::ir::*;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::report_code::ReportCode;
+use program_structure::template_data::{TemplateData, TemplateInfo};
+use program_structure::template_library::TemplateLibrary;
+
+use crate::analysis_cache::{content_hash, AnalysisCache, CacheEntry, ContentHash};
+use crate::analysis_pass::{PassRegistry, PassTimings};
+use crate::get_analysis_passes;
+
+/// Analysis passes that are not run on template instances. Since conditions
+/// depending on template parameters are constant for each instance, these
+/// would only generate noise.
+const GENERIC_PASSES: [&str; 1] = ["constant-conditional"];
+
+/// The result of analyzing a single definition.
+#[derive(Clone, Default)]
+struct AnalysisResult {
+    reports: ReportCollection,
+    // The functions and templates called (or instantiated) by the definition.
+    callees: HashSet<String>,
+    // True if some analysis passes were skipped because of a timeout.
+    incomplete: bool,
+}
+
+pub struct AnalysisTimeoutWarning {
+    name: String,
+    definition_type: DefinitionType,
+    timeout: Duration,
+    skipped_passes: Vec<String>,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl AnalysisTimeoutWarning {
+    pub fn into_report(self) -> Report {
+        let seconds = self.timeout.as_secs_f64();
+        let mut report = Report::info(
+            format!(
+                "Analysis of the {} `{}` was stopped after {} second{}.",
+                self.definition_type,
+                self.name,
+                seconds,
+                if seconds == 1.0 { "" } else { "s" }
+            ),
+            ReportCode::AnalysisTimeout,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                format!("The remaining analysis passes were skipped for `{}`.", self.name),
+            );
+        }
+        report.add_note(format!("Skipped passes: {}.", self.skipped_passes.join(", ")));
+        report.add_note(
+            "The time limit can be increased using `--timeout-per-template`.".to_string(),
+        );
+        report
+    }
+}
+
+/// The result of re-analyzing a program after an edit.
+#[derive(Clone, Default)]
+pub struct Reanalysis {
+    /// The definitions that were re-analyzed.
+    pub analyzed: Vec<String>,
+    /// The definitions that were removed by the edit.
+    pub removed: Vec<String>,
+    /// The updated reports for the re-analyzed definitions.
+    pub reports: ReportCollection,
+}
+
+/// Runs the analysis passes on each function and template in a program and
+/// caches the result. After a file has been edited, `AnalysisRunner::reanalyze`
+/// can be used to re-analyze only the definitions affected by the edit,
+/// together with any definitions that depend on them.
+///
+/// If an on-disk cache is provided using `AnalysisRunner::with_cache`, results
+/// are reused across runs for definitions that are unchanged.
+///
+/// If a timeout is set using `AnalysisRunner::with_timeout`, the remaining
+/// analysis passes are skipped for any definition whose analysis exceeds the
+/// timeout, and an informational report is generated instead.
+pub struct AnalysisRunner {
+    curve: Curve,
+    file_library: FileLibrary,
+    functions: FunctionInfo,
+    templates: TemplateInfo,
+    passes: PassRegistry,
+    results: HashMap<String, AnalysisResult>,
+    cache: Option<AnalysisCache>,
+    timings: PassTimings,
+    timeout: Option<Duration>,
+}
+
+impl AnalysisRunner {
+    #[must_use]
+    pub fn new(
+        curve: &Curve,
+        file_library: FileLibrary,
+        functions: FunctionInfo,
+        templates: TemplateInfo,
+    ) -> AnalysisRunner {
+        AnalysisRunner {
+            curve: curve.clone(),
+            file_library,
+            functions,
+            templates,
+            passes: get_analysis_passes(),
+            results: HashMap::new(),
+            cache: None,
+            timings: PassTimings::new(),
+            timeout: None,
+        }
+    }
+
+    /// Use the given analysis passes instead of the built-in passes.
+    #[must_use]
+    pub fn with_passes(mut self, passes: PassRegistry) -> AnalysisRunner {
+        self.passes = passes;
+        self
+    }
+
+    #[must_use]
+    pub fn passes(&self) -> &PassRegistry {
+        &self.passes
+    }
+
+    /// Use the given cache to avoid re-analyzing unchanged definitions.
+    #[must_use]
+    pub fn with_cache(mut self, cache: AnalysisCache) -> AnalysisRunner {
+        self.cache = Some(cache);
+        self
+    }
+
+    #[must_use]
+    pub fn cache(&self) -> Option<&AnalysisCache> {
+        self.cache.as_ref()
+    }
+
+    /// Limit the time spent analyzing each function, template, or template
+    /// instance. Passes are not interrupted, so the remaining passes are
+    /// skipped once the timeout is exceeded.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> AnalysisRunner {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Returns the total time spent running each analysis pass. Definitions
+    /// loaded from the cache are not included.
+    #[must_use]
+    pub fn pass_timings(&self) -> &PassTimings {
+        &self.timings
+    }
+
+    #[must_use]
+    pub fn file_library(&self) -> &FileLibrary {
+        &self.file_library
+    }
+
+    #[must_use]
+    pub fn functions(&self) -> &FunctionInfo {
+        &self.functions
+    }
+
+    #[must_use]
+    pub fn templates(&self) -> &TemplateInfo {
+        &self.templates
+    }
+
+    /// Analyze the function with the given name and return the generated
+    /// reports. Returns `None` if the function is not defined.
+    pub fn analyze_function(&mut self, name: &str) -> Option<&ReportCollection> {
+        let key = self.definition_hash(name);
+        let result = match self.get_cached_result(key) {
+            Some(result) => result,
+            None => {
+                let result = analyze_ast(
+                    self.functions.get(name)?,
+                    &self.curve,
+                    &self.templates,
+                    &self.passes,
+                    &mut self.timings,
+                    self.timeout,
+                );
+                // Incomplete results are not cached.
+                if !result.incomplete {
+                    self.cache_result(key, &result);
+                }
+                result
+            }
+        };
+        self.results.insert(name.to_string(), result);
+        self.get_reports(name)
+    }
+
+    /// Analyze the template with the given name and return the generated
+    /// reports. Returns `None` if the template is not defined.
+    pub fn analyze_template(&mut self, name: &str) -> Option<&ReportCollection> {
+        let key = self.definition_hash(name);
+        let result = match self.get_cached_result(key) {
+            Some(result) => result,
+            None => {
+                let result = analyze_ast(
+                    self.templates.get(name)?,
+                    &self.curve,
+                    &self.templates,
+                    &self.passes,
+                    &mut self.timings,
+                    self.timeout,
+                );
+                // Incomplete results are not cached.
+                if !result.incomplete {
+                    self.cache_result(key, &result);
+                }
+                result
+            }
+        };
+        self.results.insert(name.to_string(), result);
+        self.get_reports(name)
+    }
+
+    /// Analyze the instance of the template with the given name obtained by
+    /// passing the given arguments (where `None` denotes an unknown value),
+    /// and return the generated reports. Since the parameter values are known,
+    /// this may generate reports that are not generated for the generic
+    /// template. The result is not cached. Returns `None` if the template is
+    /// not defined.
+    pub fn analyze_template_instance(
+        &mut self,
+        name: &str,
+        args: &[Option<BigInt>],
+    ) -> Option<ReportCollection> {
+        let template = self.templates.get(name)?;
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        let mut reports = ReportCollection::new();
+        match generate_cfg(template, &self.curve, &mut reports) {
+            Ok(mut cfg) => {
+                cfg.propagate_parameter_values(args);
+                let (instance_reports, skipped_passes) = self.passes.run_until(
+                    &cfg,
+                    &self.templates,
+                    &mut PassTimings::new(),
+                    deadline,
+                    &GENERIC_PASSES,
+                );
+                reports.extend(instance_reports);
+                if let (Some(timeout), false) = (self.timeout, skipped_passes.is_empty()) {
+                    reports.push(build_timeout_report(&cfg, timeout, skipped_passes));
+                }
+            }
+            Err(error) => reports.push(error),
+        }
+        Some(reports)
+    }
+
+    /// Returns the CFG (in SSA form) of the function or template with the
+    /// given name, or the error generated when building the CFG. Returns
+    /// `None` if the definition is not defined.
+    #[allow(clippy::result_large_err)]
+    pub fn get_cfg(&self, name: &str) -> Option<Result<Cfg, Report>> {
+        let mut reports = ReportCollection::new();
+        if let Some(function) = self.functions.get(name) {
+            Some(generate_cfg(function, &self.curve, &mut reports))
+        } else {
+            let template = self.templates.get(name)?;
+            Some(generate_cfg(template, &self.curve, &mut reports))
+        }
+    }
+
+    /// Returns the call graph of the program, mapping each function and
+    /// template to the functions and templates it calls (or instantiates).
+    /// Definitions for which the CFG cannot be generated have no callees.
+    #[must_use]
+    pub fn call_graph(&self) -> BTreeMap<String, BTreeSet<String>> {
+        self.functions
+            .keys()
+            .chain(self.templates.keys())
+            .map(|name| {
+                let callees = match self.get_cfg(name) {
+                    Some(Ok(cfg)) => find_callees(&cfg).into_iter().collect(),
+                    _ => BTreeSet::new(),
+                };
+                (name.clone(), callees)
+            })
+            .collect()
+    }
+
+    /// Analyze all functions and templates in the program and return the
+    /// generated reports.
+    pub fn analyze_all(&mut self) -> ReportCollection {
+        let names = self.functions.keys().chain(self.templates.keys()).cloned().collect();
+        self.analyze_definitions(&names)
+    }
+
+    /// Returns the cached reports for the given definition, if it has been
+    /// analyzed.
+    #[must_use]
+    pub fn get_reports(&self, name: &str) -> Option<&ReportCollection> {
+        self.results.get(name).map(|result| &result.reports)
+    }
+
+    /// Returns all cached reports.
+    #[must_use]
+    pub fn reports(&self) -> ReportCollection {
+        self.results.values().flat_map(|result| result.reports.iter().cloned()).collect()
+    }
+
+    /// Update the source of the given file, and re-analyze all definitions
+    /// affected by the edit. Here, `edited_range` is the location of the
+    /// updated text in the new file source.
+    ///
+    /// Definitions in the file which overlap or follow the edited range are
+    /// re-analyzed (since the locations of definitions following the edit may
+    /// have changed), as are all definitions which call or instantiate a
+    /// re-analyzed or removed definition. If the updated file fails to parse,
+    /// the parse error is returned and the state of the runner is unchanged.
+    #[allow(clippy::result_large_err)]
+    pub fn reanalyze(
+        &mut self,
+        file_id: FileID,
+        edited_range: FileLocation,
+        file_source: String,
+    ) -> Result<Reanalysis, Report> {
+        let definitions = parse_definitions(&file_source, file_id, &self.templates)?;
+        if !self.file_library.update_file(file_id, file_source) {
+            debug!("file ID {file_id} is not tracked by the file library");
+        }
+        let library =
+            TemplateLibrary::new(HashMap::from([(file_id, definitions)]), Default::default());
+
+        // Remove definitions in the edited file that overlap or follow the edit.
+        let is_affected = |file: FileID, location: FileLocation| {
+            file == file_id && location.end >= edited_range.start
+        };
+        let mut removed = HashSet::new();
+        self.functions.retain(|name, function| {
+            let affected = is_affected(function.get_file_id(), function_location(function));
+            if affected {
+                removed.insert(name.clone());
+            }
+            !affected
+        });
+        self.templates.retain(|name, template| {
+            let affected = is_affected(template.get_file_id(), template_location(template));
+            if affected {
+                removed.insert(name.clone());
+            }
+            !affected
+        });
+
+        // Add the updated definitions. Definitions preceding the edit are
+        // unchanged and are not updated.
+        let mut updated = HashSet::new();
+        for (name, function) in library.functions {
+            if is_affected(file_id, function_location(&function)) {
+                updated.insert(name.clone());
+                self.functions.insert(name, function);
+            }
+        }
+        for (name, template) in library.templates {
+            if is_affected(file_id, template_location(&template)) {
+                updated.insert(name.clone());
+                self.templates.insert(name, template);
+            }
+        }
+        let removed = removed.difference(&updated).cloned().collect::<HashSet<_>>();
+        for name in &removed {
+            self.results.remove(name);
+        }
+
+        // Compute the set of dependent definitions.
+        let changed = updated.union(&removed).cloned().collect::<HashSet<_>>();
+        let mut affected = self.get_dependents(&changed);
+        affected.extend(updated);
+        affected.retain(|name| !removed.contains(name));
+
+        let mut analyzed = affected.iter().cloned().collect::<Vec<_>>();
+        analyzed.sort();
+        let mut removed = removed.into_iter().collect::<Vec<_>>();
+        removed.sort();
+        debug!("re-analyzing {} definitions after edit", analyzed.len());
+        let reports = self.analyze_definitions(&analyzed);
+        Ok(Reanalysis { analyzed, removed, reports })
+    }
+
+    /// Returns the set of definitions that (transitively) call or instantiate
+    /// any of the given definitions.
+    fn get_dependents(&self, names: &HashSet<String>) -> HashSet<String> {
+        let mut dependents = HashSet::new();
+        let mut worklist = names.iter().cloned().collect::<Vec<_>>();
+        while let Some(callee) = worklist.pop() {
+            for (caller, result) in &self.results {
+                if result.callees.contains(&callee) && dependents.insert(caller.clone()) {
+                    worklist.push(caller.clone());
+                }
+            }
+        }
+        dependents
+    }
+
+    /// Returns the set of definitions (transitively) called or instantiated by
+    /// the given definitions, including the given definitions themselves.
+    fn get_transitive_callees(&self, names: &HashSet<String>) -> BTreeSet<String> {
+        let mut callees = BTreeSet::new();
+        let mut worklist = names.iter().cloned().collect::<Vec<_>>();
+        while let Some(name) = worklist.pop() {
+            if !callees.insert(name.clone()) {
+                continue;
+            }
+            match self.results.get(&name) {
+                Some(result) => worklist.extend(result.callees.iter().cloned()),
+                None => {
+                    if let Some(Ok(cfg)) = self.get_cfg(&name) {
+                        worklist.extend(find_callees(&cfg));
+                    }
+                }
+            }
+        }
+        callees
+    }
+
+    /// Returns the content hash of the given definition. This covers the
+    /// definition source and location, as well as the curve and the analysis
+    /// passes used.
+    fn definition_hash(&self, name: &str) -> Option<ContentHash> {
+        let (file_id, location) = match (self.functions.get(name), self.templates.get(name)) {
+            (Some(function), _) => (function.get_file_id(), function_location(function)),
+            (None, Some(template)) => (template.get_file_id(), template_location(template)),
+            (None, None) => return None,
+        };
+        let source = self.file_library.to_storage().source(file_id)?;
+        let text = source.get(location.clone())?;
+        let (curve, file_id, start) =
+            (self.curve.to_string(), file_id.to_string(), location.start.to_string());
+        let passes = self.passes.enabled().map(|pass| pass.name()).collect::<Vec<_>>().join(",");
+        let parts = [
+            curve.as_bytes(),
+            passes.as_bytes(),
+            name.as_bytes(),
+            file_id.as_bytes(),
+            start.as_bytes(),
+        ];
+        Some(content_hash(parts.into_iter().chain([text.as_bytes()])))
+    }
+
+    fn get_cached_result(&mut self, key: Option<ContentHash>) -> Option<AnalysisResult> {
+        let key = key?;
+        let mut cache = self.cache.take()?;
+        let result =
+            cache.get(key, |callee| self.definition_hash(callee)).map(|entry| AnalysisResult {
+                reports: entry.reports.clone(),
+                callees: entry.callees.iter().map(|(callee, _)| callee.clone()).collect(),
+                incomplete: false,
+            });
+        self.cache = Some(cache);
+        result
+    }
+
+    fn cache_result(&mut self, key: Option<ContentHash>, result: &AnalysisResult) {
+        let (Some(key), Some(mut cache)) = (key, self.cache.take()) else {
+            return;
+        };
+        // Since the result may depend on the callees of each callee, the entry
+        // is validated against the content hash of all transitive callees.
+        let callees = self
+            .get_transitive_callees(&result.callees)
+            .into_iter()
+            .filter_map(|callee| Some((callee.clone(), self.definition_hash(&callee)?)))
+            .collect();
+        cache.insert(key, CacheEntry { reports: result.reports.clone(), callees });
+        self.cache = Some(cache);
+    }
+
+    fn analyze_definitions(&mut self, names: &Vec<String>) -> ReportCollection {
+        let mut reports = ReportCollection::new();
+        for name in names {
+            if self.functions.contains_key(name) {
+                reports.extend(self.analyze_function(name).into_iter().flatten().cloned());
+            } else {
+                reports.extend(self.analyze_template(name).into_iter().flatten().cloned());
+            }
+        }
+        reports
+    }
+}
+
+/// Returns the location of the function parameters and body.
+fn function_location(function: &FunctionData) -> FileLocation {
+    function.get_param_location().start..function.get_body().get_meta().get_end()
+}
+
+/// Returns the location of the template parameters and body.
+fn template_location(template: &TemplateData) -> FileLocation {
+    template.get_param_location().start..template.get_body().get_meta().get_end()
+}
+
+#[allow(clippy::result_large_err)]
+fn generate_cfg<Ast: IntoCfg>(
+    ast: Ast,
+    curve: &Curve,
+    reports: &mut ReportCollection,
+) -> Result<Cfg, Report> {
+    ast.into_cfg(curve, reports).map_err(Report::from)?.into_ssa().map_err(Report::from)
+}
+
+fn analyze_ast<Ast: IntoCfg>(
+    ast: Ast,
+    curve: &Curve,
+    templates: &TemplateInfo,
+    passes: &PassRegistry,
+    timings: &mut PassTimings,
+    timeout: Option<Duration>,
+) -> AnalysisResult {
+    // The deadline includes the time spent generating the CFG.
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    let mut result = AnalysisResult::default();
+    match generate_cfg(ast, curve, &mut result.reports) {
+        Ok(cfg) => {
+            let (reports, skipped_passes) =
+                passes.run_until(&cfg, templates, timings, deadline, &[]);
+            result.reports.extend(reports);
+            result.callees = find_callees(&cfg);
+            if let (Some(timeout), false) = (timeout, skipped_passes.is_empty()) {
+                debug!("analysis of `{}` timed out", cfg.name());
+                result.reports.push(build_timeout_report(&cfg, timeout, skipped_passes));
+                result.incomplete = true;
+            }
+        }
+        Err(error) => {
+            result.reports.push(error);
+        }
+    }
+    result
+}
+
+fn build_timeout_report(cfg: &Cfg, timeout: Duration, skipped_passes: Vec<String>) -> Report {
+    AnalysisTimeoutWarning {
+        name: cfg.name().to_string(),
+        definition_type: cfg.definition_type().clone(),
+        timeout,
+        skipped_passes,
+        file_id: *cfg.file_id(),
+        file_location: cfg.parameters().file_location().clone(),
+    }
+    .into_report()
+}
+
+/// Returns the names of all functions and templates called by the CFG.
+fn find_callees(cfg: &Cfg) -> HashSet<String> {
+    let mut callees = HashSet::new();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            visit_statement(stmt, &mut callees);
+        }
+    }
+    callees
+}
+
+fn visit_statement(stmt: &Statement, callees: &mut HashSet<String>) {
+    use Statement::*;
+    match stmt {
+        Declaration { dimensions, .. } => {
+            for size in dimensions {
+                visit_expression(size, callees);
+            }
+        }
+        LogCall { args, .. } => {
+            use LogArgument::*;
+            for arg in args {
+                if let Expr(value) = arg {
+                    visit_expression(value, callees);
+                }
+            }
+        }
+        IfThenElse { cond, .. } => visit_expression(cond, callees),
+        Substitution { rhe, .. } => visit_expression(rhe, callees),
+        Return { value, .. } => visit_expression(value, callees),
+        Assert { arg, .. } => visit_expression(arg, callees),
+        ConstraintEquality { lhe, rhe, .. } => {
+            visit_expression(lhe, callees);
+            visit_expression(rhe, callees);
+        }
+    }
+}
+
+fn visit_expression(expr: &Expression, callees: &mut HashSet<String>) {
+    use Expression::*;
+    match expr {
+        Call { name, args, .. } => {
+            callees.insert(name.clone());
+            for arg in args {
+                visit_expression(arg, callees);
+            }
+        }
+        InfixOp { lhe, rhe, .. } => {
+            visit_expression(lhe, callees);
+            visit_expression(rhe, callees);
+        }
+        PrefixOp { rhe, .. } => {
+            visit_expression(rhe, callees);
+        }
+        SwitchOp { cond, if_true, if_false, .. } => {
+            visit_expression(cond, callees);
+            visit_expression(if_true, callees);
+            visit_expression(if_false, callees);
+        }
+        InlineArray { values, .. } => {
+            for value in values {
+                visit_expression(value, callees);
+            }
+        }
+        Access { access, .. } => {
+            for index in access {
+                if let AccessType::ArrayAccess(index) = index {
+                    visit_expression(index, callees);
+                }
+            }
+        }
+        Update { access, rhe, .. } => {
+            for index in access {
+                if let AccessType::ArrayAccess(index) = index {
+                    visit_expression(index, callees);
+                }
+            }
+            visit_expression(rhe, callees);
+        }
+        Number(_, _) | Variable { .. } | Phi { .. } => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRC: &str = r#"
+        function f(a) {
+            return a + 1;
+        }
+
+        template A() {
+            signal input in;
+            signal output out;
+            out <== f(in);
+        }
+
+        template B() {
+            signal input in;
+            signal output out;
+            component a = A();
+            a.in <== in;
+            out <== a.out;
+        }
+
+        template C() {
+            signal input in;
+            signal output out;
+            out <== in;
+        }
+    "#;
+
+    fn build_runner(src: &str) -> AnalysisRunner {
+        let mut file_library = FileLibrary::new();
+        let file_id = file_library.add_file("test.circom".to_string(), src.to_string());
+        let definitions = parse_definitions(src, file_id, &TemplateInfo::new()).ok().unwrap();
+        let library = TemplateLibrary::new(HashMap::from([(file_id, definitions)]), file_library);
+        AnalysisRunner::new(
+            &Curve::default(),
+            library.file_library,
+            library.functions,
+            library.templates,
+        )
+    }
+
+    #[test]
+    fn test_analysis_cache() {
+        let cache_dir =
+            std::env::temp_dir().join(format!("circomspect-cache-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&cache_dir);
+
+        // The first run populates the cache.
+        let mut runner = build_runner(SRC).with_cache(AnalysisCache::load(&cache_dir, &[]));
+        let reports = runner.analyze_all();
+        runner.cache().unwrap().save().ok().unwrap();
+        assert_eq!(runner.cache().unwrap().hits(), 0);
+
+        // The second run uses the cached results.
+        let mut runner = build_runner(SRC).with_cache(AnalysisCache::load(&cache_dir, &[]));
+        assert_eq!(runner.analyze_all().len(), reports.len());
+        assert_eq!(runner.cache().unwrap().hits(), 4);
+
+        // Editing `C` only invalidates the cached result for `C`.
+        let src = SRC.replace("out <== in;", "out <== 2 * in;");
+        let mut runner = build_runner(&src).with_cache(AnalysisCache::load(&cache_dir, &[]));
+        runner.analyze_all();
+        assert_eq!(runner.cache().unwrap().hits(), 3);
+        runner.cache().unwrap().save().ok().unwrap();
+
+        // Editing `f` invalidates the cached results for `f`, `A` (which calls
+        // `f`), and `B` (which instantiates `A`).
+        let src = src.replace("return a + 1;", "return a + 2;");
+        let mut runner = build_runner(&src).with_cache(AnalysisCache::load(&cache_dir, &[]));
+        runner.analyze_all();
+        assert_eq!(runner.cache().unwrap().hits(), 1);
+        runner.cache().unwrap().save().ok().unwrap();
+
+        // Cached results are only reused if the settings are unchanged.
+        let settings = ["max-statements = 10"];
+        let mut runner = build_runner(&src).with_cache(AnalysisCache::load(&cache_dir, &settings));
+        runner.analyze_all();
+        assert_eq!(runner.cache().unwrap().hits(), 0);
+
+        let _ = std::fs::remove_dir_all(&cache_dir);
+    }
+
+    #[test]
+    fn test_analysis_timeout() {
+        let cache_dir =
+            std::env::temp_dir().join(format!("circomspect-timeout-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&cache_dir);
+
+        // With a zero timeout all passes are skipped.
+        let mut runner = build_runner(SRC)
+            .with_cache(AnalysisCache::load(&cache_dir, &[]))
+            .with_timeout(Duration::ZERO);
+        let reports = runner.analyze_all();
+        assert_eq!(reports.len(), 4);
+        assert!(reports.iter().all(|report| report.id() == "CS0029"));
+        let instance = runner.analyze_template_instance("C", &[]).unwrap();
+        assert!(instance.iter().all(|report| report.id() == "CS0029"));
+
+        // Incomplete results are not cached.
+        runner.cache().unwrap().save().ok().unwrap();
+        let mut runner = build_runner(SRC).with_cache(AnalysisCache::load(&cache_dir, &[]));
+        let reports = runner.analyze_all();
+        assert_eq!(runner.cache().unwrap().hits(), 0);
+        assert!(!reports.iter().any(|report| report.id() == "CS0029"));
+
+        let _ = std::fs::remove_dir_all(&cache_dir);
+    }
+
+    #[test]
+    fn test_reanalysis() {
+        let mut runner = build_runner(SRC);
+        runner.analyze_all();
+        assert!(runner.get_reports("B").is_some());
+
+        // Edit the function `f`. This should trigger re-analysis of all
+        // definitions following `f`, since their locations have changed.
+        let src = SRC.replace("a + 1", "a + 2");
+        let start = src.find("a + 2").unwrap();
+        let result = runner.reanalyze(0, start..start + 5, src.clone()).ok().unwrap();
+        assert_eq!(result.analyzed, vec!["A", "B", "C", "f"]);
+        assert!(result.removed.is_empty());
+
+        // Edit `C`. No other definition depends on `C`.
+        let src = src.replace("out <== in;", "out <== 2 * in;");
+        let start = src.find("2 * in").unwrap();
+        let result = runner.reanalyze(0, start..start + 6, src.clone()).ok().unwrap();
+        assert_eq!(result.analyzed, vec!["C"]);
+
+        // Remove `C`.
+        let end = src.find("template C").unwrap();
+        let src = src[..end].to_string();
+        let result = runner.reanalyze(0, end..end, src).ok().unwrap();
+        assert!(result.analyzed.is_empty());
+        assert_eq!(result.removed, vec!["C"]);
+        assert!(runner.get_reports("C").is_none());
+
+        // Parse errors leave the runner unchanged.
+        assert!(runner.reanalyze(0, 0..0, "template {".to_string()).is_err());
+        assert!(runner.templates().contains_key("B"));
+    }
+
+    #[test]
+    fn test_dependents() {
+        let mut runner = build_runner(SRC);
+        runner.analyze_all();
+
+        let dependents = runner.get_dependents(&HashSet::from(["f".to_string()]));
+        assert_eq!(dependents, HashSet::from(["A".to_string(), "B".to_string()]));
+        let dependents = runner.get_dependents(&HashSet::from(["C".to_string()]));
+        assert!(dependents.is_empty());
+    }
+
+    #[test]
+    fn test_template_instances() {
+        let src = r#"
+            template A(n) {
+                signal input in[n];
+                signal output out;
+                out <== in[n];
+                if (n > 0) {
+                    out === in[0];
+                }
+            }
+        "#;
+        let mut runner = build_runner(src);
+        let generic = runner.analyze_template("A").cloned().unwrap();
+        // The out of bounds access is only detected if `n` is known, and the
+        // constant condition `n > 0` is not reported for instances.
+        let instance = runner.analyze_template_instance("A", &[Some(BigInt::from(2))]).unwrap();
+        assert!(instance.iter().any(|report| report.id() == "CS0015"));
+        assert!(!generic.iter().any(|report| report.id() == "CS0015"));
+        assert!(!instance.iter().any(|report| report.id() == "CS0009"));
+        assert!(runner.analyze_template_instance("B", &[]).is_none());
+    }
+
+    #[test]
+    fn test_call_graph() {
+        let runner = build_runner(SRC);
+        let call_graph = runner.call_graph();
+
+        assert_eq!(call_graph.len(), 4);
+        assert!(call_graph["f"].is_empty());
+        assert_eq!(call_graph["A"], BTreeSet::from(["f".to_string()]));
+        assert_eq!(call_graph["B"], BTreeSet::from(["A".to_string()]));
+        assert!(call_graph["C"].is_empty());
+    }
+}
@@ -0,0 +1,267 @@
+use log::debug;
+use num_bigint::BigInt;
+use std::collections::HashMap;
+
+use program_structure::cfg::Cfg;
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::value_meta::{ValueMeta, ValueReduction};
+use program_structure::ir::*;
+
+pub struct OutOfBoundsArrayAccessWarning {
+    var: VariableName,
+    index: BigInt,
+    size: BigInt,
+    file_id: Option<FileID>,
+    primary_location: FileLocation,
+    secondary_location: FileLocation,
+}
+
+impl OutOfBoundsArrayAccessWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            format!("The array `{}` is accessed out of bounds.", self.var),
+            ReportCode::OutOfBoundsArrayAccess,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.primary_location,
+                file_id,
+                format!(
+                    "The index `{}` is out of bounds for a dimension of size `{}`.",
+                    self.index, self.size
+                ),
+            );
+            report.add_secondary(
+                self.secondary_location,
+                file_id,
+                Some(format!("The array `{}` is declared here.", self.var)),
+            );
+        }
+        report
+    }
+}
+
+/// The declared dimensions of an array.
+struct ArrayDeclaration {
+    meta: Meta,
+    dimensions: Vec<Option<BigInt>>,
+}
+
+/// If both the dimensions of an array and the index used to access the array
+/// evaluate to constants, we can check that the access is within the declared
+/// bounds of the array.
+pub fn find_out_of_bounds_array_access(cfg: &Cfg) -> ReportCollection {
+    debug!("running out-of-bounds array access analysis pass");
+    let mut declarations = HashMap::new();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            if let Statement::Declaration { meta, names, dimensions, .. } = stmt {
+                if dimensions.is_empty() {
+                    continue;
+                }
+                for name in names {
+                    let dimensions = dimensions.iter().map(to_bigint).collect();
+                    declarations.insert(
+                        name.without_version(),
+                        ArrayDeclaration { meta: meta.clone(), dimensions },
+                    );
+                }
+            }
+        }
+    }
+    let mut reports = ReportCollection::new();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            visit_statement(stmt, cfg.constants().prime(), &declarations, &mut reports);
+        }
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+fn visit_statement(
+    stmt: &Statement,
+    prime: &BigInt,
+    declarations: &HashMap<VariableName, ArrayDeclaration>,
+    reports: &mut ReportCollection,
+) {
+    use Statement::*;
+    match stmt {
+        Declaration { dimensions, .. } => {
+            for size in dimensions {
+                visit_expression(size, prime, declarations, reports);
+            }
+        }
+        LogCall { args, .. } => {
+            use LogArgument::*;
+            for arg in args {
+                if let Expr(value) = arg {
+                    visit_expression(value, prime, declarations, reports);
+                }
+            }
+        }
+        IfThenElse { cond, .. } => visit_expression(cond, prime, declarations, reports),
+        Substitution { rhe, .. } => visit_expression(rhe, prime, declarations, reports),
+        Return { value, .. } => visit_expression(value, prime, declarations, reports),
+        Assert { arg, .. } => visit_expression(arg, prime, declarations, reports),
+        ConstraintEquality { lhe, rhe, .. } => {
+            visit_expression(lhe, prime, declarations, reports);
+            visit_expression(rhe, prime, declarations, reports);
+        }
+    }
+}
+
+fn visit_expression(
+    expr: &Expression,
+    prime: &BigInt,
+    declarations: &HashMap<VariableName, ArrayDeclaration>,
+    reports: &mut ReportCollection,
+) {
+    use Expression::*;
+    match expr {
+        InfixOp { lhe, rhe, .. } => {
+            visit_expression(lhe, prime, declarations, reports);
+            visit_expression(rhe, prime, declarations, reports);
+        }
+        PrefixOp { rhe, .. } => {
+            visit_expression(rhe, prime, declarations, reports);
+        }
+        SwitchOp { cond, if_true, if_false, .. } => {
+            visit_expression(cond, prime, declarations, reports);
+            visit_expression(if_true, prime, declarations, reports);
+            visit_expression(if_false, prime, declarations, reports);
+        }
+        Call { args, .. } => {
+            for arg in args {
+                visit_expression(arg, prime, declarations, reports);
+            }
+        }
+        InlineArray { values, .. } => {
+            for value in values {
+                visit_expression(value, prime, declarations, reports);
+            }
+        }
+        Access { var, access, .. } => {
+            visit_access(var, access, prime, declarations, reports);
+        }
+        Update { var, access, rhe, .. } => {
+            visit_access(var, access, prime, declarations, reports);
+            visit_expression(rhe, prime, declarations, reports);
+        }
+        Number(_, _) | Variable { .. } | Phi { .. } => (),
+    }
+}
+
+fn visit_access(
+    var: &VariableName,
+    access: &[AccessType],
+    prime: &BigInt,
+    declarations: &HashMap<VariableName, ArrayDeclaration>,
+    reports: &mut ReportCollection,
+) {
+    let declaration = declarations.get(&var.without_version());
+    // Only the leading array accesses index into the declared array. Any array
+    // accesses following a component access index into a component signal.
+    let indices = access.iter().map_while(|access| match access {
+        AccessType::ArrayAccess(index) => Some(index),
+        AccessType::ComponentAccess(_) => None,
+    });
+    for (i, index) in indices.enumerate() {
+        let size = declaration.and_then(|declaration| declaration.dimensions.get(i));
+        if let (Some(Some(size)), Some(value)) = (size, to_bigint(index)) {
+            if &value >= size {
+                // We know that `declaration` is `Some` here, so it is ok to unwrap.
+                let declaration = declaration.unwrap();
+                reports.push(build_report(var, index.meta(), declaration, value, size, prime));
+            }
+        }
+    }
+    for access in access {
+        if let AccessType::ArrayAccess(index) = access {
+            visit_expression(index, prime, declarations, reports);
+        }
+    }
+}
+
+/// Returns the value of the expression if it evaluates to a constant field
+/// element.
+fn to_bigint(expr: &Expression) -> Option<BigInt> {
+    match expr.value() {
+        Some(ValueReduction::FieldElement { value }) => Some(value.clone()),
+        _ => None,
+    }
+}
+
+fn build_report(
+    var: &VariableName,
+    meta: &Meta,
+    declaration: &ArrayDeclaration,
+    index: BigInt,
+    size: &BigInt,
+    prime: &BigInt,
+) -> Report {
+    // Negative indices are represented as field elements greater than `p/2`.
+    let index = if index > prime / 2u32 { index - prime } else { index };
+    OutOfBoundsArrayAccessWarning {
+        var: var.without_version(),
+        index,
+        size: size.clone(),
+        file_id: meta.file_id(),
+        primary_location: meta.file_location(),
+        secondary_location: declaration.meta.file_location(),
+    }
+    .into_report()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_out_of_bounds_array_access() {
+        let src = r#"
+            template T() {
+                signal input in[2];
+                signal output out[2][3];
+                var x[2] = [0, 1];
+
+                out[0][3] <== in[2];
+                out[1][0] <== in[0] + x[-1];
+                out[1][1] <== x[1];
+            }
+        "#;
+        validate_reports(src, 3);
+
+        let src = r#"
+            template T(n) {
+                signal input in[n];
+                signal output out;
+
+                out <== in[0] + in[n - 1];
+            }
+        "#;
+        validate_reports(src, 0);
+    }
+
+    fn validate_reports(src: &str, expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_out_of_bounds_array_access(&cfg);
+
+        assert_eq!(reports.len(), expected_len);
+    }
+}
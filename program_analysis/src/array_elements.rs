@@ -0,0 +1,96 @@
+use num_bigint::BigInt;
+use std::collections::HashSet;
+
+use program_structure::ir::value_meta::{ValueMeta, ValueReduction};
+use program_structure::ir::AccessType;
+
+/// A summary of the array elements accessed by a set of variable uses.
+/// Elements accessed using constant indices are tracked individually, while
+/// accesses using non-constant indices (e.g. loop-indexed accesses) are
+/// summarized as accessing all elements.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ArrayElements {
+    /// All elements of the array may be accessed.
+    All,
+    /// Only elements with the given (possibly partial) indices are accessed.
+    Elements(HashSet<Vec<BigInt>>),
+}
+
+impl Default for ArrayElements {
+    fn default() -> ArrayElements {
+        ArrayElements::Elements(HashSet::new())
+    }
+}
+
+impl ArrayElements {
+    /// Adds the elements accessed by the given access to the set.
+    pub fn insert(&mut self, access: &[AccessType]) {
+        match (self, constant_indices(access)) {
+            (ArrayElements::Elements(elements), Some(indices)) => {
+                elements.insert(indices);
+            }
+            (elements, None) => *elements = ArrayElements::All,
+            (ArrayElements::All, Some(_)) => {}
+        }
+    }
+
+    /// Adds all elements of the other set to this set.
+    pub fn extend(&mut self, other: &ArrayElements) {
+        match (self, other) {
+            (ArrayElements::Elements(elements), ArrayElements::Elements(other)) => {
+                elements.extend(other.iter().cloned());
+            }
+            (elements, ArrayElements::All) => *elements = ArrayElements::All,
+            (ArrayElements::All, ArrayElements::Elements(_)) => {}
+        }
+    }
+
+    /// Returns true if the element with the given indices may be accessed.
+    /// (An access using partial indices `a[i]` accesses all elements `a[i][j]`.)
+    #[must_use]
+    pub fn contains(&self, indices: &[BigInt]) -> bool {
+        match self {
+            ArrayElements::All => true,
+            ArrayElements::Elements(elements) => {
+                elements.iter().any(|element| indices.starts_with(element))
+            }
+        }
+    }
+}
+
+/// Returns the indices of the access if all indices are constant array
+/// indices.
+fn constant_indices(access: &[AccessType]) -> Option<Vec<BigInt>> {
+    access
+        .iter()
+        .map(|access| match access {
+            AccessType::ArrayAccess(index) => match index.value() {
+                Some(ValueReduction::FieldElement { value }) => Some(value.clone()),
+                _ => None,
+            },
+            AccessType::ComponentAccess(_) => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_array_elements() {
+        let mut elements = ArrayElements::default();
+        assert!(!elements.contains(&[BigInt::from(0)]));
+
+        elements.extend(&ArrayElements::Elements(HashSet::from([vec![BigInt::from(1)]])));
+        assert!(!elements.contains(&[BigInt::from(0), BigInt::from(1)]));
+        assert!(elements.contains(&[BigInt::from(1), BigInt::from(0)]));
+
+        // Accessing the entire array accesses all elements.
+        elements.insert(&[]);
+        assert!(elements.contains(&[BigInt::from(0), BigInt::from(1)]));
+
+        elements.extend(&ArrayElements::All);
+        assert_eq!(elements, ArrayElements::All);
+    }
+}
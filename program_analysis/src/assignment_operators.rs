@@ -0,0 +1,168 @@
+use log::debug;
+
+use program_structure::cfg::Cfg;
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::*;
+
+/// Describes how the assignment operator does not match the assigned variable.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mismatch {
+    // A signal is assigned using `=`.
+    SignalAssignedAsVariable,
+    // A variable is assigned using `<==` or `<--`.
+    VariableAssignedAsSignal(AssignOp),
+}
+
+pub struct AssignmentOperatorMismatchWarning {
+    var: VariableName,
+    mismatch: Mismatch,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl AssignmentOperatorMismatchWarning {
+    pub fn into_report(self) -> Report {
+        let name = self.var.without_version();
+        let mut report = match self.mismatch {
+            Mismatch::SignalAssignedAsVariable => Report::warning(
+                format!("The signal `{name}` is assigned using the variable assignment operator `=`."),
+                ReportCode::AssignmentOperatorMismatch,
+            ),
+            Mismatch::VariableAssignedAsSignal(op) => Report::warning(
+                format!("The variable `{name}` is assigned using the signal assignment operator `{op}`."),
+                ReportCode::AssignmentOperatorMismatch,
+            ),
+        };
+        if let Some(file_id) = self.file_id {
+            let message = match self.mismatch {
+                Mismatch::SignalAssignedAsVariable => format!("`{name}` is declared as a signal."),
+                Mismatch::VariableAssignedAsSignal(_) => {
+                    format!("`{name}` is declared as a variable.")
+                }
+            };
+            report.add_primary(self.file_location, file_id, message);
+        }
+        match self.mismatch {
+            Mismatch::SignalAssignedAsVariable => {
+                report.add_note(
+                    "Signals are assigned a value once using `<==` (which also adds a constraint) or `<--` (which does not), while `=` only updates the value of a variable during witness generation. The Circom compiler rejects this assignment.".to_string(),
+                );
+                report.add_help(format!(
+                    "Consider replacing `=` with `<==` to both assign and constrain `{name}`."
+                ));
+            }
+            Mismatch::VariableAssignedAsSignal(_) => {
+                report.add_note(
+                    "Variables are not part of the witness, and cannot occur in constraints. Assigning a variable never generates a constraint, and the Circom compiler rejects this assignment.".to_string(),
+                );
+                report.add_help(format!(
+                    "Consider replacing the operator with `=`, or declaring `{name}` as a signal if the assigned value should be constrained."
+                ));
+            }
+        }
+        report
+    }
+}
+
+/// The assignment operators `<==` and `<--` can only be used to assign
+/// signals, and `=` can only be used to assign variables and components.
+/// Mixing them up is rejected by the compiler, but it typically indicates
+/// confusion about whether the assigned value is constrained, so this analysis
+/// pass reports each mismatch together with an explanation of the difference.
+pub fn find_assignment_operator_mismatches(cfg: &Cfg) -> ReportCollection {
+    debug!("running assignment operator analysis pass");
+    let mut reports = ReportCollection::new();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            visit_statement(stmt, &mut reports);
+        }
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+fn visit_statement(stmt: &Statement, reports: &mut ReportCollection) {
+    use AssignOp::*;
+    let Statement::Substitution { meta, var, op, .. } = stmt else {
+        return;
+    };
+    let type_knowledge = meta.type_knowledge();
+    let mismatch = match op {
+        AssignLocalOrComponent if type_knowledge.is_signal() => Mismatch::SignalAssignedAsVariable,
+        AssignSignal | AssignConstraintSignal if type_knowledge.is_local() => {
+            Mismatch::VariableAssignedAsSignal(*op)
+        }
+        _ => return,
+    };
+    reports.push(build_report(var, mismatch, meta));
+}
+
+fn build_report(var: &VariableName, mismatch: Mismatch, meta: &Meta) -> Report {
+    AssignmentOperatorMismatchWarning {
+        var: var.clone(),
+        mismatch,
+        file_id: meta.file_id(),
+        file_location: meta.file_location(),
+    }
+    .into_report()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_assignment_operator_mismatch() {
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                signal tmp;
+                var x;
+                x <== in * 2;
+                tmp = in * in;
+                var y[2];
+                y[0] <-- in;
+                out <== tmp;
+            }
+        "#;
+        validate_reports(src, 3);
+
+        let src = r#"
+            template T(n) {
+                signal input in[n];
+                signal output out;
+                component c = C();
+                var sum = 0;
+                for (var i = 0; i < n; i++) {
+                    sum += in[i];
+                }
+                c.in <== sum;
+                out <-- c.out;
+                out === sum;
+            }
+        "#;
+        validate_reports(src, 0);
+    }
+
+    fn validate_reports(src: &str, expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_assignment_operator_mismatches(&cfg);
+        assert_eq!(reports.len(), expected_len);
+    }
+}
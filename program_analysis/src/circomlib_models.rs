@@ -0,0 +1,259 @@
+use num_bigint::BigInt;
+use std::collections::HashMap;
+
+use program_structure::cfg::Cfg;
+use program_structure::ir::value_meta::{ValueMeta, ValueReduction};
+use program_structure::ir::{AccessType, AssignOp, Expression, Statement, VariableName};
+
+/// A precondition on the parameters or inputs of a template. If the
+/// precondition does not hold, the constraints generated by the template may
+/// not enforce the expected relation between inputs and outputs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Precondition {
+    /// The parameter with the given index must be strictly less than `bound`.
+    ParameterBelow { parameter: usize, bound: usize },
+    /// The input signal must be range checked to the number of bits given by
+    /// the parameter with the given index.
+    InputBitWidth { input: &'static str, parameter: usize },
+    /// Each element of the input signal must be binary.
+    BinaryInput { input: &'static str },
+}
+
+/// A semantic summary of a template from Circomlib.
+pub struct TemplateModel {
+    /// The name of the template.
+    pub name: &'static str,
+    /// Each output signal, together with the input signals constraining it.
+    pub outputs: &'static [(&'static str, &'static [&'static str])],
+    /// The preconditions that must hold for the template to be sound.
+    pub preconditions: &'static [Precondition],
+}
+
+impl TemplateModel {
+    /// Returns the input signals constraining the given output signal.
+    #[must_use]
+    pub fn inputs_constraining(&self, output: &str) -> Option<&'static [&'static str]> {
+        self.outputs.iter().find(|(name, _)| *name == output).map(|(_, inputs)| *inputs)
+    }
+}
+
+/// Returns the model of the Circomlib template with the given name, if it
+/// exists.
+#[must_use]
+pub fn find_template_model(name: &str) -> Option<&'static TemplateModel> {
+    TEMPLATE_MODELS.iter().find(|model| model.name == name)
+}
+
+/// Maps the components of a template to the models of the instantiated
+/// templates. Since templates are identified by name, we assume that any
+/// template with the same name as a Circomlib template is the corresponding
+/// template from Circomlib.
+#[derive(Default)]
+pub struct ComponentModels {
+    models: HashMap<VariableName, &'static TemplateModel>,
+    // The constant values of the template parameters, if known.
+    parameters: HashMap<VariableName, Vec<Option<BigInt>>>,
+}
+
+impl ComponentModels {
+    #[must_use]
+    pub fn new(cfg: &Cfg) -> ComponentModels {
+        use AssignOp::*;
+        use Expression::*;
+        use Statement::*;
+        let mut result = ComponentModels::default();
+        for basic_block in cfg.iter() {
+            for stmt in basic_block.iter() {
+                let Substitution { var, op: AssignLocalOrComponent, rhe, .. } = stmt else {
+                    continue;
+                };
+                // Assignments to component arrays are lifted to updates.
+                let rhe = if let Update { rhe, .. } = rhe { rhe.as_ref() } else { rhe };
+                let Call { name, args, .. } = rhe else {
+                    continue;
+                };
+                if let Some(model) = find_template_model(name) {
+                    let parameters = args.iter().map(constant_value).collect();
+                    result.models.insert(var.clone(), model);
+                    result.parameters.insert(var.clone(), parameters);
+                }
+            }
+        }
+        result
+    }
+
+    /// Returns the model of the template instantiated by the given component.
+    #[must_use]
+    pub fn get(&self, component: &VariableName) -> Option<&'static TemplateModel> {
+        self.models.get(component).copied()
+    }
+
+    /// Returns the value of the parameter with the given index, if the
+    /// component instantiates a known template and the value is constant.
+    #[must_use]
+    pub fn parameter(&self, component: &VariableName, index: usize) -> Option<&BigInt> {
+        self.parameters.get(component)?.get(index)?.as_ref()
+    }
+}
+
+/// Returns the name of the component signal accessed, if the access is a
+/// component access.
+pub(crate) fn get_signal_name(access: &[AccessType]) -> Option<&str> {
+    access.iter().find_map(|access| match access {
+        AccessType::ComponentAccess(signal_name) => Some(signal_name.as_str()),
+        AccessType::ArrayAccess(_) => None,
+    })
+}
+
+fn constant_value(expr: &Expression) -> Option<BigInt> {
+    match expr.value() {
+        Some(ValueReduction::FieldElement { value }) => Some(value.clone()),
+        _ => None,
+    }
+}
+
+const COMPARATOR_PRECONDITIONS: &[Precondition] = &[
+    Precondition::ParameterBelow { parameter: 0, bound: 252 },
+    Precondition::InputBitWidth { input: "in", parameter: 0 },
+];
+
+const BINARY_GATE_PRECONDITIONS: &[Precondition] =
+    &[Precondition::BinaryInput { input: "a" }, Precondition::BinaryInput { input: "b" }];
+
+static TEMPLATE_MODELS: &[TemplateModel] = &[
+    // bitify.circom
+    TemplateModel {
+        name: "Num2Bits",
+        outputs: &[("out", &["in"])],
+        preconditions: &[Precondition::ParameterBelow { parameter: 0, bound: 252 }],
+    },
+    TemplateModel { name: "Num2Bits_strict", outputs: &[("out", &["in"])], preconditions: &[] },
+    TemplateModel {
+        name: "Bits2Num",
+        outputs: &[("out", &["in"])],
+        preconditions: &[Precondition::BinaryInput { input: "in" }],
+    },
+    TemplateModel {
+        name: "Bits2Num_strict",
+        outputs: &[("out", &["in"])],
+        preconditions: &[Precondition::BinaryInput { input: "in" }],
+    },
+    // comparators.circom
+    TemplateModel { name: "IsZero", outputs: &[("out", &["in"])], preconditions: &[] },
+    TemplateModel { name: "IsEqual", outputs: &[("out", &["in"])], preconditions: &[] },
+    TemplateModel { name: "ForceEqualIfEnabled", outputs: &[], preconditions: &[] },
+    TemplateModel {
+        name: "LessThan",
+        outputs: &[("out", &["in"])],
+        preconditions: COMPARATOR_PRECONDITIONS,
+    },
+    TemplateModel {
+        name: "LessEqThan",
+        outputs: &[("out", &["in"])],
+        preconditions: COMPARATOR_PRECONDITIONS,
+    },
+    TemplateModel {
+        name: "GreaterThan",
+        outputs: &[("out", &["in"])],
+        preconditions: COMPARATOR_PRECONDITIONS,
+    },
+    TemplateModel {
+        name: "GreaterEqThan",
+        outputs: &[("out", &["in"])],
+        preconditions: COMPARATOR_PRECONDITIONS,
+    },
+    // aliascheck.circom, compconstant.circom, and sign.circom
+    TemplateModel {
+        name: "AliasCheck",
+        outputs: &[],
+        preconditions: &[Precondition::BinaryInput { input: "in" }],
+    },
+    TemplateModel {
+        name: "CompConstant",
+        outputs: &[("out", &["in"])],
+        preconditions: &[Precondition::BinaryInput { input: "in" }],
+    },
+    TemplateModel {
+        name: "Sign",
+        outputs: &[("sign", &["in"])],
+        preconditions: &[Precondition::BinaryInput { input: "in" }],
+    },
+    // gates.circom
+    TemplateModel {
+        name: "AND",
+        outputs: &[("out", &["a", "b"])],
+        preconditions: BINARY_GATE_PRECONDITIONS,
+    },
+    TemplateModel {
+        name: "OR",
+        outputs: &[("out", &["a", "b"])],
+        preconditions: BINARY_GATE_PRECONDITIONS,
+    },
+    TemplateModel {
+        name: "XOR",
+        outputs: &[("out", &["a", "b"])],
+        preconditions: BINARY_GATE_PRECONDITIONS,
+    },
+    TemplateModel {
+        name: "NAND",
+        outputs: &[("out", &["a", "b"])],
+        preconditions: BINARY_GATE_PRECONDITIONS,
+    },
+    TemplateModel {
+        name: "NOR",
+        outputs: &[("out", &["a", "b"])],
+        preconditions: BINARY_GATE_PRECONDITIONS,
+    },
+    TemplateModel {
+        name: "NOT",
+        outputs: &[("out", &["in"])],
+        preconditions: &[Precondition::BinaryInput { input: "in" }],
+    },
+    // mux1.circom and switcher.circom
+    TemplateModel {
+        name: "Mux1",
+        outputs: &[("out", &["c", "s"])],
+        preconditions: &[Precondition::BinaryInput { input: "s" }],
+    },
+    TemplateModel {
+        name: "MultiMux1",
+        outputs: &[("out", &["c", "s"])],
+        preconditions: &[Precondition::BinaryInput { input: "s" }],
+    },
+    TemplateModel {
+        name: "Switcher",
+        outputs: &[("outL", &["sel", "L", "R"]), ("outR", &["sel", "L", "R"])],
+        preconditions: &[Precondition::BinaryInput { input: "sel" }],
+    },
+    // binsum.circom
+    TemplateModel {
+        name: "BinSum",
+        outputs: &[("out", &["in"])],
+        preconditions: &[Precondition::BinaryInput { input: "in" }],
+    },
+    // Hash functions.
+    TemplateModel { name: "Poseidon", outputs: &[("out", &["inputs"])], preconditions: &[] },
+    TemplateModel { name: "MiMC7", outputs: &[("out", &["x_in", "k"])], preconditions: &[] },
+    TemplateModel { name: "MultiMiMC7", outputs: &[("out", &["in", "k"])], preconditions: &[] },
+    TemplateModel { name: "MiMCSponge", outputs: &[("outs", &["ins", "k"])], preconditions: &[] },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_models() {
+        let names = TEMPLATE_MODELS.iter().map(|model| model.name).collect::<Vec<_>>();
+        for (i, name) in names.iter().enumerate() {
+            assert!(!names[i + 1..].contains(name), "duplicate model for `{name}`");
+        }
+        let model = find_template_model("LessThan").unwrap();
+        assert_eq!(model.inputs_constraining("out"), Some(&["in"][..]));
+        assert_eq!(model.inputs_constraining("in"), None);
+        assert!(model
+            .preconditions
+            .contains(&Precondition::ParameterBelow { parameter: 0, bound: 252 }));
+        assert!(find_template_model("Foo").is_none());
+    }
+}
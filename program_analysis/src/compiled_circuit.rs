@@ -0,0 +1,409 @@
+//! Maps reports to the signals of a circuit compiled by the Circom compiler,
+//! and cross-checks reports about unconstrained signals against the generated
+//! constraint system. Compiled signals are mapped back to template signals
+//! using the `.sym` file generated together with the R1CS file.
+use std::collections::{HashMap, HashSet};
+
+use log::debug;
+
+use program_structure::ast::{AssignOp, Expression, Statement};
+use program_structure::report::{MessageCategory, Report, ReportCollection};
+use program_structure::r1cs::R1CS;
+use program_structure::sym_file::{without_indices, Symbol};
+use program_structure::template_data::TemplateInfo;
+
+/// The maximum number of compiled signals listed in a note.
+const MAX_LISTED_SIGNALS: usize = 3;
+
+/// The maximum number of compiled signals listed when annotating a report.
+const MAX_ANNOTATED_SIGNALS: usize = 5;
+
+/// A compiled circuit, consisting of the constraint system and the symbols
+/// mapping R1CS wires to signal names.
+#[derive(Clone, Debug)]
+pub struct CompiledCircuit {
+    /// The name of the R1CS file (used in report notes).
+    pub name: String,
+    pub r1cs: R1CS,
+    pub symbols: Vec<Symbol>,
+}
+
+/// Maps compiled signals from a `.sym` file to the templates declaring them.
+pub struct SymbolMapping<'a> {
+    symbols: &'a [Symbol],
+    // The template instantiated by each component path (without indices).
+    templates: HashMap<String, String>,
+}
+
+impl<'a> SymbolMapping<'a> {
+    /// Maps the given symbols to templates, starting from the template
+    /// instantiated by the main component.
+    #[must_use]
+    pub fn new(
+        symbols: &'a [Symbol],
+        main_template: &str,
+        templates: &TemplateInfo,
+    ) -> SymbolMapping<'a> {
+        let instantiations = templates
+            .iter()
+            .map(|(name, template)| {
+                let mut components = HashMap::new();
+                collect_instantiations(template.get_body(), templates, &mut components);
+                (name.clone(), components)
+            })
+            .collect::<HashMap<_, _>>();
+        let mut paths = HashMap::from([("main".to_string(), Some(main_template.to_string()))]);
+        for symbol in symbols {
+            resolve_template(
+                &without_indices(symbol.component_path()),
+                &instantiations,
+                &mut paths,
+            );
+        }
+        let templates =
+            paths.into_iter().filter_map(|(path, template)| Some((path, template?))).collect();
+        SymbolMapping { symbols, templates }
+    }
+
+    /// Returns the compiled signals corresponding to the given template
+    /// signal. If the signal name has no array indices, all array elements
+    /// are returned.
+    pub fn find_symbols(
+        &self,
+        template_name: &str,
+        signal_name: &str,
+    ) -> impl Iterator<Item = &'a Symbol> + '_ {
+        let depth = signal_name.split('.').count();
+        let signal_name = signal_name.to_string();
+        let template_name = template_name.to_string();
+        self.symbols.iter().filter(move |symbol| {
+            let parts = symbol.name.split('.').collect::<Vec<_>>();
+            if parts.len() <= depth {
+                return false;
+            }
+            let (path, name) = parts.split_at(parts.len() - depth);
+            let name = name.join(".");
+            (name == signal_name || without_indices(&name) == signal_name)
+                && self.templates.get(&without_indices(&path.join("."))) == Some(&template_name)
+        })
+    }
+
+    /// Returns the compiled signals corresponding to the signals affected by
+    /// the given report, ordered by signal index.
+    #[must_use]
+    pub fn affected_symbols(&self, report: &Report) -> Vec<&'a Symbol> {
+        let mut symbols = report
+            .affected_signals()
+            .iter()
+            .flat_map(|signal| self.find_symbols(&signal.template_name, &signal.signal_name))
+            .collect::<Vec<_>>();
+        symbols.sort_by_key(|symbol| symbol.label);
+        symbols.dedup_by_key(|symbol| symbol.label);
+        symbols
+    }
+}
+
+/// Maps compiled signals to the templates declaring them, and to the
+/// constraints of the compiled circuit.
+pub struct CircuitMapping<'a> {
+    circuit: &'a CompiledCircuit,
+    symbols: SymbolMapping<'a>,
+    constrained_wires: HashSet<usize>,
+}
+
+impl<'a> CircuitMapping<'a> {
+    /// Maps the symbols of the compiled circuit to templates, starting from
+    /// the template instantiated by the main component.
+    #[must_use]
+    pub fn new(
+        circuit: &'a CompiledCircuit,
+        main_template: &str,
+        templates: &TemplateInfo,
+    ) -> CircuitMapping<'a> {
+        CircuitMapping {
+            circuit,
+            symbols: SymbolMapping::new(&circuit.symbols, main_template, templates),
+            constrained_wires: circuit.r1cs.constrained_wires(),
+        }
+    }
+
+    #[must_use]
+    pub fn symbols(&self) -> &SymbolMapping<'a> {
+        &self.symbols
+    }
+
+    /// Returns true if the compiled signal occurs in a constraint. Signals
+    /// removed by the optimizer are considered constrained, since the
+    /// optimizer only removes signals determined by linear constraints.
+    #[must_use]
+    pub fn is_constrained(&self, symbol: &Symbol) -> bool {
+        symbol.witness.is_none_or(|wire| self.constrained_wires.contains(&wire))
+    }
+}
+
+/// Adds a note to each report affecting a signal, listing the signal index,
+/// witness index, and component path of each compiled instance of the affected
+/// signals. This makes it easier to correlate reports with the output of
+/// tools like snarkjs, which refer to signals by index.
+pub fn annotate_reports(reports: &mut ReportCollection, mapping: &SymbolMapping) {
+    for report in reports.iter_mut() {
+        for signal in report.affected_signals_mut() {
+            signal.compiled_signals =
+                mapping.find_symbols(&signal.template_name, &signal.signal_name).cloned().collect();
+        }
+        let symbols = mapping.affected_symbols(report);
+        if symbols.is_empty() {
+            continue;
+        }
+        let mut listed = symbols
+            .iter()
+            .take(MAX_ANNOTATED_SIGNALS)
+            .map(|symbol| {
+                let witness = match symbol.witness {
+                    Some(witness) => format!("witness {witness}"),
+                    None => "removed by the optimizer".to_string(),
+                };
+                format!(
+                    "`{}` (signal {}, {witness}, component {} at `{}`)",
+                    symbol.name,
+                    symbol.label,
+                    symbol.component,
+                    symbol.component_path()
+                )
+            })
+            .collect::<Vec<_>>();
+        if symbols.len() > MAX_ANNOTATED_SIGNALS {
+            listed.push(format!("and {} more", symbols.len() - MAX_ANNOTATED_SIGNALS));
+        }
+        report.add_note(format!("Compiled signal(s): {}.", listed.join(", ")));
+    }
+}
+
+/// Cross-checks each report affecting a signal against the compiled circuit.
+/// Reports where the compiled circuit constrains all instances of the
+/// affected signals are downgraded one level (for example, because the
+/// signal is constrained by the parent component). Reports where some
+/// instance of an affected signal does not occur in any constraint are
+/// confirmed, and upgraded to errors. Reports affecting signals not found in
+/// the compiled circuit are left unchanged.
+pub fn cross_check_reports(reports: &mut ReportCollection, mapping: &CircuitMapping) {
+    for report in reports.iter_mut() {
+        let symbols = mapping.symbols.affected_symbols(report);
+        if symbols.is_empty() {
+            continue;
+        }
+        let unconstrained = symbols
+            .iter()
+            .filter(|symbol| !mapping.is_constrained(symbol))
+            .map(|symbol| format!("`{}`", symbol.name))
+            .collect::<Vec<_>>();
+        debug!(
+            "{} of {} compiled signals are unconstrained for report `{}`",
+            unconstrained.len(),
+            symbols.len(),
+            report.message()
+        );
+        if unconstrained.is_empty() {
+            downgrade(report);
+            report.add_note(format!(
+                "All {} compiled instances of the affected signals occur in a constraint in `{}`.",
+                symbols.len(),
+                mapping.circuit.name
+            ));
+        } else {
+            report.set_category(MessageCategory::Error);
+            let listed = if unconstrained.len() > MAX_LISTED_SIGNALS {
+                format!(
+                    "{} (and {} more)",
+                    unconstrained[..MAX_LISTED_SIGNALS].join(", "),
+                    unconstrained.len() - MAX_LISTED_SIGNALS
+                )
+            } else {
+                unconstrained.join(", ")
+            };
+            report.add_note(format!(
+                "Confirmed by `{}`: the compiled signal(s) {listed} do not occur in any constraint.",
+                mapping.circuit.name
+            ));
+        }
+    }
+}
+
+fn downgrade(report: &mut Report) {
+    let category = match report.category() {
+        MessageCategory::Error => MessageCategory::Warning,
+        MessageCategory::Warning | MessageCategory::Info => MessageCategory::Info,
+    };
+    report.set_category(category);
+}
+
+/// Returns the template instantiated by the given component path (without
+/// indices), or `None` if the template cannot be determined (for example,
+/// for anonymous components).
+fn resolve_template(
+    path: &str,
+    instantiations: &HashMap<String, HashMap<String, String>>,
+    paths: &mut HashMap<String, Option<String>>,
+) -> Option<String> {
+    if let Some(template) = paths.get(path) {
+        return template.clone();
+    }
+    let template = path.rsplit_once('.').and_then(|(parent, component)| {
+        let parent_template = resolve_template(parent, instantiations, paths)?;
+        instantiations.get(&parent_template)?.get(component).cloned()
+    });
+    paths.insert(path.to_string(), template.clone());
+    template
+}
+
+/// Collects the template instantiated by each component in the given
+/// statement. If a component is assigned more than one template, the first
+/// instantiation is used.
+fn collect_instantiations(
+    stmt: &Statement,
+    templates: &TemplateInfo,
+    components: &mut HashMap<String, String>,
+) {
+    use Statement::*;
+    match stmt {
+        IfThenElse { if_case, else_case, .. } => {
+            collect_instantiations(if_case, templates, components);
+            if let Some(else_case) = else_case {
+                collect_instantiations(else_case, templates, components);
+            }
+        }
+        While { stmt, .. } => collect_instantiations(stmt, templates, components),
+        InitializationBlock { initializations: stmts, .. } | Block { stmts, .. } => {
+            for stmt in stmts {
+                collect_instantiations(stmt, templates, components);
+            }
+        }
+        Substitution { var, op: AssignOp::AssignVar, rhe, .. } => {
+            let rhe = match rhe {
+                Expression::ParallelOp { rhe, .. } => rhe,
+                rhe => rhe,
+            };
+            if let Expression::Call { id, .. } = rhe {
+                if templates.contains_key(id) {
+                    components.entry(var.clone()).or_insert_with(|| id.clone());
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint_dig::BigInt;
+    use parser::{parse_source, ParseResult};
+    use program_structure::r1cs::Constraint;
+    use program_structure::report_code::ReportCode;
+    use program_structure::sym_file::parse_symbols;
+
+    use super::*;
+
+    const SOURCE: &str = r#"
+            template Inner() {
+                signal input in;
+                signal output out;
+                signal tmp;
+                out <-- in;
+                tmp <-- in;
+            }
+
+            template Main() {
+                signal input in;
+                signal output out;
+                component inner[2];
+                for (var i = 0; i < 2; i++) {
+                    inner[i] = Inner();
+                    inner[i].in <== in;
+                }
+                out <== inner[0].out + inner[1].out;
+            }
+
+            component main = Main();
+        "#;
+
+    // The wires are `1`, `main.out`, `main.in`, `main.inner[0].out`,
+    // `main.inner[1].out`, and `main.inner[0].tmp`. The second `tmp` signal
+    // and the component inputs were removed by the optimizer.
+    const SYMBOLS: &str = "1,1,0,main.out\n2,2,0,main.in\n3,3,1,main.inner[0].out\n\
+        4,-1,1,main.inner[0].in\n5,5,1,main.inner[0].tmp\n6,4,2,main.inner[1].out\n\
+        7,-1,2,main.inner[1].in\n8,-1,2,main.inner[1].tmp\n";
+
+    #[test]
+    fn test_annotate_reports() {
+        let templates = parse_templates();
+        let symbols = parse_symbols(SYMBOLS).unwrap();
+        let mapping = SymbolMapping::new(&symbols, "Main", &templates);
+
+        let mut reports = ReportCollection::new();
+        for signal_name in ["tmp", "unknown"] {
+            let mut report = Report::warning(String::new(), ReportCode::UnconstrainedSignal);
+            report.add_affected_signal("Inner", signal_name);
+            reports.push(report);
+        }
+        annotate_reports(&mut reports, &mapping);
+        assert_eq!(
+            reports[0].notes(),
+            &["Compiled signal(s): `main.inner[0].tmp` (signal 5, witness 5, component 1 at `main.inner[0]`), `main.inner[1].tmp` (signal 8, removed by the optimizer, component 2 at `main.inner[1]`)."]
+        );
+        assert_eq!(reports[0].affected_signals()[0].compiled_signals.len(), 2);
+        assert!(reports[1].notes().is_empty());
+    }
+
+    #[test]
+    fn test_cross_check_reports() {
+        let templates = parse_templates();
+        let symbols = parse_symbols(SYMBOLS).unwrap();
+        let one = BigInt::from(1);
+        let constraint = Constraint {
+            a: Vec::new(),
+            b: Vec::new(),
+            c: vec![(1, one.clone()), (3, one.clone()), (4, one)],
+        };
+        let r1cs = R1CS {
+            prime: BigInt::from(23),
+            wires: 6,
+            public_outputs: 1,
+            public_inputs: 0,
+            private_inputs: 1,
+            labels: 8,
+            constraints: vec![constraint],
+            wire_to_label: vec![0, 1, 2, 3, 6, 5],
+        };
+        let circuit = CompiledCircuit { name: "main.r1cs".to_string(), r1cs, symbols };
+        let mapping = CircuitMapping::new(&circuit, "Main", &templates);
+        assert_eq!(mapping.symbols().find_symbols("Inner", "out").count(), 2);
+        assert_eq!(mapping.symbols().find_symbols("Main", "inner.out").count(), 2);
+        assert_eq!(mapping.symbols().find_symbols("Main", "tmp").count(), 0);
+
+        let mut reports = ReportCollection::new();
+        for signal_name in ["out", "tmp", "unknown"] {
+            let mut report = Report::warning(String::new(), ReportCode::UnconstrainedSignal);
+            report.add_affected_signal("Inner", signal_name);
+            reports.push(report);
+        }
+        cross_check_reports(&mut reports, &mapping);
+        let categories = reports.iter().map(|report| *report.category()).collect::<Vec<_>>();
+        assert_eq!(
+            categories,
+            [MessageCategory::Info, MessageCategory::Error, MessageCategory::Warning]
+        );
+        assert_eq!(
+            reports[1].notes(),
+            &["Confirmed by `main.r1cs`: the compiled signal(s) `main.inner[0].tmp` do not occur in any constraint."]
+        );
+        assert!(reports[2].notes().is_empty());
+    }
+    fn parse_templates() -> TemplateInfo {
+        let ParseResult::Program(program, _) =
+            parse_source("main.circom", SOURCE, &[], &[], "2.1.0")
+        else {
+            panic!("failed to parse program");
+        };
+        program.templates
+    }
+}
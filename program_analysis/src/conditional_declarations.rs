@@ -0,0 +1,194 @@
+use log::debug;
+use std::collections::HashSet;
+
+use program_structure::cfg::{Cfg, DefinitionType};
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::value_meta::ValueMeta;
+use program_structure::ir::*;
+
+use crate::conditional_instantiation::depends_on_signals;
+use crate::loop_bounds::loop_headers;
+use crate::taint_analysis::run_taint_analysis;
+
+pub struct ConditionalSignalDeclarationError {
+    signal_name: VariableName,
+    is_loop: bool,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+    cond_file_id: Option<FileID>,
+    cond_file_location: FileLocation,
+}
+
+impl ConditionalSignalDeclarationError {
+    pub fn into_report(self) -> Report {
+        let statement = if self.is_loop { "loop" } else { "if-statement" };
+        let mut report = Report::error(
+            format!(
+                "The signal `{}` is declared in the body of a {statement} with a condition which is unknown at compile time.",
+                self.signal_name
+            ),
+            ReportCode::ConditionalSignalDeclaration,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                format!("The signal `{}` is declared here.", self.signal_name),
+            );
+        }
+        if let Some(file_id) = self.cond_file_id {
+            report.add_secondary(
+                self.cond_file_location,
+                file_id,
+                Some("This condition depends on the value of a signal.".to_string()),
+            );
+        }
+        report.add_note(
+            "The signals of a template must be known when constraints are generated, which means that signals can only be declared at the top level of the template, or under conditions which are known at compile time.".to_string()
+        );
+        report.add_help(format!(
+            "Consider moving the declaration of `{}` to the top level of the template.",
+            self.signal_name
+        ));
+        report
+    }
+}
+
+/// This analysis pass reports signal declarations in the body of if-statements
+/// and loops with conditions depending on signals. Since the signals of a
+/// template must be known when constraints are generated, these declarations
+/// are rejected by the compiler.
+pub fn find_conditional_signal_declarations(cfg: &Cfg) -> ReportCollection {
+    debug!("running conditional signal declaration analysis pass");
+    let mut reports = ReportCollection::new();
+    if !matches!(cfg.definition_type(), DefinitionType::Template | DefinitionType::CustomTemplate) {
+        return reports;
+    }
+    let loop_headers = loop_headers(cfg);
+    let mut taint_analysis = None;
+    let mut reported = HashSet::new();
+    for header in cfg.iter() {
+        let Some(Statement::IfThenElse { cond, .. }) = header.statements().last() else {
+            continue;
+        };
+        if cond.value().is_some() {
+            continue;
+        }
+        let is_loop = loop_headers.contains(&header.index());
+        let mut body = cfg.get_true_branch(header);
+        if !is_loop {
+            body.extend(cfg.get_false_branch(header));
+        }
+        let declarations = body
+            .iter()
+            .flat_map(|basic_block| basic_block.iter())
+            .filter_map(|stmt| match stmt {
+                Statement::Declaration {
+                    meta, names, var_type: VariableType::Signal(..), ..
+                } => Some((meta, names)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if declarations.is_empty() {
+            continue;
+        }
+        let taint_analysis = taint_analysis.get_or_insert_with(|| run_taint_analysis(cfg));
+        if !depends_on_signals(cfg, taint_analysis, cond) {
+            continue;
+        }
+        for (meta, names) in declarations {
+            for name in names.iter() {
+                if reported.insert(name.clone()) {
+                    reports.push(build_report(name, is_loop, meta, cond.meta()));
+                }
+            }
+        }
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+fn build_report(name: &VariableName, is_loop: bool, meta: &Meta, cond_meta: &Meta) -> Report {
+    ConditionalSignalDeclarationError {
+        signal_name: name.clone(),
+        is_loop,
+        file_id: meta.file_id(),
+        file_location: meta.file_location(),
+        cond_file_id: cond_meta.file_id(),
+        cond_file_location: cond_meta.file_location(),
+    }
+    .into_report()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_conditional_signal_declarations() {
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                if (in == 0) {
+                    signal tmp;
+                    tmp <== in * in;
+                    out <== tmp;
+                } else {
+                    out <== in;
+                }
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                var n = in;
+                for (var i = 0; i < n; i++) {
+                    signal tmp;
+                    tmp <== in * in;
+                }
+                out <== in;
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            template T(n) {
+                signal input in;
+                signal output out;
+                if (n > 0) {
+                    signal tmp;
+                    tmp <== in * in;
+                    out <== tmp;
+                } else {
+                    out <== in;
+                }
+            }
+        "#;
+        validate_reports(src, 0);
+    }
+
+    fn validate_reports(src: &str, expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_conditional_signal_declarations(&cfg);
+        assert_eq!(reports.len(), expected_len);
+    }
+}
@@ -0,0 +1,396 @@
+use log::debug;
+use std::collections::HashSet;
+
+use program_structure::cfg::{BasicBlock, Cfg, DefinitionType, Index};
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::value_meta::ValueMeta;
+use program_structure::ir::variable_meta::VariableMeta;
+use program_structure::ir::*;
+
+use crate::loop_bounds::loop_headers;
+use crate::taint_analysis::{run_taint_analysis, TaintAnalysis};
+
+pub struct SignalDependentInstantiationError {
+    component_name: VariableName,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+    cond_file_id: Option<FileID>,
+    cond_file_location: FileLocation,
+}
+
+impl SignalDependentInstantiationError {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::error(
+            format!(
+                "The component `{}` is instantiated under a condition which is unknown at compile time.",
+                self.component_name
+            ),
+            ReportCode::SignalDependentInstantiation,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                format!("The component `{}` is instantiated here.", self.component_name),
+            );
+        }
+        if let Some(file_id) = self.cond_file_id {
+            report.add_secondary(
+                self.cond_file_location,
+                file_id,
+                Some("This condition depends on the value of a signal.".to_string()),
+            );
+        }
+        report.add_note(
+            "The components of a template must be known when constraints are generated, which means that components cannot be instantiated under conditions depending on signal values.".to_string()
+        );
+        report.add_help(
+            "Consider instantiating the component unconditionally and selecting the output using a multiplexer.".to_string()
+        );
+        report
+    }
+}
+
+pub struct PartialInstantiationWarning {
+    component_name: VariableName,
+    inputs: Vec<VariableName>,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+    cond_file_id: Option<FileID>,
+    cond_file_location: FileLocation,
+}
+
+impl PartialInstantiationWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            format!(
+                "The component `{}` is only instantiated in one branch of an if-statement.",
+                self.component_name
+            ),
+            ReportCode::PartialConditionalInstantiation,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                format!("The component `{}` is instantiated here.", self.component_name),
+            );
+        }
+        if let Some(file_id) = self.cond_file_id {
+            report.add_secondary(
+                self.cond_file_location,
+                file_id,
+                Some("The branch taken depends on this condition.".to_string()),
+            );
+        }
+        let inputs =
+            self.inputs.iter().map(|input| format!("`{input}`")).collect::<Vec<_>>().join(", ");
+        report.add_note(format!(
+            "The input signal(s) {inputs} are only constrained by this branch, and remain unconstrained if the other branch is taken."
+        ));
+        report
+    }
+}
+
+/// A component instantiation of the form `c = T(...)` or `c[i] = T(...)`.
+struct Instantiation<'a> {
+    meta: &'a Meta,
+    component_name: VariableName,
+}
+
+/// This analysis pass inspects each if-statement (which is not a loop
+/// condition) with a non-constant condition, and reports
+///
+///   1. component instantiations under conditions depending on signals,
+///      which are rejected by the compiler, and
+///   2. components which are only instantiated in one branch of an
+///      if-statement, if some input signals of the template are only
+///      constrained by the branch instantiating the component.
+pub fn find_conditional_instantiations(cfg: &Cfg) -> ReportCollection {
+    debug!("running conditional instantiation analysis pass");
+    let mut reports = ReportCollection::new();
+    if !matches!(cfg.definition_type(), DefinitionType::Template | DefinitionType::CustomTemplate) {
+        return reports;
+    }
+    let loop_headers = loop_headers(cfg);
+    let mut taint_analysis = None;
+    let mut reported = HashSet::new();
+    for header in cfg.iter() {
+        let Some(Statement::IfThenElse { cond, .. }) = header.statements().last() else {
+            continue;
+        };
+        if cond.value().is_some() || loop_headers.contains(&header.index()) {
+            continue;
+        }
+        let true_branch = cfg.get_true_branch(header);
+        let false_branch = cfg.get_false_branch(header);
+        let true_instantiations = instantiations(&true_branch);
+        let false_instantiations = instantiations(&false_branch);
+        if true_instantiations.is_empty() && false_instantiations.is_empty() {
+            continue;
+        }
+        let taint_analysis = taint_analysis.get_or_insert_with(|| run_taint_analysis(cfg));
+        if depends_on_signals(cfg, taint_analysis, cond) {
+            for instantiation in true_instantiations.iter().chain(false_instantiations.iter()) {
+                if reported.insert(instantiation.meta.location.clone()) {
+                    reports.push(build_signal_dependent_report(instantiation, cond.meta()));
+                }
+            }
+            continue;
+        }
+        for (branch, instantiations, other_instantiations) in [
+            (&true_branch, &true_instantiations, &false_instantiations),
+            (&false_branch, &false_instantiations, &true_instantiations),
+        ] {
+            let other_components = other_instantiations
+                .iter()
+                .map(|instantiation| &instantiation.component_name)
+                .collect::<HashSet<_>>();
+            for instantiation in instantiations {
+                if other_components.contains(&instantiation.component_name) {
+                    continue;
+                }
+                let inputs = unconstrained_inputs(cfg, branch, &instantiation.component_name);
+                if !inputs.is_empty() && reported.insert(instantiation.meta.location.clone()) {
+                    reports.push(build_partial_instantiation_report(
+                        instantiation,
+                        inputs,
+                        cond.meta(),
+                    ));
+                }
+            }
+        }
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+/// Returns the component instantiations in the given basic blocks.
+fn instantiations<'a>(basic_blocks: &[&'a BasicBlock]) -> Vec<Instantiation<'a>> {
+    let mut instantiations = Vec::new();
+    for basic_block in basic_blocks {
+        for stmt in basic_block.iter() {
+            let Statement::Substitution { meta, var, rhe, .. } = stmt else {
+                continue;
+            };
+            if !meta.type_knowledge().is_component() {
+                continue;
+            }
+            // Array element instantiations are represented using update
+            // expressions.
+            let rhe = match rhe {
+                Expression::Update { access, rhe, .. }
+                    if access.iter().all(|access| matches!(access, AccessType::ArrayAccess(_))) =>
+                {
+                    rhe
+                }
+                rhe => rhe,
+            };
+            if matches!(rhe, Expression::Call { .. }) {
+                instantiations.push(Instantiation { meta, component_name: var.without_version() });
+            }
+        }
+    }
+    instantiations
+}
+
+/// Returns true if the expression reads a signal, or a variable which
+/// depends on a signal.
+pub(crate) fn depends_on_signals(
+    cfg: &Cfg,
+    taint_analysis: &TaintAnalysis,
+    expr: &Expression,
+) -> bool {
+    if !expr.signals_read().is_empty() {
+        return true;
+    }
+    let locals =
+        expr.locals_read().iter().map(|var_use| var_use.name().clone()).collect::<HashSet<_>>();
+    cfg.variables()
+        .filter(|name| matches!(cfg.get_type(name), Some(VariableType::Signal(..))))
+        .any(|signal| taint_analysis.taints_any(signal, &locals))
+}
+
+/// Returns the input signals (of the current template) which flow into the
+/// given component in the given branch, and are not constrained outside the
+/// branch.
+fn unconstrained_inputs(
+    cfg: &Cfg,
+    branch: &[&BasicBlock],
+    component_name: &VariableName,
+) -> Vec<VariableName> {
+    let branch_indices =
+        branch.iter().map(|basic_block| basic_block.index()).collect::<HashSet<Index>>();
+    let mut inputs = HashSet::new();
+    for basic_block in branch {
+        for stmt in basic_block.iter() {
+            if let Statement::Substitution { var, .. } = stmt {
+                if var.without_version() == *component_name {
+                    inputs.extend(
+                        stmt.signals_read()
+                            .iter()
+                            .map(|var_use| var_use.name().without_version())
+                            .filter(|name| is_input_signal(cfg, name)),
+                    );
+                }
+            }
+        }
+    }
+    let constrained = cfg
+        .iter()
+        .filter(|basic_block| !branch_indices.contains(&basic_block.index()))
+        .flat_map(|basic_block| basic_block.iter())
+        .filter(|stmt| {
+            matches!(
+                stmt,
+                Statement::Substitution { op: AssignOp::AssignConstraintSignal, .. }
+                    | Statement::ConstraintEquality { .. }
+            )
+        })
+        .flat_map(|stmt| stmt.signals_read().iter().map(|var_use| var_use.name().without_version()))
+        .collect::<HashSet<_>>();
+    let mut inputs =
+        inputs.into_iter().filter(|input| !constrained.contains(input)).collect::<Vec<_>>();
+    inputs.sort_by_key(ToString::to_string);
+    inputs
+}
+
+fn is_input_signal(cfg: &Cfg, name: &VariableName) -> bool {
+    matches!(cfg.get_type(name), Some(VariableType::Signal(SignalType::Input, _)))
+}
+
+fn build_signal_dependent_report(instantiation: &Instantiation, cond_meta: &Meta) -> Report {
+    SignalDependentInstantiationError {
+        component_name: instantiation.component_name.clone(),
+        file_id: instantiation.meta.file_id(),
+        file_location: instantiation.meta.file_location(),
+        cond_file_id: cond_meta.file_id(),
+        cond_file_location: cond_meta.file_location(),
+    }
+    .into_report()
+}
+
+fn build_partial_instantiation_report(
+    instantiation: &Instantiation,
+    inputs: Vec<VariableName>,
+    cond_meta: &Meta,
+) -> Report {
+    PartialInstantiationWarning {
+        component_name: instantiation.component_name.clone(),
+        inputs,
+        file_id: instantiation.meta.file_id(),
+        file_location: instantiation.meta.file_location(),
+        cond_file_id: cond_meta.file_id(),
+        cond_file_location: cond_meta.file_location(),
+    }
+    .into_report()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_signal_dependent_instantiation() {
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                component c;
+                if (in == 0) {
+                    c = IsZero();
+                } else {
+                    c = Num2Bits(8);
+                }
+                c.in <== in;
+                out <== c.out;
+            }
+        "#;
+        validate_reports(src, 2);
+
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                component c;
+                var x = in + 1;
+                if (x > 0) {
+                    c = IsZero();
+                    c.in <== in;
+                    out <== c.out;
+                }
+            }
+        "#;
+        validate_reports(src, 1);
+    }
+
+    #[test]
+    fn test_partial_instantiation() {
+        let src = r#"
+            template T(n) {
+                signal input in;
+                signal output out;
+                component c;
+                if (n > 0) {
+                    c = IsZero();
+                    c.in <== in;
+                    out <== c.out;
+                } else {
+                    out <== 0;
+                }
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            template T(n) {
+                signal input in;
+                signal output out;
+                component c;
+                if (n > 0) {
+                    c = IsZero();
+                    c.in <== in;
+                    out <== c.out;
+                } else {
+                    out <== in;
+                }
+            }
+        "#;
+        validate_reports(src, 0);
+
+        let src = r#"
+            template T(n) {
+                signal input in[n];
+                signal output out[n];
+                component c[n];
+                for (var i = 0; i < n; i++) {
+                    c[i] = IsZero();
+                    c[i].in <== in[i];
+                    out[i] <== c[i].out;
+                }
+            }
+        "#;
+        validate_reports(src, 0);
+    }
+
+    fn validate_reports(src: &str, expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_conditional_instantiations(&cfg);
+        assert_eq!(reports.len(), expected_len);
+    }
+}
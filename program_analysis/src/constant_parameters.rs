@@ -0,0 +1,85 @@
+use log::debug;
+use num_bigint::BigInt;
+use std::collections::BTreeSet;
+
+use program_structure::report::ReportCollection;
+use program_structure::report_code::ReportCode;
+
+use crate::side_effect_analysis::unused_parameter_message;
+
+/// Parameters which are never read are reported by the side-effect analysis
+/// pass. If the parameter is also passed the same constant value by every
+/// instance of the template in the project, the parameter is redundant. This
+/// function adds a suggestion to drop the parameter to the corresponding
+/// reports, given the names of the template parameters and the (distinct)
+/// arguments passed to each instance of the template. Unknown arguments are
+/// given as `None`.
+pub fn annotate_constant_parameters(
+    reports: &mut ReportCollection,
+    template_name: &str,
+    params: &[String],
+    instances: &BTreeSet<Vec<Option<BigInt>>>,
+) {
+    debug!("checking for constant unused parameters in `{template_name}`");
+    for (index, param) in params.iter().enumerate() {
+        let Some(value) = constant_argument(index, instances) else {
+            continue;
+        };
+        let message = unused_parameter_message(param);
+        for report in reports.iter_mut() {
+            if !matches!(report.code(), ReportCode::UnusedParameterValue)
+                || report.message() != &message
+            {
+                continue;
+            }
+            report.add_note(format!(
+                "Every instance of `{template_name}` passes the same value `{value}` for `{param}`."
+            ));
+            report.add_help(format!(
+                "Consider removing the parameter `{param}` from `{template_name}`, and using the constant `{value}` directly if it is needed."
+            ));
+        }
+    }
+}
+
+/// Returns the argument passed at the given index if it is known and the same
+/// for all instances.
+fn constant_argument(index: usize, instances: &BTreeSet<Vec<Option<BigInt>>>) -> Option<&BigInt> {
+    let mut values = instances.iter().map(|args| args.get(index).and_then(Option::as_ref));
+    let value = values.next()??;
+    values.all(|other| other == Some(value)).then_some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use program_structure::report::Report;
+
+    use super::*;
+
+    #[test]
+    fn test_constant_parameters() {
+        let report =
+            || Report::warning(unused_parameter_message(&"n"), ReportCode::UnusedParameterValue);
+        let params = vec!["n".to_string(), "m".to_string()];
+        let args = |n: Option<u32>, m: u32| vec![n.map(BigInt::from), Some(BigInt::from(m))];
+
+        // `n` is always passed the value 3.
+        let mut reports = vec![report()];
+        let instances = BTreeSet::from([args(Some(3), 1), args(Some(3), 2)]);
+        annotate_constant_parameters(&mut reports, "A", &params, &instances);
+        assert_eq!(reports[0].notes().len(), 1);
+        assert!(reports[0].help()[0].contains("the constant `3`"));
+
+        // `n` is passed different or unknown values.
+        for instances in [
+            BTreeSet::from([args(Some(3), 1), args(Some(4), 1)]),
+            BTreeSet::from([args(Some(3), 1), args(None, 2)]),
+            BTreeSet::new(),
+        ] {
+            let mut reports = vec![report()];
+            annotate_constant_parameters(&mut reports, "A", &params, &instances);
+            assert!(reports[0].notes().is_empty());
+            assert!(reports[0].help().is_empty());
+        }
+    }
+}
@@ -5,7 +5,9 @@ use program_structure::cfg::Cfg;
 use program_structure::intermediate_representation::variable_meta::VariableMeta;
 use program_structure::intermediate_representation::AssignOp;
 use program_structure::ir::variable_meta::VariableUse;
-use program_structure::ir::{Statement, VariableName};
+use program_structure::ir::{Expression, Statement, VariableName, VariableType};
+
+use crate::array_elements::ArrayElements;
 
 /// This analysis computes the transitive closure of the constraint relation.
 /// (Note that the resulting relation will not be reflexive in general.)
@@ -14,6 +16,7 @@ pub struct ConstraintAnalysis {
     constraint_map: HashMap<VariableName, HashSet<VariableName>>,
     declarations: HashMap<VariableName, VariableUse>,
     definitions: HashMap<VariableName, VariableUse>,
+    constrained_elements: HashMap<VariableName, ArrayElements>,
 }
 
 impl ConstraintAnalysis {
@@ -63,6 +66,18 @@ impl ConstraintAnalysis {
         sinks.insert(sink.clone());
     }
 
+    /// Add the elements accessed by the variable use to the set of
+    /// constrained elements.
+    fn add_constrained_element(&mut self, var: &VariableUse) {
+        self.constrained_elements.entry(var.name().clone()).or_default().insert(var.access());
+    }
+
+    /// Returns the elements of the given variable which occur in a constraint
+    /// together with at least one other variable.
+    pub fn constrained_elements(&self, var: &VariableName) -> Option<&ArrayElements> {
+        self.constrained_elements.get(var)
+    }
+
     /// Returns variables constrained in a single step by `source`.
     pub fn single_step_constraint(&self, source: &VariableName) -> HashSet<VariableName> {
         self.constraint_map.get(source).cloned().unwrap_or_default()
@@ -96,6 +111,7 @@ pub fn run_constraint_analysis(cfg: &Cfg) -> ConstraintAnalysis {
     let mut result = ConstraintAnalysis::new();
 
     use AssignOp::*;
+    use Expression::*;
     use Statement::*;
     for basic_block in cfg.iter() {
         for stmt in basic_block.iter() {
@@ -123,6 +139,18 @@ pub fn run_constraint_analysis(cfg: &Cfg) -> ConstraintAnalysis {
                                 result.add_constraint_step(source.name(), sink.name());
                             }
                         }
+                        // The array updated by a signal assignment `x[i] <== ...` is
+                        // only read to construct the new array, so we ignore it here.
+                        let is_update_base = matches!(
+                            stmt,
+                            Substitution { var, rhe: Update { .. }, .. }
+                                if var == source.name() && source.access().is_empty()
+                        );
+                        if !is_update_base
+                            && stmt.variables_used().any(|sink| source.name() != sink.name())
+                        {
+                            result.add_constrained_element(source);
+                        }
                     }
                 }
                 _ => {}
@@ -131,3 +159,69 @@ pub fn run_constraint_analysis(cfg: &Cfg) -> ConstraintAnalysis {
     }
     result
 }
+
+/// Returns a map from each signal and component occurring in a constraint to
+/// the signals and components it is constrained together with in a single
+/// step. Local variables occurring in a constraint are replaced by the signals
+/// and components they are computed from. This ensures that constraints like
+/// `lc === in`, where `lc` accumulates the signals `out[i]` in a loop, relate
+/// `out` to `in`.
+pub fn run_signal_constraint_analysis(cfg: &Cfg) -> HashMap<VariableName, HashSet<VariableName>> {
+    use AssignOp::*;
+    use Statement::*;
+    // Declarations of local variables may be versioned, so any variable which
+    // is not a signal or a component is treated as a local variable.
+    let is_local = |name: &VariableName| {
+        !matches!(cfg.get_type(name), Some(VariableType::Signal(..) | VariableType::Component))
+    };
+    let mut dependencies = HashMap::<VariableName, HashSet<VariableName>>::new();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            if let Substitution { var, .. } = stmt {
+                if is_local(var) {
+                    dependencies
+                        .entry(var.clone())
+                        .or_default()
+                        .extend(stmt.variables_read().map(|var_use| var_use.name().clone()));
+                }
+            }
+        }
+    }
+    let local_sources = |var: &VariableName| {
+        let mut sources = HashSet::new();
+        let mut visited = HashSet::from([var.clone()]);
+        let mut worklist = vec![var.clone()];
+        while let Some(var) = worklist.pop() {
+            if !is_local(&var) {
+                sources.insert(var);
+                continue;
+            }
+            for dependency in dependencies.get(&var).into_iter().flatten() {
+                if visited.insert(dependency.clone()) {
+                    worklist.push(dependency.clone());
+                }
+            }
+        }
+        sources
+    };
+    let mut constraints = HashMap::<VariableName, HashSet<VariableName>>::new();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            if !matches!(
+                stmt,
+                ConstraintEquality { .. } | Substitution { op: AssignConstraintSignal, .. }
+            ) {
+                continue;
+            }
+            let constrained = stmt
+                .variables_used()
+                .flat_map(|var_use| local_sources(var_use.name()))
+                .collect::<HashSet<_>>();
+            for source in &constrained {
+                let sinks = constraints.entry(source.clone()).or_default();
+                sinks.extend(constrained.iter().filter(|sink| *sink != source).cloned());
+            }
+        }
+    }
+    constraints
+}
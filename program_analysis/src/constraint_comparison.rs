@@ -0,0 +1,181 @@
+use log::debug;
+
+use program_structure::cfg::{Cfg, DefinitionType};
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::variable_meta::VariableMeta;
+use program_structure::ir::*;
+
+pub struct ConstraintComparisonWarning {
+    op: ExpressionInfixOpcode,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl ConstraintComparisonWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            "Comparison operators are evaluated during witness generation and the result is not constrained.".to_string(),
+            ReportCode::ConstraintComparison,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                format!("The comparison `{}` depends on the value of a signal.", self.op),
+            );
+        }
+        use ExpressionInfixOpcode::*;
+        let help = match self.op {
+            Eq | NotEq => {
+                "Consider using the Circomlib templates `IsZero` or `IsEqual` to constrain the result."
+            }
+            _ => "Consider using a Circomlib comparator like `LessThan` to constrain the result.",
+        };
+        report.add_help(help.to_string());
+        report
+    }
+}
+
+/// Comparisons like `a < b` do not correspond to arithmetic constraints. If a
+/// comparison over signals is used in a constraint, like `out <== a < b`, the
+/// relation between the result and the compared signals is not enforced by the
+/// circuit. Comparisons over signals should use range-checked comparator
+/// templates like `LessThan` from Circomlib.
+pub fn find_constraint_comparisons(cfg: &Cfg) -> ReportCollection {
+    use DefinitionType::*;
+    if matches!(cfg.definition_type(), Function | CustomTemplate) {
+        // Exit early if this is a function or custom template.
+        return ReportCollection::new();
+    }
+    debug!("running constraint comparison analysis pass");
+    let mut reports = ReportCollection::new();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            visit_statement(stmt, &mut reports);
+        }
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+fn visit_statement(stmt: &Statement, reports: &mut ReportCollection) {
+    use AssignOp::*;
+    use Expression::Update;
+    use Statement::*;
+    match stmt {
+        Substitution { op: AssignConstraintSignal, rhe, .. } => {
+            // Assignments to array elements are lifted to updates.
+            let rhe = if let Update { rhe, .. } = rhe { rhe.as_ref() } else { rhe };
+            visit_expression(rhe, reports);
+        }
+        ConstraintEquality { lhe, rhe, .. } => {
+            visit_expression(lhe, reports);
+            visit_expression(rhe, reports);
+        }
+        _ => {}
+    }
+}
+
+fn visit_expression(expr: &Expression, reports: &mut ReportCollection) {
+    use Expression::*;
+    match expr {
+        InfixOp { meta, infix_op, .. } if is_comparison_op(infix_op) && reads_signals(expr) => {
+            reports.push(build_report(meta, infix_op));
+        }
+        InfixOp { lhe, rhe, .. } => {
+            visit_expression(lhe, reports);
+            visit_expression(rhe, reports);
+        }
+        PrefixOp { rhe, .. } => {
+            visit_expression(rhe, reports);
+        }
+        SwitchOp { if_true, if_false, .. } => {
+            visit_expression(if_true, reports);
+            visit_expression(if_false, reports);
+        }
+        InlineArray { values, .. } => {
+            for value in values {
+                visit_expression(value, reports);
+            }
+        }
+        Call { .. }
+        | Access { .. }
+        | Update { .. }
+        | Number(_, _)
+        | Variable { .. }
+        | Phi { .. } => {}
+    }
+}
+
+fn is_comparison_op(op: &ExpressionInfixOpcode) -> bool {
+    use ExpressionInfixOpcode::*;
+    matches!(op, Lesser | Greater | LesserEq | GreaterEq | Eq | NotEq)
+}
+
+fn reads_signals(expr: &Expression) -> bool {
+    !expr.signals_read().is_empty() || !expr.components_read().is_empty()
+}
+
+fn build_report(meta: &Meta, op: &ExpressionInfixOpcode) -> Report {
+    ConstraintComparisonWarning {
+        op: *op,
+        file_id: meta.file_id(),
+        file_location: meta.file_location(),
+    }
+    .into_report()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_constraint_comparison() {
+        let src = r#"
+            template T(n) {
+                signal input a;
+                signal input b;
+                signal output out[3];
+
+                out[0] <== a < b;
+                out[1] <== (n > 2) * a;
+                (a == b) === out[2];
+            }
+        "#;
+        validate_reports(src, 2);
+
+        let src = r#"
+            template T() {
+                signal input a;
+                signal input b;
+                signal output out;
+
+                out <-- a < b;
+                out * (out - 1) === 0;
+            }
+        "#;
+        validate_reports(src, 0);
+    }
+
+    fn validate_reports(src: &str, expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_constraint_comparisons(&cfg);
+
+        assert_eq!(reports.len(), expected_len);
+    }
+}
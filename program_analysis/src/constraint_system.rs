@@ -0,0 +1,848 @@
+//! Lowers the constraints of a template instance (with concrete parameter
+//! values) to a system of polynomial equations over the prime field. Loops
+//! and branches are unrolled by interpreting the template body, so loop
+//! bounds and branch conditions must only depend on the template parameters.
+//! Signals of sub-components are treated as free variables, which means that
+//! the constraint system only captures the constraints generated by the
+//! template itself.
+use std::collections::HashSet;
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+
+use anyhow::{anyhow, bail, Result};
+use log::debug;
+use num_bigint::BigInt;
+use num_traits::{One, ToPrimitive, Zero};
+
+use circom_algebra::modular_arithmetic;
+use program_structure::ast::{
+    Access, AssignOp, Expression, ExpressionInfixOpcode, ExpressionPrefixOpcode, SignalType,
+    Statement, VariableType,
+};
+use program_structure::constants::{Curve, UsefulConstants};
+use program_structure::function_data::FunctionInfo;
+use program_structure::template_data::TemplateInfo;
+
+/// The maximum number of statements executed before the lowering gives up.
+const MAX_STEPS: usize = 10_000_000;
+
+/// The maximum depth of nested function calls.
+const MAX_DEPTH: usize = 128;
+
+/// The maximum number of elements of a single array.
+const MAX_ARRAY_SIZE: usize = 1 << 20;
+
+/// The kind of a signal in the constraint system.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SignalKind {
+    Input,
+    Output,
+    Intermediate,
+    /// A signal of a sub-component (like `n2b.out[0]`).
+    Component,
+}
+
+impl fmt::Display for SignalKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use SignalKind::*;
+        match self {
+            Input => write!(f, "input"),
+            Output => write!(f, "output"),
+            Intermediate => write!(f, "intermediate"),
+            Component => write!(f, "component"),
+        }
+    }
+}
+
+/// A signal of the constraint system. Array signals are represented by one
+/// signal per element (like `in[0]`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Signal {
+    pub name: String,
+    pub kind: SignalKind,
+}
+
+/// A polynomial over the signals of the constraint system. Constants are
+/// always reduced modulo the prime.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Term {
+    Constant(BigInt),
+    Signal(String),
+    Add(Box<Term>, Box<Term>),
+    Mul(Box<Term>, Box<Term>),
+    Neg(Box<Term>),
+}
+
+impl Term {
+    fn to_constant(&self) -> Option<&BigInt> {
+        match self {
+            Term::Constant(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the term in SMT-LIB syntax.
+    #[must_use]
+    pub fn to_smt_lib(&self) -> String {
+        use Term::*;
+        match self {
+            Constant(value) => format!("(as ff{value} F)"),
+            Signal(name) => format!("|{name}|"),
+            Add(lhs, rhs) => {
+                format!("(ff.add {} {})", lhs.to_smt_lib(), rhs.to_smt_lib())
+            }
+            Mul(lhs, rhs) => {
+                format!("(ff.mul {} {})", lhs.to_smt_lib(), rhs.to_smt_lib())
+            }
+            Neg(value) => format!("(ff.neg {})", value.to_smt_lib()),
+        }
+    }
+}
+
+/// An equation `lhs = rhs` generated by a `<==` or `===` statement.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Constraint {
+    pub lhs: Term,
+    pub rhs: Term,
+}
+
+/// The constraints generated by a single template instance.
+#[derive(Clone, Debug)]
+pub struct ConstraintSystem {
+    pub template_name: String,
+    pub args: Vec<BigInt>,
+    pub prime: BigInt,
+    /// The signals of the template instance in declaration order, followed
+    /// by the sub-component signals in order of first use.
+    pub signals: Vec<Signal>,
+    pub constraints: Vec<Constraint>,
+    /// The signals assigned using `<--`. The values of these signals are only
+    /// restricted by the constraints.
+    pub hints: Vec<String>,
+}
+
+impl ConstraintSystem {
+    /// Returns the constraint system in SMT-LIB format, using the theory of
+    /// finite fields (supported by e.g. cvc5). If `uniqueness` is set, the
+    /// constraints are duplicated for a second assignment to all signals
+    /// except the inputs, together with an assertion that some output
+    /// differs between the two assignments. The query is then unsatisfiable
+    /// if and only if the outputs are uniquely determined by the inputs
+    /// (assuming that sub-component signals are unconstrained).
+    #[must_use]
+    pub fn to_smt_lib(&self, uniqueness: bool) -> String {
+        let args = self.args.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
+        let mut smt = String::new();
+        let _ = writeln!(smt, "; The constraint system of `{}({args})`.", self.template_name);
+        let _ = writeln!(smt, "(set-logic QF_FF)");
+        let _ = writeln!(smt, "(define-sort F () (_ FiniteField {}))", self.prime);
+        let declare = |smt: &mut String, suffix: &str, skip_inputs: bool| {
+            for signal in &self.signals {
+                if !(skip_inputs && signal.kind == SignalKind::Input) {
+                    let _ = writeln!(
+                        smt,
+                        "(declare-const |{}{suffix}| F) ; {}",
+                        signal.name, signal.kind
+                    );
+                }
+            }
+        };
+        declare(&mut smt, "", false);
+        for hint in &self.hints {
+            let _ = writeln!(smt, "; `{hint}` is assigned using `<--`.");
+        }
+        for constraint in &self.constraints {
+            let _ = writeln!(
+                smt,
+                "(assert (= {} {}))",
+                constraint.lhs.to_smt_lib(),
+                constraint.rhs.to_smt_lib()
+            );
+        }
+        if !uniqueness {
+            return smt;
+        }
+        // Inputs are shared by both assignments, so the suffix is only added
+        // to the remaining signals.
+        let inputs = self
+            .signals
+            .iter()
+            .filter(|signal| signal.kind == SignalKind::Input)
+            .map(|signal| signal.name.clone())
+            .collect::<HashSet<_>>();
+        let rename = |term: &Term| rename_signals(term, &inputs).to_smt_lib();
+        let _ = writeln!(smt, "; A second assignment for the same inputs.");
+        declare(&mut smt, "'", true);
+        for constraint in &self.constraints {
+            let _ = writeln!(
+                smt,
+                "(assert (= {} {}))",
+                rename(&constraint.lhs),
+                rename(&constraint.rhs)
+            );
+        }
+        let outputs = self
+            .signals
+            .iter()
+            .filter(|signal| signal.kind == SignalKind::Output)
+            .map(|signal| format!("(not (= |{0}| |{0}'|))", signal.name))
+            .collect::<Vec<_>>();
+        if outputs.is_empty() {
+            let _ = writeln!(smt, "; The template has no outputs.");
+            let _ = writeln!(smt, "(assert false)");
+        } else {
+            let _ = writeln!(smt, "(assert (or false {}))", outputs.join(" "));
+        }
+        let _ = writeln!(smt, "(check-sat)");
+        smt
+    }
+}
+
+/// Adds a `'` suffix to all signals except the given inputs.
+fn rename_signals(term: &Term, inputs: &HashSet<String>) -> Term {
+    use Term::*;
+    match term {
+        Constant(_) => term.clone(),
+        Signal(name) if inputs.contains(name) => term.clone(),
+        Signal(name) => Signal(format!("{name}'")),
+        Add(lhs, rhs) => {
+            Add(Box::new(rename_signals(lhs, inputs)), Box::new(rename_signals(rhs, inputs)))
+        }
+        Mul(lhs, rhs) => {
+            Mul(Box::new(rename_signals(lhs, inputs)), Box::new(rename_signals(rhs, inputs)))
+        }
+        Neg(value) => Neg(Box::new(rename_signals(value, inputs))),
+    }
+}
+
+/// Lowers the constraints of the given template instantiated with the given
+/// arguments. Returns an error if the template is not defined, or if some
+/// statement cannot be lowered (for example, because a loop bound depends on
+/// a signal).
+pub fn lower_template(
+    name: &str,
+    args: &[BigInt],
+    templates: &TemplateInfo,
+    functions: &FunctionInfo,
+    curve: &Curve,
+) -> Result<ConstraintSystem> {
+    debug!("lowering the constraints of `{name}`");
+    let template = templates.get(name).ok_or_else(|| anyhow!("unknown template `{name}`"))?;
+    if template.get_num_of_params() != args.len() {
+        bail!(
+            "`{name}` takes {} parameter(s), but {} argument(s) were given",
+            template.get_num_of_params(),
+            args.len()
+        );
+    }
+    let prime = UsefulConstants::new(curve).prime().clone();
+    let mut lowering = Lowering {
+        templates,
+        functions,
+        system: ConstraintSystem {
+            template_name: name.to_string(),
+            args: args.to_vec(),
+            prime: prime.clone(),
+            signals: Vec::new(),
+            constraints: Vec::new(),
+            hints: Vec::new(),
+        },
+        component_signals: HashSet::new(),
+        steps: 0,
+    };
+    let mut env = template
+        .get_name_of_params()
+        .iter()
+        .cloned()
+        .zip(args.iter().map(|arg| Value::Term(Term::Constant(modulo(arg, &prime)))))
+        .collect();
+    lowering.exec(template.get_body(), &mut env, 0)?;
+    Ok(lowering.system)
+}
+
+/// A value computed by the lowering.
+#[derive(Clone, Debug)]
+enum Value {
+    Term(Term),
+    Array(Vec<Value>),
+    /// A component, given by its name in the template (like `n2b[0]`).
+    Component(String),
+}
+
+impl Value {
+    /// Returns an array with the given dimensions, where each element is
+    /// created from the element name (like `in[0][1]`).
+    fn build(name: &str, dimensions: &[usize], element: &impl Fn(String) -> Value) -> Value {
+        match dimensions.split_first() {
+            Some((size, dimensions)) => Value::Array(
+                (0..*size)
+                    .map(|index| Value::build(&format!("{name}[{index}]"), dimensions, element))
+                    .collect(),
+            ),
+            None => element(name.to_string()),
+        }
+    }
+
+    fn get(&self, indices: &[usize]) -> Result<&Value> {
+        match (indices.split_first(), self) {
+            (None, value) => Ok(value),
+            (Some((index, indices)), Value::Array(values)) => values
+                .get(*index)
+                .ok_or_else(|| anyhow!("array index {index} is out of bounds"))?
+                .get(indices),
+            _ => bail!("indexed value is not an array"),
+        }
+    }
+
+    fn set(&mut self, indices: &[usize], value: Value) -> Result<()> {
+        match (indices.split_first(), self) {
+            (None, target) => *target = value,
+            (Some((index, indices)), Value::Array(values)) => values
+                .get_mut(*index)
+                .ok_or_else(|| anyhow!("array index {index} is out of bounds"))?
+                .set(indices, value)?,
+            _ => bail!("indexed value is not an array"),
+        }
+        Ok(())
+    }
+
+    fn into_term(self) -> Result<Term> {
+        match self {
+            Value::Term(term) => Ok(term),
+            _ => bail!("expected a field element"),
+        }
+    }
+
+    fn to_constant(&self) -> Result<BigInt> {
+        match self {
+            Value::Term(Term::Constant(value)) => Ok(value.clone()),
+            Value::Term(_) => bail!("value depends on a signal"),
+            _ => bail!("expected a field element"),
+        }
+    }
+}
+
+/// The result of executing a statement.
+enum Flow {
+    Continue,
+    Return(Value),
+}
+
+struct Lowering<'a> {
+    templates: &'a TemplateInfo,
+    functions: &'a FunctionInfo,
+    system: ConstraintSystem,
+    component_signals: HashSet<String>,
+    steps: usize,
+}
+
+impl<'a> Lowering<'a> {
+    fn exec(
+        &mut self,
+        stmt: &Statement,
+        env: &mut HashMap<String, Value>,
+        depth: usize,
+    ) -> Result<Flow> {
+        use Statement::*;
+        self.steps += 1;
+        if self.steps > MAX_STEPS {
+            bail!("too many statements executed");
+        }
+        match stmt {
+            Block { stmts, .. } | InitializationBlock { initializations: stmts, .. } => {
+                for stmt in stmts {
+                    if let Flow::Return(value) = self.exec(stmt, env, depth)? {
+                        return Ok(Flow::Return(value));
+                    }
+                }
+            }
+            IfThenElse { cond, if_case, else_case, .. } => {
+                let cond = self.eval_constant(cond, env, depth, "branch condition")?;
+                if self.as_bool(&cond) {
+                    return self.exec(if_case, env, depth);
+                } else if let Some(else_case) = else_case {
+                    return self.exec(else_case, env, depth);
+                }
+            }
+            While { cond, stmt, .. } => loop {
+                let cond = self.eval_constant(cond, env, depth, "loop condition")?;
+                if !self.as_bool(&cond) {
+                    break;
+                }
+                if let Flow::Return(value) = self.exec(stmt, env, depth)? {
+                    return Ok(Flow::Return(value));
+                }
+            },
+            Return { value, .. } => return Ok(Flow::Return(self.eval(value, env, depth)?)),
+            Declaration { xtype, name, dimensions, .. } => {
+                let dimensions = dimensions
+                    .iter()
+                    .map(|size| {
+                        self.eval(size, env, depth)?
+                            .to_constant()?
+                            .to_usize()
+                            .filter(|size| *size <= MAX_ARRAY_SIZE)
+                            .ok_or_else(|| anyhow!("invalid array size"))
+                    })
+                    .collect::<Result<Vec<_>>>()
+                    .map_err(|error| anyhow!("cannot evaluate size of `{name}`: {error}"))?;
+                let value = match xtype {
+                    VariableType::Var => Value::build(name, &dimensions, &|_| {
+                        Value::Term(Term::Constant(BigInt::zero()))
+                    }),
+                    VariableType::Component => {
+                        Value::build(name, &dimensions, &|name| Value::Component(name))
+                    }
+                    VariableType::Signal(signal_type, _, _) => {
+                        let kind = match signal_type {
+                            SignalType::Input => SignalKind::Input,
+                            SignalType::Output => SignalKind::Output,
+                            SignalType::Intermediate => SignalKind::Intermediate,
+                        };
+                        let value = Value::build(name, &dimensions, &|name| {
+                            Value::Term(Term::Signal(name))
+                        });
+                        self.add_signals(&value, kind);
+                        value
+                    }
+                };
+                env.insert(name.clone(), value);
+            }
+            Substitution { var, access, op: AssignOp::AssignVar, rhe, .. } => {
+                // Components are declared with their names, so instantiations
+                // can be ignored.
+                if !is_instantiation(rhe, self.templates) {
+                    let value = self.eval(rhe, env, depth)?;
+                    let (indices, component_access) = self.indices(access, env, depth)?;
+                    if !component_access.is_empty() {
+                        bail!("cannot assign to component signal `{var}` using `=`");
+                    }
+                    env.entry(var.clone())
+                        .or_insert(Value::Term(Term::Constant(BigInt::zero())))
+                        .set(&indices, value)?;
+                }
+            }
+            Substitution { var, access, op, rhe, .. } => {
+                let target = self.eval_variable(var, access, env, depth)?;
+                if *op == AssignOp::AssignConstraintSignal {
+                    let value = self.eval(rhe, env, depth)?;
+                    self.add_constraints(target, value)?;
+                } else {
+                    // Hints are computed by the witness generator, so the
+                    // assigned value is not evaluated.
+                    self.add_hints(&target);
+                }
+            }
+            ConstraintEquality { lhe, rhe, .. } => {
+                let lhs = self.eval(lhe, env, depth)?;
+                let rhs = self.eval(rhe, env, depth)?;
+                self.add_constraints(lhs, rhs)?;
+            }
+            MultiSubstitution { .. } => bail!("tuple assignments are not supported"),
+            // Asserts are checked by the witness generator, and do not generate constraints.
+            LogCall { .. } | Assert { .. } => {}
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn eval(
+        &mut self,
+        expr: &Expression,
+        env: &mut HashMap<String, Value>,
+        depth: usize,
+    ) -> Result<Value> {
+        use Expression::*;
+        let value = match expr {
+            Number(_, value) => Value::Term(Term::Constant(modulo(value, &self.system.prime))),
+            Variable { name, access, .. } => self.eval_variable(name, access, env, depth)?,
+            InfixOp { lhe, infix_op, rhe, .. } => {
+                let lhs = self.eval(lhe, env, depth)?.into_term()?;
+                let rhs = self.eval(rhe, env, depth)?.into_term()?;
+                Value::Term(self.eval_infix_op(lhs, *infix_op, rhs)?)
+            }
+            PrefixOp { prefix_op, rhe, .. } => {
+                let rhs = self.eval(rhe, env, depth)?.into_term()?;
+                let p = &self.system.prime;
+                Value::Term(match (prefix_op, rhs.to_constant()) {
+                    (ExpressionPrefixOpcode::Sub, _) => self.neg(rhs),
+                    (ExpressionPrefixOpcode::BoolNot, Some(value)) => {
+                        Term::Constant(modular_arithmetic::not(value, p))
+                    }
+                    (ExpressionPrefixOpcode::Complement, Some(value)) => {
+                        Term::Constant(modular_arithmetic::complement_256(value, p))
+                    }
+                    _ => bail!("`{prefix_op}` cannot be applied to signals"),
+                })
+            }
+            InlineSwitchOp { cond, if_true, if_false, .. } => {
+                let cond = self.eval_constant(cond, env, depth, "condition")?;
+                if self.as_bool(&cond) {
+                    self.eval(if_true, env, depth)?
+                } else {
+                    self.eval(if_false, env, depth)?
+                }
+            }
+            ParallelOp { rhe, .. } => self.eval(rhe, env, depth)?,
+            Call { id, args, .. } => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.eval(arg, env, depth))
+                    .collect::<Result<Vec<_>>>()?;
+                self.call(id, args, depth + 1)?
+            }
+            ArrayInLine { values, .. } => Value::Array(
+                values.iter().map(|value| self.eval(value, env, depth)).collect::<Result<_>>()?,
+            ),
+            AnonymousComponent { .. } | Tuple { .. } => {
+                bail!("anonymous components and tuples are not supported")
+            }
+        };
+        Ok(value)
+    }
+
+    /// Evaluates the given expression, which must not depend on any signals.
+    /// The description of the expression is used in the returned error.
+    fn eval_constant(
+        &mut self,
+        expr: &Expression,
+        env: &mut HashMap<String, Value>,
+        depth: usize,
+        description: &str,
+    ) -> Result<BigInt> {
+        self.eval(expr, env, depth)
+            .and_then(|value| value.to_constant())
+            .map_err(|error| anyhow!("cannot evaluate {description}: {error}"))
+    }
+
+    /// Evaluates the given variable, signal, or component signal access.
+    fn eval_variable(
+        &mut self,
+        name: &str,
+        access: &[Access],
+        env: &mut HashMap<String, Value>,
+        depth: usize,
+    ) -> Result<Value> {
+        let (indices, component_access) = self.indices(access, env, depth)?;
+        let value = env
+            .get(name)
+            .ok_or_else(|| anyhow!("`{name}` is not declared"))?
+            .get(&indices)?
+            .clone();
+        match (value, component_access.is_empty()) {
+            (Value::Component(component), false) => {
+                let term = Term::Signal(format!("{component}.{component_access}"));
+                self.add_signals(&Value::Term(term.clone()), SignalKind::Component);
+                Ok(Value::Term(term))
+            }
+            (Value::Component(component), true) => {
+                bail!("component `{component}` cannot be used as a value")
+            }
+            (value, true) => Ok(value),
+            (_, false) => bail!("`{name}` is not a component"),
+        }
+    }
+
+    /// Returns the array indices preceding the first component access, and
+    /// the remaining accesses as a string (like `out[0]`).
+    fn indices(
+        &mut self,
+        access: &[Access],
+        env: &mut HashMap<String, Value>,
+        depth: usize,
+    ) -> Result<(Vec<usize>, String)> {
+        let mut indices = Vec::new();
+        let mut component_access = String::new();
+        for access in access {
+            match access {
+                Access::ArrayAccess(index) => {
+                    let index = self
+                        .eval_constant(index, env, depth, "array index")?
+                        .to_usize()
+                        .ok_or_else(|| anyhow!("invalid array index"))?;
+                    if component_access.is_empty() {
+                        indices.push(index);
+                    } else {
+                        let _ = write!(component_access, "[{index}]");
+                    }
+                }
+                Access::ComponentAccess(signal_name) if component_access.is_empty() => {
+                    component_access.push_str(signal_name);
+                }
+                Access::ComponentAccess(_) => bail!("nested component accesses are not supported"),
+            }
+        }
+        Ok((indices, component_access))
+    }
+
+    /// Returns the value returned by the given function.
+    fn call(&mut self, name: &str, args: Vec<Value>, depth: usize) -> Result<Value> {
+        if self.templates.contains_key(name) {
+            bail!("template `{name}` cannot be used as a value");
+        }
+        let function =
+            self.functions.get(name).ok_or_else(|| anyhow!("unknown function `{name}`"))?;
+        if depth > MAX_DEPTH {
+            bail!("maximum call depth exceeded");
+        }
+        let mut env = function.get_name_of_params().iter().cloned().zip(args).collect();
+        match self.exec(function.get_body(), &mut env, depth)? {
+            Flow::Return(value) => Ok(value),
+            Flow::Continue => bail!("function `{name}` does not return a value"),
+        }
+    }
+
+    fn eval_infix_op(&self, lhs: Term, infix_op: ExpressionInfixOpcode, rhs: Term) -> Result<Term> {
+        use ExpressionInfixOpcode::*;
+        use modular_arithmetic::*;
+        let p = &self.system.prime;
+        match (infix_op, lhs.to_constant(), rhs.to_constant()) {
+            (Add, _, _) => return Ok(self.add(lhs, rhs)),
+            (Sub, _, _) => return Ok(self.add(lhs, self.neg(rhs))),
+            (Mul, _, _) => return Ok(self.mul(lhs, rhs)),
+            // Division by a constant is multiplication by the inverse.
+            (Div, None, Some(rhv)) => {
+                let inverse =
+                    div(&BigInt::one(), rhv, p).map_err(|_| anyhow!("division by zero"))?;
+                return Ok(self.mul(lhs, Term::Constant(inverse)));
+            }
+            (_, Some(_), Some(_)) => {}
+            _ => bail!("`{infix_op}` cannot be applied to signals"),
+        }
+        let (Some(lhv), Some(rhv)) = (lhs.to_constant(), rhs.to_constant()) else {
+            unreachable!("both operands are constant");
+        };
+        let value = match infix_op {
+            Div => div(lhv, rhv, p).map_err(|_| anyhow!("division by zero"))?,
+            Pow => pow(lhv, rhv, p),
+            IntDiv => idiv(lhv, rhv, p).map_err(|_| anyhow!("division by zero"))?,
+            Mod => mod_op(lhv, rhv, p).map_err(|_| anyhow!("division by zero"))?,
+            ShiftL => shift_l(lhv, rhv, p).map_err(|_| anyhow!("invalid shift"))?,
+            ShiftR => shift_r(lhv, rhv, p).map_err(|_| anyhow!("invalid shift"))?,
+            LesserEq => lesser_eq(lhv, rhv, p),
+            GreaterEq => greater_eq(lhv, rhv, p),
+            Lesser => lesser(lhv, rhv, p),
+            Greater => greater(lhv, rhv, p),
+            Eq => eq(lhv, rhv, p),
+            NotEq => not_eq(lhv, rhv, p),
+            BoolOr => bool_or(lhv, rhv, p),
+            BoolAnd => bool_and(lhv, rhv, p),
+            BitOr => bit_or(lhv, rhv, p),
+            BitAnd => bit_and(lhv, rhv, p),
+            BitXor => bit_xor(lhv, rhv, p),
+            Add | Sub | Mul => unreachable!("handled above"),
+        };
+        Ok(Term::Constant(value))
+    }
+
+    fn add(&self, lhs: Term, rhs: Term) -> Term {
+        match (lhs.to_constant(), rhs.to_constant()) {
+            (Some(lhv), Some(rhv)) => {
+                Term::Constant(modular_arithmetic::add(lhv, rhv, &self.system.prime))
+            }
+            (Some(lhv), None) if lhv.is_zero() => rhs,
+            (None, Some(rhv)) if rhv.is_zero() => lhs,
+            _ => Term::Add(Box::new(lhs), Box::new(rhs)),
+        }
+    }
+
+    fn mul(&self, lhs: Term, rhs: Term) -> Term {
+        match (lhs.to_constant(), rhs.to_constant()) {
+            (Some(lhv), Some(rhv)) => {
+                Term::Constant(modular_arithmetic::mul(lhv, rhv, &self.system.prime))
+            }
+            (Some(value), _) | (_, Some(value)) if value.is_zero() => {
+                Term::Constant(BigInt::zero())
+            }
+            (Some(lhv), None) if lhv.is_one() => rhs,
+            (None, Some(rhv)) if rhv.is_one() => lhs,
+            _ => Term::Mul(Box::new(lhs), Box::new(rhs)),
+        }
+    }
+
+    fn neg(&self, value: Term) -> Term {
+        match value {
+            Term::Constant(value) => {
+                Term::Constant(modular_arithmetic::prefix_sub(&value, &self.system.prime))
+            }
+            Term::Neg(value) => *value,
+            value => Term::Neg(Box::new(value)),
+        }
+    }
+
+    fn add_signals(&mut self, value: &Value, kind: SignalKind) {
+        match value {
+            // Component signals are added on first use.
+            Value::Term(Term::Signal(name))
+                if kind != SignalKind::Component || self.component_signals.insert(name.clone()) =>
+            {
+                self.system.signals.push(Signal { name: name.clone(), kind });
+            }
+            Value::Array(values) => values.iter().for_each(|value| self.add_signals(value, kind)),
+            _ => {}
+        }
+    }
+
+    /// Adds the constraint `lhs = rhs`. Arrays are constrained element-wise.
+    fn add_constraints(&mut self, lhs: Value, rhs: Value) -> Result<()> {
+        match (lhs, rhs) {
+            (Value::Term(lhs), Value::Term(rhs)) => {
+                self.system.constraints.push(Constraint { lhs, rhs })
+            }
+            (Value::Array(lhs), Value::Array(rhs)) if lhs.len() == rhs.len() => {
+                for (lhs, rhs) in lhs.into_iter().zip(rhs) {
+                    self.add_constraints(lhs, rhs)?;
+                }
+            }
+            _ => bail!("both sides of a constraint must have the same dimensions"),
+        }
+        Ok(())
+    }
+
+    fn add_hints(&mut self, target: &Value) {
+        match target {
+            Value::Term(Term::Signal(name)) if !self.system.hints.contains(name) => {
+                self.system.hints.push(name.clone());
+            }
+            Value::Array(values) => values.iter().for_each(|value| self.add_hints(value)),
+            _ => {}
+        }
+    }
+
+    fn as_bool(&self, value: &BigInt) -> bool {
+        modular_arithmetic::as_bool(value, &self.system.prime)
+    }
+}
+
+/// Returns the least non-negative residue of the value modulo the prime.
+fn modulo(value: &BigInt, prime: &BigInt) -> BigInt {
+    ((value % prime) + prime) % prime
+}
+
+/// Returns true if the expression instantiates a template.
+fn is_instantiation(expr: &Expression, templates: &TemplateInfo) -> bool {
+    match expr {
+        Expression::Call { id, .. } => templates.contains_key(id),
+        Expression::ParallelOp { rhe, .. } => is_instantiation(rhe, templates),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{parse_source, ParseResult};
+
+    use super::*;
+
+    fn lower(src: &str, name: &str, args: &[u64]) -> Result<ConstraintSystem> {
+        let ParseResult::Library(library, _) = parse_source("main.circom", src, &[], &[], "2.1.0")
+        else {
+            panic!("failed to parse library");
+        };
+        let args = args.iter().map(|arg| BigInt::from(*arg)).collect::<Vec<_>>();
+        lower_template(name, &args, &library.templates, &library.functions, &Curve::default())
+    }
+
+    #[test]
+    fn test_lower_template() {
+        let src = r#"
+            function double(x) {
+                return 2 * x;
+            }
+
+            template Num2Bits(n) {
+                signal input in;
+                signal output out[n];
+                var sum = 0;
+                for (var i = 0; i < n; i++) {
+                    out[i] <-- (in >> i) & 1;
+                    out[i] * (out[i] - 1) === 0;
+                    sum += out[i] * double(i);
+                }
+                sum / 2 === in;
+            }
+        "#;
+        let system = lower(src, "Num2Bits", &[2]).unwrap();
+        let signals = system
+            .signals
+            .iter()
+            .map(|signal| (signal.name.as_str(), signal.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            signals,
+            [
+                ("in", SignalKind::Input),
+                ("out[0]", SignalKind::Output),
+                ("out[1]", SignalKind::Output)
+            ]
+        );
+        assert_eq!(system.hints, ["out[0]", "out[1]"]);
+        assert_eq!(system.constraints.len(), 3);
+
+        let smt = system.to_smt_lib(false);
+        assert!(smt.contains("(declare-const |out[1]| F) ; output"));
+        // Constants are reduced, so `out[0] - 1` becomes `out[0] + (p - 1)`.
+        assert!(smt.contains("(assert (= (ff.mul |out[0]| (ff.add |out[0]| (as ff"));
+        assert!(!smt.contains("check-sat"));
+
+        let smt = system.to_smt_lib(true);
+        assert!(!smt.contains("|in'|"));
+        assert!(smt.contains("(declare-const |out[0]'| F) ; output"));
+        assert!(smt.contains(
+            "(assert (or false (not (= |out[0]| |out[0]'|)) (not (= |out[1]| |out[1]'|))))"
+        ));
+        assert!(smt.ends_with("(check-sat)\n"));
+    }
+
+    #[test]
+    fn test_lower_components() {
+        let src = r#"
+            template IsZero() {
+                signal input in;
+                signal output out;
+                signal inv;
+                inv <-- in != 0 ? 1 / in : 0;
+                out <== -in * inv + 1;
+                in * out === 0;
+            }
+
+            template IsEqual() {
+                signal input in[2];
+                signal output out;
+                component isZero = IsZero();
+                isZero.in <== in[1] - in[0];
+                isZero.out ==> out;
+            }
+        "#;
+        let system = lower(src, "IsEqual", &[]).unwrap();
+        let components = system
+            .signals
+            .iter()
+            .filter(|signal| signal.kind == SignalKind::Component)
+            .map(|signal| signal.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(components, ["isZero.in", "isZero.out"]);
+        assert_eq!(system.constraints.len(), 2);
+        assert_eq!(lower(src, "IsZero", &[]).unwrap().hints, ["inv"]);
+
+        assert!(lower(src, "IsEqual", &[1]).is_err());
+        assert!(lower(src, "Unknown", &[]).is_err());
+    }
+
+    #[test]
+    fn test_signal_dependent_loop() {
+        let src = r#"
+            template T() {
+                signal input n;
+                signal output out[2];
+                for (var i = 0; i < n; i++) {
+                    out[i] <== i;
+                }
+            }
+        "#;
+        let error = lower(src, "T", &[]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "cannot evaluate loop condition: `<` cannot be applied to signals"
+        );
+    }
+}
@@ -0,0 +1,410 @@
+use log::debug;
+use num_bigint::BigInt;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use program_structure::cfg::{BasicBlock, Cfg};
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::value_meta::{ValueMeta, ValueReduction};
+use program_structure::ir::variable_meta::VariableMeta;
+use program_structure::ir::*;
+
+pub struct DeadStoreWarning {
+    element: String,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl DeadStoreWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            format!("The value assigned to `{}` is overwritten before it is read.", self.element),
+            ReportCode::DeadStore,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                format!("The value assigned to `{}` here is never read.", self.element),
+            );
+        }
+        report.add_note(
+            "On every path from this assignment, the value is overwritten before it is read."
+                .to_string(),
+        );
+        report
+    }
+}
+
+/// An array element (or subarray) `name[i_1]...[i_k]` with constant indices.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct Element {
+    name: VariableName,
+    indices: Vec<BigInt>,
+}
+
+impl Element {
+    /// Returns true if the two elements may refer to the same value. (That is,
+    /// if the index list of one element is a prefix of the other.)
+    fn overlaps(&self, other: &Element) -> bool {
+        self.name == other.name && self.indices.iter().zip(&other.indices).all(|(i, j)| i == j)
+    }
+
+    /// Returns true if this element contains the given element.
+    fn contains(&self, other: &Element) -> bool {
+        self.overlaps(other) && self.indices.len() <= other.indices.len()
+    }
+}
+
+impl fmt::Display for Element {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        for index in &self.indices {
+            write!(f, "[{index}]")?;
+        }
+        Ok(())
+    }
+}
+
+/// The set of live values at a program point. Since array elements are
+/// updated in place, SSA versions are dropped and array elements are tracked
+/// individually where the index is constant.
+#[derive(Clone, Default, PartialEq, Eq)]
+struct LiveValues {
+    // Variables which may be read in their entirety, together with the
+    // elements that are overwritten before the variable is read.
+    variables: HashMap<VariableName, HashSet<Vec<BigInt>>>,
+    // Array elements which may be read.
+    elements: HashSet<Element>,
+}
+
+impl LiveValues {
+    fn is_live(&self, element: &Element) -> bool {
+        let is_overwritten = |overwritten: &HashSet<Vec<BigInt>>| {
+            overwritten.iter().any(|indices| element.indices.starts_with(indices))
+        };
+        self.variables.get(&element.name).is_some_and(|overwritten| !is_overwritten(overwritten))
+            || self.elements.iter().any(|other| other.overlaps(element))
+    }
+
+    fn read_element(&mut self, element: Element) {
+        self.elements.insert(element);
+    }
+
+    fn read_variable(&mut self, name: VariableName) {
+        self.variables.insert(name, HashSet::new());
+    }
+
+    fn kill_element(&mut self, element: &Element) {
+        self.elements.retain(|other| !element.contains(other));
+        if let Some(overwritten) = self.variables.get_mut(&element.name) {
+            overwritten.retain(|indices| !indices.starts_with(&element.indices));
+            overwritten.insert(element.indices.clone());
+        }
+    }
+
+    fn kill_variable(&mut self, name: &VariableName) {
+        self.variables.remove(name);
+        self.elements.retain(|element| element.name != *name);
+    }
+
+    /// Joins the live values from two different paths. An element of a
+    /// variable is only overwritten if it is overwritten along both paths.
+    fn union(&mut self, other: &LiveValues) {
+        for (name, other_overwritten) in &other.variables {
+            match self.variables.get_mut(name) {
+                Some(overwritten) => {
+                    overwritten.retain(|indices| other_overwritten.contains(indices))
+                }
+                None => {
+                    self.variables.insert(name.clone(), other_overwritten.clone());
+                }
+            }
+        }
+        self.elements.extend(other.elements.iter().cloned());
+    }
+}
+
+/// An array element assignment where the assigned value is never read.
+struct DeadStore<'a> {
+    meta: &'a Meta,
+    var: &'a VariableName,
+    element: Element,
+}
+
+/// SSA form ensures that a local variable which is overwritten before it is
+/// read is reported as unused by the side-effect analysis pass. However, array
+/// elements are updated in place, which means that the previous version of
+/// the array is always read by the update. This analysis pass runs a liveness
+/// analysis over the CFG, tracking array elements with constant indices, and
+/// reports assignments to array elements where the assigned value is
+/// overwritten before it is read on all paths.
+pub fn find_dead_stores(cfg: &Cfg) -> ReportCollection {
+    debug!("running dead store analysis pass");
+    let live_out = run_liveness_analysis(cfg);
+
+    // Values which are never read are reported by the side-effect analysis.
+    let mut variables_read = HashSet::new();
+    for basic_block in cfg.iter() {
+        variables_read.extend(basic_block.variables_read().map(|var| var.name().clone()));
+    }
+    let mut dead_stores = Vec::new();
+    for basic_block in cfg.iter() {
+        let mut live_values = live_out[basic_block.index()].clone();
+        transfer_block(basic_block, &mut live_values, &mut dead_stores);
+    }
+    let mut reports = ReportCollection::new();
+    for dead_store in dead_stores {
+        if variables_read.contains(dead_store.var) {
+            reports.push(build_report(dead_store.meta, &dead_store.element));
+        }
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+/// Computes the set of live values at the end of each basic block.
+fn run_liveness_analysis(cfg: &Cfg) -> Vec<LiveValues> {
+    let mut live_in = vec![LiveValues::default(); cfg.len()];
+    let mut live_out = vec![LiveValues::default(); cfg.len()];
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for basic_block in cfg.iter().collect::<Vec<_>>().into_iter().rev() {
+            let index = basic_block.index();
+            let mut live_values = LiveValues::default();
+            for successor in basic_block.successors() {
+                live_values.union(&live_in[*successor]);
+            }
+            live_out[index] = live_values.clone();
+            transfer_block(basic_block, &mut live_values, &mut Vec::new());
+            if live_values != live_in[index] {
+                live_in[index] = live_values;
+                changed = true;
+            }
+        }
+    }
+    live_out
+}
+
+/// Updates the live values by visiting the statements of the basic block in
+/// reverse order. Array element assignments where the assigned value is not
+/// live are added to `dead_stores`.
+fn transfer_block<'a>(
+    basic_block: &'a BasicBlock,
+    live_values: &mut LiveValues,
+    dead_stores: &mut Vec<DeadStore<'a>>,
+) {
+    for stmt in basic_block.statements().iter().rev() {
+        transfer_statement(stmt, live_values, dead_stores);
+    }
+}
+
+fn transfer_statement<'a>(
+    stmt: &'a Statement,
+    live_values: &mut LiveValues,
+    dead_stores: &mut Vec<DeadStore<'a>>,
+) {
+    use Expression::*;
+    use Statement::*;
+    match stmt {
+        Substitution { meta, var, op: AssignOp::AssignLocalOrComponent, rhe }
+            if meta.type_knowledge().is_local() =>
+        {
+            match rhe {
+                Update { access, rhe, .. } => {
+                    let name = var.without_version();
+                    if let Some(indices) = constant_indices(access) {
+                        let element = Element { name, indices };
+                        if !live_values.is_live(&element) {
+                            dead_stores.push(DeadStore { meta, var, element: element.clone() });
+                        }
+                        live_values.kill_element(&element);
+                    }
+                    for index in access {
+                        if let AccessType::ArrayAccess(index) = index {
+                            visit_expression(index, live_values);
+                        }
+                    }
+                    visit_expression(rhe, live_values);
+                }
+                // Phi expressions do not affect liveness since SSA versions
+                // are dropped.
+                Phi { .. } => {}
+                _ => {
+                    live_values.kill_variable(&var.without_version());
+                    visit_expression(rhe, live_values);
+                }
+            }
+        }
+        Substitution { rhe, .. } => visit_expression(rhe, live_values),
+        Declaration { dimensions, .. } => {
+            for size in dimensions {
+                visit_expression(size, live_values);
+            }
+        }
+        LogCall { args, .. } => {
+            for arg in args {
+                if let LogArgument::Expr(value) = arg {
+                    visit_expression(value, live_values);
+                }
+            }
+        }
+        IfThenElse { cond, .. } => visit_expression(cond, live_values),
+        Return { value, .. } => visit_expression(value, live_values),
+        Assert { arg, .. } => visit_expression(arg, live_values),
+        ConstraintEquality { lhe, rhe, .. } => {
+            visit_expression(lhe, live_values);
+            visit_expression(rhe, live_values);
+        }
+    }
+}
+
+/// Adds the values read by the expression to the set of live values.
+fn visit_expression(expr: &Expression, live_values: &mut LiveValues) {
+    use Expression::*;
+    match expr {
+        Access { var, access, .. } => {
+            let name = var.without_version();
+            match constant_indices(access) {
+                Some(indices) => live_values.read_element(Element { name, indices }),
+                None => live_values.read_variable(name),
+            }
+            for index in access {
+                if let AccessType::ArrayAccess(index) = index {
+                    visit_expression(index, live_values);
+                }
+            }
+        }
+        Variable { name, .. } => {
+            live_values.read_variable(name.without_version());
+        }
+        Update { var, access, rhe, .. } => {
+            live_values.read_variable(var.without_version());
+            for index in access {
+                if let AccessType::ArrayAccess(index) = index {
+                    visit_expression(index, live_values);
+                }
+            }
+            visit_expression(rhe, live_values);
+        }
+        InfixOp { lhe, rhe, .. } => {
+            visit_expression(lhe, live_values);
+            visit_expression(rhe, live_values);
+        }
+        PrefixOp { rhe, .. } => visit_expression(rhe, live_values),
+        SwitchOp { cond, if_true, if_false, .. } => {
+            visit_expression(cond, live_values);
+            visit_expression(if_true, live_values);
+            visit_expression(if_false, live_values);
+        }
+        Call { args: values, .. } | InlineArray { values, .. } => {
+            for value in values {
+                visit_expression(value, live_values);
+            }
+        }
+        Number(_, _) | Phi { .. } => (),
+    }
+}
+
+/// Returns the indices of the access if all indices are constant array
+/// indices.
+fn constant_indices(access: &[AccessType]) -> Option<Vec<BigInt>> {
+    access
+        .iter()
+        .map(|access| match access {
+            AccessType::ArrayAccess(index) => match index.value() {
+                Some(ValueReduction::FieldElement { value }) => Some(value.clone()),
+                _ => None,
+            },
+            AccessType::ComponentAccess(_) => None,
+        })
+        .collect()
+}
+
+fn build_report(meta: &Meta, element: &Element) -> Report {
+    DeadStoreWarning {
+        element: element.to_string(),
+        file_id: meta.file_id(),
+        file_location: meta.file_location(),
+    }
+    .into_report()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_dead_stores() {
+        let src = r#"
+            function f(a) {
+                var x[2];
+                x[0] = a;
+                x[0] = 3;
+                return x[0] + x[1];
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            function f(a) {
+                var x[2];
+                x[0] = a;
+                if (a > 0) {
+                    x[0] = 1;
+                }
+                return x[0] + x[1];
+            }
+        "#;
+        validate_reports(src, 0);
+
+        let src = r#"
+            function f(a) {
+                var x[2][2];
+                x[0][1] = a;
+                x[0] = [1, 2];
+                x[1][0] = a;
+                x[1][1] = a;
+                return x;
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            function f(a, n) {
+                var x[2];
+                x[0] = a;
+                for (var i = 0; i < n; i++) {
+                    x[0] = x[0] + i;
+                }
+                x[1] = a;
+                x[1] = x[0];
+                return x;
+            }
+        "#;
+        validate_reports(src, 1);
+    }
+
+    fn validate_reports(src: &str, expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_dead_stores(&cfg);
+
+        assert_eq!(reports.len(), expected_len);
+    }
+}
@@ -1,8 +1,13 @@
+use std::collections::HashSet;
+
 use program_structure::cfg::{Cfg, DefinitionType};
+use program_structure::ir::{Expression, Statement};
 use program_structure::report_code::ReportCode;
 use program_structure::report::{Report, ReportCollection};
 use program_structure::file_definition::{FileID, FileLocation};
 
+use crate::analysis_pass::AnalysisPass;
+
 pub struct TooManyArgumentsWarning {
     definition_name: String,
     definition_type: DefinitionType,
@@ -33,49 +38,233 @@ impl TooManyArgumentsWarning {
 pub struct CyclomaticComplexityWarning {
     definition_name: String,
     definition_type: DefinitionType,
+    complexity: usize,
+    max_complexity: usize,
 }
 
 impl CyclomaticComplexityWarning {
     pub fn into_report(self) -> Report {
-        Report::warning(
+        let mut report = Report::warning(
             format!(
                 "The {} `{}` is too complex and would benefit from being refactored into smaller components.",
                 self.definition_type,
                 self.definition_name
             ),
             ReportCode::CyclomaticComplexity,
-        )
+        );
+        report.add_note(format!(
+            "The cyclomatic complexity is {} (the configured maximum is {}).",
+            self.complexity, self.max_complexity
+        ));
+        report
+    }
+}
+
+pub struct LargeDefinitionWarning {
+    definition_name: String,
+    definition_type: DefinitionType,
+    statements: usize,
+    max_statements: usize,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl LargeDefinitionWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            format!(
+                "The {} `{}` contains {} statements and would benefit from being split up.",
+                self.definition_type, self.definition_name, self.statements
+            ),
+            ReportCode::LargeDefinition,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                format!("This {} contains too many statements.", self.definition_type),
+            );
+        }
+        report.add_note(format!("The configured maximum is {} statements.", self.max_statements));
+        report
+    }
+}
+
+pub struct DeepNestingWarning {
+    definition_name: String,
+    definition_type: DefinitionType,
+    depth: usize,
+    max_depth: usize,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl DeepNestingWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            format!(
+                "The {} `{}` contains branches and loops nested {} levels deep.",
+                self.definition_type, self.definition_name, self.depth
+            ),
+            ReportCode::DeepNesting,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                format!("This {} is deeply nested.", self.definition_type),
+            );
+        }
+        report.add_note(format!("The configured maximum nesting depth is {}.", self.max_depth));
+        report
     }
 }
 
 const MAX_NOF_PARAMETERS: usize = 7;
 const MAX_CYCLOMATIC_COMPLEXITY: usize = 20;
+const MAX_NOF_STATEMENTS: usize = 250;
+const MAX_NESTING_DEPTH: usize = 5;
+
+/// The thresholds above which the complexity analysis generates warnings.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ComplexityThresholds {
+    pub max_parameters: usize,
+    pub max_cyclomatic_complexity: usize,
+    pub max_statements: usize,
+    pub max_nesting_depth: usize,
+}
 
-pub fn run_complexity_analysis(cfg: &Cfg) -> ReportCollection {
+impl Default for ComplexityThresholds {
+    fn default() -> Self {
+        ComplexityThresholds {
+            max_parameters: MAX_NOF_PARAMETERS,
+            max_cyclomatic_complexity: MAX_CYCLOMATIC_COMPLEXITY,
+            max_statements: MAX_NOF_STATEMENTS,
+            max_nesting_depth: MAX_NESTING_DEPTH,
+        }
+    }
+}
+
+/// Size and complexity metrics for a single function or template.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ComplexityMetrics {
+    /// The cyclomatic complexity of the CFG.
+    pub cyclomatic_complexity: usize,
+    /// The number of statements in the CFG (excluding phi statements).
+    pub statements: usize,
+    /// The maximum number of nested branches and loops.
+    pub nesting_depth: usize,
+}
+
+/// Computes the size and complexity metrics of the given CFG.
+#[must_use]
+pub fn compute_complexity(cfg: &Cfg) -> ComplexityMetrics {
     // Compute the cyclomatic complexity as `M = E - N + 2P` where `E` is the
     // number of edges, `N` is the number of nodes, and `P` is the number of
     // connected components (which is always 1 here).
     let mut edges = 0;
     let mut nodes = 0;
+    let mut statements = 0;
     for basic_block in cfg.iter() {
         edges += basic_block.successors().len();
         nodes += 1;
+        statements += basic_block
+            .iter()
+            .filter(|stmt| {
+                !matches!(stmt, Statement::Substitution { rhe: Expression::Phi { .. }, .. })
+            })
+            .count();
+    }
+    let cyclomatic_complexity = edges + 2 - nodes;
+
+    // The nesting depth of a basic block is the number of branches and loop
+    // bodies containing the block. A branch contains the blocks dominated by
+    // its first block. The false branch is skipped if it is empty, if the true
+    // branch never joins up with the false branch (like an early return), or
+    // if the header is a loop header (in which case the false branch is the
+    // code following the loop).
+    let mut branch_starts = HashSet::new();
+    for header_block in cfg.iter() {
+        let Some(Statement::IfThenElse { true_index, false_index, .. }) =
+            header_block.statements().last()
+        else {
+            continue;
+        };
+        branch_starts.insert(*true_index);
+        let true_block = cfg.get_basic_block(*true_index).expect("in control-flow graph");
+        let frontier = cfg
+            .get_dominance_frontier(true_block)
+            .iter()
+            .map(|basic_block| basic_block.index())
+            .collect::<HashSet<_>>();
+        if let Some(false_index) = false_index {
+            if !frontier.is_empty()
+                && !frontier.contains(false_index)
+                && !frontier.contains(&header_block.index())
+            {
+                branch_starts.insert(*false_index);
+            }
+        }
     }
-    let complexity = edges - nodes + 2;
+    let nesting_depth = cfg
+        .iter()
+        .map(|basic_block| {
+            cfg.get_dominators(basic_block)
+                .iter()
+                .filter(|dominator| branch_starts.contains(&dominator.index()))
+                .count()
+        })
+        .max()
+        .unwrap_or_default();
 
+    ComplexityMetrics { cyclomatic_complexity, statements, nesting_depth }
+}
+
+pub fn run_complexity_analysis(cfg: &Cfg, thresholds: &ComplexityThresholds) -> ReportCollection {
+    let metrics = compute_complexity(cfg);
     let mut reports = ReportCollection::new();
     // Generate a report if the cyclomatic complexity is high.
-    if complexity > MAX_CYCLOMATIC_COMPLEXITY {
+    if metrics.cyclomatic_complexity > thresholds.max_cyclomatic_complexity {
         reports.push(
             CyclomaticComplexityWarning {
                 definition_name: cfg.name().to_string(),
                 definition_type: cfg.definition_type().clone(),
+                complexity: metrics.cyclomatic_complexity,
+                max_complexity: thresholds.max_cyclomatic_complexity,
+            }
+            .into_report(),
+        );
+    }
+    // Generate a report if the number of statements is high.
+    if metrics.statements > thresholds.max_statements {
+        reports.push(
+            LargeDefinitionWarning {
+                definition_name: cfg.name().to_string(),
+                definition_type: cfg.definition_type().clone(),
+                statements: metrics.statements,
+                max_statements: thresholds.max_statements,
+                file_id: *cfg.parameters().file_id(),
+                file_location: cfg.parameters().file_location().clone(),
+            }
+            .into_report(),
+        );
+    }
+    // Generate a report if branches and loops are deeply nested.
+    if metrics.nesting_depth > thresholds.max_nesting_depth {
+        reports.push(
+            DeepNestingWarning {
+                definition_name: cfg.name().to_string(),
+                definition_type: cfg.definition_type().clone(),
+                depth: metrics.nesting_depth,
+                max_depth: thresholds.max_nesting_depth,
+                file_id: *cfg.parameters().file_id(),
+                file_location: cfg.parameters().file_location().clone(),
             }
             .into_report(),
         );
     }
     // Generate a report if the number of arguments is high.
-    if cfg.parameters().len() > MAX_NOF_PARAMETERS {
+    if cfg.parameters().len() > thresholds.max_parameters {
         reports.push(
             TooManyArgumentsWarning {
                 definition_name: cfg.name().to_string(),
@@ -88,3 +277,102 @@ pub fn run_complexity_analysis(cfg: &Cfg) -> ReportCollection {
     }
     reports
 }
+
+/// The complexity analysis pass. Unlike most passes, the complexity analysis
+/// is configurable, so it is implemented as a struct holding the thresholds
+/// used to generate warnings.
+pub struct ComplexityPass {
+    thresholds: ComplexityThresholds,
+}
+
+impl ComplexityPass {
+    #[must_use]
+    pub fn new(thresholds: ComplexityThresholds) -> ComplexityPass {
+        ComplexityPass { thresholds }
+    }
+}
+
+impl AnalysisPass for ComplexityPass {
+    fn name(&self) -> &str {
+        "definition-complexity"
+    }
+
+    fn description(&self) -> &str {
+        "Reports overly complex functions and templates."
+    }
+
+    fn run(&self, cfg: &Cfg) -> ReportCollection {
+        run_complexity_analysis(cfg, &self.thresholds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::cfg::IntoCfg;
+    use program_structure::constants::Curve;
+    use program_structure::report::ReportCollection;
+
+    use super::*;
+
+    #[test]
+    fn test_complexity_metrics() {
+        let src = r#"
+            template T(n) {
+                signal input in[n];
+                signal output out;
+                var sum = 0;
+                for (var i = 0; i < n; i++) {
+                    if (i % 2 == 0) {
+                        if (i % 3 == 0) {
+                            sum += 1;
+                        }
+                    } else {
+                        sum += 2;
+                    }
+                }
+                out <== sum * in[0];
+            }
+        "#;
+        let cfg = build_cfg(src);
+        let metrics = compute_complexity(&cfg);
+        assert_eq!(metrics.cyclomatic_complexity, 4);
+        assert_eq!(metrics.nesting_depth, 3);
+        assert!(metrics.statements > 0);
+
+        assert!(run_complexity_analysis(&cfg, &ComplexityThresholds::default()).is_empty());
+        let thresholds = ComplexityThresholds {
+            max_cyclomatic_complexity: 3,
+            max_statements: metrics.statements - 1,
+            max_nesting_depth: 2,
+            max_parameters: 0,
+        };
+        let codes = run_complexity_analysis(&cfg, &thresholds)
+            .iter()
+            .map(|report| report.id())
+            .collect::<Vec<_>>();
+        assert_eq!(codes, ["CS0011", "CS0054", "CS0055", "CS0012"]);
+    }
+
+    #[test]
+    fn test_complexity_without_branches() {
+        let src = r#"
+            function f(x) {
+                return x + 1;
+            }
+        "#;
+        let metrics = compute_complexity(&build_cfg(src));
+        assert_eq!(metrics.cyclomatic_complexity, 1);
+        assert_eq!(metrics.nesting_depth, 0);
+    }
+
+    fn build_cfg(src: &str) -> Cfg {
+        let mut reports = ReportCollection::new();
+        parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap()
+    }
+}
@@ -0,0 +1,296 @@
+use log::debug;
+use num_traits::Zero;
+
+use program_structure::cfg::{BasicBlock, Cfg, Index};
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::value_meta::{ValueMeta, ValueReduction};
+use program_structure::ir::*;
+
+use crate::range_analysis::{run_range_analysis, RangeAnalysis};
+
+pub struct DivisionByZeroWarning {
+    divisor: String,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl DivisionByZeroWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            format!("The divisor `{}` may be zero.", self.divisor),
+            ReportCode::DivisionByZero,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                format!("Division by `{}` here.", self.divisor),
+            );
+        }
+        report.add_note(
+            "Division by zero evaluates to 0 in Circom witness code. Consider checking that the divisor is non-zero before dividing.".to_string(),
+        );
+        report
+    }
+}
+
+/// Division by zero (using `/`, `\`, or `%`) silently evaluates to 0 during
+/// witness generation. This analysis pass reports divisions where the divisor
+/// is not known to be non-zero. The divisor is considered non-zero if the range
+/// analysis shows that the divisor is non-zero, or if the division is guarded
+/// by a condition like `x != 0` (either using an if-statement or a ternary
+/// operator `x != 0 ? 1 / x : 0`).
+pub fn find_division_by_zero(cfg: &Cfg) -> ReportCollection {
+    debug!("running division by zero analysis pass");
+    let ranges = run_range_analysis(cfg);
+    let mut reports = ReportCollection::new();
+    for basic_block in cfg.iter() {
+        let mut guards = get_guards(cfg, basic_block);
+        for stmt in basic_block.iter() {
+            visit_statement(stmt, &ranges, &mut guards, &mut reports);
+        }
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+/// Returns the expressions known to be non-zero in the given basic block,
+/// based on the conditions of the if-statements dominating the block.
+fn get_guards<'a>(cfg: &'a Cfg, basic_block: &BasicBlock) -> Vec<&'a Expression> {
+    let mut guards = Vec::new();
+    let dominators = cfg.get_dominators(basic_block);
+    for header_block in &dominators {
+        let Some(Statement::IfThenElse { cond, true_index, false_index, .. }) =
+            header_block.statements().last()
+        else {
+            continue;
+        };
+        let is_dominated_by =
+            |index: &Index| dominators.iter().any(|block| block.index() == *index);
+        if is_dominated_by(true_index) {
+            guards.extend(get_guard(cond, true));
+        }
+        if let Some(false_index) = false_index {
+            // If the false branch is empty, the false index points to the
+            // block following the if-statement.
+            let true_block = cfg.get_basic_block(*true_index).expect("in control-flow graph");
+            let is_empty = cfg
+                .get_dominance_frontier(true_block)
+                .iter()
+                .any(|block| block.index() == *false_index);
+            if !is_empty && is_dominated_by(false_index) {
+                guards.extend(get_guard(cond, false));
+            }
+        }
+    }
+    guards
+}
+
+/// Returns the expression known to be non-zero if `cond` evaluates to the
+/// given value. (That is, `x` if `cond` is `x != 0` and `value` is true, or
+/// if `cond` is `x == 0` and `value` is false.)
+fn get_guard(cond: &Expression, value: bool) -> Option<&Expression> {
+    use ExpressionInfixOpcode::*;
+    let Expression::InfixOp { lhe, infix_op, rhe, .. } = cond else {
+        return None;
+    };
+    let expr = if is_zero(rhe) {
+        lhe.as_ref()
+    } else if is_zero(lhe) {
+        rhe.as_ref()
+    } else {
+        return None;
+    };
+    match (infix_op, value) {
+        (NotEq, true) | (Eq, false) => Some(expr),
+        _ => None,
+    }
+}
+
+fn is_zero(expr: &Expression) -> bool {
+    matches!(expr.value(), Some(ValueReduction::FieldElement { value }) if value.is_zero())
+}
+
+fn visit_statement<'a>(
+    stmt: &'a Statement,
+    ranges: &RangeAnalysis,
+    guards: &mut Vec<&'a Expression>,
+    reports: &mut ReportCollection,
+) {
+    use Statement::*;
+    match stmt {
+        Declaration { dimensions, .. } => {
+            for size in dimensions {
+                visit_expression(size, ranges, guards, reports);
+            }
+        }
+        LogCall { args, .. } => {
+            use LogArgument::*;
+            for arg in args {
+                if let Expr(value) = arg {
+                    visit_expression(value, ranges, guards, reports);
+                }
+            }
+        }
+        IfThenElse { cond, .. } => visit_expression(cond, ranges, guards, reports),
+        Substitution { rhe, .. } => visit_expression(rhe, ranges, guards, reports),
+        Return { value, .. } => visit_expression(value, ranges, guards, reports),
+        Assert { arg, .. } => visit_expression(arg, ranges, guards, reports),
+        ConstraintEquality { lhe, rhe, .. } => {
+            visit_expression(lhe, ranges, guards, reports);
+            visit_expression(rhe, ranges, guards, reports);
+        }
+    }
+}
+
+fn visit_expression<'a>(
+    expr: &'a Expression,
+    ranges: &RangeAnalysis,
+    guards: &mut Vec<&'a Expression>,
+    reports: &mut ReportCollection,
+) {
+    use Expression::*;
+    use ExpressionInfixOpcode::*;
+    match expr {
+        InfixOp { meta, lhe, infix_op, rhe } => {
+            if matches!(infix_op, Div | IntDiv | Mod) && !is_non_zero(rhe, ranges, guards) {
+                reports.push(build_report(meta, rhe));
+            }
+            visit_expression(lhe, ranges, guards, reports);
+            visit_expression(rhe, ranges, guards, reports);
+        }
+        PrefixOp { rhe, .. } => {
+            visit_expression(rhe, ranges, guards, reports);
+        }
+        SwitchOp { cond, if_true, if_false, .. } => {
+            visit_expression(cond, ranges, guards, reports);
+            for (branch, value) in [(if_true, true), (if_false, false)] {
+                // Add the guard implied by the condition (if any) while
+                // visiting the corresponding branch.
+                let guard = get_guard(cond, value);
+                guards.extend(guard);
+                visit_expression(branch, ranges, guards, reports);
+                if guard.is_some() {
+                    guards.pop();
+                }
+            }
+        }
+        Call { args, .. } => {
+            for arg in args {
+                visit_expression(arg, ranges, guards, reports);
+            }
+        }
+        InlineArray { values, .. } => {
+            for value in values {
+                visit_expression(value, ranges, guards, reports);
+            }
+        }
+        Access { access, .. } => {
+            for index in access {
+                if let AccessType::ArrayAccess(index) = index {
+                    visit_expression(index, ranges, guards, reports);
+                }
+            }
+        }
+        Update { access, rhe, .. } => {
+            for index in access {
+                if let AccessType::ArrayAccess(index) = index {
+                    visit_expression(index, ranges, guards, reports);
+                }
+            }
+            visit_expression(rhe, ranges, guards, reports);
+        }
+        Number(_, _) | Variable { .. } | Phi { .. } => (),
+    }
+}
+
+/// Returns true if the divisor is guarded by a non-zero check, or if the range
+/// analysis shows that the divisor is non-zero.
+fn is_non_zero(divisor: &Expression, ranges: &RangeAnalysis, guards: &[&Expression]) -> bool {
+    guards.contains(&divisor)
+        || ranges.evaluate(divisor).is_some_and(|range| !range.contains(&Zero::zero()))
+}
+
+fn build_report(meta: &Meta, divisor: &Expression) -> Report {
+    DivisionByZeroWarning {
+        divisor: divisor.to_string(),
+        file_id: meta.file_id(),
+        file_location: meta.file_location(),
+    }
+    .into_report()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_division_by_zero() {
+        let src = r#"
+            template IsZero() {
+                signal input in;
+                signal output out;
+                signal inv;
+
+                inv <-- in != 0 ? 1 / in : 0;
+                out <== -in * inv + 1;
+                in * out === 0;
+            }
+        "#;
+        validate_reports(src, 0);
+
+        let src = r#"
+            function f(a, b) {
+                var c = 0;
+                if (b == 0) {
+                    c = a \ b;
+                } else {
+                    c = a % b;
+                }
+                return c + a / 2;
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+
+                out <-- 1 / in;
+                out * in === 1;
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            function f(a) {
+                var b = a % 4 + 1;
+                return a / b;
+            }
+        "#;
+        validate_reports(src, 0);
+    }
+
+    fn validate_reports(src: &str, expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_division_by_zero(&cfg);
+
+        assert_eq!(reports.len(), expected_len);
+    }
+}
@@ -0,0 +1,161 @@
+use log::debug;
+use std::collections::HashSet;
+
+use program_structure::cfg::{Cfg, DefinitionType};
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::variable_meta::VariableMeta;
+use program_structure::ir::*;
+
+pub struct EqualityComparisonWarning {
+    var: VariableName,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl EqualityComparisonWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            format!(
+                "The comparison assigned to `{}` does not constrain the compared signals.",
+                self.var.without_version()
+            ),
+            ReportCode::ComparisonInsteadOfConstraint,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                "The comparison `==` is only evaluated during witness generation.".to_string(),
+            );
+        }
+        report.add_note(format!(
+            "Since `{}` never occurs in a constraint, the comparison has no effect on the circuit. If the signals should be equal, use the constraint operator `===` instead.",
+            self.var.without_version()
+        ));
+        report
+    }
+}
+
+/// The comparison `a == b` only evaluates to 1 or 0 during witness
+/// generation, while the constraint `a === b` enforces that `a` and `b` are
+/// equal. This analysis pass reports assignments of bare equality comparisons
+/// over signals where the assigned value is never constrained, which typically
+/// means that `==` was used where `===` was intended.
+pub fn find_equality_comparisons(cfg: &Cfg) -> ReportCollection {
+    use DefinitionType::*;
+    if matches!(cfg.definition_type(), Function | CustomTemplate) {
+        // Exit early if this is a function or custom template.
+        return ReportCollection::new();
+    }
+    debug!("running equality comparison analysis pass");
+    // Collect all variables and signals occurring in a constraint.
+    let mut constrained = HashSet::new();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            use AssignOp::*;
+            use Statement::*;
+            if matches!(
+                stmt,
+                ConstraintEquality { .. } | Substitution { op: AssignConstraintSignal, .. }
+            ) {
+                constrained.extend(stmt.variables_used().map(|var| var.name().clone()));
+            }
+        }
+    }
+    let mut reports = ReportCollection::new();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            visit_statement(stmt, &constrained, &mut reports);
+        }
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+fn visit_statement(
+    stmt: &Statement,
+    constrained: &HashSet<VariableName>,
+    reports: &mut ReportCollection,
+) {
+    use AssignOp::*;
+    use Expression::*;
+    let Statement::Substitution { meta, var, op: AssignLocalOrComponent | AssignSignal, rhe } =
+        stmt
+    else {
+        return;
+    };
+    // Assignments to array elements are lifted to updates.
+    let rhe = if let Update { rhe, .. } = rhe { rhe.as_ref() } else { rhe };
+    if !matches!(rhe, InfixOp { infix_op: ExpressionInfixOpcode::Eq, .. }) {
+        return;
+    }
+    if rhe.signals_read().is_empty() || constrained.contains(var) {
+        return;
+    }
+    reports.push(build_report(var, meta));
+}
+
+fn build_report(var: &VariableName, meta: &Meta) -> Report {
+    EqualityComparisonWarning {
+        var: var.clone(),
+        file_id: meta.file_id(),
+        file_location: meta.file_location(),
+    }
+    .into_report()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_equality_comparison() {
+        let src = r#"
+            template T() {
+                signal input a;
+                signal input b;
+                signal output out;
+                var equal = a == b;
+                signal c;
+                c <-- a == b;
+                out <== a * b;
+            }
+        "#;
+        validate_reports(src, 2);
+
+        let src = r#"
+            template T(n) {
+                signal input a;
+                signal input b;
+                signal output out;
+                var equal = n == 1;
+                var same = a == b;
+                same === 1;
+                out <-- a == b;
+                out * (out - 1) === 0;
+            }
+        "#;
+        validate_reports(src, 0);
+    }
+
+    fn validate_reports(src: &str, expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_equality_comparisons(&cfg);
+        assert_eq!(reports.len(), expected_len);
+    }
+}
@@ -6,6 +6,8 @@ use program_structure::report::{Report, ReportCollection};
 use program_structure::file_definition::{FileID, FileLocation};
 use program_structure::ir::*;
 
+use crate::range_analysis::{run_range_analysis, RangeAnalysis};
+
 pub struct FieldElementArithmeticWarning {
     file_id: Option<FileID>,
     file_location: FileLocation,
@@ -32,87 +34,91 @@ impl FieldElementArithmeticWarning {
 /// Field element arithmetic in Circom may overflow, which could produce
 /// unexpected results. Worst case, it may allow a malicious prover to forge
 /// proofs.
+///
+/// Arithmetic where the range analysis shows that the result is contained in
+/// `(-p/2, p/2]` cannot overflow and is not reported.
 pub fn find_field_element_arithmetic(cfg: &Cfg) -> ReportCollection {
     debug!("running field element arithmetic analysis pass");
     let mut reports = ReportCollection::new();
+    let ranges = run_range_analysis(cfg);
     for basic_block in cfg.iter() {
         for stmt in basic_block.iter() {
-            visit_statement(stmt, &mut reports);
+            visit_statement(stmt, &ranges, &mut reports);
         }
     }
     debug!("{} new reports generated", reports.len());
     reports
 }
 
-fn visit_statement(stmt: &Statement, reports: &mut ReportCollection) {
+fn visit_statement(stmt: &Statement, ranges: &RangeAnalysis, reports: &mut ReportCollection) {
     use Statement::*;
     match stmt {
         Declaration { dimensions, .. } => {
             for size in dimensions {
-                visit_expression(size, reports);
+                visit_expression(size, ranges, reports);
             }
         }
         LogCall { args, .. } => {
             use LogArgument::*;
             for arg in args {
                 if let Expr(value) = arg {
-                    visit_expression(value, reports);
+                    visit_expression(value, ranges, reports);
                 }
             }
         }
-        IfThenElse { cond, .. } => visit_expression(cond, reports),
-        Substitution { rhe, .. } => visit_expression(rhe, reports),
-        Return { value, .. } => visit_expression(value, reports),
-        Assert { arg, .. } => visit_expression(arg, reports),
+        IfThenElse { cond, .. } => visit_expression(cond, ranges, reports),
+        Substitution { rhe, .. } => visit_expression(rhe, ranges, reports),
+        Return { value, .. } => visit_expression(value, ranges, reports),
+        Assert { arg, .. } => visit_expression(arg, ranges, reports),
         ConstraintEquality { lhe, rhe, .. } => {
-            visit_expression(lhe, reports);
-            visit_expression(rhe, reports);
+            visit_expression(lhe, ranges, reports);
+            visit_expression(rhe, ranges, reports);
         }
     }
 }
 
-fn visit_expression(expr: &Expression, reports: &mut ReportCollection) {
+fn visit_expression(expr: &Expression, ranges: &RangeAnalysis, reports: &mut ReportCollection) {
     use Expression::*;
     match expr {
-        InfixOp { meta, infix_op, .. } if may_overflow(infix_op) => {
+        InfixOp { meta, infix_op, .. } if may_overflow(infix_op) && !is_reduced(expr, ranges) => {
             reports.push(build_report(meta));
         }
         InfixOp { lhe, rhe, .. } => {
-            visit_expression(lhe, reports);
-            visit_expression(rhe, reports);
+            visit_expression(lhe, ranges, reports);
+            visit_expression(rhe, ranges, reports);
         }
         PrefixOp { rhe, .. } => {
-            visit_expression(rhe, reports);
+            visit_expression(rhe, ranges, reports);
         }
         SwitchOp { cond, if_true, if_false, .. } => {
-            visit_expression(cond, reports);
-            visit_expression(if_true, reports);
-            visit_expression(if_false, reports);
+            visit_expression(cond, ranges, reports);
+            visit_expression(if_true, ranges, reports);
+            visit_expression(if_false, ranges, reports);
         }
         Call { args, .. } => {
             for arg in args {
-                visit_expression(arg, reports);
+                visit_expression(arg, ranges, reports);
             }
         }
         InlineArray { values, .. } => {
             for value in values {
-                visit_expression(value, reports);
+                visit_expression(value, ranges, reports);
             }
         }
         Access { access, .. } => {
             for index in access {
                 if let AccessType::ArrayAccess(index) = index {
-                    visit_expression(index, reports);
+                    visit_expression(index, ranges, reports);
                 }
             }
         }
         Update { access, rhe, .. } => {
             for index in access {
                 if let AccessType::ArrayAccess(index) = index {
-                    visit_expression(index, reports);
+                    visit_expression(index, ranges, reports);
                 }
             }
-            visit_expression(rhe, reports);
+            visit_expression(rhe, ranges, reports);
         }
         Number(_, _) | Variable { .. } | Phi { .. } => (),
     }
@@ -132,6 +138,12 @@ fn may_overflow(op: &ExpressionInfixOpcode) -> bool {
     is_arithmetic_infix_op(op) && !matches!(op, IntDiv | Mod | BitOr | BitAnd | BitXor)
 }
 
+/// Returns true if the range analysis shows that the value of the expression
+/// is contained in `(-p/2, p/2]`.
+fn is_reduced(expr: &Expression, ranges: &RangeAnalysis) -> bool {
+    ranges.evaluate(expr).is_some_and(|range| range.is_reduced(ranges.prime()))
+}
+
 fn build_report(meta: &Meta) -> Report {
     FieldElementArithmeticWarning { file_id: meta.file_id(), file_location: meta.file_location() }
         .into_report()
@@ -154,6 +166,15 @@ mod tests {
             }
         "#;
         validate_reports(src, 2);
+
+        let src = r#"
+            function f(a) {
+                var b = a % 16;
+                var c = (a & 255) * b + 1;
+                return b + c;
+            }
+        "#;
+        validate_reports(src, 0);
     }
 
     fn validate_reports(src: &str, expected_len: usize) {
@@ -0,0 +1,209 @@
+use log::debug;
+
+use program_structure::cfg::{Cfg, DefinitionType};
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::*;
+
+use crate::range_analysis::{run_range_analysis, Interval, RangeAnalysis};
+
+pub struct FieldElementOverflowWarning {
+    range: Interval,
+    prime_size: usize,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl FieldElementOverflowWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            "Constraint arithmetic may overflow the field modulus, which could allow unexpected (aliased) values to satisfy the constraint.".to_string(),
+            ReportCode::FieldElementOverflow,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                format!(
+                    "The value of this expression may require up to {} bits.",
+                    self.range.bits()
+                ),
+            );
+        }
+        report.add_note(format!(
+            "The result is reduced modulo the {}-bit prime `p`, so values differing by a multiple of `p` cannot be distinguished.",
+            self.prime_size
+        ));
+        report
+    }
+}
+
+/// Arithmetic in constraints is performed modulo the prime `p`. If the range
+/// analysis shows that the operands of an arithmetic expression are bounded,
+/// but that the result may not fit in a field element, the result will wrap
+/// around the prime. For example, if `x` is a 200-bit value, then `x * x` may
+/// exceed `p` over BN128, which means that the constraint `y === x * x` does
+/// not uniquely determine `x` over the integers.
+pub fn find_field_element_overflow(cfg: &Cfg) -> ReportCollection {
+    use DefinitionType::*;
+    if matches!(cfg.definition_type(), Function | CustomTemplate) {
+        // Exit early if this is a function or custom template.
+        return ReportCollection::new();
+    }
+    debug!("running field element overflow analysis pass");
+    let mut reports = ReportCollection::new();
+    let ranges = run_range_analysis(cfg);
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            visit_statement(stmt, &ranges, &mut reports);
+        }
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+fn visit_statement(stmt: &Statement, ranges: &RangeAnalysis, reports: &mut ReportCollection) {
+    use AssignOp::*;
+    use Expression::Update;
+    use Statement::*;
+    match stmt {
+        Substitution { op: AssignConstraintSignal, rhe, .. } => {
+            // Assignments to array elements are lifted to updates.
+            let rhe = if let Update { rhe, .. } = rhe { rhe.as_ref() } else { rhe };
+            visit_expression(rhe, ranges, reports);
+        }
+        ConstraintEquality { lhe, rhe, .. } => {
+            visit_expression(lhe, ranges, reports);
+            visit_expression(rhe, ranges, reports);
+        }
+        _ => {}
+    }
+}
+
+/// Visit the expression and report the innermost arithmetic expressions that
+/// may overflow. Returns true if a report was generated.
+fn visit_expression(
+    expr: &Expression,
+    ranges: &RangeAnalysis,
+    reports: &mut ReportCollection,
+) -> bool {
+    use Expression::*;
+    use ExpressionInfixOpcode::*;
+    match expr {
+        InfixOp { meta, lhe, infix_op: Add | Sub | Mul | ShiftL | Pow, rhe } => {
+            let mut reported = visit_expression(lhe, ranges, reports);
+            reported = visit_expression(rhe, ranges, reports) || reported;
+            if reported {
+                return true;
+            }
+            // Only arithmetic on bounded operands is reported.
+            if ranges.evaluate(lhe).is_none() || ranges.evaluate(rhe).is_none() {
+                return false;
+            }
+            match ranges.evaluate(expr) {
+                Some(range) if !range.fits_in_field(ranges.prime()) => {
+                    reports.push(build_report(meta, range, ranges));
+                    true
+                }
+                _ => false,
+            }
+        }
+        InfixOp { lhe, rhe, .. } => {
+            let reported = visit_expression(lhe, ranges, reports);
+            visit_expression(rhe, ranges, reports) || reported
+        }
+        PrefixOp { rhe, .. } => visit_expression(rhe, ranges, reports),
+        SwitchOp { cond, if_true, if_false, .. } => {
+            let mut reported = visit_expression(cond, ranges, reports);
+            reported = visit_expression(if_true, ranges, reports) || reported;
+            visit_expression(if_false, ranges, reports) || reported
+        }
+        Call { .. }
+        | InlineArray { .. }
+        | Access { .. }
+        | Update { .. }
+        | Number(_, _)
+        | Variable { .. }
+        | Phi { .. } => false,
+    }
+}
+
+fn build_report(meta: &Meta, range: Interval, ranges: &RangeAnalysis) -> Report {
+    FieldElementOverflowWarning {
+        range,
+        prime_size: ranges.prime().bits(),
+        file_id: meta.file_id(),
+        file_location: meta.file_location(),
+    }
+    .into_report()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_field_overflow() {
+        let src = r#"
+            template T() {
+                signal input a;
+                signal input b;
+                signal x;
+                signal output out;
+
+                a * (a - 1) === 0;
+                b * (b - 1) === 0;
+                x <== a * 2**200;
+                out <== x * x + b;
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            template T() {
+                signal input a;
+                signal input b;
+                signal x;
+                signal output out;
+
+                a * (a - 1) === 0;
+                b * (b - 1) === 0;
+                x <== a * 2**100;
+                out <== x * x + b;
+            }
+        "#;
+        validate_reports(src, 0);
+
+        let src = r#"
+            template T() {
+                signal input a;
+                signal input b;
+                signal output out;
+
+                out <== a * b;
+            }
+        "#;
+        validate_reports(src, 0);
+    }
+
+    fn validate_reports(src: &str, expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_field_element_overflow(&cfg);
+
+        assert_eq!(reports.len(), expected_len);
+    }
+}
@@ -0,0 +1,197 @@
+use log::debug;
+use std::collections::HashSet;
+
+use program_structure::cfg::{Cfg, DefinitionType};
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::*;
+
+use crate::constraint_analysis::run_constraint_analysis;
+
+pub struct FreeSignalConstraintWarning {
+    lhs_name: VariableName,
+    rhs_name: VariableName,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl FreeSignalConstraintWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            format!(
+                "The constraint relates the signals `{}` and `{}`, which are both assigned using `<--` and never constrained by the inputs or outputs of the template.",
+                self.lhs_name, self.rhs_name
+            ),
+            ReportCode::FreeSignalConstraint,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                "This constraint can be trivially satisfied by the prover.".to_string(),
+            );
+        }
+        report.add_note(format!(
+            "Since both `{}` and `{}` are chosen freely by the prover, the prover can satisfy the constraint by assigning the same value to both signals.",
+            self.lhs_name, self.rhs_name
+        ));
+        report
+    }
+}
+
+/// A constraint `a === b` where both `a` and `b` are signals assigned using
+/// `<--`, and neither signal is constrained (in one or more steps) together
+/// with an input signal, an output signal, or a subcomponent, can always be
+/// satisfied by the prover. This analysis pass reports each such constraint.
+pub fn find_free_signal_constraints(cfg: &Cfg) -> ReportCollection {
+    debug!("running free signal constraint analysis pass");
+    let mut reports = ReportCollection::new();
+    if !matches!(cfg.definition_type(), DefinitionType::Template | DefinitionType::CustomTemplate) {
+        return reports;
+    }
+    let mut hints = HashSet::new();
+    let mut constrained = HashSet::new();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            match stmt {
+                Statement::Substitution { var, op: AssignOp::AssignSignal, .. } => {
+                    hints.insert(var.clone());
+                }
+                Statement::Substitution { var, op: AssignOp::AssignConstraintSignal, .. } => {
+                    constrained.insert(var.clone());
+                }
+                _ => {}
+            }
+        }
+    }
+    if hints.is_empty() {
+        return reports;
+    }
+    let constraint_analysis = run_constraint_analysis(cfg);
+    let is_free = |name: &VariableName| {
+        hints.contains(name)
+            && !constrained.contains(name)
+            && !is_anchor(cfg, name)
+            && !constraint_analysis
+                .multi_step_constraint(name)
+                .iter()
+                .any(|name| is_anchor(cfg, name))
+    };
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            let Statement::ConstraintEquality { meta, lhe, rhe } = stmt else {
+                continue;
+            };
+            let (Some(lhs_name), Some(rhs_name)) = (signal_name(lhe), signal_name(rhe)) else {
+                continue;
+            };
+            if is_free(lhs_name) && is_free(rhs_name) {
+                reports.push(
+                    FreeSignalConstraintWarning {
+                        lhs_name: lhs_name.clone(),
+                        rhs_name: rhs_name.clone(),
+                        file_id: meta.file_id(),
+                        file_location: meta.file_location(),
+                    }
+                    .into_report(),
+                );
+            }
+        }
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+/// Returns the name of the signal if the expression is a signal (or signal
+/// array element).
+fn signal_name(expr: &Expression) -> Option<&VariableName> {
+    match expr {
+        Expression::Variable { meta, name } | Expression::Access { meta, var: name, .. }
+            if meta.type_knowledge().is_signal() =>
+        {
+            Some(name)
+        }
+        _ => None,
+    }
+}
+
+/// Input signals, output signals, and components anchor the signals that they
+/// are constrained together with.
+fn is_anchor(cfg: &Cfg, name: &VariableName) -> bool {
+    matches!(
+        cfg.get_type(name),
+        Some(
+            VariableType::Signal(SignalType::Input | SignalType::Output, _)
+                | VariableType::Component
+        )
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_free_signal_constraints() {
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                signal a;
+                signal b;
+                a <-- in * 2;
+                b <-- in * 2;
+                a === b;
+                out <== in * 2;
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                signal a;
+                signal b;
+                a <-- in * 2;
+                b <-- in * 2;
+                a === b;
+                a === 2 * in;
+                out <== a;
+            }
+        "#;
+        validate_reports(src, 0);
+
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                signal a;
+                a <-- in * 2;
+                out <-- in * 2;
+                a === out;
+            }
+        "#;
+        validate_reports(src, 0);
+    }
+
+    fn validate_reports(src: &str, expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_free_signal_constraints(&cfg);
+        assert_eq!(reports.len(), expected_len);
+    }
+}
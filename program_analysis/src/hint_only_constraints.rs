@@ -0,0 +1,256 @@
+use log::debug;
+use std::collections::{HashMap, HashSet};
+
+use program_structure::cfg::Cfg;
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::variable_meta::VariableMeta;
+use program_structure::ir::*;
+
+pub struct HintOnlyConstraintWarning {
+    locals: Vec<VariableName>,
+    signals: Vec<VariableName>,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl HintOnlyConstraintWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            "The constraint only depends on signals assigned using `<--`.".to_string(),
+            ReportCode::HintOnlyConstraint,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                "This constraint does not depend on any input or constrained signals.".to_string(),
+            );
+        }
+        let names = |names: &[VariableName]| {
+            names.iter().map(|name| format!("`{name}`")).collect::<Vec<_>>().join(", ")
+        };
+        report.add_note(format!(
+            "The variable(s) {} used by the constraint only depend on the signal(s) {}, which are assigned using `<--`.",
+            names(&self.locals),
+            names(&self.signals)
+        ));
+        report.add_note(
+            "This means that the constraint only relates values chosen by the prover to each other, and does not bind them to the inputs of the template.".to_string()
+        );
+        report
+    }
+}
+
+/// Signals assigned using `<--` are unconstrained hints provided by the
+/// prover. A constraint which only depends on these hints (typically through
+/// a variable accumulating the hinted values) does not bind the hints to the
+/// inputs of the template. This analysis pass reports constraints `a === b`
+/// reading a local variable, where every signal and component that the
+/// constraint depends on (either directly, or through local variables) is a
+/// signal assigned using `<--`.
+pub fn find_hint_only_constraints(cfg: &Cfg) -> ReportCollection {
+    debug!("running hint-only constraint analysis pass");
+    let mut hints = HashSet::new();
+    let mut dependencies = HashMap::<VariableName, HashSet<VariableName>>::new();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            let Statement::Substitution { meta, var, op, .. } = stmt else {
+                continue;
+            };
+            if matches!(op, AssignOp::AssignSignal) {
+                hints.insert(var.without_version());
+            }
+            if meta.type_knowledge().is_local() {
+                dependencies
+                    .entry(var.clone())
+                    .or_default()
+                    .extend(stmt.variables_read().map(|var_use| var_use.name().clone()));
+            }
+        }
+    }
+    let mut reports = ReportCollection::new();
+    if hints.is_empty() {
+        return reports;
+    }
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            let Statement::ConstraintEquality { meta, lhe, rhe } = stmt else {
+                continue;
+            };
+            // Local variables only used as array indices are ignored.
+            let mut locals = Vec::new();
+            value_locals(lhe, &mut locals);
+            value_locals(rhe, &mut locals);
+            if locals.is_empty() {
+                continue;
+            }
+            let mut leaves = stmt
+                .signals_read()
+                .iter()
+                .chain(stmt.components_read().iter())
+                .map(|var_use| var_use.name().without_version())
+                .collect::<HashSet<_>>();
+            leaves.extend(local_dependencies(cfg, &dependencies, &locals));
+            if !leaves.is_empty() && leaves.is_subset(&hints) {
+                reports.push(build_report(meta, &locals, leaves));
+            }
+        }
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+/// Collects the local variables occurring in the expression, except for local
+/// variables only occurring in array indices.
+fn value_locals(expr: &Expression, locals: &mut Vec<VariableName>) {
+    use Expression::*;
+    match expr {
+        Variable { meta, name } if meta.type_knowledge().is_local() => locals.push(name.clone()),
+        InfixOp { lhe, rhe, .. } => {
+            value_locals(lhe, locals);
+            value_locals(rhe, locals);
+        }
+        PrefixOp { rhe, .. } => value_locals(rhe, locals),
+        SwitchOp { if_true, if_false, .. } => {
+            value_locals(if_true, locals);
+            value_locals(if_false, locals);
+        }
+        Call { args: values, .. } | InlineArray { values, .. } => {
+            for value in values {
+                value_locals(value, locals);
+            }
+        }
+        Access { meta, var, .. } if meta.type_knowledge().is_local() => locals.push(var.clone()),
+        Variable { .. } | Access { .. } | Number(..) | Update { .. } | Phi { .. } => {}
+    }
+}
+
+/// Returns the signals and components that the given local variables depend
+/// on through assignments to local variables.
+pub(crate) fn local_dependencies(
+    cfg: &Cfg,
+    dependencies: &HashMap<VariableName, HashSet<VariableName>>,
+    locals: &[VariableName],
+) -> HashSet<VariableName> {
+    let mut leaves = HashSet::new();
+    let mut visited = locals.iter().cloned().collect::<HashSet<_>>();
+    let mut worklist = locals.to_vec();
+    while let Some(local) = worklist.pop() {
+        for var in dependencies.get(&local).into_iter().flatten() {
+            if dependencies.contains_key(var) {
+                if visited.insert(var.clone()) {
+                    worklist.push(var.clone());
+                }
+            } else if !matches!(cfg.get_type(var), Some(VariableType::Local) | None) {
+                leaves.insert(var.without_version());
+            }
+        }
+    }
+    leaves
+}
+
+fn build_report(meta: &Meta, locals: &[VariableName], signals: HashSet<VariableName>) -> Report {
+    let mut locals = locals
+        .iter()
+        .map(VariableName::without_version)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    locals.sort_by_key(ToString::to_string);
+    let mut signals = signals.into_iter().collect::<Vec<_>>();
+    signals.sort_by_key(ToString::to_string);
+    HintOnlyConstraintWarning {
+        locals,
+        signals,
+        file_id: meta.file_id(),
+        file_location: meta.file_location(),
+    }
+    .into_report()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_hint_only_constraints() {
+        let src = r#"
+            template Num2Bits(n) {
+                signal input in;
+                signal output out[n];
+                var lc1 = 0;
+                var e2 = 1;
+                for (var i = 0; i < n; i++) {
+                    out[i] <-- (in >> i) & 1;
+                    out[i] * (out[i] - 1) === 0;
+                    lc1 += out[i] * e2;
+                    e2 = e2 + e2;
+                }
+                lc1 === in;
+            }
+        "#;
+        validate_reports(src, 0);
+
+        let src = r#"
+            template Num2Bits(n) {
+                signal input in;
+                signal output out[n];
+                signal sum;
+                var lc1 = 0;
+                var e2 = 1;
+                for (var i = 0; i < n; i++) {
+                    out[i] <-- (in >> i) & 1;
+                    out[i] * (out[i] - 1) === 0;
+                    lc1 += out[i] * e2;
+                    e2 = e2 + e2;
+                }
+                sum <-- lc1;
+                lc1 === sum;
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                out <-- in / 2;
+                var x = 2 * out;
+                x === 2 * out;
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                out <-- in / 2;
+                var x = 2 * out;
+                x === in;
+            }
+        "#;
+        validate_reports(src, 0);
+    }
+
+    fn validate_reports(src: &str, expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_hint_only_constraints(&cfg);
+        assert_eq!(reports.len(), expected_len);
+    }
+}
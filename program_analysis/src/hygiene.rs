@@ -0,0 +1,212 @@
+//! Reports `log` statements and TODO/FIXME comments in the templates and
+//! functions reachable from the main component. These are often left over
+//! from development, and frequently mark unfinished constraint work.
+use log::debug;
+
+use program_structure::ast::{Comment, Statement};
+use program_structure::file_definition::{FileID, FileLibrary, FileLocation};
+use program_structure::function_data::FunctionInfo;
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::template_data::TemplateInfo;
+
+use crate::instantiation::InstantiationMetrics;
+
+/// Comment markers reported by the hygiene checks.
+const MARKERS: [&str; 2] = ["TODO", "FIXME"];
+
+pub struct LogStatementWarning {
+    definition_name: String,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl LogStatementWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            format!("The definition `{}` contains a `log` statement.", self.definition_name),
+            ReportCode::LogStatement,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                "This `log` statement is reachable from the main component.".to_string(),
+            );
+        }
+        report.add_note(
+            "`log` statements are typically used for debugging. Consider removing them from production circuits.".to_string(),
+        );
+        report
+    }
+}
+
+pub struct TodoCommentWarning {
+    definition_name: String,
+    marker: &'static str,
+    file_id: FileID,
+    file_location: FileLocation,
+}
+
+impl TodoCommentWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            format!(
+                "The definition `{}` contains a `{}` comment.",
+                self.definition_name, self.marker
+            ),
+            ReportCode::TodoComment,
+        );
+        report.add_primary(
+            self.file_location,
+            self.file_id,
+            "This comment may mark unfinished work.".to_string(),
+        );
+        report.add_note(
+            "Make sure that the comment does not mark missing constraints before deploying the circuit.".to_string(),
+        );
+        report
+    }
+}
+
+/// Reports each `log` statement and each TODO/FIXME comment in the templates
+/// and functions reachable from the main component.
+#[must_use]
+pub fn find_hygiene_issues(
+    templates: &TemplateInfo,
+    functions: &FunctionInfo,
+    reachable: &InstantiationMetrics,
+    file_library: &FileLibrary,
+) -> ReportCollection {
+    debug!("running hygiene checks");
+    let mut reports = ReportCollection::new();
+    let template_bodies = reachable.instances.keys().filter_map(|name| {
+        templates.get(name).map(|template| (name, template.get_file_id(), template.get_body()))
+    });
+    let function_bodies = reachable.functions.iter().filter_map(|name| {
+        functions.get(name).map(|function| (name, function.get_file_id(), function.get_body()))
+    });
+    for (name, file_id, body) in template_bodies.chain(function_bodies) {
+        let mut log_statements = Vec::new();
+        find_log_statements(body, &mut log_statements);
+        for stmt in log_statements {
+            reports.push(
+                LogStatementWarning {
+                    definition_name: name.clone(),
+                    file_id: stmt.get_meta().file_id,
+                    file_location: stmt.get_meta().file_location(),
+                }
+                .into_report(),
+            );
+        }
+        let Some(trivia) = file_library.get_trivia(file_id) else {
+            continue;
+        };
+        for comment in trivia.comments_in(&body.get_meta().file_location()) {
+            if let Some(marker) = find_marker(comment) {
+                reports.push(
+                    TodoCommentWarning {
+                        definition_name: name.clone(),
+                        marker,
+                        file_id,
+                        file_location: comment.location.clone(),
+                    }
+                    .into_report(),
+                );
+            }
+        }
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+fn find_log_statements<'a>(stmt: &'a Statement, log_statements: &mut Vec<&'a Statement>) {
+    use Statement::*;
+    match stmt {
+        LogCall { .. } => log_statements.push(stmt),
+        IfThenElse { if_case, else_case, .. } => {
+            find_log_statements(if_case, log_statements);
+            if let Some(else_case) = else_case {
+                find_log_statements(else_case, log_statements);
+            }
+        }
+        While { stmt, .. } => find_log_statements(stmt, log_statements),
+        Block { stmts, .. } => {
+            for stmt in stmts {
+                find_log_statements(stmt, log_statements);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns the first marker occurring as a separate word in the comment.
+fn find_marker(comment: &Comment) -> Option<&'static str> {
+    let text = &comment.text;
+    MARKERS.into_iter().find(|marker| {
+        text.match_indices(marker).any(|(start, _)| {
+            let end = start + marker.len();
+            !text[..start].chars().next_back().is_some_and(char::is_alphanumeric)
+                && !text[end..].chars().next().is_some_and(char::is_alphanumeric)
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{parse_source, ParseResult};
+
+    use crate::instantiation::estimate_instantiation;
+
+    use super::*;
+
+    #[test]
+    fn test_hygiene_issues() {
+        let src = r#"
+            pragma circom 2.0.0;
+
+            template Main() {
+                signal input in;
+                signal output out;
+                // TODO: Constrain `in` to be non-zero.
+                log("in: ", in);
+                var k = f(2);
+                out <== k * in;
+            }
+
+            function f(x) {
+                if (x > 0) {
+                    log(x); /* FIXME */
+                }
+                // TODOS and NOTODO are not markers.
+                return x;
+            }
+
+            template Unused() {
+                // TODO: Implement this template.
+                log("unused");
+            }
+
+            component main = Main();
+        "#;
+        let ParseResult::Program(program, _) = parse_source("main.circom", src, &[], &[], "2.1.0")
+        else {
+            panic!("failed to parse program");
+        };
+        let reachable = estimate_instantiation(
+            &program.initial_template_call,
+            &program.templates,
+            &program.functions,
+            &Default::default(),
+        )
+        .unwrap();
+        let reports = find_hygiene_issues(
+            &program.templates,
+            &program.functions,
+            &reachable,
+            &program.file_library,
+        );
+        let codes = reports.iter().map(|report| report.id()).collect::<Vec<_>>();
+        assert_eq!(codes, ["CS0048", "CS0049", "CS0048", "CS0049"]);
+    }
+}
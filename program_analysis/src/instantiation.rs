@@ -0,0 +1,625 @@
+//! Estimates the cost of instantiating the main component of a program, by
+//! interpreting the program starting from the main component, using the
+//! constant parameter values passed to each template.
+use log::debug;
+use num_bigint::BigInt;
+use num_traits::{ToPrimitive, Zero};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use circom_algebra::modular_arithmetic;
+use program_structure::ast::{
+    AssignOp, Access, Expression, ExpressionInfixOpcode, ExpressionPrefixOpcode, Statement,
+    VariableType,
+};
+use program_structure::constants::{Curve, UsefulConstants};
+use program_structure::function_data::FunctionInfo;
+use program_structure::template_data::TemplateInfo;
+
+/// The maximum number of statements executed before the interpreter gives up.
+const MAX_STEPS: usize = 10_000_000;
+
+/// The maximum depth of nested template instantiations and function calls.
+const MAX_DEPTH: usize = 128;
+
+/// The maximum number of elements of a variable array tracked by the
+/// interpreter. Larger arrays are treated as unknown.
+const MAX_ARRAY_SIZE: usize = 1 << 20;
+
+/// The estimated cost of all instances of a single template.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TemplateCost {
+    /// The number of instances of the template.
+    pub instances: usize,
+    /// The number of signals declared by the instances.
+    pub signals: usize,
+    /// The number of constraints generated by the instances. Each `<==` and
+    /// `===` statement executed is counted as a single constraint.
+    pub constraints: usize,
+}
+
+impl TemplateCost {
+    fn add(&mut self, other: &TemplateCost) {
+        self.instances += other.instances;
+        self.signals = self.signals.saturating_add(other.signals);
+        self.constraints += other.constraints;
+    }
+}
+
+/// The estimated cost of instantiating the main component of a program.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InstantiationMetrics {
+    /// The estimated cost of each template instantiated by the program.
+    pub templates: BTreeMap<String, TemplateCost>,
+    /// The distinct arguments passed to each template instantiated by the
+    /// program. Unknown arguments (and array arguments) are given as `None`.
+    pub instances: BTreeMap<String, BTreeSet<Vec<Option<BigInt>>>>,
+    /// The functions called by the program.
+    pub functions: BTreeSet<String>,
+    /// True if some values (like loop bounds, conditions, or array sizes)
+    /// could not be evaluated, in which case the metrics are approximate.
+    pub approximate: bool,
+}
+
+impl InstantiationMetrics {
+    /// Returns the total cost of the program.
+    #[must_use]
+    pub fn total(&self) -> TemplateCost {
+        let mut total = TemplateCost::default();
+        for cost in self.templates.values() {
+            total.add(cost);
+        }
+        total
+    }
+}
+
+/// Estimates the number of instances, signals, and constraints for each
+/// template instantiated by the given main component. Returns `None` if the
+/// main component is not a call to a known template.
+#[must_use]
+pub fn estimate_instantiation(
+    main_component: &Expression,
+    templates: &TemplateInfo,
+    functions: &FunctionInfo,
+    curve: &Curve,
+) -> Option<InstantiationMetrics> {
+    debug!("estimating the cost of instantiating the main component");
+    let Expression::Call { id, args, .. } = main_component else {
+        return None;
+    };
+    if !templates.contains_key(id) {
+        return None;
+    }
+    let mut interpreter = Interpreter::new(templates, functions, curve);
+    let mut env = HashMap::new();
+    let args = args.iter().map(|arg| interpreter.eval(arg, &mut env, 0)).collect();
+    let costs = interpreter.instantiate(id, args, 0);
+    Some(interpreter.into_metrics(costs))
+}
+
+/// Estimates the number of instances, signals, and constraints for each
+/// template instantiated by the given template, assuming that the values of
+/// the template parameters are unknown. Returns `None` if the template is not
+/// defined.
+#[must_use]
+pub fn estimate_template_instantiation(
+    name: &str,
+    templates: &TemplateInfo,
+    functions: &FunctionInfo,
+    curve: &Curve,
+) -> Option<InstantiationMetrics> {
+    debug!("estimating the cost of instantiating `{name}`");
+    let args = vec![Value::Unknown; templates.get(name)?.get_num_of_params()];
+    let mut interpreter = Interpreter::new(templates, functions, curve);
+    let costs = interpreter.instantiate(name, args, 0);
+    Some(interpreter.into_metrics(costs))
+}
+
+/// A value computed by the interpreter.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Value {
+    Unknown,
+    Scalar(BigInt),
+    Array(Vec<Value>),
+}
+
+impl Value {
+    /// Returns an array with the given dimensions where each element is
+    /// initialized to zero.
+    fn zeros(dimensions: &[usize]) -> Value {
+        match dimensions.split_first() {
+            Some((size, dimensions)) => Value::Array(vec![Value::zeros(dimensions); *size]),
+            None => Value::Scalar(BigInt::zero()),
+        }
+    }
+
+    fn to_scalar(&self) -> Option<BigInt> {
+        match self {
+            Value::Scalar(value) => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    fn to_usize(&self) -> Option<usize> {
+        match self {
+            Value::Scalar(value) => value.to_usize(),
+            _ => None,
+        }
+    }
+
+    fn get(&self, indices: &[Option<usize>]) -> Value {
+        match (indices.split_first(), self) {
+            (None, value) => value.clone(),
+            (Some((Some(index), indices)), Value::Array(values)) if *index < values.len() => {
+                values[*index].get(indices)
+            }
+            _ => Value::Unknown,
+        }
+    }
+
+    fn set(&mut self, indices: &[Option<usize>], value: Value) {
+        match (indices.split_first(), self) {
+            (None, target) => *target = value,
+            (Some((Some(index), indices)), Value::Array(values)) if *index < values.len() => {
+                values[*index].set(indices, value)
+            }
+            (_, target) => *target = Value::Unknown,
+        }
+    }
+}
+
+/// The result of executing a statement.
+enum Flow {
+    Continue,
+    Return(Value),
+}
+
+/// The local state of a template instance or function call.
+#[derive(Default)]
+struct Frame {
+    env: HashMap<String, Value>,
+    costs: BTreeMap<String, TemplateCost>,
+    template: Option<String>,
+}
+
+impl Frame {
+    fn cost(&mut self) -> Option<&mut TemplateCost> {
+        let template = self.template.as_ref()?;
+        self.costs.get_mut(template)
+    }
+}
+
+struct Interpreter<'a> {
+    templates: &'a TemplateInfo,
+    functions: &'a FunctionInfo,
+    prime: BigInt,
+    steps: usize,
+    approximate: bool,
+    functions_called: BTreeSet<String>,
+    // The cost of each template instance, indexed by name and arguments.
+    instances: HashMap<(String, Vec<Value>), BTreeMap<String, TemplateCost>>,
+}
+
+impl<'a> Interpreter<'a> {
+    fn new(
+        templates: &'a TemplateInfo,
+        functions: &'a FunctionInfo,
+        curve: &Curve,
+    ) -> Interpreter<'a> {
+        Interpreter {
+            templates,
+            functions,
+            prime: UsefulConstants::new(curve).prime().clone(),
+            steps: 0,
+            approximate: false,
+            functions_called: BTreeSet::new(),
+            instances: HashMap::new(),
+        }
+    }
+
+    fn into_metrics(self, costs: BTreeMap<String, TemplateCost>) -> InstantiationMetrics {
+        let mut instances = BTreeMap::<_, BTreeSet<_>>::new();
+        for (name, args) in self.instances.into_keys() {
+            let args = args.iter().map(Value::to_scalar).collect();
+            instances.entry(name).or_default().insert(args);
+        }
+        InstantiationMetrics {
+            templates: costs,
+            instances,
+            functions: self.functions_called,
+            approximate: self.approximate,
+        }
+    }
+
+    /// Returns the cost of instantiating the given template (including all
+    /// sub-components) with the given arguments.
+    fn instantiate(
+        &mut self,
+        name: &str,
+        args: Vec<Value>,
+        depth: usize,
+    ) -> BTreeMap<String, TemplateCost> {
+        let key = (name.to_string(), args);
+        if let Some(costs) = self.instances.get(&key) {
+            return costs.clone();
+        }
+        let Some(template) = self.templates.get(name) else {
+            self.approximate = true;
+            return BTreeMap::new();
+        };
+        if depth > MAX_DEPTH {
+            self.approximate = true;
+            return BTreeMap::new();
+        }
+        let mut frame = Frame {
+            env: template.get_name_of_params().iter().cloned().zip(key.1.iter().cloned()).collect(),
+            costs: BTreeMap::from([(
+                name.to_string(),
+                TemplateCost { instances: 1, ..TemplateCost::default() },
+            )]),
+            template: Some(name.to_string()),
+        };
+        self.exec(template.get_body(), &mut frame, depth);
+        self.instances.insert(key, frame.costs.clone());
+        frame.costs
+    }
+
+    /// Returns the value returned by the given function.
+    fn call(&mut self, name: &str, args: Vec<Value>, depth: usize) -> Value {
+        let Some(function) = self.functions.get(name) else {
+            return Value::Unknown;
+        };
+        if depth > MAX_DEPTH {
+            self.approximate = true;
+            return Value::Unknown;
+        }
+        self.functions_called.insert(name.to_string());
+        let mut frame = Frame {
+            env: function.get_name_of_params().iter().cloned().zip(args).collect(),
+            ..Frame::default()
+        };
+        match self.exec(function.get_body(), &mut frame, depth) {
+            Flow::Return(value) => value,
+            Flow::Continue => Value::Unknown,
+        }
+    }
+
+    fn exec(&mut self, stmt: &Statement, frame: &mut Frame, depth: usize) -> Flow {
+        use Statement::*;
+        self.steps += 1;
+        if self.steps > MAX_STEPS {
+            self.approximate = true;
+            return Flow::Continue;
+        }
+        match stmt {
+            Block { stmts, .. } | InitializationBlock { initializations: stmts, .. } => {
+                for stmt in stmts {
+                    if let Flow::Return(value) = self.exec(stmt, frame, depth) {
+                        return Flow::Return(value);
+                    }
+                }
+            }
+            IfThenElse { cond, if_case, else_case, .. } => {
+                match self.eval(cond, &mut frame.env, depth) {
+                    Value::Scalar(value) if self.as_bool(&value) => {
+                        return self.exec(if_case, frame, depth);
+                    }
+                    Value::Scalar(_) => {
+                        if let Some(else_case) = else_case {
+                            return self.exec(else_case, frame, depth);
+                        }
+                    }
+                    _ => {
+                        // If the condition is unknown, we execute both branches.
+                        self.approximate = true;
+                        if let Flow::Return(value) = self.exec(if_case, frame, depth) {
+                            return Flow::Return(value);
+                        }
+                        if let Some(else_case) = else_case {
+                            return self.exec(else_case, frame, depth);
+                        }
+                    }
+                }
+            }
+            While { cond, stmt, .. } => loop {
+                if self.steps > MAX_STEPS {
+                    self.approximate = true;
+                    break;
+                }
+                match self.eval(cond, &mut frame.env, depth) {
+                    Value::Scalar(value) if self.as_bool(&value) => {
+                        if let Flow::Return(value) = self.exec(stmt, frame, depth) {
+                            return Flow::Return(value);
+                        }
+                    }
+                    Value::Scalar(_) => break,
+                    _ => {
+                        // If the condition is unknown, we execute the body once.
+                        self.approximate = true;
+                        return self.exec(stmt, frame, depth);
+                    }
+                }
+            },
+            Return { value, .. } => return Flow::Return(self.eval(value, &mut frame.env, depth)),
+            Declaration { xtype, name, dimensions, .. } => {
+                let dimensions = dimensions
+                    .iter()
+                    .map(|size| self.eval(size, &mut frame.env, depth).to_usize())
+                    .collect::<Option<Vec<_>>>();
+                if dimensions.is_none() {
+                    self.approximate = true;
+                }
+                let size = dimensions
+                    .as_ref()
+                    .map(|dims| dims.iter().try_fold(1_usize, |size, dim| size.checked_mul(*dim)));
+                match xtype {
+                    VariableType::Var => {
+                        let value = match (dimensions, size) {
+                            (Some(dims), Some(Some(size))) if size <= MAX_ARRAY_SIZE => {
+                                Value::zeros(&dims)
+                            }
+                            (Some(_), _) => {
+                                self.approximate = true;
+                                Value::Unknown
+                            }
+                            (None, _) => Value::Unknown,
+                        };
+                        frame.env.insert(name.clone(), value);
+                    }
+                    VariableType::Signal(..) => {
+                        let size = size.map_or(1, |size| size.unwrap_or(usize::MAX));
+                        if let Some(cost) = frame.cost() {
+                            cost.signals = cost.signals.saturating_add(size);
+                        }
+                    }
+                    VariableType::Component => {
+                        frame.env.insert(name.clone(), Value::Unknown);
+                    }
+                }
+            }
+            Substitution { var, access, op: AssignOp::AssignVar, rhe, .. } => {
+                if let Some((template, args)) = instantiation(rhe, self.templates) {
+                    let args =
+                        args.iter().map(|arg| self.eval(arg, &mut frame.env, depth)).collect();
+                    for (name, cost) in self.instantiate(template, args, depth + 1) {
+                        frame.costs.entry(name).or_default().add(&cost);
+                    }
+                } else {
+                    let value = self.eval(rhe, &mut frame.env, depth);
+                    let indices = self.indices(access, &mut frame.env, depth);
+                    frame.env.entry(var.clone()).or_insert(Value::Unknown).set(&indices, value);
+                }
+            }
+            Substitution { op: AssignOp::AssignConstraintSignal, .. }
+            | MultiSubstitution { op: AssignOp::AssignConstraintSignal, .. }
+            | ConstraintEquality { .. } => {
+                if let Some(cost) = frame.cost() {
+                    cost.constraints += 1;
+                }
+            }
+            Substitution { .. } | MultiSubstitution { .. } | LogCall { .. } | Assert { .. } => {}
+        }
+        Flow::Continue
+    }
+
+    fn eval(&mut self, expr: &Expression, env: &mut HashMap<String, Value>, depth: usize) -> Value {
+        use Expression::*;
+        match expr {
+            Number(_, value) => Value::Scalar(value % &self.prime),
+            Variable { name, access, .. } => {
+                let indices = self.indices(access, env, depth);
+                match env.get(name) {
+                    Some(value) => value.get(&indices),
+                    None => Value::Unknown,
+                }
+            }
+            InfixOp { lhe, infix_op, rhe, .. } => {
+                let lhv = self.eval(lhe, env, depth);
+                let rhv = self.eval(rhe, env, depth);
+                match (lhv, rhv) {
+                    (Value::Scalar(lhv), Value::Scalar(rhv)) => self
+                        .eval_infix_op(&lhv, *infix_op, &rhv)
+                        .map_or(Value::Unknown, Value::Scalar),
+                    _ => Value::Unknown,
+                }
+            }
+            PrefixOp { prefix_op, rhe, .. } => {
+                let Value::Scalar(rhv) = self.eval(rhe, env, depth) else {
+                    return Value::Unknown;
+                };
+                let p = &self.prime;
+                Value::Scalar(match prefix_op {
+                    ExpressionPrefixOpcode::Sub => modular_arithmetic::prefix_sub(&rhv, p),
+                    ExpressionPrefixOpcode::BoolNot => modular_arithmetic::not(&rhv, p),
+                    ExpressionPrefixOpcode::Complement => {
+                        modular_arithmetic::complement_256(&rhv, p)
+                    }
+                })
+            }
+            InlineSwitchOp { cond, if_true, if_false, .. } => match self.eval(cond, env, depth) {
+                Value::Scalar(value) if self.as_bool(&value) => self.eval(if_true, env, depth),
+                Value::Scalar(_) => self.eval(if_false, env, depth),
+                _ => Value::Unknown,
+            },
+            ParallelOp { rhe, .. } => self.eval(rhe, env, depth),
+            Call { id, args, .. } => {
+                let args = args.iter().map(|arg| self.eval(arg, env, depth)).collect();
+                self.call(id, args, depth + 1)
+            }
+            ArrayInLine { values, .. } => {
+                Value::Array(values.iter().map(|value| self.eval(value, env, depth)).collect())
+            }
+            AnonymousComponent { .. } | Tuple { .. } => Value::Unknown,
+        }
+    }
+
+    fn eval_infix_op(
+        &self,
+        lhv: &BigInt,
+        infix_op: ExpressionInfixOpcode,
+        rhv: &BigInt,
+    ) -> Option<BigInt> {
+        use ExpressionInfixOpcode::*;
+        use modular_arithmetic::*;
+        let p = &self.prime;
+        match infix_op {
+            Mul => Some(mul(lhv, rhv, p)),
+            Div => div(lhv, rhv, p).ok(),
+            Add => Some(add(lhv, rhv, p)),
+            Sub => Some(sub(lhv, rhv, p)),
+            Pow => Some(pow(lhv, rhv, p)),
+            IntDiv => idiv(lhv, rhv, p).ok(),
+            Mod => mod_op(lhv, rhv, p).ok(),
+            ShiftL => shift_l(lhv, rhv, p).ok(),
+            ShiftR => shift_r(lhv, rhv, p).ok(),
+            LesserEq => Some(lesser_eq(lhv, rhv, p)),
+            GreaterEq => Some(greater_eq(lhv, rhv, p)),
+            Lesser => Some(lesser(lhv, rhv, p)),
+            Greater => Some(greater(lhv, rhv, p)),
+            Eq => Some(eq(lhv, rhv, p)),
+            NotEq => Some(not_eq(lhv, rhv, p)),
+            BoolOr => Some(bool_or(lhv, rhv, p)),
+            BoolAnd => Some(bool_and(lhv, rhv, p)),
+            BitOr => Some(bit_or(lhv, rhv, p)),
+            BitAnd => Some(bit_and(lhv, rhv, p)),
+            BitXor => Some(bit_xor(lhv, rhv, p)),
+        }
+    }
+
+    fn indices(
+        &mut self,
+        access: &[Access],
+        env: &mut HashMap<String, Value>,
+        depth: usize,
+    ) -> Vec<Option<usize>> {
+        access
+            .iter()
+            .filter_map(|access| match access {
+                Access::ArrayAccess(index) => Some(self.eval(index, env, depth).to_usize()),
+                // Component member accesses are not tracked.
+                Access::ComponentAccess(_) => None,
+            })
+            .collect()
+    }
+
+    fn as_bool(&self, value: &BigInt) -> bool {
+        modular_arithmetic::as_bool(value, &self.prime)
+    }
+}
+
+/// Returns the template name and arguments if the expression instantiates a
+/// template.
+fn instantiation<'a>(
+    expr: &'a Expression,
+    templates: &TemplateInfo,
+) -> Option<(&'a str, &'a Vec<Expression>)> {
+    match expr {
+        Expression::Call { id, args, .. } if templates.contains_key(id) => Some((id, args)),
+        Expression::ParallelOp { rhe, .. } => instantiation(rhe, templates),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{parse_source, ParseResult};
+
+    use super::*;
+
+    fn estimate(src: &str) -> InstantiationMetrics {
+        let ParseResult::Program(program, _) = parse_source("main.circom", src, &[], &[], "2.1.0")
+        else {
+            panic!("failed to parse program");
+        };
+        estimate_instantiation(
+            &program.initial_template_call,
+            &program.templates,
+            &program.functions,
+            &Curve::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_instantiation() {
+        let src = r#"
+            pragma circom 2.0.0;
+
+            function double(n) {
+                return 2 * n;
+            }
+
+            template Square() {
+                signal input in;
+                signal output out;
+                out <== in * in;
+            }
+
+            template Main(n) {
+                signal input in[double(n)];
+                signal output out[double(n)];
+
+                component squares[double(n)];
+                for (var i = 0; i < double(n); i++) {
+                    squares[i] = Square();
+                    squares[i].in <== in[i];
+                    out[i] <== squares[i].out;
+                }
+            }
+
+            component main = Main(3);
+        "#;
+        let metrics = estimate(src);
+        assert!(!metrics.approximate);
+        let main = &metrics.templates["Main"];
+        assert_eq!(main, &TemplateCost { instances: 1, signals: 12, constraints: 12 });
+        let square = &metrics.templates["Square"];
+        assert_eq!(square, &TemplateCost { instances: 6, signals: 12, constraints: 6 });
+        assert_eq!(metrics.total().constraints, 18);
+        assert_eq!(metrics.instances["Main"], BTreeSet::from([vec![Some(BigInt::from(3))]]));
+        assert_eq!(metrics.instances["Square"], BTreeSet::from([vec![]]));
+        assert_eq!(metrics.functions, BTreeSet::from(["double".to_string()]));
+    }
+
+    #[test]
+    fn test_unknown_values() {
+        let src = r#"
+            pragma circom 2.0.0;
+
+            template Main() {
+                signal input in;
+                signal output out;
+                var n = 0;
+                while (n < in) {
+                    n += 1;
+                }
+                out <== in;
+            }
+
+            component main = Main();
+        "#;
+        let metrics = estimate(src);
+        assert!(metrics.approximate);
+        assert_eq!(metrics.templates["Main"].constraints, 1);
+    }
+
+    #[test]
+    fn test_large_arrays() {
+        let src = r#"
+            pragma circom 2.0.0;
+
+            template Main() {
+                signal input in[1 << 32];
+                signal output out;
+                var x[1 << 32][1 << 32];
+                x[0][0] = 1;
+                out <== in[0];
+            }
+
+            component main = Main();
+        "#;
+        // Large variable arrays are not tracked by the interpreter.
+        let metrics = estimate(src);
+        assert!(metrics.approximate);
+        assert_eq!(metrics.templates["Main"].signals, (1 << 32) + 1);
+    }
+}
@@ -1,30 +1,244 @@
-use program_structure::cfg::Cfg;
-use program_structure::report::ReportCollection;
+use analysis_pass::{FunctionPass, PassRegistry, TemplatePass};
+use definition_complexity::{ComplexityPass, ComplexityThresholds};
 
 extern crate num_bigint_dig as num_bigint;
 
+pub mod analysis_cache;
+pub mod analysis_pass;
+pub mod analysis_runner;
+pub mod array_elements;
+pub mod circomlib_models;
+pub mod compiled_circuit;
+pub mod constant_parameters;
 pub mod constraint_analysis;
+pub mod constraint_system;
+pub mod definition_complexity;
+pub mod hygiene;
+pub mod instantiation;
+pub mod main_inputs;
+pub mod naming_conventions;
+pub mod range_analysis;
 pub mod taint_analysis;
+pub mod template_summary;
 
 // Analysis passes.
+mod array_bounds;
+mod assignment_operators;
 mod bitwise_complement;
+mod conditional_declarations;
+mod conditional_instantiation;
 mod constant_conditional;
-mod definition_complexity;
+mod constraint_comparison;
+mod dead_stores;
+mod division_by_zero;
+mod equality_comparisons;
 mod field_arithmetic;
 mod field_comparisons;
+mod field_overflow;
+mod free_signal_constraints;
+mod hint_only_constraints;
+mod loop_bounds;
+mod missing_return;
 mod nonstrict_binary_conversion;
 mod side_effect_analysis;
+mod signal_assert;
 mod signal_assignments;
+mod signal_dependent_loops;
+mod tag_violations;
+mod trust_boundary;
+mod unchecked_comparator_inputs;
+mod unchecked_parameters;
+mod unconstrained_component_outputs;
+mod unconstrained_inverse;
+mod unconstrained_outputs;
+mod unsafe_bit_width;
+mod unconstrained_bitwise_ops;
+mod uninitialized_components;
+mod unused_return_value;
+mod use_before_assignment;
+mod witness_divergence;
 
-pub fn get_analysis_passes<'a>() -> Vec<Box<dyn Fn(&'a Cfg) -> ReportCollection + 'a>> {
-    vec![
-        Box::new(bitwise_complement::find_bitwise_complement),
-        Box::new(signal_assignments::find_signal_assignments),
-        Box::new(definition_complexity::run_complexity_analysis),
-        Box::new(side_effect_analysis::run_side_effect_analysis),
-        Box::new(field_arithmetic::find_field_element_arithmetic),
-        Box::new(field_comparisons::find_field_element_comparisons),
-        Box::new(constant_conditional::find_constant_conditional_statement),
-        Box::new(nonstrict_binary_conversion::find_nonstrict_binary_conversion),
-    ]
+/// Returns a registry containing all built-in analysis passes.
+#[must_use]
+pub fn get_analysis_passes() -> PassRegistry {
+    let mut passes = PassRegistry::new();
+    passes.register(FunctionPass::new(
+        "bitwise-complement",
+        "Reports bitwise complements of field elements.",
+        bitwise_complement::find_bitwise_complement,
+    ));
+    passes.register(FunctionPass::new(
+        "assignment-operators",
+        "Reports signals assigned using `=`, and variables assigned using `<==` or `<--`.",
+        assignment_operators::find_assignment_operator_mismatches,
+    ));
+    passes.register(FunctionPass::new(
+        "signal-assignments",
+        "Reports signals assigned using the signal assignment operator `<--`.",
+        signal_assignments::find_signal_assignments,
+    ));
+    passes.register(ComplexityPass::new(ComplexityThresholds::default()));
+    passes.register(FunctionPass::new(
+        "side-effect-analysis",
+        "Reports variables and signals without side effects.",
+        side_effect_analysis::run_side_effect_analysis,
+    ));
+    passes.register(FunctionPass::new(
+        "field-arithmetic",
+        "Reports field element arithmetic that may overflow.",
+        field_arithmetic::find_field_element_arithmetic,
+    ));
+    passes.register(FunctionPass::new(
+        "field-comparisons",
+        "Reports comparisons of field elements.",
+        field_comparisons::find_field_element_comparisons,
+    ));
+    passes.register(FunctionPass::new(
+        "field-overflow",
+        "Reports arithmetic on bounded values that may exceed the field modulus.",
+        field_overflow::find_field_element_overflow,
+    ));
+    passes.register(FunctionPass::new(
+        "constant-conditional",
+        "Reports branch conditions that evaluate to a constant value.",
+        constant_conditional::find_constant_conditional_statement,
+    ));
+    passes.register(FunctionPass::new(
+        "nonstrict-binary-conversion",
+        "Reports uses of the non-strict versions of `Num2Bits` and `Bits2Num`.",
+        nonstrict_binary_conversion::find_nonstrict_binary_conversion,
+    ));
+    passes.register(FunctionPass::new(
+        "array-bounds",
+        "Reports out-of-bounds array accesses.",
+        array_bounds::find_out_of_bounds_array_access,
+    ));
+    passes.register(FunctionPass::new(
+        "missing-return",
+        "Reports function paths without a return statement.",
+        missing_return::find_missing_return_path,
+    ));
+    passes.register(FunctionPass::new(
+        "signal-assert",
+        "Reports asserts depending on signal values.",
+        signal_assert::find_signal_assert,
+    ));
+    passes.register(FunctionPass::new(
+        "constraint-comparison",
+        "Reports comparisons over signals in constraints.",
+        constraint_comparison::find_constraint_comparisons,
+    ));
+    passes.register(FunctionPass::new(
+        "equality-comparisons",
+        "Reports equality comparisons over signals where the constraint `===` was likely intended.",
+        equality_comparisons::find_equality_comparisons,
+    ));
+    passes.register(FunctionPass::new(
+        "unconstrained-outputs",
+        "Reports output signals that are not constrained.",
+        unconstrained_outputs::find_unconstrained_outputs,
+    ));
+    passes.register(FunctionPass::new(
+        "unconstrained-component-outputs",
+        "Reports component outputs that are not constrained.",
+        unconstrained_component_outputs::find_unconstrained_component_outputs,
+    ));
+    passes.register(FunctionPass::new(
+        "unsafe-bit-width",
+        "Reports unsafe bit widths passed to Circomlib templates.",
+        unsafe_bit_width::find_unsafe_bit_width,
+    ));
+    passes.register(FunctionPass::new(
+        "unchecked-comparator-inputs",
+        "Reports comparator inputs that are not range checked.",
+        unchecked_comparator_inputs::find_unchecked_comparator_inputs,
+    ));
+    passes.register(FunctionPass::new(
+        "division-by-zero",
+        "Reports divisions by values that may be zero.",
+        division_by_zero::find_division_by_zero,
+    ));
+    passes.register(FunctionPass::new(
+        "unconstrained-inverse",
+        "Reports inverses assigned using `<--` that are not constrained.",
+        unconstrained_inverse::find_unconstrained_inverse,
+    ));
+    passes.register(FunctionPass::new(
+        "dead-stores",
+        "Reports array element assignments that are overwritten before they are read.",
+        dead_stores::find_dead_stores,
+    ));
+    passes.register(FunctionPass::new(
+        "use-before-assignment",
+        "Reports variables and signals read before they are assigned a value.",
+        use_before_assignment::find_use_before_assignment,
+    ));
+    passes.register(FunctionPass::new(
+        "loop-bounds",
+        "Reports loops that probably never terminate, or never execute.",
+        loop_bounds::find_loop_bound_issues,
+    ));
+    passes.register(FunctionPass::new(
+        "unused-return-value",
+        "Reports function calls where the returned value is never used.",
+        unused_return_value::find_unused_return_values,
+    ));
+    passes.register(FunctionPass::new(
+        "uninitialized-components",
+        "Reports component arrays where some elements are never instantiated.",
+        uninitialized_components::find_uninitialized_components,
+    ));
+    passes.register(FunctionPass::new(
+        "conditional-instantiation",
+        "Reports components instantiated under unknown or parameter-dependent conditions.",
+        conditional_instantiation::find_conditional_instantiations,
+    ));
+    passes.register(FunctionPass::new(
+        "conditional-signal-declarations",
+        "Reports signals declared under conditions depending on signals.",
+        conditional_declarations::find_conditional_signal_declarations,
+    ));
+    passes.register(FunctionPass::new(
+        "unconstrained-bitwise-ops",
+        "Reports bitwise operations on signals in unconstrained signal assignments.",
+        unconstrained_bitwise_ops::find_unconstrained_bitwise_ops,
+    ));
+    passes.register(FunctionPass::new(
+        "hint-only-constraints",
+        "Reports constraints which only depend on signals assigned using `<--`.",
+        hint_only_constraints::find_hint_only_constraints,
+    ));
+    passes.register(FunctionPass::new(
+        "signal-dependent-loops",
+        "Reports loops with conditions depending on signals, where the loop body generates constraints.",
+        signal_dependent_loops::find_signal_dependent_loops,
+    ));
+    passes.register(FunctionPass::new(
+        "trust-boundary",
+        "Summarizes the input signals constraining each output signal, and reports outputs depending on unbalanced hints.",
+        trust_boundary::find_trust_boundary,
+    ));
+    passes.register(FunctionPass::new(
+        "witness-constraint-divergence",
+        "Reports signals assigned using `<--` where the constraints admit more values than the assigned expression.",
+        witness_divergence::find_witness_constraint_divergence,
+    ));
+    passes.register(FunctionPass::new(
+        "free-signal-constraints",
+        "Reports constraints between two signals assigned using `<--`, which are not constrained by the inputs or outputs.",
+        free_signal_constraints::find_free_signal_constraints,
+    ));
+    passes.register(FunctionPass::new(
+        "unchecked-parameters",
+        "Reports template parameters used as array dimensions or loop bounds without an assert restricting them.",
+        unchecked_parameters::find_unchecked_parameters,
+    ));
+    // Passes that also require information about the templates defined by the
+    // program.
+    passes.register(TemplatePass::new(
+        "tag-violations",
+        "Reports component inputs assigned untagged values.",
+        tag_violations::find_tag_violations,
+    ));
+    passes
 }
@@ -0,0 +1,442 @@
+use log::debug;
+use num_bigint::BigInt;
+use num_traits::Zero;
+use std::collections::{HashMap, HashSet};
+
+use program_structure::cfg::{BasicBlock, Cfg, Index};
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::value_meta::{ValueEnvironment, ValueMeta, ValueReduction};
+use program_structure::ir::variable_meta::VariableMeta;
+use program_structure::ir::*;
+
+pub struct InfiniteLoopWarning {
+    reason: String,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl InfiniteLoopWarning {
+    pub fn into_report(self) -> Report {
+        let mut report =
+            Report::warning("Probable infinite loop found.".to_string(), ReportCode::InfiniteLoop);
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                "This loop condition may never become false.".to_string(),
+            );
+        }
+        report.add_note(self.reason);
+        report
+    }
+}
+
+pub struct ZeroIterationLoopWarning {
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl ZeroIterationLoopWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            "The loop body is never executed.".to_string(),
+            ReportCode::ZeroIterationLoop,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                "This loop condition is false on entry to the loop.".to_string(),
+            );
+        }
+        report
+    }
+}
+
+/// A loop given by the header block (ending with the loop condition) and the
+/// blocks in the loop body.
+pub(crate) struct Loop<'a> {
+    pub(crate) cond: &'a Expression,
+    pub(crate) header: &'a BasicBlock,
+    pub(crate) body: HashSet<Index>,
+}
+
+/// This analysis pass inspects the condition of each loop and reports
+///
+///   1. loops where none of the variables in the condition are modified by the
+///      loop body,
+///   2. loops where the loop variable is updated in the wrong direction (e.g.
+///      `for (var i = 0; i < n; i--)`), and
+///   3. loops where the condition is false on entry to the loop, based on the
+///      constant values of the variables in the condition.
+///
+/// Loops with a constant condition are reported by the constant conditional
+/// analysis pass, and loops containing a return statement are not reported
+/// as infinite.
+pub fn find_loop_bound_issues(cfg: &Cfg) -> ReportCollection {
+    debug!("running loop bound analysis pass");
+    let definitions = find_definitions(cfg);
+    let mut reports = ReportCollection::new();
+    for cfg_loop in find_loops(cfg) {
+        if cfg_loop.cond.is_constant() {
+            continue;
+        }
+        if let Some(reason) = is_infinite(cfg, &cfg_loop, &definitions) {
+            reports.push(build_infinite_loop(cfg_loop.cond.meta(), reason));
+        } else if is_false_on_entry(cfg, &cfg_loop, &definitions) {
+            reports.push(build_zero_iteration_loop(cfg_loop.cond.meta()));
+        }
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+/// The maximum number of loop iterations enumerated by `loop_variable_values`.
+const MAX_LOOP_ITERATIONS: usize = 1 << 16;
+
+/// Returns the values taken by the loop variable `var` (defined by a phi
+/// expression in the loop header) on each iteration of the loop, if the
+/// initial value, the step, and the loop bound are all constant.
+pub(crate) fn loop_variable_values(cfg: &Cfg, var: &VariableName) -> Option<Vec<BigInt>> {
+    let definitions = find_definitions(cfg);
+    let (header, Expression::Phi { args, .. }) = definitions.get(var)? else {
+        return None;
+    };
+    let cfg_loop =
+        find_loops(cfg).into_iter().find(|cfg_loop| cfg_loop.header.index() == *header)?;
+    if !cfg_loop.cond.locals_read().iter().any(|local| local.name() == var) {
+        return None;
+    }
+    let step = loop_step(cfg, &cfg_loop, var, &definitions)?;
+    let mut value = args
+        .iter()
+        .filter_map(|arg| definitions.get(arg))
+        .find(|(index, _)| !cfg_loop.body.contains(index))
+        .and_then(|(_, rhe)| match rhe.value() {
+            Some(ValueReduction::FieldElement { value }) => Some(value.clone()),
+            _ => None,
+        })?;
+    let prime = cfg.constants().prime();
+    let mut values = Vec::new();
+    while values.len() < MAX_LOOP_ITERATIONS {
+        let mut env = ValueEnvironment::new(cfg.constants());
+        env.add_variable(var, &ValueReduction::FieldElement { value: value.clone() });
+        let mut cond = cfg_loop.cond.clone();
+        while cond.propagate_values(&mut env) {}
+        let is_true = match cond.value()? {
+            ValueReduction::Boolean { value } => *value,
+            ValueReduction::FieldElement { value } => !value.is_zero(),
+        };
+        if !is_true {
+            return Some(values);
+        }
+        values.push(value.clone());
+        value = (value + &step) % prime;
+    }
+    None
+}
+
+/// Returns the indices of the header blocks of all loops in the CFG.
+pub(crate) fn loop_headers(cfg: &Cfg) -> HashSet<Index> {
+    find_loops(cfg).iter().map(|cfg_loop| cfg_loop.header.index()).collect()
+}
+
+/// Returns the definition of each variable, together with the index of the
+/// basic block where it is defined.
+fn find_definitions(cfg: &Cfg) -> HashMap<&VariableName, (Index, &Expression)> {
+    let mut definitions = HashMap::new();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            if let Statement::Substitution { var, rhe, .. } = stmt {
+                definitions.insert(var, (basic_block.index(), rhe));
+            }
+        }
+    }
+    definitions
+}
+
+/// Returns the loops of the CFG. A loop header is a block ending with an
+/// if-statement, which has a predecessor dominated by the header.
+pub(crate) fn find_loops(cfg: &Cfg) -> Vec<Loop<'_>> {
+    let mut loops = Vec::new();
+    for header in cfg.iter() {
+        let Some(Statement::IfThenElse { cond, .. }) = header.statements().last() else {
+            continue;
+        };
+        let latches = header
+            .predecessors()
+            .iter()
+            .filter(|index| {
+                let block = cfg.get_basic_block(**index).expect("in control-flow graph");
+                cfg.get_dominators(block).iter().any(|block| block.index() == header.index())
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        if latches.is_empty() {
+            continue;
+        }
+        // The loop body consists of all blocks reaching a latch without
+        // passing through the header.
+        let mut body = HashSet::from([header.index()]);
+        let mut update = latches;
+        while let Some(index) = update.pop() {
+            if body.insert(index) {
+                let block = cfg.get_basic_block(index).expect("in control-flow graph");
+                update.extend(block.predecessors().iter().cloned());
+            }
+        }
+        loops.push(Loop { cond, header, body });
+    }
+    loops
+}
+
+/// Returns a description of why the loop is considered infinite, or `None`
+/// if the loop is not considered infinite.
+fn is_infinite(
+    cfg: &Cfg,
+    cfg_loop: &Loop,
+    definitions: &HashMap<&VariableName, (Index, &Expression)>,
+) -> Option<String> {
+    let mut written = HashSet::new();
+    for index in &cfg_loop.body {
+        let basic_block = cfg.get_basic_block(*index).expect("in control-flow graph");
+        for stmt in basic_block.iter() {
+            match stmt {
+                Statement::Return { .. } => return None,
+                Statement::Substitution { rhe: Expression::Phi { .. }, .. } => {}
+                _ => {
+                    written.extend(stmt.variables_written().map(|var| var.name().without_version()))
+                }
+            }
+        }
+    }
+    let cond_vars = cfg_loop
+        .cond
+        .variables_read()
+        .map(|var| var.name().without_version())
+        .collect::<HashSet<_>>();
+    if written.is_disjoint(&cond_vars) {
+        let mut names = cond_vars.iter().map(|name| format!("`{name}`")).collect::<Vec<_>>();
+        names.sort();
+        return Some(match names.len() {
+            0 => "The loop condition does not depend on any variables.".to_string(),
+            1 => format!("The variable {} is not modified by the loop body.", names[0]),
+            _ => format!("The variables {} are not modified by the loop body.", names.join(", ")),
+        });
+    }
+    // Check if the loop variable is updated in the wrong direction.
+    use ExpressionInfixOpcode::*;
+    let Expression::InfixOp { lhe, infix_op, rhe, .. } = cfg_loop.cond else {
+        return None;
+    };
+    let (var, bound, must_increase) = match (lhe.as_ref(), infix_op, rhe.as_ref()) {
+        (Expression::Variable { name, .. }, Lesser | LesserEq, bound)
+        | (bound, Greater | GreaterEq, Expression::Variable { name, .. }) => (name, bound, true),
+        (Expression::Variable { name, .. }, Greater | GreaterEq, bound)
+        | (bound, Lesser | LesserEq, Expression::Variable { name, .. }) => (name, bound, false),
+        _ => return None,
+    };
+    let bound_vars = bound.variables_read().map(|var| var.name().without_version());
+    if bound_vars.into_iter().any(|name| written.contains(&name)) {
+        return None;
+    }
+    let step = loop_step(cfg, cfg_loop, var, definitions)?;
+    let prime = cfg.constants().prime();
+    let is_increasing = step < prime / 2;
+    if is_increasing != must_increase {
+        let direction = if is_increasing { "incremented" } else { "decremented" };
+        return Some(format!(
+            "The loop variable `{var}` is {direction} by the loop body, which means that the condition `{}` never becomes false.",
+            cfg_loop.cond
+        ));
+    }
+    None
+}
+
+/// Returns the constant step by which the loop variable is updated by the
+/// loop body (as a field element), if the loop variable is updated using
+/// addition or subtraction of a constant.
+fn loop_step(
+    cfg: &Cfg,
+    cfg_loop: &Loop,
+    var: &VariableName,
+    definitions: &HashMap<&VariableName, (Index, &Expression)>,
+) -> Option<BigInt> {
+    use ExpressionInfixOpcode::*;
+    // The loop variable is defined by a phi expression in the loop header.
+    let (index, Expression::Phi { args, .. }) = definitions.get(var)? else {
+        return None;
+    };
+    if *index != cfg_loop.header.index() {
+        return None;
+    }
+    // Find the definition of the loop variable in the loop body.
+    let mut updates = args.iter().filter_map(|arg| {
+        let (index, rhe) = definitions.get(arg)?;
+        cfg_loop.body.contains(index).then_some(*rhe)
+    });
+    let (update, None) = (updates.next()?, updates.next()) else {
+        return None;
+    };
+    let prime = cfg.constants().prime();
+    let constant = |expr: &Expression| match expr.value() {
+        Some(ValueReduction::FieldElement { value }) if !value.is_zero() => Some(value.clone()),
+        _ => None,
+    };
+    let is_var =
+        |expr: &Expression| matches!(expr, Expression::Variable { name, .. } if name == var);
+    match update {
+        Expression::InfixOp { lhe, infix_op: Add, rhe, .. } if is_var(lhe) => constant(rhe),
+        Expression::InfixOp { lhe, infix_op: Add, rhe, .. } if is_var(rhe) => constant(lhe),
+        Expression::InfixOp { lhe, infix_op: Sub, rhe, .. } if is_var(lhe) => {
+            constant(rhe).map(|value| prime - value)
+        }
+        _ => None,
+    }
+}
+
+/// Returns true if the loop condition evaluates to false when the loop
+/// variables are replaced by their initial values.
+fn is_false_on_entry(
+    cfg: &Cfg,
+    cfg_loop: &Loop,
+    definitions: &HashMap<&VariableName, (Index, &Expression)>,
+) -> bool {
+    let mut env = ValueEnvironment::new(cfg.constants());
+    for var in cfg_loop.cond.locals_read() {
+        let Some((index, rhe)) = definitions.get(var.name()) else {
+            continue;
+        };
+        let value = match rhe {
+            // Use the value of the argument defined outside the loop.
+            Expression::Phi { args, .. } if *index == cfg_loop.header.index() => args
+                .iter()
+                .filter_map(|arg| definitions.get(arg))
+                .find(|(index, _)| !cfg_loop.body.contains(index))
+                .and_then(|(_, rhe)| rhe.value()),
+            _ => rhe.value(),
+        };
+        if let Some(value) = value {
+            env.add_variable(var.name(), value);
+        }
+    }
+    let mut cond = cfg_loop.cond.clone();
+    // Propagation may need to be repeated since sub-expressions are visited
+    // lazily.
+    while cond.propagate_values(&mut env) {}
+    match cond.value() {
+        Some(ValueReduction::Boolean { value }) => !value,
+        Some(ValueReduction::FieldElement { value }) => value.is_zero(),
+        None => false,
+    }
+}
+
+fn build_infinite_loop(meta: &Meta, reason: String) -> Report {
+    InfiniteLoopWarning { reason, file_id: meta.file_id(), file_location: meta.file_location() }
+        .into_report()
+}
+
+fn build_zero_iteration_loop(meta: &Meta) -> Report {
+    ZeroIterationLoopWarning { file_id: meta.file_id(), file_location: meta.file_location() }
+        .into_report()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_loop_bounds() {
+        let src = r#"
+            function f(n) {
+                var result = 0;
+                for (var i = 0; i < n; i++) {
+                    result += i;
+                }
+                return result;
+            }
+        "#;
+        validate_reports(src, 0);
+
+        let src = r#"
+            function f(n) {
+                var result = 0;
+                var i = 0;
+                while (i < n) {
+                    result += i;
+                }
+                return result;
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            function f(n) {
+                var result = 0;
+                for (var i = 0; i < n; i--) {
+                    result += i;
+                }
+                return result;
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            function f(n) {
+                var result = 0;
+                for (var i = n; i > 0; i--) {
+                    result += i;
+                }
+                return result;
+            }
+        "#;
+        validate_reports(src, 0);
+
+        let src = r#"
+            function f(n) {
+                var result = 0;
+                var k = 8;
+                for (var i = 10; i < k; i++) {
+                    result += n;
+                }
+                return result;
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            function f(n) {
+                var i = 0;
+                while (i < n) {
+                    if (i * i > n) {
+                        return i;
+                    }
+                }
+                return n;
+            }
+        "#;
+        validate_reports(src, 0);
+    }
+
+    fn validate_reports(src: &str, expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_loop_bound_issues(&cfg);
+
+        assert_eq!(reports.len(), expected_len);
+    }
+}
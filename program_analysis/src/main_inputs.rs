@@ -0,0 +1,156 @@
+use log::debug;
+
+use program_structure::cfg::Cfg;
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::*;
+
+use crate::constraint_analysis::run_signal_constraint_analysis;
+
+pub struct UnconstrainedMainInputWarning {
+    name: VariableName,
+    template_name: String,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+    main_file_id: Option<FileID>,
+    main_location: FileLocation,
+}
+
+impl UnconstrainedMainInputWarning {
+    pub fn into_report(self) -> Report {
+        let name = &self.name;
+        let mut report = Report::warning(
+            format!(
+                "The input signal `{name}` of the main template `{}` never occurs in a constraint.",
+                self.template_name
+            ),
+            ReportCode::UnconstrainedMainInput,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                format!("The input signal `{name}` is declared here."),
+            );
+        }
+        if let Some(file_id) = self.main_file_id {
+            report.add_secondary(
+                self.main_location,
+                file_id,
+                Some("The main component is declared here.".to_string()),
+            );
+        }
+        report.add_note(format!(
+            "Since `{name}` is not constrained, a proof generated for one value of `{name}` is also valid for any other value. In some protocols this allows proofs to be trivially forged."
+        ));
+        report.add_help(format!(
+            "Consider adding a constraint like `{name} * 0 === 0` if the input is used to bind the proof to a value."
+        ));
+        report.add_affected_signal(&self.template_name, &name.to_string());
+        report
+    }
+}
+
+/// If an input signal of the main template never occurs in a constraint, a
+/// proof generated for one value of the input is also valid for any other
+/// value. Given the CFG of the main template and the location of the main
+/// component, this function reports each input signal which does not occur in
+/// a constraint, either directly or through a local variable computed from the
+/// input (like `var s = 0; s += in[i]; out <== s;`).
+pub fn find_unconstrained_main_inputs(
+    cfg: &Cfg,
+    main_file_id: Option<FileID>,
+    main_location: FileLocation,
+) -> ReportCollection {
+    debug!("checking the input signals of the main template `{}`", cfg.name());
+    let mut reports = ReportCollection::new();
+    let constraints = run_signal_constraint_analysis(cfg);
+    let mut inputs = cfg
+        .declarations()
+        .iter()
+        .filter(|(_, declaration)| {
+            matches!(declaration.variable_type(), VariableType::Signal(SignalType::Input, _))
+        })
+        .collect::<Vec<_>>();
+    inputs.sort_by_key(|(_, declaration)| declaration.file_location().start);
+    for (name, declaration) in inputs {
+        if constraints.contains_key(name) {
+            continue;
+        }
+        reports.push(
+            UnconstrainedMainInputWarning {
+                name: name.clone(),
+                template_name: cfg.name().to_string(),
+                file_id: declaration.file_id(),
+                file_location: declaration.file_location(),
+                main_file_id,
+                main_location: main_location.clone(),
+            }
+            .into_report(),
+        );
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_unconstrained_main_inputs() {
+        let src = r#"
+            template T() {
+                signal input in;
+                signal input unused;
+                signal output out;
+                out <== in * in;
+            }
+        "#;
+        validate_reports(src, 1);
+
+        // Inputs constrained through a variable accumulator are constrained.
+        let src = r#"
+            template T(n) {
+                signal input in[n];
+                signal output out;
+                var s = 0;
+                for (var i = 0; i < n; i++) {
+                    s += in[i];
+                }
+                out <== s;
+            }
+        "#;
+        validate_reports(src, 0);
+
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                var x = in;
+                out <-- x;
+            }
+        "#;
+        validate_reports(src, 1);
+    }
+
+    fn validate_reports(src: &str, expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_unconstrained_main_inputs(&cfg, None, 0..0);
+        assert_eq!(reports.len(), expected_len);
+    }
+}
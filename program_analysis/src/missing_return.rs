@@ -0,0 +1,218 @@
+use log::debug;
+use std::collections::HashSet;
+
+use program_structure::cfg::{BasicBlock, Cfg, DefinitionType, Index};
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::value_meta::ValueReduction;
+use program_structure::ir::*;
+
+pub struct MissingReturnWarning {
+    function_name: String,
+    is_branch: bool,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl MissingReturnWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            format!(
+                "The function `{}` may reach the end of the function body without returning a value.",
+                self.function_name
+            ),
+            ReportCode::FunctionPathWithoutReturn,
+        );
+        if let Some(file_id) = self.file_id {
+            let message = if self.is_branch {
+                "If this condition is false, the function ends without returning a value."
+            } else {
+                "The function may end here without returning a value."
+            };
+            report.add_primary(self.file_location, file_id, message.to_string());
+        }
+        report.add_note(
+            "Missing return values are only detected by Circom during witness generation."
+                .to_string(),
+        );
+        report
+    }
+}
+
+/// The possible ways control flow can leave a basic block.
+enum Exit {
+    // Control flows to the basic block with the given index.
+    Block(Index),
+    // Control flows to the end of the function body. The flag is true if this
+    // is the false branch of a control-flow statement.
+    End { is_branch: bool },
+}
+
+/// Circom functions must return a value on every path through the function
+/// body. This pass walks the CFG from the entry block (without passing through
+/// blocks containing a `return` statement) and reports each point where the
+/// end of the function body can be reached. Branches with constant conditions
+/// are only followed in the direction taken.
+pub fn find_missing_return_path(cfg: &Cfg) -> ReportCollection {
+    if !matches!(cfg.definition_type(), DefinitionType::Function) {
+        // Exit early if this is not a function.
+        return ReportCollection::new();
+    }
+    debug!("running missing return path analysis pass");
+    let mut reports = ReportCollection::new();
+    let mut visited = HashSet::new();
+    let mut worklist = vec![cfg.entry_block().index()];
+    while let Some(index) = worklist.pop() {
+        if !visited.insert(index) {
+            continue;
+        }
+        // This is ok since `index` is either the entry block or a successor.
+        let basic_block = cfg.get_basic_block(index).unwrap();
+        if basic_block.iter().any(|stmt| matches!(stmt, Statement::Return { .. })) {
+            continue;
+        }
+        for exit in get_exits(basic_block) {
+            match exit {
+                Exit::Block(index) => worklist.push(index),
+                Exit::End { is_branch } => {
+                    reports.push(build_report(cfg.name(), basic_block, is_branch));
+                }
+            }
+        }
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+/// Returns the feasible exits from the given basic block.
+fn get_exits(basic_block: &BasicBlock) -> Vec<Exit> {
+    use ValueReduction::*;
+    match basic_block.iter().last() {
+        Some(Statement::IfThenElse { cond, true_index, false_index, .. }) => {
+            // If the false branch is not set, it is the remaining successor of
+            // the block. If there is no such successor, the false branch leads
+            // to the end of the function body.
+            let false_index = false_index.or_else(|| {
+                basic_block.successors().iter().find(|index| *index != true_index).cloned()
+            });
+            let false_exit = match false_index {
+                Some(false_index) => Exit::Block(false_index),
+                None => Exit::End { is_branch: true },
+            };
+            match cond.meta().value_knowledge().get_reduces_to() {
+                Some(Boolean { value: true }) => vec![Exit::Block(*true_index)],
+                Some(Boolean { value: false }) => vec![false_exit],
+                _ => vec![Exit::Block(*true_index), false_exit],
+            }
+        }
+        _ if basic_block.successors().is_empty() => vec![Exit::End { is_branch: false }],
+        _ => basic_block.successors().iter().map(|index| Exit::Block(*index)).collect(),
+    }
+}
+
+fn build_report(function_name: &str, basic_block: &BasicBlock, is_branch: bool) -> Report {
+    // Use the location of the final statement of the block, if it exists.
+    let meta = basic_block.iter().last().map(|stmt| stmt.meta()).unwrap_or(basic_block.meta());
+    MissingReturnWarning {
+        function_name: function_name.to_string(),
+        is_branch,
+        file_id: meta.file_id(),
+        file_location: meta.file_location(),
+    }
+    .into_report()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_missing_return() {
+        let src = r#"
+            function f(x) {
+                if (x == 0) {
+                    return 1;
+                }
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            function f(x) {
+                var y = 0;
+                while (y < x) {
+                    y += 1;
+                }
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            function f(x) {
+                if (x == 0) {
+                    return 1;
+                } else {
+                    x += 1;
+                }
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            function f(x) {
+                if (x == 0) {
+                    return 1;
+                } else {
+                    return 2;
+                }
+            }
+        "#;
+        validate_reports(src, 0);
+
+        let src = r#"
+            function f(x) {
+                var y = 0;
+                while (y < x) {
+                    if (y == 2) {
+                        return y;
+                    }
+                    y += 1;
+                }
+                return x;
+            }
+        "#;
+        validate_reports(src, 0);
+
+        let src = r#"
+            function f(x) {
+                while (1 == 1) {
+                    if (x == 0) {
+                        return x;
+                    }
+                }
+            }
+        "#;
+        validate_reports(src, 0);
+    }
+
+    fn validate_reports(src: &str, expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_missing_return_path(&cfg);
+
+        assert_eq!(reports.len(), expected_len);
+    }
+}
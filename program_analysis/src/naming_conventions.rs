@@ -0,0 +1,248 @@
+//! Reports signals and templates with names that do not follow the naming
+//! conventions configured by the user (e.g. in `circomspect.toml`).
+use std::fmt;
+use std::str::FromStr;
+
+use log::debug;
+use regex::Regex;
+
+use program_structure::ast::{SignalType, Statement, VariableType};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::template_data::TemplateInfo;
+
+/// A naming convention, given either as one of the predefined styles
+/// `camelCase`, `PascalCase`, `snake_case`, and `SCREAMING_SNAKE_CASE`, or as
+/// a regular expression which must match the entire name.
+#[derive(Clone, Debug)]
+pub struct NamingPattern {
+    source: String,
+    regex: Regex,
+}
+
+impl NamingPattern {
+    #[must_use]
+    pub fn is_match(&self, name: &str) -> bool {
+        self.regex.is_match(name)
+    }
+}
+
+impl FromStr for NamingPattern {
+    type Err = regex::Error;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let pattern = match source {
+            "camelCase" => "[a-z][a-zA-Z0-9]*",
+            "PascalCase" => "[A-Z][a-zA-Z0-9]*",
+            "snake_case" => "[a-z][a-z0-9]*(_[a-z0-9]+)*",
+            "SCREAMING_SNAKE_CASE" => "[A-Z][A-Z0-9]*(_[A-Z0-9]+)*",
+            pattern => pattern,
+        };
+        let regex = Regex::new(&format!("^(?:{pattern})$"))?;
+        Ok(NamingPattern { source: source.to_string(), regex })
+    }
+}
+
+impl fmt::Display for NamingPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+/// The naming conventions enforced for each kind of name. Names without a
+/// configured convention are not checked.
+#[derive(Clone, Debug, Default)]
+pub struct NamingConventions {
+    pub input_signals: Option<NamingPattern>,
+    pub output_signals: Option<NamingPattern>,
+    pub intermediate_signals: Option<NamingPattern>,
+    pub templates: Option<NamingPattern>,
+}
+
+impl NamingConventions {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.input_signals.is_none()
+            && self.output_signals.is_none()
+            && self.intermediate_signals.is_none()
+            && self.templates.is_none()
+    }
+
+    fn signal_pattern(&self, signal_type: &SignalType) -> Option<&NamingPattern> {
+        match signal_type {
+            SignalType::Input => self.input_signals.as_ref(),
+            SignalType::Output => self.output_signals.as_ref(),
+            SignalType::Intermediate => self.intermediate_signals.as_ref(),
+        }
+    }
+}
+
+pub struct NamingConventionWarning {
+    kind: &'static str,
+    name: String,
+    pattern: NamingPattern,
+    file_id: FileID,
+    file_location: FileLocation,
+}
+
+impl NamingConventionWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            format!(
+                "The {} `{}` does not follow the naming convention `{}`.",
+                self.kind, self.name, self.pattern
+            ),
+            ReportCode::NamingConvention,
+        );
+        report.add_primary(
+            self.file_location,
+            self.file_id,
+            format!("The {} `{}` is declared here.", self.kind, self.name),
+        );
+        report
+    }
+}
+
+/// Reports each template and template signal with a name not matching the
+/// corresponding naming convention.
+#[must_use]
+pub fn find_naming_convention_violations(
+    templates: &TemplateInfo,
+    conventions: &NamingConventions,
+) -> ReportCollection {
+    debug!("running naming convention checks");
+    let mut reports = ReportCollection::new();
+    let mut names = templates.keys().collect::<Vec<_>>();
+    names.sort();
+    for name in names {
+        let template = &templates[name];
+        if let Some(pattern) = &conventions.templates {
+            if !pattern.is_match(name) {
+                reports.push(
+                    NamingConventionWarning {
+                        kind: "template",
+                        name: name.clone(),
+                        pattern: pattern.clone(),
+                        file_id: template.get_file_id(),
+                        file_location: template.get_param_location(),
+                    }
+                    .into_report(),
+                );
+            }
+        }
+        visit_statement(template.get_body(), template.get_file_id(), conventions, &mut reports);
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+fn visit_statement(
+    stmt: &Statement,
+    file_id: FileID,
+    conventions: &NamingConventions,
+    reports: &mut ReportCollection,
+) {
+    use Statement::*;
+    match stmt {
+        Declaration { meta, xtype: VariableType::Signal(signal_type, _, _), name, .. } => {
+            let Some(pattern) = conventions.signal_pattern(signal_type) else {
+                return;
+            };
+            if !pattern.is_match(name) {
+                let kind = match signal_type {
+                    SignalType::Input => "input signal",
+                    SignalType::Output => "output signal",
+                    SignalType::Intermediate => "intermediate signal",
+                };
+                reports.push(
+                    NamingConventionWarning {
+                        kind,
+                        name: name.clone(),
+                        pattern: pattern.clone(),
+                        file_id,
+                        file_location: meta.file_location(),
+                    }
+                    .into_report(),
+                );
+            }
+        }
+        IfThenElse { if_case, else_case, .. } => {
+            visit_statement(if_case, file_id, conventions, reports);
+            if let Some(else_case) = else_case {
+                visit_statement(else_case, file_id, conventions, reports);
+            }
+        }
+        While { stmt, .. } => visit_statement(stmt, file_id, conventions, reports),
+        InitializationBlock { initializations: stmts, .. } | Block { stmts, .. } => {
+            for stmt in stmts {
+                visit_statement(stmt, file_id, conventions, reports);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{parse_source, ParseResult};
+
+    use super::*;
+
+    #[test]
+    fn test_naming_patterns() {
+        let camel_case = NamingPattern::from_str("camelCase").unwrap();
+        assert!(camel_case.is_match("inValue"));
+        assert!(!camel_case.is_match("in_value"));
+        assert!(!camel_case.is_match("InValue"));
+        let snake_case = NamingPattern::from_str("snake_case").unwrap();
+        assert!(snake_case.is_match("in_value"));
+        assert!(!snake_case.is_match("in__value"));
+        // Regular expressions must match the entire name.
+        let pattern = NamingPattern::from_str("[a-z]+").unwrap();
+        assert!(pattern.is_match("in"));
+        assert!(!pattern.is_match("in0"));
+        assert!(NamingPattern::from_str("[a-z").is_err());
+    }
+
+    #[test]
+    fn test_naming_conventions() {
+        let src = r#"
+            template num_adder(n) {
+                signal input in_values[n];
+                signal output out;
+                signal partialSums[n + 1];
+                partialSums[0] <== 0;
+                for (var i = 0; i < n; i++) {
+                    partialSums[i + 1] <== partialSums[i] + in_values[i];
+                }
+                out <== partialSums[n];
+            }
+
+            component main = num_adder(2);
+        "#;
+        let ParseResult::Program(program, _) = parse_source("main.circom", src, &[], &[], "2.1.0")
+        else {
+            panic!("failed to parse program");
+        };
+        let conventions = NamingConventions {
+            input_signals: Some("camelCase".parse().unwrap()),
+            output_signals: Some("camelCase".parse().unwrap()),
+            intermediate_signals: Some("snake_case".parse().unwrap()),
+            templates: Some("PascalCase".parse().unwrap()),
+        };
+        let reports = find_naming_convention_violations(&program.templates, &conventions);
+        let messages = reports.iter().map(|report| report.message().clone()).collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            [
+                "The template `num_adder` does not follow the naming convention `PascalCase`.",
+                "The input signal `in_values` does not follow the naming convention `camelCase`.",
+                "The intermediate signal `partialSums` does not follow the naming convention `snake_case`.",
+            ]
+        );
+        assert!(
+            find_naming_convention_violations(&program.templates, &Default::default()).is_empty()
+        );
+    }
+}
@@ -28,7 +28,7 @@ impl NonStrictBinaryConversionWarning {
                         "Circomlib template `Num2Bits` instantiated here.".to_string(),
                     );
                 }
-                report.add_note(
+                report.add_help(
                     "Consider using `Num2Bits_strict` if the input size may be >= than the prime size."
                         .to_string(),
                 );
@@ -46,7 +46,7 @@ impl NonStrictBinaryConversionWarning {
                         "Circomlib template `Bits2Num` instantiated here.".to_string(),
                     );
                 }
-                report.add_note(
+                report.add_help(
                     "Consider using `Bits2Num_strict` if the input size may be >= than the prime size."
                         .to_string(),
                 );
@@ -57,9 +57,10 @@ impl NonStrictBinaryConversionWarning {
 }
 
 /// If the size in bits of the input `x` to the Circomlib circuit `NumBits` is
-/// >= than the size of the prime there will be two valid bit-representations of
-/// the input: One representation of `x` and one of `p + x`. This is typically
-/// not expected by developers and may lead to issues.
+/// greater than or equal to the size of the prime there will be two valid
+/// bit-representations of the input: One representation of `x` and one of
+/// `p + x`. This is typically not expected by developers and may lead to
+/// issues.
 pub fn find_nonstrict_binary_conversion(cfg: &Cfg) -> ReportCollection {
     use DefinitionType::*;
     if matches!(cfg.definition_type(), Function | CustomTemplate) {
@@ -87,7 +88,7 @@ fn visit_statement(stmt: &Statement, prime_size: &BigInt, reports: &mut ReportCo
     if let Substitution {
         meta: var_meta,
         op: AssignLocalOrComponent,
-        rhe: Call { meta: component_meta, name: component_name, args },
+        rhe: Call { meta: component_meta, name: component_name, args, .. },
         ..
     } = stmt
     {
@@ -0,0 +1,755 @@
+use log::{debug, trace};
+use num_bigint::BigInt;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use std::cmp::{max, min};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use program_structure::cfg::Cfg;
+use program_structure::ir::value_meta::{ValueMeta, ValueReduction};
+use program_structure::ir::*;
+
+/// The maximum number of passes over the CFG before variables with growing
+/// ranges are considered unbounded.
+const MAX_ITERATIONS: usize = 16;
+
+/// The maximum exponent (and shift) evaluated when computing ranges.
+const MAX_EXPONENT: usize = 512;
+
+/// A closed interval `[lower, upper]` of integers.
+///
+/// Field elements are represented using the signed representation `(-p/2,
+/// p/2]` used by Circom when comparing field elements. Interval arithmetic is
+/// performed over the integers, which means that the range of an expression
+/// may extend beyond the field. This is used to detect arithmetic which could
+/// wrap around the prime.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Interval {
+    lower: BigInt,
+    upper: BigInt,
+}
+
+impl Interval {
+    /// Returns the interval `[lower, upper]`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `lower > upper`.
+    #[must_use]
+    pub fn new(lower: BigInt, upper: BigInt) -> Interval {
+        assert!(lower <= upper, "invalid interval [{lower}, {upper}]");
+        Interval { lower, upper }
+    }
+
+    /// Returns the interval `[value, value]`.
+    #[must_use]
+    pub fn constant(value: BigInt) -> Interval {
+        Interval { lower: value.clone(), upper: value }
+    }
+
+    /// Returns the interval `[0, 1]`.
+    #[must_use]
+    pub fn boolean() -> Interval {
+        Interval { lower: BigInt::zero(), upper: BigInt::one() }
+    }
+
+    /// Returns the interval `[0, 2^bits - 1]`.
+    #[must_use]
+    pub fn from_bits(bits: usize) -> Interval {
+        Interval { lower: BigInt::zero(), upper: (BigInt::one() << bits) - 1 }
+    }
+
+    #[must_use]
+    pub fn lower(&self) -> &BigInt {
+        &self.lower
+    }
+
+    #[must_use]
+    pub fn upper(&self) -> &BigInt {
+        &self.upper
+    }
+
+    /// Returns true if the interval contains a single value.
+    #[must_use]
+    pub fn is_constant(&self) -> bool {
+        self.lower == self.upper
+    }
+
+    /// Returns true if the interval contains the given value.
+    #[must_use]
+    pub fn contains(&self, value: &BigInt) -> bool {
+        &self.lower <= value && value <= &self.upper
+    }
+
+    /// Returns true if all values in the interval are non-negative.
+    #[must_use]
+    pub fn is_non_negative(&self) -> bool {
+        !self.lower.is_negative()
+    }
+
+    /// Returns the number of bits needed to represent the largest absolute
+    /// value in the interval.
+    #[must_use]
+    pub fn bits(&self) -> usize {
+        max(self.lower.abs().bits(), self.upper.abs().bits())
+    }
+
+    /// Returns true if no two values in the interval are congruent modulo the
+    /// given prime. In this case the interval is an exact representation of a
+    /// set of field elements.
+    #[must_use]
+    pub fn fits_in_field(&self, prime: &BigInt) -> bool {
+        &self.upper - &self.lower < *prime
+    }
+
+    /// Returns true if the interval is contained in the interval `(-p/2, p/2]`
+    /// used by Circom to represent field elements. If this is the case,
+    /// arithmetic and comparisons over the interval behave as they would over
+    /// the integers.
+    #[must_use]
+    pub fn is_reduced(&self, prime: &BigInt) -> bool {
+        let half: BigInt = prime / 2u32;
+        self.lower > -half.clone() && self.upper <= half
+    }
+
+    /// Returns the smallest interval containing both intervals.
+    #[must_use]
+    pub fn join(&self, other: &Interval) -> Interval {
+        Interval {
+            lower: min(&self.lower, &other.lower).clone(),
+            upper: max(&self.upper, &other.upper).clone(),
+        }
+    }
+
+    /// Returns the intersection of the two intervals, or `None` if the
+    /// intersection is empty.
+    #[must_use]
+    pub fn meet(&self, other: &Interval) -> Option<Interval> {
+        let lower = max(&self.lower, &other.lower);
+        let upper = min(&self.upper, &other.upper);
+        if lower <= upper {
+            Some(Interval { lower: lower.clone(), upper: upper.clone() })
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn add(&self, other: &Interval) -> Interval {
+        Interval { lower: &self.lower + &other.lower, upper: &self.upper + &other.upper }
+    }
+
+    #[must_use]
+    pub fn sub(&self, other: &Interval) -> Interval {
+        Interval { lower: &self.lower - &other.upper, upper: &self.upper - &other.lower }
+    }
+
+    #[must_use]
+    pub fn neg(&self) -> Interval {
+        Interval { lower: -&self.upper, upper: -&self.lower }
+    }
+
+    #[must_use]
+    pub fn mul(&self, other: &Interval) -> Interval {
+        let products = [
+            &self.lower * &other.lower,
+            &self.lower * &other.upper,
+            &self.upper * &other.lower,
+            &self.upper * &other.upper,
+        ];
+        // The iterators are non-empty so it is ok to unwrap here.
+        Interval {
+            lower: products.iter().min().unwrap().clone(),
+            upper: products.iter().max().unwrap().clone(),
+        }
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}, {}]", self.lower, self.upper)
+    }
+}
+
+/// This analysis computes conservative value ranges for local variables and
+/// signals from constant assignments, bit constraints (on the form `x * (x - 1)
+/// === 0`), asserted comparisons, and the operators used to compute each
+/// value.
+///
+/// Note that signals are only bounded by constraints, so signal assignments
+/// using `<--` do not affect the range of the assigned signal. Ranges for
+/// arrays are tracked for the array as a whole.
+#[derive(Clone, Default)]
+pub struct RangeAnalysis {
+    prime: BigInt,
+    ranges: HashMap<VariableName, Interval>,
+    bounds: HashMap<VariableName, Interval>,
+    unbounded: HashSet<VariableName>,
+}
+
+impl RangeAnalysis {
+    fn new(prime: &BigInt) -> RangeAnalysis {
+        RangeAnalysis { prime: prime.clone(), ..Default::default() }
+    }
+
+    /// Returns the prime used by the analysis.
+    pub fn prime(&self) -> &BigInt {
+        &self.prime
+    }
+
+    /// Returns the range of the given variable, or `None` if the variable is
+    /// unbounded.
+    pub fn get_range(&self, var: &VariableName) -> Option<&Interval> {
+        self.ranges.get(var)
+    }
+
+    /// Returns an iterator over all bounded variables.
+    pub fn ranges(&self) -> impl Iterator<Item = (&VariableName, &Interval)> {
+        self.ranges.iter()
+    }
+
+    /// Returns a conservative range for the given expression, or `None` if no
+    /// range could be determined.
+    pub fn evaluate(&self, expr: &Expression) -> Option<Interval> {
+        self.evaluate_impl(expr, false)
+    }
+
+    /// Returns the range of the expression. If `optimistic` is true, phi
+    /// arguments and updated arrays that have not been visited yet are ignored.
+    fn evaluate_impl(&self, expr: &Expression, optimistic: bool) -> Option<Interval> {
+        use Expression::*;
+        if let Some(value) = expr.value() {
+            return self.value_range(value);
+        }
+        match expr {
+            Number(_, value) => Some(Interval::constant(self.normalize(value))),
+            Variable { name, .. } => self.get_range(name).cloned(),
+            Access { var, access, .. } => {
+                if access.iter().any(|access| matches!(access, AccessType::ComponentAccess(_))) {
+                    // We do not track ranges for component signals.
+                    None
+                } else {
+                    self.get_range(var).cloned()
+                }
+            }
+            Update { var, rhe, .. } => {
+                let rhe = self.evaluate_impl(rhe, optimistic)?;
+                match self.get_range(var) {
+                    Some(range) => Some(range.join(&rhe)),
+                    None if optimistic && !self.unbounded.contains(var) => Some(rhe),
+                    None => None,
+                }
+            }
+            Phi { args, .. } => {
+                let mut result: Option<Interval> = None;
+                for arg in args {
+                    match self.get_range(arg) {
+                        Some(range) => {
+                            result = Some(match result {
+                                Some(result) => result.join(range),
+                                None => range.clone(),
+                            });
+                        }
+                        None if optimistic && !self.unbounded.contains(arg) => {}
+                        None => return None,
+                    }
+                }
+                result
+            }
+            InlineArray { values, .. } => {
+                let mut result: Option<Interval> = None;
+                for value in values {
+                    let range = self.evaluate_impl(value, optimistic)?;
+                    result = Some(match result {
+                        Some(result) => result.join(&range),
+                        None => range,
+                    });
+                }
+                result
+            }
+            SwitchOp { if_true, if_false, .. } => {
+                let if_true = self.evaluate_impl(if_true, optimistic)?;
+                let if_false = self.evaluate_impl(if_false, optimistic)?;
+                Some(if_true.join(&if_false))
+            }
+            PrefixOp { prefix_op, rhe, .. } => {
+                use ExpressionPrefixOpcode::*;
+                match prefix_op {
+                    BoolNot => Some(Interval::boolean()),
+                    Sub => self.evaluate_impl(rhe, optimistic).map(|range| range.neg()),
+                    Complement => None,
+                }
+            }
+            InfixOp { lhe, infix_op, rhe, .. } => {
+                let lhr = self.evaluate_impl(lhe, optimistic);
+                let rhr = self.evaluate_impl(rhe, optimistic);
+                evaluate_infix_op(infix_op, lhr, rhr)
+            }
+            Call { .. } => None,
+        }
+    }
+
+    /// Converts a constant value to an interval.
+    fn value_range(&self, value: &ValueReduction) -> Option<Interval> {
+        use ValueReduction::*;
+        match value {
+            Boolean { value } => Some(Interval::constant(BigInt::from(*value as u8))),
+            FieldElement { value } => Some(Interval::constant(self.normalize(value))),
+        }
+    }
+
+    /// Maps a field element to the signed representation `(-p/2, p/2]`.
+    fn normalize(&self, value: &BigInt) -> BigInt {
+        let value = ((value % &self.prime) + &self.prime) % &self.prime;
+        if value > &self.prime / 2u32 {
+            value - &self.prime
+        } else {
+            value
+        }
+    }
+
+    /// Add an asserted bound on the given variable.
+    fn add_bound(&mut self, var: &VariableName, bound: Interval) {
+        trace!("adding bound {bound} for `{var:?}`");
+        let bound = match self.bounds.get(var) {
+            Some(previous) => previous.meet(&bound).unwrap_or(bound),
+            None => bound,
+        };
+        self.bounds.insert(var.clone(), bound);
+    }
+
+    /// Update the range of the given variable. Returns true if the range was
+    /// updated.
+    fn update_range(&mut self, var: &VariableName, range: Option<Interval>) -> bool {
+        if self.unbounded.contains(var) {
+            return false;
+        }
+        // Apply any asserted bounds to the computed range.
+        let range = match (range, self.bounds.get(var)) {
+            (Some(range), Some(bound)) => Some(range.meet(bound).unwrap_or(range)),
+            (None, Some(bound)) => Some(bound.clone()),
+            (range, None) => range,
+        };
+        match range {
+            Some(range) if range.fits_in_field(&self.prime) => {
+                let range = match self.ranges.get(var) {
+                    Some(previous) => previous.join(&range),
+                    None => range,
+                };
+                if self.ranges.get(var) == Some(&range) {
+                    return false;
+                }
+                trace!("updating range of `{var:?}` to {range}");
+                self.ranges.insert(var.clone(), range);
+                true
+            }
+            _ => {
+                self.set_unbounded(var);
+                true
+            }
+        }
+    }
+
+    fn set_unbounded(&mut self, var: &VariableName) {
+        trace!("`{var:?}` is unbounded");
+        self.ranges.remove(var);
+        self.unbounded.insert(var.clone());
+    }
+
+    /// Visit each statement in the CFG once, updating variable ranges. Returns
+    /// the set of variables that were updated.
+    fn visit_cfg(&mut self, cfg: &Cfg) -> HashSet<VariableName> {
+        let mut updated = HashSet::new();
+        for basic_block in cfg.iter() {
+            for stmt in basic_block.iter() {
+                for var in self.visit_statement(stmt) {
+                    updated.insert(var);
+                }
+            }
+        }
+        updated
+    }
+
+    fn visit_statement(&mut self, stmt: &Statement) -> Vec<VariableName> {
+        use AssignOp::*;
+        use Statement::*;
+        let mut updated = Vec::new();
+        match stmt {
+            Substitution { meta, var, op, rhe } => {
+                let range = match (meta.type_knowledge().variable_type(), op) {
+                    // Local variables are bounded by the assigned value, while
+                    // signals are only bounded by constraints.
+                    (Some(VariableType::Local), _)
+                    | (Some(VariableType::Signal(..)), AssignConstraintSignal) => {
+                        self.evaluate_impl(rhe, true)
+                    }
+                    _ => return updated,
+                };
+                if self.update_range(var, range) {
+                    updated.push(var.clone());
+                }
+            }
+            ConstraintEquality { lhe, rhe, .. } => {
+                if let Some(var) = is_bit_constraint(lhe, rhe) {
+                    if self.update_range(&var, Some(Interval::boolean())) {
+                        updated.push(var);
+                    }
+                    return updated;
+                }
+                for (signal, value) in [(lhe, rhe), (rhe, lhe)] {
+                    if let Some(var) = signal_name(signal) {
+                        if let Some(range) = self.evaluate_impl(value, true) {
+                            if self.update_range(&var, Some(range)) {
+                                updated.push(var);
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        updated
+    }
+
+    /// Add bounds implied by an asserted expression on the form `x < c`.
+    fn visit_assert(&mut self, arg: &Expression) {
+        use Expression::*;
+        use ExpressionInfixOpcode::*;
+        let InfixOp { lhe, infix_op, rhe, .. } = arg else {
+            return;
+        };
+        if matches!(infix_op, BoolAnd) {
+            self.visit_assert(lhe);
+            self.visit_assert(rhe);
+            return;
+        }
+        let one = BigInt::one();
+        match (local_name(lhe), local_name(rhe)) {
+            // The case `x op c`.
+            (Some(var), None) => {
+                let Some(value) = rhe.value().and_then(|value| self.value_range(value)) else {
+                    return;
+                };
+                let bound = match infix_op {
+                    Lesser => self.upper_bound(value.upper() - &one),
+                    LesserEq => self.upper_bound(value.upper().clone()),
+                    Greater => self.lower_bound(value.lower() + &one),
+                    GreaterEq => self.lower_bound(value.lower().clone()),
+                    Eq => Some(value),
+                    _ => None,
+                };
+                if let Some(bound) = bound {
+                    self.add_bound(&var, bound);
+                }
+            }
+            // The case `c op x`.
+            (None, Some(var)) => {
+                let Some(value) = lhe.value().and_then(|value| self.value_range(value)) else {
+                    return;
+                };
+                let bound = match infix_op {
+                    Lesser => self.lower_bound(value.lower() + &one),
+                    LesserEq => self.lower_bound(value.lower().clone()),
+                    Greater => self.upper_bound(value.upper() - &one),
+                    GreaterEq => self.upper_bound(value.upper().clone()),
+                    Eq => Some(value),
+                    _ => None,
+                };
+                if let Some(bound) = bound {
+                    self.add_bound(&var, bound);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the interval `(-p/2, upper]`, or `None` if the interval is empty.
+    fn upper_bound(&self, upper: BigInt) -> Option<Interval> {
+        let lower: BigInt = -(&self.prime / 2u32) + 1;
+        (lower <= upper).then(|| Interval::new(lower, upper))
+    }
+
+    /// Returns the interval `[lower, p/2]`, or `None` if the interval is empty.
+    fn lower_bound(&self, lower: BigInt) -> Option<Interval> {
+        let upper: BigInt = &self.prime / 2u32;
+        (lower <= upper).then(|| Interval::new(lower, upper))
+    }
+}
+
+/// Computes the range of an infix operation given the ranges of the operands.
+fn evaluate_infix_op(
+    infix_op: &ExpressionInfixOpcode,
+    lhr: Option<Interval>,
+    rhr: Option<Interval>,
+) -> Option<Interval> {
+    use ExpressionInfixOpcode::*;
+    match infix_op {
+        // Comparisons and boolean operators always evaluate to 0 or 1.
+        Lesser | Greater | LesserEq | GreaterEq | Eq | NotEq | BoolAnd | BoolOr => {
+            Some(Interval::boolean())
+        }
+        Add => Some(lhr?.add(&rhr?)),
+        Sub => Some(lhr?.sub(&rhr?)),
+        Mul => Some(lhr?.mul(&rhr?)),
+        BitAnd => {
+            // The result is bounded by any non-negative operand.
+            match (lhr, rhr) {
+                (Some(lhr), Some(rhr)) if lhr.is_non_negative() && rhr.is_non_negative() => {
+                    Some(Interval::new(BigInt::zero(), min(lhr.upper(), rhr.upper()).clone()))
+                }
+                (Some(range), _) | (_, Some(range)) if range.is_non_negative() => {
+                    Some(Interval::new(BigInt::zero(), range.upper().clone()))
+                }
+                _ => None,
+            }
+        }
+        BitOr | BitXor => {
+            let (lhr, rhr) = (lhr?, rhr?);
+            if lhr.is_non_negative() && rhr.is_non_negative() {
+                Some(Interval::from_bits(max(lhr.bits(), rhr.bits())))
+            } else {
+                None
+            }
+        }
+        Mod => {
+            // The result of `x % c` is in `[0, c - 1]` for positive `c`.
+            let rhr = rhr?;
+            if rhr.lower().is_positive() {
+                Some(Interval::new(BigInt::zero(), rhr.upper() - 1))
+            } else {
+                None
+            }
+        }
+        IntDiv => {
+            let (lhr, rhr) = (lhr?, rhr?);
+            if lhr.is_non_negative() && rhr.lower().is_positive() {
+                Some(Interval::new(lhr.lower() / rhr.upper(), lhr.upper() / rhr.lower()))
+            } else {
+                None
+            }
+        }
+        ShiftR => {
+            let (lhr, rhr) = (lhr?, rhr?);
+            let shift = small_constant(&rhr)?;
+            if lhr.is_non_negative() {
+                Some(Interval::new(lhr.lower() >> shift, lhr.upper() >> shift))
+            } else {
+                None
+            }
+        }
+        ShiftL => {
+            let (lhr, rhr) = (lhr?, rhr?);
+            let shift = small_constant(&rhr)?;
+            Some(lhr.mul(&Interval::constant(BigInt::one() << shift)))
+        }
+        Pow => {
+            let (lhr, rhr) = (lhr?, rhr?);
+            let exponent = small_constant(&rhr)?;
+            if lhr.is_non_negative() {
+                Some(Interval::new(
+                    num_traits::pow(lhr.lower().clone(), exponent),
+                    num_traits::pow(lhr.upper().clone(), exponent),
+                ))
+            } else {
+                None
+            }
+        }
+        // Field division does not preserve ranges.
+        Div => None,
+    }
+}
+
+/// Returns the value of a constant non-negative interval if it is small enough
+/// to be used as an exponent or shift.
+fn small_constant(range: &Interval) -> Option<usize> {
+    if range.is_constant() && range.is_non_negative() {
+        range.lower().to_usize().filter(|value| *value <= MAX_EXPONENT)
+    } else {
+        None
+    }
+}
+
+/// Returns the name of the variable if the expression is a local variable (or
+/// an element of a local array).
+fn local_name(expr: &Expression) -> Option<VariableName> {
+    variable_name(expr).filter(|_| expr.meta().type_knowledge().is_local())
+}
+
+/// Returns the name of the signal if the expression is a signal (or an element
+/// of a signal array).
+fn signal_name(expr: &Expression) -> Option<VariableName> {
+    variable_name(expr).filter(|_| expr.meta().type_knowledge().is_signal())
+}
+
+fn variable_name(expr: &Expression) -> Option<VariableName> {
+    use Expression::*;
+    match expr {
+        Variable { name, .. } => Some(name.clone()),
+        Access { var, access, .. }
+            if access.iter().all(|access| matches!(access, AccessType::ArrayAccess(_))) =>
+        {
+            Some(var.clone())
+        }
+        _ => None,
+    }
+}
+
+/// Returns the constrained variable if the constraint is on the form
+/// `x * (x - 1) === 0`, or any of the equivalent forms `x * (1 - x) === 0`,
+/// `(x - 1) * x === 0`, and `0 === x * (x - 1)`.
+fn is_bit_constraint(lhe: &Expression, rhe: &Expression) -> Option<VariableName> {
+    use Expression::*;
+    use ExpressionInfixOpcode::*;
+    let product = if is_zero(rhe) {
+        lhe
+    } else if is_zero(lhe) {
+        rhe
+    } else {
+        return None;
+    };
+    let InfixOp { lhe, infix_op: Mul, rhe, .. } = product else {
+        return None;
+    };
+    for (var, factor) in [(lhe, rhe), (rhe, lhe)] {
+        if let InfixOp { lhe: x, infix_op: Sub, rhe: y, .. } = factor.as_ref() {
+            // Check for `x * (x - 1)` and `x * (1 - x)`.
+            if (x == var && is_one(y)) || (y == var && is_one(x)) {
+                return variable_name(var);
+            }
+        }
+    }
+    None
+}
+
+fn is_zero(expr: &Expression) -> bool {
+    matches!(expr.value(), Some(ValueReduction::FieldElement { value }) if value.is_zero())
+}
+
+fn is_one(expr: &Expression) -> bool {
+    matches!(expr.value(), Some(ValueReduction::FieldElement { value }) if value.is_one())
+}
+
+pub fn run_range_analysis(cfg: &Cfg) -> RangeAnalysis {
+    debug!("running range analysis pass");
+    let mut result = RangeAnalysis::new(cfg.constants().prime());
+
+    // Collect bounds on local variables from asserted comparisons. Since each
+    // variable is assigned once in SSA form, an asserted bound holds globally.
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            if let Statement::Assert { arg, .. } = stmt {
+                result.visit_assert(arg);
+            }
+        }
+    }
+    // Parameters are only bounded by asserted comparisons.
+    for param in cfg.parameters().iter() {
+        result.update_range(param, None);
+    }
+
+    // Iterate until a fixed point is reached. If ranges are still growing after
+    // `MAX_ITERATIONS` passes, the updated variables are considered unbounded.
+    // Since unbounded variables are never updated, this process terminates.
+    let mut iterations = 0;
+    loop {
+        let updated = result.visit_cfg(cfg);
+        if updated.is_empty() {
+            break;
+        }
+        iterations += 1;
+        if iterations >= MAX_ITERATIONS {
+            for var in updated {
+                result.set_unbounded(&var);
+            }
+            iterations = 0;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::cfg::IntoCfg;
+    use program_structure::constants::Curve;
+    use program_structure::report::ReportCollection;
+
+    use super::*;
+
+    #[test]
+    fn test_interval_arithmetic() {
+        let a = Interval::new(BigInt::from(-1), BigInt::from(2));
+        let b = Interval::new(BigInt::from(3), BigInt::from(5));
+        assert_eq!(a.add(&b), Interval::new(BigInt::from(2), BigInt::from(7)));
+        assert_eq!(a.sub(&b), Interval::new(BigInt::from(-6), BigInt::from(-1)));
+        assert_eq!(a.mul(&b), Interval::new(BigInt::from(-5), BigInt::from(10)));
+        assert_eq!(a.neg(), Interval::new(BigInt::from(-2), BigInt::from(1)));
+        assert_eq!(a.join(&b), Interval::new(BigInt::from(-1), BigInt::from(5)));
+        assert_eq!(a.meet(&b), None);
+        assert_eq!(Interval::from_bits(8).upper(), &BigInt::from(255));
+    }
+
+    #[test]
+    fn test_range_analysis() {
+        let src = r#"
+            template T(n) {
+                signal input in;
+                signal bits[4];
+                signal output out;
+
+                assert(n >= 0 && n <= 8);
+                var e = 1;
+                var sum = 0;
+                for (var i = 0; i < 4; i++) {
+                    bits[i] <-- (in >> i) & 1;
+                    bits[i] * (bits[i] - 1) === 0;
+                    sum += bits[i] * e;
+                    e = 2 * e;
+                }
+                var m = in % 16;
+                var k = n + 1;
+                out <== bits[0] + bits[1];
+            }
+        "#;
+        let mut ranges = HashMap::new();
+        ranges.insert("bits", Some((0, 1)));
+        ranges.insert("out", Some((0, 2)));
+        ranges.insert("n", Some((0, 8)));
+        ranges.insert("k", Some((1, 9)));
+        ranges.insert("m", Some((0, 15)));
+        ranges.insert("in", None);
+        // These are defined in a loop with a growing range.
+        ranges.insert("sum", None);
+        ranges.insert("e", None);
+        validate_ranges(src, &ranges);
+    }
+
+    fn validate_ranges(src: &str, ranges: &HashMap<&str, Option<(i64, i64)>>) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        let range_analysis = run_range_analysis(&cfg);
+        for (name, expected) in ranges {
+            // Compute the join over all versions of the variable.
+            let mut actual: Option<Interval> = None;
+            for (_, range) in range_analysis.ranges().filter(|(var, _)| var.name() == *name) {
+                actual = Some(match actual {
+                    Some(actual) => actual.join(range),
+                    None => range.clone(),
+                });
+            }
+            let bounded = !range_analysis.unbounded.iter().any(|var| var.name() == *name);
+            let actual = if bounded { actual } else { None };
+            let expected = expected
+                .map(|(lower, upper)| Interval::new(BigInt::from(lower), BigInt::from(upper)));
+            assert_eq!(actual, expected, "unexpected range for `{name}`");
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use log::debug;
-use std::fmt::Write;
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+use std::fmt::{self, Write};
 use std::collections::{HashMap, HashSet};
 
 use program_structure::cfg::{Cfg, DefinitionType};
@@ -8,13 +10,19 @@ use program_structure::report::{Report, ReportCollection};
 use program_structure::file_definition::{FileID, FileLocation};
 use program_structure::ir::declarations::Declaration;
 use program_structure::ir::variable_meta::{VariableMeta, VariableUse};
-use program_structure::ir::{Expression, SignalType, Statement, VariableType};
+use program_structure::ir::value_meta::{ValueMeta, ValueReduction};
+use program_structure::ir::{Expression, SignalType, Statement, VariableName, VariableType};
 
-use crate::constraint_analysis::run_constraint_analysis;
-use crate::taint_analysis::run_taint_analysis;
+use crate::constraint_analysis::{run_constraint_analysis, ConstraintAnalysis};
+use crate::taint_analysis::{run_taint_analysis, TaintAnalysis};
+use crate::unused_return_value::find_discarded_call_results;
+
+/// The maximum number of elements of a signal array checked individually.
+const MAX_ARRAY_ELEMENTS: usize = 256;
 
 pub struct UnusedVariableWarning {
     var: VariableUse,
+    useful_definitions: Vec<VariableUse>,
 }
 
 impl UnusedVariableWarning {
@@ -33,6 +41,7 @@ impl UnusedVariableWarning {
                 format!("The value assigned to `{}` here is never read.", self.var),
             );
         }
+        add_useful_definitions(&mut report, &self.useful_definitions);
         report
     }
 }
@@ -79,6 +88,33 @@ impl UnconstrainedSignalWarning {
     }
 }
 
+pub struct UnconstrainedSignalElementsWarning {
+    elements: Vec<String>,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl UnconstrainedSignalElementsWarning {
+    pub fn into_report(self) -> Report {
+        let elements =
+            self.elements.iter().map(|element| format!("`{element}`")).collect::<Vec<_>>();
+        let message = if elements.len() == 1 {
+            format!("The signal {} is not constrained by the template.", elements[0])
+        } else {
+            format!("The signals {} are not constrained by the template.", elements.join(", "))
+        };
+        let mut report = Report::warning(message, ReportCode::UnconstrainedSignal);
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                "Only some elements of this signal array occur in a constraint.".to_string(),
+            );
+        }
+        report
+    }
+}
+
 pub struct UnusedSignalWarning {
     signal_name: String,
     dimensions: Vec<Expression>,
@@ -130,7 +166,7 @@ pub struct UnusedParameterWarning {
 impl UnusedParameterWarning {
     pub fn into_report(self) -> Report {
         let mut report = Report::warning(
-            format!("The parameter `{}` is never read.", self.param.name()),
+            unused_parameter_message(self.param.name()),
             ReportCode::UnusedParameterValue,
         );
         if let Some(file_id) = self.param.meta().file_id() {
@@ -148,9 +184,15 @@ impl UnusedParameterWarning {
     }
 }
 
+/// Returns the message used to report that the given parameter is unused.
+pub(crate) fn unused_parameter_message(param: &impl fmt::Display) -> String {
+    format!("The parameter `{param}` is never read.")
+}
+
 pub struct VariableWithoutSideEffectsWarning {
     var: VariableUse,
     cfg_type: DefinitionType,
+    useful_definitions: Vec<VariableUse>,
 }
 
 impl VariableWithoutSideEffectsWarning {
@@ -177,6 +219,7 @@ impl VariableWithoutSideEffectsWarning {
         if let Some(file_id) = self.var.meta().file_id() {
             report.add_primary(self.var.meta().file_location(), file_id, primary);
         }
+        add_useful_definitions(&mut report, &self.useful_definitions);
         report
     }
 }
@@ -217,6 +260,60 @@ impl ParamWithoutSideEffectsWarning {
     }
 }
 
+pub struct ConfusableNameWarning {
+    name: VariableName,
+    used_name: VariableName,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+    used_file_id: Option<FileID>,
+    used_file_location: FileLocation,
+}
+
+impl ConfusableNameWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            format!(
+                "The name `{}` is easily confused with the name `{}`.",
+                self.name, self.used_name
+            ),
+            ReportCode::ConfusableName,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                format!("`{}` is declared here, but has no side effects.", self.name),
+            );
+        }
+        if let Some(file_id) = self.used_file_id {
+            report.add_secondary(
+                self.used_file_location,
+                file_id,
+                Some(format!("`{}` is declared here, and is used.", self.used_name)),
+            );
+        }
+        report.add_note(format!(
+            "This may indicate that `{}` is used by mistake, where `{}` was intended.",
+            self.used_name, self.name
+        ));
+        report
+    }
+}
+
+/// Adds a secondary label for each assignment to the same variable whose
+/// value is used.
+fn add_useful_definitions(report: &mut Report, useful_definitions: &[VariableUse]) {
+    for definition in useful_definitions {
+        if let Some(file_id) = definition.meta().file_id() {
+            report.add_secondary(
+                definition.meta().file_location(),
+                file_id,
+                Some(format!("The value assigned to `{definition}` here is used.")),
+            );
+        }
+    }
+}
+
 /// Local variables and intermediate signals that do not flow into either
 ///
 ///   1. an input or output signal,
@@ -225,6 +322,10 @@ impl ParamWithoutSideEffectsWarning {
 ///
 /// are side-effect free and do not affect either witness or constraint
 /// generation.
+///
+/// Each assignment that does not flow into a sink is reported separately.
+/// Other assignments to the same variable which are used are included as
+/// secondary labels.
 pub fn run_side_effect_analysis(cfg: &Cfg) -> ReportCollection {
     debug!("running side-effect analysis pass");
 
@@ -255,7 +356,7 @@ pub fn run_side_effect_analysis(cfg: &Cfg) -> ReportCollection {
         .declarations()
         .iter()
         .filter_map(|(name, declaration)| {
-            if matches!(declaration.variable_type(), VariableType::Signal(_)) {
+            if matches!(declaration.variable_type(), VariableType::Signal(..)) {
                 Some((name, declaration))
             } else {
                 None
@@ -267,7 +368,7 @@ pub fn run_side_effect_analysis(cfg: &Cfg) -> ReportCollection {
         .filter_map(|(name, declaration)| {
             if matches!(
                 declaration.variable_type(),
-                VariableType::Signal(SignalType::Input | SignalType::Output)
+                VariableType::Signal(SignalType::Input | SignalType::Output, _)
             ) {
                 Some(*name)
             } else {
@@ -299,7 +400,24 @@ pub fn run_side_effect_analysis(cfg: &Cfg) -> ReportCollection {
         .collect::<HashSet<_>>();
 
     // Add input and output signals to this set.
-    sinks.extend(exported_signals.into_iter());
+    sinks.extend(exported_signals);
+
+    // Values assigned to component inputs are used to generate the witness
+    // and constraints of the subcomponent, so we add components with assigned
+    // inputs, together with the variables flowing into these inputs.
+    for (name, declaration) in cfg.declarations().iter() {
+        if !matches!(declaration.variable_type(), VariableType::Component) {
+            continue;
+        }
+        let members = taint_analysis.assigned_members(name);
+        if members.is_empty() {
+            continue;
+        }
+        for member in members {
+            sinks.extend(taint_analysis.member_sources(name, member.as_str()));
+        }
+        sinks.insert(name.clone());
+    }
 
     // println!("constraint sinks: {:?}", sinks);
 
@@ -321,29 +439,77 @@ pub fn run_side_effect_analysis(cfg: &Cfg) -> ReportCollection {
     // println!("all sinks: {:?}", sinks);
     // println!("variables read: {:?}", variables_read);
 
+    // Partition the assignments into useful and side-effect free
+    // assignments.
+    let mut useful_definitions = HashMap::<_, Vec<_>>::new();
+    let mut useless_definitions = Vec::new();
+    for source in taint_analysis.definitions() {
+        if !variables_read.contains(source.name())
+            || !taint_analysis.taints_any(source.name(), &sinks)
+        {
+            useless_definitions.push(source);
+        } else {
+            useful_definitions.entry(source.name().without_version()).or_default().push(source);
+        }
+    }
+    // Sort assignments by location to ensure that reports and labels are
+    // generated in a deterministic order.
+    let location = |var: &&VariableUse| var.meta().file_location().start;
+    useless_definitions.sort_by_key(location);
+    for definitions in useful_definitions.values_mut() {
+        definitions.sort_by_key(location);
+    }
+
     let mut reports = ReportCollection::new();
     let mut reported_vars = HashSet::new();
+    // Variables and signals without side effects (ignoring variables with
+    // some useful assignments).
+    let mut side_effect_free = HashSet::new();
 
-    // Generate a report for any variable that does not taint a sink.
-    for source in taint_analysis.definitions() {
+    // Discarded function return values are reported by the unused return
+    // value analysis.
+    let discarded_call_results = find_discarded_call_results(cfg);
+
+    // Generate a report for any assignment that does not taint a sink.
+    for source in useless_definitions {
+        if discarded_call_results.contains(source.name()) {
+            continue;
+        }
+        if !useful_definitions.contains_key(&source.name().without_version()) {
+            side_effect_free.insert(source.name().without_version());
+        }
+        let is_param = cfg.parameters().contains(source.name());
+        let useful = useful_definitions
+            .get(&source.name().without_version())
+            .map(|definitions| definitions.iter().map(|var| (*var).clone()).collect())
+            .unwrap_or_default();
         if !variables_read.contains(source.name()) {
             // If the variable is unread, the corresponding value is unused.
-            if cfg.parameters().contains(source.name()) {
+            if is_param {
                 reports.push(build_unused_param(source, cfg.name()))
             } else {
-                reports.push(build_unused_variable(source));
+                reports.push(build_unused_variable(source, useful));
             }
-            reported_vars.insert(source.name().to_string());
-        } else if !taint_analysis.taints_any(source.name(), &sinks) {
+        } else if is_param {
             // If the variable does not flow into any of the sinks, it is side-effect free.
-            if cfg.parameters().contains(source.name()) {
-                reports.push(build_param_without_side_effect(source, cfg.definition_type()));
-            } else {
-                reports.push(build_variable_without_side_effect(source, cfg.definition_type()));
+            reports.push(build_param_without_side_effect(source, cfg.definition_type()));
+        } else {
+            reports.push(build_variable_without_side_effect(source, cfg.definition_type(), useful));
+        }
+        reported_vars.insert(source.name().to_string());
+    }
+    // Collect the dimensions of declared variables from the CFG. (Unlike the
+    // declarations returned by `Cfg::declarations`, these have been
+    // evaluated during value propagation.)
+    let mut dimensions = HashMap::new();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            if let Declaration { names, dimensions: sizes, .. } = stmt {
+                dimensions.extend(names.iter().map(|name| (name, &sizes[..])));
             }
-            reported_vars.insert(source.name().to_string());
         }
     }
+
     // Generate reports for unused or unconstrained signals.
     for (source, declaration) in signal_decls {
         // Don't generate multiple reports for the same variable.
@@ -353,20 +519,172 @@ pub fn run_side_effect_analysis(cfg: &Cfg) -> ReportCollection {
         if !variables_read.contains(source) {
             // If the variable is unread, it must be unconstrained.
             reports.push(build_unused_signal(declaration));
+            side_effect_free.insert(source.clone());
         } else if matches!(cfg.definition_type(), DefinitionType::Template)
             && !taint_analysis.taints_any(source, &constraint_analysis.constrained_variables())
         {
             // If the signal does not flow to a constraint, it is unconstrained.
             // (Note that we exclude functions and custom templates here since
             // they are not allowed to contain constraints.)
-            reports.push(build_unconstrained_signal(declaration));
+            reports.push(build_unconstrained_signal(declaration, cfg.name()));
+            side_effect_free.insert(source.clone());
+        } else if matches!(cfg.definition_type(), DefinitionType::Template) {
+            // If the signal is an array, check that each element is constrained.
+            let elements = unconstrained_elements(
+                source,
+                dimensions.get(source).copied().unwrap_or_default(),
+                &taint_analysis,
+                &constraint_analysis,
+            );
+            if !elements.is_empty() {
+                reports.push(build_unconstrained_signal_elements(
+                    declaration,
+                    &elements,
+                    cfg.name(),
+                ));
+            }
+        }
+    }
+
+    // A side-effect free variable or signal with a name which is easily
+    // confused with the name of a used variable or signal often means that
+    // the wrong name is used.
+    let names_read = variables_read.iter().map(VariableName::without_version).collect();
+    reports.extend(find_confusable_names(cfg, &side_effect_free, &names_read));
+    reports
+}
+
+/// Reports each side-effect free variable or signal with a name that is
+/// easily confused with the name of a variable or signal that is read.
+fn find_confusable_names(
+    cfg: &Cfg,
+    side_effect_free: &HashSet<VariableName>,
+    names_read: &HashSet<VariableName>,
+) -> ReportCollection {
+    // Each SSA version of a variable is declared separately, so we keep the
+    // first declaration of each variable.
+    let mut first_declarations = HashMap::new();
+    for (name, declaration) in cfg.declarations().iter() {
+        if !matches!(declaration.variable_type(), VariableType::Local | VariableType::Signal(..)) {
+            continue;
+        }
+        first_declarations
+            .entry(name.without_version())
+            .and_modify(|first: &mut &Declaration| {
+                if declaration.file_location().start < first.file_location().start {
+                    *first = declaration;
+                }
+            })
+            .or_insert(declaration);
+    }
+    let mut declarations = first_declarations.into_iter().collect::<Vec<_>>();
+    declarations.sort_by_key(|(_, declaration)| declaration.file_location().start);
+    let mut reports = ReportCollection::new();
+    for (name, declaration) in &declarations {
+        if !side_effect_free.contains(name) {
+            continue;
+        }
+        let used = declarations.iter().find(|(other, _)| {
+            !side_effect_free.contains(other)
+                && names_read.contains(other)
+                && is_confusable(name.name(), other.name())
+        });
+        if let Some((used_name, used_declaration)) = used {
+            reports.push(
+                ConfusableNameWarning {
+                    name: name.clone(),
+                    used_name: used_name.clone(),
+                    file_id: declaration.file_id(),
+                    file_location: declaration.file_location(),
+                    used_file_id: used_declaration.file_id(),
+                    used_file_location: used_declaration.file_location(),
+                }
+                .into_report(),
+            );
         }
     }
     reports
 }
 
-fn build_unused_variable(definition: &VariableUse) -> Report {
-    UnusedVariableWarning { var: definition.clone() }.into_report()
+/// Returns true if the two names differ only by case, or by a single
+/// character (like `lout` and `nout`, or `in1` and `inl`). Names differing
+/// in a single digit (like `in1` and `in2`) and names with fewer than three
+/// characters are not considered confusable.
+fn is_confusable(name: &str, other: &str) -> bool {
+    if name == other {
+        return false;
+    }
+    if name.eq_ignore_ascii_case(other) {
+        return true;
+    }
+    let (name, other) = (name.chars().collect::<Vec<_>>(), other.chars().collect::<Vec<_>>());
+    if name.len() != other.len() || name.len() < 3 {
+        return false;
+    }
+    let mut differences = name.iter().zip(other.iter()).filter(|(c, d)| c != d);
+    match (differences.next(), differences.next()) {
+        (Some((c, d)), None) => !(c.is_ascii_digit() && d.is_ascii_digit()),
+        _ => false,
+    }
+}
+
+/// Returns the elements of the given signal array which do not flow to a
+/// constraint. The elements of the array are only checked individually if
+/// the dimensions of the array are known. Elements accessed using
+/// non-constant indices are conservatively assumed to be constrained.
+fn unconstrained_elements(
+    signal: &VariableName,
+    dimensions: &[Expression],
+    taint_analysis: &TaintAnalysis,
+    constraint_analysis: &ConstraintAnalysis,
+) -> Vec<Vec<BigInt>> {
+    let Some(elements) = array_elements(dimensions) else {
+        return Vec::new();
+    };
+    let constrained_variables = constraint_analysis.constrained_variables();
+    // Collect the elements occurring in a constraint, or flowing into a
+    // variable which (in zero or more steps) flows into a constraint.
+    let mut constrained_elements =
+        constraint_analysis.constrained_elements(signal).cloned().unwrap_or_default();
+    for sink in taint_analysis.single_step_taint(signal) {
+        if &sink == signal || !taint_analysis.taints_any(&sink, &constrained_variables) {
+            continue;
+        }
+        if let Some(tainting_elements) = taint_analysis.tainting_elements(signal, &sink) {
+            constrained_elements.extend(tainting_elements);
+        }
+    }
+    elements.into_iter().filter(|element| !constrained_elements.contains(element)).collect()
+}
+
+/// Returns all elements of an array with the given dimensions, or `None` if
+/// the dimensions are not constant, or if the array is too large.
+fn array_elements(dimensions: &[Expression]) -> Option<Vec<Vec<BigInt>>> {
+    if dimensions.is_empty() {
+        return None;
+    }
+    let mut elements = vec![Vec::new()];
+    for size in dimensions {
+        let Some(ValueReduction::FieldElement { value }) = size.value() else {
+            return None;
+        };
+        let size = value.to_usize().filter(|size| elements.len() * size <= MAX_ARRAY_ELEMENTS)?;
+        elements = elements
+            .into_iter()
+            .flat_map(|element| {
+                (0..size).map(move |index| {
+                    let mut element = element.clone();
+                    element.push(BigInt::from(index));
+                    element
+                })
+            })
+            .collect();
+    }
+    Some(elements)
+}
+
+fn build_unused_variable(definition: &VariableUse, useful_definitions: Vec<VariableUse>) -> Report {
+    UnusedVariableWarning { var: definition.clone(), useful_definitions }.into_report()
 }
 
 fn build_unused_param(definition: &VariableUse, cfg_name: &str) -> Report {
@@ -384,22 +702,57 @@ fn build_unused_signal(declaration: &Declaration) -> Report {
     .into_report()
 }
 
-fn build_unconstrained_signal(declaration: &Declaration) -> Report {
-    UnconstrainedSignalWarning {
+fn build_unconstrained_signal(declaration: &Declaration, template_name: &str) -> Report {
+    let mut report = UnconstrainedSignalWarning {
         signal_name: declaration.variable_name().to_string(),
         dimensions: declaration.dimensions().clone(),
         file_id: declaration.file_id(),
         file_location: declaration.file_location(),
     }
-    .into_report()
+    .into_report();
+    report.add_affected_signal(template_name, &declaration.variable_name().to_string());
+    report
+}
+
+fn build_unconstrained_signal_elements(
+    declaration: &Declaration,
+    elements: &[Vec<BigInt>],
+    template_name: &str,
+) -> Report {
+    let elements = elements
+        .iter()
+        .map(|element| {
+            let mut result = declaration.variable_name().to_string();
+            for index in element {
+                // We ignore errors here.
+                let _ = write!(result, "[{index}]");
+            }
+            result
+        })
+        .collect::<Vec<_>>();
+    let mut report = UnconstrainedSignalElementsWarning {
+        elements: elements.clone(),
+        file_id: declaration.file_id(),
+        file_location: declaration.file_location(),
+    }
+    .into_report();
+    for element in &elements {
+        report.add_affected_signal(template_name, element);
+    }
+    report
 }
 
 fn build_variable_without_side_effect(
     definition: &VariableUse,
     cfg_type: &DefinitionType,
+    useful_definitions: Vec<VariableUse>,
 ) -> Report {
-    VariableWithoutSideEffectsWarning { var: definition.clone(), cfg_type: cfg_type.clone() }
-        .into_report()
+    VariableWithoutSideEffectsWarning {
+        var: definition.clone(),
+        cfg_type: cfg_type.clone(),
+        useful_definitions,
+    }
+    .into_report()
 }
 
 fn build_param_without_side_effect(definition: &VariableUse, cfg_type: &DefinitionType) -> Report {
@@ -446,7 +799,8 @@ mod tests {
               lin === nout;  // Should use `lout`, but uses `nout` by mistake.
             }
         "#;
-        validate_reports(src, 4);
+        // The side-effect free variable `lout` is also confused with `nout`.
+        validate_reports(src, 5);
 
         let src = r#"
             template PointOnLine(k, m, n) {
@@ -465,7 +819,9 @@ mod tests {
                 left.b <== right[0].b;
             }
         "#;
-        validate_reports(src, 4);
+        // Since `left.a` and `left.b` are assigned, `left` is used to generate
+        // constraints and only the unused input signal `in` is reported.
+        validate_reports(src, 1);
 
         let src = r#"
             template Sum(n) {
@@ -499,6 +855,148 @@ mod tests {
         validate_reports(src, 1);
     }
 
+    #[test]
+    fn test_component_inputs() {
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+
+                var k = 3;  // `k` only flows into a component input.
+                component c = A(1);
+                c.in[0] <== k;
+                c.in[1] <== k;
+
+                out <== in;
+            }
+        "#;
+        validate_reports(src, 0);
+
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+
+                var k = 3;  // The value assigned here is side-effect free.
+                component c[2];
+                c[k] = A(1);  // The value assigned here is side-effect free.
+
+                out <== in;
+            }
+        "#;
+        validate_reports(src, 2);
+    }
+
+    #[test]
+    fn test_unconstrained_signal_elements() {
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out[2];
+                signal tmp[2];
+
+                tmp[0] <== in * in;
+                tmp[1] <-- in;  // `tmp[1]` is not constrained.
+                out[0] <== tmp[0] + 1;
+                out[1] <== in + 1;
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out[2];
+                signal tmp[2];
+
+                tmp[0] <== in * in;
+                tmp[1] <-- in;
+                var x = tmp[1];  // `tmp[1]` is constrained through `x`.
+                out[0] <== tmp[0] + 1;
+                out[1] <== x + 1;
+            }
+        "#;
+        validate_reports(src, 0);
+
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out[2];
+                signal tmp[2];
+
+                tmp[0] <-- in;
+                tmp[1] <-- in;
+                for (var i = 0; i < 2; i++) {
+                    out[i] <== tmp[i] + 1;  // Loop-indexed accesses are summarized.
+                }
+            }
+        "#;
+        validate_reports(src, 0);
+    }
+
+    #[test]
+    fn test_side_effect_analysis_per_assignment() {
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+
+                var x = 0;  // The value assigned here is never read.
+                x = in * in;
+                out <== x;
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+
+                var x = 0;  // The value assigned here is never read.
+                x = 1;  // The value assigned here is never read.
+                x = in * in;
+                out <== x;
+            }
+        "#;
+        validate_reports(src, 2);
+    }
+
+    #[test]
+    fn test_confusable_names() {
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+
+                var Sum = in + in;
+                var sum = in * 2;
+                out <== sum;
+            }
+        "#;
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        let reports = run_side_effect_analysis(&cfg);
+        let confusable = reports
+            .iter()
+            .filter(|report| report.id() == ReportCode::ConfusableName.id())
+            .collect::<Vec<_>>();
+        assert_eq!(confusable.len(), 1);
+        assert!(confusable[0].message().contains("`Sum`"));
+
+        assert!(is_confusable("lout", "nout"));
+        assert!(is_confusable("in1", "inl"));
+        assert!(is_confusable("Out", "out"));
+        assert!(!is_confusable("in1", "in2"));
+        assert!(!is_confusable("lin", "lout"));
+        assert!(!is_confusable("a", "b"));
+    }
+
     fn validate_reports(src: &str, expected_len: usize) {
         // Build CFG.
         let mut reports = ReportCollection::new();
@@ -0,0 +1,169 @@
+use log::debug;
+
+use program_structure::cfg::{Cfg, DefinitionType};
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::degree_meta::{Degree, DegreeMeta};
+use program_structure::ir::variable_meta::VariableMeta;
+use program_structure::ir::*;
+
+/// Describes an equivalent way to constrain the asserted expression.
+enum Suggestion {
+    // The expression is a quadratic equality.
+    Constraint,
+    // The expression is an (in)equality.
+    IsEqual,
+    // The expression is a comparison.
+    Comparator,
+}
+
+pub struct SignalAssertWarning {
+    suggestion: Option<Suggestion>,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl SignalAssertWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            "Asserted expressions are only checked during witness generation and do not add any constraints.".to_string(),
+            ReportCode::SignalAssert,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                "This assert depends on the value of a signal.".to_string(),
+            );
+        }
+        use Suggestion::*;
+        match self.suggestion {
+            Some(Constraint) => {
+                report.add_note(
+                    "Since the asserted equality is quadratic, it can be enforced by replacing `assert(a == b)` with the constraint `a === b`.".to_string()
+                );
+            }
+            Some(IsEqual) => {
+                report.add_help(
+                    "Consider using the Circomlib templates `IsZero` or `IsEqual` to constrain the asserted expression.".to_string()
+                );
+            }
+            Some(Comparator) => {
+                report.add_help(
+                    "Consider using a Circomlib comparator like `LessThan` to constrain the asserted comparison.".to_string()
+                );
+            }
+            None => {}
+        }
+        report
+    }
+}
+
+/// `assert` statements are only evaluated during witness generation. If the
+/// asserted expression depends on the value of a signal, a malicious prover
+/// can simply ignore the assert, so the expression typically needs to be
+/// enforced using a constraint as well.
+pub fn find_signal_assert(cfg: &Cfg) -> ReportCollection {
+    use DefinitionType::*;
+    if matches!(cfg.definition_type(), Function | CustomTemplate) {
+        // Exit early if this is a function or custom template.
+        return ReportCollection::new();
+    }
+    debug!("running signal assert analysis pass");
+    let mut reports = ReportCollection::new();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            visit_statement(stmt, &mut reports);
+        }
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+fn visit_statement(stmt: &Statement, reports: &mut ReportCollection) {
+    if let Statement::Assert { meta, arg } = stmt {
+        if arg.signals_read().is_empty() && arg.components_read().is_empty() {
+            return;
+        }
+        reports.push(build_report(meta, get_suggestion(arg)));
+    }
+}
+
+fn get_suggestion(arg: &Expression) -> Option<Suggestion> {
+    use Expression::*;
+    use ExpressionInfixOpcode::*;
+    match arg {
+        InfixOp { lhe, infix_op: Eq, rhe, .. } if is_quadratic(lhe) && is_quadratic(rhe) => {
+            Some(Suggestion::Constraint)
+        }
+        InfixOp { infix_op: Eq | NotEq, .. } => Some(Suggestion::IsEqual),
+        InfixOp { infix_op: Lesser | Greater | LesserEq | GreaterEq, .. } => {
+            Some(Suggestion::Comparator)
+        }
+        _ => None,
+    }
+}
+
+fn is_quadratic(expr: &Expression) -> bool {
+    expr.degree().is_some_and(|range| range.end() <= Degree::Quadratic)
+}
+
+fn build_report(meta: &Meta, suggestion: Option<Suggestion>) -> Report {
+    SignalAssertWarning { suggestion, file_id: meta.file_id(), file_location: meta.file_location() }
+        .into_report()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_signal_assert() {
+        let src = r#"
+            template T(n) {
+                signal input in[2];
+                signal output out;
+
+                assert(n > 0);
+                assert(in[0] * in[1] == 1);
+                assert(in[0] < in[1]);
+                out <== in[0] + in[1];
+            }
+        "#;
+        validate_reports(src, 2);
+
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                component c = C();
+
+                c.in <== in;
+                assert(c.out != 0);
+                out <== c.out;
+            }
+        "#;
+        validate_reports(src, 1);
+    }
+
+    fn validate_reports(src: &str, expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_signal_assert(&cfg);
+
+        assert_eq!(reports.len(), expected_len);
+    }
+}
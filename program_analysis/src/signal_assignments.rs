@@ -52,7 +52,7 @@ impl SignalAssignmentWarning {
         }
         // If no constraints are identified, suggest using `<==` instead.
         if report.secondary().is_empty() {
-            report.add_note(
+            report.add_help(
                 "Consider if it is possible to rewrite the statement using `<==` instead."
                     .to_string(),
             );
@@ -86,7 +86,7 @@ impl UnecessarySignalAssignmentWarning {
             );
         }
         // We always suggest using `<==` instead.
-        report.add_note(
+        report.add_help(
             "Consider rewriting the statement using the constraint assignment operator `<==`."
                 .to_string(),
         );
@@ -347,6 +347,7 @@ mod tests {
 
         let src = r#"
             template T(n) {
+                signal input in;
                 signal output out[n];
 
                 in + 1 === out[0];
@@ -0,0 +1,203 @@
+use log::debug;
+use std::collections::HashSet;
+
+use program_structure::cfg::{Cfg, DefinitionType};
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::value_meta::ValueMeta;
+use program_structure::ir::variable_meta::VariableMeta;
+use program_structure::ir::*;
+
+use crate::loop_bounds::find_loops;
+use crate::taint_analysis::{run_taint_analysis, TaintAnalysis};
+
+pub struct SignalDependentLoopError {
+    taint_path: Vec<VariableName>,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+    constraint_file_id: Option<FileID>,
+    constraint_file_location: FileLocation,
+}
+
+impl SignalDependentLoopError {
+    pub fn into_report(self) -> Report {
+        let signal = &self.taint_path[0];
+        let mut report = Report::error(
+            format!(
+                "The loop condition depends on the signal `{signal}`, but the loop body generates constraints."
+            ),
+            ReportCode::SignalDependentLoop,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                format!("This condition depends on the signal `{signal}`."),
+            );
+        }
+        if let Some(file_id) = self.constraint_file_id {
+            report.add_secondary(
+                self.constraint_file_location,
+                file_id,
+                Some("Constraint generated by the loop body here.".to_string()),
+            );
+        }
+        if self.taint_path.len() > 1 {
+            let path = self
+                .taint_path
+                .iter()
+                .map(|name| format!("`{name}`"))
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            report.add_note(format!("The condition depends on `{signal}` through {path}."));
+        }
+        report.add_note(
+            "The constraints generated by a template must be known at compile time, which means that loops generating constraints can only have bounds depending on template parameters and constants.".to_string()
+        );
+        report
+    }
+}
+
+/// This analysis pass reports loops with conditions depending on signals,
+/// where the loop body generates constraints. The report includes the chain
+/// of variables through which the signal flows into the loop condition.
+pub fn find_signal_dependent_loops(cfg: &Cfg) -> ReportCollection {
+    debug!("running signal-dependent loop analysis pass");
+    let mut reports = ReportCollection::new();
+    if !matches!(cfg.definition_type(), DefinitionType::Template | DefinitionType::CustomTemplate) {
+        return reports;
+    }
+    let mut taint_analysis = None;
+    for cfg_loop in find_loops(cfg) {
+        if cfg_loop.cond.value().is_some() {
+            continue;
+        }
+        let mut indices = cfg_loop.body.iter().collect::<Vec<_>>();
+        indices.sort();
+        let constraint = indices
+            .into_iter()
+            .filter_map(|index| cfg.get_basic_block(*index))
+            .flat_map(|basic_block| basic_block.iter())
+            .find(|stmt| {
+                matches!(
+                    stmt,
+                    Statement::ConstraintEquality { .. }
+                        | Statement::Substitution { op: AssignOp::AssignConstraintSignal, .. }
+                )
+            });
+        let Some(constraint) = constraint else {
+            continue;
+        };
+        let taint_analysis = taint_analysis.get_or_insert_with(|| run_taint_analysis(cfg));
+        if let Some(taint_path) = signal_taint_path(cfg, taint_analysis, cfg_loop.cond) {
+            reports.push(build_report(taint_path, cfg_loop.cond.meta(), constraint.meta()));
+        }
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+/// Returns a chain of variables from a signal to a variable read by the given
+/// expression, if the expression depends on a signal.
+fn signal_taint_path(
+    cfg: &Cfg,
+    taint_analysis: &TaintAnalysis,
+    expr: &Expression,
+) -> Option<Vec<VariableName>> {
+    let mut signals =
+        expr.signals_read().iter().map(|var_use| var_use.name().clone()).collect::<Vec<_>>();
+    signals.sort_by_key(ToString::to_string);
+    if let Some(signal) = signals.into_iter().next() {
+        return Some(vec![signal]);
+    }
+    let locals =
+        expr.locals_read().iter().map(|var_use| var_use.name().clone()).collect::<HashSet<_>>();
+    let mut signals = cfg
+        .variables()
+        .filter(|name| matches!(cfg.get_type(name), Some(VariableType::Signal(..))))
+        .collect::<Vec<_>>();
+    signals.sort_by_key(|name| name.to_string());
+    signals.into_iter().find_map(|signal| taint_analysis.taint_path(signal, &locals))
+}
+
+fn build_report(taint_path: Vec<VariableName>, cond_meta: &Meta, constraint_meta: &Meta) -> Report {
+    // Versions of the same variable are shown once.
+    let mut path: Vec<VariableName> = Vec::new();
+    for name in taint_path.iter().map(VariableName::without_version) {
+        if path.last() != Some(&name) {
+            path.push(name);
+        }
+    }
+    SignalDependentLoopError {
+        taint_path: path,
+        file_id: cond_meta.file_id(),
+        file_location: cond_meta.file_location(),
+        constraint_file_id: constraint_meta.file_id(),
+        constraint_file_location: constraint_meta.file_location(),
+    }
+    .into_report()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_signal_dependent_loops() {
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out[8];
+                var n = in + 1;
+                for (var i = 0; i < n; i++) {
+                    out[i] <== in * i;
+                }
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            template T(n) {
+                signal input in;
+                signal output out[n];
+                for (var i = 0; i < n; i++) {
+                    out[i] <== in * i;
+                }
+            }
+        "#;
+        validate_reports(src, 0);
+
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                var x = 0;
+                while (x < in) {
+                    x += 1;
+                }
+                out <-- x;
+            }
+        "#;
+        validate_reports(src, 0);
+    }
+
+    fn validate_reports(src: &str, expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_signal_dependent_loops(&cfg);
+        assert_eq!(reports.len(), expected_len);
+    }
+}
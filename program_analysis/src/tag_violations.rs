@@ -0,0 +1,399 @@
+use log::debug;
+use std::collections::{HashMap, HashSet};
+
+use program_structure::cfg::{Cfg, DefinitionType};
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::*;
+use program_structure::template_data::TemplateInfo;
+
+pub struct TagViolationWarning {
+    template_name: String,
+    signal_name: String,
+    missing_tags: Vec<String>,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl TagViolationWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            format!(
+                "The input signal `{}` of `{}` requires the tag(s) {}, but the assigned value is not tagged.",
+                self.signal_name,
+                self.template_name,
+                format_tags(&self.missing_tags)
+            ),
+            ReportCode::TagViolation,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                format!(
+                    "The assigned value is missing the tag(s) {}.",
+                    format_tags(&self.missing_tags)
+                ),
+            );
+        }
+        report.add_note(
+            "Tags are only propagated through direct signal assignments. Consider checking the value using a template with a correspondingly tagged output signal.".to_string(),
+        );
+        report
+    }
+}
+
+/// The tags of a set of signals. A signal is mapped to `None` if the tags of
+/// the signal are not yet known.
+type TagMap = HashMap<VariableName, Option<HashSet<String>>>;
+
+/// Tracks the template instantiated by each component, and the (declared or
+/// propagated) tags of each signal.
+struct TagEnvironment<'a> {
+    templates: &'a TemplateInfo,
+    components: HashMap<VariableName, String>,
+    signal_tags: TagMap,
+    // Untagged intermediate signals. The tags of these signals are inferred.
+    inferred_signals: HashSet<VariableName>,
+}
+
+impl<'a> TagEnvironment<'a> {
+    fn new(templates: &'a TemplateInfo) -> TagEnvironment<'a> {
+        TagEnvironment {
+            templates,
+            components: HashMap::new(),
+            signal_tags: HashMap::new(),
+            inferred_signals: HashSet::new(),
+        }
+    }
+
+    /// Returns the tags of the value of the given expression. Tags are only
+    /// propagated by (array) signal accesses and component output accesses.
+    fn get_tags(&self, expr: &Expression) -> Option<HashSet<String>> {
+        use Expression::*;
+        match expr {
+            Variable { name, .. } => {
+                self.signal_tags.get(&name.without_version()).cloned().unwrap_or_default()
+            }
+            Access { var, access, .. } => {
+                let var = var.without_version();
+                let signal_name = access.iter().find_map(|access| match access {
+                    AccessType::ComponentAccess(signal_name) => Some(signal_name.as_str()),
+                    AccessType::ArrayAccess(_) => None,
+                });
+                match signal_name {
+                    Some(signal_name) => Some(self.output_tags(&var, signal_name)),
+                    None => self.signal_tags.get(&var).cloned().unwrap_or_default(),
+                }
+            }
+            _ => Some(HashSet::new()),
+        }
+    }
+
+    fn output_tags(&self, component: &VariableName, signal_name: &str) -> HashSet<String> {
+        self.components
+            .get(component)
+            .and_then(|template_name| self.templates.get(template_name))
+            .and_then(|template| template.get_output_info(signal_name))
+            .map(|(_, _, tags)| tags.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn input_tags(&self, component: &VariableName, signal_name: &str) -> Option<HashSet<String>> {
+        self.components
+            .get(component)
+            .and_then(|template_name| self.templates.get(template_name))
+            .and_then(|template| template.get_input_info(signal_name))
+            .map(|(_, _, tags)| tags.iter().cloned().collect())
+    }
+}
+
+/// Circom 2.1 allows signals to be tagged (as in `signal input {binary} in`).
+/// If an input signal of a template declares a tag, the signal assigned to
+/// the input is expected to carry the same tag. Tags are declared by input and
+/// output signals, and are propagated to intermediate signals by direct
+/// signal assignments. This analysis pass reports component inputs requiring
+/// a tag that are assigned values that are not known to carry the tag.
+pub fn find_tag_violations(cfg: &Cfg, templates: &TemplateInfo) -> ReportCollection {
+    use DefinitionType::*;
+    if matches!(cfg.definition_type(), Function | CustomTemplate) {
+        // Exit early if this is a function or custom template.
+        return ReportCollection::new();
+    }
+    debug!("running tag violation analysis pass");
+    let mut env = TagEnvironment::new(templates);
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            visit_declaration(stmt, &mut env);
+        }
+    }
+    // Tags are propagated to intermediate signals until a fixed point is
+    // reached. Since the tags of each signal only decrease, this terminates.
+    loop {
+        let mut changed = false;
+        for basic_block in cfg.iter() {
+            for stmt in basic_block.iter() {
+                changed |= propagate_tags(stmt, &mut env);
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    let mut reports = ReportCollection::new();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            visit_statement(stmt, &env, &mut reports);
+        }
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+/// Records the declared tags of each signal and the template instantiated by
+/// each component.
+fn visit_declaration(stmt: &Statement, env: &mut TagEnvironment) {
+    use Expression::*;
+    use Statement::*;
+    match stmt {
+        Declaration { names, var_type: VariableType::Signal(signal_type, tags), .. } => {
+            for name in names {
+                let name = name.without_version();
+                if matches!(signal_type, SignalType::Intermediate) && tags.is_empty() {
+                    env.inferred_signals.insert(name.clone());
+                    env.signal_tags.insert(name, None);
+                } else {
+                    env.signal_tags.insert(name, Some(tags.iter().cloned().collect()));
+                }
+            }
+        }
+        Substitution { var, op: AssignOp::AssignLocalOrComponent, rhe, .. } => {
+            // Assignments to component arrays are lifted to updates.
+            let rhe = if let Update { rhe, .. } = rhe { rhe.as_ref() } else { rhe };
+            if let Call { name, .. } = rhe {
+                if env.templates.contains_key(name) {
+                    env.components.insert(var.without_version(), name.clone());
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Propagates tags to intermediate signals. Returns true if the tags of some
+/// signal were updated.
+fn propagate_tags(stmt: &Statement, env: &mut TagEnvironment) -> bool {
+    use AssignOp::*;
+    use Expression::*;
+    use Statement::*;
+    let Substitution { var, op: AssignSignal | AssignConstraintSignal, rhe, .. } = stmt else {
+        return false;
+    };
+    let var = var.without_version();
+    if !env.inferred_signals.contains(&var) {
+        return false;
+    }
+    // Assignments to array elements are lifted to updates.
+    let rhe = match rhe {
+        Update { access, rhe, .. }
+            if access.iter().all(|access| matches!(access, AccessType::ArrayAccess(_))) =>
+        {
+            rhe.as_ref()
+        }
+        Update { .. } => return false,
+        _ => rhe,
+    };
+    let Some(new_tags) = env.get_tags(rhe) else {
+        // The tags of the assigned value are not yet known.
+        return false;
+    };
+    // If the signal is assigned more than once, it only carries the tags
+    // common to all assigned values.
+    let current_tags = env.signal_tags.get(&var).cloned().flatten();
+    let new_tags = match &current_tags {
+        Some(current_tags) => current_tags.intersection(&new_tags).cloned().collect(),
+        None => new_tags,
+    };
+    if current_tags.as_ref() == Some(&new_tags) {
+        return false;
+    }
+    env.signal_tags.insert(var, Some(new_tags));
+    true
+}
+
+fn visit_statement(stmt: &Statement, env: &TagEnvironment, reports: &mut ReportCollection) {
+    use AssignOp::*;
+    use Expression::*;
+    use Statement::*;
+    let Substitution { var, op: AssignSignal | AssignConstraintSignal, rhe, .. } = stmt else {
+        return;
+    };
+    // Assignments to component inputs are lifted to updates.
+    let Update { access, rhe, .. } = rhe else {
+        return;
+    };
+    let var = var.without_version();
+    let Some(signal_name) = access.iter().find_map(|access| match access {
+        AccessType::ComponentAccess(signal_name) => Some(signal_name.as_str()),
+        AccessType::ArrayAccess(_) => None,
+    }) else {
+        return;
+    };
+    let Some(required_tags) = env.input_tags(&var, signal_name) else {
+        return;
+    };
+    let tags = env.get_tags(rhe).unwrap_or_default();
+    let mut missing_tags = required_tags.difference(&tags).cloned().collect::<Vec<_>>();
+    if missing_tags.is_empty() {
+        return;
+    }
+    missing_tags.sort();
+    let template_name = env.components.get(&var).cloned().unwrap_or_default();
+    reports.push(build_report(rhe.meta(), template_name, signal_name, missing_tags));
+}
+
+fn format_tags(tags: &[String]) -> String {
+    tags.iter().map(|tag| format!("`{tag}`")).collect::<Vec<_>>().join(", ")
+}
+
+fn build_report(
+    meta: &Meta,
+    template_name: String,
+    signal_name: &str,
+    missing_tags: Vec<String>,
+) -> Report {
+    TagViolationWarning {
+        template_name,
+        signal_name: signal_name.to_string(),
+        missing_tags,
+        file_id: meta.file_id(),
+        file_location: meta.file_location(),
+    }
+    .into_report()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use parser::parse_definitions;
+    use program_structure::cfg::IntoCfg;
+    use program_structure::constants::Curve;
+    use program_structure::file_definition::FileLibrary;
+    use program_structure::template_library::TemplateLibrary;
+
+    use super::*;
+
+    #[test]
+    fn test_tag_violations() {
+        let src = r#"
+            template Not() {
+                signal input {binary} in;
+                signal output {binary} out;
+                out <== 1 - in;
+            }
+
+            template T() {
+                signal input a;
+                signal input {binary} b;
+                signal output out[3];
+                component not[3];
+
+                not[0] = Not();
+                not[0].in <== a;
+                not[1] = Not();
+                not[1].in <== b;
+                not[2] = Not();
+                not[2].in <== not[1].out;
+                out[0] <== not[0].out;
+                out[1] <== not[1].out;
+                out[2] <== not[2].out;
+            }
+        "#;
+        validate_reports(src, "T", 1);
+
+        let src = r#"
+            template Not() {
+                signal input {binary} in;
+                signal output {binary} out;
+                out <== 1 - in;
+            }
+
+            template T() {
+                signal input {binary} a;
+                signal output out;
+                signal x;
+                signal y;
+                component not = Not();
+
+                x <== a;
+                y <== x;
+                not.in <== y;
+                out <== not.out;
+            }
+        "#;
+        validate_reports(src, "T", 0);
+
+        let src = r#"
+            template Not() {
+                signal input {binary} in;
+                signal output {binary} out;
+                out <== 1 - in;
+            }
+
+            template T() {
+                signal input {binary} a;
+                signal output out;
+                signal x;
+                component not = Not();
+
+                x <== a;
+                x <== 2 * a;
+                not.in <== x;
+                out <== not.out;
+            }
+        "#;
+        validate_reports(src, "T", 1);
+
+        let src = r#"
+            template Num2Bits(n) {
+                signal input {maxbit} in;
+                signal output {binary} out[n];
+            }
+
+            template T() {
+                signal input {binary} a;
+                signal output out[8];
+                component n2b = Num2Bits(8);
+
+                n2b.in <== a;
+                out <== n2b.out;
+            }
+        "#;
+        validate_reports(src, "T", 1);
+    }
+
+    fn validate_reports(src: &str, name: &str, expected_len: usize) {
+        let mut file_library = FileLibrary::new();
+        let file_id = file_library.add_file("test.circom".to_string(), src.to_string());
+        let definitions = parse_definitions(src, file_id, &TemplateInfo::new()).ok().unwrap();
+        let library = TemplateLibrary::new(HashMap::from([(file_id, definitions)]), file_library);
+
+        // Generate CFG and convert to SSA.
+        let mut reports = ReportCollection::new();
+        let cfg = library
+            .templates
+            .get(name)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_tag_violations(&cfg, &library.templates);
+        assert_eq!(reports.len(), expected_len);
+    }
+}
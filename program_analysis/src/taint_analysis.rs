@@ -2,17 +2,27 @@ use log::{debug, trace};
 use program_structure::cfg::parameters::Parameters;
 use program_structure::intermediate_representation::value_meta::ValueMeta;
 use program_structure::intermediate_representation::Meta;
-use std::collections::{HashMap, HashSet};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::rc::Rc;
 
 use program_structure::cfg::Cfg;
 use program_structure::ir::variable_meta::{VariableMeta, VariableUse};
 use program_structure::ir::{Expression, Statement, VariableName};
+use program_structure::symbol::Symbol;
+
+use crate::array_elements::ArrayElements;
 
 #[derive(Clone, Default)]
 pub struct TaintAnalysis {
     taint_map: HashMap<VariableName, HashSet<VariableName>>,
     declarations: HashMap<VariableName, VariableUse>,
-    definitions: HashMap<VariableName, VariableUse>,
+    definitions: HashMap<VariableName, Vec<VariableUse>>,
+    element_taint_map: HashMap<(VariableName, VariableName), ArrayElements>,
+    member_taint_map: HashMap<(VariableName, Symbol), HashSet<VariableName>>,
+    members_read: HashMap<VariableName, HashSet<Symbol>>,
+    /// Memoized multi-step taint, computed on demand.
+    closures: RefCell<HashMap<VariableName, Rc<HashSet<VariableName>>>>,
 }
 
 impl TaintAnalysis {
@@ -29,27 +39,24 @@ impl TaintAnalysis {
     }
 
     /// Add the variable use corresponding to the definition of the variable.
+    ///
+    /// Since components and signals are not versioned, the same variable name
+    /// may be assigned multiple times. Each assignment is kept as a separate
+    /// definition.
     fn add_definition(&mut self, var: &VariableUse) {
-        // TODO: Since we don't version components and signals, we may end up
-        // overwriting component initializations here. For example, in the
-        // following case the component initialization will be clobbered.
-        //
-        //   component c[2] = C();
-        //   c[0].in[0] <== 0;
-        //   c[1].in[1] <== 1;
-        //
-        // As long as the initialized component flows to a constraint it will
-        // not be flagged during side-effect analysis.
-        self.definitions.insert(var.name().clone(), var.clone());
-    }
-
-    /// Get the variable use corresponding to the definition of the variable.
+        self.definitions.entry(var.name().clone()).or_default().push(var.clone());
+    }
+
+    /// Get the variable use corresponding to the (last) definition of the
+    /// variable.
     pub fn get_definition(&self, var: &VariableName) -> Option<VariableUse> {
-        self.definitions.get(var).cloned()
+        self.definitions.get(var).and_then(|definitions| definitions.last()).cloned()
     }
 
+    /// Returns an iterator over all assignments, including multiple
+    /// assignments to the same signal or component.
     pub fn definitions(&self) -> impl Iterator<Item = &VariableUse> {
-        self.definitions.values()
+        self.definitions.values().flatten()
     }
 
     /// Add the variable use corresponding to the declaration of the variable.
@@ -67,9 +74,64 @@ impl TaintAnalysis {
     }
 
     /// Add a single step taint from source to sink.
-    fn add_taint_step(&mut self, source: &VariableName, sink: &VariableName) {
-        let sinks = self.taint_map.entry(source.clone()).or_default();
+    fn add_taint_step(&mut self, source: &VariableUse, sink: &VariableName) {
+        let sinks = self.taint_map.entry(source.name().clone()).or_default();
         sinks.insert(sink.clone());
+        // Track which elements of the source taint the sink.
+        self.element_taint_map
+            .entry((source.name().clone(), sink.clone()))
+            .or_default()
+            .insert(source.access());
+    }
+
+    /// Returns the elements of `source` which taint `sink` in a single step.
+    pub fn tainting_elements(
+        &self,
+        source: &VariableName,
+        sink: &VariableName,
+    ) -> Option<&ArrayElements> {
+        self.element_taint_map.get(&(source.clone(), sink.clone()))
+    }
+
+    /// Add an assignment to the given component member, tainted by the given
+    /// sources.
+    fn add_member_assignment<'a>(
+        &mut self,
+        component: &VariableName,
+        member: &str,
+        sources: impl Iterator<Item = &'a VariableName>,
+    ) {
+        let entry =
+            self.member_taint_map.entry((component.clone(), Symbol::intern(member))).or_default();
+        entry.extend(sources.filter(|source| *source != component).cloned());
+    }
+
+    /// Add a read of the given component member.
+    fn add_member_read(&mut self, component: &VariableName, member: &str) {
+        self.members_read.entry(component.clone()).or_default().insert(Symbol::intern(member));
+    }
+
+    /// Returns the variables flowing into the given component member in a
+    /// single step.
+    pub fn member_sources(&self, component: &VariableName, member: &str) -> HashSet<VariableName> {
+        self.member_taint_map
+            .get(&(component.clone(), Symbol::intern(member)))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns the members of the given component which are assigned a value.
+    pub fn assigned_members(&self, component: &VariableName) -> HashSet<Symbol> {
+        self.member_taint_map
+            .keys()
+            .filter(|(name, _)| name == component)
+            .map(|(_, member)| *member)
+            .collect()
+    }
+
+    /// Returns the members of the given component which are read.
+    pub fn read_members(&self, component: &VariableName) -> HashSet<Symbol> {
+        self.members_read.get(component).cloned().unwrap_or_default()
     }
 
     /// Returns variables tainted in a single step by `source`.
@@ -79,18 +141,88 @@ impl TaintAnalysis {
 
     /// Returns variables tainted in zero or more steps by `source`.
     pub fn multi_step_taint(&self, source: &VariableName) -> HashSet<VariableName> {
-        let mut result = HashSet::new();
-        let mut update = HashSet::from([source.clone()]);
-        while !update.is_subset(&result) {
-            result.extend(update.iter().cloned());
-            update = update.iter().flat_map(|source| self.single_step_taint(source)).collect();
+        if !self.closures.borrow().contains_key(source) {
+            // This computes and memoizes the full multi-step taint.
+            self.search(source, |_| false);
         }
-        result
+        self.closures.borrow()[source].as_ref().clone()
+    }
+
+    /// Returns true if `source` taints `sink` in zero or more steps. The
+    /// search stops as soon as the sink is found, so this is cheaper than
+    /// computing the full multi-step taint of the source.
+    pub fn taints(&self, source: &VariableName, sink: &VariableName) -> bool {
+        self.search(source, |var| var == sink)
     }
 
     /// Returns true if the source taints any of the sinks.
     pub fn taints_any(&self, source: &VariableName, sinks: &HashSet<VariableName>) -> bool {
-        self.multi_step_taint(source).iter().any(|sink| sinks.contains(sink))
+        self.search(source, |var| sinks.contains(var))
+    }
+
+    /// Returns a shortest chain of variables from `source` to one of the sinks,
+    /// where each variable taints the next in a single step, or `None` if the
+    /// source does not taint any of the sinks.
+    pub fn taint_path(
+        &self,
+        source: &VariableName,
+        sinks: &HashSet<VariableName>,
+    ) -> Option<Vec<VariableName>> {
+        let mut parents = HashMap::<_, Option<VariableName>>::from([(source.clone(), None)]);
+        let mut worklist = VecDeque::from([source.clone()]);
+        while let Some(current) = worklist.pop_front() {
+            if sinks.contains(&current) {
+                let mut path = vec![current.clone()];
+                while let Some(Some(parent)) = parents.get(path.last().unwrap()) {
+                    path.push(parent.clone());
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for sink in self.taint_map.get(&current).into_iter().flatten() {
+                if !parents.contains_key(sink) {
+                    parents.insert(sink.clone(), Some(current.clone()));
+                    worklist.push_back(sink.clone());
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns true if a variable satisfying `is_sink` is tainted in zero or
+    /// more steps by `source`. Memoized multi-step taint is reused, and if no
+    /// such variable is found, the multi-step taint of the source is memoized.
+    fn search(&self, source: &VariableName, is_sink: impl Fn(&VariableName) -> bool) -> bool {
+        if let Some(closure) = self.closures.borrow().get(source) {
+            return closure.iter().any(is_sink);
+        }
+        if is_sink(source) {
+            return true;
+        }
+        let mut visited = HashSet::from([source.clone()]);
+        let mut worklist = vec![source.clone()];
+        while let Some(current) = worklist.pop() {
+            if current != *source {
+                // Variables tainted by a memoized variable do not need to be visited.
+                if let Some(closure) = self.closures.borrow().get(&current) {
+                    if closure.iter().any(&is_sink) {
+                        return true;
+                    }
+                    visited.extend(closure.iter().cloned());
+                    continue;
+                }
+            }
+            for sink in self.taint_map.get(&current).into_iter().flatten() {
+                if visited.insert(sink.clone()) {
+                    if is_sink(sink) {
+                        return true;
+                    }
+                    worklist.push(sink.clone());
+                }
+            }
+        }
+        self.closures.borrow_mut().insert(source.clone(), Rc::new(visited));
+        false
     }
 }
 
@@ -103,6 +235,12 @@ pub fn run_taint_analysis(cfg: &Cfg) -> TaintAnalysis {
     for basic_block in cfg.iter() {
         for stmt in basic_block.iter() {
             trace!("visiting statement `{stmt:?}`");
+            // Track component member reads (e.g. `c.out`).
+            for component in stmt.components_read() {
+                if let Some(member) = component.member() {
+                    result.add_member_read(component.name(), member);
+                }
+            }
             match stmt {
                 Substitution { .. } => {
                     // Variables read taint variables written by the statement.
@@ -112,6 +250,11 @@ pub fn run_taint_analysis(cfg: &Cfg) -> TaintAnalysis {
                             trace!("adding variable assignment for `{:?}`", sink.name());
                             result.add_definition(sink);
                         }
+                        if let Some(member) = sink.member() {
+                            // Variables read taint the assigned component member (e.g. `c.in`).
+                            let sources = stmt.variables_read().map(|source| source.name());
+                            result.add_member_assignment(sink.name(), member, sources);
+                        }
                         for source in stmt.variables_read() {
                             // Add each taint step to the result.
                             trace!(
@@ -119,7 +262,7 @@ pub fn run_taint_analysis(cfg: &Cfg) -> TaintAnalysis {
                                 source.name(),
                                 sink.name()
                             );
-                            result.add_taint_step(source.name(), sink.name());
+                            result.add_taint_step(source, sink.name());
                         }
                     }
                 }
@@ -129,7 +272,7 @@ pub fn run_taint_analysis(cfg: &Cfg) -> TaintAnalysis {
                         result.add_declaration(&VariableUse::new(meta, sink, &Vec::new()));
                         for size in dimensions {
                             for source in size.variables_read() {
-                                result.add_taint_step(source.name(), sink)
+                                result.add_taint_step(source, sink)
                             }
                         }
                     }
@@ -152,7 +295,7 @@ pub fn run_taint_analysis(cfg: &Cfg) -> TaintAnalysis {
                                     source.name(),
                                     sink.name()
                                 );
-                                result.add_taint_step(source.name(), sink.name());
+                                result.add_taint_step(source, sink.name());
                             }
                         }
                     }
@@ -226,7 +369,129 @@ mod tests {
         );
         taint_map.insert("i", HashSet::from(["i".to_string(), "right".to_string()]));
 
-        validate_taint(&src, &taint_map);
+        validate_taint(src, &taint_map);
+    }
+
+    #[test]
+    fn test_component_members() {
+        let src = r#"
+            template T() {
+                signal input in[2];
+                signal output out;
+
+                var k = 2;
+                component c = A();
+                c.in[0] <== in[0] * k;
+                c.in[1] <== 1;
+                out <== c.out;
+            }
+        "#;
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        let taint_analysis = run_taint_analysis(&cfg);
+        let component = VariableName::from_name("c");
+        assert_eq!(
+            taint_analysis.assigned_members(&component),
+            HashSet::from([Symbol::intern("in")])
+        );
+        assert_eq!(taint_analysis.read_members(&component), HashSet::from([Symbol::intern("out")]));
+        let sources = taint_analysis
+            .member_sources(&component, "in")
+            .iter()
+            .map(|var| var.name().to_string())
+            .collect::<HashSet<_>>();
+        assert_eq!(sources, HashSet::from(["in".to_string(), "k".to_string()]));
+    }
+
+    #[test]
+    fn test_memoized_taint() {
+        let src = r#"
+            template T(n) {
+                signal input in;
+                signal output out;
+
+                var x = in;
+                var y = 1;
+                var z = 0;
+                for (var i = 0; i < n; i++) {
+                    x = x + y;
+                    y = y * x;
+                    if (i == 2) {
+                        z = y;
+                    }
+                }
+                out <== z;
+            }
+        "#;
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        let taint_analysis = run_taint_analysis(&cfg);
+        let mut vars = taint_analysis
+            .definitions()
+            .map(|definition| definition.name().clone())
+            .collect::<Vec<_>>();
+        vars.sort_by_key(ToString::to_string);
+        // The loop introduces cyclic dataflow between the versions of `x` and `y`.
+        assert!(vars.iter().any(|var| {
+            let closure = uncached_taint(&taint_analysis, var);
+            closure
+                .iter()
+                .any(|sink| sink != var && uncached_taint(&taint_analysis, sink).contains(var))
+        }));
+
+        // Queries are repeated in different orders, which means that both cold
+        // and warm caches are exercised.
+        for order in [vars.clone(), vars.iter().rev().cloned().collect()] {
+            let cached_analysis = taint_analysis.clone();
+            for _ in 0..2 {
+                for source in &order {
+                    let expected = uncached_taint(&taint_analysis, source);
+                    for sink in &vars {
+                        assert_eq!(cached_analysis.taints(source, sink), expected.contains(sink));
+                    }
+                    for sinks in [HashSet::new(), HashSet::from_iter(vars.iter().take(3).cloned())]
+                    {
+                        assert_eq!(
+                            cached_analysis.taints_any(source, &sinks),
+                            !expected.is_disjoint(&sinks)
+                        );
+                    }
+                    assert_eq!(cached_analysis.multi_step_taint(source), expected);
+                }
+            }
+        }
+    }
+
+    /// Returns the multi-step taint of the source, computed without using the
+    /// memoized closures.
+    fn uncached_taint(
+        taint_analysis: &TaintAnalysis,
+        source: &VariableName,
+    ) -> HashSet<VariableName> {
+        let mut visited = HashSet::from([source.clone()]);
+        let mut worklist = vec![source.clone()];
+        while let Some(current) = worklist.pop() {
+            for sink in taint_analysis.single_step_taint(&current) {
+                if visited.insert(sink.clone()) {
+                    worklist.push(sink);
+                }
+            }
+        }
+        visited
     }
 
     fn validate_taint(src: &str, taint_map: &HashMap<&str, HashSet<String>>) {
@@ -243,11 +508,13 @@ mod tests {
         let taint_analysis = run_taint_analysis(&cfg);
         for (source, expected_sinks) in taint_map {
             let source = VariableName::from_name(source).with_version(0);
-            let sinks = taint_analysis
-                .multi_step_taint(&source)
-                .iter()
-                .map(|var| var.name().to_string())
-                .collect::<HashSet<_>>();
+            // On-demand queries must agree with the full multi-step taint.
+            let lazy_analysis = taint_analysis.clone();
+            let closure = taint_analysis.multi_step_taint(&source);
+            for sink in taint_analysis.definitions().map(|definition| definition.name()) {
+                assert_eq!(lazy_analysis.taints(&source, sink), closure.contains(sink));
+            }
+            let sinks = closure.iter().map(|var| var.name().to_string()).collect::<HashSet<_>>();
             assert_eq!(&sinks, expected_sinks);
         }
     }
@@ -0,0 +1,367 @@
+//! Summarizes what each template guarantees: the inputs and outputs of the
+//! template, the constraints relating them, and the signals assigned using
+//! `<--` (whose values are only restricted by the constraints). Constraints
+//! are listed together with the loops and branches containing them.
+use std::fmt::Write;
+
+use codespan_reporting::files::Files;
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+use serde_json::{json, Value};
+
+use program_structure::ast::{
+    AssignOp, Expression, ExpressionInfixOpcode, Meta, SignalType, Statement, VariableType,
+};
+use program_structure::file_definition::FileLibrary;
+use program_structure::printer::print_expression;
+use program_structure::template_data::TemplateData;
+
+/// An input or output signal of a template.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignalSummary {
+    /// The signal name, including dimensions (like `in[n][2]`).
+    pub name: String,
+    pub tags: Vec<String>,
+}
+
+/// A constraint or `<--` assignment of a template.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StatementSummary {
+    /// The simplified statement (like `out === a * b`).
+    pub statement: String,
+    /// The conditions of the loops and branches containing the statement,
+    /// from the outermost to the innermost (like `while i < n`).
+    pub context: Vec<String>,
+    /// The (1-based) line of the statement, if known.
+    pub line: Option<usize>,
+}
+
+/// The interface and constraints of a single template.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TemplateSummary {
+    pub name: String,
+    pub parameters: Vec<String>,
+    pub inputs: Vec<SignalSummary>,
+    pub outputs: Vec<SignalSummary>,
+    pub constraints: Vec<StatementSummary>,
+    pub hints: Vec<StatementSummary>,
+}
+
+/// Summarizes the given template. The file library is used to resolve line
+/// numbers.
+#[must_use]
+pub fn summarize_template(template: &TemplateData, file_library: &FileLibrary) -> TemplateSummary {
+    let mut summary = TemplateSummary {
+        name: template.get_name().to_string(),
+        parameters: template.get_name_of_params().clone(),
+        inputs: Vec::new(),
+        outputs: Vec::new(),
+        constraints: Vec::new(),
+        hints: Vec::new(),
+    };
+    let mut visitor = Visitor { summary: &mut summary, file_library, context: Vec::new() };
+    visitor.visit_statement(template.get_body());
+    summary
+}
+
+struct Visitor<'a> {
+    summary: &'a mut TemplateSummary,
+    file_library: &'a FileLibrary,
+    context: Vec<String>,
+}
+
+impl<'a> Visitor<'a> {
+    fn visit_statement(&mut self, stmt: &Statement) {
+        use Statement::*;
+        match stmt {
+            Block { stmts, .. } | InitializationBlock { initializations: stmts, .. } => {
+                stmts.iter().for_each(|stmt| self.visit_statement(stmt));
+            }
+            IfThenElse { cond, if_case, else_case, .. } => {
+                let cond = print_expression(&simplify(cond));
+                self.context.push(format!("if {cond}"));
+                self.visit_statement(if_case);
+                self.context.pop();
+                if let Some(else_case) = else_case {
+                    self.context.push(format!("if !({cond})"));
+                    self.visit_statement(else_case);
+                    self.context.pop();
+                }
+            }
+            While { cond, stmt, .. } => {
+                self.context.push(format!("while {}", print_expression(&simplify(cond))));
+                self.visit_statement(stmt);
+                self.context.pop();
+            }
+            Declaration {
+                xtype: VariableType::Signal(signal_type, _, tags),
+                name,
+                dimensions,
+                ..
+            } => {
+                let mut name = name.clone();
+                for size in dimensions {
+                    let _ = write!(name, "[{}]", print_expression(&simplify(size)));
+                }
+                let signal = SignalSummary { name, tags: tags.clone() };
+                match signal_type {
+                    SignalType::Input => self.summary.inputs.push(signal),
+                    SignalType::Output => self.summary.outputs.push(signal),
+                    SignalType::Intermediate => {}
+                }
+            }
+            Substitution { meta, var, access, op, rhe } if *op != AssignOp::AssignVar => {
+                let lhe = Expression::Variable {
+                    meta: meta.clone(),
+                    name: var.clone(),
+                    access: access.clone(),
+                };
+                let operator = if *op == AssignOp::AssignSignal { "<--" } else { "===" };
+                let statement = format!(
+                    "{} {operator} {}",
+                    print_expression(&lhe),
+                    print_expression(&simplify(rhe))
+                );
+                let summary = self.statement_summary(meta, statement);
+                if *op == AssignOp::AssignSignal {
+                    self.summary.hints.push(summary);
+                } else {
+                    self.summary.constraints.push(summary);
+                }
+            }
+            ConstraintEquality { meta, lhe, rhe } => {
+                let statement = format!(
+                    "{} === {}",
+                    print_expression(&simplify(lhe)),
+                    print_expression(&simplify(rhe))
+                );
+                let summary = self.statement_summary(meta, statement);
+                self.summary.constraints.push(summary);
+            }
+            _ => {}
+        }
+    }
+
+    fn statement_summary(&self, meta: &Meta, statement: String) -> StatementSummary {
+        let line = meta.file_id.and_then(|file_id| {
+            self.file_library
+                .to_storage()
+                .location(file_id, meta.start)
+                .map(|location| location.line_number)
+        });
+        StatementSummary { statement, context: self.context.clone(), line }
+    }
+}
+
+/// Simplifies the given expression by evaluating additions, subtractions,
+/// and multiplications of constants, and by removing additions of zero and
+/// multiplications by one.
+#[must_use]
+pub fn simplify(expr: &Expression) -> Expression {
+    use Expression::*;
+    use ExpressionInfixOpcode::*;
+    match expr {
+        InfixOp { meta, lhe, infix_op, rhe } => {
+            let lhe = simplify(lhe);
+            let rhe = simplify(rhe);
+            match (&lhe, infix_op, &rhe) {
+                (Number(_, lhv), Add, Number(_, rhv)) => Number(meta.clone(), lhv + rhv),
+                (Number(_, lhv), Sub, Number(_, rhv)) => Number(meta.clone(), lhv - rhv),
+                (Number(_, lhv), Mul, Number(_, rhv)) => Number(meta.clone(), lhv * rhv),
+                (Number(_, value), Add, other) | (other, Add | Sub, Number(_, value))
+                    if value.is_zero() =>
+                {
+                    other.clone()
+                }
+                (Number(_, value), Mul, other) | (other, Mul | Div, Number(_, value))
+                    if value.is_one() =>
+                {
+                    other.clone()
+                }
+                (Number(_, value), Mul, _) | (_, Mul, Number(_, value)) if value.is_zero() => {
+                    Number(meta.clone(), BigInt::zero())
+                }
+                _ => InfixOp {
+                    meta: meta.clone(),
+                    lhe: Box::new(lhe),
+                    infix_op: *infix_op,
+                    rhe: Box::new(rhe),
+                },
+            }
+        }
+        PrefixOp { meta, prefix_op, rhe } => {
+            PrefixOp { meta: meta.clone(), prefix_op: *prefix_op, rhe: Box::new(simplify(rhe)) }
+        }
+        InlineSwitchOp { meta, cond, if_true, if_false } => InlineSwitchOp {
+            meta: meta.clone(),
+            cond: Box::new(simplify(cond)),
+            if_true: Box::new(simplify(if_true)),
+            if_false: Box::new(simplify(if_false)),
+        },
+        Call { meta, id, args } => {
+            Call { meta: meta.clone(), id: id.clone(), args: args.iter().map(simplify).collect() }
+        }
+        ArrayInLine { meta, values } => {
+            ArrayInLine { meta: meta.clone(), values: values.iter().map(simplify).collect() }
+        }
+        expr => expr.clone(),
+    }
+}
+
+/// Renders the given summaries as Markdown, with one section per template.
+#[must_use]
+pub fn to_markdown(summaries: &[TemplateSummary]) -> String {
+    let signals = |signals: &[SignalSummary]| {
+        if signals.is_empty() {
+            return "-".to_string();
+        }
+        signals
+            .iter()
+            .map(|signal| match signal.tags.as_slice() {
+                [] => format!("`{}`", signal.name),
+                tags => format!("`{}` {{{}}}", signal.name, tags.join(", ")),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let statements = |markdown: &mut String, title: &str, statements: &[StatementSummary]| {
+        let _ = writeln!(markdown, "\n{title}\n");
+        if statements.is_empty() {
+            let _ = writeln!(markdown, "None.");
+            return;
+        }
+        let _ = writeln!(markdown, "| Line | Statement | Context |");
+        let _ = writeln!(markdown, "|------|-----------|---------|");
+        for statement in statements {
+            let line = statement.line.map(|line| line.to_string()).unwrap_or_default();
+            let context = statement
+                .context
+                .iter()
+                .map(|context| format!("`{context}`"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let _ = writeln!(
+                markdown,
+                "| {line} | `{}` | {context} |",
+                statement.statement.replace('|', "\\|")
+            );
+        }
+    };
+    let mut markdown = String::new();
+    for (index, summary) in summaries.iter().enumerate() {
+        if index > 0 {
+            markdown.push('\n');
+        }
+        let _ = writeln!(markdown, "## `{}({})`\n", summary.name, summary.parameters.join(", "));
+        let _ = writeln!(markdown, "- Inputs: {}", signals(&summary.inputs));
+        let _ = writeln!(markdown, "- Outputs: {}", signals(&summary.outputs));
+        statements(&mut markdown, "### Constraints", &summary.constraints);
+        statements(&mut markdown, "### Hints (`<--`)", &summary.hints);
+    }
+    markdown
+}
+
+/// Returns the given summaries as a JSON array.
+#[must_use]
+pub fn to_json(summaries: &[TemplateSummary]) -> Value {
+    let signals = |signals: &[SignalSummary]| {
+        signals
+            .iter()
+            .map(|signal| json!({ "name": signal.name, "tags": signal.tags }))
+            .collect::<Vec<_>>()
+    };
+    let statements = |statements: &[StatementSummary]| {
+        statements
+            .iter()
+            .map(|statement| {
+                json!({
+                    "statement": statement.statement,
+                    "context": statement.context,
+                    "line": statement.line,
+                })
+            })
+            .collect::<Vec<_>>()
+    };
+    summaries
+        .iter()
+        .map(|summary| {
+            json!({
+                "name": summary.name,
+                "parameters": summary.parameters,
+                "inputs": signals(&summary.inputs),
+                "outputs": signals(&summary.outputs),
+                "constraints": statements(&summary.constraints),
+                "hints": statements(&summary.hints),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{parse_source, ParseResult};
+
+    use super::*;
+
+    #[test]
+    fn test_summarize_template() {
+        let src = r#"
+            template Num2Bits(n) {
+                signal input {maxbits} in;
+                signal output out[n];
+                var lc = 0;
+                var e2 = 1;
+                for (var i = 0; i < n; i++) {
+                    out[i] <-- (in >> i) & 1;
+                    out[i] * (out[i] - 1) === 0;
+                    lc += out[i] * e2;
+                    e2 = e2 + e2;
+                }
+                lc * 1 === in + 0;
+            }
+        "#;
+        let ParseResult::Library(library, _) = parse_source("main.circom", src, &[], &[], "2.1.0")
+        else {
+            panic!("failed to parse library");
+        };
+        let template = library.templates.get("Num2Bits").unwrap();
+        let summary = summarize_template(template, &library.file_library);
+        assert_eq!(summary.parameters, ["n"]);
+        assert_eq!(
+            summary.inputs,
+            [SignalSummary { name: "in".to_string(), tags: vec!["maxbits".to_string()] }]
+        );
+        assert_eq!(summary.outputs[0].name, "out[n]");
+        let constraints = summary
+            .constraints
+            .iter()
+            .map(|constraint| constraint.statement.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(constraints, ["out[i] * (out[i] - 1) === 0", "lc === in"]);
+        assert_eq!(summary.constraints[0].context, ["while i < n"]);
+        assert_eq!(summary.constraints[0].line, Some(9));
+        assert!(summary.constraints[1].context.is_empty());
+        assert_eq!(summary.hints[0].statement, "out[i] <-- in >> i & 1");
+
+        let markdown = to_markdown(std::slice::from_ref(&summary));
+        assert!(markdown.starts_with("## `Num2Bits(n)`\n\n- Inputs: `in` {maxbits}\n"));
+        assert!(markdown.contains("| 9 | `out[i] * (out[i] - 1) === 0` | `while i < n` |"));
+        let json = to_json(&[summary]);
+        assert_eq!(json[0]["hints"][0]["line"], 8);
+    }
+
+    #[test]
+    fn test_simplify() {
+        let ParseResult::Library(library, _) = parse_source(
+            "main.circom",
+            "template T() { signal input a; signal output b; b <== (2 * 3 - 6) + a * 1; }",
+            &[],
+            &[],
+            "2.1.0",
+        ) else {
+            panic!("failed to parse library");
+        };
+        let summary = summarize_template(&library.templates["T"], &library.file_library);
+        assert_eq!(summary.constraints[0].statement, "b === a");
+    }
+}
@@ -0,0 +1,391 @@
+use log::debug;
+use std::collections::{HashMap, HashSet};
+
+use program_structure::cfg::{Cfg, DefinitionType};
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::declarations::Declaration;
+use program_structure::ir::variable_meta::VariableMeta;
+use program_structure::ir::*;
+
+use crate::constraint_analysis::{run_constraint_analysis, run_signal_constraint_analysis};
+
+/// A row of the trust summary of a template.
+struct OutputSummary {
+    output_name: VariableName,
+    inputs: Vec<VariableName>,
+    hints: Vec<VariableName>,
+}
+
+pub struct TrustBoundarySummary {
+    template_name: String,
+    outputs: Vec<OutputSummary>,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl TrustBoundarySummary {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::info(
+            format!(
+                "Trust summary for the output signals of the template `{}`.",
+                self.template_name
+            ),
+            ReportCode::TrustBoundarySummary,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                format!("The template `{}` is defined here.", self.template_name),
+            );
+        }
+        let names = |names: &[VariableName]| {
+            if names.is_empty() {
+                "-".to_string()
+            } else {
+                names.iter().map(|name| format!("`{name}`")).collect::<Vec<_>>().join(", ")
+            }
+        };
+        let rows = self
+            .outputs
+            .iter()
+            .map(|output| {
+                [format!("`{}`", output.output_name), names(&output.inputs), names(&output.hints)]
+            })
+            .collect::<Vec<_>>();
+        let header = [
+            "Output".to_string(),
+            "Constrained by inputs".to_string(),
+            "Unbalanced hints".to_string(),
+        ];
+        let widths = (0..header.len())
+            .map(|column| {
+                rows.iter().chain([&header]).map(|row| row[column].len()).max().unwrap_or(0)
+            })
+            .collect::<Vec<_>>();
+        let table = [&header]
+            .into_iter()
+            .chain(rows.iter())
+            .map(|row| {
+                row.iter()
+                    .zip(widths.iter())
+                    .map(|(cell, width)| format!("{cell:width$}"))
+                    .collect::<Vec<_>>()
+                    .join(" | ")
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        report.add_note(table);
+        report.add_note(
+            "Unbalanced hints are signals assigned using `<--` which the output depends on, but which are never constrained together with an input signal or a subcomponent.".to_string()
+        );
+        report
+    }
+}
+
+pub struct UnbalancedHintWarning {
+    output_name: VariableName,
+    hint_name: VariableName,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+    hint_file_id: Option<FileID>,
+    hint_file_location: FileLocation,
+}
+
+impl UnbalancedHintWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            format!(
+                "The output signal `{}` depends on the signal `{}`, which is assigned using `<--` but never constrained by the inputs of the template.",
+                self.output_name, self.hint_name
+            ),
+            ReportCode::UnbalancedHint,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                format!("The output signal `{}` is declared here.", self.output_name),
+            );
+        }
+        if let Some(file_id) = self.hint_file_id {
+            report.add_secondary(
+                self.hint_file_location,
+                file_id,
+                Some(format!("The signal `{}` is assigned here.", self.hint_name)),
+            );
+        }
+        report.add_note(format!(
+            "Since `{}` never occurs in a constraint together with an input signal or a subcomponent, a malicious prover can choose its value freely, and the value flows into the output `{}`.",
+            self.hint_name, self.output_name
+        ));
+        report
+    }
+}
+
+/// This audit-oriented analysis pass summarizes the trust boundary of each
+/// template. For each output signal, it lists the input signals that the
+/// output is constrained by (in one or more steps), and the hints (signals
+/// assigned using `<--`) that the output depends on, which are not balanced by
+/// a constraint relating them to an input signal or a subcomponent. A summary
+/// table is generated for each template, and each output depending on an
+/// unbalanced hint is reported separately.
+pub fn find_trust_boundary(cfg: &Cfg) -> ReportCollection {
+    debug!("running trust boundary analysis pass");
+    let mut reports = ReportCollection::new();
+    if !matches!(cfg.definition_type(), DefinitionType::Template) {
+        return reports;
+    }
+    let outputs = signals(cfg, SignalType::Output);
+    if outputs.is_empty() {
+        return reports;
+    }
+    let inputs = signals(cfg, SignalType::Input)
+        .into_iter()
+        .map(|(name, _)| name.clone())
+        .collect::<HashSet<_>>();
+    let constraint_analysis = run_constraint_analysis(cfg);
+
+    let mut hints = HashMap::new();
+    let mut dependencies = HashMap::<VariableName, HashSet<VariableName>>::new();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            let Statement::Substitution { meta, var, op, .. } = stmt else {
+                continue;
+            };
+            if matches!(op, AssignOp::AssignSignal) {
+                hints.entry(var.clone()).or_insert(meta);
+            }
+            dependencies
+                .entry(var.clone())
+                .or_default()
+                .extend(stmt.variables_read().map(|var_use| var_use.name().clone()));
+        }
+    }
+    let constraints = run_signal_constraint_analysis(cfg);
+    let unbalanced = hints
+        .keys()
+        .filter(|hint| !is_balanced(cfg, &constraints, &inputs, hint))
+        .cloned()
+        .collect::<HashSet<_>>();
+
+    let mut summaries = Vec::new();
+    for (output_name, declaration) in outputs {
+        let mut constrained_by = constraint_analysis
+            .multi_step_constraint(output_name)
+            .into_iter()
+            .filter(|name| inputs.contains(name))
+            .collect::<Vec<_>>();
+        constrained_by.sort_by_key(ToString::to_string);
+        let mut output_hints = dependency_cone(&dependencies, output_name)
+            .into_iter()
+            .filter(|name| unbalanced.contains(name))
+            .collect::<Vec<_>>();
+        output_hints.sort_by_key(ToString::to_string);
+        for hint_name in &output_hints {
+            // Outputs which never occur in a constraint are reported by the
+            // unconstrained output analysis pass.
+            if hint_name == output_name
+                && !constraint_analysis.constrained_variables().contains(output_name)
+            {
+                continue;
+            }
+            reports.push(
+                UnbalancedHintWarning {
+                    output_name: output_name.clone(),
+                    hint_name: hint_name.clone(),
+                    file_id: declaration.file_id(),
+                    file_location: declaration.file_location(),
+                    hint_file_id: hints[hint_name].file_id(),
+                    hint_file_location: hints[hint_name].file_location(),
+                }
+                .into_report(),
+            );
+        }
+        summaries.push(OutputSummary {
+            output_name: output_name.clone(),
+            inputs: constrained_by,
+            hints: output_hints,
+        });
+    }
+    reports.push(
+        TrustBoundarySummary {
+            template_name: cfg.name().to_string(),
+            outputs: summaries,
+            file_id: *cfg.parameters().file_id(),
+            file_location: cfg.parameters().file_location().clone(),
+        }
+        .into_report(),
+    );
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+/// Returns the signals of the given type, sorted by declaration order.
+fn signals(cfg: &Cfg, signal_type: SignalType) -> Vec<(&VariableName, &Declaration)> {
+    let mut signals = cfg
+        .declarations()
+        .iter()
+        .filter(|(_, declaration)| {
+            matches!(declaration.variable_type(), VariableType::Signal(t, _) if *t == signal_type)
+        })
+        .collect::<Vec<_>>();
+    signals.sort_by_key(|(_, declaration)| declaration.file_location().start);
+    signals
+}
+
+/// A hint is balanced if it is constrained (in one or more steps) together
+/// with an input signal or a subcomponent.
+fn is_balanced(
+    cfg: &Cfg,
+    constraints: &HashMap<VariableName, HashSet<VariableName>>,
+    inputs: &HashSet<VariableName>,
+    hint: &VariableName,
+) -> bool {
+    let mut visited = HashSet::from([hint.clone()]);
+    let mut worklist = vec![hint.clone()];
+    while let Some(var) = worklist.pop() {
+        if inputs.contains(&var) || matches!(cfg.get_type(&var), Some(VariableType::Component)) {
+            return true;
+        }
+        for sink in constraints.get(&var).into_iter().flatten() {
+            if visited.insert(sink.clone()) {
+                worklist.push(sink.clone());
+            }
+        }
+    }
+    false
+}
+
+/// Returns the variables that the given variable depends on through
+/// assignments, including the variable itself.
+fn dependency_cone(
+    dependencies: &HashMap<VariableName, HashSet<VariableName>>,
+    var: &VariableName,
+) -> HashSet<VariableName> {
+    let mut cone = HashSet::from([var.clone()]);
+    let mut worklist = vec![var.clone()];
+    while let Some(var) = worklist.pop() {
+        for dependency in dependencies.get(&var).into_iter().flatten() {
+            if cone.insert(dependency.clone()) {
+                worklist.push(dependency.clone());
+            }
+        }
+    }
+    cone
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_trust_boundary() {
+        let src = r#"
+            template IsZero() {
+                signal input in;
+                signal output out;
+                signal inv;
+                inv <-- in != 0 ? 1 / in : 0;
+                out <== -in * inv + 1;
+                in * out === 0;
+            }
+        "#;
+        validate_reports(src, &[ReportCode::TrustBoundarySummary]);
+
+        let src = r#"
+            template T() {
+                signal input a;
+                signal input b;
+                signal output out;
+                signal tmp;
+                tmp <-- a / b;
+                out <== 2 * tmp;
+            }
+        "#;
+        validate_reports(src, &[ReportCode::UnbalancedHint, ReportCode::TrustBoundarySummary]);
+
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                out <-- in * in;
+            }
+        "#;
+        validate_reports(src, &[ReportCode::TrustBoundarySummary]);
+
+        // Hints constrained through a variable accumulator are balanced.
+        let src = r#"
+            template Num2Bits(n) {
+                signal input in;
+                signal output out[n];
+                var lc1 = 0;
+                var e2 = 1;
+                for (var i = 0; i < n; i++) {
+                    out[i] <-- (in >> i) & 1;
+                    out[i] * (out[i] - 1) === 0;
+                    lc1 += out[i] * e2;
+                    e2 = e2 + e2;
+                }
+                lc1 === in;
+            }
+        "#;
+        validate_reports(src, &[ReportCode::TrustBoundarySummary]);
+
+        let src = r#"
+            template Bits2Num(n) {
+                signal input in[n];
+                signal output out;
+                var lc1 = 0;
+                var e2 = 1;
+                for (var i = 0; i < n; i++) {
+                    lc1 += in[i] * e2;
+                    e2 = e2 + e2;
+                }
+                lc1 ==> out;
+            }
+        "#;
+        validate_reports(src, &[ReportCode::TrustBoundarySummary]);
+
+        let src = r#"
+            template Bits2Num(n) {
+                signal input in[n];
+                signal output out;
+                var lc1 = 0;
+                var e2 = 1;
+                for (var i = 0; i < n; i++) {
+                    lc1 += in[i] * e2;
+                    e2 = e2 + e2;
+                }
+                out <-- lc1;
+                out === lc1;
+            }
+        "#;
+        validate_reports(src, &[ReportCode::TrustBoundarySummary]);
+    }
+
+    fn validate_reports(src: &str, expected_codes: &[ReportCode]) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_trust_boundary(&cfg);
+        let ids = reports.iter().map(|report| report.id()).collect::<Vec<_>>();
+        let expected_ids = expected_codes.iter().map(|code| code.id()).collect::<Vec<_>>();
+        assert_eq!(ids, expected_ids);
+    }
+}
@@ -0,0 +1,236 @@
+use log::debug;
+use num_bigint::BigInt;
+use std::collections::HashMap;
+
+use program_structure::cfg::{Cfg, DefinitionType};
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::variable_meta::VariableMeta;
+use program_structure::ir::*;
+
+use crate::circomlib_models::{get_signal_name, ComponentModels, Precondition};
+
+/// Circomlib templates used to range check signals.
+const RANGE_CHECKS: &[&str] = &["Num2Bits", "Num2Bits_strict"];
+
+pub struct UncheckedComparatorInputWarning {
+    signal_name: String,
+    template_name: String,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl UncheckedComparatorInputWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            format!(
+                "The signal `{}` is passed to the Circomlib template `{}`, but is never range checked.",
+                self.signal_name, self.template_name
+            ),
+            ReportCode::UncheckedComparatorInput,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                format!(
+                    "The signal `{}` is passed to `{}` here.",
+                    self.signal_name, self.template_name
+                ),
+            );
+        }
+        report.add_note(format!(
+            "The template `{}(n)` assumes that its inputs are at most `n` bits. If the inputs are not range checked (e.g. using `Num2Bits(n)`), a malicious prover may be able to make the comparison return the wrong result.",
+            self.template_name
+        ));
+        report
+    }
+}
+
+/// A comparison of a signal against the bit width required by a component.
+struct ComparatorInput {
+    meta: Meta,
+    template_name: &'static str,
+    bit_width: Option<BigInt>,
+    signals: Vec<VariableName>,
+}
+
+/// Circomlib comparators like `LessThan(n)` are only sound if the inputs are
+/// at most `n` bits. If an input is not range checked, a malicious prover may
+/// be able to prove an incorrect comparison result. This analysis pass reports
+/// signals that are passed to comparators without being range checked using
+/// `Num2Bits` (or `Num2Bits_strict`) with a sufficiently small bit width.
+///
+/// Note that the range check may be performed by the caller, in which case
+/// this is a false positive.
+pub fn find_unchecked_comparator_inputs(cfg: &Cfg) -> ReportCollection {
+    use DefinitionType::*;
+    if matches!(cfg.definition_type(), Function | CustomTemplate) {
+        // Exit early if this is a function or custom template.
+        return ReportCollection::new();
+    }
+    debug!("running unchecked comparator input analysis pass");
+    let models = ComponentModels::new(cfg);
+    let mut range_checks = HashMap::new();
+    let mut comparator_inputs = Vec::new();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            visit_statement(stmt, &models, &mut range_checks, &mut comparator_inputs);
+        }
+    }
+    let mut reports = ReportCollection::new();
+    for input in comparator_inputs {
+        for signal_name in &input.signals {
+            let is_checked = match (range_checks.get(signal_name), &input.bit_width) {
+                // If either bit width is unknown we assume that the range check
+                // is sufficient.
+                (Some(Some(range)), Some(bit_width)) => range <= bit_width,
+                (Some(_), _) => true,
+                (None, _) => false,
+            };
+            if !is_checked {
+                reports.push(build_report(&input.meta, signal_name, input.template_name));
+            }
+        }
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+/// Records signals flowing into range checks and comparators. Range checks are
+/// recorded together with the (smallest) bit width, if it is known.
+fn visit_statement(
+    stmt: &Statement,
+    models: &ComponentModels,
+    range_checks: &mut HashMap<VariableName, Option<BigInt>>,
+    comparator_inputs: &mut Vec<ComparatorInput>,
+) {
+    use AssignOp::*;
+    use Expression::*;
+    use Statement::*;
+    // Assignments to component inputs are lifted to updates.
+    let Substitution { meta, var, op: AssignSignal | AssignConstraintSignal, rhe, .. } = stmt
+    else {
+        return;
+    };
+    let Update { access, rhe: value, .. } = rhe else {
+        return;
+    };
+    let (Some(model), Some(input)) = (models.get(var), get_signal_name(access)) else {
+        return;
+    };
+    let mut signals = value.signals_read().iter().map(|var| var.name().clone()).collect::<Vec<_>>();
+    signals.sort_by_key(ToString::to_string);
+    signals.dedup();
+
+    if RANGE_CHECKS.contains(&model.name) && input == "in" {
+        let bit_width = models.parameter(var, 0);
+        for signal_name in signals {
+            let range = range_checks.entry(signal_name).or_insert_with(|| bit_width.cloned());
+            *range = match (range.take(), bit_width) {
+                (Some(range), Some(bit_width)) => Some(range.min(bit_width.clone())),
+                _ => None,
+            };
+        }
+        return;
+    }
+    for precondition in model.preconditions {
+        match precondition {
+            Precondition::InputBitWidth { input: name, parameter } if *name == input => {
+                comparator_inputs.push(ComparatorInput {
+                    meta: meta.clone(),
+                    template_name: model.name,
+                    bit_width: models.parameter(var, *parameter).cloned(),
+                    signals: signals.clone(),
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+fn build_report(meta: &Meta, signal_name: &VariableName, template_name: &str) -> Report {
+    UncheckedComparatorInputWarning {
+        signal_name: signal_name.to_string(),
+        template_name: template_name.to_string(),
+        file_id: meta.file_id(),
+        file_location: meta.file_location(),
+    }
+    .into_report()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_unchecked_comparator_inputs() {
+        let src = r#"
+            template T() {
+                signal input a;
+                signal input b;
+                signal output out;
+                component lt = LessThan(64);
+
+                lt.in[0] <== a;
+                lt.in[1] <== b;
+                out <== lt.out;
+            }
+        "#;
+        validate_reports(src, 2);
+
+        let src = r#"
+            template T() {
+                signal input a;
+                signal input b;
+                signal output out;
+                component a_bits = Num2Bits(64);
+                component b_bits = Num2Bits(128);
+                component lt = LessThan(64);
+
+                a_bits.in <== a;
+                b_bits.in <== b;
+                lt.in[0] <== a;
+                lt.in[1] <== b;
+                out <== lt.out;
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            template T(n) {
+                signal input in[2];
+                signal output out;
+                component n2b = Num2Bits(n);
+                component lt = GreaterThan(n);
+
+                n2b.in <== in[0];
+                lt.in[0] <== in[0];
+                lt.in[1] <== 1000;
+                out <== lt.out;
+            }
+        "#;
+        validate_reports(src, 0);
+    }
+
+    fn validate_reports(src: &str, expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_unchecked_comparator_inputs(&cfg);
+
+        assert_eq!(reports.len(), expected_len);
+    }
+}
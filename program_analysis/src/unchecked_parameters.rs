@@ -0,0 +1,214 @@
+use log::debug;
+use std::collections::{HashMap, HashSet};
+
+use program_structure::cfg::{Cfg, DefinitionType};
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::variable_meta::VariableMeta;
+use program_structure::ir::*;
+
+use crate::loop_bounds::find_loops;
+
+#[derive(Clone, Copy)]
+enum ParameterUse {
+    ArrayDimension,
+    LoopBound,
+}
+
+impl ParameterUse {
+    fn description(&self) -> &'static str {
+        match self {
+            ParameterUse::ArrayDimension => "an array dimension",
+            ParameterUse::LoopBound => "a loop bound",
+        }
+    }
+}
+
+pub struct UncheckedParameterWarning {
+    param: VariableName,
+    param_use: ParameterUse,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl UncheckedParameterWarning {
+    pub fn into_report(self) -> Report {
+        let param = self.param.without_version();
+        let mut report = Report::warning(
+            format!(
+                "The template parameter `{param}` is used as {}, but is not restricted by an assert.",
+                self.param_use.description()
+            ),
+            ReportCode::UncheckedTemplateParameter,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                format!(
+                    "The parameter `{param}` is used as {} here.",
+                    self.param_use.description()
+                ),
+            );
+        }
+        report.add_note(format!(
+            "If the template is instantiated with `{param} = 0` (or a value which is negative when interpreted as a field element), the resulting circuit is silently degenerate."
+        ));
+        report.add_help(format!(
+            "Consider restricting the parameter using an assert like `assert({param} > 0);`."
+        ));
+        report
+    }
+}
+
+/// This analysis pass reports template parameters which occur in an array
+/// dimension or a loop condition, but which are not read by any assert
+/// statement in the template. Only direct uses of the parameter are
+/// considered.
+pub fn find_unchecked_parameters(cfg: &Cfg) -> ReportCollection {
+    debug!("running unchecked template parameter analysis pass");
+    let mut reports = ReportCollection::new();
+    if !matches!(cfg.definition_type(), DefinitionType::Template | DefinitionType::CustomTemplate) {
+        return reports;
+    }
+    let params = cfg.parameters().iter().map(VariableName::without_version).collect::<HashSet<_>>();
+    // Collect the variables read by assert statements.
+    let mut asserted = HashSet::new();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            if let Statement::Assert { arg, .. } = stmt {
+                asserted.extend(arg.locals_read().iter().map(|var| var.name().without_version()));
+            }
+        }
+    }
+    // Find the first use of each unchecked parameter.
+    let mut uses = HashMap::new();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            if let Statement::Declaration { dimensions, .. } = stmt {
+                for size in dimensions {
+                    let param_use = ParameterUse::ArrayDimension;
+                    visit_expression(size, param_use, &params, &asserted, &mut uses);
+                }
+            }
+        }
+    }
+    for cfg_loop in find_loops(cfg) {
+        visit_expression(cfg_loop.cond, ParameterUse::LoopBound, &params, &asserted, &mut uses);
+    }
+    let mut uses = uses.into_iter().collect::<Vec<_>>();
+    uses.sort_by_key(|(_, (_, meta))| meta.file_location().start);
+    for (param, (param_use, meta)) in uses {
+        reports.push(build_report(param, param_use, &meta));
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+fn visit_expression(
+    expr: &Expression,
+    param_use: ParameterUse,
+    params: &HashSet<VariableName>,
+    asserted: &HashSet<VariableName>,
+    uses: &mut HashMap<VariableName, (ParameterUse, Meta)>,
+) {
+    for var in expr.locals_read() {
+        let name = var.name().without_version();
+        if !params.contains(&name) || asserted.contains(&name) {
+            continue;
+        }
+        let meta = expr.meta();
+        match uses.get(&name) {
+            Some((_, first_meta))
+                if first_meta.file_location().start <= meta.file_location().start => {}
+            _ => {
+                uses.insert(name, (param_use, meta.clone()));
+            }
+        }
+    }
+}
+
+fn build_report(param: VariableName, param_use: ParameterUse, meta: &Meta) -> Report {
+    UncheckedParameterWarning {
+        param,
+        param_use,
+        file_id: meta.file_id(),
+        file_location: meta.file_location(),
+    }
+    .into_report()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_unchecked_parameters() {
+        let src = r#"
+            template T(n, m) {
+                signal input in[n];
+                signal output out;
+                var sum = 0;
+                for (var i = 0; i < m; i++) {
+                    sum += in[i];
+                }
+                out <== sum;
+            }
+        "#;
+        validate_reports(src, 2);
+
+        // Each parameter is reported once.
+        let src = r#"
+            template T(n) {
+                signal input in[n];
+                signal output out[n];
+                for (var i = 0; i < n; i++) {
+                    out[i] <== in[i];
+                }
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            template T(n) {
+                assert(n > 0);
+                signal input in[n];
+                signal output out[n];
+                for (var i = 0; i < n; i++) {
+                    out[i] <== in[i];
+                }
+            }
+        "#;
+        validate_reports(src, 0);
+
+        // Parameters which are not used as dimensions or loop bounds are ignored.
+        let src = r#"
+            template T(k) {
+                signal input in;
+                signal output out;
+                out <== k * in;
+            }
+        "#;
+        validate_reports(src, 0);
+    }
+
+    fn validate_reports(src: &str, expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_unchecked_parameters(&cfg);
+        assert_eq!(reports.len(), expected_len);
+    }
+}
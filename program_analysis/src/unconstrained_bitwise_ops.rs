@@ -0,0 +1,277 @@
+use log::debug;
+use num_traits::{One, Zero};
+use std::collections::HashSet;
+
+use program_structure::cfg::Cfg;
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::value_meta::{ValueMeta, ValueReduction};
+use program_structure::ir::variable_meta::VariableMeta;
+use program_structure::ir::*;
+
+pub struct UnconstrainedBitwiseOperationWarning {
+    signal_name: VariableName,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+    op_file_id: Option<FileID>,
+    op_file_location: FileLocation,
+}
+
+impl UnconstrainedBitwiseOperationWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            format!(
+                "The signal `{}` is assigned the result of a bitwise operation on signals, but the bits of `{}` are never constrained.",
+                self.signal_name, self.signal_name
+            ),
+            ReportCode::UnconstrainedBitwiseOperation,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                format!("The signal `{}` is assigned here.", self.signal_name),
+            );
+        }
+        if let Some(file_id) = self.op_file_id {
+            report.add_secondary(
+                self.op_file_location,
+                file_id,
+                Some("Bitwise operation on signals computed here.".to_string()),
+            );
+        }
+        report.add_note(
+            "Bitwise operations are not reflected in the constraint system, which means that the prover may assign any value to the signal.".to_string()
+        );
+        report.add_help(format!(
+            "Consider constraining `{}` to be binary using `{} * ({} - 1) === 0`, or using a circomlib template like `Num2Bits` to decompose the input.",
+            self.signal_name, self.signal_name, self.signal_name
+        ));
+        report
+    }
+}
+
+/// Bitwise operations like `&`, `|`, `^`, `<<`, and `>>` cannot be expressed
+/// using quadratic constraints, and are typically used in signal assignments
+/// (using `<--`) to compute a bit decomposition of a signal. The result must
+/// then be constrained separately. This analysis pass reports signals assigned
+/// the result of a bitwise operation on signals using `<--`, if the assigned
+/// signal is never constrained to be binary and is never passed to a
+/// component (which may constrain it).
+pub fn find_unconstrained_bitwise_ops(cfg: &Cfg) -> ReportCollection {
+    debug!("running unconstrained bitwise operation analysis pass");
+    let mut constrained = HashSet::new();
+    let mut assignments = Vec::new();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            match stmt {
+                Statement::Substitution { meta, var, op: AssignOp::AssignSignal, rhe } => {
+                    let rhe = match rhe {
+                        Expression::Update { rhe, .. } => rhe,
+                        rhe => rhe,
+                    };
+                    if let Some(op_meta) = find_bitwise_op(rhe) {
+                        assignments.push((var.without_version(), meta, op_meta));
+                    }
+                }
+                Statement::Substitution { meta, .. } if meta.type_knowledge().is_component() => {
+                    // Signals passed to a component may be constrained by the
+                    // component.
+                    constrained.extend(
+                        stmt.signals_read().iter().map(|var_use| var_use.name().without_version()),
+                    );
+                }
+                Statement::ConstraintEquality { lhe, rhe, .. } => {
+                    constrained.extend(binary_constraint(lhe, rhe));
+                    constrained.extend(binary_constraint(rhe, lhe));
+                }
+                _ => {}
+            }
+        }
+    }
+    let mut reports = ReportCollection::new();
+    let mut reported = HashSet::new();
+    for (signal_name, meta, op_meta) in assignments {
+        if !constrained.contains(&signal_name) && reported.insert(signal_name.clone()) {
+            reports.push(build_report(signal_name, meta, op_meta));
+        }
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+/// Returns the first bitwise operation with an operand reading a signal.
+pub(crate) fn find_bitwise_op(expr: &Expression) -> Option<&Meta> {
+    use Expression::*;
+    use ExpressionInfixOpcode::*;
+    match expr {
+        InfixOp { meta, lhe, infix_op: BitAnd | BitOr | BitXor | ShiftL | ShiftR, rhe }
+            if !lhe.signals_read().is_empty() || !rhe.signals_read().is_empty() =>
+        {
+            Some(meta)
+        }
+        InfixOp { lhe, rhe, .. } => find_bitwise_op(lhe).or_else(|| find_bitwise_op(rhe)),
+        PrefixOp { rhe, .. } => find_bitwise_op(rhe),
+        SwitchOp { cond, if_true, if_false, .. } => find_bitwise_op(cond)
+            .or_else(|| find_bitwise_op(if_true))
+            .or_else(|| find_bitwise_op(if_false)),
+        Call { args, .. } => args.iter().find_map(find_bitwise_op),
+        InlineArray { values, .. } => values.iter().find_map(find_bitwise_op),
+        Access { access, .. } | Update { access, .. } => {
+            access.iter().find_map(|access| match access {
+                AccessType::ArrayAccess(index) => find_bitwise_op(index),
+                AccessType::ComponentAccess(_) => None,
+            })
+        }
+        Variable { .. } | Number(..) | Phi { .. } => None,
+    }
+}
+
+/// Returns the signal `x` if the constraint `lhe === rhe` is of the form
+/// `x * (x - 1) === 0`, `x * (1 - x) === 0`, or `x * x === x`.
+pub(crate) fn binary_constraint(lhe: &Expression, rhe: &Expression) -> Option<VariableName> {
+    use ExpressionInfixOpcode::*;
+    let Expression::InfixOp { lhe: first, infix_op: Mul, rhe: second, .. } = lhe else {
+        return None;
+    };
+    if is_zero(rhe) {
+        for (x, y) in [(first, second), (second, first)] {
+            let Some(name) = signal_name(x) else {
+                continue;
+            };
+            if let Expression::InfixOp { lhe: a, infix_op: Sub, rhe: b, .. } = y.as_ref() {
+                if (signal_name(a) == Some(name.clone()) && is_one(b))
+                    || (is_one(a) && signal_name(b) == Some(name.clone()))
+                {
+                    return Some(name);
+                }
+            }
+        }
+        None
+    } else {
+        let name = signal_name(rhe)?;
+        (signal_name(first) == Some(name.clone()) && signal_name(second) == Some(name.clone()))
+            .then_some(name)
+    }
+}
+
+/// Returns the name of the signal if the expression is a signal (or signal
+/// array element).
+fn signal_name(expr: &Expression) -> Option<VariableName> {
+    match expr {
+        Expression::Variable { meta, name } | Expression::Access { meta, var: name, .. }
+            if meta.type_knowledge().is_signal() =>
+        {
+            Some(name.without_version())
+        }
+        _ => None,
+    }
+}
+
+fn is_zero(expr: &Expression) -> bool {
+    matches!(expr.value(), Some(ValueReduction::FieldElement { value }) if value.is_zero())
+}
+
+fn is_one(expr: &Expression) -> bool {
+    matches!(expr.value(), Some(ValueReduction::FieldElement { value }) if value.is_one())
+}
+
+fn build_report(signal_name: VariableName, meta: &Meta, op_meta: &Meta) -> Report {
+    UnconstrainedBitwiseOperationWarning {
+        signal_name,
+        file_id: meta.file_id(),
+        file_location: meta.file_location(),
+        op_file_id: op_meta.file_id(),
+        op_file_location: op_meta.file_location(),
+    }
+    .into_report()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_unconstrained_bitwise_ops() {
+        let src = r#"
+            template Num2Bits(n) {
+                signal input in;
+                signal output out[n];
+                var lc1 = 0;
+                var e2 = 1;
+                for (var i = 0; i < n; i++) {
+                    out[i] <-- (in >> i) & 1;
+                    out[i] * (out[i] - 1) === 0;
+                    lc1 += out[i] * e2;
+                    e2 = e2 + e2;
+                }
+                lc1 === in;
+            }
+        "#;
+        validate_reports(src, 0);
+
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                out <-- in & 1;
+                (1 - out) * out === 0;
+            }
+        "#;
+        validate_reports(src, 0);
+
+        let src = r#"
+            template T() {
+                signal input a;
+                signal input b;
+                signal output out;
+                out <-- a ^ b;
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            template T(n) {
+                signal input in;
+                signal output out[n];
+                for (var i = 0; i < n; i++) {
+                    out[i] <-- (in >> i) & 1;
+                }
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                signal bits;
+                bits <-- in >> 1;
+                component n2b = Num2Bits(8);
+                n2b.in <== bits;
+                out <== n2b.out[0];
+            }
+        "#;
+        validate_reports(src, 0);
+    }
+
+    fn validate_reports(src: &str, expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_unconstrained_bitwise_ops(&cfg);
+        assert_eq!(reports.len(), expected_len);
+    }
+}
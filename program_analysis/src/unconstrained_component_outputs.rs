@@ -0,0 +1,415 @@
+use log::debug;
+use std::collections::{HashMap, HashSet};
+
+use program_structure::cfg::{Cfg, DefinitionType};
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::variable_meta::VariableMeta;
+use program_structure::ir::*;
+
+use crate::circomlib_models::{get_signal_name, ComponentModels};
+
+pub struct UnconstrainedComponentOutputWarning {
+    component_name: String,
+    signal_name: String,
+    // If the output occurs in a constraint, these are the inputs constraining
+    // the output according to the template model.
+    unconstrained_inputs: Vec<String>,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl UnconstrainedComponentOutputWarning {
+    pub fn into_report(self) -> Report {
+        let (message, primary) = if self.unconstrained_inputs.is_empty() {
+            (
+                format!(
+                    "The component signal `{}.{}` is read, but never occurs in a constraint.",
+                    self.component_name, self.signal_name
+                ),
+                format!(
+                    "The value of `{}.{}` is read here without being constrained.",
+                    self.component_name, self.signal_name
+                ),
+            )
+        } else {
+            let inputs = self
+                .unconstrained_inputs
+                .iter()
+                .map(|input| format!("`{}.{input}`", self.component_name))
+                .collect::<Vec<_>>()
+                .join(", ");
+            (
+                format!(
+                    "The component signal `{}.{}` is constrained, but the inputs constraining it are only assigned using `<--`.",
+                    self.component_name, self.signal_name
+                ),
+                format!("The value of `{}.{}` depends on {inputs}, which are not constrained by the template.", self.component_name, self.signal_name),
+            )
+        };
+        let mut report = Report::warning(message, ReportCode::UnconstrainedComponentOutput);
+        if let Some(file_id) = self.file_id {
+            report.add_primary(self.file_location, file_id, primary);
+        }
+        report.add_note(
+            "Since the output of the component is not constrained by the template, the constraints of the component do not restrict the signals of the template. Consider using the constraint assignment operator `<==`, or adding a constraint relating the output to the signals of the template.".to_string(),
+        );
+        report
+    }
+}
+
+/// A component signal, identified by the component name and signal name. Note
+/// that array indices are ignored, so component arrays are treated as a single
+/// component.
+type ComponentSignal = (VariableName, String);
+
+/// If the output of a component is read, but never occurs in a constraint, the
+/// constraints generated by the component do not restrict the signals of the
+/// parent template. This typically means that the component is instantiated
+/// but unconstrained. This analysis pass reports component outputs that are
+/// read, but which do not occur in any constraint in the template, either
+/// directly or through a local variable computed from the output (as in
+/// `acc += eq[i].out * in[i]; out <== acc;`).
+///
+/// If the component instantiates a known Circomlib template, the pass also
+/// reports component outputs occurring in a constraint, if all inputs
+/// constraining the output (according to the template model) are only
+/// assigned using the signal assignment operator `<--`.
+pub fn find_unconstrained_component_outputs(cfg: &Cfg) -> ReportCollection {
+    if !matches!(cfg.definition_type(), DefinitionType::Template) {
+        // Exit early if this is a function or custom template.
+        return ReportCollection::new();
+    }
+    debug!("running unconstrained component output analysis pass");
+    let mut signals = ComponentSignals::default();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            signals.visit_statement(cfg, stmt);
+        }
+    }
+    signals.propagate_local_reads();
+    let models = ComponentModels::new(cfg);
+    let mut reports = Vec::new();
+    for (component_signal, meta) in &signals.unconstrained_reads {
+        if !signals.constrained_reads.contains_key(component_signal) {
+            reports.push((meta, component_signal, Vec::new()));
+        }
+    }
+    for (component_signal, meta) in &signals.constrained_reads {
+        let (component_name, signal_name) = component_signal;
+        let Some(inputs) =
+            models.get(component_name).and_then(|model| model.inputs_constraining(signal_name))
+        else {
+            continue;
+        };
+        let is_unconstrained = |input: &&str| {
+            let input = (component_name.clone(), input.to_string());
+            signals.unconstrained_inputs.contains(&input)
+                && !signals.constrained_inputs.contains(&input)
+        };
+        if !inputs.is_empty() && inputs.iter().all(is_unconstrained) {
+            let inputs = inputs.iter().map(ToString::to_string).collect();
+            reports.push((meta, component_signal, inputs));
+        }
+    }
+    // Sort reports by location to ensure that the output is stable.
+    reports.sort_by_key(|(meta, _, _)| meta.file_location().start);
+
+    let reports = reports
+        .into_iter()
+        .map(|(meta, (component_name, signal_name), inputs)| {
+            build_report(meta, component_name, signal_name, inputs)
+        })
+        .collect::<ReportCollection>();
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+/// Tracks reads of component signals and assignments to component inputs.
+#[derive(Default)]
+struct ComponentSignals {
+    // The first read of each component signal outside a constraint.
+    unconstrained_reads: HashMap<ComponentSignal, Meta>,
+    // The first read of each component signal in a constraint.
+    constrained_reads: HashMap<ComponentSignal, Meta>,
+    // Component inputs assigned using `<--`.
+    unconstrained_inputs: HashSet<ComponentSignal>,
+    // Component inputs assigned using `<==`.
+    constrained_inputs: HashSet<ComponentSignal>,
+    // Reads of component signals assigned to a local variable, together with
+    // the assigned variable.
+    local_reads: Vec<(ComponentSignal, Meta, VariableName)>,
+    // Maps each local variable to the local variables assigned a value
+    // computed from the variable.
+    local_taint: HashMap<VariableName, HashSet<VariableName>>,
+    // Local variables occurring in a constraint.
+    constrained_locals: HashSet<VariableName>,
+}
+
+impl ComponentSignals {
+    fn visit_statement(&mut self, cfg: &Cfg, stmt: &Statement) {
+        use AssignOp::*;
+        use Expression::*;
+        use Statement::*;
+        // Declarations of local variables may be versioned, so any variable
+        // which is not a signal or a component is treated as a local variable.
+        let is_local = |name: &VariableName| {
+            !matches!(cfg.get_type(name), Some(VariableType::Signal(..) | VariableType::Component))
+        };
+        let is_constraint = matches!(
+            stmt,
+            ConstraintEquality { .. } | Substitution { op: AssignConstraintSignal, .. }
+        );
+        let local_var = match stmt {
+            Substitution { var, op: AssignLocalOrComponent, .. } if is_local(var) => Some(var),
+            _ => None,
+        };
+        if is_constraint {
+            self.constrained_locals.extend(
+                stmt.variables_used().map(|var_use| var_use.name().clone()).filter(is_local),
+            );
+        }
+        if let Some(var) = local_var {
+            for source in
+                stmt.variables_read().map(|var_use| var_use.name()).filter(|name| is_local(name))
+            {
+                self.local_taint.entry(source.clone()).or_default().insert(var.clone());
+            }
+        }
+        for component in stmt.components_read() {
+            let Some(signal_name) = get_signal_name(component.access()) else {
+                continue;
+            };
+            let component_signal = (component.name().clone(), signal_name.to_string());
+            let reads = match local_var {
+                _ if is_constraint => &mut self.constrained_reads,
+                Some(var) => {
+                    // Whether the read is constrained depends on how the
+                    // variable is used, which is determined once all
+                    // statements have been visited.
+                    self.local_reads.push((
+                        component_signal,
+                        component.meta().clone(),
+                        var.clone(),
+                    ));
+                    continue;
+                }
+                None => &mut self.unconstrained_reads,
+            };
+            reads.entry(component_signal).or_insert_with(|| component.meta().clone());
+        }
+        // Assignments to component inputs are lifted to updates.
+        if let Substitution { var, op, rhe: Update { access, .. }, .. } = stmt {
+            let Some(signal_name) = get_signal_name(access) else {
+                return;
+            };
+            let component_signal = (var.clone(), signal_name.to_string());
+            match op {
+                AssignSignal => self.unconstrained_inputs.insert(component_signal),
+                AssignConstraintSignal => self.constrained_inputs.insert(component_signal),
+                AssignLocalOrComponent => false,
+            };
+        }
+    }
+
+    /// Component signals assigned to a local variable are constrained if the
+    /// variable flows (through zero or more local variables) into a
+    /// constraint.
+    fn propagate_local_reads(&mut self) {
+        for (component_signal, meta, var) in std::mem::take(&mut self.local_reads) {
+            let reads = if self.taints_constraint(&var) {
+                &mut self.constrained_reads
+            } else {
+                &mut self.unconstrained_reads
+            };
+            reads.entry(component_signal).or_insert(meta);
+        }
+    }
+
+    fn taints_constraint(&self, var: &VariableName) -> bool {
+        let mut visited = HashSet::from([var.clone()]);
+        let mut worklist = vec![var.clone()];
+        while let Some(var) = worklist.pop() {
+            if self.constrained_locals.contains(&var) {
+                return true;
+            }
+            for sink in self.local_taint.get(&var).into_iter().flatten() {
+                if visited.insert(sink.clone()) {
+                    worklist.push(sink.clone());
+                }
+            }
+        }
+        false
+    }
+}
+
+fn build_report(
+    meta: &Meta,
+    component_name: &VariableName,
+    signal_name: &str,
+    unconstrained_inputs: Vec<String>,
+) -> Report {
+    UnconstrainedComponentOutputWarning {
+        component_name: component_name.to_string(),
+        signal_name: signal_name.to_string(),
+        unconstrained_inputs,
+        file_id: meta.file_id(),
+        file_location: meta.file_location(),
+    }
+    .into_report()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_unconstrained_component_outputs() {
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                component isz = IsZero();
+
+                isz.in <== in;
+                out <-- isz.out;
+                out === 1;
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                component isz[2];
+                component eq = IsEqual();
+
+                isz[0] = IsZero();
+                isz[0].in <== in;
+                isz[1] = IsZero();
+                isz[1].in <== in + 1;
+                eq.in[0] <== in;
+                eq.in[1] <== 1;
+                if (eq.out == 1) {
+                    out <-- isz[0].out;
+                } else {
+                    out <-- isz[1].out;
+                }
+                out === isz[1].out;
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                component isz = IsZero();
+                component not = Not();
+
+                isz.in <== in;
+                not.in <== isz.out;
+                out <-- not.out;
+                out === not.out;
+            }
+        "#;
+        validate_reports(src, 0);
+
+        let src = r#"
+            template T() {
+                signal input in[2];
+                signal output out;
+                component lt = LessThan(8);
+
+                lt.in[0] <-- in[0];
+                lt.in[1] <-- in[1];
+                out <== lt.out;
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            template T() {
+                signal input in[2];
+                signal output out;
+                component lt = LessThan(8);
+
+                lt.in[0] <-- in[0];
+                lt.in[1] <== in[1];
+                out <== lt.out;
+            }
+        "#;
+        validate_reports(src, 0);
+
+        // Outputs flowing into a constraint through a variable are constrained.
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                component isz = IsZero();
+
+                isz.in <== in;
+                var t = isz.out;
+                out <== t;
+            }
+        "#;
+        validate_reports(src, 0);
+
+        // Outputs accumulated in a loop are constrained if the accumulator is.
+        let src = r#"
+            template T(n) {
+                signal input in[n];
+                signal input index;
+                signal output out;
+                component eq[n];
+
+                var acc = 0;
+                for (var i = 0; i < n; i++) {
+                    eq[i] = IsEqual();
+                    eq[i].in[0] <== i;
+                    eq[i].in[1] <== index;
+                    acc += eq[i].out * in[i];
+                }
+                out <== acc;
+            }
+        "#;
+        validate_reports(src, 0);
+
+        // Variables which are only used in signal assignments do not constrain
+        // the output.
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                component isz = IsZero();
+
+                isz.in <== in;
+                var t = isz.out;
+                out <-- t;
+            }
+        "#;
+        validate_reports(src, 1);
+    }
+
+    fn validate_reports(src: &str, expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_unconstrained_component_outputs(&cfg);
+
+        assert_eq!(reports.len(), expected_len);
+    }
+}
@@ -0,0 +1,316 @@
+use log::debug;
+use num_traits::Zero;
+use std::collections::{HashMap, HashSet};
+
+use program_structure::cfg::{Cfg, DefinitionType};
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::value_meta::{ValueMeta, ValueReduction};
+use program_structure::ir::variable_meta::VariableMeta;
+use program_structure::ir::*;
+
+pub struct UnconstrainedInverseWarning {
+    signal_name: String,
+    inverted_value: String,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl UnconstrainedInverseWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            format!(
+                "The signal `{}` is assigned the inverse of `{}` using `<--`, but is never constrained to be the inverse.",
+                self.signal_name, self.inverted_value
+            ),
+            ReportCode::UnconstrainedInverse,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                format!("The inverse of `{}` is computed here.", self.inverted_value),
+            );
+        }
+        report.add_help(format!(
+            "Consider adding the constraint `{} * {} === 1`. (If `{}` may be zero, consider using the `IsZero` template from Circomlib instead.)",
+            self.signal_name, self.inverted_value, self.inverted_value
+        ));
+        report
+    }
+}
+
+/// The maximum number of local variable definitions followed when resolving
+/// the value of an expression.
+const MAX_DEPTH: usize = 16;
+
+/// A signal assigned the inverse of an expression using the signal assignment
+/// operator `<--` must be constrained to be the inverse, typically using a
+/// constraint like `inv * x === 1`. If there is no constraint relating the
+/// signal to the inverted expression, a malicious prover can assign an
+/// arbitrary value to the signal. This analysis pass reports each signal
+/// assigned an inverse, where no constraint contains the product of the signal
+/// and the inverted expression. Intermediate variables are resolved using
+/// their (SSA) definitions, and the constraint may occur anywhere in the
+/// template.
+pub fn find_unconstrained_inverse(cfg: &Cfg) -> ReportCollection {
+    if !matches!(cfg.definition_type(), DefinitionType::Template) {
+        // Exit early if this is a function or custom template.
+        return ReportCollection::new();
+    }
+    debug!("running unconstrained inverse analysis pass");
+    let mut definitions = HashMap::new();
+    let mut inversions = Vec::new();
+    let mut constraints = Vec::new();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            visit_statement(stmt, &mut definitions, &mut inversions, &mut constraints);
+        }
+    }
+    let resolver = Resolver { definitions };
+    let mut reports = ReportCollection::new();
+    for (meta, signal_name, rhe) in inversions {
+        let Some(inverted_value) = resolver.inverted_value(rhe, 0) else {
+            continue;
+        };
+        let inverted_signals = resolver.signals(inverted_value);
+        if inverted_signals.is_empty() {
+            continue;
+        }
+        // Output signals which do not occur in any constraint are reported by
+        // the unconstrained output analysis pass.
+        let is_output =
+            matches!(cfg.get_type(signal_name), Some(VariableType::Signal(SignalType::Output, _)));
+        let is_constrained = constraints.iter().any(|(var, expr)| {
+            *var == Some(signal_name) || resolver.signals(expr).contains(signal_name)
+        });
+        if is_output && !is_constrained {
+            continue;
+        }
+        let relates_signals = |(_, expr): &(Option<&VariableName>, &Expression)| {
+            let mut products = Vec::new();
+            find_products(expr, &mut products);
+            products.into_iter().any(|(lhe, rhe)| {
+                let lhs = resolver.signals(lhe);
+                let rhs = resolver.signals(rhe);
+                (lhs.contains(signal_name) && inverted_signals.is_subset(&rhs))
+                    || (rhs.contains(signal_name) && inverted_signals.is_subset(&lhs))
+            })
+        };
+        if !constraints.iter().any(relates_signals) {
+            reports.push(build_report(meta, signal_name, inverted_value));
+        }
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+/// Records local variable definitions, signal assignments using `<--`, and
+/// the expressions occurring in constraints (together with the assigned
+/// signal, if the constraint is a constraint assignment).
+fn visit_statement<'a>(
+    stmt: &'a Statement,
+    definitions: &mut HashMap<&'a VariableName, &'a Expression>,
+    inversions: &mut Vec<(&'a Meta, &'a VariableName, &'a Expression)>,
+    constraints: &mut Vec<(Option<&'a VariableName>, &'a Expression)>,
+) {
+    use AssignOp::*;
+    use Statement::*;
+    match stmt {
+        Substitution { meta, var, op: AssignLocalOrComponent, rhe }
+            if meta.type_knowledge().is_local() =>
+        {
+            definitions.insert(var, rhe);
+        }
+        Substitution { meta, var, op: AssignSignal, rhe } => {
+            inversions.push((meta, var, rhe));
+        }
+        Substitution { var, op: AssignConstraintSignal, rhe, .. } => {
+            constraints.push((Some(var), rhe));
+        }
+        ConstraintEquality { lhe, rhe, .. } => {
+            constraints.push((None, lhe));
+            constraints.push((None, rhe));
+        }
+        _ => {}
+    }
+}
+
+/// Resolves local variables to their definitions.
+struct Resolver<'a> {
+    definitions: HashMap<&'a VariableName, &'a Expression>,
+}
+
+impl<'a> Resolver<'a> {
+    /// Returns the expression `x`, if the given expression evaluates to `c / x`
+    /// for some non-zero constant `c` (possibly guarded by a ternary operator).
+    fn inverted_value(&self, expr: &'a Expression, depth: usize) -> Option<&'a Expression> {
+        use Expression::*;
+        use ExpressionInfixOpcode::*;
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        match expr {
+            InfixOp { lhe, infix_op: Div, rhe, .. } if is_non_zero_constant(lhe) => Some(rhe),
+            SwitchOp { if_true, if_false, .. } => self
+                .inverted_value(if_true, depth + 1)
+                .or_else(|| self.inverted_value(if_false, depth + 1)),
+            Update { rhe, .. } => self.inverted_value(rhe, depth + 1),
+            Variable { name, .. } => {
+                let definition = self.definitions.get(name)?;
+                self.inverted_value(definition, depth + 1)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the signals the given expression depends on.
+    fn signals(&self, expr: &Expression) -> HashSet<VariableName> {
+        let mut signals = HashSet::new();
+        let mut visited = HashSet::new();
+        self.signals_impl(expr, &mut signals, &mut visited);
+        signals
+    }
+
+    fn signals_impl(
+        &self,
+        expr: &Expression,
+        signals: &mut HashSet<VariableName>,
+        visited: &mut HashSet<VariableName>,
+    ) {
+        signals.extend(expr.signals_read().iter().map(|var| var.name().clone()));
+        for local in expr.locals_read() {
+            if !visited.insert(local.name().clone()) {
+                continue;
+            }
+            if let Some(definition) = self.definitions.get(local.name()) {
+                self.signals_impl(definition, signals, visited);
+            }
+        }
+    }
+}
+
+/// Returns all products `lhe * rhe` occurring in the given expression.
+fn find_products<'a>(expr: &'a Expression, products: &mut Vec<(&'a Expression, &'a Expression)>) {
+    use Expression::*;
+    use ExpressionInfixOpcode::*;
+    match expr {
+        InfixOp { lhe, infix_op, rhe, .. } => {
+            if matches!(infix_op, Mul) {
+                products.push((lhe, rhe));
+            }
+            find_products(lhe, products);
+            find_products(rhe, products);
+        }
+        PrefixOp { rhe, .. } | Update { rhe, .. } => find_products(rhe, products),
+        SwitchOp { cond, if_true, if_false, .. } => {
+            find_products(cond, products);
+            find_products(if_true, products);
+            find_products(if_false, products);
+        }
+        Call { args: values, .. } | InlineArray { values, .. } => {
+            for value in values {
+                find_products(value, products);
+            }
+        }
+        Number(_, _) | Variable { .. } | Access { .. } | Phi { .. } => (),
+    }
+}
+
+fn is_non_zero_constant(expr: &Expression) -> bool {
+    matches!(expr.value(), Some(ValueReduction::FieldElement { value }) if !value.is_zero())
+}
+
+fn build_report(meta: &Meta, signal_name: &VariableName, inverted_value: &Expression) -> Report {
+    UnconstrainedInverseWarning {
+        signal_name: signal_name.to_string(),
+        inverted_value: inverted_value.to_string(),
+        file_id: meta.file_id(),
+        file_location: meta.file_location(),
+    }
+    .into_report()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_unconstrained_inverse() {
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                signal inv;
+
+                inv <-- 1 / in;
+                out <== inv + 1;
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            template IsZero() {
+                signal input in;
+                signal output out;
+                signal inv;
+
+                inv <-- in != 0 ? 1 / in : 0;
+                out <== -in * inv + 1;
+                in * out === 0;
+            }
+        "#;
+        validate_reports(src, 0);
+
+        let src = r#"
+            template T() {
+                signal input in[2];
+                signal output out;
+                signal inv;
+
+                out <== inv + 1;
+                (in[0] - in[1]) * inv === 1;
+                var diff = in[0] - in[1];
+                var res = 1 / diff;
+                inv <-- res;
+            }
+        "#;
+        validate_reports(src, 0);
+
+        let src = r#"
+            template T() {
+                signal input a;
+                signal input b;
+                signal output out;
+                signal inv;
+
+                out <== inv + 1;
+                a * inv === 1;
+                var diff = a - b;
+                inv <-- 1 / diff;
+            }
+        "#;
+        validate_reports(src, 1);
+    }
+
+    fn validate_reports(src: &str, expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_unconstrained_inverse(&cfg);
+
+        assert_eq!(reports.len(), expected_len);
+    }
+}
@@ -0,0 +1,197 @@
+use log::debug;
+use std::collections::{HashMap, HashSet};
+
+use program_structure::cfg::{Cfg, DefinitionType};
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::declarations::Declaration;
+use program_structure::ir::variable_meta::VariableMeta;
+use program_structure::ir::*;
+
+pub struct UnconstrainedOutputError {
+    signal_name: String,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+    declaration_file_id: Option<FileID>,
+    declaration_file_location: FileLocation,
+}
+
+impl UnconstrainedOutputError {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::error(
+            format!(
+                "The output signal `{}` is assigned using `<--`, but never occurs in a constraint.",
+                self.signal_name
+            ),
+            ReportCode::UnconstrainedOutputSignal,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                format!("The output signal `{}` is assigned here.", self.signal_name),
+            );
+        }
+        if let Some(file_id) = self.declaration_file_id {
+            report.add_secondary(
+                self.declaration_file_location,
+                file_id,
+                Some(format!("The output signal `{}` is declared here.", self.signal_name)),
+            );
+        }
+        report.add_note(
+            "Since the output is unconstrained, a malicious prover can assign an arbitrary value to it. Consider using the constraint assignment operator `<==`, or adding a constraint restricting the value of the signal.".to_string(),
+        );
+        report
+    }
+}
+
+/// Output signals that are only assigned using the signal assignment operator
+/// `<--`, and never occur in a constraint, can be assigned arbitrary values by
+/// a malicious prover. This is almost always a soundness bug, so this analysis
+/// pass reports each such output signal as an error.
+pub fn find_unconstrained_outputs(cfg: &Cfg) -> ReportCollection {
+    if !matches!(cfg.definition_type(), DefinitionType::Template) {
+        // Exit early if this is a function or custom template.
+        return ReportCollection::new();
+    }
+    debug!("running unconstrained output analysis pass");
+    let mut outputs = cfg
+        .declarations()
+        .iter()
+        .filter(|(_, declaration)| {
+            matches!(declaration.variable_type(), VariableType::Signal(SignalType::Output, _))
+        })
+        .collect::<Vec<_>>();
+    // Sort outputs by declaration order to ensure that the output is stable.
+    outputs.sort_by_key(|(_, declaration)| declaration.file_location().start);
+
+    let mut assignments = HashMap::new();
+    let mut constrained_signals = HashSet::new();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            visit_statement(stmt, &mut assignments, &mut constrained_signals);
+        }
+    }
+    let mut reports = ReportCollection::new();
+    for (name, declaration) in outputs {
+        if constrained_signals.contains(name) {
+            continue;
+        }
+        if let Some(meta) = assignments.get(name) {
+            reports.push(build_report(meta, declaration, cfg.name()));
+        }
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+/// Records the first signal assignment `<--` to each signal, and each signal
+/// occurring in a constraint.
+fn visit_statement(
+    stmt: &Statement,
+    assignments: &mut HashMap<VariableName, Meta>,
+    constrained_signals: &mut HashSet<VariableName>,
+) {
+    use AssignOp::*;
+    use Statement::*;
+    match stmt {
+        Substitution { meta, var, op: AssignSignal, .. } => {
+            assignments.entry(var.clone()).or_insert_with(|| meta.clone());
+        }
+        Substitution { op: AssignConstraintSignal, .. } | ConstraintEquality { .. } => {
+            constrained_signals.extend(stmt.variables_used().map(|var| var.name().clone()));
+        }
+        _ => {}
+    }
+}
+
+fn build_report(meta: &Meta, declaration: &Declaration, template_name: &str) -> Report {
+    let mut report = UnconstrainedOutputError {
+        signal_name: declaration.variable_name().to_string(),
+        file_id: meta.file_id(),
+        file_location: meta.file_location(),
+        declaration_file_id: declaration.file_id(),
+        declaration_file_location: declaration.file_location(),
+    }
+    .into_report();
+    report.add_affected_signal(template_name, &declaration.variable_name().to_string());
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_unconstrained_outputs() {
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out[2];
+
+                out[0] <-- in * in;
+                out[1] <-- in + 1;
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                signal output inv;
+
+                inv <-- in != 0 ? 1 / in : 0;
+                out <== in + 1;
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                signal output inv;
+
+                inv <-- in != 0 ? 1 / in : 0;
+                out <== -in * inv + 1;
+                in * out === 0;
+            }
+        "#;
+        validate_reports(src, 0);
+
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                component c = C();
+
+                out <-- in * in;
+                c.in <== out;
+            }
+        "#;
+        validate_reports(src, 0);
+    }
+
+    fn validate_reports(src: &str, expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_unconstrained_outputs(&cfg);
+
+        assert_eq!(reports.len(), expected_len);
+    }
+}
@@ -0,0 +1,391 @@
+use log::debug;
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+use std::collections::{BTreeSet, HashMap};
+
+use program_structure::cfg::Cfg;
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::value_meta::{ValueMeta, ValueReduction};
+use program_structure::ir::variable_meta::VariableMeta;
+use program_structure::ir::*;
+
+use crate::loop_bounds::loop_variable_values;
+
+/// The maximum number of elements of a component array tracked by the
+/// analysis. Larger arrays are ignored.
+const MAX_ARRAY_SIZE: usize = 1 << 16;
+
+/// The maximum number of uninitialized elements listed in a report.
+const MAX_LISTED_ELEMENTS: usize = 8;
+
+pub struct UninitializedComponentWarning {
+    name: String,
+    elements: Vec<Vec<usize>>,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+    accesses: Vec<(Option<FileID>, FileLocation)>,
+}
+
+impl UninitializedComponentWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            format!("Some elements of the component array `{}` are never instantiated.", self.name),
+            ReportCode::UninitializedComponent,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                format!("The component array `{}` is declared here.", self.name),
+            );
+        }
+        for (file_id, file_location) in self.accesses {
+            if let Some(file_id) = file_id {
+                report.add_secondary(
+                    file_location,
+                    file_id,
+                    Some(
+                        "Signals of an uninstantiated component may be accessed here.".to_string(),
+                    ),
+                );
+            }
+        }
+        let mut elements = self
+            .elements
+            .iter()
+            .take(MAX_LISTED_ELEMENTS)
+            .map(|indices| format!("`{}{}`", self.name, format_indices(indices)))
+            .collect::<Vec<_>>();
+        if self.elements.len() > MAX_LISTED_ELEMENTS {
+            elements.push(format!("{} more", self.elements.len() - MAX_LISTED_ELEMENTS));
+        }
+        report.add_note(format!(
+            "The following elements are never instantiated: {}.",
+            elements.join(", ")
+        ));
+        report
+    }
+}
+
+fn format_indices(indices: &[usize]) -> String {
+    indices.iter().map(|index| format!("[{index}]")).collect()
+}
+
+/// A component array declared with constant dimensions.
+struct ComponentArray<'a> {
+    meta: &'a Meta,
+    dimensions: Vec<usize>,
+    /// The elements that may be instantiated, or `None` if some element is
+    /// instantiated using indices which could not be evaluated.
+    instantiated: Option<BTreeSet<Vec<usize>>>,
+    /// The signal accesses of the array, together with the elements that
+    /// they may access.
+    accesses: Vec<(&'a Meta, Option<Vec<Vec<usize>>>)>,
+}
+
+/// For each component array declared with constant dimensions, this analysis
+/// pass evaluates the indices of each element instantiation, where indices
+/// given by loop variables are evaluated if the loop bounds are constant. If
+/// some element of the array is never instantiated, the array is reported
+/// together with any signal accesses which may access the uninitialized
+/// elements.
+pub fn find_uninitialized_components(cfg: &Cfg) -> ReportCollection {
+    debug!("running uninitialized component analysis pass");
+    let mut arrays = HashMap::new();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            let Statement::Declaration {
+                meta,
+                names,
+                var_type: VariableType::Component,
+                dimensions,
+            } = stmt
+            else {
+                continue;
+            };
+            let Some(dimensions) = constant_dimensions(dimensions) else {
+                continue;
+            };
+            for name in names.iter() {
+                arrays.insert(
+                    name.without_version(),
+                    ComponentArray {
+                        meta,
+                        dimensions: dimensions.clone(),
+                        instantiated: Some(BTreeSet::new()),
+                        accesses: Vec::new(),
+                    },
+                );
+            }
+        }
+    }
+    if arrays.is_empty() {
+        return ReportCollection::new();
+    }
+    let mut indices = IndexEvaluator::new(cfg);
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            // Element instantiations are represented as updates of the form
+            // `c = update(c, (i, ..., k), T(...))`.
+            if let Statement::Substitution {
+                var,
+                rhe: Expression::Update { access, rhe, .. },
+                ..
+            } = stmt
+            {
+                if let (Some(array), Expression::Call { .. }) =
+                    (arrays.get_mut(&var.without_version()), rhe.as_ref())
+                {
+                    if !is_signal_access(access) {
+                        let elements = (access.len() == array.dimensions.len())
+                            .then(|| indices.elements(access))
+                            .flatten();
+                        match (&mut array.instantiated, elements) {
+                            (Some(instantiated), Some(elements)) => instantiated.extend(elements),
+                            (instantiated, _) => *instantiated = None,
+                        }
+                        continue;
+                    }
+                }
+            }
+            for var_use in stmt.components_read().iter().chain(stmt.components_written().iter()) {
+                let Some(array) = arrays.get_mut(&var_use.name().without_version()) else {
+                    continue;
+                };
+                if !is_signal_access(var_use.access()) {
+                    continue;
+                }
+                let access = var_use
+                    .access()
+                    .iter()
+                    .take_while(|access| matches!(access, AccessType::ArrayAccess(_)))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                array.accesses.push((var_use.meta(), indices.elements(&access)));
+            }
+        }
+    }
+    let mut reports = ReportCollection::new();
+    let mut arrays = arrays.into_iter().collect::<Vec<_>>();
+    arrays.sort_by_key(|(_, array)| array.meta.file_location().start);
+    for (name, array) in arrays {
+        let Some(instantiated) = &array.instantiated else {
+            continue;
+        };
+        let uninitialized = all_elements(&array.dimensions)
+            .into_iter()
+            .filter(|element| !instantiated.contains(element))
+            .collect::<Vec<_>>();
+        if uninitialized.is_empty() {
+            continue;
+        }
+        let accesses = array
+            .accesses
+            .iter()
+            .filter(|(_, elements)| match elements {
+                Some(elements) => elements
+                    .iter()
+                    .any(|prefix| uninitialized.iter().any(|element| element.starts_with(prefix))),
+                None => false,
+            })
+            .map(|(meta, _)| (meta.file_id(), meta.file_location()))
+            .collect();
+        reports.push(
+            UninitializedComponentWarning {
+                name: name.to_string(),
+                elements: uninitialized,
+                file_id: array.meta.file_id(),
+                file_location: array.meta.file_location(),
+                accesses,
+            }
+            .into_report(),
+        );
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+/// Returns true if the access is an access to a component signal.
+fn is_signal_access(access: &[AccessType]) -> bool {
+    access.iter().any(|access| matches!(access, AccessType::ComponentAccess(_)))
+}
+
+/// Returns the dimensions of a component array if all dimensions are
+/// constant and the array is not too large.
+fn constant_dimensions(dimensions: &[Expression]) -> Option<Vec<usize>> {
+    if dimensions.is_empty() {
+        return None;
+    }
+    let dimensions = dimensions.iter().map(constant_index).collect::<Option<Vec<_>>>()?;
+    let size =
+        dimensions.iter().try_fold(1_usize, |size, dimension| size.checked_mul(*dimension))?;
+    (size <= MAX_ARRAY_SIZE).then_some(dimensions)
+}
+
+fn constant_index(expr: &Expression) -> Option<usize> {
+    match expr.value() {
+        Some(ValueReduction::FieldElement { value }) => value.to_usize(),
+        _ => None,
+    }
+}
+
+/// Returns all elements of an array with the given dimensions.
+fn all_elements(dimensions: &[usize]) -> Vec<Vec<usize>> {
+    dimensions.iter().fold(vec![Vec::new()], |elements, dimension| {
+        elements
+            .iter()
+            .flat_map(|element| {
+                (0..*dimension).map(move |index| {
+                    let mut element = element.clone();
+                    element.push(index);
+                    element
+                })
+            })
+            .collect()
+    })
+}
+
+/// Evaluates array indices given by constants, or by loop variables of loops
+/// with constant bounds.
+struct IndexEvaluator<'a> {
+    cfg: &'a Cfg,
+    loop_variables: HashMap<VariableName, Option<Vec<BigInt>>>,
+}
+
+impl<'a> IndexEvaluator<'a> {
+    fn new(cfg: &'a Cfg) -> IndexEvaluator<'a> {
+        IndexEvaluator { cfg, loop_variables: HashMap::new() }
+    }
+
+    /// Returns the (possibly partial) array elements that may be accessed by
+    /// the given array access, or `None` if some index cannot be evaluated.
+    fn elements(&mut self, access: &[AccessType]) -> Option<Vec<Vec<usize>>> {
+        let mut elements = vec![Vec::new()];
+        for access in access {
+            let AccessType::ArrayAccess(index) = access else {
+                return None;
+            };
+            let values = self.values(index)?;
+            elements = elements
+                .iter()
+                .flat_map(|element| {
+                    values.iter().map(move |value| {
+                        let mut element = element.clone();
+                        element.push(*value);
+                        element
+                    })
+                })
+                .collect();
+            if elements.len() > MAX_ARRAY_SIZE {
+                return None;
+            }
+        }
+        Some(elements)
+    }
+
+    fn values(&mut self, index: &Expression) -> Option<Vec<usize>> {
+        if let Some(index) = constant_index(index) {
+            return Some(vec![index]);
+        }
+        let Expression::Variable { name, .. } = index else {
+            return None;
+        };
+        let cfg = self.cfg;
+        let values = self
+            .loop_variables
+            .entry(name.clone())
+            .or_insert_with(|| loop_variable_values(cfg, name))
+            .as_ref()?;
+        Some(values.iter().filter_map(ToPrimitive::to_usize).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_traits::One;
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_uninitialized_components() {
+        let src = r#"
+            template T() {
+                signal input in[4];
+                signal output out[4];
+                component c[4];
+                for (var i = 0; i < 4; i++) {
+                    c[i] = Num2Bits(8);
+                    c[i].in <== in[i];
+                    out[i] <== c[i].out[0];
+                }
+            }
+        "#;
+        validate_reports(src, &[], 0);
+
+        let src = r#"
+            template T() {
+                signal input in[4];
+                signal output out[4];
+                component c[4];
+                for (var i = 0; i < 3; i++) {
+                    c[i] = Num2Bits(8);
+                }
+                for (var i = 0; i < 4; i++) {
+                    c[i].in <== in[i];
+                    out[i] <== c[i].out[0];
+                }
+            }
+        "#;
+        validate_reports(src, &[], 1);
+
+        let src = r#"
+            template T(n) {
+                signal input in[n][2];
+                component c[n][2];
+                for (var i = 1; i < n; i++) {
+                    for (var j = 0; j < 2; j++) {
+                        c[i][j] = IsZero();
+                        c[i][j].in <== in[i][j];
+                    }
+                }
+                c[0][0] = IsZero();
+            }
+        "#;
+        // The template parameter is unknown.
+        validate_reports(src, &[], 0);
+        validate_reports(src, &[Some(BigInt::one() + 1)], 1);
+
+        let src = r#"
+            template T(n) {
+                signal input in[4];
+                component c[4];
+                for (var i = 0; i < n; i++) {
+                    c[i] = IsZero();
+                    c[i].in <== in[i];
+                }
+            }
+        "#;
+        validate_reports(src, &[], 0);
+    }
+
+    fn validate_reports(src: &str, params: &[Option<BigInt>], expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let mut cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+        cfg.propagate_parameter_values(params);
+
+        // Generate report collection.
+        let reports = find_uninitialized_components(&cfg);
+        assert_eq!(reports.len(), expected_len);
+    }
+}
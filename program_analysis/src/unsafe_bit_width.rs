@@ -0,0 +1,199 @@
+use log::debug;
+use num_bigint::BigInt;
+
+use program_structure::cfg::{Cfg, DefinitionType};
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::value_meta::{ValueMeta, ValueReduction};
+use program_structure::ir::*;
+
+use crate::circomlib_models::{find_template_model, Precondition};
+
+pub struct UnsafeBitWidthWarning {
+    template_name: String,
+    bit_width: BigInt,
+    bound: usize,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl UnsafeBitWidthWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            format!(
+                "The Circomlib template `{}` is instantiated with a bit width of {}, but the bit width must be less than {}.",
+                self.template_name, self.bit_width, self.bound
+            ),
+            ReportCode::UnsafeBitWidth,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                format!("Circomlib template `{}` instantiated here.", self.template_name),
+            );
+        }
+        report.add_note(
+            "Since the bit width is close to the size of the prime, values may wrap around the field modulus, and the constraints generated by the template may not enforce the expected relation.".to_string(),
+        );
+        report
+    }
+}
+
+/// Circomlib templates like `LessThan(n)` and `Num2Bits(n)` are only sound if
+/// the bit width `n` is sufficiently smaller than the size of the prime. (For
+/// example, `LessThan(n)` computes `in[0] + 2^n - in[1]`, which must not
+/// overflow.) This analysis pass uses constant propagation to report
+/// instantiations where the bit width is known to exceed the safe bound.
+pub fn find_unsafe_bit_width(cfg: &Cfg) -> ReportCollection {
+    use DefinitionType::*;
+    if matches!(cfg.definition_type(), Function | CustomTemplate) {
+        // Exit early if this is a function or custom template.
+        return ReportCollection::new();
+    }
+    debug!("running unsafe bit width analysis pass");
+    let mut reports = ReportCollection::new();
+    let prime_size = cfg.constants().prime_size();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            visit_statement(stmt, prime_size, &mut reports);
+        }
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+fn visit_statement(stmt: &Statement, prime_size: usize, reports: &mut ReportCollection) {
+    use AssignOp::*;
+    use Expression::*;
+    use Statement::*;
+    use ValueReduction::*;
+    // A component initialization on the form `var = component_name(args, ...)`.
+    let Substitution { meta: var_meta, op: AssignLocalOrComponent, rhe, .. } = stmt else {
+        return;
+    };
+    // If the variable `var` is declared as a local variable or signal, we exit early.
+    if var_meta.type_knowledge().is_local() || var_meta.type_knowledge().is_signal() {
+        return;
+    }
+    // Assignments to component arrays are lifted to updates.
+    let rhe = if let Update { rhe, .. } = rhe { rhe.as_ref() } else { rhe };
+    let Call { meta: component_meta, name: component_name, args, .. } = rhe else {
+        return;
+    };
+    // We assume that this is the corresponding circuit from Circomlib.
+    let Some(model) = find_template_model(component_name) else {
+        return;
+    };
+    for precondition in model.preconditions {
+        let Precondition::ParameterBelow { parameter, bound } = precondition else {
+            continue;
+        };
+        // The bound is given for the BN254 prime, and needs to be adjusted
+        // for smaller primes.
+        let bound = (*bound).min(prime_size.saturating_sub(2));
+        let Some(FieldElement { value }) = args.get(*parameter).and_then(|arg| arg.value()) else {
+            continue;
+        };
+        // Non-strict uses of `Num2Bits` are reported by a separate analysis pass.
+        if component_name == "Num2Bits" && *value >= BigInt::from(prime_size) {
+            continue;
+        }
+        if *value >= BigInt::from(bound) {
+            reports.push(build_report(component_meta, component_name, value, bound));
+        }
+    }
+}
+
+fn build_report(meta: &Meta, template_name: &str, bit_width: &BigInt, bound: usize) -> Report {
+    UnsafeBitWidthWarning {
+        template_name: template_name.to_string(),
+        bit_width: bit_width.clone(),
+        bound,
+        file_id: meta.file_id(),
+        file_location: meta.file_location(),
+    }
+    .into_report()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_unsafe_bit_width() {
+        let src = r#"
+            template T() {
+                signal input in[2];
+                signal output out;
+                component lt = LessThan(252);
+
+                lt.in[0] <== in[0];
+                lt.in[1] <== in[1];
+                out <== lt.out;
+            }
+        "#;
+        validate_reports(src, &Curve::default(), 1);
+
+        let src = r#"
+            template T(n) {
+                signal input in[2];
+                signal output out;
+                var m = 2 * n + 1;
+                component lt = GreaterEqThan(m);
+
+                lt.in[0] <== in[0];
+                lt.in[1] <== in[1];
+                out <== lt.out;
+            }
+        "#;
+        validate_reports(src, &Curve::default(), 0);
+
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out[64];
+                var n = 63;
+                component n2b = Num2Bits(n);
+
+                n2b.in <== in;
+                out <== n2b.out;
+            }
+        "#;
+        validate_reports(src, &Curve::default(), 0);
+        validate_reports(src, &Curve::Goldilocks, 1);
+
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out[254];
+                component n2b = Num2Bits(254);
+
+                n2b.in <== in;
+                out <== n2b.out;
+            }
+        "#;
+        validate_reports(src, &Curve::default(), 0);
+    }
+
+    fn validate_reports(src: &str, curve: &Curve, expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(curve, &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_unsafe_bit_width(&cfg);
+
+        assert_eq!(reports.len(), expected_len);
+    }
+}
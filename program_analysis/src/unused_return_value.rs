@@ -0,0 +1,160 @@
+use log::debug;
+use std::collections::HashSet;
+
+use program_structure::cfg::Cfg;
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::variable_meta::VariableMeta;
+use program_structure::ir::*;
+
+pub struct UnusedReturnValueWarning {
+    function_name: String,
+    var: VariableName,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl UnusedReturnValueWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            format!("The value returned by `{}` is never used.", self.function_name),
+            ReportCode::UnusedReturnValue,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                format!(
+                    "The value assigned to `{}` here is never read.",
+                    self.var.without_version()
+                ),
+            );
+        }
+        report.add_note(
+            "Since Circom functions have no side effects, discarding the returned value means that the call has no effect.".to_string(),
+        );
+        report
+    }
+}
+
+/// Since Circom functions cannot have side effects, a function call is only
+/// meaningful if the returned value is used. This analysis pass reports
+/// function calls where the returned value is assigned to a variable which is
+/// never read.
+pub fn find_unused_return_values(cfg: &Cfg) -> ReportCollection {
+    debug!("running unused return value analysis pass");
+    let mut reports = ReportCollection::new();
+    for (var, meta, function_name) in find_discarded_calls(cfg) {
+        reports.push(build_report(var, meta, function_name));
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+/// Returns the (SSA) variables assigned the value returned by a function call,
+/// which are never read.
+pub(crate) fn find_discarded_call_results(cfg: &Cfg) -> HashSet<VariableName> {
+    find_discarded_calls(cfg).into_iter().map(|(var, _, _)| var.clone()).collect()
+}
+
+fn find_discarded_calls(cfg: &Cfg) -> Vec<(&VariableName, &Meta, &str)> {
+    let mut variables_read = HashSet::new();
+    for basic_block in cfg.iter() {
+        variables_read.extend(basic_block.variables_read().map(|var| var.name().clone()));
+    }
+    let mut discarded_calls = Vec::new();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            let Statement::Substitution { meta, var, op: AssignOp::AssignLocalOrComponent, rhe } =
+                stmt
+            else {
+                continue;
+            };
+            if !meta.type_knowledge().is_local() || variables_read.contains(var) {
+                continue;
+            }
+            // Array element assignments are represented using update expressions.
+            let rhe = match rhe {
+                Expression::Update { rhe, .. } => rhe,
+                rhe => rhe,
+            };
+            if let Expression::Call { name, .. } = rhe {
+                discarded_calls.push((var, meta, name.as_str()));
+            }
+        }
+    }
+    discarded_calls
+}
+
+fn build_report(var: &VariableName, meta: &Meta, function_name: &str) -> Report {
+    UnusedReturnValueWarning {
+        function_name: function_name.to_string(),
+        var: var.clone(),
+        file_id: meta.file_id(),
+        file_location: meta.file_location(),
+    }
+    .into_report()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_unused_return_value() {
+        let src = r#"
+            template T() {
+                signal input in;
+                signal output out;
+                var x = f(in);
+                var y[2];
+                y[0] = f(in);
+                var z = f(in);
+                out <== in * z;
+            }
+        "#;
+        validate_reports(src, 2);
+
+        let src = r#"
+            function f(n) {
+                var x = g(n);
+                x = g(n + 1);
+                return x;
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            template T(n) {
+                signal input in[n];
+                signal output out;
+                var sum = 0;
+                for (var i = 0; i < n; i++) {
+                    sum = add(sum, in[i]);
+                }
+                out <== sum;
+            }
+        "#;
+        validate_reports(src, 0);
+    }
+
+    fn validate_reports(src: &str, expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_unused_return_values(&cfg);
+        assert_eq!(reports.len(), expected_len);
+    }
+}
@@ -0,0 +1,421 @@
+use log::debug;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use program_structure::cfg::{BasicBlock, Cfg, DefinitionType, Index};
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::variable_meta::{VariableMeta, VariableUse};
+use program_structure::ir::*;
+
+pub struct UseBeforeAssignmentWarning {
+    name: String,
+    is_signal: bool,
+    conditions: Vec<String>,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl UseBeforeAssignmentWarning {
+    pub fn into_report(self) -> Report {
+        let kind = if self.is_signal { "signal" } else { "variable" };
+        let mut report = Report::warning(
+            format!("The {kind} `{}` may be read before it is assigned a value.", self.name),
+            ReportCode::UseBeforeAssignment,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                format!("The {kind} `{}` is read here.", self.name),
+            );
+        }
+        if self.conditions.is_empty() {
+            report.add_note(format!(
+                "The {kind} `{}` is not assigned a value before this point.",
+                self.name
+            ));
+        } else {
+            report.add_note(format!(
+                "The {kind} `{}` is not assigned a value if {}.",
+                self.name,
+                self.conditions.join(" and ")
+            ));
+        }
+        report
+    }
+}
+
+/// The variables and signals that are definitely assigned at a program point.
+/// Unvisited blocks are represented by `None`.
+type Assigned = Option<HashSet<VariableName>>;
+
+/// A local variable (or signal) read where some path from the entry block
+/// reaches the read without passing through an assignment to the variable.
+struct UninitializedRead<'a> {
+    block: Index,
+    var: &'a VariableUse,
+}
+
+/// This analysis pass runs a reaching definitions analysis over the CFG, and
+/// reports local variables and signals that are read before they are assigned
+/// a value along some path through the CFG. (Input signals and parameters are
+/// considered to be assigned on entry.) Array elements are not tracked
+/// individually, so an array is considered assigned once any element is
+/// assigned.
+///
+/// Since loop bounds typically depend on template parameters, loops are
+/// assumed to execute at least once.
+pub fn find_use_before_assignment(cfg: &Cfg) -> ReportCollection {
+    debug!("running use-before-assignment analysis pass");
+    let loop_exits = get_loop_exits(cfg);
+    let assigned_in = run_reaching_definitions(cfg, &loop_exits);
+
+    let mut reads = Vec::new();
+    for basic_block in cfg.iter() {
+        // Unreachable blocks are ignored.
+        let Some(mut assigned) = assigned_in[basic_block.index()].clone() else {
+            continue;
+        };
+        for stmt in basic_block.iter() {
+            for var in uninitialized_reads(cfg, stmt, &assigned) {
+                reads.push(UninitializedRead { block: basic_block.index(), var });
+            }
+            transfer_statement(stmt, &mut assigned);
+        }
+    }
+    // Only report the first read of each variable.
+    let mut reports = ReportCollection::new();
+    let mut reported = HashSet::new();
+    for read in reads {
+        let name = read.var.name().without_version();
+        if !reported.insert(name.clone()) {
+            continue;
+        }
+        let conditions = find_uninitialized_path(cfg, &loop_exits, &name, read.block);
+        let is_signal = matches!(cfg.get_type(read.var.name()), Some(VariableType::Signal(..)));
+        reports.push(build_report(read.var, is_signal, conditions));
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+/// Returns the variables and signals definitely assigned on entry to each
+/// basic block.
+fn run_reaching_definitions(
+    cfg: &Cfg,
+    loop_exits: &HashMap<(Index, Index), Vec<Index>>,
+) -> Vec<Assigned> {
+    let mut assigned_in: Vec<Assigned> = vec![None; cfg.len()];
+    let mut assigned_out: Vec<Assigned> = vec![None; cfg.len()];
+    assigned_in[cfg.entry_block().index()] = Some(assigned_on_entry(cfg));
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for basic_block in cfg.iter() {
+            let index = basic_block.index();
+            if index != cfg.entry_block().index() {
+                let mut assigned: Assigned = None;
+                for predecessor in predecessors(loop_exits, basic_block) {
+                    let Some(other) = &assigned_out[predecessor] else {
+                        continue;
+                    };
+                    assigned = Some(match assigned {
+                        Some(assigned) => assigned.intersection(other).cloned().collect(),
+                        None => other.clone(),
+                    });
+                }
+                assigned_in[index] = assigned;
+            }
+            let Some(mut assigned) = assigned_in[index].clone() else {
+                continue;
+            };
+            for stmt in basic_block.iter() {
+                transfer_statement(stmt, &mut assigned);
+            }
+            if assigned_out[index].as_ref() != Some(&assigned) {
+                assigned_out[index] = Some(assigned);
+                changed = true;
+            }
+        }
+    }
+    assigned_in
+}
+
+/// Parameters and input signals are assigned on entry.
+fn assigned_on_entry(cfg: &Cfg) -> HashSet<VariableName> {
+    let mut assigned =
+        cfg.parameters().iter().map(VariableName::without_version).collect::<HashSet<_>>();
+    for (name, declaration) in cfg.declarations().iter() {
+        match declaration.variable_type() {
+            VariableType::Signal(SignalType::Input, _) | VariableType::Component => {
+                assigned.insert(name.without_version());
+            }
+            _ => {}
+        }
+    }
+    assigned
+}
+
+fn transfer_statement(stmt: &Statement, assigned: &mut HashSet<VariableName>) {
+    // Phi expressions do not correspond to assignments in the source.
+    if let Statement::Substitution { rhe: Expression::Phi { .. }, .. } = stmt {
+        return;
+    }
+    assigned.extend(stmt.variables_written().map(|var| var.name().without_version()));
+}
+
+/// Returns the local variables and signals read by the statement that are not
+/// definitely assigned.
+fn uninitialized_reads<'a>(
+    cfg: &Cfg,
+    stmt: &'a Statement,
+    assigned: &HashSet<VariableName>,
+) -> Vec<&'a VariableUse> {
+    use Statement::*;
+    let excluded = match stmt {
+        // Phi expressions do not correspond to reads in the source.
+        Substitution { rhe: Expression::Phi { .. }, .. } => return Vec::new(),
+        // Updating an array element does not read the element, and constraint
+        // assignments are not considered to read the assigned signal.
+        Substitution { var, rhe: Expression::Update { var: base, .. }, .. } => {
+            vec![var.without_version(), base.without_version()]
+        }
+        Substitution { var, .. } => vec![var.without_version()],
+        _ => Vec::new(),
+    };
+    let is_template = matches!(cfg.definition_type(), DefinitionType::Template);
+    let mut reads = stmt
+        .locals_read()
+        .iter()
+        .chain(stmt.signals_read().iter().filter(|_| is_template))
+        .filter(|var| {
+            let name = var.name().without_version();
+            !excluded.contains(&name) && !assigned.contains(&name)
+        })
+        .collect::<Vec<_>>();
+    reads.sort_by_key(|var| var.meta().file_location().start);
+    reads
+}
+
+/// Returns the successors of loop headers exiting the loop, mapped to the
+/// blocks at the end of the loop body. Since loops are assumed to execute at
+/// least once, the edge from the loop header to the exit block is replaced by
+/// edges from the end of the loop body to the exit block.
+fn get_loop_exits(cfg: &Cfg) -> HashMap<(Index, Index), Vec<Index>> {
+    let mut loop_exits = HashMap::new();
+    for header_block in cfg.iter() {
+        if !matches!(header_block.statements().last(), Some(Statement::IfThenElse { .. })) {
+            continue;
+        }
+        // The header block dominates the end of the loop body.
+        let latches = header_block
+            .predecessors()
+            .iter()
+            .map(|index| cfg.get_basic_block(*index).expect("in control-flow graph"))
+            .filter(|block| dominates(cfg, header_block.index(), block))
+            .collect::<Vec<_>>();
+        if latches.is_empty() {
+            continue;
+        }
+        for successor in header_block.successors() {
+            if !latches.iter().any(|latch| dominates(cfg, *successor, latch)) {
+                let latches = latches.iter().map(|latch| latch.index()).collect();
+                loop_exits.insert((header_block.index(), *successor), latches);
+            }
+        }
+    }
+    loop_exits
+}
+
+fn dominates(cfg: &Cfg, index: Index, basic_block: &BasicBlock) -> bool {
+    cfg.get_dominators(basic_block).iter().any(|block| block.index() == index)
+}
+
+/// Returns the predecessors of the basic block, where loop exit edges are
+/// replaced by edges from the end of the loop body.
+fn predecessors(
+    loop_exits: &HashMap<(Index, Index), Vec<Index>>,
+    basic_block: &BasicBlock,
+) -> Vec<Index> {
+    let mut predecessors = Vec::new();
+    for predecessor in basic_block.predecessors() {
+        match loop_exits.get(&(*predecessor, basic_block.index())) {
+            Some(latches) => predecessors.extend(latches),
+            None => predecessors.push(*predecessor),
+        }
+    }
+    predecessors
+}
+
+/// Finds a path from the entry block to the given block which does not assign
+/// the given variable, and returns the branch conditions along the path.
+fn find_uninitialized_path(
+    cfg: &Cfg,
+    loop_exits: &HashMap<(Index, Index), Vec<Index>>,
+    name: &VariableName,
+    target: Index,
+) -> Vec<String> {
+    let assigns = |basic_block: &BasicBlock| {
+        let mut assigned = HashSet::new();
+        for stmt in basic_block.iter() {
+            transfer_statement(stmt, &mut assigned);
+        }
+        assigned.contains(name)
+    };
+    // Breadth-first search from the entry block, recording the predecessor of
+    // each visited block.
+    let entry = cfg.entry_block().index();
+    let mut parents = HashMap::new();
+    let mut queue = VecDeque::from([entry]);
+    let mut visited = HashSet::from([entry]);
+    while let Some(index) = queue.pop_front() {
+        if index == target {
+            break;
+        }
+        let basic_block = cfg.get_basic_block(index).expect("in control-flow graph");
+        if assigns(basic_block) {
+            continue;
+        }
+        for successor in cfg.iter() {
+            for predecessor in predecessors(loop_exits, successor) {
+                if predecessor == index && visited.insert(successor.index()) {
+                    parents.insert(successor.index(), index);
+                    queue.push_back(successor.index());
+                }
+            }
+        }
+    }
+    // Collect the branch conditions along the path. Loop conditions are
+    // ignored.
+    let mut conditions = Vec::new();
+    let mut index = target;
+    while let Some(parent) = parents.get(&index) {
+        let basic_block = cfg.get_basic_block(*parent).expect("in control-flow graph");
+        let is_loop_header = loop_exits.keys().any(|(header, _)| header == parent);
+        if let Some(Statement::IfThenElse { cond, true_index, .. }) =
+            basic_block.statements().last()
+        {
+            if !is_loop_header {
+                let value = if *true_index == index { "true" } else { "false" };
+                conditions.push(format!("`{cond}` is {value}"));
+            }
+        }
+        index = *parent;
+    }
+    conditions.reverse();
+    conditions
+}
+
+fn build_report(var: &VariableUse, is_signal: bool, conditions: Vec<String>) -> Report {
+    UseBeforeAssignmentWarning {
+        name: var.name().to_string(),
+        is_signal,
+        conditions,
+        file_id: var.meta().file_id(),
+        file_location: var.meta().file_location(),
+    }
+    .into_report()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_use_before_assignment() {
+        let src = r#"
+            function f(a) {
+                var x;
+                if (a > 0) {
+                    x = 1;
+                }
+                return x;
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            function f(a) {
+                var x;
+                if (a > 0) {
+                    x = 1;
+                } else {
+                    x = 2;
+                }
+                return x;
+            }
+        "#;
+        validate_reports(src, 0);
+
+        let src = r#"
+            template T(n) {
+                signal input in[n];
+                signal output out[n];
+                signal tmp;
+
+                for (var i = 0; i < n; i++) {
+                    out[i] <== in[i];
+                }
+                out[0] * tmp === 0;
+                tmp <== in[0] * in[1];
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            template T(n) {
+                signal input in;
+                signal output out;
+
+                var acc[n];
+                for (var i = 0; i < n; i++) {
+                    acc[i] = i * in;
+                }
+                out <== acc[0] * in;
+            }
+        "#;
+        validate_reports(src, 0);
+
+        let src = r#"
+            template T(n) {
+                signal input in;
+                signal output out;
+
+                var acc = 0;
+                for (var i = 0; i < n; i++) {
+                    var tmp;
+                    for (var j = 0; j < n; j++) {
+                        if (j > i) {
+                            acc += j;
+                        }
+                        tmp = j;
+                    }
+                    acc += tmp;
+                }
+                out <== acc * in;
+            }
+        "#;
+        validate_reports(src, 0);
+    }
+
+    fn validate_reports(src: &str, expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_use_before_assignment(&cfg);
+
+        assert_eq!(reports.len(), expected_len);
+    }
+}
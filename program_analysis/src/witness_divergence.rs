@@ -0,0 +1,319 @@
+use log::debug;
+use num_traits::{One, Zero};
+use std::collections::{HashMap, HashSet};
+
+use program_structure::cfg::Cfg;
+use program_structure::report_code::ReportCode;
+use program_structure::report::{Report, ReportCollection};
+use program_structure::file_definition::{FileID, FileLocation};
+use program_structure::ir::value_meta::{ValueMeta, ValueReduction};
+use program_structure::ir::variable_meta::VariableMeta;
+use program_structure::ir::*;
+
+use crate::hint_only_constraints::local_dependencies;
+use crate::unconstrained_bitwise_ops::{binary_constraint, find_bitwise_op};
+
+pub struct WitnessConstraintDivergenceWarning {
+    signal_name: VariableName,
+    is_boolean: bool,
+    unconstrained_dependencies: Vec<VariableName>,
+    file_id: Option<FileID>,
+    file_location: FileLocation,
+}
+
+impl WitnessConstraintDivergenceWarning {
+    pub fn into_report(self) -> Report {
+        let mut report = Report::warning(
+            format!(
+                "The constraints on the signal `{}` admit more values than the expression assigned to it.",
+                self.signal_name
+            ),
+            ReportCode::WitnessConstraintDivergence,
+        );
+        if let Some(file_id) = self.file_id {
+            report.add_primary(
+                self.file_location,
+                file_id,
+                format!("The signal `{}` is assigned here.", self.signal_name),
+            );
+        }
+        if self.is_boolean {
+            report.add_note(format!(
+                "The assigned expression is either 0 or 1, but `{}` is never constrained to be binary. Consider adding the constraint `{} * ({} - 1) === 0`.",
+                self.signal_name, self.signal_name, self.signal_name
+            ));
+        }
+        if !self.unconstrained_dependencies.is_empty() {
+            let names = self
+                .unconstrained_dependencies
+                .iter()
+                .map(|name| format!("`{name}`"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            report.add_note(format!(
+                "The assigned expression depends on {names}, but no constraint relates `{}` to {names}.",
+                self.signal_name
+            ));
+        }
+        report
+    }
+}
+
+/// This analysis pass compares the expression assigned to each signal during
+/// witness generation (using `<--`) with the constraints mentioning the
+/// signal, and reports the assignment if the constraints admit strictly more
+/// values than the assigned expression. Currently, two kinds of divergences are
+/// detected:
+///
+///   1. The assigned expression is boolean (like `a < b` or `a == b`), but the
+///      signal is never constrained to be binary. (Bitwise operations on
+///      signals are reported by the unconstrained bitwise operation pass.)
+///   2. The assigned expression depends on a signal or component which is
+///      never related to the assigned signal by the constraints (in one or
+///      more steps, either directly or through local variables).
+///
+/// Signals which never occur in a constraint are reported by other passes and
+/// are ignored here.
+pub fn find_witness_constraint_divergence(cfg: &Cfg) -> ReportCollection {
+    debug!("running witness/constraint divergence analysis pass");
+    let mut assignments = Vec::new();
+    let mut dependencies = HashMap::<VariableName, HashSet<VariableName>>::new();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            let Statement::Substitution { meta, var, op, rhe } = stmt else {
+                continue;
+            };
+            if matches!(op, AssignOp::AssignSignal) {
+                let rhe = match rhe {
+                    Expression::Update { rhe, .. } => rhe,
+                    rhe => rhe,
+                };
+                assignments.push((meta, var.without_version(), rhe));
+            }
+            if meta.type_knowledge().is_local() {
+                dependencies
+                    .entry(var.clone())
+                    .or_default()
+                    .extend(stmt.variables_read().map(|var_use| var_use.name().clone()));
+            }
+        }
+    }
+    let mut reports = ReportCollection::new();
+    if assignments.is_empty() {
+        return reports;
+    }
+
+    // Signals and components occurring together in a constraint (either
+    // directly or through local variables) are related.
+    let mut binary = HashSet::new();
+    let mut related = HashMap::<VariableName, HashSet<VariableName>>::new();
+    for basic_block in cfg.iter() {
+        for stmt in basic_block.iter() {
+            match stmt {
+                Statement::ConstraintEquality { lhe, rhe, .. } => {
+                    binary.extend(binary_constraint(lhe, rhe));
+                    binary.extend(binary_constraint(rhe, lhe));
+                }
+                Statement::Substitution { meta, op: AssignOp::AssignConstraintSignal, .. } => {
+                    // Signals passed to a component may be constrained to be
+                    // binary by the component.
+                    if meta.type_knowledge().is_component() {
+                        binary.extend(
+                            stmt.signals_read()
+                                .iter()
+                                .map(|var_use| var_use.name().without_version()),
+                        );
+                    }
+                }
+                _ => continue,
+            }
+            let mut names = signal_dependencies(cfg, &dependencies, stmt);
+            names.extend(
+                stmt.signals_written()
+                    .iter()
+                    .chain(stmt.components_written().iter())
+                    .map(|var_use| var_use.name().without_version()),
+            );
+            for name in &names {
+                related.entry(name.clone()).or_default().extend(names.iter().cloned());
+            }
+        }
+    }
+
+    let mut reported = HashSet::new();
+    for (meta, signal_name, rhe) in assignments {
+        if !related.contains_key(&signal_name) || reported.contains(&signal_name) {
+            continue;
+        }
+        let is_boolean = is_boolean(rhe) && find_bitwise_op(rhe).is_none();
+        let is_boolean = is_boolean && !binary.contains(&signal_name);
+        let reachable = reachable(&related, &signal_name);
+        let mut unconstrained_dependencies = signal_dependencies(cfg, &dependencies, rhe)
+            .into_iter()
+            .filter(|name| !reachable.contains(name))
+            .collect::<Vec<_>>();
+        unconstrained_dependencies.sort_by_key(ToString::to_string);
+        if is_boolean || !unconstrained_dependencies.is_empty() {
+            reported.insert(signal_name.clone());
+            reports.push(
+                WitnessConstraintDivergenceWarning {
+                    signal_name,
+                    is_boolean,
+                    unconstrained_dependencies,
+                    file_id: meta.file_id(),
+                    file_location: meta.file_location(),
+                }
+                .into_report(),
+            );
+        }
+    }
+    debug!("{} new reports generated", reports.len());
+    reports
+}
+
+/// Returns the signals and components read by the given statement or
+/// expression, either directly or through local variables.
+fn signal_dependencies<T: VariableMeta>(
+    cfg: &Cfg,
+    dependencies: &HashMap<VariableName, HashSet<VariableName>>,
+    value: &T,
+) -> HashSet<VariableName> {
+    let locals =
+        value.locals_read().iter().map(|var_use| var_use.name().clone()).collect::<Vec<_>>();
+    let mut names = value
+        .signals_read()
+        .iter()
+        .chain(value.components_read().iter())
+        .map(|var_use| var_use.name().without_version())
+        .collect::<HashSet<_>>();
+    names.extend(local_dependencies(cfg, dependencies, &locals));
+    names
+}
+
+/// Returns the signals and components related to the given signal in one or
+/// more steps.
+fn reachable(
+    related: &HashMap<VariableName, HashSet<VariableName>>,
+    signal_name: &VariableName,
+) -> HashSet<VariableName> {
+    let mut visited = HashSet::from([signal_name.clone()]);
+    let mut worklist = vec![signal_name.clone()];
+    while let Some(name) = worklist.pop() {
+        for next in related.get(&name).into_iter().flatten() {
+            if visited.insert(next.clone()) {
+                worklist.push(next.clone());
+            }
+        }
+    }
+    visited
+}
+
+/// Returns true if the expression always evaluates to either 0 or 1.
+fn is_boolean(expr: &Expression) -> bool {
+    use Expression::*;
+    use ExpressionInfixOpcode::*;
+    match expr {
+        InfixOp {
+            infix_op: Lesser | Greater | LesserEq | GreaterEq | Eq | NotEq | BoolAnd | BoolOr,
+            ..
+        } => true,
+        InfixOp { lhe, infix_op: BitAnd, rhe, .. } => is_one(lhe) || is_one(rhe),
+        PrefixOp { prefix_op: ExpressionPrefixOpcode::BoolNot, .. } => true,
+        SwitchOp { if_true, if_false, .. } => {
+            (is_boolean(if_true) || is_constant_bit(if_true))
+                && (is_boolean(if_false) || is_constant_bit(if_false))
+        }
+        _ => false,
+    }
+}
+
+fn is_one(expr: &Expression) -> bool {
+    matches!(expr.value(), Some(ValueReduction::FieldElement { value }) if value.is_one())
+}
+
+fn is_constant_bit(expr: &Expression) -> bool {
+    matches!(
+        expr.value(),
+        Some(ValueReduction::FieldElement { value }) if value.is_zero() || value.is_one()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::parse_definition;
+    use program_structure::{cfg::IntoCfg, constants::Curve};
+
+    use super::*;
+
+    #[test]
+    fn test_witness_constraint_divergence() {
+        let src = r#"
+            template Num2Bits(n) {
+                signal input in;
+                signal output out[n];
+                var lc1 = 0;
+                var e2 = 1;
+                for (var i = 0; i < n; i++) {
+                    out[i] <-- (in >> i) & 1;
+                    out[i] * (out[i] - 1) === 0;
+                    lc1 += out[i] * e2;
+                    e2 = e2 + e2;
+                }
+                lc1 === in;
+            }
+        "#;
+        validate_reports(src, 0);
+
+        let src = r#"
+            template IsZero() {
+                signal input in;
+                signal output out;
+                signal inv;
+                inv <-- in != 0 ? 1 / in : 0;
+                out <== -in * inv + 1;
+                in * out === 0;
+            }
+        "#;
+        validate_reports(src, 0);
+
+        let src = r#"
+            template LessThan() {
+                signal input a;
+                signal input b;
+                signal output out;
+                out <-- a < b ? 1 : 0;
+                out * (out - 1) === 0;
+            }
+        "#;
+        validate_reports(src, 1);
+
+        let src = r#"
+            template IsEqual() {
+                signal input a;
+                signal input b;
+                signal output out;
+                signal diff;
+                diff <== a - b;
+                out <-- a == b;
+                out * diff === 0;
+            }
+        "#;
+        validate_reports(src, 1);
+    }
+
+    fn validate_reports(src: &str, expected_len: usize) {
+        // Build CFG.
+        let mut reports = ReportCollection::new();
+        let cfg = parse_definition(src)
+            .unwrap()
+            .into_cfg(&Curve::default(), &mut reports)
+            .unwrap()
+            .into_ssa()
+            .unwrap();
+        assert!(reports.is_empty());
+
+        // Generate report collection.
+        let reports = find_witness_constraint_divergence(&cfg);
+        assert_eq!(reports.len(), expected_len);
+    }
+}
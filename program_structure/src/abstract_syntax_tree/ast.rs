@@ -1,6 +1,7 @@
 use crate::file_definition::FileLocation;
 use num_bigint::BigInt;
 use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 
 pub trait FillMeta {
     fn fill(&mut self, file_id: usize, elem_id: &mut usize);
@@ -14,7 +15,7 @@ pub fn build_main_component(public: Vec<String>, call: Expression) -> MainCompon
 
 pub type Version = (usize, usize, usize);
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Include {
     pub meta: Meta,
     pub path: String,
@@ -24,7 +25,7 @@ pub fn build_include(meta: Meta, path: String) -> Include {
     Include { meta, path }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Meta {
     pub elem_id: usize,
     pub start: usize,
@@ -85,7 +86,69 @@ impl Meta {
     }
 }
 
-#[derive(Clone)]
+/// A line or block comment in the source of a file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Comment {
+    pub location: FileLocation,
+    pub text: String,
+    /// True if the comment follows code on the same line, as in `x = 1; // ...`.
+    pub is_trailing: bool,
+    /// The start of the first token following the comment. This is the start
+    /// of the AST node (or closing delimiter) that the comment precedes.
+    pub attached_to: usize,
+}
+
+/// The comments and blank lines of a file, which are not part of the AST, but
+/// are retained by the parser. Comments are attached to AST nodes using the
+/// start offset of the token following each comment.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Trivia {
+    comments: Vec<Comment>,
+    blank_lines: BTreeSet<usize>,
+}
+
+impl Trivia {
+    /// Creates the trivia of a file from the comments of the file (in order)
+    /// and the offsets of the tokens and comments preceded by a blank line.
+    pub fn new(comments: Vec<Comment>, blank_lines: BTreeSet<usize>) -> Trivia {
+        Trivia { comments, blank_lines }
+    }
+
+    pub fn comments(&self) -> &[Comment] {
+        &self.comments
+    }
+
+    /// Returns the comments contained in the given location.
+    pub fn comments_in(&self, location: &FileLocation) -> &[Comment] {
+        let start =
+            self.comments.partition_point(|comment| comment.location.start < location.start);
+        let end = self.comments.partition_point(|comment| comment.location.end <= location.end);
+        &self.comments[start..end.max(start)]
+    }
+
+    /// Returns the comments on the lines immediately preceding the node
+    /// starting at the given offset (like documentation comments).
+    pub fn leading_comments(&self, start: usize) -> &[Comment] {
+        let end = self.comments.partition_point(|comment| comment.location.start < start);
+        let mut first = end;
+        while first > 0 {
+            let comment = &self.comments[first - 1];
+            if comment.attached_to != start || comment.is_trailing {
+                break;
+            }
+            first -= 1;
+        }
+        &self.comments[first..end]
+    }
+
+    /// Returns true if the token (or comment) starting at the given offset is
+    /// preceded by a blank line.
+    pub fn has_blank_line_before(&self, offset: usize) -> bool {
+        self.blank_lines.contains(&offset)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct AST {
     pub meta: Meta,
     pub compiler_version: Option<Version>,
@@ -94,6 +157,7 @@ pub struct AST {
     pub includes: Vec<Include>,
     pub definitions: Vec<Definition>,
     pub main_component: Option<MainComponent>,
+    pub trivia: Trivia,
 }
 impl AST {
     pub fn new(
@@ -115,11 +179,12 @@ impl AST {
             includes,
             definitions,
             main_component,
+            trivia: Trivia::default(),
         }
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum Definition {
     Template {
         meta: Meta,
@@ -160,7 +225,7 @@ pub fn build_function(
     Definition::Function { meta, name, args, arg_location, body }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum Statement {
     IfThenElse {
         meta: Meta,
@@ -196,6 +261,14 @@ pub enum Statement {
         op: AssignOp,
         rhe: Expression,
     },
+    // Tuple assignments of the form `(a, b) <== C()(c)` are removed by the
+    // parser, and never occur in the AST of a parsed definition.
+    MultiSubstitution {
+        meta: Meta,
+        lhe: Expression,
+        op: AssignOp,
+        rhe: Expression,
+    },
     ConstraintEquality {
         meta: Meta,
         lhe: Expression,
@@ -229,14 +302,17 @@ pub enum SignalType {
     Intermediate,
 }
 
-#[derive(Copy, Clone, PartialEq, Ord, PartialOrd, Eq)]
+/// The list of tags declared for a signal (as in `signal input {binary} in`).
+pub type TagList = Vec<String>;
+
+#[derive(Clone, PartialEq, Ord, PartialOrd, Eq, Serialize, Deserialize)]
 pub enum VariableType {
     Var,
-    Signal(SignalType, SignalElementType),
+    Signal(SignalType, SignalElementType, TagList),
     Component,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum Expression {
     InfixOp {
         meta: Meta,
@@ -274,9 +350,25 @@ pub enum Expression {
         meta: Meta,
         values: Vec<Expression>,
     },
+    // Anonymous components of the form `C(params)(signals)` are rewritten as
+    // explicit component instantiations by the parser, and never occur in the
+    // AST of a parsed definition.
+    AnonymousComponent {
+        meta: Meta,
+        id: String,
+        params: Vec<Expression>,
+        signals: Vec<Expression>,
+    },
+    // Tuples only occur in tuple assignments, and are removed by the parser
+    // together with the assignment. The tuple `(a, _)` is represented using
+    // the variable name `_` for the ignored value.
+    Tuple {
+        meta: Meta,
+        values: Vec<Expression>,
+    },
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum Access {
     ComponentAccess(String),
     ArrayAccess(Expression),
@@ -288,14 +380,14 @@ pub fn build_array_access(expr: Expression) -> Access {
     Access::ArrayAccess(expr)
 }
 
-#[derive(Copy, Clone, Eq, PartialEq)]
+#[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum AssignOp {
     AssignVar,
     AssignSignal,
     AssignConstraintSignal,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ExpressionInfixOpcode {
     Mul,
     Div,
@@ -319,14 +411,14 @@ pub enum ExpressionInfixOpcode {
     BitXor,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ExpressionPrefixOpcode {
     Sub,
     BoolNot,
     Complement,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum LogArgument {
     LogStr(String),
     LogExp(Expression),
@@ -341,14 +433,14 @@ pub fn build_log_expression(expr: Expression) -> LogArgument {
 }
 
 // Knowledge buckets
-#[derive(Copy, Clone, PartialOrd, PartialEq, Ord, Eq)]
+#[derive(Copy, Clone, PartialOrd, PartialEq, Ord, Eq, Serialize, Deserialize)]
 pub enum TypeReduction {
     Variable,
     Component,
     Signal,
 }
 
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Serialize, Deserialize)]
 pub struct TypeKnowledge {
     reduces_to: Option<TypeReduction>,
 }
@@ -377,7 +469,7 @@ impl TypeKnowledge {
     }
 }
 
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Serialize, Deserialize)]
 pub struct MemoryKnowledge {
     concrete_dimensions: Option<Vec<usize>>,
     full_length: Option<usize>,
@@ -18,3 +18,10 @@ impl AST {
         (self.meta, self.compiler_version, self.includes, self.definitions, self.main_component)
     }
 }
+
+impl Definition {
+    /// Returns true if the definition is a template marked as `parallel`.
+    pub fn is_parallel(&self) -> bool {
+        matches!(self, Definition::Template { parallel: true, .. })
+    }
+}
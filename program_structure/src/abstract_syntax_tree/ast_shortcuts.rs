@@ -56,7 +56,7 @@ pub fn split_declaration_into_single_nodes(
 
     for symbol in symbols {
         let with_meta = meta.clone();
-        let has_type = xtype;
+        let has_type = xtype.clone();
         let name = symbol.name.clone();
         let dimensions = symbol.is_array;
         let possible_init = symbol.init;
@@ -48,3 +48,16 @@ pub fn build_call(meta: Meta, id: String, args: Vec<Expression>) -> Expression {
 pub fn build_array_in_line(meta: Meta, values: Vec<Expression>) -> Expression {
     ArrayInLine { meta, values }
 }
+
+pub fn build_anonymous_component(
+    meta: Meta,
+    id: String,
+    params: Vec<Expression>,
+    signals: Vec<Expression>,
+) -> Expression {
+    AnonymousComponent { meta, id, params, signals }
+}
+
+pub fn build_tuple(meta: Meta, values: Vec<Expression>) -> Expression {
+    Tuple { meta, values }
+}
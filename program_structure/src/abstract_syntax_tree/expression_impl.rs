@@ -12,7 +12,9 @@ impl Expression {
             | ParallelOp { meta, .. }
             | Number(meta, ..)
             | Call { meta, .. }
-            | ArrayInLine { meta, .. } => meta,
+            | ArrayInLine { meta, .. }
+            | AnonymousComponent { meta, .. }
+            | Tuple { meta, .. } => meta,
         }
     }
     pub fn get_mut_meta(&mut self) -> &mut Meta {
@@ -25,7 +27,9 @@ impl Expression {
             | ParallelOp { meta, .. }
             | Number(meta, ..)
             | Call { meta, .. }
-            | ArrayInLine { meta, .. } => meta,
+            | ArrayInLine { meta, .. }
+            | AnonymousComponent { meta, .. }
+            | Tuple { meta, .. } => meta,
         }
     }
 
@@ -68,6 +72,16 @@ impl Expression {
         use Expression::*;
         matches!(self, ParallelOp { .. })
     }
+
+    pub fn is_anonymous_component(&self) -> bool {
+        use Expression::*;
+        matches!(self, AnonymousComponent { .. })
+    }
+
+    pub fn is_tuple(&self) -> bool {
+        use Expression::*;
+        matches!(self, Tuple { .. })
+    }
 }
 
 impl FillMeta for Expression {
@@ -88,6 +102,10 @@ impl FillMeta for Expression {
             ArrayInLine { meta, values, .. } => {
                 fill_array_inline(meta, values, file_id, element_id)
             }
+            AnonymousComponent { meta, params, signals, .. } => {
+                fill_anonymous_component(meta, params, signals, file_id, element_id)
+            }
+            Tuple { meta, values, .. } => fill_tuple(meta, values, file_id, element_id),
         }
     }
 }
@@ -155,6 +173,29 @@ fn fill_array_inline(
     }
 }
 
+fn fill_anonymous_component(
+    meta: &mut Meta,
+    params: &mut [Expression],
+    signals: &mut [Expression],
+    file_id: usize,
+    element_id: &mut usize,
+) {
+    meta.set_file_id(file_id);
+    for p in params {
+        p.fill(file_id, element_id);
+    }
+    for s in signals {
+        s.fill(file_id, element_id);
+    }
+}
+
+fn fill_tuple(meta: &mut Meta, values: &mut [Expression], file_id: usize, element_id: &mut usize) {
+    meta.set_file_id(file_id);
+    for v in values {
+        v.fill(file_id, element_id);
+    }
+}
+
 fn fill_parallel(meta: &mut Meta, rhe: &mut Expression, file_id: usize, element_id: &mut usize) {
     meta.set_file_id(file_id);
     rhe.fill(file_id, element_id);
@@ -186,6 +227,10 @@ impl Display for Expression {
             }
             Call { id, args, .. } => write!(f, "{}({})", id, vec_to_string(args)),
             ArrayInLine { values, .. } => write!(f, "[{}]", vec_to_string(values)),
+            AnonymousComponent { id, params, signals, .. } => {
+                write!(f, "{}({})({})", id, vec_to_string(params), vec_to_string(signals))
+            }
+            Tuple { values, .. } => write!(f, "({})", vec_to_string(values)),
         }
     }
 }
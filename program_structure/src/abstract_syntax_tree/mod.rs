@@ -4,5 +4,6 @@ mod ast_impl;
 pub mod ast_shortcuts;
 pub mod expression_builders;
 mod expression_impl;
+pub mod printer;
 pub mod statement_builders;
 mod statement_impl;
@@ -0,0 +1,521 @@
+//! A pretty-printer rendering the AST back to canonical Circom.
+//!
+//! The parser desugars some constructs (like `for` loops, compound
+//! assignments, and declarations with initializers). These are recognized
+//! using the locations of the desugared nodes, and printed using the original
+//! syntax. Comments and (single) blank lines between statements are preserved
+//! using the trivia retained by the parser.
+
+use super::ast::*;
+
+const INDENT: &str = "    ";
+
+/// Renders the AST of a file as Circom, preserving comments. The source of the
+/// file is used to preserve the original form of number literals.
+pub fn print_ast(ast: &AST, src: &str) -> String {
+    let mut printer = Printer::new(src, &ast.trivia);
+    printer.print_ast(ast);
+    printer.output
+}
+
+/// Renders a single statement as Circom.
+pub fn print_statement(stmt: &Statement) -> String {
+    let trivia = Trivia::default();
+    let mut printer = Printer::new("", &trivia);
+    printer.print_statement(stmt);
+    printer.output
+}
+
+/// Renders a single expression as Circom, using as few parentheses as
+/// possible.
+pub fn print_expression(expr: &Expression) -> String {
+    let trivia = Trivia::default();
+    let printer = Printer::new("", &trivia);
+    printer.expression(expr)
+}
+
+struct Printer<'a> {
+    src: &'a str,
+    trivia: &'a Trivia,
+    next_comment: usize,
+    indent: usize,
+    output: String,
+}
+
+impl<'a> Printer<'a> {
+    fn new(src: &'a str, trivia: &'a Trivia) -> Printer<'a> {
+        Printer { src, trivia, next_comment: 0, indent: 0, output: String::new() }
+    }
+
+    fn print_ast(&mut self, ast: &AST) {
+        if let Some((major, minor, patch)) = ast.compiler_version {
+            self.separate(self.pragma_start("circom"), false);
+            self.line(&format!("pragma circom {major}.{minor}.{patch};"));
+        }
+        if ast.custom_gates {
+            self.separate(self.pragma_start("custom_templates"), false);
+            self.line("pragma custom_templates;");
+        }
+        for (index, include) in ast.includes.iter().enumerate() {
+            self.separate(include.meta.start, index == 0);
+            self.line(&format!("include \"{}\";", include.path));
+        }
+        for definition in &ast.definitions {
+            self.print_definition(definition);
+        }
+        if let Some((public, call)) = &ast.main_component {
+            self.separate(call.get_meta().start, true);
+            let public = if public.is_empty() {
+                String::new()
+            } else {
+                format!(" {{public [{}]}}", public.join(", "))
+            };
+            self.line(&format!("component main{public} = {};", self.expression(call)));
+        }
+        self.print_comments(usize::MAX, false);
+    }
+
+    fn print_definition(&mut self, definition: &Definition) {
+        let (meta, body) = match definition {
+            Definition::Template { meta, body, .. } | Definition::Function { meta, body, .. } => {
+                (meta, body)
+            }
+        };
+        self.separate(meta.start, true);
+        let header = match definition {
+            Definition::Template { name, args, parallel, is_custom_gate, .. } => {
+                let mut header = "template".to_string();
+                if *is_custom_gate {
+                    header.push_str(" custom");
+                }
+                if *parallel {
+                    header.push_str(" parallel");
+                }
+                format!("{header} {name}({})", args.join(", "))
+            }
+            Definition::Function { name, args, .. } => {
+                format!("function {name}({})", args.join(", "))
+            }
+        };
+        if self.print_body(&header, body) {
+            self.line("}");
+        }
+    }
+
+    /// Prints the header followed by the given body. Returns true if the body
+    /// is a block, in which case the caller is responsible for closing it.
+    fn print_body(&mut self, header: &str, body: &Statement) -> bool {
+        match body {
+            Statement::Block { meta, stmts } if !is_for_loop(body) => {
+                if header.is_empty() {
+                    self.line("{");
+                } else {
+                    self.line(&format!("{header} {{"));
+                }
+                self.indent += 1;
+                for stmt in stmts {
+                    self.print_statement(stmt);
+                }
+                // Comments before the closing brace belong to the block.
+                self.print_comments(meta.end.saturating_sub(1), false);
+                self.indent -= 1;
+                true
+            }
+            _ => {
+                self.line(header);
+                self.indent += 1;
+                self.print_statement(body);
+                self.indent -= 1;
+                false
+            }
+        }
+    }
+
+    fn print_statement(&mut self, stmt: &Statement) {
+        use Statement::*;
+        self.separate(stmt.get_meta().start, false);
+        match stmt {
+            IfThenElse { cond, if_case, else_case, .. } => {
+                self.print_if_then_else("", cond, if_case, else_case.as_deref())
+            }
+            While { cond, stmt, .. } => {
+                let header = format!("while ({})", self.expression(cond));
+                if self.print_body(&header, stmt) {
+                    self.line("}");
+                }
+            }
+            Block { stmts, .. } if is_for_loop(stmt) => {
+                let (init, While { cond, stmt: body, .. }) = (&stmts[0], &stmts[1]) else {
+                    unreachable!();
+                };
+                let Block { stmts: body, .. } = body.as_ref() else {
+                    unreachable!();
+                };
+                let header = format!(
+                    "for ({}; {}; {})",
+                    self.simple_statement(init),
+                    self.expression(cond),
+                    self.simple_statement(&body[1])
+                );
+                if self.print_body(&header, &body[0]) {
+                    self.line("}");
+                }
+            }
+            Block { .. } => {
+                if self.print_body("", stmt) {
+                    self.line("}");
+                }
+            }
+            _ => {
+                let stmt = self.simple_statement(stmt);
+                self.line(&format!("{stmt};"));
+            }
+        }
+    }
+
+    fn print_if_then_else(
+        &mut self,
+        prefix: &str,
+        cond: &Expression,
+        if_case: &Statement,
+        else_case: Option<&Statement>,
+    ) {
+        let header = format!("{prefix}if ({})", self.expression(cond));
+        let is_block = self.print_body(&header, if_case);
+        if let Some(else_case) = else_case {
+            // Comments before `else` are kept with the if-case.
+            self.print_comments(else_case.get_meta().start, false);
+        }
+        let prefix = if is_block { "} else" } else { "else" };
+        match else_case {
+            None if is_block => self.line("}"),
+            None => {}
+            Some(Statement::IfThenElse { cond, if_case, else_case, .. }) => {
+                self.print_if_then_else(&format!("{prefix} "), cond, if_case, else_case.as_deref());
+            }
+            Some(else_case) => {
+                let is_block = self.print_body(prefix, else_case);
+                if is_block {
+                    self.line("}");
+                }
+            }
+        }
+    }
+
+    /// Renders a statement which is not a compound statement, without the
+    /// terminating semicolon.
+    fn simple_statement(&self, stmt: &Statement) -> String {
+        use Statement::*;
+        match stmt {
+            Return { value, .. } => format!("return {}", self.expression(value)),
+            InitializationBlock { xtype, initializations, .. } => {
+                self.declaration(xtype, initializations)
+            }
+            Declaration { xtype, name, dimensions, .. } => {
+                format!("{} {name}{}", self.variable_type(xtype), self.dimensions(dimensions))
+            }
+            Substitution { meta, var, access, op, rhe } => {
+                let var = format!("{var}{}", self.access(access));
+                if let Some(shortcut) = self.assign_with_op_shortcut(meta, &var, op, rhe) {
+                    return shortcut;
+                }
+                let rhe_start = rhe.get_meta().start;
+                let is_reversed = rhe_start == meta.start
+                    || self.src.get(meta.start..rhe_start).is_some_and(|prefix| {
+                        prefix.chars().all(|c| c == '(' || c.is_whitespace())
+                    });
+                match op {
+                    AssignOp::AssignSignal if is_reversed => {
+                        format!("{} --> {var}", self.expression(rhe))
+                    }
+                    AssignOp::AssignConstraintSignal if is_reversed => {
+                        format!("{} ==> {var}", self.expression(rhe))
+                    }
+                    _ => format!("{var} {op} {}", self.expression(rhe)),
+                }
+            }
+            MultiSubstitution { lhe, op, rhe, .. } => {
+                format!("{} {op} {}", self.expression(lhe), self.expression(rhe))
+            }
+            ConstraintEquality { lhe, rhe, .. } => {
+                format!("{} === {}", self.expression(lhe), self.expression(rhe))
+            }
+            LogCall { args, .. } => {
+                let args = args
+                    .iter()
+                    .map(|arg| match arg {
+                        LogArgument::LogStr(message) => format!("\"{message}\""),
+                        LogArgument::LogExp(value) => self.expression(value),
+                    })
+                    .collect::<Vec<_>>();
+                format!("log({})", args.join(", "))
+            }
+            Assert { arg, .. } => format!("assert({})", self.expression(arg)),
+            // Compound statements are only expected in `for` loop headers.
+            IfThenElse { .. } | While { .. } | Block { .. } => print_statement(stmt),
+        }
+    }
+
+    /// Detects compound assignments like `x += 1` and `x++`, which are
+    /// desugared by the parser into an assignment sharing the location of the
+    /// original statement.
+    fn assign_with_op_shortcut(
+        &self,
+        meta: &Meta,
+        var: &str,
+        op: &AssignOp,
+        rhe: &Expression,
+    ) -> Option<String> {
+        use ExpressionInfixOpcode::*;
+        let Expression::InfixOp { meta: infix_meta, infix_op, rhe, .. } = rhe else {
+            return None;
+        };
+        if !matches!(op, AssignOp::AssignVar) || !same_location(meta, infix_meta) {
+            return None;
+        }
+        match (infix_op, rhe.as_ref()) {
+            (Add, Expression::Number(one_meta, _)) if same_location(meta, one_meta) => {
+                Some(format!("{var}++"))
+            }
+            (Sub, Expression::Number(one_meta, _)) if same_location(meta, one_meta) => {
+                Some(format!("{var}--"))
+            }
+            _ => Some(format!("{var} {infix_op}= {}", self.expression(rhe))),
+        }
+    }
+
+    /// Renders a declaration, merging each declared variable with its
+    /// initialization (if any).
+    fn declaration(&self, xtype: &VariableType, initializations: &[Statement]) -> String {
+        let mut symbols = Vec::new();
+        let mut initializations = initializations.iter().peekable();
+        while let Some(initialization) = initializations.next() {
+            let Statement::Declaration { name, dimensions, .. } = initialization else {
+                symbols.push(self.simple_statement(initialization));
+                continue;
+            };
+            let mut symbol = format!("{name}{}", self.dimensions(dimensions));
+            if let Some(Statement::Substitution { var, access, op, rhe, .. }) =
+                initializations.peek()
+            {
+                if var == name && access.is_empty() {
+                    symbol.push_str(&format!(" {op} {}", self.expression(rhe)));
+                    initializations.next();
+                }
+            }
+            symbols.push(symbol);
+        }
+        format!("{} {}", self.variable_type(xtype), symbols.join(", "))
+    }
+
+    fn variable_type(&self, xtype: &VariableType) -> String {
+        match xtype {
+            VariableType::Var => "var".to_string(),
+            VariableType::Component => "component".to_string(),
+            VariableType::Signal(signal_type, element_type, tags) => {
+                let mut result = "signal".to_string();
+                if matches!(element_type, SignalElementType::Binary) {
+                    result.push_str(":Binary");
+                }
+                if !matches!(signal_type, SignalType::Intermediate) {
+                    result.push_str(&format!(" {signal_type}"));
+                }
+                if !tags.is_empty() {
+                    result.push_str(&format!(" {{{}}}", tags.join(", ")));
+                }
+                result
+            }
+        }
+    }
+
+    fn dimensions(&self, dimensions: &[Expression]) -> String {
+        dimensions.iter().map(|dimension| format!("[{}]", self.expression(dimension))).collect()
+    }
+
+    fn access(&self, access: &[Access]) -> String {
+        access
+            .iter()
+            .map(|access| match access {
+                Access::ArrayAccess(index) => format!("[{}]", self.expression(index)),
+                Access::ComponentAccess(name) => format!(".{name}"),
+            })
+            .collect()
+    }
+
+    fn expression(&self, expr: &Expression) -> String {
+        self.expression_at(expr, PARALLEL)
+    }
+
+    /// Renders the expression, adding parentheses if the precedence level of
+    /// the expression is greater than `max_level`.
+    fn expression_at(&self, expr: &Expression, max_level: usize) -> String {
+        use Expression::*;
+        let level = precedence(expr);
+        let result = match expr {
+            InfixOp { lhe, infix_op, rhe, .. } => {
+                // Infix operators are left-associative.
+                format!(
+                    "{} {infix_op} {}",
+                    self.expression_at(lhe, level),
+                    self.expression_at(rhe, level - 1)
+                )
+            }
+            PrefixOp { prefix_op, rhe, .. } => {
+                format!("{prefix_op}{}", self.expression_at(rhe, level - 1))
+            }
+            InlineSwitchOp { cond, if_true, if_false, .. } => format!(
+                "{} ? {} : {}",
+                self.expression_at(cond, level - 1),
+                self.expression_at(if_true, level - 1),
+                self.expression_at(if_false, level - 1)
+            ),
+            ParallelOp { rhe, .. } => format!("parallel {}", self.expression_at(rhe, level - 1)),
+            Variable { name, access, .. } => format!("{name}{}", self.access(access)),
+            Number(meta, value) => {
+                // Hexadecimal literals are preserved.
+                let literal = self.src.get(meta.start..).unwrap_or_default();
+                let literal =
+                    literal.chars().take_while(char::is_ascii_alphanumeric).collect::<String>();
+                if literal.starts_with("0x") {
+                    literal
+                } else {
+                    value.to_string()
+                }
+            }
+            Call { id, args, .. } => format!("{id}({})", self.expressions(args)),
+            ArrayInLine { values, .. } => format!("[{}]", self.expressions(values)),
+            AnonymousComponent { id, params, signals, .. } => {
+                format!("{id}({})({})", self.expressions(params), self.expressions(signals))
+            }
+            Tuple { values, .. } => format!("({})", self.expressions(values)),
+        };
+        if level > max_level {
+            format!("({result})")
+        } else {
+            result
+        }
+    }
+
+    fn expressions(&self, exprs: &[Expression]) -> String {
+        exprs.iter().map(|expr| self.expression(expr)).collect::<Vec<_>>().join(", ")
+    }
+
+    /// Writes a line at the current indentation level.
+    fn line(&mut self, text: &str) {
+        for _ in 0..self.indent {
+            self.output.push_str(INDENT);
+        }
+        self.output.push_str(text);
+        self.output.push('\n');
+    }
+
+    /// Writes the comments occurring before the given offset in the source,
+    /// and separates the next item from the previous one by a blank line if
+    /// `blank_line` is set, or if they are separated by a blank line in the
+    /// source. Comments on the same line as preceding code are kept at the end
+    /// of the line.
+    fn separate(&mut self, offset: usize, blank_line: bool) {
+        if self.print_comments(offset, blank_line) || self.trivia.has_blank_line_before(offset) {
+            self.blank_line();
+        }
+    }
+
+    /// Writes the comments occurring before the given offset in the source.
+    /// Returns true if `blank_line` is set and no comments were written.
+    fn print_comments(&mut self, offset: usize, blank_line: bool) -> bool {
+        let mut blank_line = blank_line;
+        while let Some(comment) = self.trivia.comments().get(self.next_comment) {
+            if comment.location.start >= offset {
+                break;
+            }
+            self.next_comment += 1;
+            if comment.is_trailing && self.output.ends_with('\n') {
+                self.output.pop();
+                self.output.push(' ');
+                self.output.push_str(&comment.text);
+                self.output.push('\n');
+                continue;
+            }
+            if blank_line || self.trivia.has_blank_line_before(comment.location.start) {
+                self.blank_line();
+            }
+            blank_line = false;
+            self.line(&comment.text);
+        }
+        blank_line
+    }
+
+    fn blank_line(&mut self) {
+        if !self.output.is_empty()
+            && !self.output.ends_with("\n\n")
+            && !self.output.ends_with("{\n")
+        {
+            self.output.push('\n');
+        }
+    }
+
+    /// Returns the offset of the given pragma in the source.
+    fn pragma_start(&self, name: &str) -> usize {
+        let mut offset = 0;
+        while let Some(index) = self.src[offset..].find("pragma") {
+            let start = offset + index;
+            let is_comment =
+                self.trivia.comments().iter().any(|comment| comment.location.contains(&start));
+            if !is_comment && self.src[start + "pragma".len()..].trim_start().starts_with(name) {
+                return start;
+            }
+            offset = start + "pragma".len();
+        }
+        0
+    }
+}
+
+/// The precedence level of `parallel` expressions (the loosest binding
+/// expressions).
+const PARALLEL: usize = 14;
+
+/// Returns the precedence level of the expression, following the Circom
+/// grammar. Expressions with a lower level bind tighter.
+fn precedence(expr: &Expression) -> usize {
+    use Expression::*;
+    use ExpressionInfixOpcode::*;
+    match expr {
+        ParallelOp { .. } => PARALLEL,
+        InlineSwitchOp { .. } => 13,
+        InfixOp { infix_op, .. } => match infix_op {
+            BoolOr => 12,
+            BoolAnd => 11,
+            Eq | NotEq | Lesser | Greater | LesserEq | GreaterEq => 10,
+            BitOr => 9,
+            BitXor => 8,
+            BitAnd => 7,
+            ShiftL | ShiftR => 6,
+            Add | Sub => 5,
+            Mul | Div | IntDiv | Mod => 4,
+            Pow => 3,
+        },
+        PrefixOp { .. } => 2,
+        Call { .. } | ArrayInLine { .. } | AnonymousComponent { .. } => 1,
+        Variable { .. } | Number(..) | Tuple { .. } => 0,
+    }
+}
+
+/// The parser desugars `for (init; cond; step) body` into the block
+/// `{ init; while (cond) { body; step; } }`, where the block and the while
+/// statement both have the location of the original loop.
+fn is_for_loop(stmt: &Statement) -> bool {
+    match stmt {
+        Statement::Block { meta, stmts } => match &stmts[..] {
+            [_, Statement::While { meta: while_meta, stmt, .. }] => {
+                same_location(meta, while_meta)
+                    && matches!(stmt.as_ref(), Statement::Block { stmts, .. } if stmts.len() == 2)
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+fn same_location(lhs: &Meta, rhs: &Meta) -> bool {
+    lhs.start == rhs.start && lhs.end == rhs.end
+}
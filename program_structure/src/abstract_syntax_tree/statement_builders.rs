@@ -49,6 +49,15 @@ pub fn build_substitution(
     Substitution { meta, var, access, op, rhe }
 }
 
+pub fn build_multi_substitution(
+    meta: Meta,
+    lhe: Expression,
+    op: AssignOp,
+    rhe: Expression,
+) -> Statement {
+    MultiSubstitution { meta, lhe, op, rhe }
+}
+
 pub fn build_constraint_equality(meta: Meta, lhe: Expression, rhe: Expression) -> Statement {
     ConstraintEquality { meta, lhe, rhe }
 }
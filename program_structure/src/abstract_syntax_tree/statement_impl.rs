@@ -11,6 +11,7 @@ impl Statement {
             | Return { meta, .. }
             | Declaration { meta, .. }
             | Substitution { meta, .. }
+            | MultiSubstitution { meta, .. }
             | LogCall { meta, .. }
             | Block { meta, .. }
             | Assert { meta, .. }
@@ -26,6 +27,7 @@ impl Statement {
             | Return { meta, .. }
             | Declaration { meta, .. }
             | Substitution { meta, .. }
+            | MultiSubstitution { meta, .. }
             | LogCall { meta, .. }
             | Block { meta, .. }
             | Assert { meta, .. }
@@ -58,6 +60,10 @@ impl Statement {
         use Statement::*;
         matches!(self, Substitution { .. })
     }
+    pub fn is_multi_substitution(&self) -> bool {
+        use Statement::*;
+        matches!(self, MultiSubstitution { .. })
+    }
     pub fn is_constraint_equality(&self) -> bool {
         use Statement::*;
         matches!(self, ConstraintEquality { .. })
@@ -96,6 +102,9 @@ impl FillMeta for Statement {
             Substitution { meta, access, rhe, .. } => {
                 fill_substitution(meta, access, rhe, file_id, element_id)
             }
+            MultiSubstitution { meta, lhe, rhe, .. } => {
+                fill_multi_substitution(meta, lhe, rhe, file_id, element_id)
+            }
             ConstraintEquality { meta, lhe, rhe } => {
                 fill_constraint_equality(meta, lhe, rhe, file_id, element_id)
             }
@@ -212,6 +221,18 @@ fn fill_block(meta: &mut Meta, stmts: &mut [Statement], file_id: usize, element_
     }
 }
 
+fn fill_multi_substitution(
+    meta: &mut Meta,
+    lhe: &mut Expression,
+    rhe: &mut Expression,
+    file_id: usize,
+    element_id: &mut usize,
+) {
+    meta.set_file_id(file_id);
+    lhe.fill(file_id, element_id);
+    rhe.fill(file_id, element_id);
+}
+
 fn fill_assert(meta: &mut Meta, arg: &mut Expression, file_id: usize, element_id: &mut usize) {
     meta.set_file_id(file_id);
     arg.fill(file_id, element_id);
@@ -226,6 +247,7 @@ impl Debug for Statement {
             Return { .. } => f.write_str("Statement::Return"),
             Declaration { .. } => f.write_str("Statement::Declaration"),
             Substitution { .. } => f.write_str("Statement::Substitution"),
+            MultiSubstitution { .. } => f.write_str("Statement::MultiSubstitution"),
             LogCall { .. } => f.write_str("Statement::LogCall"),
             Block { .. } => f.write_str("Statement::Block"),
             Assert { .. } => f.write_str("Statement::Assert"),
@@ -253,6 +275,7 @@ impl Display for Statement {
                 }
                 write!(f, " {op} {rhe}")
             }
+            MultiSubstitution { lhe, op, rhe, .. } => write!(f, "{lhe} {op} {rhe}"),
             LogCall { args, .. } => {
                 write!(f, "log(")?;
                 for (index, arg) in args.iter().enumerate() {
@@ -290,7 +313,7 @@ impl Display for VariableType {
         use VariableType::*;
         match self {
             Var => write!(f, "var"),
-            Signal(signal_type, _) => {
+            Signal(signal_type, _, _) => {
                 if matches!(signal_type, Intermediate) {
                     write!(f, "signal")
                 } else {
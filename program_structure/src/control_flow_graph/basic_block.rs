@@ -7,6 +7,8 @@ use crate::ir::degree_meta::DegreeEnvironment;
 use crate::ir::value_meta::ValueEnvironment;
 use crate::ssa::traits::DirectedGraphNode;
 
+use super::cfg::MAX_PROPAGATION_ITERATIONS;
+
 use crate::ir::variable_meta::{VariableMeta, VariableUses};
 use crate::ir::{Meta, Statement};
 
@@ -120,6 +122,7 @@ impl BasicBlock {
         trace!("propagating degree ranges for basic block {}", self.index());
         let mut result = false;
         let mut rerun = true;
+        let mut iterations = 0;
         while rerun {
             // Rerun value propagation if a single child node was updated.
             rerun = false;
@@ -128,6 +131,10 @@ impl BasicBlock {
             }
             // Return true if a single child node was updated.
             result = result || rerun;
+            iterations += 1;
+            if iterations >= MAX_PROPAGATION_ITERATIONS {
+                break;
+            }
         }
         result
     }
@@ -136,6 +143,7 @@ impl BasicBlock {
         trace!("propagating values for basic block {}", self.index());
         let mut result = false;
         let mut rerun = true;
+        let mut iterations = 0;
         while rerun {
             // Rerun value propagation if a single child node was updated.
             rerun = false;
@@ -144,6 +152,10 @@ impl BasicBlock {
             }
             // Return true if a single child node was updated.
             result = result || rerun;
+            iterations += 1;
+            if iterations >= MAX_PROPAGATION_ITERATIONS {
+                break;
+            }
         }
         result
     }
@@ -1,4 +1,5 @@
 use log::debug;
+use num_bigint::BigInt;
 use std::collections::HashSet;
 use std::fmt;
 
@@ -6,7 +7,7 @@ use crate::constants::UsefulConstants;
 use crate::file_definition::FileID;
 use crate::ir::declarations::{Declaration, Declarations};
 use crate::ir::degree_meta::{DegreeEnvironment, Degree, DegreeRange};
-use crate::ir::value_meta::ValueEnvironment;
+use crate::ir::value_meta::{ValueEnvironment, ValueReduction};
 use crate::ir::variable_meta::VariableMeta;
 use crate::ir::{VariableName, VariableType};
 use crate::ssa::dominator_tree::DominatorTree;
@@ -21,6 +22,12 @@ use super::ssa_impl::{Config, Environment};
 /// Basic block index type.
 pub type Index = usize;
 
+/// The maximum number of passes over the CFG (or a basic block) when
+/// propagating degrees or values. This ensures that pathological definitions
+/// (e.g. large unrolled loops) cannot stall the analysis. If the limit is
+/// reached, propagation stops and the remaining metadata is left unknown.
+pub(crate) const MAX_PROPAGATION_ITERATIONS: usize = 64;
+
 #[derive(Clone)]
 pub enum DefinitionType {
     Function,
@@ -45,14 +52,17 @@ pub struct Cfg {
     declarations: Declarations,
     basic_blocks: Vec<BasicBlock>,
     definition_type: DefinitionType,
+    is_parallel: bool,
     dominator_tree: DominatorTree<BasicBlock>,
 }
 
 impl Cfg {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         name: String,
         constants: UsefulConstants,
         definition_type: DefinitionType,
+        is_parallel: bool,
         parameters: Parameters,
         declarations: Declarations,
         basic_blocks: Vec<BasicBlock>,
@@ -65,6 +75,7 @@ impl Cfg {
             declarations,
             basic_blocks,
             definition_type,
+            is_parallel,
             dominator_tree,
         }
     }
@@ -149,6 +160,12 @@ impl Cfg {
         &self.definition_type
     }
 
+    /// Returns true if the CFG represents a template marked as `parallel`.
+    #[must_use]
+    pub fn is_parallel(&self) -> bool {
+        self.is_parallel
+    }
+
     #[must_use]
     pub fn constants(&self) -> &UsefulConstants {
         &self.constants
@@ -434,26 +451,61 @@ impl Cfg {
             }
         }
         let mut rerun = true;
+        let mut iterations = 0;
         while rerun {
             // Rerun degree propagation if a single child node was updated.
             rerun = false;
             for basic_block in self.iter_mut() {
                 rerun = rerun || basic_block.propagate_degrees(&mut env);
             }
+            iterations += 1;
+            if rerun && iterations >= MAX_PROPAGATION_ITERATIONS {
+                debug!(
+                    "degree propagation for `{}` stopped after {iterations} passes",
+                    self.name()
+                );
+                break;
+            }
         }
     }
 
+    /// Propagate the given parameter values along the CFG. This is used to
+    /// analyze a template instance, where the parameter values are known.
+    /// Here, `values` contains the value of each parameter (in order), or
+    /// `None` if the value of the parameter is unknown.
+    ///
+    /// Note that this must be called after the CFG has been converted to SSA.
+    pub fn propagate_parameter_values(&mut self, values: &[Option<BigInt>]) {
+        debug!("propagating parameter values for `{}`", self.name());
+        let mut env = ValueEnvironment::new(&self.constants);
+        for (name, value) in self.parameters.iter().zip(values) {
+            if let Some(value) = value {
+                env.add_variable(name, &ValueReduction::FieldElement { value: value.clone() });
+            }
+        }
+        self.propagate_values_with(env);
+    }
+
     /// Propagate constant values along the CFG.
     pub(crate) fn propagate_values(&mut self) {
         debug!("propagating constant values for `{}`", self.name());
-        let mut env = ValueEnvironment::new(&self.constants);
+        self.propagate_values_with(ValueEnvironment::new(&self.constants));
+    }
+
+    fn propagate_values_with(&mut self, mut env: ValueEnvironment) {
         let mut rerun = true;
+        let mut iterations = 0;
         while rerun {
             // Rerun value propagation if a single child node was updated.
             rerun = false;
             for basic_block in self.iter_mut() {
                 rerun = rerun || basic_block.propagate_values(&mut env);
             }
+            iterations += 1;
+            if rerun && iterations >= MAX_PROPAGATION_ITERATIONS {
+                debug!("value propagation for `{}` stopped after {iterations} passes", self.name());
+                break;
+            }
         }
     }
 
@@ -0,0 +1,66 @@
+use std::fmt::Write;
+
+use crate::ir::Statement;
+
+use super::basic_block::BasicBlock;
+use super::cfg::Cfg;
+
+impl Cfg {
+    /// Returns a Graphviz DOT representation of the CFG. Each basic block is
+    /// labeled by its statements (including SSA versions), and the outgoing
+    /// edges of basic blocks ending with an if-statement are labeled by the
+    /// corresponding branch.
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        // Writing to a string cannot fail.
+        let _ = writeln!(dot, "digraph \"{}\" {{", escape(self.name()));
+        let _ = writeln!(dot, "    node [shape=box, fontname=\"monospace\"];");
+        for basic_block in self.iter() {
+            let _ = writeln!(
+                dot,
+                "    bb{} [label=\"{}\"];",
+                basic_block.index(),
+                block_label(basic_block)
+            );
+        }
+        for basic_block in self.iter() {
+            let branches = match basic_block.statements().last() {
+                Some(Statement::IfThenElse { true_index, false_index, .. }) => {
+                    Some((*true_index, *false_index))
+                }
+                _ => None,
+            };
+            let mut successors = basic_block.successors().iter().collect::<Vec<_>>();
+            successors.sort();
+            for successor in successors {
+                let label = match branches {
+                    Some((true_index, _)) if true_index == *successor => " [label=\"true\"]",
+                    Some((_, Some(false_index))) if false_index == *successor => {
+                        " [label=\"false\"]"
+                    }
+                    _ => "",
+                };
+                let _ = writeln!(dot, "    bb{} -> bb{successor}{label};", basic_block.index());
+            }
+        }
+        let _ = writeln!(dot, "}}");
+        dot
+    }
+}
+
+/// Returns the label of the basic block, with one left-justified line per
+/// statement.
+fn block_label(basic_block: &BasicBlock) -> String {
+    let mut label = format!("bb{}:\\l", basic_block.index());
+    for stmt in basic_block.iter() {
+        label.push_str(&escape(&format!("{stmt:?}")));
+        label.push_str("\\l");
+    }
+    label
+}
+
+/// Escapes a string for use in a quoted DOT identifier.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
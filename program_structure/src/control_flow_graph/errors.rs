@@ -9,7 +9,13 @@ use crate::ir::errors::IRError;
 #[derive(Debug, Error)]
 pub enum CFGError {
     #[error("The variable `{name}` is read before it is declared/written.")]
-    UndefinedVariableError { name: String, file_id: Option<FileID>, file_location: FileLocation },
+    UndefinedVariableError {
+        name: String,
+        file_id: Option<FileID>,
+        file_location: FileLocation,
+        // The closest declared name, if any.
+        suggestion: Option<String>,
+    },
     #[error("The variable name `{name}` contains invalid characters.")]
     InvalidVariableNameError { name: String, file_id: Option<FileID>, file_location: FileLocation },
     #[error("The declaration of the variable `{name}` shadows a previous declaration.")]
@@ -34,7 +40,7 @@ impl CFGError {
     pub fn produce_report(error: Self) -> Report {
         use CFGError::*;
         match error {
-            UndefinedVariableError { name, file_id, file_location } => {
+            UndefinedVariableError { name, file_id, file_location, suggestion } => {
                 let mut report = Report::error(
                     format!("The variable `{name}` is used before it is defined."),
                     ReportCode::UninitializedSymbolInExpression,
@@ -46,6 +52,9 @@ impl CFGError {
                         format!("The variable `{name}` is first seen here."),
                     );
                 }
+                if let Some(suggestion) = suggestion {
+                    report.add_note(format!("Did you mean `{suggestion}`?"));
+                }
                 report
             }
             InvalidVariableNameError { name, file_id, file_location } => {
@@ -87,7 +96,7 @@ impl CFGError {
                         Some("Shadowed variable is declared here.".to_string()),
                     );
                 }
-                report.add_note(format!("Consider renaming the second occurrence of `{name}`."));
+                report.add_help(format!("Consider renaming the second occurrence of `{name}`."));
                 report
             }
             ParameterNameCollisionError { name, file_id, file_location } => {
@@ -113,7 +122,7 @@ impl From<IRError> for CFGError {
     fn from(error: IRError) -> CFGError {
         match error {
             IRError::UndefinedVariableError { name, file_id, file_location } => {
-                CFGError::UndefinedVariableError { name, file_id, file_location }
+                CFGError::UndefinedVariableError { name, file_id, file_location, suggestion: None }
             }
             IRError::InvalidVariableNameError { name, file_id, file_location } => {
                 CFGError::InvalidVariableNameError { name, file_id, file_location }
@@ -80,7 +80,15 @@ impl TryLift<UsefulConstants> for &TemplateData {
             DefinitionType::Template
         };
         debug!("building CFG for template `{name}`");
-        try_lift_impl(name, definition_type, constants, parameters, body, reports)
+        try_lift_impl(
+            name,
+            definition_type,
+            self.is_parallel(),
+            constants,
+            parameters,
+            body,
+            reports,
+        )
     }
 }
 
@@ -98,7 +106,7 @@ impl TryLift<UsefulConstants> for &FunctionData {
         let body = self.get_body().clone();
 
         debug!("building CFG for function `{name}`");
-        try_lift_impl(name, DefinitionType::Function, constants, parameters, body, reports)
+        try_lift_impl(name, DefinitionType::Function, false, constants, parameters, body, reports)
     }
 }
 
@@ -122,6 +130,7 @@ impl TryLift<UsefulConstants> for Definition {
                 try_lift_impl(
                     name.clone(),
                     definition_type,
+                    self.is_parallel(),
                     constants,
                     self.into(),
                     body.clone(),
@@ -133,6 +142,7 @@ impl TryLift<UsefulConstants> for Definition {
                 try_lift_impl(
                     name.clone(),
                     DefinitionType::Function,
+                    false,
                     constants,
                     self.into(),
                     body.clone(),
@@ -146,6 +156,7 @@ impl TryLift<UsefulConstants> for Definition {
 fn try_lift_impl(
     name: String,
     definition_type: DefinitionType,
+    is_parallel: bool,
     constants: UsefulConstants,
     parameters: Parameters,
     mut body: ast::Statement,
@@ -163,6 +174,7 @@ fn try_lift_impl(
         name,
         constants,
         definition_type,
+        is_parallel,
         parameters,
         declarations,
         basic_blocks,
@@ -3,6 +3,7 @@ pub mod errors;
 pub mod parameters;
 
 mod cfg;
+mod dot;
 mod lifting;
 mod ssa_impl;
 mod unique_vars;
@@ -1,4 +1,5 @@
 use log::trace;
+use std::collections::HashSet;
 use std::convert::{TryFrom, TryInto};
 
 use super::errors::{CFGError, CFGResult};
@@ -8,6 +9,7 @@ use crate::ast::{Access, Expression, Meta, Statement, LogArgument};
 use crate::environment::VarEnvironment;
 use crate::report::{Report, ReportCollection};
 use crate::file_definition::{FileID, FileLocation};
+use crate::suggestions::closest_name;
 
 type Version = usize;
 
@@ -43,6 +45,8 @@ struct DeclarationEnvironment {
     // the same variable names. (See case 2 below.) If the variable is unique
     // the maximum version is `None` (i.e. the variable is not versioned).
     global_versions: VarEnvironment<Option<Version>>,
+    // Tracks undeclared variables which have already been reported.
+    undeclared: HashSet<String>,
 }
 
 impl DeclarationEnvironment {
@@ -51,9 +55,26 @@ impl DeclarationEnvironment {
             declarations: VarEnvironment::new(),
             scoped_versions: VarEnvironment::new(),
             global_versions: VarEnvironment::new(),
+            undeclared: HashSet::new(),
         }
     }
 
+    // Report the first use of each undeclared variable, together with the
+    // closest variable name in scope.
+    pub fn check_declared(&mut self, name: &str, meta: &Meta, reports: &mut ReportCollection) {
+        if self.get_declaration(name).is_some() || !self.undeclared.insert(name.to_string()) {
+            return;
+        }
+        let names = self.declarations.variable_iter().map(|(name, _)| name.as_str());
+        let suggestion = closest_name(name, names).map(ToString::to_string);
+        reports.push(CFGError::produce_report(CFGError::UndefinedVariableError {
+            name: name.to_string(),
+            file_id: meta.file_id,
+            file_location: meta.file_location(),
+            suggestion,
+        }));
+    }
+
     // Get the last declaration seen for the given variable.
     pub fn get_declaration(&self, name: &str) -> Option<&Declaration> {
         self.declarations.get_variable(name)
@@ -137,7 +158,7 @@ impl TryFrom<&Parameters> for DeclarationEnvironment {
 /// There are a number of different cases to consider.
 ///
 /// 1. The variable `x` has multiple declarations, where (at least) one
-/// declaration of `x` shadows another declaration. E.g.
+///    declaration of `x` shadows another declaration. E.g.
 ///
 /// ```rs
 /// function f(x) {
@@ -153,7 +174,7 @@ impl TryFrom<&Parameters> for DeclarationEnvironment {
 /// declaration and the second occurrence of `x` must be renamed.
 ///
 /// 2. The variable `x` has multiple declarations but no declaration of `x`
-/// shadows another declaration. E.g.
+///    shadows another declaration. E.g.
 ///
 /// ```rs
 /// function g(m) {
@@ -172,7 +193,7 @@ impl TryFrom<&Parameters> for DeclarationEnvironment {
 /// global uniqueness.
 ///
 /// 3. The variable `x` is only declared once. In this case the variable name is
-/// already unique and `x` should not be renamed.
+///    already unique and `x` should not be renamed.
 pub fn ensure_unique_variables(
     stmt: &mut Statement,
     param_data: &Parameters,
@@ -196,7 +217,7 @@ fn visit_statement(
         Declaration { meta, name, dimensions, .. } => {
             trace!("visiting declared variable `{name}`");
             for size in dimensions {
-                visit_expression(size, env);
+                visit_expression(size, env, reports);
             }
             // If the current declaration shadows a previous declaration of the same
             // variable we generate a new report.
@@ -215,8 +236,9 @@ fn visit_statement(
                 }
             }
         }
-        Substitution { var, rhe, access, .. } => {
+        Substitution { meta, var, rhe, access, .. } => {
             trace!("visiting assigned variable '{var}'");
+            env.check_declared(var, meta, reports);
             *var = match env.get_current_version(var) {
                 Some(version) => {
                     trace!("renaming assigned shadowing variable `{var}` to `{var}.{version}`");
@@ -226,28 +248,28 @@ fn visit_statement(
             };
             for access in access {
                 if let Access::ArrayAccess(index) = access {
-                    visit_expression(index, env);
+                    visit_expression(index, env, reports);
                 }
             }
-            visit_expression(rhe, env);
+            visit_expression(rhe, env, reports);
         }
         LogCall { args, .. } => {
             use LogArgument::*;
             for arg in args {
                 if let LogExp(value) = arg {
-                    visit_expression(value, env);
+                    visit_expression(value, env, reports);
                 }
             }
         }
         Return { value, .. } => {
-            visit_expression(value, env);
+            visit_expression(value, env, reports);
         }
-        ConstraintEquality { lhe, rhe, .. } => {
-            visit_expression(lhe, env);
-            visit_expression(rhe, env);
+        ConstraintEquality { lhe, rhe, .. } | MultiSubstitution { lhe, rhe, .. } => {
+            visit_expression(lhe, env, reports);
+            visit_expression(rhe, env, reports);
         }
         Assert { arg, .. } => {
-            visit_expression(arg, env);
+            visit_expression(arg, env, reports);
         }
         InitializationBlock { initializations, .. } => {
             for init in initializations {
@@ -255,7 +277,7 @@ fn visit_statement(
             }
         }
         While { cond, stmt, .. } => {
-            visit_expression(cond, env);
+            visit_expression(cond, env, reports);
             visit_statement(stmt, env, reports);
         }
         Block { stmts, .. } => {
@@ -266,7 +288,7 @@ fn visit_statement(
             env.remove_variable_block();
         }
         IfThenElse { cond, if_case, else_case, .. } => {
-            visit_expression(cond, env);
+            visit_expression(cond, env, reports);
             visit_statement(if_case, env, reports);
             if let Some(else_case) = else_case {
                 visit_statement(else_case, env, reports);
@@ -275,12 +297,17 @@ fn visit_statement(
     }
 }
 
-fn visit_expression(expr: &mut Expression, env: &DeclarationEnvironment) {
+fn visit_expression(
+    expr: &mut Expression,
+    env: &mut DeclarationEnvironment,
+    reports: &mut ReportCollection,
+) {
     use Access::*;
     use Expression::*;
     match expr {
-        Variable { name, access, .. } => {
+        Variable { meta, name, access } => {
             trace!("visiting variable '{name}'");
+            env.check_declared(name, meta, reports);
             *name = match env.get_current_version(name) {
                 Some(version) => {
                     trace!("renaming occurrence of variable `{name}` to `{name}.{version}`");
@@ -290,35 +317,40 @@ fn visit_expression(expr: &mut Expression, env: &DeclarationEnvironment) {
             };
             for access in access {
                 if let ArrayAccess(index) = access {
-                    visit_expression(index, env);
+                    visit_expression(index, env, reports);
                 }
             }
         }
         InfixOp { lhe, rhe, .. } => {
-            visit_expression(lhe, env);
-            visit_expression(rhe, env);
+            visit_expression(lhe, env, reports);
+            visit_expression(rhe, env, reports);
         }
         PrefixOp { rhe, .. } => {
-            visit_expression(rhe, env);
+            visit_expression(rhe, env, reports);
         }
         InlineSwitchOp { cond, if_true, if_false, .. } => {
-            visit_expression(cond, env);
-            visit_expression(if_true, env);
-            visit_expression(if_false, env);
+            visit_expression(cond, env, reports);
+            visit_expression(if_true, env, reports);
+            visit_expression(if_false, env, reports);
         }
         Number(_, _) => {}
         Call { args, .. } => {
             for arg in args {
-                visit_expression(arg, env);
+                visit_expression(arg, env, reports);
             }
         }
-        ArrayInLine { values, .. } => {
+        ArrayInLine { values, .. } | Tuple { values, .. } => {
             for value in values {
-                visit_expression(value, env);
+                visit_expression(value, env, reports);
+            }
+        }
+        AnonymousComponent { params, signals, .. } => {
+            for value in params.iter_mut().chain(signals) {
+                visit_expression(value, env, reports);
             }
         }
         ParallelOp { rhe, .. } => {
-            visit_expression(rhe, env);
+            visit_expression(rhe, env, reports);
         }
     }
 }
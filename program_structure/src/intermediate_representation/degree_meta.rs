@@ -17,35 +17,32 @@ pub enum Degree {
 // Degrees are linearly ordered.
 impl PartialOrd<Degree> for Degree {
     fn partial_cmp(&self, other: &Degree) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Degrees are linearly ordered.
+impl Ord for Degree {
+    fn cmp(&self, other: &Degree) -> Ordering {
         use Degree::*;
         match (self, other) {
             // `Constant <= _`
-            (Constant, Constant) => Some(Ordering::Equal),
-            (Constant, Linear) | (Constant, Quadratic) | (Constant, NonQuadratic) => {
-                Some(Ordering::Less)
-            }
+            (Constant, Constant) => Ordering::Equal,
+            (Constant, Linear) | (Constant, Quadratic) | (Constant, NonQuadratic) => Ordering::Less,
             // `Linear <= _`
-            (Linear, Linear) => Some(Ordering::Equal),
-            (Linear, Quadratic) | (Linear, NonQuadratic) => Some(Ordering::Less),
+            (Linear, Linear) => Ordering::Equal,
+            (Linear, Quadratic) | (Linear, NonQuadratic) => Ordering::Less,
             // `Quadratic <= _`
-            (Quadratic, Quadratic) => Some(Ordering::Equal),
-            (Quadratic, NonQuadratic) => Some(Ordering::Less),
+            (Quadratic, Quadratic) => Ordering::Equal,
+            (Quadratic, NonQuadratic) => Ordering::Less,
             // `NonQuadratic <= _`
-            (NonQuadratic, NonQuadratic) => Some(Ordering::Equal),
+            (NonQuadratic, NonQuadratic) => Ordering::Equal,
             // All other cases are on the form `_ >= _`.
-            _ => Some(Ordering::Greater),
+            _ => Ordering::Greater,
         }
     }
 }
 
-// Degrees are linearly ordered.
-impl Ord for Degree {
-    fn cmp(&self, other: &Degree) -> Ordering {
-        // `Degree::partial_cmp` always returns `Some(_)`.
-        self.partial_cmp(other).unwrap()
-    }
-}
-
 impl Degree {
     pub fn add(&self, other: &Degree) -> Degree {
         max(*self, *other)
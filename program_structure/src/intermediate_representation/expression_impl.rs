@@ -47,6 +47,13 @@ impl Expression {
             | Phi { meta, .. } => meta,
         }
     }
+
+    /// Returns true if the expression is a template instantiation marked as
+    /// `parallel`.
+    #[must_use]
+    pub fn is_parallel(&self) -> bool {
+        matches!(self, Expression::Call { is_parallel: true, .. })
+    }
 }
 
 /// Syntactic equality for expressions.
@@ -71,9 +78,9 @@ impl PartialEq for Expression {
             }
             (Number(_, self_value), Number(_, other_value)) => self_value == other_value,
             (
-                Call { name: self_id, args: self_args, .. },
-                Call { name: other_id, args: other_args, .. },
-            ) => self_id == other_id && self_args == other_args,
+                Call { name: self_id, args: self_args, is_parallel: self_parallel, .. },
+                Call { name: other_id, args: other_args, is_parallel: other_parallel, .. },
+            ) => self_id == other_id && self_args == other_args && self_parallel == other_parallel,
             (InlineArray { values: self_values, .. }, InlineArray { values: other_values, .. }) => {
                 self_values == other_values
             }
@@ -386,7 +393,7 @@ impl VariableMeta for Expression {
                         trace!("adding `{name:?}` to components read");
                         components_read.insert(VariableUse::new(meta, name, &Vec::new()));
                     }
-                    Some(VariableType::Signal(_)) => {
+                    Some(VariableType::Signal(..)) => {
                         trace!("adding `{name:?}` to signals read");
                         signals_read.insert(VariableUse::new(meta, name, &Vec::new()));
                     }
@@ -436,7 +443,7 @@ impl VariableMeta for Expression {
                         trace!("adding `{var:?}` to components read");
                         components_read.insert(VariableUse::new(meta, var, access));
                     }
-                    Some(VariableType::Signal(_)) => {
+                    Some(VariableType::Signal(..)) => {
                         trace!("adding `{var:?}` to signals read");
                         signals_read.insert(VariableUse::new(meta, var, access));
                     }
@@ -472,7 +479,7 @@ impl VariableMeta for Expression {
                         trace!("adding `{var:?}` to components read");
                         components_read.insert(VariableUse::new(meta, var, &Vec::new()));
                     }
-                    Some(VariableType::Signal(_)) => {
+                    Some(VariableType::Signal(..)) => {
                         trace!("adding `{var:?}` to signals read");
                         signals_read.insert(VariableUse::new(meta, var, &Vec::new()));
                     }
@@ -526,21 +533,15 @@ impl ValueMeta for Expression {
         match self {
             InfixOp { meta, lhe, infix_op, rhe, .. } => {
                 let mut result = lhe.propagate_values(env) || rhe.propagate_values(env);
-                match infix_op.propagate_values(lhe.value(), rhe.value(), env) {
-                    Some(value) => {
-                        result = result || meta.value_knowledge_mut().set_reduces_to(value)
-                    }
-                    None => {}
+                if let Some(value) = infix_op.propagate_values(lhe.value(), rhe.value(), env) {
+                    result = result || meta.value_knowledge_mut().set_reduces_to(value)
                 }
                 result
             }
             PrefixOp { meta, prefix_op, rhe } => {
                 let mut result = rhe.propagate_values(env);
-                match prefix_op.propagate_values(rhe.value(), env) {
-                    Some(value) => {
-                        result = result || meta.value_knowledge_mut().set_reduces_to(value)
-                    }
-                    None => {}
+                if let Some(value) = prefix_op.propagate_values(rhe.value(), env) {
+                    result = result || meta.value_knowledge_mut().set_reduces_to(value)
                 }
                 result
             }
@@ -867,14 +868,19 @@ impl fmt::Debug for Expression {
             SwitchOp { cond, if_true, if_false, .. } => {
                 write!(f, "({cond:?}? {if_true:?} : {if_false:?})")
             }
-            Call { name: id, args, .. } => write!(f, "{}({})", id, vec_to_debug(args, ", ")),
+            Call { name: id, args, is_parallel, .. } => {
+                if *is_parallel {
+                    write!(f, "parallel ")?;
+                }
+                write!(f, "{}({})", id, vec_to_debug(args, ", "))
+            }
             InlineArray { values, .. } => write!(f, "[{}]", vec_to_debug(values, ", ")),
             Access { var, access, .. } => {
                 let access = access
                     .iter()
                     .map(|access| match access {
                         AccessType::ArrayAccess(index) => format!("{index:?}"),
-                        AccessType::ComponentAccess(name) => name.clone(),
+                        AccessType::ComponentAccess(name) => name.to_string(),
                     })
                     .collect::<Vec<_>>()
                     .join(", ");
@@ -885,7 +891,7 @@ impl fmt::Debug for Expression {
                     .iter()
                     .map(|access| match access {
                         AccessType::ArrayAccess(index) => format!("{index:?}"),
-                        AccessType::ComponentAccess(name) => name.clone(),
+                        AccessType::ComponentAccess(name) => name.to_string(),
                     })
                     .collect::<Vec<_>>()
                     .join(", ");
@@ -909,7 +915,12 @@ impl fmt::Display for Expression {
             SwitchOp { cond, if_true, if_false, .. } => {
                 write!(f, "({cond}? {if_true} : {if_false})")
             }
-            Call { name: id, args, .. } => write!(f, "{}({})", id, vec_to_display(args, ", ")),
+            Call { name: id, args, is_parallel, .. } => {
+                if *is_parallel {
+                    write!(f, "parallel ")?;
+                }
+                write!(f, "{}({})", id, vec_to_display(args, ", "))
+            }
             InlineArray { values, .. } => write!(f, "[{}]", vec_to_display(values, ", ")),
             Access { var, access, .. } => {
                 write!(f, "{var}")?;
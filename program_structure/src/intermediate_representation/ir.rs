@@ -3,6 +3,7 @@ use std::fmt;
 
 use crate::file_definition::{FileID, FileLocation};
 use crate::nonempty_vec::NonEmptyVec;
+use crate::symbol::Symbol;
 
 use super::degree_meta::DegreeKnowledge;
 use super::type_meta::TypeKnowledge;
@@ -103,7 +104,6 @@ impl std::hash::Hash for Meta {
     {
         self.location.hash(state);
         self.file_id.hash(state);
-        state.finish();
     }
 }
 
@@ -186,8 +186,9 @@ pub enum Expression {
     Variable { meta: Meta, name: VariableName },
     /// A constant field element.
     Number(Meta, BigInt),
-    /// A function call node.
-    Call { meta: Meta, name: String, args: Vec<Expression> },
+    /// A function call or template instantiation node. Template
+    /// instantiations may be marked as `parallel`.
+    Call { meta: Meta, name: String, args: Vec<Expression>, is_parallel: bool },
     /// An inline array on the form `[value, ...]`.
     InlineArray { meta: Meta, values: Vec<Expression> },
     /// An `Access` node represents an array access of the form `a[i]...[k]`.
@@ -209,9 +210,12 @@ pub enum Expression {
 pub enum VariableType {
     Local,
     Component,
-    Signal(SignalType),
+    Signal(SignalType, TagList),
 }
 
+/// The list of tags declared for a signal.
+pub type TagList = Vec<String>;
+
 impl fmt::Display for VariableType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         use SignalType::*;
@@ -219,12 +223,16 @@ impl fmt::Display for VariableType {
         match self {
             Local => write!(f, "var"),
             Component => write!(f, "component"),
-            Signal(signal_type) => {
+            Signal(signal_type, tag_list) => {
                 if matches!(signal_type, Intermediate) {
-                    write!(f, "signal")
+                    write!(f, "signal")?;
                 } else {
-                    write!(f, "signal {signal_type}")
+                    write!(f, "signal {signal_type}")?;
+                }
+                if !tag_list.is_empty() {
+                    write!(f, " {{{}}}", tag_list.join(", "))?;
                 }
+                Ok(())
             }
         }
     }
@@ -257,11 +265,11 @@ impl fmt::Display for SignalType {
 pub struct VariableName {
     /// This is the original name of the variable from the function or template
     /// AST.
-    name: String,
+    name: Symbol,
     /// For shadowing declarations we need to rename the shadowing variable
     /// since construction of the CFG requires all variable names to be unique.
     /// This is done by adding a suffix (on the form `_n`) to the variable name.
-    suffix: Option<String>,
+    suffix: Option<Symbol>,
     /// The version is used to track variable versions when we convert the CFG
     /// to SSA.
     version: Option<Version>,
@@ -271,17 +279,23 @@ impl VariableName {
     /// Returns a new variable name with the given name (without suffix or version).
     #[must_use]
     pub fn from_name<N: ToString>(name: N) -> VariableName {
-        VariableName { name: name.to_string(), suffix: None, version: None }
+        VariableName { name: Symbol::intern(&name.to_string()), suffix: None, version: None }
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        self.name.as_str()
     }
 
+    /// Returns the original name of the variable as an interned symbol.
     #[must_use]
-    pub fn name(&self) -> &String {
-        &self.name
+    pub fn symbol(&self) -> Symbol {
+        self.name
     }
 
     #[must_use]
-    pub fn suffix(&self) -> &Option<String> {
-        &self.suffix
+    pub fn suffix(&self) -> Option<&'static str> {
+        self.suffix.map(|suffix| suffix.as_str())
     }
 
     #[must_use]
@@ -293,7 +307,7 @@ impl VariableName {
     #[must_use]
     pub fn with_suffix<S: ToString>(&self, suffix: S) -> VariableName {
         let mut result = self.clone();
-        result.suffix = Some(suffix.to_string());
+        result.suffix = Some(Symbol::intern(&suffix.to_string()));
         result
     }
 
@@ -346,7 +360,7 @@ impl fmt::Debug for VariableName {
 #[derive(Clone, Hash, Eq, PartialEq)]
 pub enum AccessType {
     ArrayAccess(Box<Expression>),
-    ComponentAccess(String),
+    ComponentAccess(Symbol),
 }
 
 #[derive(Copy, Clone, Hash, Eq, PartialEq)]
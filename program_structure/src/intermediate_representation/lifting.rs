@@ -5,6 +5,7 @@ use crate::ir;
 use crate::ir::declarations::{Declaration, Declarations};
 use crate::ir::errors::{IRError, IRResult};
 use crate::nonempty_vec::NonEmptyVec;
+use crate::symbol::Symbol;
 
 /// The `TryLift` trait is used to lift an AST node to an IR node. This may fail
 /// and produce an error. Even if the operation succeeds it may produce warnings
@@ -117,6 +118,10 @@ impl TryLift<()> for ast::Statement {
                 // These need to be handled by the caller.
                 panic!("failed to convert AST statement to IR")
             }
+            ast::Statement::MultiSubstitution { .. } => {
+                // Tuple assignments are removed by the parser.
+                panic!("failed to convert AST tuple assignment to IR")
+            }
         }
     }
 }
@@ -174,6 +179,7 @@ impl TryLift<()> for ast::Expression {
                     .iter()
                     .map(|arg| arg.try_lift((), reports))
                     .collect::<IRResult<Vec<ir::Expression>>>()?,
+                is_parallel: false,
             }),
             ast::Expression::ArrayInLine { meta, values } => Ok(ir::Expression::InlineArray {
                 meta: meta.try_lift((), reports)?,
@@ -182,9 +188,19 @@ impl TryLift<()> for ast::Expression {
                     .map(|value| value.try_lift((), reports))
                     .collect::<IRResult<Vec<ir::Expression>>>()?,
             }),
-            // TODO: We currently treat `ParallelOp` as transparent and simply
-            // lift the underlying expression. Should this be added to the IR?
-            ast::Expression::ParallelOp { rhe, .. } => rhe.try_lift((), reports),
+            // `parallel` is only meaningful for template instantiations, so
+            // we track it on the lifted call and treat it as transparent
+            // otherwise.
+            ast::Expression::ParallelOp { rhe, .. } => match rhe.try_lift((), reports)? {
+                ir::Expression::Call { meta, name, args, .. } => {
+                    Ok(ir::Expression::Call { meta, name, args, is_parallel: true })
+                }
+                rhe => Ok(rhe),
+            },
+            ast::Expression::AnonymousComponent { .. } | ast::Expression::Tuple { .. } => {
+                // Anonymous components and tuples are removed by the parser.
+                panic!("failed to convert AST expression to IR")
+            }
         }
     }
 }
@@ -208,8 +224,8 @@ impl TryLift<()> for ast::VariableType {
         match self {
             ast::VariableType::Component => Ok(ir::VariableType::Component),
             ast::VariableType::Var => Ok(ir::VariableType::Local),
-            ast::VariableType::Signal(signal_type, _) => {
-                Ok(ir::VariableType::Signal(signal_type.try_lift((), reports)?))
+            ast::VariableType::Signal(signal_type, _, tag_list) => {
+                Ok(ir::VariableType::Signal(signal_type.try_lift((), reports)?, tag_list.clone()))
             }
         }
     }
@@ -262,7 +278,9 @@ impl TryLift<()> for ast::Access {
             ast::Access::ArrayAccess(expr) => {
                 Ok(ir::AccessType::ArrayAccess(Box::new(expr.try_lift((), reports)?)))
             }
-            ast::Access::ComponentAccess(s) => Ok(ir::AccessType::ComponentAccess(s.clone())),
+            ast::Access::ComponentAccess(s) => {
+                Ok(ir::AccessType::ComponentAccess(Symbol::intern(s)))
+            }
         }
     }
 }
@@ -52,7 +52,7 @@ impl TypeKnowledge {
     /// Returns true if the node is a signal.
     #[must_use]
     pub fn is_signal(&self) -> bool {
-        matches!(self.var_type, Some(VariableType::Signal(_)))
+        matches!(self.var_type, Some(VariableType::Signal(..)))
     }
 
     /// Returns true if the node is a component.
@@ -27,6 +27,15 @@ impl VariableUse {
     pub fn access(&self) -> &Vec<AccessType> {
         &self.access
     }
+
+    /// Returns the name of the accessed component member (e.g. `in` for the
+    /// use `c[i].in[j]`), if this is a component member access.
+    pub fn member(&self) -> Option<&str> {
+        self.access.iter().find_map(|access| match access {
+            AccessType::ComponentAccess(member) => Some(member.as_str()),
+            AccessType::ArrayAccess(_) => None,
+        })
+    }
 }
 
 impl fmt::Display for VariableUse {
@@ -1,6 +1,9 @@
 use codespan_reporting::files::{Files, SimpleFiles};
+use std::collections::HashMap;
 use std::ops::Range;
 
+use crate::ast::Trivia;
+
 pub type FileSource = String;
 pub type FilePath = String;
 pub type FileID = usize;
@@ -10,11 +13,12 @@ type FileStorage = SimpleFiles<FilePath, FileSource>;
 #[derive(Clone)]
 pub struct FileLibrary {
     files: FileStorage,
+    trivia: HashMap<FileID, Trivia>,
 }
 
 impl Default for FileLibrary {
     fn default() -> Self {
-        FileLibrary { files: FileStorage::new() }
+        FileLibrary { files: FileStorage::new(), trivia: HashMap::new() }
     }
 }
 
@@ -25,6 +29,44 @@ impl FileLibrary {
     pub fn add_file(&mut self, file_name: FilePath, file_source: FileSource) -> FileID {
         self.get_mut_files().add(file_name, file_source)
     }
+    /// Sets the trivia (comments and blank lines) of the given file.
+    pub fn set_trivia(&mut self, file_id: FileID, trivia: Trivia) {
+        self.trivia.insert(file_id, trivia);
+    }
+    /// Returns the trivia of the given file, if the file has been parsed.
+    pub fn get_trivia(&self, file_id: FileID) -> Option<&Trivia> {
+        self.trivia.get(&file_id)
+    }
+    /// Replace the source of the given file. Returns false if the file is not
+    /// tracked by the library. The trivia of the file is removed, since it may
+    /// no longer match the new source.
+    pub fn update_file(&mut self, file_id: FileID, file_source: FileSource) -> bool {
+        // `SimpleFiles` does not support updating files, so we rebuild the
+        // storage. File IDs are indices into the storage and are preserved.
+        let mut updated = false;
+        let mut files = FileStorage::new();
+        let mut current_id = 0;
+        while let Some(file) = self.files.get(current_id) {
+            if current_id == file_id {
+                files.add(file.name().clone(), file_source.clone());
+                updated = true;
+            } else {
+                files.add(file.name().clone(), file.source().clone());
+            }
+            current_id += 1;
+        }
+        self.files = files;
+        self.trivia.remove(&file_id);
+        updated
+    }
+    /// Returns the names of all files in the library, ordered by file ID.
+    pub fn file_names(&self) -> Vec<FilePath> {
+        let mut file_names = Vec::new();
+        while let Some(file) = self.files.get(file_names.len()) {
+            file_names.push(file.name().clone());
+        }
+        file_names
+    }
     pub fn get_line(&self, start: usize, file_id: FileID) -> Option<usize> {
         self.files.line_index(file_id, start).map(|lines| lines + 1)
     }
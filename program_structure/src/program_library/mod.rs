@@ -1,6 +1,7 @@
 use super::ast;
 pub mod report_code;
 pub mod report;
+pub mod rules;
 pub mod file_definition;
 pub mod function_data;
 pub mod program_archive;
@@ -1,19 +1,24 @@
 use anyhow::anyhow;
+use serde_derive::{Deserialize, Serialize};
 use std::cmp::Ordering;
+use std::fmt;
 use std::str::FromStr;
+use std::sync::atomic::{self, AtomicU8};
 
 use super::report_code::ReportCode;
 use super::file_definition::{FileID, FileLibrary, FileLocation};
+use crate::sym_file::Symbol;
 use atty;
 use codespan_reporting::diagnostic::{Diagnostic, Label};
 use codespan_reporting::term;
+use codespan_reporting::term::termcolor::ColorChoice;
 
 pub type ReportCollection = Vec<Report>;
 pub type DiagnosticCode = String;
 pub type ReportLabel = Label<FileID>;
 type ReportNote = String;
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MessageCategory {
     Error,
     Warning,
@@ -22,39 +27,37 @@ pub enum MessageCategory {
 
 /// Message categories are linearly ordered.
 impl PartialOrd for MessageCategory {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for MessageCategory {
+    fn cmp(&self, other: &Self) -> Ordering {
         use MessageCategory::*;
         match (self, other) {
             // `Info <= _`
-            (Info, Info) => Some(Ordering::Equal),
-            (Info, Warning) | (Info, Error) => Some(Ordering::Less),
+            (Info, Info) => Ordering::Equal,
+            (Info, Warning) | (Info, Error) => Ordering::Less,
             // `Warning <= _`
-            (Warning, Warning) => Some(Ordering::Equal),
-            (Warning, Error) => Some(Ordering::Less),
+            (Warning, Warning) => Ordering::Equal,
+            (Warning, Error) => Ordering::Less,
             // `Error <= _`
-            (Error, Error) => Some(Ordering::Equal),
+            (Error, Error) => Ordering::Equal,
             // All other cases are on the form `_ >= _`.
-            _ => Some(Ordering::Greater),
+            _ => Ordering::Greater,
         }
     }
 }
 
-impl Ord for MessageCategory {
-    fn cmp(&self, other: &Self) -> Ordering {
-        // `MessageCategory::partial_cmp` always returns `Some(_)`.
-        self.partial_cmp(other).unwrap()
-    }
-}
-
-impl ToString for MessageCategory {
-    fn to_string(&self) -> String {
+impl fmt::Display for MessageCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use MessageCategory::*;
         match self {
-            Error => "error",
-            Warning => "warning",
-            Info => "info",
+            Error => write!(f, "error"),
+            Warning => write!(f, "warning"),
+            Info => write!(f, "info"),
         }
-        .to_string()
     }
 }
 
@@ -71,14 +74,104 @@ impl FromStr for MessageCategory {
     }
 }
 
-#[derive(Clone)]
+/// Controls when colors are used in terminal output.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Use colors if stdout is a terminal.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    /// Returns the color choice used when writing to stdout.
+    #[must_use]
+    pub fn color_choice(&self) -> ColorChoice {
+        match self {
+            ColorMode::Always => ColorChoice::Always,
+            ColorMode::Never => ColorChoice::Never,
+            ColorMode::Auto if atty::is(atty::Stream::Stdout) => ColorChoice::Always,
+            ColorMode::Auto => ColorChoice::Never,
+        }
+    }
+}
+
+impl FromStr for ColorMode {
+    type Err = anyhow::Error;
+
+    fn from_str(mode: &str) -> Result<ColorMode, Self::Err> {
+        match mode.to_lowercase().as_str() {
+            "auto" => Ok(ColorMode::Auto),
+            "always" => Ok(ColorMode::Always),
+            "never" => Ok(ColorMode::Never),
+            _ => Err(anyhow!("unknown color mode '{mode}'")),
+        }
+    }
+}
+
+static COLOR_MODE: AtomicU8 = AtomicU8::new(ColorMode::Auto as u8);
+
+/// Sets the global color mode used when printing reports.
+pub fn set_color_mode(mode: ColorMode) {
+    COLOR_MODE.store(mode as u8, atomic::Ordering::Relaxed);
+}
+
+/// Returns the global color mode used when printing reports.
+#[must_use]
+pub fn color_mode() -> ColorMode {
+    match COLOR_MODE.load(atomic::Ordering::Relaxed) {
+        0 => ColorMode::Auto,
+        1 => ColorMode::Always,
+        _ => ColorMode::Never,
+    }
+}
+
+/// How a report was suppressed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SuppressionKind {
+    /// The report was suppressed in the source code (e.g. by a comment).
+    InSource,
+    /// The report was suppressed externally (e.g. by the allow list).
+    External,
+}
+
+/// A signal affected by a report (like an unconstrained signal). The signal
+/// name is relative to the template, and may refer to a component signal
+/// (like `n2b.out`). Array indices may be omitted, in which case the report
+/// refers to all elements of the array.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AffectedSignal {
+    pub template_name: String,
+    pub signal_name: String,
+    /// The compiled instances of the signal (if a symbol file is given).
+    #[serde(default)]
+    pub compiled_signals: Vec<Symbol>,
+}
+
+/// A suppression of a report, together with the reason for suppressing it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Suppression {
+    pub kind: SuppressionKind,
+    pub justification: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Report {
     category: MessageCategory,
     message: String,
+    #[serde(with = "primary_labels")]
     primary: Vec<ReportLabel>,
+    #[serde(with = "secondary_labels")]
     secondary: Vec<ReportLabel>,
     notes: Vec<ReportNote>,
+    #[serde(default)]
+    help: Vec<ReportNote>,
     code: ReportCode,
+    #[serde(default)]
+    suppression: Option<Suppression>,
+    #[serde(default)]
+    affected_signals: Vec<AffectedSignal>,
 }
 
 impl Report {
@@ -89,17 +182,16 @@ impl Report {
             primary: Vec::new(),
             secondary: Vec::new(),
             notes: Vec::new(),
+            help: Vec::new(),
             code,
+            suppression: None,
+            affected_signals: Vec::new(),
         }
     }
 
     pub fn print_reports(reports: &[Report], file_library: &FileLibrary, verbose: bool) {
-        use codespan_reporting::term::termcolor::{ColorChoice, StandardStream};
-        let writer = if atty::is(atty::Stream::Stdout) {
-            StandardStream::stdout(ColorChoice::Always)
-        } else {
-            StandardStream::stdout(ColorChoice::Never)
-        };
+        use codespan_reporting::term::termcolor::StandardStream;
+        let writer = StandardStream::stdout(color_mode().color_choice());
         let mut config = term::Config::default();
         let mut diagnostics = Vec::new();
         let files = file_library.to_storage();
@@ -159,6 +251,13 @@ impl Report {
         self
     }
 
+    /// Adds a suggestion on how to fix the issue. Suggestions are printed
+    /// after the notes of the report.
+    pub fn add_help(&mut self, help: String) -> &mut Self {
+        self.help.push(help);
+        self
+    }
+
     fn to_diagnostic(&self, verbose: bool) -> Diagnostic<FileID> {
         let mut labels = self.primary().clone();
         let mut secondary = self.secondary().clone();
@@ -173,6 +272,7 @@ impl Report {
         .with_labels(labels);
 
         let mut notes = self.notes().clone();
+        notes.extend(self.help().iter().map(|help| format!("help: {help}")));
         if verbose {
             // Add report code and note on `--allow ID`.
             notes.push(format!("To ignore this type of result, use `--allow {}`.", self.id()));
@@ -186,10 +286,20 @@ impl Report {
         &self.category
     }
 
+    pub fn set_category(&mut self, category: MessageCategory) -> &mut Self {
+        self.category = category;
+        self
+    }
+
     pub fn message(&self) -> &String {
         &self.message
     }
 
+    pub fn set_message(&mut self, message: String) -> &mut Self {
+        self.message = message;
+        self
+    }
+
     pub fn primary(&self) -> &Vec<ReportLabel> {
         &self.primary
     }
@@ -210,6 +320,10 @@ impl Report {
         &self.notes
     }
 
+    pub fn help(&self) -> &Vec<ReportNote> {
+        &self.help
+    }
+
     fn notes_mut(&mut self) -> &mut Vec<ReportNote> {
         &mut self.notes
     }
@@ -221,4 +335,172 @@ impl Report {
     pub fn id(&self) -> String {
         self.code.id()
     }
+
+    /// Marks the report as suppressed. Suppressed reports are typically
+    /// dropped, but may be included in the output for auditing purposes.
+    pub fn suppress(&mut self, kind: SuppressionKind, justification: String) -> &mut Self {
+        self.suppression = Some(Suppression { kind, justification });
+        self
+    }
+
+    pub fn suppression(&self) -> Option<&Suppression> {
+        self.suppression.as_ref()
+    }
+
+    /// Records that the report affects the given signal of the given
+    /// template. This is used to correlate reports with compiled circuits.
+    pub fn add_affected_signal(&mut self, template_name: &str, signal_name: &str) -> &mut Self {
+        self.affected_signals.push(AffectedSignal {
+            template_name: template_name.to_string(),
+            signal_name: signal_name.to_string(),
+            compiled_signals: Vec::new(),
+        });
+        self
+    }
+
+    pub fn affected_signals(&self) -> &Vec<AffectedSignal> {
+        &self.affected_signals
+    }
+
+    pub fn affected_signals_mut(&mut self) -> &mut Vec<AffectedSignal> {
+        &mut self.affected_signals
+    }
+}
+
+/// Sorting of report collections. (Since `ReportCollection` is a type alias,
+/// this is implemented as an extension trait.)
+pub trait SortReports {
+    /// Sorts the reports by the file path of the primary location, then the
+    /// byte offset of the primary location, and then the rule ID. (Remaining
+    /// ties are broken by the message.) Reports without a primary location
+    /// are sorted first. This ensures that the order of the reports does not
+    /// depend on the order in which definitions are analyzed.
+    fn sort(&mut self, file_library: &FileLibrary);
+}
+
+impl SortReports for ReportCollection {
+    fn sort(&mut self, file_library: &FileLibrary) {
+        use codespan_reporting::files::Files;
+        let storage = file_library.to_storage();
+        self.sort_by_cached_key(|report| {
+            let location = report.primary().first().map(|label| {
+                let path = storage.name(label.file_id).map(|name| name.to_string());
+                (path.unwrap_or_default(), label.range.start, label.range.end)
+            });
+            (location, report.id(), report.message().clone())
+        });
+    }
+}
+
+/// Labels are serialized as `(file_id, start, end, message)` tuples.
+mod labels {
+    use codespan_reporting::diagnostic::LabelStyle;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{FileID, ReportLabel};
+
+    type SerializedLabel = (FileID, usize, usize, String);
+
+    pub fn serialize<S: Serializer>(
+        labels: &[ReportLabel],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        labels
+            .iter()
+            .map(|label| (label.file_id, label.range.start, label.range.end, label.message.clone()))
+            .collect::<Vec<SerializedLabel>>()
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        style: LabelStyle,
+        deserializer: D,
+    ) -> Result<Vec<ReportLabel>, D::Error> {
+        let labels = Vec::<SerializedLabel>::deserialize(deserializer)?;
+        Ok(labels
+            .into_iter()
+            .map(|(file_id, start, end, message)| {
+                ReportLabel::new(style, file_id, start..end).with_message(message)
+            })
+            .collect())
+    }
+}
+
+mod primary_labels {
+    use codespan_reporting::diagnostic::LabelStyle;
+    use serde::{Deserializer, Serializer};
+
+    use super::ReportLabel;
+
+    pub fn serialize<S: Serializer>(
+        labels: &[ReportLabel],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        super::labels::serialize(labels, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<ReportLabel>, D::Error> {
+        super::labels::deserialize(LabelStyle::Primary, deserializer)
+    }
+}
+
+mod secondary_labels {
+    use codespan_reporting::diagnostic::LabelStyle;
+    use serde::{Deserializer, Serializer};
+
+    use super::ReportLabel;
+
+    pub fn serialize<S: Serializer>(
+        labels: &[ReportLabel],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        super::labels::serialize(labels, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<ReportLabel>, D::Error> {
+        super::labels::deserialize(LabelStyle::Secondary, deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_reports() {
+        let mut files = FileLibrary::new();
+        let b = files.add_file("b.circom".to_string(), "0123456789".to_string());
+        let a = files.add_file("a.circom".to_string(), "0123456789".to_string());
+        let report = |file_id, start, code| {
+            let mut report = Report::warning("message".to_string(), code);
+            report.add_primary(start..start + 1, file_id, "primary".to_string());
+            report
+        };
+        let mut reports = vec![
+            report(b, 1, ReportCode::UnusedVariableValue),
+            report(a, 5, ReportCode::ShadowingVariable),
+            report(a, 2, ReportCode::UnusedVariableValue),
+            report(a, 2, ReportCode::ShadowingVariable),
+            Report::warning("message".to_string(), ReportCode::CyclicInclude),
+        ];
+        reports.sort(&files);
+        let order = reports
+            .iter()
+            .map(|report| (report.primary().first().map(|label| label.range.start), report.id()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            order,
+            [
+                (None, "CS0050".to_string()),
+                (Some(2), "CS0001".to_string()),
+                (Some(2), "CS0006".to_string()),
+                (Some(5), "CS0001".to_string()),
+                (Some(1), "CS0006".to_string()),
+            ]
+        );
+    }
 }
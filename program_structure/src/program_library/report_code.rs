@@ -1,4 +1,46 @@
-#[derive(Copy, Clone)]
+use anyhow::anyhow;
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// The class of issue reported using a report code.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum RuleCategory {
+    /// Issues which may allow a malicious prover to generate a proof for an
+    /// invalid witness (like under-constrained signals).
+    Soundness,
+    /// Issues which may cause the circuit to behave differently than intended,
+    /// or fail to compile.
+    Correctness,
+    /// Issues affecting the readability and maintainability of the code.
+    Style,
+}
+
+impl fmt::Display for RuleCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use RuleCategory::*;
+        match self {
+            Soundness => write!(f, "soundness"),
+            Correctness => write!(f, "correctness"),
+            Style => write!(f, "style"),
+        }
+    }
+}
+
+impl FromStr for RuleCategory {
+    type Err = anyhow::Error;
+
+    fn from_str(category: &str) -> Result<RuleCategory, Self::Err> {
+        match category.to_lowercase().as_str() {
+            "soundness" => Ok(RuleCategory::Soundness),
+            "correctness" => Ok(RuleCategory::Correctness),
+            "style" => Ok(RuleCategory::Style),
+            _ => Err(anyhow!("unknown rule category '{category}'")),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
 pub enum ReportCode {
     AssertWrongType,
     ParseFail,
@@ -30,6 +72,7 @@ pub enum ReportCode {
     NonExistentSymbol,
     NoMainFoundInProject,
     NoCompilerVersionWarning,
+    IncompatibleCompilerVersion,
     MultipleMainInComponent,
     TemplateCallAsArgument,
     TemplateWrongNumberOfArguments,
@@ -70,6 +113,7 @@ pub enum ReportCode {
     ParameterNameCollision,
     FieldElementComparison,
     FieldElementArithmetic,
+    FieldElementOverflow,
     SignalAssignmentStatement,
     UnecessarySignalAssignment,
     UnusedVariableValue,
@@ -79,6 +123,50 @@ pub enum ReportCode {
     NonStrictBinaryConversion,
     CyclomaticComplexity,
     TooManyArguments,
+    OutOfBoundsArrayAccess,
+    SignalAssert,
+    ConstraintComparison,
+    TagViolation,
+    UnconstrainedOutputSignal,
+    UnconstrainedComponentOutput,
+    UnsafeBitWidth,
+    UncheckedComparatorInput,
+    DivisionByZero,
+    UnconstrainedInverse,
+    DeadStore,
+    UseBeforeAssignment,
+    InfiniteLoop,
+    ZeroIterationLoop,
+    AnalysisTimeout,
+    UnusedReturnValue,
+    DefinitionMisuse,
+    UninitializedComponent,
+    SignalDependentInstantiation,
+    PartialConditionalInstantiation,
+    ConditionalSignalDeclaration,
+    UnconstrainedBitwiseOperation,
+    HintOnlyConstraint,
+    SignalDependentLoop,
+    TrustBoundarySummary,
+    UnbalancedHint,
+    WitnessConstraintDivergence,
+    FreeSignalConstraint,
+    UnknownPublicSignal,
+    PrivateMainInput,
+    MainComponentArgument,
+    MainComponentInterface,
+    UnconstrainedMainInput,
+    LogStatement,
+    TodoComment,
+    CyclicInclude,
+    ConfusableName,
+    UnknownComponentSignal,
+    NamingConvention,
+    LargeDefinition,
+    DeepNesting,
+    AssignmentOperatorMismatch,
+    ComparisonInsteadOfConstraint,
+    UncheckedTemplateParameter,
 }
 
 impl ReportCode {
@@ -90,6 +178,7 @@ impl ReportCode {
             MultipleMainInComponent => "P1002",
             CompilerVersionError => "P1003",
             NoCompilerVersionWarning => "P1004",
+            IncompatibleCompilerVersion => "P1005",
             WrongTypesInAssignOperation => "T2000",
             UndefinedFunction => "T2001",
             UndefinedTemplate => "T2002",
@@ -164,7 +253,98 @@ impl ReportCode {
             CyclomaticComplexity => "CS0011",
             TooManyArguments => "CS0012",
             UnecessarySignalAssignment => "CS0013",
+            FieldElementOverflow => "CS0014",
+            OutOfBoundsArrayAccess => "CS0015",
+            SignalAssert => "CS0016",
+            ConstraintComparison => "CS0017",
+            TagViolation => "CS0018",
+            UnconstrainedOutputSignal => "CS0019",
+            UnconstrainedComponentOutput => "CS0020",
+            UnsafeBitWidth => "CS0021",
+            UncheckedComparatorInput => "CS0022",
+            DivisionByZero => "CS0023",
+            UnconstrainedInverse => "CS0024",
+            DeadStore => "CS0025",
+            UseBeforeAssignment => "CS0026",
+            InfiniteLoop => "CS0027",
+            ZeroIterationLoop => "CS0028",
+            AnalysisTimeout => "CS0029",
+            UnusedReturnValue => "CS0030",
+            DefinitionMisuse => "CS0031",
+            UninitializedComponent => "CS0032",
+            SignalDependentInstantiation => "CS0033",
+            PartialConditionalInstantiation => "CS0034",
+            ConditionalSignalDeclaration => "CS0035",
+            UnconstrainedBitwiseOperation => "CS0036",
+            HintOnlyConstraint => "CS0037",
+            SignalDependentLoop => "CS0038",
+            TrustBoundarySummary => "CS0039",
+            UnbalancedHint => "CS0040",
+            WitnessConstraintDivergence => "CS0041",
+            FreeSignalConstraint => "CS0042",
+            UnknownPublicSignal => "CS0043",
+            PrivateMainInput => "CS0044",
+            MainComponentArgument => "CS0045",
+            MainComponentInterface => "CS0046",
+            UnconstrainedMainInput => "CS0047",
+            LogStatement => "CS0048",
+            TodoComment => "CS0049",
+            CyclicInclude => "CS0050",
+            ConfusableName => "CS0051",
+            UnknownComponentSignal => "CS0052",
+            NamingConvention => "CS0053",
+            LargeDefinition => "CS0054",
+            DeepNesting => "CS0055",
+            AssignmentOperatorMismatch => "CS0056",
+            ComparisonInsteadOfConstraint => "CS0057",
+            UncheckedTemplateParameter => "CS0058",
         }
         .to_string()
     }
+
+    /// Returns the category of issues reported using this code. Soundness
+    /// issues are reported as errors by default.
+    pub fn rule_category(&self) -> RuleCategory {
+        use self::ReportCode::*;
+        match self {
+            UnconstrainedSignal
+            | NonStrictBinaryConversion
+            | FieldElementOverflow
+            | SignalAssert
+            | ConstraintComparison
+            | TagViolation
+            | UnconstrainedOutputSignal
+            | UnconstrainedComponentOutput
+            | UnsafeBitWidth
+            | UncheckedComparatorInput
+            | UnconstrainedInverse
+            | SignalDependentInstantiation
+            | PartialConditionalInstantiation
+            | ConditionalSignalDeclaration
+            | UnconstrainedBitwiseOperation
+            | HintOnlyConstraint
+            | SignalDependentLoop
+            | UnbalancedHint
+            | WitnessConstraintDivergence
+            | FreeSignalConstraint
+            | UnconstrainedMainInput
+            | ComparisonInsteadOfConstraint => RuleCategory::Soundness,
+            NoCompilerVersionWarning
+            | ShadowingVariable
+            | UnusedVariableValue
+            | UnusedParameterValue
+            | VariableWithoutSideEffect
+            | CyclomaticComplexity
+            | TooManyArguments
+            | UnecessarySignalAssignment
+            | LogStatement
+            | TodoComment
+            | PrivateMainInput
+            | ConfusableName
+            | NamingConvention
+            | LargeDefinition
+            | DeepNesting => RuleCategory::Style,
+            _ => RuleCategory::Correctness,
+        }
+    }
 }
@@ -0,0 +1,1455 @@
+use super::report::MessageCategory;
+use super::report_code::{ReportCode, RuleCategory};
+
+/// Metadata describing a class of results generated by Circomspect.
+pub struct Rule {
+    /// The report code used by results generated by the rule.
+    pub code: ReportCode,
+    /// A human-readable identifier for the rule (like `unconstrained-signal`).
+    pub name: &'static str,
+    /// The default severity of results generated by the rule.
+    pub category: MessageCategory,
+    /// A one-line summary of the rule.
+    pub summary: &'static str,
+    /// A longer description of the issue detected by the rule.
+    pub description: &'static str,
+    /// An example of code which would be flagged by the rule.
+    pub example: Option<&'static str>,
+    /// An example of how the issue in `example` could be fixed.
+    pub fix: Option<&'static str>,
+}
+
+impl Rule {
+    /// The rule ID (like `CS0006`). This is the same as the ID of the results
+    /// generated by the rule.
+    pub fn id(&self) -> String {
+        self.code.id()
+    }
+
+    /// The category of the rule (soundness, correctness, or style).
+    pub fn rule_category(&self) -> RuleCategory {
+        self.code.rule_category()
+    }
+}
+
+/// Returns all rules implemented by Circomspect, sorted by ID.
+pub fn all_rules() -> &'static [Rule] {
+    RULES
+}
+
+/// Returns the rule with the given ID or name, if it exists. IDs and names are
+/// matched case insensitively.
+pub fn find_rule(id_or_name: &str) -> Option<&'static Rule> {
+    RULES.iter().find(|rule| {
+        rule.id().eq_ignore_ascii_case(id_or_name) || rule.name.eq_ignore_ascii_case(id_or_name)
+    })
+}
+
+static RULES: &[Rule] = &[
+    Rule {
+        code: ReportCode::UnconstrainedSignal,
+        name: "unconstrained-signal",
+        category: MessageCategory::Error,
+        summary: "A signal is not constrained by the template.",
+        description: "A signal which does not occur in any constraint can take any value. If the signal is an input, any value will satisfy the circuit. If the signal is an output or intermediate signal, a malicious prover can assign an arbitrary value to it.",
+        example: Some(
+            r#"template T() {
+    signal input in;
+    signal output out;
+    out <-- in * in;
+}"#,
+        ),
+        fix: Some(
+            r#"template T() {
+    signal input in;
+    signal output out;
+    out <== in * in;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::ShadowingVariable,
+        name: "shadowing-variable",
+        category: MessageCategory::Warning,
+        summary: "A variable declaration shadows a previous declaration.",
+        description: "A shadowing variable declaration is a declaration of a variable with the same name as a previously declared variable. If a variable declared in an outer scope is shadowed by mistake, this could change the semantics of the program.",
+        example: Some(
+            r#"function numberOfBits(a) {
+    var n = 1;
+    var r = 0;
+    while (n - 1 < a) {
+        var r = r + 1;
+        n *= 2;
+    }
+    return r;
+}"#,
+        ),
+        fix: Some(
+            r#"function numberOfBits(a) {
+    var n = 1;
+    var r = 0;
+    while (n - 1 < a) {
+        r = r + 1;
+        n *= 2;
+    }
+    return r;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::ParameterNameCollision,
+        name: "parameter-name-collision",
+        category: MessageCategory::Warning,
+        summary: "A function or template parameter is declared more than once.",
+        description: "If the same parameter name is used more than once, the later parameter shadows the earlier one, which means that the value of the earlier parameter cannot be accessed.",
+        example: Some("template T(n, n) { }"),
+        fix: Some("template T(n, m) { }"),
+    },
+    Rule {
+        code: ReportCode::FieldElementComparison,
+        name: "field-element-comparison",
+        category: MessageCategory::Info,
+        summary: "Field elements are compared using signed comparisons.",
+        description: "Field elements are normalized to the interval `(-p/2, p/2]` before they are compared. In particular, this means that `p/2 + 1 < 0 < p/2 - 1`, which can be surprising if you are used to thinking of field elements as unsigned integers.",
+        example: None,
+        fix: None,
+    },
+    Rule {
+        code: ReportCode::FieldElementArithmetic,
+        name: "field-element-arithmetic",
+        category: MessageCategory::Info,
+        summary: "Field element arithmetic may overflow or underflow.",
+        description: "Arithmetic expressions in Circom are evaluated modulo the prime `p`, which means that they may overflow or underflow. This includes the bitwise complement `~x`, which is reduced modulo `p`.",
+        example: None,
+        fix: None,
+    },
+    Rule {
+        code: ReportCode::SignalAssignmentStatement,
+        name: "signal-assignment",
+        category: MessageCategory::Warning,
+        summary: "A signal is assigned using the signal assignment operator `<--`.",
+        description: "The signal assignment operator `<--` assigns a value to a signal without generating a constraint. It is up to the developer to ensure that the signal is properly constrained elsewhere.",
+        example: Some(
+            r#"template IsZero() {
+    signal input in;
+    signal output out;
+    signal inv;
+    inv <-- in != 0 ? 1 / in : 0;
+    out <== -in * inv + 1;
+}"#,
+        ),
+        fix: Some(
+            r#"template IsZero() {
+    signal input in;
+    signal output out;
+    signal inv;
+    inv <-- in != 0 ? 1 / in : 0;
+    out <== -in * inv + 1;
+    in * out === 0;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::UnusedVariableValue,
+        name: "unused-variable",
+        category: MessageCategory::Warning,
+        summary: "A value assigned to a variable is never read.",
+        description: "An assigned value which does not contribute either directly or indirectly to a constraint, or a function return value, typically indicates a mistake in the implementation of the circuit.",
+        example: Some(
+            r#"function f(x) {
+    var y = x * x;
+    return x;
+}"#,
+        ),
+        fix: Some(
+            r#"function f(x) {
+    var y = x * x;
+    return y;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::UnusedParameterValue,
+        name: "unused-parameter",
+        category: MessageCategory::Warning,
+        summary: "A function or template parameter is never read.",
+        description: "A parameter which is never read typically indicates a mistake in the implementation of the function or template.",
+        example: Some(
+            r#"function f(x, y) {
+    return x * x;
+}"#,
+        ),
+        fix: Some(
+            r#"function f(x) {
+    return x * x;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::VariableWithoutSideEffect,
+        name: "variable-without-side-effect",
+        category: MessageCategory::Warning,
+        summary: "A variable does not contribute to any constraint or return value.",
+        description: "A variable which does not influence either a constraint or the return value of a function has no effect on the generated circuit. This typically indicates a mistake in the implementation.",
+        example: Some(
+            r#"template T() {
+    signal input in;
+    signal output out;
+    var lout = 0;
+    lout += in;
+    out <== in;
+}"#,
+        ),
+        fix: Some(
+            r#"template T() {
+    signal input in;
+    signal output out;
+    var lout = 0;
+    lout += in;
+    out <== lout;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::ConstantBranchCondition,
+        name: "constant-branch-condition",
+        category: MessageCategory::Warning,
+        summary: "A branch condition always evaluates to the same value.",
+        description: "If a branching statement condition always evaluates to either `true` or `false`, the branch is either always taken, or never taken. This typically indicates a mistake in the code.",
+        example: Some(
+            r#"function f(x) {
+    var n = 1;
+    if (n > 0) {
+        return x;
+    }
+    return 0;
+}"#,
+        ),
+        fix: None,
+    },
+    Rule {
+        code: ReportCode::NonStrictBinaryConversion,
+        name: "nonstrict-binary-conversion",
+        category: MessageCategory::Error,
+        summary: "`Num2Bits` or `Bits2Num` is used with an input size that may exceed the prime size.",
+        description: "Converting a field element to and from binary form using `Num2Bits` and `Bits2Num` from Circomlib is only safe if the input size is smaller than the size of the prime. Otherwise there may be multiple correct representations of the input.",
+        example: Some("component n2b = Num2Bits(254);"),
+        fix: Some("component n2b = Num2Bits_strict();"),
+    },
+    Rule {
+        code: ReportCode::CyclomaticComplexity,
+        name: "cyclomatic-complexity",
+        category: MessageCategory::Warning,
+        summary: "A function or template is overly complex.",
+        description: "As functions and templates grow in complexity they become more difficult to review and maintain. Circomspect uses cyclomatic complexity to estimate the complexity of each function and template.",
+        example: None,
+        fix: None,
+    },
+    Rule {
+        code: ReportCode::TooManyArguments,
+        name: "too-many-arguments",
+        category: MessageCategory::Warning,
+        summary: "A function or template takes too many arguments.",
+        description: "Functions and templates taking a large number of arguments are difficult to use correctly and impact the readability of the code.",
+        example: None,
+        fix: None,
+    },
+    Rule {
+        code: ReportCode::UnecessarySignalAssignment,
+        name: "unnecessary-signal-assignment",
+        category: MessageCategory::Warning,
+        summary: "A signal assignment using `<--` could use `<==` instead.",
+        description: "If the right-hand side of a signal assignment is a quadratic expression, the assignment can be rewritten using the constraint assignment operator `<==`, which ensures that the circuit and witness generation stay in sync.",
+        example: Some("out <-- in * in;"),
+        fix: Some("out <== in * in;"),
+    },
+    Rule {
+        code: ReportCode::FieldElementOverflow,
+        name: "field-element-overflow",
+        category: MessageCategory::Error,
+        summary: "Constraint arithmetic may overflow the field modulus.",
+        description: "Constraints are evaluated modulo the prime `p`. If the operands of an arithmetic expression in a constraint are bounded, but the result may not fit in a field element, the result wraps around the prime, which means that the constraint may be satisfied by unexpected (aliased) values.",
+        example: Some(
+            r#"template T() {
+    signal input a;
+    signal x;
+    signal output out;
+    a * (a - 1) === 0;
+    x <== a * 2**200;
+    out <== x * x;
+}"#,
+        ),
+        fix: None,
+    },
+    Rule {
+        code: ReportCode::OutOfBoundsArrayAccess,
+        name: "out-of-bounds-array-access",
+        category: MessageCategory::Warning,
+        summary: "An array is accessed out of bounds.",
+        description: "If both the dimensions of an array and the index used to access it evaluate to constants, the access can be checked against the declared bounds of the array.",
+        example: Some(
+            r#"template T() {
+    signal input in[2];
+    signal output out;
+    out <== in[2];
+}"#,
+        ),
+        fix: Some(
+            r#"template T() {
+    signal input in[2];
+    signal output out;
+    out <== in[1];
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::SignalAssert,
+        name: "signal-assert",
+        category: MessageCategory::Error,
+        summary: "An assert depends on the value of a signal.",
+        description: "`assert` statements are only evaluated during witness generation and do not add any constraints to the circuit. If the asserted expression depends on the value of a signal, a malicious prover can simply ignore the assert.",
+        example: Some(
+            r#"template T() {
+    signal input a;
+    signal input b;
+    assert(a * b == 1);
+}"#,
+        ),
+        fix: Some(
+            r#"template T() {
+    signal input a;
+    signal input b;
+    a * b === 1;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::ConstraintComparison,
+        name: "constraint-comparison",
+        category: MessageCategory::Error,
+        summary: "A comparison over signals is used in a constraint.",
+        description: "Comparison operators like `<` and `==` do not correspond to arithmetic constraints. If a comparison depending on a signal value is used in a constraint, the relation between the result and the compared signals is not enforced by the circuit.",
+        example: Some(
+            r#"template T() {
+    signal input a;
+    signal input b;
+    signal output out;
+    out <== a < b;
+}"#,
+        ),
+        fix: Some(
+            r#"template T() {
+    signal input a;
+    signal input b;
+    signal output out;
+    component lt = LessThan(252);
+    lt.in[0] <== a;
+    lt.in[1] <== b;
+    out <== lt.out;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::TagViolation,
+        name: "tag-violation",
+        category: MessageCategory::Error,
+        summary: "A component input requiring a tag is assigned an untagged value.",
+        description: "Circom 2.1 allows signals to be tagged to indicate that the signal satisfies some property, like being binary. If an input signal of a template declares a tag, the assigned value is expected to carry the same tag. Tags are propagated through direct signal assignments, so the value typically needs to be checked by a template with a correspondingly tagged output signal.",
+        example: Some(
+            r#"template Not() {
+    signal input {binary} in;
+    signal output {binary} out;
+    out <== 1 - in;
+}
+
+template T() {
+    signal input a;
+    signal output out;
+    component not = Not();
+    not.in <== a;
+    out <== not.out;
+}"#,
+        ),
+        fix: Some(
+            r#"template T() {
+    signal input {binary} a;
+    signal output out;
+    component not = Not();
+    not.in <== a;
+    out <== not.out;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::UnconstrainedOutputSignal,
+        name: "unconstrained-output",
+        category: MessageCategory::Error,
+        summary: "An output signal is assigned using `<--` but never constrained.",
+        description: "An output signal which is only assigned using the signal assignment operator `<--` and which does not occur in any constraint can be assigned an arbitrary value by a malicious prover. This is almost always a soundness bug.",
+        example: Some(
+            r#"template Square() {
+    signal input in;
+    signal output out;
+    out <-- in * in;
+}"#,
+        ),
+        fix: Some(
+            r#"template Square() {
+    signal input in;
+    signal output out;
+    out <== in * in;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::UnconstrainedComponentOutput,
+        name: "unconstrained-component-output",
+        category: MessageCategory::Error,
+        summary: "A component output is read, but never occurs in a constraint.",
+        description: "If the output of a component is read (for example, using the signal assignment operator `<--`), but never occurs in a constraint, the constraints generated by the component do not restrict the signals of the parent template. This typically means that the component is instantiated but unconstrained.",
+        example: Some(
+            r#"template IsNonZero() {
+    signal input in;
+    signal output out;
+    component isz = IsZero();
+    isz.in <== in;
+    out <-- 1 - isz.out;
+}"#,
+        ),
+        fix: Some(
+            r#"template IsNonZero() {
+    signal input in;
+    signal output out;
+    component isz = IsZero();
+    isz.in <== in;
+    out <== 1 - isz.out;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::UnsafeBitWidth,
+        name: "unsafe-bit-width",
+        category: MessageCategory::Error,
+        summary: "A Circomlib template is instantiated with an unsafe bit width.",
+        description: "Circomlib templates like `LessThan(n)` and `Num2Bits(n)` are only sound if the bit width `n` is sufficiently smaller than the size of the prime. If `n` is too large, intermediate values may wrap around the field modulus, which means that comparisons may return the wrong result.",
+        example: Some(
+            r#"template IsSmaller() {
+    signal input in[2];
+    signal output out;
+    component lt = LessThan(252);
+    lt.in <== in;
+    out <== lt.out;
+}"#,
+        ),
+        fix: Some(
+            r#"template IsSmaller() {
+    signal input in[2];
+    signal output out;
+    component lt = LessThan(64);
+    lt.in <== in;
+    out <== lt.out;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::UncheckedComparatorInput,
+        name: "unchecked-comparator-input",
+        category: MessageCategory::Error,
+        summary: "A signal is passed to a Circomlib comparator without being range checked.",
+        description: "Circomlib comparators like `LessThan(n)` assume that their inputs are at most `n` bits. If an input is not range checked (for example, using `Num2Bits(n)`), a malicious prover may be able to make the comparison return the wrong result.",
+        example: Some(
+            r#"template IsSmaller() {
+    signal input in[2];
+    signal output out;
+    component lt = LessThan(64);
+    lt.in <== in;
+    out <== lt.out;
+}"#,
+        ),
+        fix: Some(
+            r#"template IsSmaller() {
+    signal input in[2];
+    signal output out;
+    component n2b[2];
+    for (var i = 0; i < 2; i++) {
+        n2b[i] = Num2Bits(64);
+        n2b[i].in <== in[i];
+    }
+    component lt = LessThan(64);
+    lt.in <== in;
+    out <== lt.out;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::DivisionByZero,
+        name: "division-by-zero",
+        category: MessageCategory::Warning,
+        summary: "The divisor of a division or modulo operation may be zero.",
+        description: "Division by zero (using `/`, `\\`, or `%`) silently evaluates to 0 during witness generation. Divisions where the divisor is not known to be non-zero, either from range analysis or because the division is guarded by a condition like `x != 0`, may produce unexpected results.",
+        example: Some(
+            r#"template Inverse() {
+    signal input in;
+    signal output out;
+    out <-- 1 / in;
+    out * in === 1;
+}"#,
+        ),
+        fix: Some(
+            r#"template Inverse() {
+    signal input in;
+    signal output out;
+    out <-- in != 0 ? 1 / in : 0;
+    out * in === 1;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::UnconstrainedInverse,
+        name: "unconstrained-inverse",
+        category: MessageCategory::Error,
+        summary: "A signal is assigned an inverse using `<--`, but is never constrained to be the inverse.",
+        description: "A signal assigned the inverse `1 / x` using the signal assignment operator `<--` must be constrained to be the inverse of `x`, typically using a constraint like `inv * x === 1`. If there is no constraint relating the signal to `x`, a malicious prover can assign an arbitrary value to the signal.",
+        example: Some(
+            r#"template Inverse() {
+    signal input in;
+    signal output out;
+    signal inv;
+    inv <-- 1 / in;
+    out <== inv;
+}"#,
+        ),
+        fix: Some(
+            r#"template Inverse() {
+    signal input in;
+    signal output out;
+    signal inv;
+    inv <-- 1 / in;
+    inv * in === 1;
+    out <== inv;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::DeadStore,
+        name: "dead-store",
+        category: MessageCategory::Warning,
+        summary: "A value is assigned to an array element, but is overwritten before it is read.",
+        description: "If the value assigned to an array element is overwritten on every path before it is read, the assignment has no effect. This often indicates a mistake, like using the wrong index when initializing an array.",
+        example: Some(
+            r#"function f(a, b) {
+    var x[2];
+    x[0] = a;
+    x[0] = b;
+    return x;
+}"#,
+        ),
+        fix: Some(
+            r#"function f(a, b) {
+    var x[2];
+    x[0] = a;
+    x[1] = b;
+    return x;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::UseBeforeAssignment,
+        name: "use-before-assignment",
+        category: MessageCategory::Warning,
+        summary: "A variable or signal may be read before it is assigned a value.",
+        description: "If a local variable or signal is read along some path where it has not yet been assigned a value, the value read is undefined. This typically indicates a missing branch, or a statement that was placed in the wrong order. Loops are assumed to execute at least once, and arrays are considered assigned once any element is assigned.",
+        example: Some(
+            r#"function f(a) {
+    var x;
+    if (a > 0) {
+        x = 1;
+    }
+    return x;
+}"#,
+        ),
+        fix: Some(
+            r#"function f(a) {
+    var x = 0;
+    if (a > 0) {
+        x = 1;
+    }
+    return x;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::InfiniteLoop,
+        name: "infinite-loop",
+        category: MessageCategory::Warning,
+        summary: "A loop condition may never become false.",
+        description: "If none of the variables in a loop condition are modified by the loop body, or if the loop variable is updated in the wrong direction, the loop condition never becomes false and the loop does not terminate. Loops containing a return statement are not reported.",
+        example: Some(
+            r#"function sum(n) {
+    var result = 0;
+    for (var i = 0; i < n; i--) {
+        result += i;
+    }
+    return result;
+}"#,
+        ),
+        fix: Some(
+            r#"function sum(n) {
+    var result = 0;
+    for (var i = 0; i < n; i++) {
+        result += i;
+    }
+    return result;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::ZeroIterationLoop,
+        name: "zero-iteration-loop",
+        category: MessageCategory::Warning,
+        summary: "A loop condition is false on entry to the loop.",
+        description: "If the loop condition is false when the loop variables are replaced by their initial values, the loop body is never executed. This typically indicates that the initial value or the loop condition is incorrect.",
+        example: Some(
+            r#"function sum(n) {
+    var result = 0;
+    for (var i = 8; i < 4; i++) {
+        result += n;
+    }
+    return result;
+}"#,
+        ),
+        fix: None,
+    },
+    Rule {
+        code: ReportCode::AnalysisTimeout,
+        name: "analysis-timeout",
+        category: MessageCategory::Info,
+        summary: "The analysis of a function or template was stopped after reaching the time limit.",
+        description: "If the analysis of a single function or template takes longer than the time limit set using `--timeout-per-template`, the remaining analysis passes are skipped for that definition, and the results for the definition may be incomplete. This typically happens for templates with very large unrolled loops or arrays.",
+        example: None,
+        fix: None,
+    },
+    Rule {
+        code: ReportCode::UnusedReturnValue,
+        name: "unused-return-value",
+        category: MessageCategory::Warning,
+        summary: "The value returned by a function call is never used.",
+        description: "Circom functions cannot have side effects, so calling a function only makes sense if the returned value is used. If the returned value is assigned to a variable which is never read, the call has no effect. This typically indicates that the wrong variable is used later in the code.",
+        example: Some(
+            r#"template T() {
+    signal input in;
+    signal output out;
+    var bits = nbits(in);
+    out <== in;
+}"#,
+        ),
+        fix: None,
+    },
+    Rule {
+        code: ReportCode::DefinitionMisuse,
+        name: "template-function-misuse",
+        category: MessageCategory::Error,
+        summary: "A template is called like a function, or a function is instantiated as a component.",
+        description: "Templates must be instantiated as components, and functions must be called in expressions. Using a template in an expression, or assigning the result of a function call to a component, is rejected by the Circom compiler.",
+        example: Some(
+            r#"template T() {
+    signal input in;
+    signal output out;
+    component c = f(1);
+    var x = Num2Bits(8);
+}"#,
+        ),
+        fix: Some(
+            r#"template T() {
+    signal input in;
+    signal output out;
+    var y = f(1);
+    component x = Num2Bits(8);
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::UninitializedComponent,
+        name: "uninitialized-component",
+        category: MessageCategory::Warning,
+        summary: "Some elements of a component array are never instantiated.",
+        description: "Every element of a component array must be instantiated before the signals of the element are accessed. If the loop instantiating the elements of a component array does not cover every index, accessing the signals of the remaining elements is an error. This typically indicates an off-by-one error in the loop bounds.",
+        example: Some(
+            r#"template T() {
+    signal input in[4];
+    signal output out[4];
+    component c[4];
+    for (var i = 0; i < 3; i++) {
+        c[i] = IsZero();
+    }
+    for (var i = 0; i < 4; i++) {
+        c[i].in <== in[i];
+        out[i] <== c[i].out;
+    }
+}"#,
+        ),
+        fix: Some(
+            r#"template T() {
+    signal input in[4];
+    signal output out[4];
+    component c[4];
+    for (var i = 0; i < 4; i++) {
+        c[i] = IsZero();
+        c[i].in <== in[i];
+        out[i] <== c[i].out;
+    }
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::SignalDependentInstantiation,
+        name: "signal-dependent-instantiation",
+        category: MessageCategory::Error,
+        summary: "A component is instantiated under a condition depending on a signal.",
+        description: "The components of a template must be known when constraints are generated. Instantiating a component under a condition which depends on the value of a signal is rejected by the Circom compiler, typically with an error that does not point to the instantiation.",
+        example: Some(
+            r#"template T() {
+    signal input in;
+    signal output out;
+    component c;
+    if (in == 0) {
+        c = A();
+    } else {
+        c = B();
+    }
+    c.in <== in;
+    out <== c.out;
+}"#,
+        ),
+        fix: Some(
+            r#"template T() {
+    signal input in;
+    signal output out;
+    component a = A();
+    component b = B();
+    component isz = IsZero();
+    a.in <== in;
+    b.in <== in;
+    isz.in <== in;
+    out <== isz.out * (a.out - b.out) + b.out;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::PartialConditionalInstantiation,
+        name: "partial-conditional-instantiation",
+        category: MessageCategory::Error,
+        summary: "A component is only instantiated in one branch of an if-statement, leaving some inputs unconstrained in the other branch.",
+        description: "If a component is only instantiated in one branch of an if-statement depending on template parameters, the input signals of the template constrained by the component are unconstrained when the other branch is taken. This typically indicates that the other branch is missing a constraint.",
+        example: Some(
+            r#"template T(n) {
+    signal input in;
+    signal output out;
+    component c;
+    if (n > 0) {
+        c = IsZero();
+        c.in <== in;
+        out <== c.out;
+    } else {
+        out <== 0;
+    }
+}"#,
+        ),
+        fix: Some(
+            r#"template T(n) {
+    signal input in;
+    signal output out;
+    component c;
+    if (n > 0) {
+        c = IsZero();
+        c.in <== in;
+        out <== c.out;
+    } else {
+        in === 0;
+        out <== 0;
+    }
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::ConditionalSignalDeclaration,
+        name: "conditional-signal-declaration",
+        category: MessageCategory::Error,
+        summary: "A signal is declared under a condition depending on a signal.",
+        description: "The signals of a template must be known when constraints are generated. Declaring a signal in the body of an if-statement or loop with a condition depending on the value of a signal is rejected by the Circom compiler. Signals should be declared at the top level of the template.",
+        example: Some(
+            r#"template T() {
+    signal input in;
+    signal output out;
+    if (in == 0) {
+        signal tmp;
+        tmp <== in * in;
+        out <== tmp;
+    } else {
+        out <== in;
+    }
+}"#,
+        ),
+        fix: Some(
+            r#"template T() {
+    signal input in;
+    signal output out;
+    signal tmp;
+    component isz = IsZero();
+    isz.in <== in;
+    tmp <== in * in;
+    out <== isz.out * (tmp - in) + in;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::UnconstrainedBitwiseOperation,
+        name: "unconstrained-bitwise-operation",
+        category: MessageCategory::Error,
+        summary: "A signal is assigned the result of a bitwise operation on signals using `<--`, but its bits are never constrained.",
+        description: "Bitwise operations like `&`, `|`, `^`, `<<`, and `>>` are not reflected in the constraint system. If a signal is assigned the result of a bitwise operation using `<--`, the bit semantics of the operation must be enforced using separate constraints (typically by constraining each bit to be binary and recombining the bits). Otherwise, a malicious prover may assign any value to the signal.",
+        example: Some(
+            r#"template T(n) {
+    signal input in;
+    signal output out[n];
+    for (var i = 0; i < n; i++) {
+        out[i] <-- (in >> i) & 1;
+    }
+}"#,
+        ),
+        fix: Some(
+            r#"template T(n) {
+    signal input in;
+    signal output out[n];
+    var sum = 0;
+    for (var i = 0; i < n; i++) {
+        out[i] <-- (in >> i) & 1;
+        out[i] * (out[i] - 1) === 0;
+        sum += out[i] * 2**i;
+    }
+    sum === in;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::HintOnlyConstraint,
+        name: "hint-only-constraint",
+        category: MessageCategory::Error,
+        summary: "A constraint built from variables only depends on signals assigned using `<--`.",
+        description: "Signals assigned using `<--` are hints chosen by the prover. A constraint which only depends on hints (typically through a variable accumulating the hinted values) only relates the hints to each other, and does not bind them to the inputs of the template. This is a common mistake when the final constraint relating the hints to the input is written against the wrong signal.",
+        example: Some(
+            r#"template Num2Bits(n) {
+    signal input in;
+    signal output out[n];
+    signal sum;
+    var lc = 0;
+    for (var i = 0; i < n; i++) {
+        out[i] <-- (in >> i) & 1;
+        out[i] * (out[i] - 1) === 0;
+        lc += out[i] * 2**i;
+    }
+    sum <-- lc;
+    lc === sum;
+}"#,
+        ),
+        fix: Some(
+            r#"template Num2Bits(n) {
+    signal input in;
+    signal output out[n];
+    var lc = 0;
+    for (var i = 0; i < n; i++) {
+        out[i] <-- (in >> i) & 1;
+        out[i] * (out[i] - 1) === 0;
+        lc += out[i] * 2**i;
+    }
+    lc === in;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::SignalDependentLoop,
+        name: "signal-dependent-loop",
+        category: MessageCategory::Error,
+        summary: "A loop generating constraints has a condition depending on a signal.",
+        description: "The constraints generated by a template must be known at compile time. This means that the bounds of loops generating constraints may only depend on template parameters and constants. The compiler rejects loops generating constraints with conditions depending on signals, either directly or through local variables. Circomspect reports the chain of variables through which the signal flows into the loop condition.",
+        example: Some(
+            r#"template Repeat(n) {
+    signal input in;
+    signal input len;
+    signal output out[n];
+    var m = len;
+    for (var i = 0; i < m; i++) {
+        out[i] <== in;
+    }
+}"#,
+        ),
+        fix: Some(
+            r#"template Repeat(n) {
+    signal input in;
+    signal output out[n];
+    for (var i = 0; i < n; i++) {
+        out[i] <== in;
+    }
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::TrustBoundarySummary,
+        name: "trust-boundary-summary",
+        category: MessageCategory::Info,
+        summary: "A summary of the input signals constraining each output signal of a template.",
+        description: "For each output signal of a template, Circomspect lists the input signals that the output is constrained by (in one or more steps), and the signals assigned using `<--` that the output depends on, which are never constrained together with an input signal or a subcomponent. The summary is intended to help auditors identify the trust boundary of each template.",
+        example: None,
+        fix: None,
+    },
+    Rule {
+        code: ReportCode::UnbalancedHint,
+        name: "unbalanced-hint",
+        category: MessageCategory::Error,
+        summary: "An output signal depends on a hint which is never constrained by the inputs.",
+        description: "Signals assigned using `<--` are hints chosen by the prover. If an output signal depends on a hint which is never constrained together with an input signal or a subcomponent, a malicious prover can choose the value of the hint freely, and the output is not determined by the inputs of the template.",
+        example: Some(
+            r#"template Div() {
+    signal input a;
+    signal input b;
+    signal output out;
+    signal q;
+    q <-- a / b;
+    out <== 2 * q;
+}"#,
+        ),
+        fix: Some(
+            r#"template Div() {
+    signal input a;
+    signal input b;
+    signal output out;
+    signal q;
+    q <-- a / b;
+    q * b === a;
+    out <== 2 * q;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::WitnessConstraintDivergence,
+        name: "witness-constraint-divergence",
+        category: MessageCategory::Error,
+        summary: "The constraints on a signal admit more values than the expression assigned to it.",
+        description: "When a signal is assigned using `<--`, the assigned expression is only evaluated during witness generation. The constraints mentioning the signal should restrict the signal to the same value. Circomspect warns if the assigned expression is boolean but the signal is never constrained to be binary, or if the assigned expression depends on signals which are never related to the assigned signal by the constraints.",
+        example: Some(
+            r#"template LessThan() {
+    signal input a;
+    signal input b;
+    signal output out;
+    out <-- a < b ? 1 : 0;
+    out * (out - 1) === 0;
+}"#,
+        ),
+        fix: Some(
+            r#"template LessThan(n) {
+    signal input a;
+    signal input b;
+    signal output out;
+    component n2b = Num2Bits(n + 1);
+    n2b.in <== a + (1 << n) - b;
+    out <== 1 - n2b.out[n];
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::FreeSignalConstraint,
+        name: "free-signal-constraint",
+        category: MessageCategory::Error,
+        summary: "A constraint relates two signals which are both chosen freely by the prover.",
+        description: "Signals assigned using `<--` are chosen by the prover. A constraint `a === b` where both `a` and `b` are assigned using `<--`, and neither signal is constrained together with an input signal, an output signal, or a subcomponent, can always be satisfied by assigning the same value to both signals. Such constraints give a false sense of security.",
+        example: Some(
+            r#"template Double() {
+    signal input in;
+    signal output out;
+    signal a;
+    signal b;
+    a <-- 2 * in;
+    b <-- in + in;
+    a === b;
+    out <== 2 * in;
+}"#,
+        ),
+        fix: Some(
+            r#"template Double() {
+    signal input in;
+    signal output out;
+    signal a;
+    a <-- 2 * in;
+    a === in + in;
+    out <== a;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::UnknownPublicSignal,
+        name: "unknown-public-signal",
+        category: MessageCategory::Error,
+        summary: "A public signal of the main component is not an input signal of the main template.",
+        description: "The public signals listed by the main component must be input signals of the main template. Circomspect reports listed names which are not declared as input signals by the template. (Output signals are always public and should not be listed.)",
+        example: Some(
+            r#"template Multiplier() {
+    signal input a;
+    signal input b;
+    signal output c;
+    c <== a * b;
+}
+
+component main {public [a, d]} = Multiplier();"#,
+        ),
+        fix: Some(
+            r#"template Multiplier() {
+    signal input a;
+    signal input b;
+    signal output c;
+    c <== a * b;
+}
+
+component main {public [a, b]} = Multiplier();"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::PrivateMainInput,
+        name: "private-main-input",
+        category: MessageCategory::Info,
+        summary: "Some input signals of the main component are private by omission.",
+        description: "Input signals of the main component which are not listed as public are private. If the main component lists some, but not all, input signals as public, it is easy to forget an input that was intended to be public. Since most circuits have both public and private inputs, the private inputs are only listed at the informational level (using `--level info`).",
+        example: Some(
+            r#"template Multiplier() {
+    signal input a;
+    signal input b;
+    signal output c;
+    c <== a * b;
+}
+
+component main {public [a]} = Multiplier();"#,
+        ),
+        fix: Some(
+            r#"template Multiplier() {
+    signal input a;
+    signal input b;
+    signal output c;
+    c <== a * b;
+}
+
+component main {public [a, b]} = Multiplier();"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::MainComponentArgument,
+        name: "main-component-argument",
+        category: MessageCategory::Error,
+        summary: "An argument of the main component is not a constant expression.",
+        description: "The main component is declared outside of any template or function, which means that there are no variables in scope. The arguments passed to the main template must be constant expressions.",
+        example: Some(
+            r#"component main = Num2Bits(n);"#,
+        ),
+        fix: Some(
+            r#"component main = Num2Bits(254);"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::MainComponentInterface,
+        name: "main-component-interface",
+        category: MessageCategory::Warning,
+        summary: "The main template declares no input signals or no output signals.",
+        description: "A main template without input signals proves a statement which is independent of any data provided by the verifier, and a main template without output signals does not expose any computed values to the verifier. This typically indicates that the wrong template is used as the main component.",
+        example: None,
+        fix: None,
+    },
+    Rule {
+        code: ReportCode::UnconstrainedMainInput,
+        name: "unconstrained-main-input",
+        category: MessageCategory::Warning,
+        summary: "An input signal of the main template never occurs in a constraint.",
+        description: "If an input signal of the main template never occurs in a constraint, a proof generated for one value of the input is also valid for any other value of the input. In protocols where the input is used to bind the proof to some value (like a recipient address or a nullifier), this allows proofs to be trivially forged.",
+        example: Some(
+            r#"template Withdraw() {
+    signal input secret;
+    signal input recipient;
+    signal output commitment;
+    commitment <== secret * secret;
+}
+
+component main {public [recipient]} = Withdraw();"#,
+        ),
+        fix: Some(
+            r#"template Withdraw() {
+    signal input secret;
+    signal input recipient;
+    signal output commitment;
+    signal recipientSquare;
+    commitment <== secret * secret;
+    recipientSquare <== recipient * recipient;
+}
+
+component main {public [recipient]} = Withdraw();"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::LogStatement,
+        name: "log-statement",
+        category: MessageCategory::Warning,
+        summary: "A `log` statement is reachable from the main component.",
+        description: "`log` statements are typically used for debugging, and are often left over from development. This check is opt-in, and is enabled using `--hygiene`.",
+        example: Some(
+            r#"template IsZero() {
+    signal input in;
+    signal output out;
+    signal inv;
+    inv <-- in != 0 ? 1 / in : 0;
+    log("inv: ", inv);
+    out <== -in * inv + 1;
+    in * out === 0;
+}"#,
+        ),
+        fix: Some(
+            r#"template IsZero() {
+    signal input in;
+    signal output out;
+    signal inv;
+    inv <-- in != 0 ? 1 / in : 0;
+    out <== -in * inv + 1;
+    in * out === 0;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::TodoComment,
+        name: "todo-comment",
+        category: MessageCategory::Warning,
+        summary: "A TODO or FIXME comment occurs in a definition reachable from the main component.",
+        description: "TODO and FIXME comments in audited code frequently mark unfinished constraint work, like missing range checks. This check is opt-in, and is enabled using `--hygiene`.",
+        example: Some(
+            r#"template Num2Bits(n) {
+    signal input in;
+    signal output out[n];
+    for (var i = 0; i < n; i++) {
+        // TODO: Constrain the output bits.
+        out[i] <-- (in >> i) & 1;
+    }
+}"#,
+        ),
+        fix: None,
+    },
+    Rule {
+        code: ReportCode::CyclicInclude,
+        name: "cyclic-include",
+        category: MessageCategory::Warning,
+        summary: "The include statements of the project form a cycle.",
+        description: "Included files are only parsed once, so include cycles do not prevent the program from being compiled. However, they make the structure of the project hard to follow, and typically indicate that shared definitions should be moved into a separate file.",
+        example: Some(
+            r#"// a.circom
+include "b.circom";
+
+// b.circom
+include "a.circom";"#,
+        ),
+        fix: Some(
+            r#"// a.circom
+include "common.circom";
+
+// b.circom
+include "common.circom";"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::ConfusableName,
+        name: "confusable-name",
+        category: MessageCategory::Warning,
+        summary: "A variable or signal without side effects has a name which is easily confused with a used variable or signal.",
+        description: "A variable or signal which does not affect witness or constraint generation, and whose name differs only by case or by a single character from a variable or signal which is used, often indicates that the wrong name is used. For example, a template may accumulate bits into `lout`, but then constrain `nout`.",
+        example: Some(
+            r#"template Bits2Num(n) {
+    signal input in[n];
+    signal output out;
+    var lout = 0;
+    var nout = 0;
+    var e = 1;
+    for (var i = 0; i < n; i++) {
+        lout += in[i] * e;
+        e = e + e;
+    }
+    out <== nout;
+}"#,
+        ),
+        fix: Some(
+            r#"template Bits2Num(n) {
+    signal input in[n];
+    signal output out;
+    var lout = 0;
+    var e = 1;
+    for (var i = 0; i < n; i++) {
+        lout += in[i] * e;
+        e = e + e;
+    }
+    out <== lout;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::UnknownComponentSignal,
+        name: "unknown-component-signal",
+        category: MessageCategory::Error,
+        summary: "A signal accessed on a component is not declared by the instantiated template.",
+        description: "Only the input and output signals declared by a template can be accessed on a component instantiating the template. Accessing any other name is typically a typo, and Circomspect suggests the closest declared signal name.",
+        example: Some(
+            r#"template T() {
+    signal input in;
+    signal output out;
+    component n2b = Num2Bits(8);
+    n2b.in <== in;
+    out <== n2b.otu[0];
+}"#,
+        ),
+        fix: Some(
+            r#"template T() {
+    signal input in;
+    signal output out;
+    component n2b = Num2Bits(8);
+    n2b.in <== in;
+    out <== n2b.out[0];
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::NamingConvention,
+        name: "naming-convention",
+        category: MessageCategory::Warning,
+        summary: "A signal or template name does not follow the configured naming convention.",
+        description: "Naming conventions for input, output, and intermediate signals, and for templates, can be configured in the `[naming]` section of `circomspect.toml`. Each convention is either one of the predefined styles `camelCase`, `PascalCase`, `snake_case`, and `SCREAMING_SNAKE_CASE`, or a regular expression which must match the entire name. Names without a configured convention are not checked.",
+        example: Some(
+            r#"// With `templates = "PascalCase"` configured.
+template num_adder(n) {
+    signal input in[n];
+    signal output out;
+    // ...
+}"#,
+        ),
+        fix: Some(
+            r#"template NumAdder(n) {
+    signal input in[n];
+    signal output out;
+    // ...
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::LargeDefinition,
+        name: "large-definition",
+        category: MessageCategory::Warning,
+        summary: "A function or template contains too many statements.",
+        description: "Large functions and templates are difficult to review and maintain. Circomspect counts the statements in the control-flow graph of each function and template. The maximum number of statements can be configured using `max-statements` in the `[complexity]` section of `circomspect.toml`.",
+        example: None,
+        fix: None,
+    },
+    Rule {
+        code: ReportCode::DeepNesting,
+        name: "deep-nesting",
+        category: MessageCategory::Warning,
+        summary: "A function or template contains deeply nested branches and loops.",
+        description: "Deeply nested branches and loops make it difficult to determine which constraints are generated under which conditions. The maximum nesting depth can be configured using `max-nesting-depth` in the `[complexity]` section of `circomspect.toml`.",
+        example: None,
+        fix: None,
+    },
+    Rule {
+        code: ReportCode::AssignmentOperatorMismatch,
+        name: "assignment-operator-mismatch",
+        category: MessageCategory::Warning,
+        summary: "A signal is assigned using `=`, or a variable is assigned using `<==` or `<--`.",
+        description: "Signals must be assigned using `<==` (which assigns the value and adds a constraint) or `<--` (which only assigns the value), while variables and components must be assigned using `=`. Mixing up the operators is rejected by the compiler, and typically indicates confusion about whether the assigned value is constrained.",
+        example: Some(
+            r#"template T() {
+    signal input in;
+    signal output out;
+    var x;
+    x <== in * in;
+    out = x;
+}"#,
+        ),
+        fix: Some(
+            r#"template T() {
+    signal input in;
+    signal output out;
+    var x;
+    x = in * in;
+    out <== x;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::ComparisonInsteadOfConstraint,
+        name: "comparison-instead-of-constraint",
+        category: MessageCategory::Error,
+        summary: "An equality comparison `==` is used where the constraint `===` was likely intended.",
+        description: "The comparison `a == b` is only evaluated during witness generation, and does not constrain `a` and `b` to be equal. If the result of a comparison over signals is assigned to a variable or signal which never occurs in a constraint, the comparison has no effect on the circuit, which typically means that `===` was intended.",
+        example: Some(
+            r#"template T() {
+    signal input a;
+    signal input b;
+    var equal = a == b;
+}"#,
+        ),
+        fix: Some(
+            r#"template T() {
+    signal input a;
+    signal input b;
+    a === b;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::UncheckedTemplateParameter,
+        name: "unchecked-template-parameter",
+        category: MessageCategory::Warning,
+        summary: "A template parameter is used as an array dimension or loop bound without being restricted by an assert.",
+        description: "Template parameters are not restricted by the compiler. If a parameter used as an array dimension or loop bound is instantiated with 0 (or a value which is negative when interpreted as a field element), the template silently produces a degenerate circuit, for example with empty input arrays and no constraints. Restricting the parameter using an assert makes compilation fail instead.",
+        example: Some(
+            r#"template Sum(n) {
+    signal input in[n];
+    signal output out;
+    var sum = 0;
+    for (var i = 0; i < n; i++) {
+        sum += in[i];
+    }
+    out <== sum;
+}"#,
+        ),
+        fix: Some(
+            r#"template Sum(n) {
+    assert(n > 0);
+    signal input in[n];
+    signal output out;
+    var sum = 0;
+    for (var i = 0; i < n; i++) {
+        sum += in[i];
+    }
+    out <== sum;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::IncompatibleCompilerVersion,
+        name: "incompatible-compiler-version",
+        category: MessageCategory::Warning,
+        summary: "A file uses language features not supported by the required compiler version.",
+        description: "Some language features, like signal tags and anonymous components, were introduced in later versions of Circom. If a file uses a feature that is not supported by the version required by the version pragma (or the target compiler version if the file has no version pragma), the file will fail to compile.",
+        example: Some(
+            r#"pragma circom 2.0.0;
+
+template T() {
+    signal input {binary} in;
+    signal output out;
+    out <== 1 - in;
+}"#,
+        ),
+        fix: Some(
+            r#"pragma circom 2.1.0;
+
+template T() {
+    signal input {binary} in;
+    signal output out;
+    out <== 1 - in;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::FunctionWrongNumberOfArguments,
+        name: "function-arity",
+        category: MessageCategory::Error,
+        summary: "A function is called with the wrong number of arguments.",
+        description: "The number of arguments passed to a function must match the number of parameters in the function definition. Calls with the wrong number of arguments are rejected by the Circom compiler.",
+        example: Some(
+            r#"function f(x, y) {
+    return x + y;
+}
+
+function g(x) {
+    return f(x);
+}"#,
+        ),
+        fix: Some(
+            r#"function f(x, y) {
+    return x + y;
+}
+
+function g(x) {
+    return f(x, 1);
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::FunctionPathWithoutReturn,
+        name: "missing-return",
+        category: MessageCategory::Warning,
+        summary: "A path through a function does not return a value.",
+        description: "Circom functions must return a value on every path through the function body, but a missing return value is only detected by the Circom compiler during witness generation.",
+        example: Some(
+            r#"function f(x) {
+    if (x == 0) {
+        return 1;
+    }
+}"#,
+        ),
+        fix: Some(
+            r#"function f(x) {
+    if (x == 0) {
+        return 1;
+    }
+    return 0;
+}"#,
+        ),
+    },
+    Rule {
+        code: ReportCode::TemplateWrongNumberOfArguments,
+        name: "template-arity",
+        category: MessageCategory::Error,
+        summary: "A template is instantiated with the wrong number of arguments.",
+        description: "The number of arguments passed when a template is instantiated must match the number of parameters in the template definition. Instantiations with the wrong number of arguments are rejected by the Circom compiler.",
+        example: Some(
+            r#"template T() {
+    signal input in;
+    signal output out[2];
+    component n2b = Num2Bits();
+    n2b.in <== in;
+    out <== n2b.out;
+}"#,
+        ),
+        fix: Some(
+            r#"template T() {
+    signal input in;
+    signal output out[2];
+    component n2b = Num2Bits(2);
+    n2b.in <== in;
+    out <== n2b.out;
+}"#,
+        ),
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_rules() {
+        let ids = all_rules().iter().map(Rule::id).collect::<Vec<_>>();
+        let mut sorted_ids = ids.clone();
+        sorted_ids.sort();
+        assert_eq!(ids, sorted_ids);
+        assert_eq!(ids.iter().collect::<HashSet<_>>().len(), ids.len());
+
+        let names = all_rules().iter().map(|rule| rule.name).collect::<HashSet<_>>();
+        assert_eq!(names.len(), ids.len());
+
+        assert_eq!(find_rule("cs0006").map(Rule::id), Some("CS0006".to_string()));
+        assert_eq!(find_rule("unused-variable").map(Rule::id), Some("CS0006".to_string()));
+        assert!(find_rule("CS9999").is_none());
+    }
+}
@@ -1,11 +1,11 @@
 use super::ast;
-use super::ast::{FillMeta, SignalElementType, Statement};
+use super::ast::{FillMeta, SignalElementType, Statement, TagList};
 use super::file_definition::FileID;
 use crate::file_definition::FileLocation;
 use std::collections::hash_map::HashMap;
 
 pub type TemplateInfo = HashMap<String, TemplateData>;
-type SignalInfo = HashMap<String, (usize, SignalElementType)>;
+type SignalInfo = HashMap<String, (usize, SignalElementType, TagList)>;
 
 #[derive(Clone)]
 pub struct TemplateData {
@@ -81,10 +81,10 @@ impl TemplateData {
     pub fn get_name_of_params(&self) -> &Vec<String> {
         &self.name_of_params
     }
-    pub fn get_input_info(&self, name: &str) -> Option<&(usize, SignalElementType)> {
+    pub fn get_input_info(&self, name: &str) -> Option<&(usize, SignalElementType, TagList)> {
         self.input_signals.get(name)
     }
-    pub fn get_output_info(&self, name: &str) -> Option<&(usize, SignalElementType)> {
+    pub fn get_output_info(&self, name: &str) -> Option<&(usize, SignalElementType, TagList)> {
         self.output_signals.get(name)
     }
     pub fn get_inputs(&self) -> &SignalInfo {
@@ -130,7 +130,7 @@ fn fill_inputs_and_outputs(
             }
         }
         Statement::Declaration {
-            xtype: ast::VariableType::Signal(stype, tag),
+            xtype: ast::VariableType::Signal(stype, tag, tag_list),
             name,
             dimensions,
             ..
@@ -139,10 +139,10 @@ fn fill_inputs_and_outputs(
             let dim = dimensions.len();
             match stype {
                 ast::SignalType::Input => {
-                    input_signals.insert(signal_name, (dim, *tag));
+                    input_signals.insert(signal_name, (dim, *tag, tag_list.clone()));
                 }
                 ast::SignalType::Output => {
-                    output_signals.insert(signal_name, (dim, *tag));
+                    output_signals.insert(signal_name, (dim, *tag, tag_list.clone()));
                 }
                 _ => {} //no need to deal with intermediate signals
             }
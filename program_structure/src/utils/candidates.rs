@@ -0,0 +1,94 @@
+//! Conversion of reports into a list of suspected under-constrained signals.
+//! The list is intended as input for SMT-based tools like Picus and Ecne,
+//! which can then focus on proving (or refuting) that the listed signals are
+//! uniquely determined by the inputs of the circuit.
+use serde_json::{json, Value};
+
+use crate::file_definition::FileLibrary;
+use crate::json_conversion::ToJson;
+use crate::report::ReportCollection;
+use crate::rules::find_rule;
+
+/// The version of the candidate format. This should be bumped on any
+/// incompatible change.
+pub const CANDIDATES_VERSION: usize = 1;
+
+/// Converts the given reports into a JSON document listing each signal
+/// affected by a report as an under-constraint candidate. Reports which do not
+/// affect any signals are ignored. If the reports were mapped to a compiled
+/// circuit, each candidate also lists the compiled instances of the signal
+/// (with their signal and witness indices).
+#[must_use]
+pub fn to_candidates(reports: &ReportCollection, files: &FileLibrary) -> Value {
+    let candidates = reports
+        .iter()
+        .flat_map(|report| {
+            let location = report.primary().first().map(|label| label.to_json(files));
+            report.affected_signals().iter().map(move |signal| {
+                let compiled_signals = signal
+                    .compiled_signals
+                    .iter()
+                    .map(|symbol| {
+                        json!({
+                            "name": symbol.name,
+                            "signal": symbol.label,
+                            "witness": symbol.witness,
+                            "component": symbol.component,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                json!({
+                    "template": signal.template_name,
+                    "signal": signal.signal_name,
+                    "id": report.id(),
+                    "name": find_rule(&report.id()).map(|rule| rule.name),
+                    "level": report.category().to_string(),
+                    "message": report.message(),
+                    "location": location,
+                    "compiled_signals": compiled_signals,
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+    json!({ "version": CANDIDATES_VERSION, "candidates": candidates })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report::Report;
+    use crate::report_code::ReportCode;
+    use crate::sym_file::parse_symbols;
+
+    use super::*;
+
+    #[test]
+    fn test_to_candidates() {
+        let mut files = FileLibrary::new();
+        let file_id = files.add_file("test.circom".to_string(), "a\nbc <-- d;\n".to_string());
+        let mut reports = ReportCollection::new();
+        let mut report =
+            Report::warning("Some message.".to_string(), ReportCode::UnconstrainedOutputSignal);
+        report.add_primary(2..9, file_id, "Assigned here.".to_string());
+        report.add_affected_signal("T", "bc");
+        report.affected_signals_mut()[0].compiled_signals =
+            parse_symbols("3,-1,1,main.t.bc").unwrap();
+        reports.push(report);
+        reports.push(Report::warning(
+            "Other message.".to_string(),
+            ReportCode::FieldElementArithmetic,
+        ));
+
+        let json = to_candidates(&reports, &files);
+        assert_eq!(json["version"], 1);
+        assert_eq!(json["candidates"].as_array().map(Vec::len), Some(1));
+        let candidate = &json["candidates"][0];
+        assert_eq!(candidate["template"], "T");
+        assert_eq!(candidate["signal"], "bc");
+        assert_eq!(candidate["id"], "CS0019");
+        assert_eq!(candidate["location"]["start"], json!({ "line": 2, "column": 1 }));
+        assert_eq!(
+            candidate["compiled_signals"],
+            json!([{ "name": "main.t.bc", "signal": 3, "witness": null, "component": 1 }])
+        );
+    }
+}
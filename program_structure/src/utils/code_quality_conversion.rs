@@ -0,0 +1,99 @@
+use codespan_reporting::files::Files;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+use crate::file_definition::FileLibrary;
+use crate::report::{MessageCategory, Report, ReportCollection};
+use crate::report_code::RuleCategory;
+use crate::rules::find_rule;
+use crate::sarif_conversion::fingerprints;
+
+/// A trait for objects that can be converted into a GitLab Code Quality
+/// (Code Climate) report.
+pub trait ToCodeQuality {
+    /// Converts the object to a Code Quality JSON value. If a root directory
+    /// is given, file paths under the root are given relative to the root.
+    fn to_code_quality(&self, files: &FileLibrary, root: Option<&Path>) -> Value;
+}
+
+impl ToCodeQuality for ReportCollection {
+    fn to_code_quality(&self, files: &FileLibrary, root: Option<&Path>) -> Value {
+        // GitLab requires a location for each issue, so reports without a
+        // label are dropped.
+        let issues = self
+            .iter()
+            .zip(fingerprints(self, files, root))
+            .filter_map(|(report, fingerprint)| to_issue(report, fingerprint?, files, root))
+            .collect::<Vec<_>>();
+        Value::Array(issues)
+    }
+}
+
+fn to_issue(
+    report: &Report,
+    fingerprint: String,
+    files: &FileLibrary,
+    root: Option<&Path>,
+) -> Option<Value> {
+    let label = report.primary().first().or_else(|| report.secondary().first())?;
+    let storage = files.to_storage();
+    let path = PathBuf::from(storage.get(label.file_id)?.name().replace('"', ""));
+    let path = root.and_then(|root| path.strip_prefix(root).ok()).unwrap_or(&path);
+    let begin = storage.line_index(label.file_id, label.range.start)? + 1;
+    let end = storage.line_index(label.file_id, label.range.end)? + 1;
+    let category = match report.code().rule_category() {
+        RuleCategory::Soundness => "Security",
+        RuleCategory::Correctness => "Bug Risk",
+        RuleCategory::Style => "Style",
+    };
+    let severity = match report.category() {
+        MessageCategory::Error => "critical",
+        MessageCategory::Warning => "major",
+        MessageCategory::Info => "info",
+    };
+    let check_name =
+        find_rule(&report.id()).map_or_else(|| report.id(), |rule| rule.name.to_string());
+    Some(json!({
+        "type": "issue",
+        "check_name": check_name,
+        "description": report.message(),
+        "categories": [category],
+        "severity": severity,
+        "fingerprint": fingerprint,
+        "location": {
+            "path": path.to_string_lossy(),
+            "lines": { "begin": begin, "end": end },
+        },
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report_code::ReportCode;
+
+    use super::*;
+
+    #[test]
+    fn test_reports_to_code_quality() {
+        let mut files = FileLibrary::new();
+        let file_id =
+            files.add_file("/src/test.circom".to_string(), "a\nbc <-- d;\ne\n".to_string());
+        let mut warning =
+            Report::warning("Some warning.".to_string(), ReportCode::UnecessarySignalAssignment);
+        warning.add_primary(2..12, file_id, "Assigned here.".to_string());
+        let error = Report::error("Some error.".to_string(), ReportCode::UnconstrainedSignal);
+
+        let json = vec![warning, error].to_code_quality(&files, Some(Path::new("/src")));
+        // Reports without a location are dropped.
+        assert_eq!(json.as_array().unwrap().len(), 1);
+        let issue = &json[0];
+        assert_eq!(issue["type"], "issue");
+        assert_eq!(issue["check_name"], "unnecessary-signal-assignment");
+        assert_eq!(issue["description"], "Some warning.");
+        assert_eq!(issue["categories"], json!(["Style"]));
+        assert_eq!(issue["severity"], "major");
+        assert!(issue["fingerprint"].is_string());
+        assert_eq!(issue["location"]["path"], "test.circom");
+        assert_eq!(issue["location"]["lines"], json!({ "begin": 2, "end": 3 }));
+    }
+}
@@ -3,20 +3,15 @@ use num_bigint::BigInt;
 use std::fmt;
 use std::str::FromStr;
 
-#[derive(Clone)]
+// The default curve is used for testing.
+#[derive(Clone, Default)]
 pub enum Curve {
+    #[default]
     Bn128,
     Bls12_381,
     Goldilocks,
 }
 
-// Used for testing.
-impl Default for Curve {
-    fn default() -> Self {
-        Curve::Bn128
-    }
-}
-
 impl fmt::Display for Curve {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use Curve::*;
@@ -55,7 +50,7 @@ impl FromStr for Curve {
 
     fn from_str(prime: &str) -> Result<Self, Self::Err> {
         match &prime.to_uppercase()[..] {
-            "BN128" => Ok(Curve::Bn128),
+            "BN128" | "BN254" => Ok(Curve::Bn128),
             "BLS12_381" => Ok(Curve::Bls12_381),
             "GOLDILOCKS" => Ok(Curve::Goldilocks),
             _ => Err(anyhow!("failed to parse prime `{prime}`")),
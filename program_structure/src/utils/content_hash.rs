@@ -0,0 +1,18 @@
+/// A stable 64-bit content hash. We use FNV-1a rather than the standard
+/// library hasher since the hash must be stable across builds.
+pub type ContentHash = u64;
+
+/// Returns the FNV-1a hash of the given byte slices.
+#[must_use]
+pub fn content_hash<'a>(parts: impl IntoIterator<Item = &'a [u8]>) -> ContentHash {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    let mut hash = OFFSET_BASIS;
+    for part in parts {
+        for byte in part.iter().chain(&[0xff]) {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+    hash
+}
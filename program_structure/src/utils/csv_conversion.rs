@@ -0,0 +1,130 @@
+use codespan_reporting::files::Files;
+use log::debug;
+use std::fmt::{self, Write};
+use thiserror::Error;
+
+use crate::report::{ReportCollection, ReportLabel};
+use crate::file_definition::{FileID, FileLibrary};
+use crate::rules::find_rule;
+use crate::sarif_conversion::fingerprints;
+
+const CSV_HEADER: [&str; 8] =
+    ["rule", "name", "severity", "file", "start_line", "end_line", "message", "fingerprint"];
+
+/// A trait for objects that can be converted into CSV.
+pub trait ToCsv {
+    type Error;
+
+    /// Renders the object as CSV.
+    fn to_csv(&self, files: &FileLibrary) -> Result<String, Self::Error>;
+}
+
+impl ToCsv for ReportCollection {
+    type Error = CsvError;
+
+    fn to_csv(&self, files: &FileLibrary) -> CsvResult<String> {
+        debug!("converting report collection to csv");
+        let mut csv = String::new();
+        write_record(&mut csv, &CSV_HEADER.map(String::from))?;
+        for (report, fingerprint) in self.iter().zip(fingerprints(self, files, None)) {
+            let label = report.primary().first().or_else(|| report.secondary().first());
+            let (file, start_line, end_line) = match label {
+                Some(label) => {
+                    let (start_line, end_line) = line_range(label, files)?;
+                    (file_name(label.file_id, files)?, start_line.to_string(), end_line.to_string())
+                }
+                None => Default::default(),
+            };
+            let name = find_rule(&report.id()).map(|rule| rule.name.to_string());
+            write_record(
+                &mut csv,
+                &[
+                    report.id(),
+                    name.unwrap_or_default(),
+                    report.category().to_string(),
+                    file,
+                    start_line,
+                    end_line,
+                    report.message().clone(),
+                    fingerprint.unwrap_or_default(),
+                ],
+            )?;
+        }
+        Ok(csv)
+    }
+}
+
+/// Writes a single CSV record terminated by CRLF (as specified by RFC 4180).
+fn write_record(csv: &mut String, fields: &[String]) -> fmt::Result {
+    let fields = fields.iter().map(|field| escape(field)).collect::<Vec<_>>();
+    write!(csv, "{}\r\n", fields.join(","))
+}
+
+/// Returns the 1-based start and end line of the label.
+fn line_range(label: &ReportLabel, files: &FileLibrary) -> CsvResult<(usize, usize)> {
+    let storage = files.to_storage();
+    let line_number = |offset| {
+        storage
+            .line_index(label.file_id, offset)
+            .map(|index| index + 1)
+            .ok_or(CsvError::UnknownFile(label.file_id))
+    };
+    Ok((line_number(label.range.start)?, line_number(label.range.end)?))
+}
+
+fn file_name(file_id: FileID, files: &FileLibrary) -> CsvResult<String> {
+    let file = files.to_storage().get(file_id).ok_or(CsvError::UnknownFile(file_id))?;
+    Ok(file.name().replace('"', ""))
+}
+
+/// Quotes the field if it contains a comma, a quote, or a line break.
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum CsvError {
+    InvalidFormat(#[from] fmt::Error),
+    UnknownFile(FileID),
+}
+
+type CsvResult<T> = Result<T, CsvError>;
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "failed to convert analysis results to csv")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report::Report;
+    use crate::report_code::ReportCode;
+
+    use super::*;
+
+    #[test]
+    fn test_reports_to_csv() {
+        let mut files = FileLibrary::new();
+        let file_id = files.add_file("test.circom".to_string(), "a\nbc <-- d;\ne\n".to_string());
+        let mut warning = Report::warning(
+            "Uses `<--`, not \"<==\".".to_string(),
+            ReportCode::UnecessarySignalAssignment,
+        );
+        warning.add_primary(2..12, file_id, "Assigned here.".to_string());
+        let error = Report::error("Some error.".to_string(), ReportCode::UnconstrainedSignal);
+
+        let csv = vec![warning, error].to_csv(&files).unwrap();
+        let records = csv.split("\r\n").collect::<Vec<_>>();
+        assert_eq!(records[0], CSV_HEADER.join(","));
+        assert!(records[1].starts_with(
+            "CS0013,unnecessary-signal-assignment,warning,test.circom,2,3,\"Uses `<--`, not \"\"<==\"\".\","
+        ));
+        assert!(records[2].starts_with("CA01,unconstrained-signal,error,,,,Some error.,"));
+        assert_eq!(records[3], "");
+    }
+}
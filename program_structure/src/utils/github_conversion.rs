@@ -0,0 +1,105 @@
+use codespan_reporting::files::Files;
+use std::env;
+use std::path::PathBuf;
+
+use crate::report::{MessageCategory, Report};
+use crate::file_definition::FileLibrary;
+
+/// A trait for objects that can be converted into GitHub Actions workflow
+/// commands.
+pub trait ToGithub {
+    /// Converts the object to a workflow command (like `::warning ...::message`)
+    /// which GitHub displays as an annotation on the corresponding pull request.
+    fn to_github(&self, files: &FileLibrary) -> String;
+}
+
+impl ToGithub for Report {
+    fn to_github(&self, files: &FileLibrary) -> String {
+        let command = match self.category() {
+            MessageCategory::Error => "error",
+            MessageCategory::Warning => "warning",
+            MessageCategory::Info => "notice",
+        };
+        // GitHub only supports a single location per annotation, so we use the
+        // first primary label (or first secondary label if there are no
+        // primary labels).
+        let mut properties = Vec::new();
+        let label = self.primary().first().or_else(|| self.secondary().first());
+        if let Some(label) = label {
+            let storage = files.to_storage();
+            if let Some(file) = storage.get(label.file_id) {
+                properties.push(format!("file={}", escape_property(&relative_path(file.name()))));
+            }
+            let start = storage.location(label.file_id, label.range.start);
+            let end = storage.location(label.file_id, label.range.end);
+            if let (Some(start), Some(end)) = (start, end) {
+                properties.push(format!("line={}", start.line_number));
+                properties.push(format!("endLine={}", end.line_number));
+                properties.push(format!("col={}", start.column_number));
+                properties.push(format!("endColumn={}", end.column_number));
+            }
+        }
+        properties.push(format!("title={}", escape_property(&self.id())));
+        format!("::{command} {}::{}", properties.join(","), escape_data(self.message()))
+    }
+}
+
+/// Returns the path relative to the current working directory if possible.
+/// GitHub expects paths relative to the repository root.
+fn relative_path(file_name: &str) -> String {
+    let path = PathBuf::from(file_name.replace('"', ""));
+    match env::current_dir() {
+        Ok(current_dir) => match path.strip_prefix(current_dir) {
+            Ok(path) => path.display().to_string(),
+            Err(_) => path.display().to_string(),
+        },
+        Err(_) => path.display().to_string(),
+    }
+}
+
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+fn escape_property(property: &str) -> String {
+    escape_data(property).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report_code::ReportCode;
+
+    use super::*;
+
+    const SRC: &str =
+        "template T() {\n    signal input in;\n    signal output out;\n    out <-- in;\n}\n";
+
+    #[test]
+    fn test_report_to_github() {
+        let mut files = FileLibrary::new();
+        let file_id = files.add_file("src/a,b:c.circom".to_string(), SRC.to_string());
+        let start = SRC.find("out <--").unwrap();
+        let mut report = Report::error(
+            "100% of\r\nthe value: `out`, is unconstrained.".to_string(),
+            ReportCode::FieldElementArithmetic,
+        );
+        report.add_primary(start..start + 11, file_id, "primary".to_string());
+        assert_eq!(
+            report.to_github(&files),
+            concat!(
+                "::error file=src/a%2Cb%3Ac.circom,line=4,endLine=4,col=5,endColumn=16,",
+                "title=CS0004::100%25 of%0D%0Athe value: `out`, is unconstrained."
+            )
+        );
+
+        // Reports without a location only include the title.
+        let report = Report::info("100%".to_string(), ReportCode::FieldElementArithmetic);
+        assert_eq!(report.to_github(&files), "::notice title=CS0004::100%25");
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape_data("a%b\r\nc:d,e"), "a%25b%0D%0Ac:d,e");
+        assert_eq!(escape_property("a%b\r\nc:d,e"), "a%25b%0D%0Ac%3Ad%2Ce");
+    }
+}
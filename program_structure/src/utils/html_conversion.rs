@@ -0,0 +1,273 @@
+use codespan_reporting::files::Files;
+use log::{debug, trace};
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+use std::ops::Range;
+use thiserror::Error;
+
+use crate::report::{Report, ReportCollection, ReportLabel};
+use crate::file_definition::{FileID, FileLibrary};
+
+const DOCUMENT_TITLE: &str = "Circomspect analysis results";
+const NO_LOCATION: &str = "(no location)";
+
+const STYLE: &str = r#"
+body { font-family: sans-serif; margin: 2em; color: #24292f; }
+h2 { border-bottom: 1px solid #d0d7de; padding-bottom: 0.3em; }
+details.rule > summary { font-weight: bold; cursor: pointer; margin: 0.5em 0; }
+div.report { border: 1px solid #d0d7de; border-radius: 6px; margin: 1em 0; padding: 0.5em 1em; }
+span.category { border-radius: 4px; color: white; font-size: 0.8em; padding: 0.1em 0.4em; }
+span.error { background: #cf222e; }
+span.warning { background: #bf8700; }
+span.info { background: #0969da; }
+table.snippet { border-collapse: collapse; font-family: monospace; margin: 0.5em 0; }
+table.snippet td { padding: 0 0.5em; white-space: pre; }
+table.snippet td.line { color: #6e7781; text-align: right; user-select: none; }
+mark.primary { background: #ffd8b5; }
+mark.secondary { background: #ddf4ff; }
+p.label { color: #57606a; font-size: 0.9em; margin: 0; }
+"#;
+
+/// A trait for objects that can be rendered as a standalone HTML document.
+pub trait ToHtml {
+    type Error;
+
+    /// Renders the object as HTML.
+    fn to_html(&self, files: &FileLibrary) -> Result<String, Self::Error>;
+}
+
+impl ToHtml for ReportCollection {
+    type Error = HtmlError;
+
+    fn to_html(&self, files: &FileLibrary) -> HtmlResult<String> {
+        debug!("converting report collection to html");
+        // Group reports by file and rule. Reports are grouped under the file
+        // of the first primary label (or first secondary label if there are
+        // no primary labels).
+        let mut groups: BTreeMap<String, BTreeMap<String, Vec<&Report>>> = BTreeMap::new();
+        for report in self {
+            let file_name = match first_label(report) {
+                Some(label) => file_name(label.file_id, files)?,
+                None => NO_LOCATION.to_string(),
+            };
+            groups.entry(file_name).or_default().entry(report.id()).or_default().push(report);
+        }
+        trace!("building html document");
+        let mut html = String::new();
+        writeln!(html, "<!DOCTYPE html>")?;
+        writeln!(html, "<html lang=\"en\">")?;
+        writeln!(html, "<head>")?;
+        writeln!(html, "<meta charset=\"utf-8\">")?;
+        writeln!(html, "<title>{DOCUMENT_TITLE}</title>")?;
+        writeln!(html, "<style>{STYLE}</style>")?;
+        writeln!(html, "</head>")?;
+        writeln!(html, "<body>")?;
+        writeln!(html, "<h1>{DOCUMENT_TITLE}</h1>")?;
+        writeln!(html, "<p>{} issue(s) found in {} file(s).</p>", self.len(), groups.len())?;
+        for (file_name, rules) in groups {
+            writeln!(html, "<h2>{}</h2>", escape(&file_name))?;
+            for (rule_id, reports) in rules {
+                writeln!(html, "<details class=\"rule\" open>")?;
+                writeln!(
+                    html,
+                    "<summary>{} ({} issue(s))</summary>",
+                    escape(&rule_id),
+                    reports.len()
+                )?;
+                for report in reports {
+                    html.push_str(&report.to_html(files)?);
+                }
+                writeln!(html, "</details>")?;
+            }
+        }
+        writeln!(html, "</body>")?;
+        writeln!(html, "</html>")?;
+        Ok(html)
+    }
+}
+
+impl ToHtml for Report {
+    type Error = HtmlError;
+
+    fn to_html(&self, files: &FileLibrary) -> HtmlResult<String> {
+        let category = self.category().to_string();
+        let mut html = String::new();
+        writeln!(html, "<div class=\"report\">")?;
+        writeln!(
+            html,
+            "<p><span class=\"category {category}\">{category}</span> {}</p>",
+            escape(self.message())
+        )?;
+        trace!("building source snippets");
+        for label in self.primary() {
+            html.push_str(&render_label(label, "primary", files)?);
+        }
+        for label in self.secondary() {
+            html.push_str(&render_label(label, "secondary", files)?);
+        }
+        if !self.notes().is_empty() || !self.help().is_empty() {
+            writeln!(html, "<details>")?;
+            writeln!(html, "<summary>Explanation</summary>")?;
+            writeln!(html, "<ul>")?;
+            for note in self.notes() {
+                writeln!(html, "<li>{}</li>", escape(note))?;
+            }
+            for help in self.help() {
+                writeln!(html, "<li>Help: {}</li>", escape(help))?;
+            }
+            writeln!(html, "</ul>")?;
+            writeln!(html, "</details>")?;
+        }
+        writeln!(html, "</div>")?;
+        Ok(html)
+    }
+}
+
+/// Renders the source lines covered by the label, with the labelled range
+/// highlighted.
+fn render_label(label: &ReportLabel, class: &str, files: &FileLibrary) -> HtmlResult<String> {
+    let storage = files.to_storage();
+    let unknown_location = || HtmlError::UnknownLocation(label.file_id, label.range.clone());
+    let source = storage.source(label.file_id).ok_or(HtmlError::UnknownFile(label.file_id))?;
+    let start = storage.location(label.file_id, label.range.start).ok_or_else(unknown_location)?;
+    let end_line =
+        storage.line_index(label.file_id, label.range.end).ok_or_else(unknown_location)?;
+
+    let mut html = String::new();
+    writeln!(
+        html,
+        "<p class=\"label\">{}:{}:{}: {}</p>",
+        escape(&file_name(label.file_id, files)?),
+        start.line_number,
+        start.column_number,
+        escape(&label.message)
+    )?;
+    writeln!(html, "<table class=\"snippet\">")?;
+    for line_index in (start.line_number - 1)..=end_line {
+        let line_range =
+            storage.line_range(label.file_id, line_index).ok_or_else(unknown_location)?;
+        let line = source.get(line_range.clone()).ok_or_else(unknown_location)?;
+        let line = line.trim_end_matches(['\n', '\r']);
+        // Compute the part of the line covered by the label.
+        let highlight_start =
+            label.range.start.clamp(line_range.start, line_range.start + line.len());
+        let highlight_end = label.range.end.clamp(highlight_start, line_range.start + line.len());
+        let (before, rest) = line.split_at(highlight_start - line_range.start);
+        let (highlight, after) = rest.split_at(highlight_end - highlight_start);
+        writeln!(
+            html,
+            "<tr><td class=\"line\">{}</td><td>{}<mark class=\"{class}\">{}</mark>{}</td></tr>",
+            line_index + 1,
+            escape(before),
+            escape(highlight),
+            escape(after)
+        )?;
+    }
+    writeln!(html, "</table>")?;
+    Ok(html)
+}
+
+fn first_label(report: &Report) -> Option<&ReportLabel> {
+    report.primary().first().or_else(|| report.secondary().first())
+}
+
+fn file_name(file_id: FileID, files: &FileLibrary) -> HtmlResult<String> {
+    let file = files.to_storage().get(file_id).ok_or(HtmlError::UnknownFile(file_id))?;
+    Ok(file.name().replace('"', ""))
+}
+
+/// Escapes the characters `&`, `<`, `>`, `"`, and `'`.
+fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&#39;"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+#[derive(Error, Debug)]
+pub enum HtmlError {
+    InvalidFormat(#[from] fmt::Error),
+    UnknownLocation(FileID, Range<usize>),
+    UnknownFile(FileID),
+}
+
+type HtmlResult<T> = Result<T, HtmlError>;
+
+impl fmt::Display for HtmlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "failed to convert analysis results to html")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report_code::ReportCode;
+
+    use super::*;
+
+    const SRC: &str =
+        "template T() {\n    signal input in;\n    signal output out;\n    out <-- in;\n}\n";
+
+    fn build_reports() -> (ReportCollection, FileLibrary) {
+        let mut files = FileLibrary::new();
+        let file_id = files.add_file("main.circom".to_string(), SRC.to_string());
+        let start = SRC.find("out <--").unwrap();
+        let mut report = Report::warning(
+            "Using `<--` & \"out\" here.".to_string(),
+            ReportCode::FieldElementArithmetic,
+        );
+        report.add_primary(start..start + 11, file_id, "primary <label>".to_string());
+        report.add_note("Note & more.".to_string());
+        (vec![report], files)
+    }
+
+    #[test]
+    fn test_report_to_html() {
+        let (reports, files) = build_reports();
+        let expected = concat!(
+            "<div class=\"report\">\n",
+            "<p><span class=\"category warning\">warning</span> ",
+            "Using `&lt;--` &amp; &quot;out&quot; here.</p>\n",
+            "<p class=\"label\">main.circom:4:5: primary &lt;label&gt;</p>\n",
+            "<table class=\"snippet\">\n",
+            "<tr><td class=\"line\">4</td><td>    ",
+            "<mark class=\"primary\">out &lt;-- in;</mark></td></tr>\n",
+            "</table>\n",
+            "<details>\n",
+            "<summary>Explanation</summary>\n",
+            "<ul>\n",
+            "<li>Note &amp; more.</li>\n",
+            "</ul>\n",
+            "</details>\n",
+            "</div>\n",
+        );
+        assert_eq!(reports[0].to_html(&files).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_report_collection_to_html() {
+        let (reports, files) = build_reports();
+        let html = reports.to_html(&files).unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>\n"));
+        assert!(html.contains("<p>1 issue(s) found in 1 file(s).</p>\n"));
+        assert!(html.contains("<h2>main.circom</h2>\n"));
+        assert!(html.contains("<summary>CS0004 (1 issue(s))</summary>\n"));
+        assert!(html.ends_with("</body>\n</html>\n"));
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape("a < b && c > \"d\" 'e'"),
+            "a &lt; b &amp;&amp; c &gt; &quot;d&quot; &#39;e&#39;"
+        );
+    }
+}
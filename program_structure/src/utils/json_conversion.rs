@@ -0,0 +1,79 @@
+use codespan_reporting::files::Files;
+use serde_json::{json, Value};
+
+use crate::file_definition::FileLibrary;
+use crate::report::{Report, ReportLabel};
+use crate::rules::find_rule;
+
+/// A trait for objects that can be converted into JSON values.
+pub trait ToJson {
+    /// Converts the object to a JSON value. File IDs are resolved to file
+    /// paths and byte offsets to (1-based) line and column numbers using the
+    /// given file library.
+    fn to_json(&self, files: &FileLibrary) -> Value;
+}
+
+impl ToJson for Report {
+    fn to_json(&self, files: &FileLibrary) -> Value {
+        let labels = |labels: &[ReportLabel]| {
+            labels.iter().map(|label| label.to_json(files)).collect::<Vec<_>>()
+        };
+        json!({
+            "id": self.id(),
+            "name": find_rule(&self.id()).map(|rule| rule.name),
+            "level": self.category().to_string(),
+            "message": self.message(),
+            "primary": labels(self.primary()),
+            "secondary": labels(self.secondary()),
+            "notes": self.notes(),
+            "help": self.help(),
+            "suppressed": self.suppression().is_some(),
+        })
+    }
+}
+
+impl ToJson for ReportLabel {
+    fn to_json(&self, files: &FileLibrary) -> Value {
+        let storage = files.to_storage();
+        let file = storage.get(self.file_id).map(|file| file.name().replace('"', ""));
+        let position = |offset| {
+            storage.location(self.file_id, offset).map(|location| {
+                json!({ "line": location.line_number, "column": location.column_number })
+            })
+        };
+        json!({
+            "file": file,
+            "start": position(self.range.start),
+            "end": position(self.range.end),
+            "message": self.message,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report_code::ReportCode;
+
+    use super::*;
+
+    #[test]
+    fn test_report_to_json() {
+        let mut files = FileLibrary::new();
+        let file_id = files.add_file("test.circom".to_string(), "a\nbc <-- d;\n".to_string());
+        let mut report =
+            Report::warning("Some message.".to_string(), ReportCode::UnecessarySignalAssignment);
+        report.add_primary(2..9, file_id, "Assigned here.".to_string());
+        report.add_note("Some note.".to_string());
+        report.add_help("Some suggestion.".to_string());
+
+        let json = report.to_json(&files);
+        assert_eq!(json["id"], "CS0013");
+        assert_eq!(json["level"], "warning");
+        assert_eq!(json["primary"][0]["file"], "test.circom");
+        assert_eq!(json["primary"][0]["start"], json!({ "line": 2, "column": 1 }));
+        assert_eq!(json["primary"][0]["end"], json!({ "line": 2, "column": 8 }));
+        assert_eq!(json["notes"], json!(["Some note."]));
+        assert_eq!(json["help"], json!(["Some suggestion."]));
+        assert_eq!(json["suppressed"], false);
+    }
+}
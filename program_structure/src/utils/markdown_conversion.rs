@@ -0,0 +1,240 @@
+use codespan_reporting::files::Files;
+use log::{debug, trace};
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use crate::report::{MessageCategory, Report, ReportCollection, ReportLabel};
+use crate::file_definition::{FileID, FileLibrary};
+use crate::rules::find_rule;
+
+const DOCUMENT_TITLE: &str = "Circomspect analysis results";
+
+/// Options controlling the generated Markdown output.
+#[derive(Clone, Debug, Default)]
+pub struct MarkdownOptions {
+    /// A URL template used to link each location to the corresponding source
+    /// file (e.g. `https://github.com/org/repo/blob/main/{path}#L{line}`).
+    /// `{path}` is replaced by the file path and `{line}` by the line number.
+    pub link_template: Option<String>,
+    /// If set, file paths under this directory are given relative to it.
+    pub root: Option<PathBuf>,
+}
+
+impl MarkdownOptions {
+    /// Returns the path of the file, relative to the root if the file is
+    /// contained in the root directory.
+    fn display_path(&self, path: &str) -> String {
+        let relative_path =
+            self.root.as_ref().and_then(|root| Path::new(path).strip_prefix(root).ok());
+        match relative_path {
+            Some(relative_path) => relative_path.to_string_lossy().to_string(),
+            None => path.to_string(),
+        }
+    }
+
+    /// Returns a link to the given line, if a link template is given.
+    fn link(&self, path: &str, line: usize) -> Option<String> {
+        let template = self.link_template.as_ref()?;
+        Some(template.replace("{path}", path).replace("{line}", &line.to_string()))
+    }
+}
+
+/// A trait for objects that can be rendered as a Markdown document.
+pub trait ToMarkdown {
+    type Error;
+
+    /// Renders the object as Markdown using the default options.
+    fn to_markdown(&self, files: &FileLibrary) -> Result<String, Self::Error> {
+        self.to_markdown_with_options(files, &MarkdownOptions::default())
+    }
+
+    /// Renders the object as Markdown using the given options.
+    fn to_markdown_with_options(
+        &self,
+        files: &FileLibrary,
+        options: &MarkdownOptions,
+    ) -> Result<String, Self::Error>;
+}
+
+impl ToMarkdown for ReportCollection {
+    type Error = MarkdownError;
+
+    fn to_markdown_with_options(
+        &self,
+        files: &FileLibrary,
+        options: &MarkdownOptions,
+    ) -> MarkdownResult<String> {
+        debug!("converting report collection to markdown");
+        // Group reports by severity (most severe first) and rule.
+        let mut groups: BTreeMap<MessageCategory, BTreeMap<String, Vec<&Report>>> = BTreeMap::new();
+        for report in self {
+            groups
+                .entry(*report.category())
+                .or_default()
+                .entry(report.id())
+                .or_default()
+                .push(report);
+        }
+        trace!("building markdown document");
+        let mut markdown = String::new();
+        writeln!(markdown, "# {DOCUMENT_TITLE}\n")?;
+        writeln!(markdown, "{} issue(s) found.", self.len())?;
+        for (category, rules) in groups.into_iter().rev() {
+            writeln!(markdown, "\n## {}\n", section_title(&category))?;
+            writeln!(markdown, "| Rule | Issues |")?;
+            writeln!(markdown, "| --- | ---: |")?;
+            for (rule_id, reports) in &rules {
+                writeln!(markdown, "| {} | {} |", rule_title(rule_id), reports.len())?;
+            }
+            for (rule_id, reports) in rules {
+                writeln!(markdown, "\n### {}", rule_title(&rule_id))?;
+                for (index, report) in reports.into_iter().enumerate() {
+                    writeln!(markdown, "\n#### {}. {}\n", index + 1, report.message())?;
+                    markdown.push_str(&render_report(report, files, options)?);
+                }
+            }
+        }
+        Ok(markdown)
+    }
+}
+
+impl ToMarkdown for Report {
+    type Error = MarkdownError;
+
+    fn to_markdown_with_options(
+        &self,
+        files: &FileLibrary,
+        options: &MarkdownOptions,
+    ) -> MarkdownResult<String> {
+        let mut markdown = String::new();
+        writeln!(markdown, "**{}[{}]**: {}\n", self.category(), self.id(), self.message())?;
+        markdown.push_str(&render_report(self, files, options)?);
+        Ok(markdown)
+    }
+}
+
+/// Renders the labels, notes, and suggestions of the report.
+fn render_report(
+    report: &Report,
+    files: &FileLibrary,
+    options: &MarkdownOptions,
+) -> MarkdownResult<String> {
+    trace!("building source snippets");
+    let mut markdown = String::new();
+    for label in report.primary().iter().chain(report.secondary()) {
+        markdown.push_str(&render_label(label, files, options)?);
+    }
+    for note in report.notes() {
+        writeln!(markdown, "- {note}")?;
+    }
+    for help in report.help() {
+        writeln!(markdown, "- **Help:** {help}")?;
+    }
+    Ok(markdown)
+}
+
+/// Renders a link to the location of the label, followed by the source lines
+/// covered by the label and the label message.
+fn render_label(
+    label: &ReportLabel,
+    files: &FileLibrary,
+    options: &MarkdownOptions,
+) -> MarkdownResult<String> {
+    let storage = files.to_storage();
+    let unknown_location = || MarkdownError::UnknownLocation(label.file_id, label.range.clone());
+    let source = storage.source(label.file_id).ok_or(MarkdownError::UnknownFile(label.file_id))?;
+    let start = storage.location(label.file_id, label.range.start).ok_or_else(unknown_location)?;
+    let end_line =
+        storage.line_index(label.file_id, label.range.end).ok_or_else(unknown_location)?;
+
+    let path = options.display_path(&file_name(label.file_id, files)?);
+    let location = format!("{path}:{}:{}", start.line_number, start.column_number);
+    let mut markdown = String::new();
+    match options.link(&path, start.line_number) {
+        Some(link) => writeln!(markdown, "[`{location}`]({link})\n")?,
+        None => writeln!(markdown, "`{location}`\n")?,
+    }
+    writeln!(markdown, "```circom")?;
+    for line_index in (start.line_number - 1)..=end_line {
+        let line_range =
+            storage.line_range(label.file_id, line_index).ok_or_else(unknown_location)?;
+        let line = source.get(line_range).ok_or_else(unknown_location)?;
+        writeln!(markdown, "{}", line.trim_end_matches(['\n', '\r']))?;
+    }
+    writeln!(markdown, "```\n")?;
+    if !label.message.is_empty() {
+        writeln!(markdown, "> {}\n", label.message)?;
+    }
+    Ok(markdown)
+}
+
+fn section_title(category: &MessageCategory) -> &'static str {
+    match category {
+        MessageCategory::Error => "Errors",
+        MessageCategory::Warning => "Warnings",
+        MessageCategory::Info => "Informational results",
+    }
+}
+
+fn rule_title(rule_id: &str) -> String {
+    match find_rule(rule_id) {
+        Some(rule) => format!("{rule_id}: {}", rule.name),
+        None => rule_id.to_string(),
+    }
+}
+
+fn file_name(file_id: FileID, files: &FileLibrary) -> MarkdownResult<String> {
+    let file = files.to_storage().get(file_id).ok_or(MarkdownError::UnknownFile(file_id))?;
+    Ok(file.name().replace('"', ""))
+}
+
+#[derive(Error, Debug)]
+pub enum MarkdownError {
+    InvalidFormat(#[from] fmt::Error),
+    UnknownLocation(FileID, Range<usize>),
+    UnknownFile(FileID),
+}
+
+type MarkdownResult<T> = Result<T, MarkdownError>;
+
+impl fmt::Display for MarkdownError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "failed to convert analysis results to markdown")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report_code::ReportCode;
+
+    use super::*;
+
+    #[test]
+    fn test_reports_to_markdown() {
+        let mut files = FileLibrary::new();
+        let file_id = files.add_file("/src/test.circom".to_string(), "a\nbc <-- d;\n".to_string());
+        let mut warning =
+            Report::warning("Some warning.".to_string(), ReportCode::UnecessarySignalAssignment);
+        warning.add_primary(2..9, file_id, "Assigned here.".to_string());
+        warning.add_help("Some suggestion.".to_string());
+        let error = Report::error("Some error.".to_string(), ReportCode::UnconstrainedSignal);
+
+        let options = MarkdownOptions {
+            link_template: Some("https://example.com/{path}#L{line}".to_string()),
+            root: Some(PathBuf::from("/src")),
+        };
+        let markdown = vec![warning, error].to_markdown_with_options(&files, &options).unwrap();
+        // Errors are listed before warnings.
+        let errors = markdown.find("## Errors").unwrap();
+        let warnings = markdown.find("## Warnings").unwrap();
+        assert!(errors < warnings);
+        assert!(markdown.contains("### CS0013: unnecessary-signal-assignment"));
+        assert!(markdown.contains("[`test.circom:2:1`](https://example.com/test.circom#L2)"));
+        assert!(markdown.contains("```circom\nbc <-- d;\n```"));
+        assert!(markdown.contains("> Assigned here."));
+        assert!(markdown.contains("- **Help:** Some suggestion."));
+    }
+}
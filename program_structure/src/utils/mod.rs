@@ -1,5 +1,19 @@
+pub mod candidates;
+pub mod code_quality_conversion;
 pub mod constants;
+pub mod content_hash;
+pub mod csv_conversion;
 pub mod environment;
+pub mod github_conversion;
+pub mod html_conversion;
+pub mod json_conversion;
+pub mod markdown_conversion;
 pub mod nonempty_vec;
+pub mod r1cs;
+pub mod rdjson_conversion;
 pub mod report_writer;
 pub mod sarif_conversion;
+pub mod suggestions;
+pub mod sym_file;
+pub mod symbol;
+pub mod xml_conversion;
@@ -0,0 +1,242 @@
+//! A parser for the binary R1CS format generated by the Circom compiler. (See
+//! <https://github.com/iden3/r1csfile/blob/master/doc/r1cs_bin_format.md> for
+//! a description of the format.)
+use std::collections::HashSet;
+use std::convert::TryInto;
+
+use num_bigint_dig::BigInt;
+use num_traits::Zero;
+use thiserror::Error;
+
+const MAGIC: &[u8; 4] = b"r1cs";
+const VERSION: u32 = 1;
+
+const HEADER_SECTION: u32 = 1;
+const CONSTRAINT_SECTION: u32 = 2;
+const WIRE_TO_LABEL_SECTION: u32 = 3;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum R1CSError {
+    #[error("The file is not an R1CS file.")]
+    InvalidMagic,
+    #[error("Unsupported R1CS version {0}.")]
+    UnsupportedVersion(u32),
+    #[error("The file is truncated.")]
+    UnexpectedEof,
+    #[error("The file is missing the {0} section.")]
+    MissingSection(&'static str),
+}
+
+/// A linear combination of wires, given as `(wire, coefficient)` pairs.
+pub type LinearCombination = Vec<(usize, BigInt)>;
+
+/// A constraint of the form `A * B - C = 0`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Constraint {
+    pub a: LinearCombination,
+    pub b: LinearCombination,
+    pub c: LinearCombination,
+}
+
+impl Constraint {
+    /// Returns the wires occurring with a non-zero coefficient in the
+    /// constraint.
+    pub fn wires(&self) -> impl Iterator<Item = usize> + '_ {
+        self.a
+            .iter()
+            .chain(self.b.iter())
+            .chain(self.c.iter())
+            .filter(|(_, coefficient)| !coefficient.is_zero())
+            .map(|(wire, _)| *wire)
+    }
+}
+
+/// A constraint system parsed from an R1CS file. Wire 0 is always the
+/// constant `1`, and is followed by the public outputs, public inputs, and
+/// private inputs of the main component.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct R1CS {
+    pub prime: BigInt,
+    pub wires: usize,
+    pub public_outputs: usize,
+    pub public_inputs: usize,
+    pub private_inputs: usize,
+    pub labels: usize,
+    pub constraints: Vec<Constraint>,
+    /// The label (that is, the signal index in the `.sym` file) of each wire.
+    pub wire_to_label: Vec<usize>,
+}
+
+impl R1CS {
+    /// Parses the given R1CS file contents.
+    pub fn parse(bytes: &[u8]) -> Result<R1CS, R1CSError> {
+        let mut reader = Reader { bytes };
+        if reader.take(4)? != MAGIC {
+            return Err(R1CSError::InvalidMagic);
+        }
+        let version = reader.u32()?;
+        if version != VERSION {
+            return Err(R1CSError::UnsupportedVersion(version));
+        }
+        // Sections may occur in any order, so we first locate each section.
+        let nof_sections = reader.u32()?;
+        let mut sections = Vec::new();
+        for _ in 0..nof_sections {
+            let section_type = reader.u32()?;
+            let size = reader.u64()? as usize;
+            sections.push((section_type, reader.take(size)?));
+        }
+        let section = |section_type: u32, name: &'static str| {
+            sections
+                .iter()
+                .find(|(other_type, _)| *other_type == section_type)
+                .map(|(_, bytes)| Reader { bytes })
+                .ok_or(R1CSError::MissingSection(name))
+        };
+
+        let mut header = section(HEADER_SECTION, "header")?;
+        let field_size = header.u32()? as usize;
+        let prime = header.field_element(field_size)?;
+        let wires = header.u32()? as usize;
+        let public_outputs = header.u32()? as usize;
+        let public_inputs = header.u32()? as usize;
+        let private_inputs = header.u32()? as usize;
+        let labels = header.u64()? as usize;
+        let nof_constraints = header.u32()? as usize;
+
+        let mut reader = section(CONSTRAINT_SECTION, "constraint")?;
+        let mut constraints = Vec::with_capacity(nof_constraints);
+        for _ in 0..nof_constraints {
+            let a = reader.linear_combination(field_size)?;
+            let b = reader.linear_combination(field_size)?;
+            let c = reader.linear_combination(field_size)?;
+            constraints.push(Constraint { a, b, c });
+        }
+
+        let mut reader = section(WIRE_TO_LABEL_SECTION, "wire-to-label")?;
+        let wire_to_label = (0..wires)
+            .map(|_| reader.u64().map(|label| label as usize))
+            .collect::<Result<_, _>>()?;
+
+        Ok(R1CS {
+            prime,
+            wires,
+            public_outputs,
+            public_inputs,
+            private_inputs,
+            labels,
+            constraints,
+            wire_to_label,
+        })
+    }
+
+    /// Returns the set of wires occurring in at least one constraint.
+    #[must_use]
+    pub fn constrained_wires(&self) -> HashSet<usize> {
+        self.constraints.iter().flat_map(Constraint::wires).collect()
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, size: usize) -> Result<&'a [u8], R1CSError> {
+        if self.bytes.len() < size {
+            return Err(R1CSError::UnexpectedEof);
+        }
+        let (result, rest) = self.bytes.split_at(size);
+        self.bytes = rest;
+        Ok(result)
+    }
+
+    fn u32(&mut self) -> Result<u32, R1CSError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes(bytes.try_into().expect("slice has length 4")))
+    }
+
+    fn u64(&mut self) -> Result<u64, R1CSError> {
+        let bytes = self.take(8)?;
+        Ok(u64::from_le_bytes(bytes.try_into().expect("slice has length 8")))
+    }
+
+    fn field_element(&mut self, field_size: usize) -> Result<BigInt, R1CSError> {
+        let bytes = self.take(field_size)?;
+        Ok(BigInt::from_bytes_le(num_bigint_dig::Sign::Plus, bytes))
+    }
+
+    fn linear_combination(&mut self, field_size: usize) -> Result<LinearCombination, R1CSError> {
+        let nof_factors = self.u32()?;
+        (0..nof_factors)
+            .map(|_| Ok((self.u32()? as usize, self.field_element(field_size)?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encodes an R1CS file with 8-byte field elements over the prime 23.
+    /// Each constraint is given as `(a, b, c)` lists of `(wire, coefficient)`
+    /// pairs.
+    fn encode_r1cs(
+        wires: u32,
+        constraints: &[[&[(u32, u64)]; 3]],
+        wire_to_label: &[u64],
+    ) -> Vec<u8> {
+        let mut header = Vec::new();
+        header.extend(8u32.to_le_bytes());
+        header.extend(23u64.to_le_bytes());
+        for value in [wires, 1, 1, 0] {
+            header.extend(value.to_le_bytes());
+        }
+        header.extend((wire_to_label.len() as u64).to_le_bytes());
+        header.extend((constraints.len() as u32).to_le_bytes());
+
+        let mut constraint_section = Vec::new();
+        for constraint in constraints {
+            for linear_combination in constraint {
+                constraint_section.extend((linear_combination.len() as u32).to_le_bytes());
+                for (wire, coefficient) in linear_combination.iter() {
+                    constraint_section.extend(wire.to_le_bytes());
+                    constraint_section.extend(coefficient.to_le_bytes());
+                }
+            }
+        }
+        let labels = wire_to_label.iter().flat_map(|label| label.to_le_bytes()).collect();
+
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend(VERSION.to_le_bytes());
+        bytes.extend(3u32.to_le_bytes());
+        // Sections are not required to be ordered.
+        for (section_type, section) in [
+            (CONSTRAINT_SECTION, constraint_section),
+            (HEADER_SECTION, header),
+            (WIRE_TO_LABEL_SECTION, labels),
+        ] {
+            bytes.extend(section_type.to_le_bytes());
+            bytes.extend((section.len() as u64).to_le_bytes());
+            bytes.extend(section);
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_parse_r1cs() {
+        // Wires are `1`, `out`, `in`, and `tmp`. The constraint is `in * in = out`.
+        let bytes = encode_r1cs(4, &[[&[(2, 1)], &[(2, 1)], &[(1, 1), (3, 0)]]], &[0, 1, 2, 4]);
+        let r1cs = R1CS::parse(&bytes).unwrap();
+        assert_eq!(r1cs.prime, BigInt::from(23));
+        assert_eq!(r1cs.wires, 4);
+        assert_eq!((r1cs.public_outputs, r1cs.public_inputs, r1cs.private_inputs), (1, 1, 0));
+        assert_eq!(r1cs.constraints.len(), 1);
+        assert_eq!(r1cs.wire_to_label, [0, 1, 2, 4]);
+        // Wires with a zero coefficient do not occur in the constraint.
+        assert_eq!(r1cs.constrained_wires(), HashSet::from([1, 2]));
+
+        assert_eq!(R1CS::parse(b"wasm"), Err(R1CSError::InvalidMagic));
+        assert_eq!(R1CS::parse(&bytes[..bytes.len() - 1]), Err(R1CSError::UnexpectedEof));
+    }
+}
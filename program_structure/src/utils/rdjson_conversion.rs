@@ -0,0 +1,115 @@
+use codespan_reporting::files::Files;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+use crate::file_definition::FileLibrary;
+use crate::report::{MessageCategory, Report, ReportCollection, ReportLabel};
+
+const SOURCE_NAME: &str = "circomspect";
+const SOURCE_URL: &str = "https://github.com/trailofbits/circomspect";
+
+/// A trait for objects that can be converted into the Reviewdog Diagnostic
+/// Format (rdjson).
+pub trait ToRdjson {
+    /// Converts the object to an rdjson value. If a root directory is given,
+    /// file paths under the root are given relative to the root.
+    fn to_rdjson(&self, files: &FileLibrary, root: Option<&Path>) -> Value;
+}
+
+impl ToRdjson for ReportCollection {
+    fn to_rdjson(&self, files: &FileLibrary, root: Option<&Path>) -> Value {
+        // Reviewdog can only comment on reports with a location, so reports
+        // without a label are dropped.
+        let diagnostics = self
+            .iter()
+            .filter(|report| !report.primary().is_empty() || !report.secondary().is_empty())
+            .map(|report| report.to_rdjson(files, root))
+            .collect::<Vec<_>>();
+        json!({
+            "source": { "name": SOURCE_NAME, "url": SOURCE_URL },
+            "diagnostics": diagnostics,
+        })
+    }
+}
+
+impl ToRdjson for Report {
+    fn to_rdjson(&self, files: &FileLibrary, root: Option<&Path>) -> Value {
+        let mut labels = self.primary().iter().chain(self.secondary());
+        let location = labels.next().map(|label| label.to_rdjson(files, root));
+        let related_locations = labels
+            .map(|label| {
+                json!({ "message": label.message, "location": label.to_rdjson(files, root) })
+            })
+            .collect::<Vec<_>>();
+        let severity = match self.category() {
+            MessageCategory::Error => "ERROR",
+            MessageCategory::Warning => "WARNING",
+            MessageCategory::Info => "INFO",
+        };
+        // Notes and suggestions are included in the message, since rdjson
+        // suggestions must be given as text replacements.
+        let mut message = vec![self.message().clone()];
+        message.extend(self.notes().iter().cloned());
+        message.extend(self.help().iter().map(|help| format!("Help: {help}")));
+        json!({
+            "message": message.join("\n\n"),
+            "location": location,
+            "severity": severity,
+            "code": { "value": self.id() },
+            "related_locations": related_locations,
+        })
+    }
+}
+
+impl ToRdjson for ReportLabel {
+    fn to_rdjson(&self, files: &FileLibrary, root: Option<&Path>) -> Value {
+        let storage = files.to_storage();
+        let path =
+            storage.get(self.file_id).map(|file| PathBuf::from(file.name().replace('"', "")));
+        let path = path.map(|path| {
+            let relative_path = root.and_then(|root| path.strip_prefix(root).ok());
+            relative_path.unwrap_or(&path).to_string_lossy().to_string()
+        });
+        let position = |offset| {
+            storage.location(self.file_id, offset).map(|location| {
+                json!({ "line": location.line_number, "column": location.column_number })
+            })
+        };
+        json!({
+            "path": path,
+            "range": { "start": position(self.range.start), "end": position(self.range.end) },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report_code::ReportCode;
+
+    use super::*;
+
+    #[test]
+    fn test_reports_to_rdjson() {
+        let mut files = FileLibrary::new();
+        let file_id = files.add_file("/src/test.circom".to_string(), "a\nbc <-- d;\n".to_string());
+        let mut warning =
+            Report::warning("Some warning.".to_string(), ReportCode::UnecessarySignalAssignment);
+        warning.add_primary(2..9, file_id, "Assigned here.".to_string());
+        warning.add_secondary(0..1, file_id, Some("Declared here.".to_string()));
+        warning.add_help("Some suggestion.".to_string());
+        let error = Report::error("Some error.".to_string(), ReportCode::UnconstrainedSignal);
+
+        let json = vec![warning, error].to_rdjson(&files, Some(Path::new("/src")));
+        assert_eq!(json["source"]["name"], SOURCE_NAME);
+        // Reports without a location are dropped.
+        assert_eq!(json["diagnostics"].as_array().unwrap().len(), 1);
+        let diagnostic = &json["diagnostics"][0];
+        assert_eq!(diagnostic["message"], "Some warning.\n\nHelp: Some suggestion.");
+        assert_eq!(diagnostic["severity"], "WARNING");
+        assert_eq!(diagnostic["code"]["value"], "CS0013");
+        assert_eq!(diagnostic["location"]["path"], "test.circom");
+        assert_eq!(diagnostic["location"]["range"]["start"], json!({ "line": 2, "column": 1 }));
+        assert_eq!(diagnostic["location"]["range"]["end"], json!({ "line": 2, "column": 8 }));
+        assert_eq!(diagnostic["related_locations"][0]["message"], "Declared here.");
+    }
+}
@@ -2,12 +2,22 @@ use anyhow;
 use anyhow::Context;
 use log::{info, warn};
 use std::fs::File;
-use std::io::Write;
+use std::io::{self, Write};
 use std::path::{PathBuf, Path};
 
-use crate::sarif_conversion::ToSarif;
+use crate::candidates::to_candidates;
+use crate::code_quality_conversion::ToCodeQuality;
+use crate::csv_conversion::ToCsv;
+use crate::github_conversion::ToGithub;
+use crate::html_conversion::ToHtml;
+use crate::json_conversion::ToJson;
+use crate::markdown_conversion::{MarkdownOptions, ToMarkdown};
+use crate::rdjson_conversion::ToRdjson;
+use crate::sarif_conversion::{SarifOptions, ToSarif};
+use crate::xml_conversion::{ToCheckstyle, ToJUnit};
 use crate::{
     program_library::report::{Report, ReportCollection},
+    program_library::rules::find_rule,
     file_definition::FileLibrary,
 };
 
@@ -29,6 +39,9 @@ pub trait ReportWriter {
     /// Returns the number of reports written.
     #[must_use]
     fn written(&self) -> usize;
+
+    /// Write any buffered reports. This is called once the analysis is done.
+    fn finish(&mut self, _file_library: &FileLibrary) {}
 }
 
 #[derive(Default)]
@@ -36,6 +49,8 @@ pub struct StdoutWriter {
     verbose: bool,
     written: usize,
     filters: Vec<Box<dyn ReportFilter>>,
+    max_reports_per_rule: Option<usize>,
+    buffered: ReportCollection,
 }
 
 impl StdoutWriter {
@@ -43,6 +58,14 @@ impl StdoutWriter {
         StdoutWriter { verbose, ..Default::default() }
     }
 
+    /// Group reports by rule, and only print the given number of reports for
+    /// each rule. Since reports are grouped, they are buffered and printed
+    /// when the writer is finished.
+    pub fn with_max_reports_per_rule(mut self, max_reports_per_rule: usize) -> StdoutWriter {
+        self.max_reports_per_rule = Some(max_reports_per_rule);
+        self
+    }
+
     pub fn add_filter(mut self, filter: impl ReportFilter + 'static) -> StdoutWriter {
         self.filters.push(Box::new(filter));
         self
@@ -60,20 +83,195 @@ impl StdoutWriter {
 impl ReportWriter for StdoutWriter {
     fn write(&mut self, reports: &ReportCollection, file_library: &FileLibrary) -> usize {
         let reports = self.filter(reports);
-        Report::print_reports(&reports, file_library, self.verbose);
+        let written = reports.len();
+        if self.max_reports_per_rule.is_some() {
+            self.buffered.extend(reports);
+        } else {
+            Report::print_reports(&reports, file_library, self.verbose);
+        }
+        self.written += written;
+        written
+    }
+
+    /// Returns the number of reports written.
+    fn written(&self) -> usize {
+        self.written
+    }
+
+    /// Prints the buffered reports grouped by rule. Rules with more severe
+    /// reports are printed first.
+    fn finish(&mut self, file_library: &FileLibrary) {
+        let Some(max_reports_per_rule) = self.max_reports_per_rule else {
+            return;
+        };
+        let mut groups: Vec<(String, ReportCollection)> = Vec::new();
+        for report in self.buffered.drain(..) {
+            match groups.iter_mut().find(|(id, _)| id == &report.id()) {
+                Some((_, reports)) => reports.push(report),
+                None => groups.push((report.id(), vec![report])),
+            }
+        }
+        let max_category =
+            |reports: &ReportCollection| reports.iter().map(|report| *report.category()).max();
+        groups.sort_by(|(lhs_id, lhs), (rhs_id, rhs)| {
+            max_category(rhs).cmp(&max_category(lhs)).then_with(|| lhs_id.cmp(rhs_id))
+        });
+        for (id, reports) in groups {
+            let shown = reports.len().min(max_reports_per_rule);
+            Report::print_reports(&reports[..shown], file_library, self.verbose);
+            if reports.len() > shown {
+                let name =
+                    find_rule(&id).map(|rule| format!(" ({})", rule.name)).unwrap_or_default();
+                println!("… and {} more {id}{name} reports.\n", reports.len() - shown);
+            }
+        }
+    }
+}
+
+/// Writes reports to stdout as GitHub Actions workflow commands.
+#[derive(Default)]
+pub struct GithubWriter {
+    written: usize,
+    filters: Vec<Box<dyn ReportFilter>>,
+}
+
+impl GithubWriter {
+    pub fn new() -> GithubWriter {
+        GithubWriter::default()
+    }
+
+    pub fn add_filter(mut self, filter: impl ReportFilter + 'static) -> GithubWriter {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    fn filter(&self, reports: &ReportCollection) -> ReportCollection {
+        reports
+            .iter()
+            .filter(|report| self.filters.iter().all(|f| f.filter(report)))
+            .cloned()
+            .collect()
+    }
+}
+
+impl ReportWriter for GithubWriter {
+    fn write(&mut self, reports: &ReportCollection, file_library: &FileLibrary) -> usize {
+        let reports = self.filter(reports);
+        for report in &reports {
+            println!("{}", report.to_github(file_library));
+        }
         self.written += reports.len();
         reports.len()
     }
 
-    /// Returns the number of reports written.
     fn written(&self) -> usize {
         self.written
     }
 }
 
+/// Writes reports to stdout as JSON Lines (one JSON object per report). The
+/// output is flushed after each call to `write`, which allows other tools to
+/// process reports while the analysis is still running.
+#[derive(Default)]
+pub struct JsonLinesWriter {
+    written: usize,
+    filters: Vec<Box<dyn ReportFilter>>,
+}
+
+impl JsonLinesWriter {
+    pub fn new() -> JsonLinesWriter {
+        JsonLinesWriter::default()
+    }
+
+    pub fn add_filter(mut self, filter: impl ReportFilter + 'static) -> JsonLinesWriter {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    fn filter(&self, reports: &ReportCollection) -> ReportCollection {
+        reports
+            .iter()
+            .filter(|report| self.filters.iter().all(|f| f.filter(report)))
+            .cloned()
+            .collect()
+    }
+}
+
+impl ReportWriter for JsonLinesWriter {
+    fn write(&mut self, reports: &ReportCollection, file_library: &FileLibrary) -> usize {
+        let reports = self.filter(reports);
+        let mut stdout = io::stdout().lock();
+        for report in &reports {
+            // We ignore write failures (e.g. if the reader closed the pipe).
+            let _ = writeln!(stdout, "{}", report.to_json(file_library));
+        }
+        let _ = stdout.flush();
+        self.written += reports.len();
+        reports.len()
+    }
+
+    fn written(&self) -> usize {
+        self.written
+    }
+}
+
+/// Writes the signals affected by reports to a JSON file, as input for
+/// SMT-based tools like Picus. Since the file contains a single JSON document,
+/// all candidates written so far are included each time the file is written.
+#[derive(Default)]
+pub struct CandidatesWriter {
+    candidates_file: PathBuf,
+    reports: ReportCollection,
+    filters: Vec<Box<dyn ReportFilter>>,
+}
+
+impl CandidatesWriter {
+    pub fn new(candidates_file: &Path) -> CandidatesWriter {
+        CandidatesWriter { candidates_file: candidates_file.to_owned(), ..Default::default() }
+    }
+
+    pub fn add_filter(mut self, filter: impl ReportFilter + 'static) -> CandidatesWriter {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    fn filter(&self, reports: &ReportCollection) -> ReportCollection {
+        reports
+            .iter()
+            .filter(|report| !report.affected_signals().is_empty())
+            .filter(|report| self.filters.iter().all(|f| f.filter(report)))
+            .cloned()
+            .collect()
+    }
+}
+
+impl ReportWriter for CandidatesWriter {
+    fn write(&mut self, reports: &ReportCollection, file_library: &FileLibrary) -> usize {
+        let mut reports = self.filter(reports);
+        let written = reports.len();
+        self.reports.append(&mut reports);
+        let candidates = to_candidates(&self.reports, file_library).to_string();
+        match std::fs::write(&self.candidates_file, candidates) {
+            Ok(()) => {
+                info!("candidates written to `{}`", self.candidates_file.display());
+                written
+            }
+            Err(_) => {
+                warn!("failed to write candidates to `{}`", self.candidates_file.display());
+                0
+            }
+        }
+    }
+
+    fn written(&self) -> usize {
+        self.reports.len()
+    }
+}
+
 #[derive(Default)]
 pub struct SarifWriter {
     sarif_file: PathBuf,
+    options: SarifOptions,
     written: usize,
     filters: Vec<Box<dyn ReportFilter>>,
 }
@@ -88,6 +286,11 @@ impl SarifWriter {
         self
     }
 
+    pub fn with_options(mut self, options: SarifOptions) -> SarifWriter {
+        self.options = options;
+        self
+    }
+
     fn filter(&self, reports: &ReportCollection) -> ReportCollection {
         reports
             .iter()
@@ -101,8 +304,9 @@ impl SarifWriter {
         reports: &ReportCollection,
         file_library: &FileLibrary,
     ) -> anyhow::Result<()> {
-        let sarif =
-            reports.to_sarif(file_library).context("failed to convert reports to Sarif format")?;
+        let sarif = reports
+            .to_sarif_with_options(file_library, &self.options)
+            .context("failed to convert reports to Sarif format")?;
         let json = serde_json::to_string_pretty(&sarif)?;
         let mut sarif_file = File::create(&self.sarif_file)?;
         writeln!(sarif_file, "{}", &json)
@@ -131,3 +335,453 @@ impl ReportWriter for SarifWriter {
         self.written
     }
 }
+
+/// Writes reports to a standalone HTML file. Since reports are grouped by file
+/// and rule, all reports written so far are included each time the file is
+/// written.
+#[derive(Default)]
+pub struct HtmlWriter {
+    html_file: PathBuf,
+    reports: ReportCollection,
+    filters: Vec<Box<dyn ReportFilter>>,
+}
+
+impl HtmlWriter {
+    pub fn new(html_file: &Path) -> HtmlWriter {
+        HtmlWriter { html_file: html_file.to_owned(), ..Default::default() }
+    }
+
+    pub fn add_filter(mut self, filter: impl ReportFilter + 'static) -> HtmlWriter {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    fn filter(&self, reports: &ReportCollection) -> ReportCollection {
+        reports
+            .iter()
+            .filter(|report| self.filters.iter().all(|f| f.filter(report)))
+            .cloned()
+            .collect()
+    }
+
+    fn serialize_reports(
+        &self,
+        reports: &ReportCollection,
+        file_library: &FileLibrary,
+    ) -> anyhow::Result<()> {
+        let html =
+            reports.to_html(file_library).context("failed to convert reports to HTML format")?;
+        let mut html_file = File::create(&self.html_file)?;
+        write!(html_file, "{}", &html)
+            .with_context(|| format!("could not write to {}", self.html_file.display()))?;
+        Ok(())
+    }
+}
+
+impl ReportWriter for HtmlWriter {
+    fn write(&mut self, reports: &ReportCollection, file_library: &FileLibrary) -> usize {
+        let mut reports = self.filter(reports);
+        let written = reports.len();
+        self.reports.append(&mut reports);
+        match self.serialize_reports(&self.reports, file_library) {
+            Ok(()) => {
+                info!("reports written to `{}`", self.html_file.display());
+                written
+            }
+            Err(_) => {
+                warn!("failed to write reports to `{}`", self.html_file.display());
+                0
+            }
+        }
+    }
+
+    fn written(&self) -> usize {
+        self.reports.len()
+    }
+}
+
+/// Writes reports to a Markdown file. Since reports are grouped by severity
+/// and rule, all reports written so far are included each time the file is
+/// written.
+#[derive(Default)]
+pub struct MarkdownWriter {
+    markdown_file: PathBuf,
+    options: MarkdownOptions,
+    reports: ReportCollection,
+    filters: Vec<Box<dyn ReportFilter>>,
+}
+
+impl MarkdownWriter {
+    pub fn new(markdown_file: &Path) -> MarkdownWriter {
+        MarkdownWriter { markdown_file: markdown_file.to_owned(), ..Default::default() }
+    }
+
+    pub fn add_filter(mut self, filter: impl ReportFilter + 'static) -> MarkdownWriter {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    pub fn with_options(mut self, options: MarkdownOptions) -> MarkdownWriter {
+        self.options = options;
+        self
+    }
+
+    fn filter(&self, reports: &ReportCollection) -> ReportCollection {
+        reports
+            .iter()
+            .filter(|report| self.filters.iter().all(|f| f.filter(report)))
+            .cloned()
+            .collect()
+    }
+
+    fn serialize_reports(
+        &self,
+        reports: &ReportCollection,
+        file_library: &FileLibrary,
+    ) -> anyhow::Result<()> {
+        let markdown = reports
+            .to_markdown_with_options(file_library, &self.options)
+            .context("failed to convert reports to Markdown format")?;
+        let mut markdown_file = File::create(&self.markdown_file)?;
+        write!(markdown_file, "{}", &markdown)
+            .with_context(|| format!("could not write to {}", self.markdown_file.display()))?;
+        Ok(())
+    }
+}
+
+impl ReportWriter for MarkdownWriter {
+    fn write(&mut self, reports: &ReportCollection, file_library: &FileLibrary) -> usize {
+        let mut reports = self.filter(reports);
+        let written = reports.len();
+        self.reports.append(&mut reports);
+        match self.serialize_reports(&self.reports, file_library) {
+            Ok(()) => {
+                info!("reports written to `{}`", self.markdown_file.display());
+                written
+            }
+            Err(_) => {
+                warn!("failed to write reports to `{}`", self.markdown_file.display());
+                0
+            }
+        }
+    }
+
+    fn written(&self) -> usize {
+        self.reports.len()
+    }
+}
+
+/// Writes reports to a Checkstyle XML file. All reports written so far are
+/// included each time the file is written.
+#[derive(Default)]
+pub struct CheckstyleWriter {
+    xml_file: PathBuf,
+    reports: ReportCollection,
+    filters: Vec<Box<dyn ReportFilter>>,
+}
+
+impl CheckstyleWriter {
+    pub fn new(xml_file: &Path) -> CheckstyleWriter {
+        CheckstyleWriter { xml_file: xml_file.to_owned(), ..Default::default() }
+    }
+
+    pub fn add_filter(mut self, filter: impl ReportFilter + 'static) -> CheckstyleWriter {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    fn filter(&self, reports: &ReportCollection) -> ReportCollection {
+        reports
+            .iter()
+            .filter(|report| self.filters.iter().all(|f| f.filter(report)))
+            .cloned()
+            .collect()
+    }
+
+    fn serialize_reports(
+        &self,
+        reports: &ReportCollection,
+        file_library: &FileLibrary,
+    ) -> anyhow::Result<()> {
+        let xml = reports
+            .to_checkstyle(file_library)
+            .context("failed to convert reports to Checkstyle format")?;
+        let mut xml_file = File::create(&self.xml_file)?;
+        write!(xml_file, "{}", &xml)
+            .with_context(|| format!("could not write to {}", self.xml_file.display()))?;
+        Ok(())
+    }
+}
+
+impl ReportWriter for CheckstyleWriter {
+    fn write(&mut self, reports: &ReportCollection, file_library: &FileLibrary) -> usize {
+        let mut reports = self.filter(reports);
+        let written = reports.len();
+        self.reports.append(&mut reports);
+        match self.serialize_reports(&self.reports, file_library) {
+            Ok(()) => {
+                info!("reports written to `{}`", self.xml_file.display());
+                written
+            }
+            Err(_) => {
+                warn!("failed to write reports to `{}`", self.xml_file.display());
+                0
+            }
+        }
+    }
+
+    fn written(&self) -> usize {
+        self.reports.len()
+    }
+}
+
+/// Writes reports to a JUnit XML file. All reports written so far are
+/// included each time the file is written.
+#[derive(Default)]
+pub struct JUnitWriter {
+    xml_file: PathBuf,
+    reports: ReportCollection,
+    filters: Vec<Box<dyn ReportFilter>>,
+}
+
+impl JUnitWriter {
+    pub fn new(xml_file: &Path) -> JUnitWriter {
+        JUnitWriter { xml_file: xml_file.to_owned(), ..Default::default() }
+    }
+
+    pub fn add_filter(mut self, filter: impl ReportFilter + 'static) -> JUnitWriter {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    fn filter(&self, reports: &ReportCollection) -> ReportCollection {
+        reports
+            .iter()
+            .filter(|report| self.filters.iter().all(|f| f.filter(report)))
+            .cloned()
+            .collect()
+    }
+
+    fn serialize_reports(
+        &self,
+        reports: &ReportCollection,
+        file_library: &FileLibrary,
+    ) -> anyhow::Result<()> {
+        let xml =
+            reports.to_junit(file_library).context("failed to convert reports to JUnit format")?;
+        let mut xml_file = File::create(&self.xml_file)?;
+        write!(xml_file, "{}", &xml)
+            .with_context(|| format!("could not write to {}", self.xml_file.display()))?;
+        Ok(())
+    }
+}
+
+impl ReportWriter for JUnitWriter {
+    fn write(&mut self, reports: &ReportCollection, file_library: &FileLibrary) -> usize {
+        let mut reports = self.filter(reports);
+        let written = reports.len();
+        self.reports.append(&mut reports);
+        match self.serialize_reports(&self.reports, file_library) {
+            Ok(()) => {
+                info!("reports written to `{}`", self.xml_file.display());
+                written
+            }
+            Err(_) => {
+                warn!("failed to write reports to `{}`", self.xml_file.display());
+                0
+            }
+        }
+    }
+
+    fn written(&self) -> usize {
+        self.reports.len()
+    }
+}
+
+/// Writes reports to a CSV file. All reports written so far are included each
+/// time the file is written.
+#[derive(Default)]
+pub struct CsvWriter {
+    csv_file: PathBuf,
+    reports: ReportCollection,
+    filters: Vec<Box<dyn ReportFilter>>,
+}
+
+impl CsvWriter {
+    pub fn new(csv_file: &Path) -> CsvWriter {
+        CsvWriter { csv_file: csv_file.to_owned(), ..Default::default() }
+    }
+
+    pub fn add_filter(mut self, filter: impl ReportFilter + 'static) -> CsvWriter {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    fn filter(&self, reports: &ReportCollection) -> ReportCollection {
+        reports
+            .iter()
+            .filter(|report| self.filters.iter().all(|f| f.filter(report)))
+            .cloned()
+            .collect()
+    }
+
+    fn serialize_reports(
+        &self,
+        reports: &ReportCollection,
+        file_library: &FileLibrary,
+    ) -> anyhow::Result<()> {
+        let csv =
+            reports.to_csv(file_library).context("failed to convert reports to CSV format")?;
+        let mut csv_file = File::create(&self.csv_file)?;
+        write!(csv_file, "{}", &csv)
+            .with_context(|| format!("could not write to {}", self.csv_file.display()))?;
+        Ok(())
+    }
+}
+
+impl ReportWriter for CsvWriter {
+    fn write(&mut self, reports: &ReportCollection, file_library: &FileLibrary) -> usize {
+        let mut reports = self.filter(reports);
+        let written = reports.len();
+        self.reports.append(&mut reports);
+        match self.serialize_reports(&self.reports, file_library) {
+            Ok(()) => {
+                info!("reports written to `{}`", self.csv_file.display());
+                written
+            }
+            Err(_) => {
+                warn!("failed to write reports to `{}`", self.csv_file.display());
+                0
+            }
+        }
+    }
+
+    fn written(&self) -> usize {
+        self.reports.len()
+    }
+}
+
+/// Writes reports to a GitLab Code Quality (Code Climate) JSON file. All
+/// reports written so far are included each time the file is written.
+#[derive(Default)]
+pub struct CodeQualityWriter {
+    json_file: PathBuf,
+    root: Option<PathBuf>,
+    reports: ReportCollection,
+    filters: Vec<Box<dyn ReportFilter>>,
+}
+
+impl CodeQualityWriter {
+    pub fn new(json_file: &Path) -> CodeQualityWriter {
+        CodeQualityWriter { json_file: json_file.to_owned(), ..Default::default() }
+    }
+
+    pub fn add_filter(mut self, filter: impl ReportFilter + 'static) -> CodeQualityWriter {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    /// Emit file paths relative to the given root directory.
+    pub fn with_root(mut self, root: &Path) -> CodeQualityWriter {
+        self.root = Some(root.to_owned());
+        self
+    }
+
+    fn filter(&self, reports: &ReportCollection) -> ReportCollection {
+        reports
+            .iter()
+            .filter(|report| self.filters.iter().all(|f| f.filter(report)))
+            .cloned()
+            .collect()
+    }
+
+    fn serialize_reports(
+        &self,
+        reports: &ReportCollection,
+        file_library: &FileLibrary,
+    ) -> anyhow::Result<()> {
+        let json = reports.to_code_quality(file_library, self.root.as_deref());
+        let json_file = File::create(&self.json_file)?;
+        serde_json::to_writer_pretty(json_file, &json)
+            .with_context(|| format!("could not write to {}", self.json_file.display()))?;
+        Ok(())
+    }
+}
+
+impl ReportWriter for CodeQualityWriter {
+    fn write(&mut self, reports: &ReportCollection, file_library: &FileLibrary) -> usize {
+        let mut reports = self.filter(reports);
+        let written = reports.len();
+        self.reports.append(&mut reports);
+        match self.serialize_reports(&self.reports, file_library) {
+            Ok(()) => {
+                info!("reports written to `{}`", self.json_file.display());
+                written
+            }
+            Err(_) => {
+                warn!("failed to write reports to `{}`", self.json_file.display());
+                0
+            }
+        }
+    }
+
+    fn written(&self) -> usize {
+        self.reports.len()
+    }
+}
+
+/// Writes reports to stdout in the Reviewdog Diagnostic Format (rdjson). Since
+/// the output is a single JSON document, reports are buffered and printed when
+/// the writer is finished.
+#[derive(Default)]
+pub struct RdjsonWriter {
+    root: Option<PathBuf>,
+    reports: ReportCollection,
+    filters: Vec<Box<dyn ReportFilter>>,
+}
+
+impl RdjsonWriter {
+    pub fn new() -> RdjsonWriter {
+        RdjsonWriter::default()
+    }
+
+    pub fn add_filter(mut self, filter: impl ReportFilter + 'static) -> RdjsonWriter {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    /// Emit file paths relative to the given root directory.
+    pub fn with_root(mut self, root: &Path) -> RdjsonWriter {
+        self.root = Some(root.to_owned());
+        self
+    }
+
+    fn filter(&self, reports: &ReportCollection) -> ReportCollection {
+        reports
+            .iter()
+            .filter(|report| self.filters.iter().all(|f| f.filter(report)))
+            .cloned()
+            .collect()
+    }
+}
+
+impl ReportWriter for RdjsonWriter {
+    fn write(&mut self, reports: &ReportCollection, _: &FileLibrary) -> usize {
+        let mut reports = self.filter(reports);
+        let written = reports.len();
+        self.reports.append(&mut reports);
+        written
+    }
+
+    fn written(&self) -> usize {
+        self.reports.len()
+    }
+
+    fn finish(&mut self, file_library: &FileLibrary) {
+        let json = self.reports.to_rdjson(file_library, self.root.as_deref());
+        let mut stdout = io::stdout().lock();
+        if writeln!(stdout, "{json}").is_err() {
+            warn!("failed to write reports to stdout");
+        }
+    }
+}
@@ -1,45 +1,123 @@
 use codespan_reporting::files::Files;
 use log::{debug, trace};
 use serde_sarif::sarif;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::ops::Range;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
-use crate::report::{Report, ReportCollection, ReportLabel};
+use crate::content_hash::content_hash;
+use crate::report::{Report, ReportCollection, ReportLabel, SuppressionKind};
 use crate::file_definition::{FileID, FileLibrary};
 
 // This is the Sarif file format version, not the tool version.
 const SARIF_VERSION: &str = "2.1.0";
 const DRIVER_NAME: &str = "Circomspect";
 const ORGANIZATION: &str = "Trail of Bits";
+/// The key used for the partial fingerprints of Sarif results.
+pub const FINGERPRINT_KEY: &str = "circomspectContextHash/v1";
+
+/// The default URI base ID used for paths relative to the Sarif root.
+pub const DEFAULT_URI_BASE_ID: &str = "SRCROOT";
+
+/// Options controlling the generated Sarif output.
+#[derive(Clone, Debug)]
+pub struct SarifOptions {
+    /// Emit all primary labels as result locations, and all secondary labels
+    /// as related locations. By default, only the first label of each report
+    /// is emitted.
+    pub full_locations: bool,
+    /// If set, files under this directory are referenced using paths relative
+    /// to the URI base ID `uri_base_id`, rather than using absolute URIs.
+    pub root: Option<PathBuf>,
+    /// The URI base ID used for paths relative to `root`.
+    pub uri_base_id: String,
+}
+
+impl Default for SarifOptions {
+    fn default() -> SarifOptions {
+        SarifOptions {
+            full_locations: false,
+            root: None,
+            uri_base_id: DEFAULT_URI_BASE_ID.to_string(),
+        }
+    }
+}
+
+impl SarifOptions {
+    /// Returns the path relative to the Sarif root, if the path is contained
+    /// in the root directory.
+    fn relative_path(&self, path: &Path) -> Option<PathBuf> {
+        relative_path(path, self.root.as_ref()?)
+    }
+
+    /// Returns the URI of the root directory.
+    fn root_uri(&self) -> Option<String> {
+        let root = self.root.as_ref()?;
+        let root = fs::canonicalize(root).unwrap_or_else(|_| root.clone());
+        // URIs used as base IDs must end with a slash.
+        Some(format!("file://{}/", root.to_string_lossy().trim_end_matches('/')))
+    }
+}
+
+/// Returns the path relative to the given root, if the path is contained in
+/// the root directory.
+fn relative_path(path: &Path, root: &Path) -> Option<PathBuf> {
+    if let Ok(relative_path) = path.strip_prefix(root) {
+        return Some(relative_path.to_path_buf());
+    }
+    // The paths may not be given in the same form, so we also compare the
+    // canonicalized paths.
+    let (path, root) = (fs::canonicalize(path).ok()?, fs::canonicalize(root).ok()?);
+    path.strip_prefix(root).ok().map(Path::to_path_buf)
+}
 
 /// A trait for objects that can be converted into a Sarif artifact.
 pub trait ToSarif {
     type Sarif;
     type Error;
 
-    /// Converts the object to the corresponding Sarif artifact.
-    fn to_sarif(&self, files: &FileLibrary) -> Result<Self::Sarif, Self::Error>;
+    /// Converts the object to the corresponding Sarif artifact using the
+    /// default options.
+    fn to_sarif(&self, files: &FileLibrary) -> Result<Self::Sarif, Self::Error> {
+        self.to_sarif_with_options(files, &SarifOptions::default())
+    }
+
+    /// Converts the object to the corresponding Sarif artifact using the
+    /// given options.
+    fn to_sarif_with_options(
+        &self,
+        files: &FileLibrary,
+        options: &SarifOptions,
+    ) -> Result<Self::Sarif, Self::Error>;
 }
 
 impl ToSarif for ReportCollection {
     type Sarif = sarif::Sarif;
     type Error = SarifError;
 
-    fn to_sarif(&self, files: &FileLibrary) -> Result<Self::Sarif, Self::Error> {
+    fn to_sarif_with_options(
+        &self,
+        files: &FileLibrary,
+        options: &SarifOptions,
+    ) -> Result<Self::Sarif, Self::Error> {
         debug!("converting report collection to sarif-format");
         // Build reporting descriptors.
         let rules = self
             .iter()
             .map(|report| {
-                sarif::ReportingDescriptorBuilder::default()
+                let properties = sarif::PropertyBagBuilder::default()
+                    .tags(vec![report.code().rule_category().to_string()])
+                    .build()?;
+                Ok(sarif::ReportingDescriptorBuilder::default()
                     .name(report.id())
                     .id(report.id())
-                    .build()
+                    .properties(properties)
+                    .build()?)
             })
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(SarifError::from)?;
+            .collect::<SarifResult<Vec<_>>>()?;
         // Build tool.
         trace!("building tool");
         // TODO: Should include version.
@@ -49,11 +127,48 @@ impl ToSarif for ReportCollection {
             .rules(rules)
             .build()?;
         let tool = sarif::ToolBuilder::default().driver(driver).build()?;
+        // Build artifacts for all files referenced by the reports.
+        trace!("building artifacts");
+        let mut file_ids = Vec::new();
+        for label in
+            self.iter().flat_map(|report| report.primary().iter().chain(report.secondary()))
+        {
+            if !file_ids.contains(&label.file_id) {
+                file_ids.push(label.file_id);
+            }
+        }
+        let artifacts = file_ids
+            .iter()
+            .map(|file_id| file_id.to_sarif_with_options(files, options))
+            .collect::<SarifResult<Vec<_>>>()?;
         // Build run.
         trace!("building run");
-        let results =
-            self.iter().map(|report| report.to_sarif(files)).collect::<SarifResult<Vec<_>>>()?;
-        let run = sarif::RunBuilder::default().tool(tool).results(results).build()?;
+        let mut results = self
+            .iter()
+            .map(|report| report.to_sarif_with_options(files, options))
+            .collect::<SarifResult<Vec<_>>>()?;
+        // Results with identical fingerprints are distinguished by their
+        // order of occurrence.
+        let mut occurrences = HashMap::new();
+        for fingerprint in results
+            .iter_mut()
+            .filter_map(|result| result.partial_fingerprints.as_mut())
+            .filter_map(|fingerprints| fingerprints.get_mut(FINGERPRINT_KEY))
+        {
+            let occurrence = occurrences.entry(fingerprint.clone()).or_insert(0);
+            *occurrence += 1;
+            *fingerprint = format!("{fingerprint}:{occurrence}");
+        }
+        let mut run = sarif::RunBuilder::default();
+        run.tool(tool).artifacts(artifacts).results(results);
+        if let Some(root_uri) = options.root_uri() {
+            let root_location = sarif::ArtifactLocationBuilder::default().uri(root_uri).build()?;
+            run.original_uri_base_ids(BTreeMap::from([(
+                options.uri_base_id.clone(),
+                root_location,
+            )]));
+        }
+        let run = run.build()?;
         // Build main object.
         trace!("building main sarif object");
         let sarif = sarif::SarifBuilder::default().runs(vec![run]).version(SARIF_VERSION).build();
@@ -65,34 +180,51 @@ impl ToSarif for Report {
     type Sarif = sarif::Result;
     type Error = SarifError;
 
-    fn to_sarif(&self, files: &FileLibrary) -> SarifResult<sarif::Result> {
+    fn to_sarif_with_options(
+        &self,
+        files: &FileLibrary,
+        options: &SarifOptions,
+    ) -> SarifResult<sarif::Result> {
         let level = self.category().to_string();
         let rule_id = self.id();
         // Build message.
         trace!("building message");
         let message = sarif::MessageBuilder::default().text(self.message()).build()?;
-        // Build locations from first primary label (or first secondary label if
-        // there are no primary labels).
-        //
-        // Note: We currently only use the first available label to generate the
-        // output. The reason for this is that the VS Code Sarif viewer does not
-        // handle reports with multiple locations well.
+        // Build locations from the report labels.
         trace!("building locations");
         let primary_locations = self
             .primary()
             .iter()
-            .map(|label| label.to_sarif(files))
+            .map(|label| label.to_sarif_with_options(files, options))
             .collect::<SarifResult<Vec<_>>>()?;
         let secondary_locations = self
             .secondary()
             .iter()
-            .map(|label| label.to_sarif(files))
+            .map(|label| label.to_sarif_with_options(files, options))
             .collect::<SarifResult<Vec<_>>>()?;
-        let locations = primary_locations
-            .into_iter()
-            .chain(secondary_locations.into_iter())
-            .take(1)
-            .collect::<Vec<_>>();
+        let (locations, related_locations) = if options.full_locations {
+            // Emit all primary labels as locations and all secondary labels as
+            // related locations. (Location IDs must be unique within a result.)
+            let mut locations = primary_locations;
+            let mut related_locations = secondary_locations;
+            for (id, location) in
+                locations.iter_mut().chain(related_locations.iter_mut()).enumerate()
+            {
+                location.id = Some(id as i64);
+            }
+            (locations, related_locations)
+        } else {
+            // Only use the first primary label (or first secondary label if
+            // there are no primary labels). The reason for this is that the VS
+            // Code Sarif viewer does not handle reports with multiple locations
+            // well.
+            let locations = primary_locations
+                .into_iter()
+                .chain(secondary_locations)
+                .take(1)
+                .collect::<Vec<_>>();
+            (locations, Vec::new())
+        };
         // Build reporting descriptor reference.
         let rule = sarif::ReportingDescriptorReferenceBuilder::default()
             .id(&rule_id)
@@ -100,26 +232,89 @@ impl ToSarif for Report {
             .map_err(SarifError::from)?;
         // Build result.
         trace!("building result");
-        sarif::ResultBuilder::default()
-            .level(level)
-            .message(message)
-            .rule_id(rule_id)
-            .rule(rule)
-            .locations(locations)
-            .build()
-            .map_err(SarifError::from)
+        let mut result = sarif::ResultBuilder::default();
+        if let Some(fingerprint) = self.fingerprint(files, options.root.as_deref()) {
+            result
+                .partial_fingerprints(BTreeMap::from([(FINGERPRINT_KEY.to_string(), fingerprint)]));
+        }
+        result.level(level).message(message).rule_id(&rule_id).rule(rule).locations(locations);
+        if !related_locations.is_empty() {
+            result.related_locations(related_locations);
+        }
+        // Build suppressions.
+        if let Some(suppression) = self.suppression() {
+            trace!("building suppression");
+            let kind = match suppression.kind {
+                SuppressionKind::InSource => "inSource",
+                SuppressionKind::External => "external",
+            };
+            let suppression = sarif::SuppressionBuilder::default()
+                .kind(kind)
+                .status("accepted")
+                .justification(&suppression.justification)
+                .build()?;
+            result.suppressions(vec![suppression]);
+        }
+        result.build().map_err(SarifError::from)
     }
 }
 
+impl Report {
+    /// Returns a fingerprint identifying the report, computed from the rule
+    /// ID, the file path, and the source lines covered by the first label.
+    /// Since line numbers are not included, the fingerprint is stable under
+    /// changes that only move the reported code. If a root directory is given,
+    /// paths under the root are hashed relative to the root.
+    pub fn fingerprint(&self, files: &FileLibrary, root: Option<&Path>) -> Option<String> {
+        let label = self.primary().iter().chain(self.secondary()).next()?;
+        let file = files.to_storage().get(label.file_id)?;
+        let source = file.source();
+        let (before, after) = (source.get(..label.range.start)?, source.get(label.range.end..)?);
+        let start = before.rfind('\n').map_or(0, |index| index + 1);
+        let end = after.find('\n').map_or(source.len(), |index| label.range.end + index);
+        // Ignore indentation to make the fingerprint robust to reformatting.
+        let context = source[start..end].lines().map(str::trim).collect::<Vec<_>>().join("\n");
+        let path = file_path(label.file_id, files).ok()?;
+        let path = root.and_then(|root| relative_path(&path, root)).unwrap_or(path);
+        let path = path.to_string_lossy();
+        let hash = content_hash([self.id().as_bytes(), path.as_bytes(), context.as_bytes()]);
+        Some(format!("{hash:016x}"))
+    }
+}
+
+/// Returns the fingerprint of each report (see `Report::fingerprint`).
+/// Reports with identical fingerprints are distinguished by their order of
+/// occurrence, as in the Sarif output.
+#[must_use]
+pub fn fingerprints(
+    reports: &[Report],
+    files: &FileLibrary,
+    root: Option<&Path>,
+) -> Vec<Option<String>> {
+    let mut occurrences = HashMap::new();
+    reports
+        .iter()
+        .map(|report| {
+            let fingerprint = report.fingerprint(files, root)?;
+            let occurrence = occurrences.entry(fingerprint.clone()).or_insert(0);
+            *occurrence += 1;
+            Some(format!("{fingerprint}:{occurrence}"))
+        })
+        .collect()
+}
+
 impl ToSarif for ReportLabel {
     type Sarif = sarif::Location;
     type Error = SarifError;
 
-    fn to_sarif(&self, files: &FileLibrary) -> SarifResult<sarif::Location> {
+    fn to_sarif_with_options(
+        &self,
+        files: &FileLibrary,
+        options: &SarifOptions,
+    ) -> SarifResult<sarif::Location> {
         // Build artifact location.
         trace!("building artifact location");
-        let file_uri = self.file_id.to_uri(files)?;
-        let artifact_location = sarif::ArtifactLocationBuilder::default().uri(file_uri).build()?;
+        let artifact_location = self.file_id.to_artifact_location(files, options)?;
         // Build region.
         trace!("building region");
         assert!(self.range.start <= self.range.end);
@@ -131,11 +326,20 @@ impl ToSarif for ReportLabel {
             .to_storage()
             .location(self.file_id, self.range.end)
             .ok_or_else(|| SarifError::UnknownLocation(self.file_id, self.range.clone()))?;
+        // Include the source text of the reported range, so that consumers
+        // without access to the original files can still display it.
+        let snippet = files
+            .to_storage()
+            .source(self.file_id)
+            .and_then(|source| source.get(self.range.clone()))
+            .ok_or_else(|| SarifError::UnknownLocation(self.file_id, self.range.clone()))?;
+        let snippet = sarif::ArtifactContentBuilder::default().text(snippet).build()?;
         let region = sarif::RegionBuilder::default()
             .start_line(start.line_number as i64)
             .start_column(start.column_number as i64)
             .end_line(end.line_number as i64)
             .end_column(end.column_number as i64)
+            .snippet(snippet)
             .build()?;
         // Build physical location.
         trace!("building physical location");
@@ -157,33 +361,87 @@ impl ToSarif for ReportLabel {
     }
 }
 
-trait ToUri {
+impl ToSarif for FileID {
+    type Sarif = sarif::Artifact;
+    type Error = SarifError;
+
+    fn to_sarif_with_options(
+        &self,
+        files: &FileLibrary,
+        options: &SarifOptions,
+    ) -> SarifResult<sarif::Artifact> {
+        // Build artifact location.
+        trace!("building artifact location");
+        let artifact_location = self.to_artifact_location(files, options)?;
+        // Build artifact contents.
+        trace!("building artifact contents");
+        let source = files.to_storage().source(*self).ok_or(SarifError::UnknownFile(*self))?;
+        let contents = sarif::ArtifactContentBuilder::default().text(source).build()?;
+        // Build artifact.
+        trace!("building artifact");
+        sarif::ArtifactBuilder::default()
+            .location(artifact_location)
+            .contents(contents)
+            .build()
+            .map_err(SarifError::from)
+    }
+}
+
+trait ToArtifactLocation {
     type Error;
-    fn to_uri(&self, files: &FileLibrary) -> Result<String, Self::Error>;
+    fn to_artifact_location(
+        &self,
+        files: &FileLibrary,
+        options: &SarifOptions,
+    ) -> Result<sarif::ArtifactLocation, Self::Error>;
 }
 
-impl ToUri for FileID {
+impl ToArtifactLocation for FileID {
     type Error = SarifError;
 
-    fn to_uri(&self, files: &FileLibrary) -> Result<String, SarifError> {
-        let path: PathBuf = files
-            .to_storage()
-            .get(*self)
-            .ok_or(SarifError::UnknownFile(*self))?
-            .name()
-            .replace('"', "")
-            .into();
-        // This path already comes from an UTF-8 string so it is ok to unwrap here.
-        Ok(format!("file://{}", path.to_str().unwrap()))
+    fn to_artifact_location(
+        &self,
+        files: &FileLibrary,
+        options: &SarifOptions,
+    ) -> SarifResult<sarif::ArtifactLocation> {
+        let path = file_path(*self, files)?;
+        let mut artifact_location = sarif::ArtifactLocationBuilder::default();
+        match options.relative_path(&path) {
+            Some(relative_path) => {
+                // Relative URIs always use `/` as the path separator.
+                let components = relative_path
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>();
+                artifact_location.uri(components.join("/")).uri_base_id(&options.uri_base_id)
+            }
+            // This path already comes from an UTF-8 string so it is ok to unwrap here.
+            None => artifact_location.uri(format!("file://{}", path.to_str().unwrap())),
+        };
+        artifact_location.build().map_err(SarifError::from)
     }
 }
 
+/// Returns the path of the given file.
+fn file_path(file_id: FileID, files: &FileLibrary) -> SarifResult<PathBuf> {
+    Ok(files
+        .to_storage()
+        .get(file_id)
+        .ok_or(SarifError::UnknownFile(file_id))?
+        .name()
+        .replace('"', "")
+        .into())
+}
+
 #[derive(Error, Debug)]
 pub enum SarifError {
     InvalidReportingDescriptorReference(#[from] sarif::ReportingDescriptorReferenceBuilderError),
     InvalidReportingDescriptor(#[from] sarif::ReportingDescriptorBuilderError),
+    InvalidPropertyBag(#[from] sarif::PropertyBagBuilderError),
     InvalidPhysicalLocationError(#[from] sarif::PhysicalLocationBuilderError),
     InvalidArtifactLocation(#[from] sarif::ArtifactLocationBuilderError),
+    InvalidArtifactContent(#[from] sarif::ArtifactContentBuilderError),
+    InvalidArtifact(#[from] sarif::ArtifactBuilderError),
     InvalidToolComponent(#[from] sarif::ToolComponentBuilderError),
     InvalidLocation(#[from] sarif::LocationBuilderError),
     InvalidMessage(#[from] sarif::MessageBuilderError),
@@ -193,6 +451,7 @@ pub enum SarifError {
     InvalidSarif(#[from] sarif::SarifBuilderError),
     InvalidTool(#[from] sarif::ToolBuilderError),
     InvalidFix(#[from] sarif::FixBuilderError),
+    InvalidSuppression(#[from] sarif::SuppressionBuilderError),
     UnknownLocation(FileID, Range<usize>),
     UnknownFile(FileID),
 }
@@ -204,3 +463,110 @@ impl fmt::Display for SarifError {
         write!(f, "failed to convert analysis results to sarif-format")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::report_code::ReportCode;
+
+    use super::*;
+
+    const SRC: &str =
+        "template T() {\n    signal input in;\n    signal output out;\n    out <-- in;\n}\n";
+
+    fn build_reports() -> (ReportCollection, FileLibrary) {
+        build_reports_from(SRC)
+    }
+
+    fn build_reports_from(src: &str) -> (ReportCollection, FileLibrary) {
+        let mut files = FileLibrary::new();
+        let file_id = files.add_file("/src/main.circom".to_string(), src.to_string());
+        let start = src.find("out <--").unwrap();
+        let mut report = Report::warning("message".to_string(), ReportCode::FieldElementArithmetic);
+        report.add_primary(start..start + 11, file_id, "primary".to_string());
+        report.add_secondary(start..start + 3, file_id, Some("secondary".to_string()));
+        (vec![report], files)
+    }
+
+    #[test]
+    fn test_snippets() {
+        let (reports, files) = build_reports();
+        let sarif = serde_json::to_value(reports.to_sarif(&files).unwrap()).unwrap();
+        let run = &sarif["runs"][0];
+        assert_eq!(run["artifacts"][0]["contents"]["text"], SRC);
+        let region = &run["results"][0]["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(region["snippet"]["text"], "out <-- in;");
+    }
+
+    #[test]
+    fn test_rule_tags() {
+        let (reports, files) = build_reports();
+        let sarif = serde_json::to_value(reports.to_sarif(&files).unwrap()).unwrap();
+        let rule = &sarif["runs"][0]["tool"]["driver"]["rules"][0];
+        assert_eq!(rule["properties"]["tags"], serde_json::json!(["correctness"]));
+    }
+
+    #[test]
+    fn test_full_locations() {
+        let (reports, files) = build_reports();
+        let sarif = serde_json::to_value(reports.to_sarif(&files).unwrap()).unwrap();
+        let result = &sarif["runs"][0]["results"][0];
+        assert_eq!(result["locations"].as_array().unwrap().len(), 1);
+        assert!(result.get("relatedLocations").is_none());
+
+        let options = SarifOptions { full_locations: true, ..SarifOptions::default() };
+        let sarif =
+            serde_json::to_value(reports.to_sarif_with_options(&files, &options).unwrap()).unwrap();
+        let result = &sarif["runs"][0]["results"][0];
+        assert_eq!(result["locations"].as_array().unwrap().len(), 1);
+        assert_eq!(result["relatedLocations"][0]["message"]["text"], "secondary");
+        assert_eq!(result["relatedLocations"][0]["id"], 1);
+    }
+
+    #[test]
+    fn test_partial_fingerprints() {
+        let fingerprint = |src: &str| {
+            let (reports, files) = build_reports_from(src);
+            let sarif = serde_json::to_value(reports.to_sarif(&files).unwrap()).unwrap();
+            sarif["runs"][0]["results"][0]["partialFingerprints"][FINGERPRINT_KEY].clone()
+        };
+        // Fingerprints are not affected by line shifts or indentation.
+        let shifted = format!("// Comment.\n{}", SRC.replace("    out", "  out"));
+        assert!(fingerprint(SRC).is_string());
+        assert_eq!(fingerprint(SRC), fingerprint(&shifted));
+        // Fingerprints change if the reported code changes.
+        assert_ne!(fingerprint(SRC), fingerprint(&SRC.replace("out <-- in", "out <-- 2 * in")));
+    }
+
+    #[test]
+    fn test_relative_uris() {
+        let (reports, files) = build_reports();
+        let sarif = serde_json::to_value(reports.to_sarif(&files).unwrap()).unwrap();
+        let location = &sarif["runs"][0]["results"][0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "file:///src/main.circom");
+        assert!(sarif["runs"][0].get("originalUriBaseIds").is_none());
+
+        let options = SarifOptions { root: Some(PathBuf::from("/src")), ..SarifOptions::default() };
+        let sarif =
+            serde_json::to_value(reports.to_sarif_with_options(&files, &options).unwrap()).unwrap();
+        let location = &sarif["runs"][0]["results"][0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "main.circom");
+        assert_eq!(location["artifactLocation"]["uriBaseId"], DEFAULT_URI_BASE_ID);
+        assert_eq!(
+            sarif["runs"][0]["originalUriBaseIds"][DEFAULT_URI_BASE_ID]["uri"],
+            "file:///src/"
+        );
+    }
+
+    #[test]
+    fn test_suppressions() {
+        let (mut reports, files) = build_reports();
+        let sarif = serde_json::to_value(reports.to_sarif(&files).unwrap()).unwrap();
+        assert!(sarif["runs"][0]["results"][0].get("suppressions").is_none());
+
+        reports[0].suppress(SuppressionKind::External, "justification".to_string());
+        let sarif = serde_json::to_value(reports.to_sarif(&files).unwrap()).unwrap();
+        let suppression = &sarif["runs"][0]["results"][0]["suppressions"][0];
+        assert_eq!(suppression["kind"], "external");
+        assert_eq!(suppression["justification"], "justification");
+    }
+}
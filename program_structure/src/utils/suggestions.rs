@@ -0,0 +1,79 @@
+/// Returns the Levenshtein distance between the two strings, counted in
+/// characters. Transpositions of adjacent characters (like `vlaue` for
+/// `value`) are counted as a single edit.
+#[must_use]
+pub fn edit_distance(lhs: &str, rhs: &str) -> usize {
+    let (lhs, rhs) = (lhs.chars().collect::<Vec<_>>(), rhs.chars().collect::<Vec<_>>());
+    // `distances[i][j]` is the distance between the first `i` characters of
+    // `lhs` and the first `j` characters of `rhs`.
+    let mut distances = vec![vec![0; rhs.len() + 1]; lhs.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+    for i in 1..=lhs.len() {
+        for j in 1..=rhs.len() {
+            let cost = usize::from(lhs[i - 1] != rhs[j - 1]);
+            let mut distance = (distances[i - 1][j - 1] + cost)
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+            if i > 1 && j > 1 && lhs[i - 1] == rhs[j - 2] && lhs[i - 2] == rhs[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[lhs.len()][rhs.len()]
+}
+
+/// Returns the candidate closest to the given name, if the edit distance is
+/// at most a third of the length of the name (and at least one). Names which
+/// only differ by case are always suggested. Ties are broken by choosing the
+/// smallest candidate, to ensure that suggestions are deterministic.
+#[must_use]
+pub fn closest_name<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .filter(|candidate| *candidate != name)
+        .filter_map(|candidate| {
+            let distance = if candidate.eq_ignore_ascii_case(name) {
+                0
+            } else {
+                edit_distance(name, candidate)
+            };
+            (distance <= max_distance).then_some((distance, candidate))
+        })
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("value", ""), 5);
+        assert_eq!(edit_distance("value", "vlaue"), 1);
+        assert_eq!(edit_distance("total", "totl"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_closest_name() {
+        let candidates = ["in", "out", "total", "value"];
+        assert_eq!(closest_name("totl", candidates), Some("total"));
+        assert_eq!(closest_name("otu", candidates), Some("out"));
+        assert_eq!(closest_name("Value", candidates), Some("value"));
+        assert_eq!(closest_name("vlaue", candidates), Some("value"));
+        assert_eq!(closest_name("bits", candidates), None);
+        assert_eq!(closest_name("out", candidates), None);
+    }
+}
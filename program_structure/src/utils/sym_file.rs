@@ -0,0 +1,104 @@
+//! A parser for the `.sym` symbol files generated by the Circom compiler. Each
+//! line of a symbol file has the form `label,witness,component,name`, where
+//! `label` is the signal index, `witness` is the index of the signal in the
+//! witness (and the corresponding R1CS wire), or -1 if the signal was removed
+//! by the optimizer, `component` is the index of the component declaring the
+//! signal, and `name` is the full name of the signal (like `main.n2b.out[0]`).
+use std::convert::TryFrom;
+
+use serde_derive::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("Invalid symbol on line {line_number}.")]
+pub struct SymbolError {
+    pub line_number: usize,
+}
+
+/// A compiled signal from a `.sym` file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Symbol {
+    pub label: usize,
+    /// The witness index (or R1CS wire) of the signal. This is `None` if the
+    /// signal was removed by the optimizer.
+    pub witness: Option<usize>,
+    pub component: usize,
+    pub name: String,
+}
+
+impl Symbol {
+    /// Returns the component path of the signal (like `main.n2b`).
+    #[must_use]
+    pub fn component_path(&self) -> &str {
+        self.name.rsplit_once('.').map(|(path, _)| path).unwrap_or_default()
+    }
+
+    /// Returns the name of the signal relative to the declaring component
+    /// (like `out[0]`).
+    #[must_use]
+    pub fn signal_name(&self) -> &str {
+        self.name.rsplit_once('.').map(|(_, name)| name).unwrap_or(&self.name)
+    }
+}
+
+/// Parses the given symbol file contents.
+pub fn parse_symbols(contents: &str) -> Result<Vec<Symbol>, SymbolError> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            parse_symbol(line.trim()).ok_or(SymbolError { line_number: index + 1 })
+        })
+        .collect()
+}
+
+fn parse_symbol(line: &str) -> Option<Symbol> {
+    let mut fields = line.splitn(4, ',');
+    let label = fields.next()?.parse().ok()?;
+    let witness = match fields.next()?.parse::<i64>().ok()? {
+        -1 => None,
+        witness => Some(usize::try_from(witness).ok()?),
+    };
+    let component = fields.next()?.parse().ok()?;
+    let name = fields.next()?.to_string();
+    Some(Symbol { label, witness, component, name })
+}
+
+/// Removes all array indices from the given signal name or component path
+/// (so that `main.c[1].out[0]` becomes `main.c.out`).
+#[must_use]
+pub fn without_indices(name: &str) -> String {
+    let mut result = String::new();
+    let mut depth = 0;
+    for char in name.chars() {
+        match char {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            char if depth == 0 => result.push(char),
+            _ => {}
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_symbols() {
+        let contents = "1,1,0,main.out\n2,-1,0,main.in[0]\n3,2,1,main.n2b[1].out[0]\n";
+        let symbols = parse_symbols(contents).unwrap();
+        assert_eq!(symbols.len(), 3);
+        assert_eq!(symbols[0].witness, Some(1));
+        assert_eq!(symbols[1].witness, None);
+        assert_eq!(symbols[2].component, 1);
+        assert_eq!(symbols[2].component_path(), "main.n2b[1]");
+        assert_eq!(symbols[2].signal_name(), "out[0]");
+        assert_eq!(without_indices(&symbols[2].name), "main.n2b.out");
+
+        assert_eq!(parse_symbols("1,1,main.out"), Err(SymbolError { line_number: 1 }));
+        assert_eq!(parse_symbols("\n1,-2,0,main.out"), Err(SymbolError { line_number: 2 }));
+    }
+}
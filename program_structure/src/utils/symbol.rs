@@ -0,0 +1,128 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::fmt;
+use std::sync::{OnceLock, RwLock};
+
+/// An interned string, used to represent variable names.
+///
+/// Symbols are cheap to copy, compare, and hash, which reduces the cost of
+/// the many sets and maps of variable names maintained by the analysis passes.
+/// Interned strings are never freed, so symbols should only be used for names
+/// from the analyzed source code.
+///
+/// ```
+/// # use circomspect_program_structure::symbol::Symbol;
+///
+/// let x = Symbol::intern("x");
+/// assert_eq!(x, Symbol::intern("x"));
+/// assert_eq!(x.as_str(), "x");
+/// ```
+///
+/// Symbols are ordered by the interned string, which ensures that the order
+/// does not depend on the order in which strings are interned.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Symbol(u32);
+
+#[derive(Default)]
+struct Interner {
+    indices: HashMap<&'static str, Symbol>,
+    strings: Vec<&'static str>,
+}
+
+fn interner() -> &'static RwLock<Interner> {
+    static INTERNER: OnceLock<RwLock<Interner>> = OnceLock::new();
+    INTERNER.get_or_init(Default::default)
+}
+
+impl Symbol {
+    /// Returns the symbol corresponding to the given string, interning the
+    /// string if needed.
+    #[must_use]
+    pub fn intern(string: &str) -> Symbol {
+        if let Some(symbol) = interner().read().expect("interner poisoned").indices.get(string) {
+            return *symbol;
+        }
+        let mut interner = interner().write().expect("interner poisoned");
+        // The string may have been interned since the read lock was released.
+        if let Some(symbol) = interner.indices.get(string) {
+            return *symbol;
+        }
+        let symbol = Symbol(interner.strings.len().try_into().expect("too many symbols"));
+        let string: &'static str = Box::leak(string.to_string().into_boxed_str());
+        interner.strings.push(string);
+        interner.indices.insert(string, symbol);
+        symbol
+    }
+
+    /// Returns the interned string.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        interner().read().expect("interner poisoned").strings[self.0 as usize]
+    }
+}
+
+impl PartialOrd for Symbol {
+    fn partial_cmp(&self, other: &Symbol) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Symbol {
+    fn cmp(&self, other: &Symbol) -> Ordering {
+        if self == other {
+            return Ordering::Equal;
+        }
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(string: &str) -> Symbol {
+        Symbol::intern(string)
+    }
+}
+
+impl PartialEq<str> for Symbol {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Symbol {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    #[test]
+    fn test_symbol() {
+        let (b, a) = (Symbol::intern("symbol-test-b"), Symbol::intern("symbol-test-a"));
+        assert_eq!(a, Symbol::from("symbol-test-a"));
+        assert_ne!(a, b);
+        assert_eq!(a, "symbol-test-a");
+        assert_eq!(b.to_string(), "symbol-test-b");
+
+        // Symbols are ordered by the interned string.
+        let symbols = BTreeSet::from([b, a]);
+        assert_eq!(symbols.into_iter().collect::<Vec<_>>(), vec![a, b]);
+    }
+}
@@ -0,0 +1,226 @@
+use codespan_reporting::files::Files;
+use log::debug;
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+use thiserror::Error;
+
+use crate::report::{Report, ReportCollection, ReportLabel};
+use crate::file_definition::{FileID, FileLibrary};
+use crate::rules::find_rule;
+
+const XML_HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>"#;
+const CHECKSTYLE_VERSION: &str = "4.3";
+const NO_LOCATION: &str = "(no location)";
+
+/// A trait for objects that can be converted into a Checkstyle XML document.
+pub trait ToCheckstyle {
+    type Error;
+
+    /// Renders the object as Checkstyle XML.
+    fn to_checkstyle(&self, files: &FileLibrary) -> Result<String, Self::Error>;
+}
+
+/// A trait for objects that can be converted into a JUnit XML document.
+pub trait ToJUnit {
+    type Error;
+
+    /// Renders the object as JUnit XML.
+    fn to_junit(&self, files: &FileLibrary) -> Result<String, Self::Error>;
+}
+
+impl ToCheckstyle for ReportCollection {
+    type Error = XmlError;
+
+    fn to_checkstyle(&self, files: &FileLibrary) -> XmlResult<String> {
+        debug!("converting report collection to checkstyle");
+        // Reports are grouped by the file of the first label.
+        let mut groups: BTreeMap<String, Vec<(&Report, Option<Position>)>> = BTreeMap::new();
+        for report in self {
+            let (file_name, position) = match first_label(report) {
+                Some(label) => (file_name(label.file_id, files)?, Some(position(label, files)?)),
+                None => (NO_LOCATION.to_string(), None),
+            };
+            groups.entry(file_name).or_default().push((report, position));
+        }
+        let mut xml = String::new();
+        writeln!(xml, "{XML_HEADER}")?;
+        writeln!(xml, "<checkstyle version=\"{CHECKSTYLE_VERSION}\">")?;
+        for (file_name, reports) in groups {
+            writeln!(xml, "  <file name=\"{}\">", escape(&file_name))?;
+            for (report, position) in reports {
+                let (line, column) = position.unwrap_or_default();
+                writeln!(
+                    xml,
+                    "    <error line=\"{line}\" column=\"{column}\" severity=\"{}\" message=\"{}\" source=\"circomspect.{}\"/>",
+                    report.category(),
+                    escape(report.message()),
+                    report.id()
+                )?;
+            }
+            writeln!(xml, "  </file>")?;
+        }
+        writeln!(xml, "</checkstyle>")?;
+        Ok(xml)
+    }
+}
+
+impl ToJUnit for ReportCollection {
+    type Error = XmlError;
+
+    fn to_junit(&self, files: &FileLibrary) -> XmlResult<String> {
+        debug!("converting report collection to junit");
+        // Each rule is a test suite, and each report is a failed test case.
+        let mut groups: BTreeMap<String, Vec<&Report>> = BTreeMap::new();
+        for report in self {
+            groups.entry(report.id()).or_default().push(report);
+        }
+        let mut xml = String::new();
+        writeln!(xml, "{XML_HEADER}")?;
+        writeln!(
+            xml,
+            "<testsuites name=\"circomspect\" tests=\"{}\" failures=\"{}\">",
+            self.len(),
+            self.len()
+        )?;
+        for (rule_id, reports) in groups {
+            let suite_name = match find_rule(&rule_id) {
+                Some(rule) => format!("{rule_id}: {}", rule.name),
+                None => rule_id.clone(),
+            };
+            writeln!(
+                xml,
+                "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\">",
+                escape(&suite_name),
+                reports.len(),
+                reports.len()
+            )?;
+            for report in reports {
+                let location = match first_label(report) {
+                    Some(label) => {
+                        let (line, column) = position(label, files)?;
+                        format!("{}:{line}:{column}", file_name(label.file_id, files)?)
+                    }
+                    None => NO_LOCATION.to_string(),
+                };
+                writeln!(
+                    xml,
+                    "    <testcase name=\"{}\" classname=\"{rule_id}\">",
+                    escape(&location)
+                )?;
+                writeln!(
+                    xml,
+                    "      <failure type=\"{}\" message=\"{}\">{}</failure>",
+                    report.category(),
+                    escape(report.message()),
+                    escape(&description(report, &location))
+                )?;
+                writeln!(xml, "    </testcase>")?;
+            }
+            writeln!(xml, "  </testsuite>")?;
+        }
+        writeln!(xml, "</testsuites>")?;
+        Ok(xml)
+    }
+}
+
+/// A 1-based line and column number.
+type Position = (usize, usize);
+
+fn position(label: &ReportLabel, files: &FileLibrary) -> XmlResult<Position> {
+    let location = files
+        .to_storage()
+        .location(label.file_id, label.range.start)
+        .ok_or(XmlError::UnknownFile(label.file_id))?;
+    Ok((location.line_number, location.column_number))
+}
+
+/// Returns the full description of the report, including notes and
+/// suggestions.
+fn description(report: &Report, location: &str) -> String {
+    let mut lines = vec![format!("{location}: {}", report.message())];
+    lines.extend(report.notes().iter().cloned());
+    lines.extend(report.help().iter().map(|help| format!("Help: {help}")));
+    lines.join("\n")
+}
+
+fn first_label(report: &Report) -> Option<&ReportLabel> {
+    report.primary().first().or_else(|| report.secondary().first())
+}
+
+fn file_name(file_id: FileID, files: &FileLibrary) -> XmlResult<String> {
+    let file = files.to_storage().get(file_id).ok_or(XmlError::UnknownFile(file_id))?;
+    Ok(file.name().replace('"', ""))
+}
+
+/// Escapes the characters `&`, `<`, `>`, `"`, and `'`.
+fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+#[derive(Error, Debug)]
+pub enum XmlError {
+    InvalidFormat(#[from] fmt::Error),
+    UnknownFile(FileID),
+}
+
+type XmlResult<T> = Result<T, XmlError>;
+
+impl fmt::Display for XmlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "failed to convert analysis results to xml")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report_code::ReportCode;
+
+    use super::*;
+
+    fn reports(files: &mut FileLibrary) -> ReportCollection {
+        let file_id = files.add_file("test.circom".to_string(), "a\nbc <-- d;\n".to_string());
+        let mut warning = Report::warning(
+            "Uses `<--` & more.".to_string(),
+            ReportCode::UnecessarySignalAssignment,
+        );
+        warning.add_primary(2..9, file_id, "Assigned here.".to_string());
+        warning.add_help("Some suggestion.".to_string());
+        let error = Report::error("Some error.".to_string(), ReportCode::UnconstrainedSignal);
+        vec![warning, error]
+    }
+
+    #[test]
+    fn test_reports_to_checkstyle() {
+        let mut files = FileLibrary::new();
+        let xml = reports(&mut files).to_checkstyle(&files).unwrap();
+        assert!(xml.starts_with(XML_HEADER));
+        assert!(xml.contains("<file name=\"test.circom\">"));
+        assert!(xml.contains(
+            "<error line=\"2\" column=\"1\" severity=\"warning\" message=\"Uses `&lt;--` &amp; more.\" source=\"circomspect.CS0013\"/>"
+        ));
+        assert!(xml.contains(&format!("<file name=\"{NO_LOCATION}\">")));
+    }
+
+    #[test]
+    fn test_reports_to_junit() {
+        let mut files = FileLibrary::new();
+        let xml = reports(&mut files).to_junit(&files).unwrap();
+        assert!(xml.contains("<testsuites name=\"circomspect\" tests=\"2\" failures=\"2\">"));
+        assert!(xml.contains(
+            "<testsuite name=\"CS0013: unnecessary-signal-assignment\" tests=\"1\" failures=\"1\">"
+        ));
+        assert!(xml.contains("<testcase name=\"test.circom:2:1\" classname=\"CS0013\">"));
+        assert!(xml.contains("Help: Some suggestion.</failure>"));
+    }
+}
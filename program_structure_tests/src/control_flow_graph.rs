@@ -4,7 +4,7 @@ use parser::parse_definition;
 use program_structure::cfg::*;
 use program_structure::constants::Curve;
 use program_structure::report::ReportCollection;
-use program_structure::ir::VariableName;
+use program_structure::ir::{Statement, VariableName};
 
 #[test]
 fn test_cfg_from_if() {
@@ -260,7 +260,7 @@ fn test_dominance_from_nested_if() {
     dominance_frontier.insert(2, HashSet::from([3]));
     dominance_frontier.insert(3, HashSet::new());
 
-    validate_dominance(&src, &immediate_dominators, &dominance_frontier);
+    validate_dominance(src, &immediate_dominators, &dominance_frontier);
 }
 
 #[test]
@@ -309,7 +309,7 @@ fn test_dominance_from_nested_if_then_else() {
     dominance_frontier.insert(2, HashSet::new());
     dominance_frontier.insert(3, HashSet::new());
 
-    validate_dominance(&src, &immediate_dominators, &dominance_frontier);
+    validate_dominance(src, &immediate_dominators, &dominance_frontier);
 }
 
 #[test]
@@ -353,7 +353,7 @@ fn test_branches_from_nested_if_then_else() {
     false_branches.insert(0, HashSet::from([2, 3, 4]));
     false_branches.insert(2, HashSet::from([4]));
 
-    validate_branches(&src, &true_branches, &false_branches);
+    validate_branches(src, &true_branches, &false_branches);
 }
 
 #[test]
@@ -393,7 +393,63 @@ fn test_branches_from_nested_if() {
     false_branches.insert(0, HashSet::new());
     false_branches.insert(1, HashSet::new());
 
-    validate_branches(&src, &true_branches, &false_branches);
+    validate_branches(src, &true_branches, &false_branches);
+}
+
+#[test]
+fn test_cfg_from_parallel_template() {
+    let src = r#"
+        template parallel T() {
+            signal input in;
+            signal output out;
+            component c = parallel C();
+            c.in <== in;
+            out <== c.out;
+        }
+    "#;
+    let mut reports = ReportCollection::new();
+    let cfg = parse_definition(src).unwrap().into_cfg(&Curve::default(), &mut reports).unwrap();
+    assert!(reports.is_empty());
+    assert!(cfg.is_parallel());
+
+    let is_parallel = cfg
+        .iter()
+        .flat_map(|basic_block| basic_block.iter())
+        .any(|stmt| matches!(stmt, Statement::Substitution { rhe, .. } if rhe.is_parallel()));
+    assert!(is_parallel);
+
+    let src = r#"
+        template T() {
+            signal input in;
+            signal output out;
+            out <== in;
+        }
+    "#;
+    let cfg = parse_definition(src).unwrap().into_cfg(&Curve::default(), &mut reports).unwrap();
+    assert!(!cfg.is_parallel());
+}
+
+#[test]
+fn test_cfg_to_dot() {
+    let src = r#"
+        function f(x) {
+            var y = 0;
+            if (x > 0) {
+                y = x;
+            }
+            return y + x;
+        }
+    "#;
+    let mut reports = ReportCollection::new();
+    let cfg = parse_definition(src).unwrap().into_cfg(&Curve::default(), &mut reports).unwrap();
+    assert!(reports.is_empty());
+
+    let dot = cfg.to_dot();
+    assert!(dot.starts_with("digraph \"f\" {"));
+    assert!(dot.contains("bb0 -> bb1 [label=\"true\"];"));
+    assert!(dot.contains("bb0 -> bb2 [label=\"false\"];"));
+    assert!(dot.contains("bb1 -> bb2;"));
+    assert_eq!(dot.matches("[label=\"bb").count(), cfg.len());
 }
 
 fn validate_cfg(